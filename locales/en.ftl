@@ -0,0 +1,543 @@
+# Messages shown to the user, in English. Every other catalog falls back to this one.
+#
+# A message's `.terse` attribute is used in its place with the `--terse` flag.
+
+## Playing any game
+
+opponent-disconnected = Your opponent has disconnected, waiting up to { $seconds } seconds for them to reconnect.
+    .terse = Opponent disconnected, waiting { $seconds }s.
+opponent-reconnected = Your opponent has reconnected.
+    .terse = Opponent reconnected.
+opponent-replaced = Your opponent has left the game, so a bot is playing in their place.
+    .terse = Opponent replaced by a bot.
+spectator-joined = A spectator has started watching, there are now { $count } watching.
+    .terse = Spectators: { $count }.
+spectator-left = A spectator has stopped watching, there are now { $count } watching.
+    .terse = Spectators: { $count }.
+server-notice = Message from the server: { $message }
+    .terse = Server: { $message }
+think-time-summary = Player { $player } thought for { $average } seconds per turn on average, and { $longest } seconds at most.
+    .terse = { $player }: avg { $average }s, max { $longest }s.
+rating-changed = { $name } is now rated { $rating } ({ $change }).
+    .terse = { $name }: { $rating } ({ $change }).
+connection-lost = Connection to the game lost, attempting to reconnect.
+    .terse = Connection lost, reconnecting.
+reconnected = Reconnected to the game.
+    .terse = Reconnected.
+unrecoverable-error = An unrecoverable error has occurred, game terminating.
+    .terse = Unrecoverable error, game terminating.
+shutdown-opponent-disconnected = Your opponent has left the game, game terminating.
+    .terse = Opponent left, game terminating.
+shutdown-protocol-violation = A player sent messages the server could not accept, game terminating.
+    .terse = Invalid messages, game terminating.
+shutdown-server-shutdown = The server has stopped the game, game terminating.
+    .terse = Server stopped, game terminating.
+shutdown-timeout = Your opponent stopped responding, game terminating.
+    .terse = Opponent timed out, game terminating.
+game-quit = You have left the game.
+    .terse = Left.
+tui-game-finished = The game has finished, press any key to exit.
+
+game-suspended = The game has been saved, it can be carried on once the host resumes it.
+    .terse = Saved.
+rematch-prompt = Play again Y/n?
+    .terse = Again? Y/n
+rematch-prompt-choose = Play again Y/n? Or enter the name of another game to play it instead: { $games }
+    .terse = Again? Y/n or { $games }
+rematch-waiting = Waiting for everyone to agree to play again.
+    .terse = Waiting.
+rematch-declined = Your opponent does not want to play again.
+    .terse = No rematch.
+rematch-starting = Starting a new game of { $game }.
+    .terse = New game: { $game }.
+tournament-round = Round { $round }:
+    .terse = R{ $round }:
+tournament-match = { $first } v { $second }, yet to be played.
+    .terse = { $first } v { $second }
+tournament-match-won = { $winner } beat { $loser }.
+    .terse = { $winner } > { $loser }
+tournament-bye = { $player } goes through with a bye.
+    .terse = { $player } (bye)
+tournament-undecided = (to be decided)
+    .terse = ?
+error = Error: { $error }
+
+server-error-invalid-message = Invalid message sent.
+server-error-spectating-unavailable = This game is not accepting spectators.
+server-error-kicked = You have been removed from the game by the server operator.
+server-error-unexpected-player = You cannot act for another player.
+server-error-saving-unavailable = This game cannot be saved.
+
+## Taking turns in any game
+
+game-started-spectator = You are now spectating the game.
+    .terse = Started.
+game-started-bot = All players connected, lets begin.
+    .terse = Started.
+game-started-local-first = Lets begin. Player { $player } goes first.
+    .terse = Started.
+game-started-online-as = All players connected, lets begin. You are playing as { $player }.
+    .terse = Started.
+player-turn = Player { $player }'s turn!
+    .terse = { $player } to move.
+waiting-for-opponent = Waiting for other player to make a move.
+    .terse = Their move.
+your-turn = It's your turn!
+    .terse = Your move.
+game-draw = Game over! There was a draw!
+    .terse = Draw.
+game-won = Game over! Player { $player } won!
+    .terse = { $player } won.
+turn-timed-out-forfeit = Player { $player } ran out of time and forfeits the game!
+    .terse = { $player } timed out, forfeits.
+turn-timed-out-skip = Player { $player } ran out of time and loses their turn!
+    .terse = { $player } timed out, skipped.
+rejected-moves-warning = Warning: { $attempts } more invalid move(s) this turn will forfeit the game!
+    .terse = { $attempts } attempt(s) left.
+rejected-moves-exceeded = Player { $player } made too many invalid moves and forfeits the game!
+    .terse = { $player } forfeits, too many invalid moves.
+not-a-number = That is not a number, please try again.
+    .terse = Not a number.
+
+## Tic Tac Toe
+
+game-started-local = Lets begin. Enter "/help" on your turn to list the commands you can enter in place of a move.
+    .terse = Started.
+game-started-online = All players connected, lets begin. Enter "/help" on your turn to list the commands you can enter in place of a move.
+    .terse = Started.
+variant-misere = This is misère Tic Tac Toe, whoever completes a line loses!
+    .terse = Misère: completing a line loses.
+variant-wild = This is wild Tic Tac Toe, either player may place { $first } or { $second } and whoever completes a line wins!
+    .terse = Wild: either mark, completing a line wins.
+first-player-chosen = A coin has been tossed, player { $player } goes first!
+    .terse = Coin toss: { $player } first.
+move-prompt = Input a number between 1 and { $cells } to make your move:
+    .terse = Move (1-{ $cells }):
+move-prompt-wild = Input a number between 1 and { $cells } to make your move, followed by { $first } or { $second } to place that mark instead of your own:
+    .terse = Move (1-{ $cells }, then { $first }/{ $second }):
+unknown-mark = There is no { $mark } mark, please try again.
+    .terse = Unknown mark.
+unknown-command = There is no { $command } command, enter "/help" to list the commands there are.
+    .terse = Unknown command, see /help.
+undo-requested = Player { $player } asks to take back their last move, do you allow it y/N?
+    .terse = { $player } asks to undo (y/N):
+undo-declined = Player { $player }'s request to take back their move was declined.
+    .terse = { $player } undo declined.
+move-taken-back = Player { $player } took back their last move.
+    .terse = { $player } undid.
+draw-offered = Player { $player } offers a draw, do you accept it y/N?
+    .terse = { $player } offers draw (y/N):
+draw-declined = Player { $player }'s offer of a draw was declined.
+    .terse = { $player } draw declined.
+player-resigned = Player { $player } has resigned.
+    .terse = { $player } resigned.
+move-history = Moves so far:
+    .terse = Moves:
+move-history-entry = { $number }. Player { $player } took cell { $cell } after { $seconds } seconds.
+    .terse = { $number }. { $player } { $cell } ({ $seconds }s)
+move-history-empty = No moves have been made yet.
+    .terse = No moves.
+
+# Commands entered in place of a move, each listed by /help after its name
+command-list = Enter one of these commands in place of your move:
+    .terse = Commands:
+command-help = list the commands you can enter.
+command-board = show the board again.
+command-quit = leave the game.
+command-undo = ask to take back your last move.
+command-save = save the game to carry on later.
+command-history = see the moves so far.
+command-draw = offer a draw.
+command-resign = give up the game.
+
+# Describing the board with --screen-reader
+board-row = Row { $row }: { $cells }.
+board-cell-empty = empty
+board-cell-taken = { $player } took cell { $cell }.
+
+tic-tac-toe-error-invalid-cell-index = The input should be a number between 1 and { $cells }.
+tic-tac-toe-error-cell-occupied = This cell is already occupied.
+tic-tac-toe-error-unexpected-player = It's not your turn.
+tic-tac-toe-error-no-move-to-take-back = You have no move that can be taken back.
+tic-tac-toe-error-mark-not-allowed = You cannot place that mark.
+
+## Nim
+
+nim-counters-taken = Player { $player } took { $count } counter(s) from heap { $heap }.
+    .terse = { $player }:{ $heap }x{ $count }
+nim-game-won = Game over! Player { $player } took the last counter and won!
+    .terse = { $player } won.
+nim-move-prompt = Input the heap to take from and how many counters to take, from 1 to { $max }, such as "2 { $max }":
+    .terse = Heap and count:
+nim-not-a-move = That is not a heap and a count, please try again.
+    .terse = Not a heap and count.
+
+nim-error-invalid-heap = There is no heap { $heap }.
+nim-error-invalid-count = You can only take from 1 to { $max } counters, not { $count }.
+nim-error-not-enough-counters = Heap { $heap } only has { $remaining } counter(s) left.
+nim-error-unexpected-player = It's not your turn.
+
+## Mancala
+
+mancala-game-started-local = Lets begin. Player { $player } sows first, along the bottom row.
+    .terse = Started.
+mancala-game-started-online-bottom = All players connected, lets begin. You are playing as { $player }, sowing the pits along the bottom row.
+    .terse = Started.
+mancala-game-started-online-top = All players connected, lets begin. You are playing as { $player }, sowing the pits along the top row.
+    .terse = Started.
+mancala-seeds-sown = Player { $player } sowed pit { $pit }.
+    .terse = { $player }:{ $pit }
+mancala-seeds-sown-capturing = Player { $player } sowed pit { $pit }, capturing { $captured } seed(s).
+    .terse = { $player }:{ $pit }x{ $captured }
+mancala-extra-turn = Player { $player }'s last seed landed in their store, so they go again.
+    .terse = { $player } again.
+mancala-move-prompt = Input the number of the pit to sow, from 1 to { $pits }:
+    .terse = Pit (1-{ $pits }):
+
+mancala-error-invalid-pit = There is no pit { $pit }, the pits are numbered from 1 to { $pits }.
+mancala-error-empty-pit = Pit { $pit } is empty, so there are no seeds to sow.
+mancala-error-unexpected-player = It's not your turn.
+
+## Dots and Boxes
+
+dots-and-boxes-edge-claimed = Player { $player } claimed { $edge }.
+    .terse = { $player }:{ $edge }
+dots-and-boxes-edge-claimed-completing = Player { $player } claimed { $edge }, completing { $completed } box(es), and goes again.
+    .terse = { $player }:{ $edge }+{ $completed }
+dots-and-boxes-score = Boxes: X { $first } - { $second } O
+dots-and-boxes-move-prompt = Input the edge to claim by the dots at either end, such as "a1-b1":
+    .terse = Edge (e.g. a1-b1):
+dots-and-boxes-not-an-edge = That is not an edge between two neighbouring dots, please try again.
+    .terse = Invalid edge.
+
+dots-and-boxes-error-edge-taken = The edge { $edge } has already been claimed.
+dots-and-boxes-error-out-of-bounds = The edge { $edge } is not on the grid.
+dots-and-boxes-error-unexpected-player = It's not your turn.
+
+## Hangman
+
+hangman-game-started-local = Lets begin. Player { $setter } chooses a secret word, then player { $guesser } guesses it a letter at a time.
+    .terse = Started.
+hangman-game-started-online-setter = All players connected, lets begin. You are playing as { $player }, choosing the secret word for the other player to guess.
+    .terse = Started.
+hangman-game-started-online-guesser = All players connected, lets begin. You are playing as { $player }, guessing the secret word chosen by the other player.
+    .terse = Started.
+hangman-word-requested = Player { $player }, choose your word while player { $other } looks away!
+    .terse = { $player } to choose.
+hangman-word-chosen = Player { $player } has chosen a secret word of { $length } letters.
+    .terse = { $player } chose { $length } letters.
+hangman-secret-word = Your secret word is "{ $word }".
+    .terse = Secret: { $word }
+hangman-puzzle = Word: { $word }
+    .terse = { $word } { $misses } { $remaining }
+hangman-puzzle-misses = Misses: { $misses } ({ $remaining } wrong guess(es) left)
+hangman-no-misses = none
+hangman-letter-missed = Player { $player } guessed { $letter }, which is not in the word.
+    .terse = { $player }:{ $letter } miss
+hangman-letter-found = Player { $player } guessed { $letter }, which appears { $found } time(s) in the word.
+    .terse = { $player }:{ $letter }+{ $found }
+hangman-waiting-for-guess = Waiting for other player to make a guess.
+    .terse = Their move.
+hangman-game-won-guessed = Game over! Player { $player } guessed the word "{ $word }" and won!
+    .terse = { $player } won. { $word }
+hangman-game-won-hanged = Game over! Player { $player } won, the word was "{ $word }"!
+    .terse = { $player } won. { $word }
+hangman-turn-timed-out-skip = Player { $player } ran out of time and loses a guess!
+    .terse = { $player } timed out, skipped.
+hangman-word-prompt = Input the secret word, of { $min } to { $max } letters from a to z:
+    .terse = Word ({ $min }-{ $max } letters):
+hangman-letter-prompt = Input the letter to guess:
+    .terse = Letter:
+hangman-not-a-letter = That is not a single letter, please try again.
+    .terse = Not a letter.
+
+hangman-error-invalid-word = The secret word must be { $min } to { $max } letters from a to z.
+hangman-error-word-already-chosen = The secret word has already been chosen.
+hangman-error-word-not-chosen = No letters can be guessed until the secret word has been chosen.
+hangman-error-invalid-letter = { $letter } is not a letter from a to z.
+hangman-error-already-guessed = { $letter } has already been guessed.
+hangman-error-unexpected-player = It's not your turn.
+
+## Trivia
+
+trivia-game-started-local = Lets begin. Both players answer each question, scoring a point for each they get right.
+    .terse = Started.
+trivia-game-started-online = All players connected, lets begin. Score a point for each question you answer correctly before time runs out.
+    .terse = Started.
+trivia-question = Question { $round } of { $rounds }, with { $seconds } seconds to answer:
+    .terse = Q{ $round }/{ $rounds } ({ $seconds }s): { $prompt } { $choices }
+trivia-waiting-for-answer = Waiting for other player to answer.
+    .terse = Their move.
+trivia-player-answered = Player { $player } has answered.
+    .terse = { $player } answered.
+trivia-answer-was = The answer was { $answer }.
+    .terse = Answer { $number }.
+trivia-answered-right = Player { $player } answered { $choice }, which is right.
+    .terse = { $player }:{ $number }
+trivia-answered-wrong = Player { $player } answered { $choice }, which is wrong.
+    .terse = { $player }:{ $number }
+trivia-out-of-time = Player { $player } ran out of time.
+    .terse = { $player }:-
+trivia-score = The score is X { $first } - { $second } O.
+    .terse = X { $first }-{ $second } O.
+trivia-game-won = Game over! Player { $player } won with { $score } correct answer(s) to { $other }!
+    .terse = { $player } won { $score }-{ $other }.
+trivia-game-draw = Game over! It's a draw, with { $score } correct answer(s) each.
+    .terse = Draw { $score }-{ $score }.
+trivia-answer-prompt = Input the number of your answer, from 1 to { $count }:
+    .terse = Answer (1-{ $count }):
+trivia-not-a-choice = That is not one of the choices, please try again.
+    .terse = Invalid choice.
+
+trivia-error-already-answered = You have already answered this question.
+trivia-error-question-closed = That question has closed, answer the one being asked now.
+trivia-error-invalid-choice = That is not one of the choices.
+
+## Blackjack
+
+blackjack-game-started-local = Lets begin. Each player plays their own hand against the dealer, trying to get closer to { $best } without going over.
+    .terse = Started.
+blackjack-game-started-online = All players connected, lets begin. You are playing as { $player }, trying to get closer to { $best } than the dealer without going over.
+    .terse = Started.
+blackjack-round-started = Round { $round } of { $rounds }, betting { $bet } chips. Chips: { $chips }.
+    .terse = R{ $round }/{ $rounds } bet { $bet }. { $chips }.
+blackjack-dealer-up-card = Dealer: { $card } ??
+    .terse = D: { $card } ??
+blackjack-player-hand = Player { $player }: { $hand }
+    .terse = { $player }: { $hand }
+blackjack-waiting-for-hand = Waiting for other player to play their hand.
+    .terse = Their move.
+blackjack-card-dealt = Player { $player } drew { $card }, making { $total }.
+    .terse = { $player } drew { $card } ({ $total }).
+blackjack-card-dealt-bust = Player { $player } drew { $card }, making { $total }, bust!
+    .terse = { $player } drew { $card } ({ $total }) bust.
+blackjack-stood = Player { $player } stands on { $total }.
+    .terse = { $player } stands { $total }.
+blackjack-standing-on = Standing on { $total }.
+    .terse = { $total }, standing.
+blackjack-dealer-played = The dealer reveals { $hand }.
+    .terse = D: { $hand }.
+blackjack-dealer-played-bust = The dealer reveals { $hand }, bust!
+    .terse = D: { $hand }, bust!
+blackjack-outcome-blackjack = Player { $player } wins with blackjack, { $payout } chips.
+    .terse = { $player } { $payout }
+blackjack-outcome-won = Player { $player } wins, { $payout } chips.
+    .terse = { $player } { $payout }
+blackjack-outcome-push = Player { $player } ties with the dealer, { $payout } chips.
+    .terse = { $player } { $payout }
+blackjack-outcome-lost = Player { $player } loses, { $payout } chips.
+    .terse = { $player } { $payout }
+blackjack-outcome-busted = Player { $player } went bust, { $payout } chips.
+    .terse = { $player } { $payout }
+blackjack-chips = Chips: { $chips }.
+    .terse = | { $chips }.
+blackjack-game-won = Game over! Player { $player } won with { $chips } chips to { $other }!
+    .terse = { $player } won { $chips }-{ $other }.
+blackjack-game-draw = Game over! It's a draw, with { $chips } chips each.
+    .terse = Draw { $chips }-{ $chips }.
+blackjack-turn-timed-out-stand = Player { $player } ran out of time and stands!
+    .terse = { $player } timed out, stands.
+blackjack-move-prompt = Input hit or stand:
+    .terse = Hit or stand (h/s):
+blackjack-not-a-move = That is not a valid move, please input hit or stand.
+    .terse = Invalid move.
+
+blackjack-error-hand-complete = Your hand is already worth 21 or more, so you must stand.
+blackjack-error-unexpected-player = It's not your turn.
+
+## Battleship
+
+battleship-game-started-local = Lets begin. Each player places their fleet, then takes turns to fire at the other's.
+    .terse = Started.
+battleship-game-started-online = All players connected, lets begin. Place your fleet, then take turns to fire at the other player's.
+    .terse = Started.
+battleship-ship-carrier = carrier
+battleship-ship-battleship = battleship
+battleship-ship-cruiser = cruiser
+battleship-ship-submarine = submarine
+battleship-ship-destroyer = destroyer
+battleship-fleet-requested = Player { $player }, place your fleet!
+    .terse = { $player } to place.
+battleship-fleet-placed = Player { $player } has placed their fleet.
+    .terse = { $player } placed fleet.
+battleship-own-fleet = Your fleet:
+    .terse = { $player } fleet:
+battleship-fleet = Player { $player }'s fleet:
+    .terse = { $player } fleet:
+battleship-own-shots = Your shots:
+    .terse = { $player } shots:
+battleship-shots = Player { $player }'s shots:
+    .terse = { $player } shots:
+battleship-own-shot-missed = You fired at { $target } and missed.
+    .terse = { $player }:{ $target } miss
+battleship-shot-missed = Player { $player } fired at { $target } and missed.
+    .terse = { $player }:{ $target } miss
+battleship-own-shot-hit = You fired at { $target } and hit a ship!
+    .terse = { $player }:{ $target } hit
+battleship-shot-hit = Player { $player } fired at { $target } and hit a ship!
+    .terse = { $player }:{ $target } hit
+battleship-own-shot-sunk = You fired at { $target } and sank the { $ship }!
+    .terse = { $player }:{ $target } sank { $ship }
+battleship-shot-sunk = Player { $player } fired at { $target } and sank the { $ship }!
+    .terse = { $player }:{ $target } sank { $ship }
+battleship-placement-prompt = Place your { $ship } ({ $length } squares) by the square at its top or left end and the way it runs, such as "b2 across" or "b2 down". Enter "random" to place the rest of your fleet at random:
+    .terse = { $ship } ({ $length }), e.g. b2 across:
+battleship-not-a-placement = That is not a valid placement, please try again.
+    .terse = Invalid placement.
+battleship-target-prompt = Input the square to fire at, such as "b7":
+    .terse = Target (e.g. b7):
+battleship-not-a-square = That is not a valid square, please try again.
+    .terse = Invalid square.
+
+battleship-error-ship-off-grid = Your { $ship } would run off the edge of the grid.
+battleship-error-ships-overlap = Your { $ship } would overlap another of your ships.
+battleship-error-incomplete-fleet = A fleet is made up of one of each ship, placed from the largest down.
+battleship-error-fleet-already-placed = Your fleet has already been placed.
+battleship-error-fleets-not-placed = No shots can be fired until both fleets have been placed.
+battleship-error-off-grid = That square is not on the grid.
+battleship-error-already-fired-at = You have already fired at { $target }.
+battleship-error-unexpected-player = It's not your turn.
+
+## Rock Paper Scissors
+
+rock-paper-scissors-game-started-local = Lets begin. The first player to win { $rounds } rounds wins the game.
+    .terse = Started.
+rock-paper-scissors-game-started-online = All players connected, lets begin. The first player to win { $rounds } rounds wins the game.
+    .terse = Started.
+rock-paper-scissors-hand-rock = rock
+rock-paper-scissors-hand-paper = paper
+rock-paper-scissors-hand-scissors = scissors
+rock-paper-scissors-round-started = Round { $round }! The score is X { $first } - { $second } O.
+    .terse = Round { $round }, X { $first }-{ $second } O.
+rock-paper-scissors-waiting-for-hand = Waiting for other player to choose.
+    .terse = Their move.
+rock-paper-scissors-hand-chosen = Player { $player } has chosen their hand.
+    .terse = { $player } chose.
+rock-paper-scissors-hand-shown = Player { $player } chose { $hand }
+    .terse = { $player }:{ $hand }
+rock-paper-scissors-hands-shown = { $first } and { $second }
+    .terse = { $first } { $second }
+rock-paper-scissors-round-won = Player { $player } wins the round!
+    .terse = { $player } wins round
+rock-paper-scissors-round-drawn = It's a draw, so the round will be played again.
+    .terse = draw
+rock-paper-scissors-round-played = { $hands }. { $result }
+    .terse = { $hands }, { $result }.
+rock-paper-scissors-round-played-unseen = { $result }
+    .terse = { $result }.
+rock-paper-scissors-turn-timed-out-skip = Player { $player } ran out of time and loses the round!
+    .terse = { $player } timed out, loses round.
+rock-paper-scissors-hand-prompt = Choose rock, paper or scissors:
+    .terse = Hand (r/p/s):
+rock-paper-scissors-not-a-hand = That is not a valid hand, please try again.
+    .terse = Invalid hand.
+
+rock-paper-scissors-error-already-chosen = You have already chosen your hand for this round.
+rock-paper-scissors-error-unexpected-player = It's not your turn.
+
+## Othello
+
+othello-game-started-local = Lets begin. Player { $player } places the first disc.
+    .terse = Started.
+othello-discs = Discs: X { $first } - { $second } O
+    .terse = X{ $first }-{ $second }O
+othello-disc-placed = Player { $player } placed a disc on { $square }, flipping { $flipped } disc(s).
+    .terse = { $player }:{ $square }x{ $flipped }
+othello-turn-passed = Player { $player } has nowhere to place a disc, so passes.
+    .terse = { $player } passes.
+othello-move-prompt = Input the square to place your disc on, such as "d3":
+    .terse = Square (e.g. d3):
+othello-not-a-square = That is not a valid square, please try again.
+    .terse = Invalid square.
+
+othello-error-square-taken = There is already a disc on { $square }.
+othello-error-nothing-flipped = A disc on { $square } would not enclose any of the other player's discs, so cannot be placed there.
+othello-error-unexpected-player = It's not your turn.
+
+## Checkers
+
+checkers-game-started-local = Lets begin. Player { $player } starts from the bottom of the board.
+    .terse = Started.
+checkers-game-started-online-bottom = All players connected, lets begin. You are playing from the bottom of the board.
+    .terse = Started.
+checkers-game-started-online-top = All players connected, lets begin. You are playing from the top of the board.
+    .terse = Started.
+checkers-move-made = Player { $player } moved { $move }.
+    .terse = { $player }:{ $move }
+checkers-move-prompt = Input the squares your piece moves through to make your move, such as "c3-d4" or "a3xc5xe7":
+    .terse = Move (e.g. c3-d4):
+checkers-not-a-move = That is not a valid move, please try again.
+    .terse = Invalid move.
+
+checkers-error-incomplete-move = A move needs the square the piece starts on and at least one square it moves to.
+checkers-error-unplayable-square = Pieces only stand on the dark squares, which { $square } is not.
+checkers-error-not-your-piece = You do not have a piece on { $square }.
+checkers-error-illegal-move = Pieces move one square diagonally, forwards unless crowned, or jump over an opposing piece onto the empty square beyond.
+checkers-error-capture-required = One of your pieces can capture, so you must capture.
+checkers-error-capture-incomplete = Your piece can capture again, so must keep jumping.
+checkers-error-unexpected-player = It's not your turn.
+
+## Choosing and setting up a game
+
+welcome = Hello from Rust Game Server, welcome to Tic Tac Toe, Checkers, Battleship, Othello, Rock Paper Scissors, Mancala, Nim, Dots and Boxes, Hangman, Trivia and Blackjack!
+mode-prompt = Please select your game mode; local or online.
+online-mode-prompt = Do you want to host, join, or spectate a game, or run an exhibition?
+join-address-prompt = Please enter the address of the game to join:
+spectate-address-prompt = Please enter the address of the game to spectate:
+invalid-option = That is not a valid option, please try again!
+invalid-number = That is not a valid number, try again.
+port-question = Do you wish to specify a port to bind to (the default is { $port }) y/N?
+port-prompt = Please provide the port:
+join-token-prompt = Please enter the join token shared by the host:
+join-token-invalid = { $error } Please try again.
+turn-limit-question = Do you wish to limit the time each player has to make a move y/N?
+turn-limit-prompt = Please provide the time limit in seconds:
+timeout-action-prompt = Should a player who runs out of time forfeit or skip their turn?
+first-move-prompt = Who should make the first move; host, joiner or random (the default is host)?
+spectators-question = Do you wish to allow spectators to watch the game y/N?
+max-spectators-prompt = Please provide the maximum number of spectators:
+notify-players-question = Should players be told when someone starts or stops watching y/N?
+
+## Hosting and joining
+
+host-details = People can join you on port { $port } using the join token { $token }!
+web-details = People can also join from their browser at http://<your address>:{ $port }/?token={ $token }
+wait-timed-out = No one has joined in { $minutes } minutes. Keep waiting Y/n?
+wait-cancelled = Stopped waiting for players, so the game will not be played.
+exhibition-details = People can spectate the exhibition on port { $port } using the join token { $token }!
+exhibition-finished = The exhibition has finished.
+waiting-for-hot-seat = Waiting for someone to join and play both sides of the game.
+certificate-saved = A self-signed certificate has been saved to { $path }, players will need a copy of it to join.
+stats-empty = No games have been recorded yet.
+stats-record = { $game }: played { $played }, won { $won }, lost { $lost }, drawn { $drawn }, over { $seconds } seconds, thinking for { $think_time } seconds per turn on average.
+ratings-empty = No players have been rated yet.
+ratings-game = { $game }:
+ratings-entry = { $rank }. { $name }, rated { $rating } after { $games } game(s).
+browse-empty = No games are waiting for players yet.
+browse-game = { $number }. Room { $room }: { $game } opened by { $host }, needing { $count } more player(s).
+browse-unnamed-host = an unnamed player
+browse-prompt = Enter the number of the game to join, or "new" to open a game of your own:
+game-offer = The host lets you choose which game to play:
+game-offer-entry = { $number }. { $game }
+game-offer-prompt = Enter the number of the game to play:
+
+## Failures
+
+port-in-use = Port { $port } is already in use, choose another with --port. Aborting.
+bind-failed = Error listening on port { $port }: { $error }. Aborting.
+admin-socket-failed = Error opening the admin socket: { $error }. Aborting.
+stats-open-failed = Error opening statistics: { $error }. Aborting.
+stats-read-failed = Error reading statistics: { $error }. Aborting.
+replay-create-failed = Error creating replay: { $error }. Aborting.
+replay-play-failed = Error playing replay: { $error }. Aborting.
+save-open-failed = Error opening saved game: { $error }. Aborting.
+tls-failed = Error setting up TLS: { $error }. Aborting.
+connect-failed = Error connecting to game: { $error } Aborting.
+server-address-missing = Error finding server: no address for { $address }. Aborting.
+server-lookup-failed = Error finding server: { $error }. Aborting.
+script-open-failed = Error opening script: { $error }. Aborting.
+bot-needs-script = A bot can only play both sides of a game from a script. Aborting.
+browse-unknown-game = The server hosts { $game }, which this release cannot play. Aborting.
+tui-start-failed = Error starting the terminal interface: { $error }. Continuing without it.
+tui-failed = Error in the terminal interface: { $error }.
+gui-failed = Error in the desktop window: { $error }.
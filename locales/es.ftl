@@ -0,0 +1,544 @@
+# Messages shown to the user, in Spanish.
+#
+# Answers such as "y", "local" and "undo" are still typed in English, so they are left as they are
+# in the prompts asking for them.
+
+## Playing any game
+
+opponent-disconnected = Tu rival se ha desconectado, esperando hasta { $seconds } segundos a que se vuelva a conectar.
+    .terse = Rival desconectado, esperando { $seconds }s.
+opponent-reconnected = Tu rival se ha vuelto a conectar.
+    .terse = Rival reconectado.
+opponent-replaced = Tu rival ha abandonado la partida, así que un bot juega en su lugar.
+    .terse = Rival sustituido por un bot.
+spectator-joined = Un espectador ha empezado a mirar, ahora hay { $count } mirando.
+    .terse = Espectadores: { $count }.
+spectator-left = Un espectador ha dejado de mirar, ahora hay { $count } mirando.
+    .terse = Espectadores: { $count }.
+server-notice = Mensaje del servidor: { $message }
+    .terse = Servidor: { $message }
+think-time-summary = El jugador { $player } pensó { $average } segundos por turno de media, y { $longest } segundos como máximo.
+    .terse = { $player }: media { $average }s, máx { $longest }s.
+rating-changed = { $name } tiene ahora una puntuación de { $rating } ({ $change }).
+    .terse = { $name }: { $rating } ({ $change }).
+connection-lost = Se ha perdido la conexión con la partida, intentando reconectar.
+    .terse = Conexión perdida, reconectando.
+reconnected = Reconectado a la partida.
+    .terse = Reconectado.
+unrecoverable-error = Se ha producido un error irrecuperable, la partida termina.
+    .terse = Error irrecuperable, la partida termina.
+shutdown-opponent-disconnected = Tu rival ha abandonado la partida, la partida termina.
+    .terse = Rival fuera, la partida termina.
+shutdown-protocol-violation = Un jugador ha enviado mensajes que el servidor no puede aceptar, la partida termina.
+    .terse = Mensajes no válidos, la partida termina.
+shutdown-server-shutdown = El servidor ha detenido la partida, la partida termina.
+    .terse = Servidor detenido, la partida termina.
+shutdown-timeout = Tu rival ha dejado de responder, la partida termina.
+    .terse = Rival sin respuesta, la partida termina.
+game-quit = Has abandonado la partida.
+    .terse = Abandonada.
+tui-game-finished = La partida ha terminado, pulsa cualquier tecla para salir.
+
+game-suspended = Se ha guardado la partida, podrá continuar cuando el anfitrión la reanude.
+    .terse = Guardada.
+rematch-prompt = ¿Jugar otra vez? Y/n
+    .terse = ¿Otra? Y/n
+rematch-prompt-choose = ¿Jugar otra vez? Y/n, o escribe el nombre de otro juego para jugarlo en su lugar: { $games }
+    .terse = ¿Otra? Y/n o { $games }
+rematch-waiting = Esperando a que todos acepten jugar otra vez.
+    .terse = Esperando.
+rematch-declined = Tu rival no quiere jugar otra vez.
+    .terse = Sin revancha.
+rematch-starting = Empieza una nueva partida de { $game }.
+    .terse = Nueva partida: { $game }.
+tournament-round = Ronda { $round }:
+    .terse = R{ $round }:
+tournament-match = { $first } contra { $second }, por jugar.
+    .terse = { $first } v { $second }
+tournament-match-won = { $winner } ganó a { $loser }.
+    .terse = { $winner } > { $loser }
+tournament-bye = { $player } pasa de ronda sin jugar.
+    .terse = { $player } (pasa)
+tournament-undecided = (por decidir)
+    .terse = ?
+error = Error: { $error }
+
+server-error-invalid-message = Se ha enviado un mensaje no válido.
+server-error-spectating-unavailable = Esta partida no admite espectadores.
+server-error-kicked = El operador del servidor te ha expulsado de la partida.
+server-error-unexpected-player = No puedes actuar por otro jugador.
+server-error-saving-unavailable = Esta partida no se puede guardar.
+
+## Taking turns in any game
+
+game-started-spectator = Ahora estás mirando la partida.
+    .terse = Empezada.
+game-started-bot = Todos los jugadores conectados, empecemos.
+    .terse = Empezada.
+game-started-local-first = Empecemos. Empieza el jugador { $player }.
+    .terse = Empezada.
+game-started-online-as = Todos los jugadores conectados, empecemos. Juegas como { $player }.
+    .terse = Empezada.
+player-turn = ¡Turno del jugador { $player }!
+    .terse = Mueve { $player }.
+waiting-for-opponent = Esperando a que el otro jugador mueva.
+    .terse = Su turno.
+your-turn = ¡Es tu turno!
+    .terse = Tu turno.
+game-draw = ¡Fin de la partida! ¡Ha sido un empate!
+    .terse = Empate.
+game-won = ¡Fin de la partida! ¡Ha ganado el jugador { $player }!
+    .terse = Gana { $player }.
+turn-timed-out-forfeit = ¡El jugador { $player } se ha quedado sin tiempo y pierde la partida!
+    .terse = { $player } sin tiempo, pierde.
+turn-timed-out-skip = ¡El jugador { $player } se ha quedado sin tiempo y pierde su turno!
+    .terse = { $player } sin tiempo, pasa turno.
+rejected-moves-warning = ¡Aviso: { $attempts } movimiento(s) no válido(s) más en este turno y perderás la partida!
+    .terse = Quedan { $attempts } intento(s).
+rejected-moves-exceeded = ¡El jugador { $player } ha hecho demasiados movimientos no válidos y pierde la partida!
+    .terse = { $player } pierde, demasiados movimientos no válidos.
+not-a-number = Eso no es un número, inténtalo de nuevo.
+    .terse = No es un número.
+
+## Tic Tac Toe
+
+game-started-local = Empecemos. Escribe "/help" en tu turno para ver los comandos que puedes usar en lugar de un movimiento.
+    .terse = Empezada.
+game-started-online = Todos los jugadores conectados, empecemos. Escribe "/help" en tu turno para ver los comandos que puedes usar en lugar de un movimiento.
+    .terse = Empezada.
+variant-misere = Esto es Tres en Raya misère, ¡quien complete una línea pierde!
+    .terse = Misère: completar una línea pierde.
+variant-wild = Esto es Tres en Raya salvaje, cualquier jugador puede poner { $first } o { $second } y ¡quien complete una línea gana!
+    .terse = Salvaje: cualquier marca, completar una línea gana.
+first-player-chosen = Se ha lanzado una moneda, ¡empieza el jugador { $player }!
+    .terse = Moneda: empieza { $player }.
+move-prompt = Introduce un número entre 1 y { $cells } para mover:
+    .terse = Movimiento (1-{ $cells }):
+move-prompt-wild = Introduce un número entre 1 y { $cells } para mover, seguido de { $first } o { $second } para poner esa marca en lugar de la tuya:
+    .terse = Movimiento (1-{ $cells }, luego { $first }/{ $second }):
+unknown-mark = No existe la marca { $mark }, inténtalo de nuevo.
+    .terse = Marca desconocida.
+unknown-command = No existe el comando { $command }, escribe "/help" para ver los comandos que hay.
+    .terse = Comando desconocido, ver /help.
+undo-requested = El jugador { $player } pide deshacer su último movimiento, ¿lo permites? y/N
+    .terse = { $player } pide deshacer (y/N):
+undo-declined = Se ha rechazado la petición del jugador { $player } de deshacer su movimiento.
+    .terse = { $player } deshacer rechazado.
+move-taken-back = El jugador { $player } ha deshecho su último movimiento.
+    .terse = { $player } deshizo.
+draw-offered = El jugador { $player } ofrece tablas, ¿las aceptas? y/N
+    .terse = { $player } ofrece tablas (y/N):
+draw-declined = Se ha rechazado la oferta de tablas del jugador { $player }.
+    .terse = { $player } tablas rechazadas.
+player-resigned = El jugador { $player } se ha rendido.
+    .terse = { $player } se rindió.
+move-history = Movimientos hasta ahora:
+    .terse = Movimientos:
+move-history-entry = { $number }. El jugador { $player } ocupó la casilla { $cell } a los { $seconds } segundos.
+    .terse = { $number }. { $player } { $cell } ({ $seconds }s)
+move-history-empty = Todavía no se ha hecho ningún movimiento.
+    .terse = Sin movimientos.
+
+# Commands entered in place of a move, each listed by /help after its name
+command-list = Escribe uno de estos comandos en lugar de tu movimiento:
+    .terse = Comandos:
+command-help = muestra los comandos que puedes usar.
+command-board = vuelve a mostrar el tablero.
+command-quit = abandona la partida.
+command-undo = pide deshacer tu último movimiento.
+command-save = guarda la partida para seguirla más tarde.
+command-history = muestra los movimientos hechos.
+command-draw = ofrece tablas.
+command-resign = ríndete.
+
+# Describing the board with --screen-reader
+board-row = Fila { $row }: { $cells }.
+board-cell-empty = vacía
+board-cell-taken = { $player } ha ocupado la casilla { $cell }.
+
+tic-tac-toe-error-invalid-cell-index = Debes introducir un número entre 1 y { $cells }.
+tic-tac-toe-error-cell-occupied = Esta casilla ya está ocupada.
+tic-tac-toe-error-unexpected-player = No es tu turno.
+tic-tac-toe-error-no-move-to-take-back = No tienes ningún movimiento que deshacer.
+tic-tac-toe-error-mark-not-allowed = No puedes poner esa marca.
+
+## Nim
+
+nim-counters-taken = El jugador { $player } ha cogido { $count } ficha(s) del montón { $heap }.
+    .terse = { $player }:{ $heap }x{ $count }
+nim-game-won = ¡Fin de la partida! ¡El jugador { $player } ha cogido la última ficha y ha ganado!
+    .terse = Gana { $player }.
+nim-move-prompt = Introduce el montón del que coger y cuántas fichas coger, de 1 a { $max }, por ejemplo "2 { $max }":
+    .terse = Montón y cantidad:
+nim-not-a-move = Eso no es un montón y una cantidad, inténtalo de nuevo.
+    .terse = No es montón y cantidad.
+
+nim-error-invalid-heap = No existe el montón { $heap }.
+nim-error-invalid-count = Solo puedes coger de 1 a { $max } fichas, no { $count }.
+nim-error-not-enough-counters = Al montón { $heap } solo le quedan { $remaining } ficha(s).
+nim-error-unexpected-player = No es tu turno.
+
+## Mancala
+
+mancala-game-started-local = Empecemos. El jugador { $player } siembra primero, en la fila de abajo.
+    .terse = Empezada.
+mancala-game-started-online-bottom = Todos los jugadores conectados, empecemos. Juegas como { $player }, sembrando los hoyos de la fila de abajo.
+    .terse = Empezada.
+mancala-game-started-online-top = Todos los jugadores conectados, empecemos. Juegas como { $player }, sembrando los hoyos de la fila de arriba.
+    .terse = Empezada.
+mancala-seeds-sown = El jugador { $player } ha sembrado el hoyo { $pit }.
+    .terse = { $player }:{ $pit }
+mancala-seeds-sown-capturing = El jugador { $player } ha sembrado el hoyo { $pit }, capturando { $captured } semilla(s).
+    .terse = { $player }:{ $pit }x{ $captured }
+mancala-extra-turn = La última semilla del jugador { $player } ha caído en su almacén, así que vuelve a jugar.
+    .terse = { $player } repite.
+mancala-move-prompt = Introduce el número del hoyo que sembrar, de 1 a { $pits }:
+    .terse = Hoyo (1-{ $pits }):
+
+mancala-error-invalid-pit = No existe el hoyo { $pit }, los hoyos van del 1 al { $pits }.
+mancala-error-empty-pit = El hoyo { $pit } está vacío, así que no hay semillas que sembrar.
+mancala-error-unexpected-player = No es tu turno.
+
+## Dots and Boxes
+
+dots-and-boxes-edge-claimed = El jugador { $player } ha trazado { $edge }.
+    .terse = { $player }:{ $edge }
+dots-and-boxes-edge-claimed-completing = El jugador { $player } ha trazado { $edge }, completando { $completed } caja(s), y vuelve a jugar.
+    .terse = { $player }:{ $edge }+{ $completed }
+dots-and-boxes-score = Cajas: X { $first } - { $second } O
+dots-and-boxes-move-prompt = Introduce la línea que trazar por los puntos de cada extremo, por ejemplo "a1-b1":
+    .terse = Línea (p. ej. a1-b1):
+dots-and-boxes-not-an-edge = Eso no es una línea entre dos puntos vecinos, inténtalo de nuevo.
+    .terse = Línea no válida.
+
+dots-and-boxes-error-edge-taken = La línea { $edge } ya está trazada.
+dots-and-boxes-error-out-of-bounds = La línea { $edge } no está en la cuadrícula.
+dots-and-boxes-error-unexpected-player = No es tu turno.
+
+## Hangman
+
+hangman-game-started-local = Empecemos. El jugador { $setter } elige una palabra secreta y después el jugador { $guesser } la adivina letra a letra.
+    .terse = Empezada.
+hangman-game-started-online-setter = Todos los jugadores conectados, empecemos. Juegas como { $player }, eligiendo la palabra secreta que adivinará el otro jugador.
+    .terse = Empezada.
+hangman-game-started-online-guesser = Todos los jugadores conectados, empecemos. Juegas como { $player }, adivinando la palabra secreta elegida por el otro jugador.
+    .terse = Empezada.
+hangman-word-requested = ¡Jugador { $player }, elige tu palabra mientras el jugador { $other } no mira!
+    .terse = Elige { $player }.
+hangman-word-chosen = El jugador { $player } ha elegido una palabra secreta de { $length } letras.
+    .terse = { $player } eligió { $length } letras.
+hangman-secret-word = Tu palabra secreta es "{ $word }".
+    .terse = Secreta: { $word }
+hangman-puzzle = Palabra: { $word }
+    .terse = { $word } { $misses } { $remaining }
+hangman-puzzle-misses = Fallos: { $misses } (quedan { $remaining } fallo(s))
+hangman-no-misses = ninguno
+hangman-letter-missed = El jugador { $player } ha probado { $letter }, que no está en la palabra.
+    .terse = { $player }:{ $letter } fallo
+hangman-letter-found = El jugador { $player } ha probado { $letter }, que aparece { $found } vez/veces en la palabra.
+    .terse = { $player }:{ $letter }+{ $found }
+hangman-waiting-for-guess = Esperando a que el otro jugador pruebe una letra.
+    .terse = Su turno.
+hangman-game-won-guessed = ¡Fin de la partida! ¡El jugador { $player } ha adivinado la palabra "{ $word }" y gana!
+    .terse = Gana { $player }. { $word }
+hangman-game-won-hanged = ¡Fin de la partida! ¡Gana el jugador { $player }, la palabra era "{ $word }"!
+    .terse = Gana { $player }. { $word }
+hangman-turn-timed-out-skip = ¡El jugador { $player } se ha quedado sin tiempo y pierde un intento!
+    .terse = { $player } sin tiempo, pasa turno.
+hangman-word-prompt = Escribe la palabra secreta, de { $min } a { $max } letras de la a a la z:
+    .terse = Palabra ({ $min }-{ $max } letras):
+hangman-letter-prompt = Escribe la letra que quieres probar:
+    .terse = Letra:
+hangman-not-a-letter = Eso no es una sola letra, inténtalo de nuevo.
+    .terse = No es una letra.
+
+hangman-error-invalid-word = La palabra secreta debe tener de { $min } a { $max } letras de la a a la z.
+hangman-error-word-already-chosen = La palabra secreta ya ha sido elegida.
+hangman-error-word-not-chosen = No se pueden probar letras hasta que se elija la palabra secreta.
+hangman-error-invalid-letter = { $letter } no es una letra de la a a la z.
+hangman-error-already-guessed = { $letter } ya se ha probado.
+hangman-error-unexpected-player = No es tu turno.
+
+## Trivia
+
+trivia-game-started-local = Empecemos. Los dos jugadores responden cada pregunta y ganan un punto por cada acierto.
+    .terse = Empezada.
+trivia-game-started-online = Todos los jugadores conectados, empecemos. Gana un punto por cada pregunta que aciertes antes de que se acabe el tiempo.
+    .terse = Empezada.
+trivia-question = Pregunta { $round } de { $rounds }, con { $seconds } segundos para responder:
+    .terse = P{ $round }/{ $rounds } ({ $seconds }s): { $prompt } { $choices }
+trivia-waiting-for-answer = Esperando a que el otro jugador responda.
+    .terse = Su turno.
+trivia-player-answered = El jugador { $player } ha respondido.
+    .terse = { $player } respondió.
+trivia-answer-was = La respuesta era { $answer }.
+    .terse = Respuesta { $number }.
+trivia-answered-right = El jugador { $player } ha respondido { $choice }, que es correcta.
+    .terse = { $player }:{ $number }
+trivia-answered-wrong = El jugador { $player } ha respondido { $choice }, que es incorrecta.
+    .terse = { $player }:{ $number }
+trivia-out-of-time = Al jugador { $player } se le ha acabado el tiempo.
+    .terse = { $player }:-
+trivia-score = El marcador es X { $first } - { $second } O.
+    .terse = X { $first }-{ $second } O.
+trivia-game-won = ¡Fin de la partida! ¡Gana el jugador { $player } con { $score } acierto(s) contra { $other }!
+    .terse = Gana { $player } { $score }-{ $other }.
+trivia-game-draw = ¡Fin de la partida! Empate, con { $score } acierto(s) cada uno.
+    .terse = Empate { $score }-{ $score }.
+trivia-answer-prompt = Escribe el número de tu respuesta, del 1 al { $count }:
+    .terse = Respuesta (1-{ $count }):
+trivia-not-a-choice = Esa no es una de las opciones, inténtalo de nuevo.
+    .terse = Opción no válida.
+
+trivia-error-already-answered = Ya has respondido esta pregunta.
+trivia-error-question-closed = Esa pregunta ya se ha cerrado, responde la que se está haciendo ahora.
+trivia-error-invalid-choice = Esa no es una de las opciones.
+
+## Blackjack
+
+blackjack-game-started-local = Empecemos. Cada jugador juega su propia mano contra la banca, intentando acercarse a { $best } sin pasarse.
+    .terse = Empezada.
+blackjack-game-started-online = Todos los jugadores conectados, empecemos. Juegas como { $player }, intentando acercarte a { $best } más que la banca sin pasarte.
+    .terse = Empezada.
+blackjack-round-started = Ronda { $round } de { $rounds }, apostando { $bet } fichas. Fichas: { $chips }.
+    .terse = R{ $round }/{ $rounds } apuesta { $bet }. { $chips }.
+blackjack-dealer-up-card = Banca: { $card } ??
+    .terse = B: { $card } ??
+blackjack-player-hand = Jugador { $player }: { $hand }
+    .terse = { $player }: { $hand }
+blackjack-waiting-for-hand = Esperando a que el otro jugador juegue su mano.
+    .terse = Su turno.
+blackjack-card-dealt = El jugador { $player } ha robado { $card }, sumando { $total }.
+    .terse = { $player } robó { $card } ({ $total }).
+blackjack-card-dealt-bust = El jugador { $player } ha robado { $card }, sumando { $total }, ¡se pasa!
+    .terse = { $player } robó { $card } ({ $total }) se pasa.
+blackjack-stood = El jugador { $player } se planta con { $total }.
+    .terse = { $player } se planta { $total }.
+blackjack-standing-on = Te plantas con { $total }.
+    .terse = { $total }, plantado.
+blackjack-dealer-played = La banca muestra { $hand }.
+    .terse = B: { $hand }.
+blackjack-dealer-played-bust = La banca muestra { $hand }, ¡se pasa!
+    .terse = B: { $hand }, ¡se pasa!
+blackjack-outcome-blackjack = El jugador { $player } gana con blackjack, { $payout } fichas.
+    .terse = { $player } { $payout }
+blackjack-outcome-won = El jugador { $player } gana, { $payout } fichas.
+    .terse = { $player } { $payout }
+blackjack-outcome-push = El jugador { $player } empata con la banca, { $payout } fichas.
+    .terse = { $player } { $payout }
+blackjack-outcome-lost = El jugador { $player } pierde, { $payout } fichas.
+    .terse = { $player } { $payout }
+blackjack-outcome-busted = El jugador { $player } se ha pasado, { $payout } fichas.
+    .terse = { $player } { $payout }
+blackjack-chips = Fichas: { $chips }.
+    .terse = | { $chips }.
+blackjack-game-won = ¡Fin de la partida! ¡Gana el jugador { $player } con { $chips } fichas contra { $other }!
+    .terse = Gana { $player } { $chips }-{ $other }.
+blackjack-game-draw = ¡Fin de la partida! Empate, con { $chips } fichas cada uno.
+    .terse = Empate { $chips }-{ $chips }.
+blackjack-turn-timed-out-stand = ¡El jugador { $player } se ha quedado sin tiempo y se planta!
+    .terse = { $player } sin tiempo, se planta.
+blackjack-move-prompt = Escribe pedir o plantarse (hit/stand):
+    .terse = Pedir o plantarse (h/s):
+blackjack-not-a-move = Esa no es una jugada válida, escribe hit o stand.
+    .terse = Jugada no válida.
+
+blackjack-error-hand-complete = Tu mano ya vale 21 o más, así que debes plantarte.
+blackjack-error-unexpected-player = No es tu turno.
+
+## Battleship
+
+battleship-game-started-local = Empecemos. Cada jugador coloca su flota y después se turnan para disparar a la del otro.
+    .terse = Empezada.
+battleship-game-started-online = Todos los jugadores conectados, empecemos. Coloca tu flota y después turnaos para disparar a la del otro jugador.
+    .terse = Empezada.
+battleship-ship-carrier = portaaviones
+battleship-ship-battleship = acorazado
+battleship-ship-cruiser = crucero
+battleship-ship-submarine = submarino
+battleship-ship-destroyer = destructor
+battleship-fleet-requested = ¡Jugador { $player }, coloca tu flota!
+    .terse = Coloca { $player }.
+battleship-fleet-placed = El jugador { $player } ha colocado su flota.
+    .terse = { $player } colocó su flota.
+battleship-own-fleet = Tu flota:
+    .terse = Flota { $player }:
+battleship-fleet = Flota del jugador { $player }:
+    .terse = Flota { $player }:
+battleship-own-shots = Tus disparos:
+    .terse = Disparos { $player }:
+battleship-shots = Disparos del jugador { $player }:
+    .terse = Disparos { $player }:
+battleship-own-shot-missed = Has disparado a { $target } y has fallado.
+    .terse = { $player }:{ $target } agua
+battleship-shot-missed = El jugador { $player } ha disparado a { $target } y ha fallado.
+    .terse = { $player }:{ $target } agua
+battleship-own-shot-hit = ¡Has disparado a { $target } y has tocado un barco!
+    .terse = { $player }:{ $target } tocado
+battleship-shot-hit = ¡El jugador { $player } ha disparado a { $target } y ha tocado un barco!
+    .terse = { $player }:{ $target } tocado
+battleship-own-shot-sunk = ¡Has disparado a { $target } y has hundido el { $ship }!
+    .terse = { $player }:{ $target } hundido { $ship }
+battleship-shot-sunk = ¡El jugador { $player } ha disparado a { $target } y ha hundido el { $ship }!
+    .terse = { $player }:{ $target } hundido { $ship }
+battleship-placement-prompt = Coloca tu { $ship } ({ $length } casillas) indicando la casilla de su extremo superior o izquierdo y su orientación, como "b2 across" o "b2 down". Escribe "random" para colocar el resto de tu flota al azar:
+    .terse = { $ship } ({ $length }), p. ej. b2 across:
+battleship-not-a-placement = Esa no es una colocación válida, inténtalo de nuevo.
+    .terse = Colocación no válida.
+battleship-target-prompt = Escribe la casilla a la que disparar, como "b7":
+    .terse = Objetivo (p. ej. b7):
+battleship-not-a-square = Esa no es una casilla válida, inténtalo de nuevo.
+    .terse = Casilla no válida.
+
+battleship-error-ship-off-grid = Tu { $ship } se saldría del borde del tablero.
+battleship-error-ships-overlap = Tu { $ship } se solaparía con otro de tus barcos.
+battleship-error-incomplete-fleet = Una flota tiene uno de cada barco, colocados del más grande al más pequeño.
+battleship-error-fleet-already-placed = Tu flota ya está colocada.
+battleship-error-fleets-not-placed = No se puede disparar hasta que las dos flotas estén colocadas.
+battleship-error-off-grid = Esa casilla no está en el tablero.
+battleship-error-already-fired-at = Ya has disparado a { $target }.
+battleship-error-unexpected-player = No es tu turno.
+
+## Rock Paper Scissors
+
+rock-paper-scissors-game-started-local = Empecemos. El primer jugador en ganar { $rounds } rondas gana la partida.
+    .terse = Empezada.
+rock-paper-scissors-game-started-online = Todos los jugadores conectados, empecemos. El primer jugador en ganar { $rounds } rondas gana la partida.
+    .terse = Empezada.
+rock-paper-scissors-hand-rock = piedra
+rock-paper-scissors-hand-paper = papel
+rock-paper-scissors-hand-scissors = tijera
+rock-paper-scissors-round-started = ¡Ronda { $round }! El marcador es X { $first } - { $second } O.
+    .terse = Ronda { $round }, X { $first }-{ $second } O.
+rock-paper-scissors-waiting-for-hand = Esperando a que el otro jugador elija.
+    .terse = Su turno.
+rock-paper-scissors-hand-chosen = El jugador { $player } ha elegido su mano.
+    .terse = { $player } eligió.
+rock-paper-scissors-hand-shown = { $player } eligió { $hand }
+    .terse = { $player }:{ $hand }
+rock-paper-scissors-hands-shown = { $first } y { $second }
+    .terse = { $first } { $second }
+rock-paper-scissors-round-won = ¡El jugador { $player } gana la ronda!
+    .terse = { $player } gana la ronda
+rock-paper-scissors-round-drawn = Empate, así que la ronda se volverá a jugar.
+    .terse = empate
+rock-paper-scissors-round-played = { $hands }. { $result }
+    .terse = { $hands }, { $result }.
+rock-paper-scissors-round-played-unseen = { $result }
+    .terse = { $result }.
+rock-paper-scissors-turn-timed-out-skip = ¡El jugador { $player } se ha quedado sin tiempo y pierde la ronda!
+    .terse = { $player } sin tiempo, pierde la ronda.
+rock-paper-scissors-hand-prompt = Elige piedra (rock), papel (paper) o tijera (scissors):
+    .terse = Mano (r/p/s):
+rock-paper-scissors-not-a-hand = Esa no es una mano válida, inténtalo de nuevo.
+    .terse = Mano no válida.
+
+rock-paper-scissors-error-already-chosen = Ya has elegido tu mano en esta ronda.
+rock-paper-scissors-error-unexpected-player = No es tu turno.
+
+## Othello
+
+othello-game-started-local = Empecemos. El jugador { $player } coloca la primera ficha.
+    .terse = Empezada.
+othello-discs = Fichas: X { $first } - { $second } O
+    .terse = X{ $first }-{ $second }O
+othello-disc-placed = El jugador { $player } ha colocado una ficha en { $square }, volteando { $flipped } ficha(s).
+    .terse = { $player }:{ $square }x{ $flipped }
+othello-turn-passed = El jugador { $player } no tiene dónde colocar una ficha, así que pasa.
+    .terse = { $player } pasa.
+othello-move-prompt = Escribe la casilla en la que colocar tu ficha, como "d3":
+    .terse = Casilla (p. ej. d3):
+othello-not-a-square = Esa no es una casilla válida, inténtalo de nuevo.
+    .terse = Casilla no válida.
+
+othello-error-square-taken = Ya hay una ficha en { $square }.
+othello-error-nothing-flipped = Una ficha en { $square } no encerraría ninguna ficha del otro jugador, así que no se puede colocar ahí.
+othello-error-unexpected-player = No es tu turno.
+
+## Checkers
+
+checkers-game-started-local = Empecemos. El jugador { $player } empieza desde la parte de abajo del tablero.
+    .terse = Empezada.
+checkers-game-started-online-bottom = Todos los jugadores conectados, empecemos. Juegas desde la parte de abajo del tablero.
+    .terse = Empezada.
+checkers-game-started-online-top = Todos los jugadores conectados, empecemos. Juegas desde la parte de arriba del tablero.
+    .terse = Empezada.
+checkers-move-made = El jugador { $player } ha movido { $move }.
+    .terse = { $player }:{ $move }
+checkers-move-prompt = Escribe las casillas por las que pasa tu pieza para hacer tu movimiento, como "c3-d4" o "a3xc5xe7":
+    .terse = Movimiento (p. ej. c3-d4):
+checkers-not-a-move = Ese no es un movimiento válido, inténtalo de nuevo.
+    .terse = Movimiento no válido.
+
+checkers-error-incomplete-move = Un movimiento necesita la casilla en la que empieza la pieza y al menos una casilla a la que se mueve.
+checkers-error-unplayable-square = Las piezas solo se colocan en las casillas oscuras, y { $square } no lo es.
+checkers-error-not-your-piece = No tienes ninguna pieza en { $square }.
+checkers-error-illegal-move = Las piezas se mueven una casilla en diagonal, hacia delante salvo que estén coronadas, o saltan sobre una pieza rival a la casilla vacía de detrás.
+checkers-error-capture-required = Una de tus piezas puede capturar, así que debes capturar.
+checkers-error-capture-incomplete = Tu pieza puede volver a capturar, así que debe seguir saltando.
+checkers-error-unexpected-player = No es tu turno.
+
+## Choosing and setting up a game
+
+welcome = ¡Hola desde Rust Game Server, bienvenido a Tres en Raya, Damas, Hundir la Flota, Othello, Piedra, Papel o Tijera, Mancala, Nim, Puntos y Cajas, Ahorcado, Trivia y Blackjack!
+mode-prompt = Elige tu modo de juego; local u online (local/online).
+online-mode-prompt = ¿Quieres alojar, unirte o mirar una partida, o celebrar una exhibición (host/join/spectate/exhibition)?
+join-address-prompt = Introduce la dirección de la partida a la que unirte:
+spectate-address-prompt = Introduce la dirección de la partida que quieres mirar:
+invalid-option = Esa no es una opción válida, ¡inténtalo de nuevo!
+invalid-number = Ese no es un número válido, inténtalo de nuevo.
+port-question = ¿Quieres indicar un puerto en el que escuchar (por defecto es { $port })? y/N
+port-prompt = Indica el puerto:
+join-token-prompt = Introduce el código de acceso que ha compartido el anfitrión:
+join-token-invalid = { $error } Inténtalo de nuevo.
+turn-limit-question = ¿Quieres limitar el tiempo que tiene cada jugador para mover? y/N
+turn-limit-prompt = Indica el límite de tiempo en segundos:
+timeout-action-prompt = ¿Un jugador que se quede sin tiempo debe perder la partida o su turno (forfeit/skip)?
+first-move-prompt = ¿Quién debe hacer el primer movimiento (host/joiner/random, por defecto host)?
+spectators-question = ¿Quieres permitir que haya espectadores mirando la partida? y/N
+max-spectators-prompt = Indica el número máximo de espectadores:
+notify-players-question = ¿Se debe avisar a los jugadores cuando alguien empiece o deje de mirar? y/N
+
+## Hosting and joining
+
+host-details = ¡Pueden unirse a tu partida en el puerto { $port } con el código de acceso { $token }!
+web-details = También pueden unirse desde su navegador en http://<tu dirección>:{ $port }/?token={ $token }
+wait-timed-out = Nadie se ha unido en { $minutes } minutos. ¿Seguir esperando? Y/n
+wait-cancelled = Se ha dejado de esperar a los jugadores, así que no se jugará la partida.
+exhibition-details = ¡Pueden mirar la exhibición en el puerto { $port } con el código de acceso { $token }!
+exhibition-finished = La exhibición ha terminado.
+waiting-for-hot-seat = Esperando a que alguien se una y juegue ambos lados de la partida.
+certificate-saved = Se ha guardado un certificado autofirmado en { $path }, los jugadores necesitarán una copia para unirse.
+stats-empty = Todavía no se ha registrado ninguna partida.
+stats-record = { $game }: jugadas { $played }, ganadas { $won }, perdidas { $lost }, empatadas { $drawn }, durante { $seconds } segundos, pensando { $think_time } segundos por turno de media.
+ratings-empty = Todavía no se ha puntuado a ningún jugador.
+ratings-game = { $game }:
+ratings-entry = { $rank }. { $name }, con una puntuación de { $rating } tras { $games } partida(s).
+browse-empty = Todavía no hay partidas esperando jugadores.
+browse-game = { $number }. Sala { $room }: { $game } abierta por { $host }, a falta de { $count } jugador(es).
+browse-unnamed-host = un jugador sin nombre
+browse-prompt = Escribe el número de la partida a la que unirte, o "new" para abrir una propia:
+game-offer = El anfitrión te deja elegir a qué juego jugar:
+game-offer-entry = { $number }. { $game }
+game-offer-prompt = Escribe el número del juego al que jugar:
+
+## Failures
+
+port-in-use = El puerto { $port } ya está en uso, elige otro con --port. Abortando.
+bind-failed = Error al escuchar en el puerto { $port }: { $error }. Abortando.
+admin-socket-failed = Error al abrir el socket de administración: { $error }. Abortando.
+stats-open-failed = Error al abrir las estadísticas: { $error }. Abortando.
+stats-read-failed = Error al leer las estadísticas: { $error }. Abortando.
+replay-create-failed = Error al crear la repetición: { $error }. Abortando.
+replay-play-failed = Error al reproducir la repetición: { $error }. Abortando.
+save-open-failed = Error al abrir la partida guardada: { $error }. Abortando.
+tls-failed = Error al configurar TLS: { $error }. Abortando.
+connect-failed = Error al conectar con la partida: { $error } Abortando.
+server-address-missing = Error al buscar el servidor: no hay dirección para { $address }. Abortando.
+server-lookup-failed = Error al buscar el servidor: { $error }. Abortando.
+script-open-failed = Error al abrir el guion: { $error }. Abortando.
+bot-needs-script = Un bot solo puede jugar ambos lados de una partida a partir de un guion. Abortando.
+browse-unknown-game = El servidor aloja { $game }, que esta versión no sabe jugar. Abortando.
+tui-start-failed = Error al iniciar la interfaz de terminal: { $error }. Continuando sin ella.
+tui-failed = Error en la interfaz de terminal: { $error }.
+gui-failed = Error en la ventana de escritorio: { $error }.
@@ -0,0 +1,466 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use crate::server::{EventSender, SendError, ServerEvent, PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+#[cfg(unix)]
+pub use socket::{bind, run};
+
+const HELP: &str = "games - List the games being played.
+players <game> - Show who is taking part in a game.
+kick <game> <player> - Remove a player from a game, ending it.
+notice <message> - Send a message to everyone in every game.";
+
+/// The games being played on a dedicated server, shared between the lobby starting them and the
+/// admin socket controlling them.
+#[derive(Debug, Clone, Default)]
+pub struct ActiveGames {
+    games: Arc<Mutex<BTreeMap<u64, ActiveGame>>>,
+}
+
+#[derive(Debug)]
+struct ActiveGame {
+    /// The ID of the `GameDescriptor` being played.
+    game: &'static str,
+    started_at: Instant,
+    server_channel: EventSender,
+}
+
+impl ActiveGames {
+    pub(crate) fn insert(&self, number: u64, game: &'static str, server_channel: EventSender) {
+        let active_game = ActiveGame {
+            game,
+            started_at: Instant::now(),
+            server_channel,
+        };
+        self.games.lock().unwrap().insert(number, active_game);
+    }
+
+    pub(crate) fn remove(&self, number: u64) {
+        self.games.lock().unwrap().remove(&number);
+    }
+
+    /// Carries out the command, returning the text to send back to the operator.
+    pub async fn execute(&self, command: Command) -> Result<String, CommandError> {
+        match command {
+            Command::Help => Ok(String::from(HELP)),
+            Command::Games => Ok(self.describe_games()),
+            Command::Players { game } => {
+                let (reply, status) = oneshot::channel();
+                self.send_event(game, ServerEvent::GetStatus { reply })
+                    .await?;
+                let status = status.await.map_err(|_| CommandError::NoSuchGame(game))?;
+
+                let mut lines = status
+                    .players
+                    .into_iter()
+                    .map(|(player_id, connected)| {
+                        let state = if connected {
+                            "connected"
+                        } else {
+                            "disconnected"
+                        };
+                        format!("Player {}: {}", player_id, state)
+                    })
+                    .collect::<Vec<_>>();
+                lines.push(format!("Spectators: {}", status.spectators));
+
+                Ok(lines.join("\n"))
+            }
+            Command::Kick { game, player_id } => {
+                self.send_event(game, ServerEvent::KickPlayer { player_id })
+                    .await?;
+
+                Ok(format!(
+                    "Player {} has been removed from game {}.",
+                    player_id, game
+                ))
+            }
+            Command::Notice { message } => {
+                let server_channels = self
+                    .games
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .map(|active_game| active_game.server_channel.clone())
+                    .collect::<Vec<_>>();
+
+                let mut sent = 0;
+                for server_channel in server_channels {
+                    let event = ServerEvent::Notice {
+                        message: message.clone(),
+                    };
+                    // Games finishing in the meantime have no one left to tell, and those not
+                    // keeping up may be refused it
+                    if server_channel.send(event).await.is_ok() {
+                        sent += 1;
+                    }
+                }
+
+                Ok(format!("Sent to {} games.", sent))
+            }
+        }
+    }
+
+    fn describe_games(&self) -> String {
+        let games = self.games.lock().unwrap();
+        if games.is_empty() {
+            return String::from("No games are being played.");
+        }
+
+        games
+            .iter()
+            .map(|(number, active_game)| {
+                format!(
+                    "Game {}: {}, started {} seconds ago",
+                    number,
+                    active_game.game,
+                    active_game.started_at.elapsed().as_secs()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Feeds the event into the numbered game, provided it is still being played.
+    async fn send_event(&self, number: u64, event: ServerEvent) -> Result<(), CommandError> {
+        let server_channel = self
+            .games
+            .lock()
+            .unwrap()
+            .get(&number)
+            .map(|active_game| active_game.server_channel.clone())
+            .ok_or(CommandError::NoSuchGame(number))?;
+
+        server_channel
+            .send(event)
+            .await
+            .map_err(|error| match error {
+                SendError::Closed => CommandError::NoSuchGame(number),
+                SendError::Full => CommandError::GameBusy(number),
+            })
+    }
+}
+
+/// A command given by the server operator over the admin socket.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Games,
+    Players { game: u64 },
+    Kick { game: u64, player_id: u8 },
+    Notice { message: String },
+}
+
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let line = line.trim();
+        let (name, arguments) = line.split_once(' ').unwrap_or((line, ""));
+        let arguments = arguments.split_whitespace().collect::<Vec<_>>();
+
+        match (name, arguments.as_slice()) {
+            ("help", []) => Ok(Command::Help),
+            ("games", []) => Ok(Command::Games),
+            ("players", [game]) => Ok(Command::Players {
+                game: game
+                    .parse()
+                    .map_err(|_| CommandError::Usage("players <game>"))?,
+            }),
+            ("players", _) => Err(CommandError::Usage("players <game>")),
+            ("kick", [game, player_id]) => {
+                let usage = CommandError::Usage("kick <game> <player>");
+                let game = game.parse().map_err(|_| usage.clone())?;
+                match player_id.parse() {
+                    Ok(player_id @ (PLAYER_ONE_ID | PLAYER_TWO_ID)) => {
+                        Ok(Command::Kick { game, player_id })
+                    }
+                    _ => Err(usage),
+                }
+            }
+            ("kick", _) => Err(CommandError::Usage("kick <game> <player>")),
+            ("notice", [_, ..]) => Ok(Command::Notice {
+                message: arguments.join(" "),
+            }),
+            ("notice", []) => Err(CommandError::Usage("notice <message>")),
+            _ => Err(CommandError::Unknown),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq)]
+pub enum CommandError {
+    #[error("Unknown command, try \"help\".")]
+    Unknown,
+    #[error("Usage: {0}")]
+    Usage(&'static str),
+    #[error("There is no game {0} being played.")]
+    NoSuchGame(u64),
+    #[error("Game {0} is not keeping up, try again later.")]
+    GameBusy(u64),
+}
+
+/// Serves commands to operators connecting over a Unix socket, one per line. Only the user running
+/// the server is able to connect.
+#[cfg(unix)]
+mod socket {
+    use std::fs::{self, Permissions};
+    use std::io;
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+    use std::path::Path;
+
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+
+    use super::{ActiveGames, Command};
+
+    /// Listens for operators at the path, replacing the socket of a server that has since stopped.
+    pub fn bind(path: &Path) -> io::Result<UnixListener> {
+        if fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+            fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        fs::set_permissions(path, Permissions::from_mode(0o600))?;
+
+        Ok(listener)
+    }
+
+    /// Accepts operators for as long as the server runs, carrying out their commands on the games.
+    pub async fn run(listener: UnixListener, active_games: ActiveGames) {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                continue;
+            };
+
+            let active_games = active_games.clone();
+            tokio::spawn(async move {
+                // The operator going away mid-command leaves nothing to clean up
+                let _ = handle_operator(stream, active_games).await;
+            });
+        }
+    }
+
+    async fn handle_operator(stream: UnixStream, active_games: ActiveGames) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = match line.parse::<Command>() {
+                Ok(command) => active_games.execute(command).await,
+                Err(error) => Err(error),
+            };
+            let response = response.unwrap_or_else(|error| format!("Error: {}", error));
+            writer
+                .write_all(format!("{}\n", response).as_bytes())
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::{self, BackpressurePolicy, EventReceiver, GameStatus};
+
+    use super::*;
+
+    /// Adds a game to be controlled, returning the receiving end of its channel in place of the
+    /// server that would play it.
+    fn add_game(active_games: &ActiveGames, number: u64) -> EventReceiver {
+        add_game_with(active_games, number, BackpressurePolicy::default())
+    }
+
+    /// Adds a game as `add_game` does, whose channel is managed with the given policy.
+    fn add_game_with(
+        active_games: &ActiveGames,
+        number: u64,
+        policy: BackpressurePolicy,
+    ) -> EventReceiver {
+        let (server_channel, receiver) = server::event_queue(1, policy);
+        active_games.insert(number, "tic-tac-toe", server_channel);
+        receiver
+    }
+
+    #[test]
+    fn commands_are_parsed_with_their_arguments() {
+        assert_eq!("games".parse(), Ok(Command::Games));
+        assert_eq!(" players 3 ".parse(), Ok(Command::Players { game: 3 }));
+        assert_eq!(
+            "kick 3 2".parse(),
+            Ok(Command::Kick {
+                game: 3,
+                player_id: 2
+            })
+        );
+        assert_eq!(
+            "notice Restarting in  5 minutes.".parse(),
+            Ok(Command::Notice {
+                message: String::from("Restarting in 5 minutes.")
+            })
+        );
+    }
+
+    #[test]
+    fn commands_with_invalid_arguments_are_rejected() {
+        assert_eq!(
+            "kick 3 7".parse::<Command>(),
+            Err(CommandError::Usage("kick <game> <player>"))
+        );
+        assert_eq!(
+            "players".parse::<Command>(),
+            Err(CommandError::Usage("players <game>"))
+        );
+        assert_eq!("restart".parse::<Command>(), Err(CommandError::Unknown));
+    }
+
+    #[tokio::test]
+    async fn games_lists_each_active_game() {
+        let active_games = ActiveGames::default();
+        assert_eq!(
+            active_games.execute(Command::Games).await.unwrap(),
+            "No games are being played."
+        );
+
+        let _receiver_one = add_game(&active_games, 1);
+        let _receiver_two = add_game(&active_games, 2);
+        active_games.remove(1);
+
+        assert_eq!(
+            active_games.execute(Command::Games).await.unwrap(),
+            "Game 2: tic-tac-toe, started 0 seconds ago"
+        );
+    }
+
+    #[tokio::test]
+    async fn players_shows_status_reported_by_game() {
+        let active_games = ActiveGames::default();
+        let mut receiver = add_game(&active_games, 1);
+        tokio::spawn(async move {
+            let ServerEvent::GetStatus { reply } = receiver.recv().await else {
+                panic!("Expected the game to be asked for its status");
+            };
+            let status = GameStatus {
+                players: vec![(PLAYER_ONE_ID, true), (PLAYER_TWO_ID, false)],
+                spectators: 3,
+            };
+            reply.send(status).unwrap();
+        });
+
+        assert_eq!(
+            active_games
+                .execute(Command::Players { game: 1 })
+                .await
+                .unwrap(),
+            "Player 1: connected\nPlayer 2: disconnected\nSpectators: 3"
+        );
+    }
+
+    #[tokio::test]
+    async fn kick_is_fed_into_game() {
+        let active_games = ActiveGames::default();
+        let mut receiver = add_game(&active_games, 4);
+
+        let response = active_games
+            .execute(Command::Kick {
+                game: 4,
+                player_id: PLAYER_TWO_ID,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response, "Player 2 has been removed from game 4.");
+        assert!(matches!(
+            receiver.recv().await,
+            ServerEvent::KickPlayer { player_id: 2 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn commands_for_games_not_keeping_up_are_refused() {
+        let active_games = ActiveGames::default();
+        let _receiver = add_game_with(&active_games, 3, BackpressurePolicy::Disconnect);
+        let kick = Command::Kick {
+            game: 3,
+            player_id: PLAYER_ONE_ID,
+        };
+        active_games.execute(kick).await.unwrap();
+
+        assert_eq!(
+            active_games.execute(Command::Players { game: 3 }).await,
+            Err(CommandError::GameBusy(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn commands_for_finished_games_are_rejected() {
+        let active_games = ActiveGames::default();
+        drop(add_game(&active_games, 1));
+
+        assert_eq!(
+            active_games.execute(Command::Players { game: 1 }).await,
+            Err(CommandError::NoSuchGame(1))
+        );
+        assert_eq!(
+            active_games.execute(Command::Players { game: 2 }).await,
+            Err(CommandError::NoSuchGame(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn notice_is_sent_to_every_game() {
+        let active_games = ActiveGames::default();
+        let mut receivers = [add_game(&active_games, 1), add_game(&active_games, 2)];
+
+        let response = active_games
+            .execute(Command::Notice {
+                message: String::from("Restarting soon."),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(response, "Sent to 2 games.");
+        for receiver in receivers.iter_mut() {
+            assert!(matches!(
+                receiver.recv().await,
+                ServerEvent::Notice { message } if message == "Restarting soon."
+            ));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_answers_each_command_on_a_line() {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+        use tokio::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("game-admin-{}.sock", std::process::id()));
+        let listener = bind(&path).unwrap();
+        tokio::spawn(run(listener, ActiveGames::default()));
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        writer.write_all(b"games\n\nkick 1 2\n").await.unwrap();
+
+        assert_eq!(
+            lines.next_line().await.unwrap().unwrap(),
+            "No games are being played."
+        );
+        assert_eq!(
+            lines.next_line().await.unwrap().unwrap(),
+            "Error: There is no game 1 being played."
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+}
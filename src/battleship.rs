@@ -0,0 +1,1472 @@
+use std::collections::HashMap;
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::battleship::grid::Fleet;
+pub use crate::battleship::grid::{
+    random_placements, Cell, Coordinate, Orientation, Placement, ShipKind, ShotResult, GRID_SIZE,
+};
+use crate::battleship::ClientEvent::{FleetPlaced, ShotFired};
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod bot;
+mod grid;
+
+/// Identifies Battleship among the games in `game::GAMES`.
+pub const GAME_ID: &str = "battleship";
+
+/// Battleship, where each player secretly places a fleet on their own grid before taking turns to
+/// fire at the other's, until one fleet has been sunk.
+///
+/// Each player is only ever sent their own fleet, and what their shots have found of the other
+/// player's, while spectators only see the shots of each player.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Battleship;
+
+impl GameDescriptor for Battleship {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(BattleshipServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(BattleshipClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => Box::new(BattleshipClient::new(
+                frontend,
+                channel,
+                OnlineClient { id },
+            )),
+            ClientKind::Spectator => {
+                Box::new(BattleshipClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(BattleshipClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// Asks the player to place their fleet, before any shots are fired.
+    FleetRequested {
+        player_id: u8,
+    },
+    FleetPlaced {
+        player_id: u8,
+    },
+    /// The player's own fleet along with the shots fired at it, sent only to that player.
+    FleetUpdated {
+        player_id: u8,
+        cells: Vec<Cell>,
+    },
+    /// What the shots of the player have found of the other player's fleet.
+    TargetUpdated {
+        player_id: u8,
+        cells: Vec<Cell>,
+    },
+    ShotFired {
+        player_id: u8,
+        target: Coordinate,
+        result: ShotResult,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    GameOver {
+        winner: u8,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Your {ship} would run off the edge of the grid.")]
+    ShipOffGrid { ship: ShipKind },
+    #[error("Your {ship} would overlap another of your ships.")]
+    ShipsOverlap { ship: ShipKind },
+    #[error("A fleet is made up of one of each ship, placed from the largest down.")]
+    IncompleteFleet,
+    #[error("Your fleet has already been placed.")]
+    FleetAlreadyPlaced,
+    #[error("No shots can be fired until both fleets have been placed.")]
+    FleetsNotPlaced,
+    #[error("That square is not on the grid.")]
+    OffGrid,
+    #[error("You have already fired at {target}.")]
+    AlreadyFiredAt { target: Coordinate },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct BattleshipServer {
+    current_player: u8,
+    fleets: HashMap<u8, Fleet>,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the outcome has been sent, after which any shots still arriving are ignored.
+    game_over: bool,
+}
+
+impl BattleshipServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> BattleshipServer {
+        BattleshipServer {
+            current_player: PLAYER_ONE_ID,
+            fleets: HashMap::new(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    /// Whether both fleets have been placed, so that shots may be fired.
+    fn has_started(&self) -> bool {
+        self.fleets.len() == 2
+    }
+
+    fn swap_player(&mut self) {
+        self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_error_event(&self, player_id: u8, error: Error) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::ErrorOccurred { error },
+        )
+        .await
+    }
+
+    async fn dispatch_fleet_requested_event(&self, player_id: u8) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::FleetRequested { player_id },
+        )
+        .await
+    }
+
+    /// Sends the player their own fleet, if they have placed it.
+    async fn dispatch_fleet_updated_event(&self, player_id: u8) {
+        if let Some(fleet) = self.fleets.get(&player_id) {
+            let cells = fleet.get_owner_view();
+            self.dispatch_event(
+                DispatchMode::SinglePlayer { player_id },
+                ServerEvent::FleetUpdated { player_id, cells },
+            )
+            .await
+        }
+    }
+
+    /// Sends what the player's shots have found, if the other player has placed their fleet.
+    async fn dispatch_target_updated_event(&self, dispatch_mode: DispatchMode, player_id: u8) {
+        if let Some(fleet) = self.fleets.get(&get_alternative_player_id(player_id)) {
+            let cells = fleet.get_target_view();
+            self.dispatch_event(
+                dispatch_mode,
+                ServerEvent::TargetUpdated { player_id, cells },
+            )
+            .await
+        }
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, winner: u8) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { winner })
+            .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver {
+                winner: Some(winner),
+            })
+            .await
+    }
+
+    async fn handle_fleet_placed_event(&mut self, player_id: u8, placements: &[Placement]) {
+        if self.fleets.contains_key(&player_id) {
+            self.dispatch_error_event(player_id, Error::FleetAlreadyPlaced)
+                .await;
+            return;
+        }
+
+        let fleet = match Fleet::new(placements) {
+            Ok(fleet) => fleet,
+            Err(error) => {
+                self.dispatch_error_event(player_id, error).await;
+                self.dispatch_fleet_requested_event(player_id).await;
+                return;
+            }
+        };
+
+        self.fleets.insert(player_id, fleet);
+        self.dispatch_fleet_updated_event(player_id).await;
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::FleetPlaced { player_id },
+        )
+        .await;
+        if self.has_started() {
+            self.server_channel
+                .send(GameServerEvent::SetupComplete)
+                .await;
+            self.start_turn().await;
+        }
+    }
+
+    fn handle_shot_fired_event(
+        &mut self,
+        player_id: u8,
+        target: Coordinate,
+    ) -> Result<ShotResult, Error> {
+        if !self.has_started() {
+            return Err(Error::FleetsNotPlaced);
+        }
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.fleets
+            .get_mut(&get_alternative_player_id(player_id))
+            .unwrap()
+            .receive_shot(target)
+    }
+}
+
+#[async_trait]
+impl GameServer for BattleshipServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    fn is_setting_up(&self) -> bool {
+        !self.has_started()
+    }
+
+    async fn begin(&self) {
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            self.dispatch_fleet_requested_event(player_id).await;
+        }
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        match event {
+            FleetPlaced {
+                player_id,
+                placements,
+            } => self.handle_fleet_placed_event(player_id, &placements).await,
+            ShotFired { player_id, target } => {
+                let result = match self.handle_shot_fired_event(player_id, target) {
+                    Ok(result) => result,
+                    Err(error) => {
+                        self.dispatch_error_event(player_id, error).await;
+
+                        // Shots fired out of turn, or before every fleet is placed, are not
+                        // answered with another turn
+                        if !self.has_started() || player_id != self.current_player {
+                            return;
+                        }
+                        if self
+                            .rejected_moves
+                            .reject(&self.server_channel, self.current_player, player_id)
+                            .await
+                        {
+                            self.dispatch_game_over_event(get_alternative_player_id(player_id))
+                                .await;
+                            return;
+                        }
+
+                        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                            .await;
+
+                        return;
+                    }
+                };
+
+                let opponent_id = get_alternative_player_id(player_id);
+                self.dispatch_event(
+                    DispatchMode::AllPlayers,
+                    ServerEvent::ShotFired {
+                        player_id,
+                        target,
+                        result,
+                    },
+                )
+                .await;
+                self.dispatch_fleet_updated_event(opponent_id).await;
+                self.dispatch_target_updated_event(DispatchMode::AllPlayers, player_id)
+                    .await;
+
+                if self.fleets[&opponent_id].is_destroyed() {
+                    self.dispatch_game_over_event(player_id).await;
+                } else {
+                    self.swap_player();
+                    self.start_turn().await;
+                }
+            }
+        }
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(get_alternative_player_id(player_id))
+                    .await
+            }
+            TimeoutAction::SkipTurn => {
+                self.swap_player();
+                self.start_turn().await;
+            }
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        if !self.fleets.contains_key(&player_id) {
+            self.dispatch_fleet_requested_event(player_id).await;
+            return;
+        }
+
+        self.dispatch_fleet_updated_event(player_id).await;
+        let dispatch_mode = DispatchMode::SinglePlayer { player_id };
+        self.dispatch_target_updated_event(dispatch_mode, player_id)
+            .await;
+        if self.has_started() {
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+        }
+    }
+
+    async fn handle_spectator_joined(&self) {
+        if !self.has_started() {
+            return;
+        }
+
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            self.dispatch_target_updated_event(DispatchMode::Spectators, player_id)
+                .await;
+        }
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// Places a ship of each kind in `ShipKind::FLEET`, in the same order.
+    FleetPlaced {
+        player_id: u8,
+        placements: Vec<Placement>,
+    },
+    ShotFired {
+        player_id: u8,
+        target: Coordinate,
+    },
+}
+
+pub struct BattleshipClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    /// What the shots of the client's own player have found, for a bot to choose its next shot.
+    shots: Vec<Cell>,
+}
+
+impl<F, C> BattleshipClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> BattleshipClient<F, C> {
+        BattleshipClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            shots: vec![Cell::Water; (GRID_SIZE * GRID_SIZE) as usize],
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn is_own_player(&self, player_id: u8) -> bool {
+        self.client_type.get_own_player_id() == Some(player_id)
+    }
+
+    fn get_ship_name(&self, ship: ShipKind) -> String {
+        let key = match ship {
+            ShipKind::Carrier => "battleship-ship-carrier",
+            ShipKind::Battleship => "battleship-ship-battleship",
+            ShipKind::Cruiser => "battleship-ship-cruiser",
+            ShipKind::Submarine => "battleship-ship-submarine",
+            ShipKind::Destroyer => "battleship-ship-destroyer",
+        };
+        self.user_output.locale().message(key, &[])
+    }
+
+    fn get_cell_icon(&self, cell: Cell) -> String {
+        match cell {
+            Cell::Water => String::from("."),
+            Cell::Ship => String::from("#"),
+            Cell::Miss => String::from("o"),
+            Cell::Hit => self.user_output.paint('x', Paint::Error),
+            Cell::Sunk => self.user_output.paint('X', Paint::Error),
+        }
+    }
+
+    /// Writes the grid under a heading naming whose it is, kept to a single line in terse output.
+    fn write_grid(&self, heading: &str, cells: &[Cell]) -> Result<(), io::Error> {
+        let rows = cells.chunks(GRID_SIZE as usize);
+        let output = match self.user_output.output_style() {
+            OutputStyle::Standard => {
+                let columns: String = (0..GRID_SIZE)
+                    .map(|column| format!(" {}", char::from(b'a' + column)))
+                    .collect();
+                let rows: Vec<String> = rows
+                    .enumerate()
+                    .map(|(row, cells)| {
+                        let cells: String = cells
+                            .iter()
+                            .map(|cell| format!(" {}", self.get_cell_icon(*cell)))
+                            .collect();
+                        format!("{:>2}{}", row + 1, cells)
+                    })
+                    .collect();
+                format!("{}\n  {}\n{}\n", heading, columns, rows.join("\n"))
+            }
+            OutputStyle::Terse => {
+                let rows: Vec<String> = rows
+                    .map(|cells| cells.iter().map(|cell| self.get_cell_icon(*cell)).collect())
+                    .collect();
+                format!("{} {}", heading, rows.join("/"))
+            }
+        };
+
+        self.user_output.write_line(output)
+    }
+
+    async fn handle_fleet_placed_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        if self.is_own_player(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("battleship-fleet-placed", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_fleet_updated_event(
+        &self,
+        player_id: u8,
+        cells: Vec<Cell>,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match self.is_own_player(player_id) {
+            true => "battleship-own-fleet",
+            false => "battleship-fleet",
+        };
+        let heading = self.user_output.message(key, &[("player", &player_icon)]);
+        self.write_grid(&heading, &cells)?;
+
+        Ok(())
+    }
+
+    /// Shows what the player's shots have found, unless the fleet shot at is the client's own, as
+    /// it is then shown along with the ships.
+    async fn handle_target_updated_event(
+        &mut self,
+        player_id: u8,
+        cells: Vec<Cell>,
+    ) -> Result<(), GameClientError> {
+        if self.is_own_player(get_alternative_player_id(player_id)) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match self.is_own_player(player_id) {
+            true => "battleship-own-shots",
+            false => "battleship-shots",
+        };
+        let heading = self.user_output.message(key, &[("player", &player_icon)]);
+        self.write_grid(&heading, &cells)?;
+        if self.is_own_player(player_id) {
+            self.shots = cells;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_shot_fired_event(
+        &self,
+        player_id: u8,
+        target: Coordinate,
+        result: ShotResult,
+    ) -> Result<(), GameClientError> {
+        let own_shot = self.is_own_player(player_id);
+        let (key, ship) = match result {
+            ShotResult::Miss if own_shot => ("battleship-own-shot-missed", None),
+            ShotResult::Miss => ("battleship-shot-missed", None),
+            ShotResult::Hit if own_shot => ("battleship-own-shot-hit", None),
+            ShotResult::Hit => ("battleship-shot-hit", None),
+            ShotResult::Sunk { ship } if own_shot => ("battleship-own-shot-sunk", Some(ship)),
+            ShotResult::Sunk { ship } => ("battleship-shot-sunk", Some(ship)),
+        };
+        let ship = ship
+            .map(|ship| self.get_ship_name(ship))
+            .unwrap_or_default();
+        self.user_output.write_message(
+            key,
+            &[
+                ("player", &self.get_player_icon_by_id(player_id)),
+                ("target", &target),
+                ("ship", &ship),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, winner: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(winner);
+        self.user_output
+            .write_message("game-won", &[("player", &player_icon)])?;
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        self.write_error(error)?;
+
+        Ok(())
+    }
+
+    /// Writes the error, whether sent by the server or found while placing the fleet.
+    fn write_error(&self, error: Error) -> Result<(), io::Error> {
+        match error {
+            Error::ShipOffGrid { ship } => self.user_output.write_error(
+                "battleship-error-ship-off-grid",
+                &[("ship", &self.get_ship_name(ship))],
+            ),
+            Error::ShipsOverlap { ship } => self.user_output.write_error(
+                "battleship-error-ships-overlap",
+                &[("ship", &self.get_ship_name(ship))],
+            ),
+            Error::IncompleteFleet => self
+                .user_output
+                .write_error("battleship-error-incomplete-fleet", &[]),
+            Error::FleetAlreadyPlaced => self
+                .user_output
+                .write_error("battleship-error-fleet-already-placed", &[]),
+            Error::FleetsNotPlaced => self
+                .user_output
+                .write_error("battleship-error-fleets-not-placed", &[]),
+            Error::OffGrid => self
+                .user_output
+                .write_error("battleship-error-off-grid", &[]),
+            Error::AlreadyFiredAt { target } => self
+                .user_output
+                .write_error("battleship-error-already-fired-at", &[("target", &target)]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("battleship-error-unexpected-player", &[]),
+        }
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn place_player_fleet(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let placements = self.get_placements().await?;
+        self.send_event(FleetPlaced {
+            player_id,
+            placements,
+        })
+        .await
+    }
+
+    async fn fire_player_shot(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let target = self.get_target().await?;
+        self.send_event(ShotFired { player_id, target }).await
+    }
+
+    async fn send_event(&mut self, event: ClientEvent) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer { event })
+            .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads where to place each ship of the fleet in turn, checking each fits alongside those
+    /// already placed so that the user can correct it straight away.
+    async fn get_placements(&mut self) -> Result<Vec<Placement>, io::Error> {
+        let mut placements = Vec::new();
+        while let Some(ship) = ShipKind::FLEET.get(placements.len()) {
+            self.user_output.write_message(
+                "battleship-placement-prompt",
+                &[
+                    ("ship", &self.get_ship_name(*ship)),
+                    ("length", &ship.length()),
+                ],
+            )?;
+
+            let input_text = self.frontend.read_line().await?;
+            if input_text.trim().eq_ignore_ascii_case("random") {
+                return Ok(random_placements(&placements));
+            }
+
+            match input_text.parse::<Placement>() {
+                Err(_) => self
+                    .user_output
+                    .write_message("battleship-not-a-placement", &[])?,
+                Ok(placement) => {
+                    placements.push(placement);
+                    if let Err(error) = grid::check_placements(&placements) {
+                        placements.pop();
+                        self.write_error(error)?;
+                    }
+                }
+            }
+        }
+
+        Ok(placements)
+    }
+
+    /// Reads the square to fire at, leaving the server to decide whether it may be fired at.
+    async fn get_target(&mut self) -> Result<Coordinate, io::Error> {
+        loop {
+            self.user_output
+                .write_message("battleship-target-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.trim().parse() {
+                Err(_) => self
+                    .user_output
+                    .write_message("battleship-not-a-square", &[])?,
+                Ok(target) => return Ok(target),
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_fleet_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BattleshipClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output
+            .message("battleship-game-started-local", &[])
+    }
+
+    async fn handle_fleet_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("battleship-fleet-requested", &[("player", &player_icon)])?;
+
+        self.place_player_fleet(player_id).await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.fire_player_shot(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BattleshipClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output
+            .message("battleship-game-started-online", &[])
+    }
+
+    async fn handle_fleet_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.place_player_fleet(player_id).await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.fire_player_shot(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BattleshipClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_fleet_requested_event(
+        &mut self,
+        _player_id: u8,
+    ) -> Result<(), GameClientError> {
+        Ok(())
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BattleshipClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    /// Bots place their fleet at random.
+    async fn handle_fleet_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.send_event(FleetPlaced {
+            player_id,
+            placements: random_placements(&[]),
+        })
+        .await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(target) = bot::choose_target(&self.shots) {
+            self.send_event(ShotFired { player_id, target }).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for BattleshipClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::FleetRequested { player_id } => {
+                self.handle_fleet_requested_event(player_id).await
+            }
+            ServerEvent::FleetPlaced { player_id } => {
+                self.handle_fleet_placed_event(player_id).await
+            }
+            ServerEvent::FleetUpdated { player_id, cells } => {
+                self.handle_fleet_updated_event(player_id, cells).await
+            }
+            ServerEvent::TargetUpdated { player_id, cells } => {
+                self.handle_target_updated_event(player_id, cells).await
+            }
+            ServerEvent::ShotFired {
+                player_id,
+                target,
+                result,
+            } => {
+                self.handle_shot_fired_event(player_id, target, result)
+                    .await
+            }
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { winner } => self.handle_game_over_event(winner).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+    use crate::connection::{self, Codec, SUPPORTED_CODECS};
+    use crate::server::OutgoingEvent;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        BattleshipClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = BattleshipClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    /// Every ship starting in the first column, one to each row from the top.
+    fn stacked_placements() -> Vec<Placement> {
+        [
+            "a1 across",
+            "a2 across",
+            "a3 across",
+            "a4 across",
+            "a5 across",
+        ]
+        .into_iter()
+        .map(|placement| placement.parse().unwrap())
+        .collect()
+    }
+
+    async fn send_client_event(server: &mut BattleshipServer, event: ClientEvent) {
+        server.handle_event(event).await;
+    }
+
+    async fn place_both_fleets(server: &mut BattleshipServer) {
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            send_client_event(
+                server,
+                FleetPlaced {
+                    player_id,
+                    placements: stacked_placements(),
+                },
+            )
+            .await;
+        }
+    }
+
+    async fn fire(server: &mut BattleshipServer, player_id: u8, target: &str) {
+        let target = target.parse().unwrap();
+        send_client_event(server, ShotFired { player_id, target }).await;
+    }
+
+    /// Collects the events dispatched by the server, along with who each was sent to.
+    fn drain_dispatched_events(
+        receiver: &mut Receiver<GameServerEvent>,
+    ) -> Vec<(DispatchMode, ServerEvent)> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            } = event
+            {
+                events.push((dispatch_mode, game::deserialize_event(event)));
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn both_grids_fit_within_event_length_limit() {
+        let event = OutgoingEvent::Game {
+            event: game::serialize_event(ServerEvent::FleetUpdated {
+                player_id: PLAYER_ONE_ID,
+                cells: vec![Cell::Sunk; (GRID_SIZE * GRID_SIZE) as usize],
+            }),
+        };
+
+        for codec in SUPPORTED_CODECS {
+            assert!(codec.encode(&event).unwrap().len() <= connection::MAX_EVENT_LENGTH);
+        }
+    }
+
+    #[tokio::test]
+    async fn server_asks_each_player_for_their_fleet_before_turns_start() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+
+        server.begin().await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                (
+                    DispatchMode::SinglePlayer {
+                        player_id: PLAYER_ONE_ID
+                    },
+                    ServerEvent::FleetRequested {
+                        player_id: PLAYER_ONE_ID
+                    }
+                ),
+                (
+                    DispatchMode::SinglePlayer {
+                        player_id: PLAYER_TWO_ID
+                    },
+                    ServerEvent::FleetRequested {
+                        player_id: PLAYER_TWO_ID
+                    }
+                ),
+            ]
+        ));
+
+        place_both_fleets(&mut server).await;
+        let events = drain_dispatched_events(&mut receiver);
+        assert!(matches!(
+            events.last(),
+            Some((
+                DispatchMode::AllPlayers,
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn setup_completes_once_both_fleets_are_placed_in_either_order() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+        assert!(server.is_setting_up());
+
+        for player_id in [PLAYER_TWO_ID, PLAYER_ONE_ID] {
+            send_client_event(
+                &mut server,
+                FleetPlaced {
+                    player_id,
+                    placements: stacked_placements(),
+                },
+            )
+            .await;
+        }
+
+        assert!(!server.is_setting_up());
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if !matches!(event, GameServerEvent::DispatchToClient { .. }) {
+                events.push(event);
+            }
+        }
+        assert!(matches!(
+            events[..],
+            [
+                GameServerEvent::SetupComplete,
+                GameServerEvent::TurnStarted {
+                    player_id: PLAYER_ONE_ID
+                }
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_sends_fleet_only_to_its_owner() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+        place_both_fleets(&mut server).await;
+        drain_dispatched_events(&mut receiver);
+
+        fire(&mut server, PLAYER_ONE_ID, "a1").await;
+
+        for (dispatch_mode, event) in drain_dispatched_events(&mut receiver) {
+            match event {
+                ServerEvent::FleetUpdated { player_id, cells } => {
+                    assert_eq!(player_id, PLAYER_TWO_ID);
+                    assert!(matches!(
+                        dispatch_mode,
+                        DispatchMode::SinglePlayer {
+                            player_id: PLAYER_TWO_ID
+                        }
+                    ));
+                    assert_eq!(cells[0..2], [Cell::Hit, Cell::Ship]);
+                }
+                ServerEvent::TargetUpdated { player_id, cells } => {
+                    assert_eq!(player_id, PLAYER_ONE_ID);
+                    assert_eq!(cells[0..2], [Cell::Hit, Cell::Water]);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn server_refuses_shots_before_fleets_placed() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+
+        fire(&mut server, PLAYER_ONE_ID, "a1").await;
+
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [(
+                _,
+                ServerEvent::ErrorOccurred {
+                    error: Error::FleetsNotPlaced
+                }
+            )]
+        ));
+        assert_eq!(server.rejected_moves.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn server_asks_again_for_invalid_fleet() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+
+        let mut placements = stacked_placements();
+        placements[1] = "a1 down".parse().unwrap();
+        send_client_event(
+            &mut server,
+            FleetPlaced {
+                player_id: PLAYER_TWO_ID,
+                placements,
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                (
+                    _,
+                    ServerEvent::ErrorOccurred {
+                        error: Error::ShipsOverlap {
+                            ship: ShipKind::Battleship
+                        }
+                    }
+                ),
+                (
+                    _,
+                    ServerEvent::FleetRequested {
+                        player_id: PLAYER_TWO_ID
+                    }
+                ),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_shot_at_same_square_twice() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BattleshipServer::new(sender);
+        place_both_fleets(&mut server).await;
+        fire(&mut server, PLAYER_ONE_ID, "j10").await;
+        fire(&mut server, PLAYER_TWO_ID, "j10").await;
+        drain_dispatched_events(&mut receiver);
+
+        fire(&mut server, PLAYER_ONE_ID, "j10").await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                (
+                    _,
+                    ServerEvent::ErrorOccurred {
+                        error: Error::AlreadyFiredAt { .. }
+                    }
+                ),
+                (
+                    _,
+                    ServerEvent::PlayerTurn {
+                        player_id: PLAYER_ONE_ID
+                    }
+                ),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_fleet_sunk() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(100);
+        let mut server = BattleshipServer::new(sender);
+        place_both_fleets(&mut server).await;
+
+        // Player two only fires at open water, in the rows below the fleet
+        let ship_squares = ShipKind::FLEET
+            .iter()
+            .enumerate()
+            .flat_map(|(row, ship)| (0..ship.length()).map(move |column| (row as u8, column)));
+        let mut events = Vec::new();
+        for (shot, (row, column)) in ship_squares.enumerate() {
+            assert!(!server.game_over);
+            let target = Coordinate::new(row, column).to_string();
+            fire(&mut server, PLAYER_ONE_ID, &target).await;
+            let target = Coordinate::from_index(50 + shot).to_string();
+            fire(&mut server, PLAYER_TWO_ID, &target).await;
+            events = drain_dispatched_events(&mut receiver);
+        }
+
+        assert!(server.game_over);
+        assert!(matches!(
+            events.last(),
+            Some((
+                DispatchMode::AllPlayers,
+                ServerEvent::GameOver {
+                    winner: PLAYER_ONE_ID
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_shows_own_fleet_and_shots() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let mut cells = vec![Cell::Water; (GRID_SIZE * GRID_SIZE) as usize];
+        cells[0] = Cell::Ship;
+        cells[1] = Cell::Hit;
+        cells[99] = Cell::Miss;
+        client
+            .handle_fleet_updated_event(PLAYER_ONE_ID, cells.clone())
+            .await
+            .unwrap();
+        // The other player's shots at the client's fleet are already shown on the fleet
+        client
+            .handle_target_updated_event(PLAYER_TWO_ID, cells.clone())
+            .await
+            .unwrap();
+        client
+            .handle_target_updated_event(PLAYER_ONE_ID, cells.clone())
+            .await
+            .unwrap();
+
+        let rows = "#x......../........../........../........../........../........../........../........../........../.........o";
+        assert_client_output(output, &format!("X fleet: {}\nX shots: {}\n", rows, rows));
+        assert_eq!(client.shots, cells);
+    }
+
+    #[tokio::test]
+    async fn client_shows_grid_with_coordinates() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        let mut cells = vec![Cell::Water; (GRID_SIZE * GRID_SIZE) as usize];
+        cells[10] = Cell::Sunk;
+        client
+            .handle_target_updated_event(PLAYER_TWO_ID, cells)
+            .await
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Player O's shots:\n   \
+             a b c d e f g h i j\n \
+             1 . . . . . . . . . .\n \
+             2 X . . . . . . . . .\n \
+             3 . . . . . . . . . .\n \
+             4 . . . . . . . . . .\n \
+             5 . . . . . . . . . .\n \
+             6 . . . . . . . . . .\n \
+             7 . . . . . . . . . .\n \
+             8 . . . . . . . . . .\n \
+             9 . . . . . . . . . .\n\
+             10 . . . . . . . . . .\n\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_describes_shots() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 2 }).await;
+
+        let target = "b7".parse().unwrap();
+        client
+            .handle_shot_fired_event(PLAYER_ONE_ID, target, ShotResult::Miss)
+            .await
+            .unwrap();
+        client
+            .handle_shot_fired_event(
+                PLAYER_TWO_ID,
+                target,
+                ShotResult::Sunk {
+                    ship: ShipKind::Cruiser,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Player X fired at b7 and missed.\nYou fired at b7 and sank the cruiser!\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 2 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_shot_fired_event(
+                PLAYER_TWO_ID,
+                "b7".parse().unwrap(),
+                ShotResult::Sunk {
+                    ship: ShipKind::Cruiser,
+                },
+            )
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::ShipsOverlap {
+                ship: ShipKind::Carrier,
+            })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "¡Has disparado a b7 y has hundido el crucero!\n\
+             Error: Tu portaaviones se solaparía con otro de tus barcos.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_places_fleet_correcting_invalid_placements() {
+        let input = "a1 across\na1 down\nb2\na2 across\nrandom\n".as_bytes();
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(input, OnlineClient { id: 1 }).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        client.handle_fleet_requested_event(1).await.unwrap();
+
+        assert_client_output(
+            output,
+            "carrier (5), e.g. b2 across:\n\
+             battleship (4), e.g. b2 across:\n\
+             Error: Your battleship would overlap another of your ships.\n\
+             battleship (4), e.g. b2 across:\n\
+             Invalid placement.\n\
+             battleship (4), e.g. b2 across:\n\
+             cruiser (3), e.g. b2 across:\n",
+        );
+        let Some(GameClientEvent::DispatchToServer { event }) = receiver.recv().await else {
+            panic!("Expected the fleet to be sent to the server");
+        };
+        let FleetPlaced { placements, .. } = game::deserialize_event(event) else {
+            panic!("Expected the fleet to be placed");
+        };
+        assert_eq!(placements[..2], stacked_placements()[..2]);
+        assert!(Fleet::new(&placements).is_ok());
+    }
+
+    #[tokio::test]
+    async fn client_get_target_handles_errors_until_valid_square_provided() {
+        let input = "k1\nb7\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+
+        let target = client.get_target().await.unwrap();
+        assert_eq!(target.to_string(), "b7");
+        assert_client_output(
+            output,
+            "Input the square to fire at, such as \"b7\":\n\
+             That is not a valid square, please try again.\n\
+             Input the square to fire at, such as \"b7\":\n",
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bot_client_places_fleet_and_fires() {
+        let bot_client = BotClient {
+            id: 2,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_fleet_requested_event(2).await.unwrap();
+        let Some(GameClientEvent::DispatchToServer { event }) = receiver.recv().await else {
+            panic!("Expected the fleet to be sent to the server");
+        };
+        assert!(matches!(
+            game::deserialize_event(event),
+            FleetPlaced { player_id: 2, .. }
+        ));
+
+        client.handle_player_turn_event(2).await.unwrap();
+        let Some(GameClientEvent::DispatchToServer { event }) = receiver.recv().await else {
+            panic!("Expected a shot to be sent to the server");
+        };
+        assert!(matches!(
+            game::deserialize_event(event),
+            ShotFired { player_id: 2, .. }
+        ));
+    }
+}
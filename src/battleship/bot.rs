@@ -0,0 +1,106 @@
+use rand::seq::SliceRandom;
+
+use crate::battleship::grid::{Cell, Coordinate};
+
+/// Chooses a square to fire at, based on what the bot's shots have found so far.
+///
+/// While a ship has been hit without being sunk, the bot fires next to the hits to finish it off,
+/// continuing along a line of hits where it can. Otherwise it hunts at random over every other
+/// square, as even the smallest ship cannot fit between them.
+///
+/// # Returns
+///
+/// - `Some(Coordinate)` containing the square to fire at.
+/// - `None` if every square has been fired at.
+pub(crate) fn choose_target(cells: &[Cell]) -> Option<Coordinate> {
+    let is_unknown = |square: &Coordinate| cells[square.index()] == Cell::Water;
+    let is_hit = |square: &Coordinate| cells[square.index()] == Cell::Hit;
+    let mut rng = rand::thread_rng();
+
+    let hits: Vec<Coordinate> = (0..cells.len())
+        .map(Coordinate::from_index)
+        .filter(is_hit)
+        .collect();
+    let next_to_hits: Vec<(Coordinate, Coordinate)> = hits
+        .iter()
+        .flat_map(|hit| {
+            hit.neighbours()
+                .filter(is_unknown)
+                .map(move |square| (*hit, square))
+        })
+        .collect();
+    // A square continues a line when the hit beside it has another hit on its far side
+    let continuing_line = next_to_hits.iter().find(|(hit, square)| {
+        let row = 2 * hit.row as i16 - square.row as i16;
+        let column = 2 * hit.column as i16 - square.column as i16;
+        hit.neighbours().any(|beyond| {
+            beyond.row as i16 == row && beyond.column as i16 == column && is_hit(&beyond)
+        })
+    });
+    if let Some((_, square)) = continuing_line.or(next_to_hits.first()) {
+        return Some(*square);
+    }
+
+    let unknown: Vec<Coordinate> = (0..cells.len())
+        .map(Coordinate::from_index)
+        .filter(is_unknown)
+        .collect();
+    let spaced: Vec<Coordinate> = unknown
+        .iter()
+        .copied()
+        .filter(|square| (square.row + square.column) % 2 == 0)
+        .collect();
+
+    spaced
+        .choose(&mut rng)
+        .or_else(|| unknown.choose(&mut rng))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::battleship::grid::GRID_SIZE;
+
+    use super::*;
+
+    fn cells_with(known: &[(&str, Cell)]) -> Vec<Cell> {
+        let mut cells = vec![Cell::Water; (GRID_SIZE * GRID_SIZE) as usize];
+        for (square, cell) in known {
+            let square: Coordinate = square.parse().unwrap();
+            cells[square.index()] = *cell;
+        }
+
+        cells
+    }
+
+    #[test]
+    fn choose_target_fires_next_to_hit() {
+        let cells = cells_with(&[("e5", Cell::Hit), ("e4", Cell::Miss)]);
+
+        let target = choose_target(&cells).unwrap();
+        assert!(["e6", "d5", "f5"].contains(&target.to_string().as_str()));
+    }
+
+    #[test]
+    fn choose_target_continues_line_of_hits() {
+        let cells = cells_with(&[("e5", Cell::Hit), ("f5", Cell::Hit), ("d5", Cell::Miss)]);
+
+        assert_eq!(choose_target(&cells).unwrap().to_string(), "g5");
+    }
+
+    #[test]
+    fn choose_target_hunts_unknown_squares_when_ships_sunk() {
+        let cells = cells_with(&[("a1", Cell::Sunk), ("b1", Cell::Sunk)]);
+
+        let target = choose_target(&cells).unwrap();
+        assert_eq!(cells[target.index()], Cell::Water);
+        assert_eq!((target.row + target.column) % 2, 0);
+    }
+
+    #[test]
+    fn choose_target_returns_none_once_every_square_fired_at() {
+        let cells = vec![Cell::Miss; (GRID_SIZE * GRID_SIZE) as usize];
+
+        assert_eq!(choose_target(&cells), None);
+    }
+}
@@ -0,0 +1,508 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::battleship::Error;
+
+/// The width and height of each player's grid.
+pub const GRID_SIZE: u8 = 10;
+
+/// A square on a grid, counted from the top left.
+///
+/// Squares are written as a player would enter them, with a letter for the column and a number
+/// for the row, such as "b7".
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coordinate {
+    pub row: u8,
+    pub column: u8,
+}
+
+impl Coordinate {
+    pub const fn new(row: u8, column: u8) -> Coordinate {
+        Coordinate { row, column }
+    }
+
+    pub fn is_on_grid(&self) -> bool {
+        self.row < GRID_SIZE && self.column < GRID_SIZE
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        (self.row * GRID_SIZE + self.column) as usize
+    }
+
+    pub(crate) fn from_index(index: usize) -> Coordinate {
+        Coordinate::new(index as u8 / GRID_SIZE, index as u8 % GRID_SIZE)
+    }
+
+    /// The squares directly above, below and to either side, leaving out any off the grid.
+    pub(crate) fn neighbours(&self) -> impl Iterator<Item = Coordinate> {
+        let Coordinate { row, column } = *self;
+        [
+            row.checked_sub(1).map(|row| Coordinate::new(row, column)),
+            Some(Coordinate::new(row + 1, column)),
+            column
+                .checked_sub(1)
+                .map(|column| Coordinate::new(row, column)),
+            Some(Coordinate::new(row, column + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(Coordinate::is_on_grid)
+    }
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", char::from(b'a' + self.column), self.row + 1)
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = InvalidCoordinate;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let column = chars
+            .next()
+            .map(|column| column.to_ascii_lowercase())
+            .filter(|column| ('a'..(b'a' + GRID_SIZE) as char).contains(column))
+            .ok_or(InvalidCoordinate)?;
+        let row: u8 = chars.as_str().parse().map_err(|_| InvalidCoordinate)?;
+        if !(1..=GRID_SIZE).contains(&row) {
+            return Err(InvalidCoordinate);
+        }
+
+        Ok(Coordinate::new(row - 1, column as u8 - b'a'))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidCoordinate;
+
+/// The ships making up each fleet, one of each.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ShipKind {
+    Carrier,
+    Battleship,
+    Cruiser,
+    Submarine,
+    Destroyer,
+}
+
+impl ShipKind {
+    /// Every ship in a fleet, in the order they are placed.
+    pub const FLEET: [ShipKind; 5] = [
+        ShipKind::Carrier,
+        ShipKind::Battleship,
+        ShipKind::Cruiser,
+        ShipKind::Submarine,
+        ShipKind::Destroyer,
+    ];
+
+    /// The number of squares the ship takes up.
+    pub fn length(&self) -> u8 {
+        match self {
+            ShipKind::Carrier => 5,
+            ShipKind::Battleship => 4,
+            ShipKind::Cruiser | ShipKind::Submarine => 3,
+            ShipKind::Destroyer => 2,
+        }
+    }
+}
+
+impl fmt::Display for ShipKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ShipKind::Carrier => "carrier",
+            ShipKind::Battleship => "battleship",
+            ShipKind::Cruiser => "cruiser",
+            ShipKind::Submarine => "submarine",
+            ShipKind::Destroyer => "destroyer",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Orientation {
+    Across,
+    Down,
+}
+
+/// Where a ship is placed, by the square at its top or left end and the way it runs from there.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Placement {
+    pub start: Coordinate,
+    pub orientation: Orientation,
+}
+
+impl Placement {
+    /// The squares taken up by a ship of the given kind, which may run off the grid.
+    fn squares(&self, kind: ShipKind) -> impl Iterator<Item = Coordinate> + '_ {
+        (0..kind.length()).map(move |offset| match self.orientation {
+            Orientation::Across => {
+                Coordinate::new(self.start.row, self.start.column.saturating_add(offset))
+            }
+            Orientation::Down => {
+                Coordinate::new(self.start.row.saturating_add(offset), self.start.column)
+            }
+        })
+    }
+}
+
+impl FromStr for Placement {
+    type Err = InvalidCoordinate;
+
+    /// Reads a placement written as its starting square followed by "across" or "down", such as
+    /// "b2 across", allowing the direction to be shortened to its first letter.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let start = words.next().ok_or(InvalidCoordinate)?.parse()?;
+        let orientation = match words.next().map(str::to_ascii_lowercase).as_deref() {
+            Some("across" | "a") => Orientation::Across,
+            Some("down" | "d") => Orientation::Down,
+            _ => return Err(InvalidCoordinate),
+        };
+        if words.next().is_some() {
+            return Err(InvalidCoordinate);
+        }
+
+        Ok(Placement { start, orientation })
+    }
+}
+
+/// What is known about a square, as shown on a grid.
+///
+/// Sent as a single number to keep a pair of grids within the length allowed for an event.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum Cell {
+    /// Open water, or a square that has not been fired at when viewing the other player's grid.
+    Water,
+    /// Part of a ship that has not been hit.
+    Ship,
+    Miss,
+    Hit,
+    /// Part of a ship that has been hit on every square.
+    Sunk,
+}
+
+impl From<Cell> for u8 {
+    fn from(cell: Cell) -> u8 {
+        cell as u8
+    }
+}
+
+impl TryFrom<u8> for Cell {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        [Cell::Water, Cell::Ship, Cell::Miss, Cell::Hit, Cell::Sunk]
+            .get(value as usize)
+            .copied()
+            .ok_or_else(|| format!("{} is not a cell", value))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ShotResult {
+    Miss,
+    Hit,
+    Sunk { ship: ShipKind },
+}
+
+struct Ship {
+    kind: ShipKind,
+    squares: Vec<Coordinate>,
+}
+
+/// Checks that each placement fits on the grid without overlapping those before it, giving a
+/// placement for each ship of the fleet in turn. Fewer placements than ships may be given, so
+/// that a fleet can be checked as it is being placed.
+pub(crate) fn check_placements(placements: &[Placement]) -> Result<(), Error> {
+    place_ships(placements).map(|_| ())
+}
+
+fn place_ships(placements: &[Placement]) -> Result<Vec<Ship>, Error> {
+    if placements.len() > ShipKind::FLEET.len() {
+        return Err(Error::IncompleteFleet);
+    }
+
+    let mut ships: Vec<Ship> = Vec::new();
+    for (placement, kind) in placements.iter().zip(ShipKind::FLEET) {
+        let squares: Vec<Coordinate> = placement.squares(kind).collect();
+        if !squares.iter().all(Coordinate::is_on_grid) {
+            return Err(Error::ShipOffGrid { ship: kind });
+        }
+        if ships
+            .iter()
+            .any(|ship| ship.squares.iter().any(|square| squares.contains(square)))
+        {
+            return Err(Error::ShipsOverlap { ship: kind });
+        }
+
+        ships.push(Ship { kind, squares });
+    }
+
+    Ok(ships)
+}
+
+/// Places the rest of the fleet at random after the ships already placed, for players happy to
+/// leave it to chance.
+pub fn random_placements(placed: &[Placement]) -> Vec<Placement> {
+    let mut rng = rand::thread_rng();
+    let mut placements = placed.to_vec();
+    while placements.len() < ShipKind::FLEET.len() {
+        let kind = ShipKind::FLEET[placements.len()];
+        let orientation = if rng.gen() {
+            Orientation::Across
+        } else {
+            Orientation::Down
+        };
+        // Keeping the start far enough from the edge leaves overlapping as the only way to fail
+        let (rows, columns) = match orientation {
+            Orientation::Across => (GRID_SIZE, GRID_SIZE - kind.length() + 1),
+            Orientation::Down => (GRID_SIZE - kind.length() + 1, GRID_SIZE),
+        };
+        placements.push(Placement {
+            start: Coordinate::new(rng.gen_range(0..rows), rng.gen_range(0..columns)),
+            orientation,
+        });
+        if check_placements(&placements).is_err() {
+            placements.pop();
+        }
+    }
+
+    placements
+}
+
+/// A player's fleet, along with the shots fired at it by the other player.
+pub struct Fleet {
+    ships: Vec<Ship>,
+    shots_received: Vec<bool>,
+}
+
+impl Fleet {
+    pub(crate) fn new(placements: &[Placement]) -> Result<Fleet, Error> {
+        if placements.len() != ShipKind::FLEET.len() {
+            return Err(Error::IncompleteFleet);
+        }
+
+        Ok(Fleet {
+            ships: place_ships(placements)?,
+            shots_received: vec![false; (GRID_SIZE * GRID_SIZE) as usize],
+        })
+    }
+
+    fn ship_at(&self, square: Coordinate) -> Option<&Ship> {
+        self.ships
+            .iter()
+            .find(|ship| ship.squares.contains(&square))
+    }
+
+    fn is_ship_sunk(&self, ship: &Ship) -> bool {
+        ship.squares
+            .iter()
+            .all(|square| self.shots_received[square.index()])
+    }
+
+    pub(crate) fn receive_shot(&mut self, target: Coordinate) -> Result<ShotResult, Error> {
+        if !target.is_on_grid() {
+            return Err(Error::OffGrid);
+        }
+        if self.shots_received[target.index()] {
+            return Err(Error::AlreadyFiredAt { target });
+        }
+
+        self.shots_received[target.index()] = true;
+        Ok(match self.ship_at(target) {
+            None => ShotResult::Miss,
+            Some(ship) if self.is_ship_sunk(ship) => ShotResult::Sunk { ship: ship.kind },
+            Some(_) => ShotResult::Hit,
+        })
+    }
+
+    /// Whether every ship in the fleet has been sunk, losing the game.
+    pub(crate) fn is_destroyed(&self) -> bool {
+        self.ships.iter().all(|ship| self.is_ship_sunk(ship))
+    }
+
+    fn get_cell(&self, square: Coordinate, reveal_ships: bool) -> Cell {
+        let fired_at = self.shots_received[square.index()];
+        match self.ship_at(square) {
+            Some(ship) if self.is_ship_sunk(ship) => Cell::Sunk,
+            Some(_) if fired_at => Cell::Hit,
+            Some(_) if reveal_ships => Cell::Ship,
+            None if fired_at => Cell::Miss,
+            _ => Cell::Water,
+        }
+    }
+
+    fn get_cells(&self, reveal_ships: bool) -> Vec<Cell> {
+        (0..self.shots_received.len())
+            .map(|index| self.get_cell(Coordinate::from_index(index), reveal_ships))
+            .collect()
+    }
+
+    /// The fleet as its owner sees it, with every ship shown.
+    pub(crate) fn get_owner_view(&self) -> Vec<Cell> {
+        self.get_cells(true)
+    }
+
+    /// The fleet as the other player sees it, showing only what their shots have found.
+    pub(crate) fn get_target_view(&self) -> Vec<Cell> {
+        self.get_cells(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(text: &str) -> Placement {
+        text.parse().unwrap()
+    }
+
+    /// Every ship starting in the first column, one to each row from the top.
+    fn stacked_placements() -> Vec<Placement> {
+        [
+            "a1 across",
+            "a2 across",
+            "a3 across",
+            "a4 across",
+            "a5 across",
+        ]
+        .into_iter()
+        .map(placement)
+        .collect()
+    }
+
+    #[test]
+    fn coordinate_is_written_as_column_letter_and_row_number() {
+        let coordinate: Coordinate = "J10".parse().unwrap();
+
+        assert_eq!(coordinate, Coordinate::new(9, 9));
+        assert_eq!(coordinate.to_string(), "j10");
+        assert_eq!("k1".parse::<Coordinate>(), Err(InvalidCoordinate));
+        assert_eq!("a0".parse::<Coordinate>(), Err(InvalidCoordinate));
+        assert_eq!("a11".parse::<Coordinate>(), Err(InvalidCoordinate));
+    }
+
+    #[test]
+    fn placement_is_read_with_direction() {
+        assert_eq!(
+            placement("b2 down"),
+            Placement {
+                start: Coordinate::new(1, 1),
+                orientation: Orientation::Down,
+            }
+        );
+        assert_eq!(placement("c3 A").orientation, Orientation::Across);
+        assert!("b2".parse::<Placement>().is_err());
+        assert!("b2 sideways".parse::<Placement>().is_err());
+    }
+
+    #[test]
+    fn ships_cannot_run_off_grid() {
+        let result = check_placements(&[placement("g1 across")]);
+
+        assert!(matches!(
+            result,
+            Err(Error::ShipOffGrid {
+                ship: ShipKind::Carrier
+            })
+        ));
+    }
+
+    #[test]
+    fn ships_cannot_overlap() {
+        let result = check_placements(&[placement("a1 across"), placement("c1 down")]);
+
+        assert!(matches!(
+            result,
+            Err(Error::ShipsOverlap {
+                ship: ShipKind::Battleship
+            })
+        ));
+    }
+
+    #[test]
+    fn fleet_needs_every_ship() {
+        assert!(matches!(
+            Fleet::new(&stacked_placements()[..4]),
+            Err(Error::IncompleteFleet)
+        ));
+        assert!(Fleet::new(&stacked_placements()).is_ok());
+    }
+
+    #[test]
+    fn random_placements_make_a_valid_fleet() {
+        for _ in 0..100 {
+            assert!(Fleet::new(&random_placements(&[])).is_ok());
+        }
+
+        let placed = &stacked_placements()[..2];
+        assert_eq!(random_placements(placed)[..2], *placed);
+    }
+
+    #[test]
+    fn shots_report_misses_hits_and_sinkings() {
+        let mut fleet = Fleet::new(&stacked_placements()).unwrap();
+
+        assert_eq!(
+            fleet.receive_shot("a10".parse().unwrap()).unwrap(),
+            ShotResult::Miss
+        );
+        assert_eq!(
+            fleet.receive_shot("a5".parse().unwrap()).unwrap(),
+            ShotResult::Hit
+        );
+        assert_eq!(
+            fleet.receive_shot("b5".parse().unwrap()).unwrap(),
+            ShotResult::Sunk {
+                ship: ShipKind::Destroyer
+            }
+        );
+        assert!(matches!(
+            fleet.receive_shot("b5".parse().unwrap()),
+            Err(Error::AlreadyFiredAt { .. })
+        ));
+        assert!(matches!(
+            fleet.receive_shot(Coordinate::new(10, 0)),
+            Err(Error::OffGrid)
+        ));
+    }
+
+    #[test]
+    fn target_view_hides_ships_not_yet_hit() {
+        let mut fleet = Fleet::new(&stacked_placements()).unwrap();
+        fleet.receive_shot("a1".parse().unwrap()).unwrap();
+        fleet.receive_shot("a10".parse().unwrap()).unwrap();
+
+        let owner_view = fleet.get_owner_view();
+        let target_view = fleet.get_target_view();
+        assert_eq!((owner_view[0], target_view[0]), (Cell::Hit, Cell::Hit));
+        assert_eq!((owner_view[1], target_view[1]), (Cell::Ship, Cell::Water));
+        assert_eq!((owner_view[90], target_view[90]), (Cell::Miss, Cell::Miss));
+    }
+
+    #[test]
+    fn fleet_is_destroyed_once_every_ship_sinks() {
+        let mut fleet = Fleet::new(&stacked_placements()).unwrap();
+        let ship_squares: Vec<usize> = fleet
+            .get_owner_view()
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == Cell::Ship)
+            .map(|(index, _)| index)
+            .collect();
+
+        for (count, index) in ship_squares.iter().enumerate() {
+            assert!(!fleet.is_destroyed(), "destroyed after {} hits", count);
+            fleet.receive_shot(Coordinate::from_index(*index)).unwrap();
+        }
+        assert!(fleet.is_destroyed());
+    }
+}
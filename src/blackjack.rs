@@ -0,0 +1,1379 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::blackjack::rules::{Outcome, BEST_TOTAL};
+use crate::blackjack::ClientEvent::{Hit, Stand};
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::cards::{Card, Deck, Hand};
+use crate::game::rand::GameRng;
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod rules;
+
+/// Identifies Blackjack among the games in `game::GAMES`.
+pub const GAME_ID: &str = "blackjack";
+
+/// The chips each player starts the game with.
+pub const STARTING_CHIPS: u32 = 100;
+/// The chips each player bets on every round.
+pub const BET: u32 = 10;
+/// The number of rounds played, fewer if a player runs out of chips to bet.
+pub const ROUNDS: u8 = 5;
+
+/// The players, in the order they play their hands each round.
+const PLAYER_IDS: [u8; 2] = [PLAYER_ONE_ID, PLAYER_TWO_ID];
+
+/// Blackjack, where both players play their own hand against a dealer played by the server, each
+/// drawing cards to get closer to 21 than the dealer without going over. The dealer's second card
+/// stays face down until every player has finished their hand. Each round is played for a fixed
+/// bet, and the player with the most chips after the last round wins.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Blackjack;
+
+impl GameDescriptor for Blackjack {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(BlackjackServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(BlackjackClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(BlackjackClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(BlackjackClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(BlackjackClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// Sent before the cards of each round are shown, with the chips each player has to bet.
+    RoundStarted {
+        round: u8,
+        rounds: u8,
+        bet: u32,
+        chips: BTreeMap<u8, u32>,
+    },
+    /// The hand of each player along with the dealer's face up card, never the one face down.
+    TableShown {
+        hands: BTreeMap<u8, Vec<Card>>,
+        dealer_up_card: Card,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    CardDealt {
+        player_id: u8,
+        card: Card,
+    },
+    Stood {
+        player_id: u8,
+    },
+    /// The dealer's whole hand, revealed once every player has finished theirs, including any
+    /// cards the dealer drew.
+    DealerPlayed {
+        cards: Vec<Card>,
+    },
+    RoundOver {
+        outcomes: BTreeMap<u8, Outcome>,
+        chips: BTreeMap<u8, u32>,
+    },
+    GameOver {
+        /// The ID of the player with the most chips, `None` if the players are tied.
+        winner: Option<u8>,
+        chips: BTreeMap<u8, u32>,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Your hand is already worth 21 or more, so you must stand.")]
+    HandComplete,
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct BlackjackServer {
+    rng: GameRng,
+    /// Shuffled afresh for every round.
+    deck: Deck,
+    hands: BTreeMap<u8, Hand>,
+    /// The dealer's hand, of which only the first card is shown until the players have finished.
+    dealer: Hand,
+    chips: BTreeMap<u8, u32>,
+    round: u8,
+    /// The player playing their hand, `None` between rounds.
+    current_player: Option<u8>,
+    server_channel: ServerChannel<ServerEvent>,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl BlackjackServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> BlackjackServer {
+        let mut server = BlackjackServer {
+            rng: GameRng::new(),
+            deck: Deck::standard(),
+            hands: BTreeMap::new(),
+            dealer: Hand::new(),
+            chips: PLAYER_IDS
+                .iter()
+                .map(|player_id| (*player_id, STARTING_CHIPS))
+                .collect(),
+            round: 1,
+            current_player: None,
+            server_channel: ServerChannel::new(server_channel),
+            game_over: false,
+        };
+        // The first round is dealt straight away, as the game is not changed as it begins
+        server.deal_round();
+
+        server
+    }
+
+    /// Deals two cards to each player and the dealer from a freshly shuffled deck, ready for the
+    /// first player to play their hand.
+    fn deal_round(&mut self) {
+        self.deck = Deck::shuffled(&mut self.rng);
+        self.hands = PLAYER_IDS
+            .iter()
+            .map(|player_id| (*player_id, self.deal_hand()))
+            .collect();
+        self.dealer = self.deal_hand();
+        self.current_player = Some(PLAYER_ONE_ID);
+    }
+
+    fn deal_hand(&mut self) -> Hand {
+        self.deck
+            .deal_hand(2)
+            .expect("A fresh deck has cards for every hand")
+    }
+
+    fn deal_card(&mut self) -> Card {
+        // Every hand stops drawing by 21, so a round never gets through the whole deck
+        self.deck.deal().expect("A round never uses the whole deck")
+    }
+
+    fn hand_total(&self, player_id: u8) -> u8 {
+        self.hands
+            .get(&player_id)
+            .map_or(0, |hand| rules::hand_total(hand.cards()))
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_error_event(&self, player_id: u8, error: Error) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::ErrorOccurred { error },
+        )
+        .await
+    }
+
+    async fn dispatch_table_shown_event(&self, dispatch_mode: DispatchMode) {
+        let hands = self
+            .hands
+            .iter()
+            .map(|(player_id, hand)| (*player_id, hand.cards().to_vec()))
+            .collect();
+        let dealer_up_card = self.dealer.cards()[0];
+        self.dispatch_event(
+            dispatch_mode,
+            ServerEvent::TableShown {
+                hands,
+                dealer_up_card,
+            },
+        )
+        .await
+    }
+
+    /// Tells the given clients whose turn it is, if a hand is being played.
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        if let Some(player_id) = self.current_player {
+            self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+                .await
+        }
+    }
+
+    async fn start_round(&self) {
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::RoundStarted {
+                round: self.round,
+                rounds: ROUNDS,
+                bet: BET,
+                chips: self.chips.clone(),
+            },
+        )
+        .await;
+        self.dispatch_table_shown_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn start_turn(&self) {
+        let Some(player_id) = self.current_player else {
+            return;
+        };
+
+        self.server_channel
+            .send(GameServerEvent::TurnStarted { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    /// Moves on from the hand just finished to the next player's, or to the dealer's once every
+    /// player has finished.
+    async fn advance_turn(&mut self, player_id: u8) {
+        match PLAYER_IDS.iter().skip_while(|id| **id != player_id).nth(1) {
+            Some(next_player_id) => {
+                self.current_player = Some(*next_player_id);
+                self.start_turn().await;
+            }
+            None => self.finish_round().await,
+        }
+    }
+
+    /// Plays the dealer's hand, which draws until it is worth `rules::DEALER_STANDS_ON` unless
+    /// every player has gone bust, then settles each player's bet against it.
+    async fn finish_round(&mut self) {
+        self.current_player = None;
+        let all_bust = self.hands.values().all(|hand| rules::is_bust(hand.cards()));
+        while !all_bust && rules::dealer_hits(self.dealer.cards()) {
+            let card = self.deal_card();
+            self.dealer.add(card);
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::DealerPlayed {
+                cards: self.dealer.cards().to_vec(),
+            },
+        )
+        .await;
+
+        let outcomes: BTreeMap<u8, Outcome> = self
+            .hands
+            .iter()
+            .map(|(player_id, hand)| {
+                (
+                    *player_id,
+                    Outcome::settle(hand.cards(), self.dealer.cards()),
+                )
+            })
+            .collect();
+        for (player_id, outcome) in &outcomes {
+            let chips = self.chips.entry(*player_id).or_default();
+            *chips = chips.saturating_add_signed(outcome.payout(BET));
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::RoundOver {
+                outcomes,
+                chips: self.chips.clone(),
+            },
+        )
+        .await;
+
+        if self.round >= ROUNDS || self.chips.values().any(|chips| *chips < BET) {
+            self.dispatch_game_over_event(self.get_chip_leader()).await;
+            return;
+        }
+
+        self.round += 1;
+        self.deal_round();
+        self.start_round().await;
+    }
+
+    /// The player with the most chips, `None` if the players are tied.
+    fn get_chip_leader(&self) -> Option<u8> {
+        match self.chips[&PLAYER_ONE_ID].cmp(&self.chips[&PLAYER_TWO_ID]) {
+            std::cmp::Ordering::Greater => Some(PLAYER_ONE_ID),
+            std::cmp::Ordering::Less => Some(PLAYER_TWO_ID),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    async fn dispatch_game_over_event(&mut self, winner: Option<u8>) {
+        self.game_over = true;
+        self.current_player = None;
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::GameOver {
+                winner,
+                chips: self.chips.clone(),
+            },
+        )
+        .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    async fn handle_hit_event(&mut self, player_id: u8) {
+        if self.hand_total(player_id) >= BEST_TOTAL {
+            self.dispatch_error_event(player_id, Error::HandComplete)
+                .await;
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+            return;
+        }
+
+        let card = self.deal_card();
+        if let Some(hand) = self.hands.get_mut(&player_id) {
+            hand.add(card);
+        }
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::CardDealt { player_id, card },
+        )
+        .await;
+
+        // A player who has gone bust has nothing left to play for
+        match self.hand_total(player_id) > BEST_TOTAL {
+            true => self.advance_turn(player_id).await,
+            false => self.start_turn().await,
+        }
+    }
+
+    async fn handle_stand_event(&mut self, player_id: u8) {
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::Stood { player_id })
+            .await;
+        self.advance_turn(player_id).await;
+    }
+}
+
+#[async_trait]
+impl GameServer for BlackjackServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    fn set_rng(&mut self, rng: GameRng) {
+        self.rng = rng;
+        self.deal_round();
+    }
+
+    async fn begin(&self) {
+        self.start_round().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let player_id = match event {
+            Hit { player_id } | Stand { player_id } => player_id,
+        };
+        if self.current_player != Some(player_id) {
+            self.dispatch_error_event(player_id, Error::UnexpectedPlayer)
+                .await;
+            return;
+        }
+
+        match event {
+            Hit { player_id } => self.handle_hit_event(player_id).await,
+            Stand { player_id } => self.handle_stand_event(player_id).await,
+        }
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || self.current_player != Some(player_id) {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Some(get_alternative_player_id(player_id)))
+                    .await
+            }
+            // A player who runs out of time stands on the hand they have
+            TimeoutAction::SkipTurn => self.advance_turn(player_id).await,
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_table_shown_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_table_shown_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// Asks the dealer for another card.
+    Hit { player_id: u8 },
+    /// Finishes the player's hand, keeping the cards they have.
+    Stand { player_id: u8 },
+}
+
+pub struct BlackjackClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    /// The cards of each player's hand in the round being played.
+    hands: BTreeMap<u8, Vec<Card>>,
+}
+
+impl<F, C> BlackjackClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> BlackjackClient<F, C> {
+        BlackjackClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            hands: BTreeMap::new(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn hand(&self, player_id: u8) -> &[Card] {
+        self.hands.get(&player_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// The cards separated by spaces, followed by their total.
+    fn format_hand(cards: &[Card]) -> String {
+        let cards_text = cards
+            .iter()
+            .map(Card::to_string)
+            .collect::<Vec<String>>()
+            .join(" ");
+        format!("{} ({})", cards_text, rules::hand_total(cards))
+    }
+
+    fn format_chips(&self, chips: &BTreeMap<u8, u32>) -> String {
+        let separator = match self.user_output.output_style() {
+            OutputStyle::Standard => ", ",
+            OutputStyle::Terse => " ",
+        };
+        PLAYER_IDS
+            .iter()
+            .map(|player_id| {
+                format!(
+                    "{} {}",
+                    self.get_player_icon_by_id(*player_id),
+                    chips.get(player_id).copied().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(separator)
+    }
+
+    fn handle_round_started_event(
+        &self,
+        round: u8,
+        rounds: u8,
+        bet: u32,
+        chips: &BTreeMap<u8, u32>,
+    ) -> Result<(), io::Error> {
+        self.user_output.write_message(
+            "blackjack-round-started",
+            &[
+                ("round", &round),
+                ("rounds", &rounds),
+                ("bet", &bet),
+                ("chips", &self.format_chips(chips)),
+            ],
+        )
+    }
+
+    /// Shows the dealer's face up card above each player's hand.
+    fn handle_table_shown_event(
+        &mut self,
+        hands: BTreeMap<u8, Vec<Card>>,
+        dealer_up_card: Card,
+    ) -> Result<(), io::Error> {
+        self.hands = hands;
+
+        let mut lines = vec![self
+            .user_output
+            .message("blackjack-dealer-up-card", &[("card", &dealer_up_card)])];
+        for (player_id, cards) in &self.hands {
+            lines.push(self.user_output.message(
+                "blackjack-player-hand",
+                &[
+                    ("player", &self.get_player_icon_by_id(*player_id)),
+                    ("hand", &Self::format_hand(cards)),
+                ],
+            ));
+        }
+
+        self.user_output.write_line(lines.join("\n"))
+    }
+
+    fn handle_card_dealt_event(&mut self, player_id: u8, card: Card) -> Result<(), io::Error> {
+        self.hands.entry(player_id).or_default().push(card);
+        let total = rules::hand_total(self.hand(player_id));
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match total > BEST_TOTAL {
+            true => "blackjack-card-dealt-bust",
+            false => "blackjack-card-dealt",
+        };
+
+        self.user_output.write_message(
+            key,
+            &[("player", &player_icon), ("card", &card), ("total", &total)],
+        )
+    }
+
+    fn handle_stood_event(&self, player_id: u8) -> Result<(), io::Error> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let total = rules::hand_total(self.hand(player_id));
+        self.user_output.write_message(
+            "blackjack-stood",
+            &[("player", &player_icon), ("total", &total)],
+        )
+    }
+
+    fn handle_dealer_played_event(&self, cards: &[Card]) -> Result<(), io::Error> {
+        let key = match rules::is_bust(cards) {
+            true => "blackjack-dealer-played-bust",
+            false => "blackjack-dealer-played",
+        };
+        self.user_output
+            .write_message(key, &[("hand", &Self::format_hand(cards))])
+    }
+
+    fn handle_round_over_event(
+        &self,
+        outcomes: &BTreeMap<u8, Outcome>,
+        chips: &BTreeMap<u8, u32>,
+    ) -> Result<(), io::Error> {
+        let mut results = Vec::new();
+        for (player_id, outcome) in outcomes {
+            let key = match outcome {
+                Outcome::Blackjack => "blackjack-outcome-blackjack",
+                Outcome::Won => "blackjack-outcome-won",
+                Outcome::Push => "blackjack-outcome-push",
+                Outcome::Lost => "blackjack-outcome-lost",
+                Outcome::Busted => "blackjack-outcome-busted",
+            };
+            results.push(self.user_output.message(
+                key,
+                &[
+                    ("player", &self.get_player_icon_by_id(*player_id)),
+                    ("payout", &format!("{:+}", outcome.payout(BET))),
+                ],
+            ));
+        }
+        results.push(
+            self.user_output
+                .message("blackjack-chips", &[("chips", &self.format_chips(chips))]),
+        );
+
+        self.user_output.write_line(results.join(" "))
+    }
+
+    async fn handle_game_over_event(
+        &mut self,
+        winner: Option<u8>,
+        chips: BTreeMap<u8, u32>,
+    ) -> Result<(), GameClientError> {
+        let chip_count = |player_id| chips.get(&player_id).copied().unwrap_or_default();
+        match winner {
+            Some(winner) => self.user_output.write_message(
+                "blackjack-game-won",
+                &[
+                    ("player", &self.get_player_icon_by_id(winner)),
+                    ("chips", &chip_count(winner)),
+                    ("other", &chip_count(get_alternative_player_id(winner))),
+                ],
+            )?,
+            None => self.user_output.write_message(
+                "blackjack-game-draw",
+                &[("chips", &chip_count(PLAYER_ONE_ID))],
+            )?,
+        };
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        let key = match error {
+            Error::HandComplete => "blackjack-error-hand-complete",
+            Error::UnexpectedPlayer => "blackjack-error-unexpected-player",
+        };
+        self.user_output.write_error(key, &[])?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "blackjack-turn-timed-out-stand",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    /// Asks the user whether to hit or stand, standing without asking on a hand worth 21 as
+    /// there is nothing to gain from another card.
+    async fn play_hand(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let total = rules::hand_total(self.hand(player_id));
+        if total >= BEST_TOTAL {
+            self.user_output
+                .write_message("blackjack-standing-on", &[("total", &total)])?;
+            return self.send_event(Stand { player_id }).await;
+        }
+
+        let event = match self.get_move().await? {
+            true => Hit { player_id },
+            false => Stand { player_id },
+        };
+        self.send_event(event).await
+    }
+
+    async fn send_event(&mut self, event: ClientEvent) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer { event })
+            .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads whether the user hits, `true`, or stands, `false`.
+    async fn get_move(&mut self) -> Result<bool, io::Error> {
+        loop {
+            self.user_output
+                .write_message("blackjack-move-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.trim().to_lowercase().as_str() {
+                "h" | "hit" => return Ok(true),
+                "s" | "stand" => return Ok(false),
+                _ => self
+                    .user_output
+                    .write_message("blackjack-not-a-move", &[])?,
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BlackjackClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output
+            .message("blackjack-game-started-local", &[("best", &BEST_TOTAL)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.play_hand(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BlackjackClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "blackjack-game-started-online",
+            &[
+                ("player", &self.get_player_icon_by_id(self.client_type.id)),
+                ("best", &BEST_TOTAL),
+            ],
+        )
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("blackjack-waiting-for-hand", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.play_hand(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BlackjackClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for BlackjackClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    /// Bots play their hand by the same rule as the dealer.
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        let event = match rules::dealer_hits(self.hand(player_id)) {
+            true => Hit { player_id },
+            false => Stand { player_id },
+        };
+        self.send_event(event).await
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for BlackjackClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::RoundStarted {
+                round,
+                rounds,
+                bet,
+                chips,
+            } => Ok(self.handle_round_started_event(round, rounds, bet, &chips)?),
+            ServerEvent::TableShown {
+                hands,
+                dealer_up_card,
+            } => Ok(self.handle_table_shown_event(hands, dealer_up_card)?),
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::CardDealt { player_id, card } => {
+                Ok(self.handle_card_dealt_event(player_id, card)?)
+            }
+            ServerEvent::Stood { player_id } => Ok(self.handle_stood_event(player_id)?),
+            ServerEvent::DealerPlayed { cards } => Ok(self.handle_dealer_played_event(&cards)?),
+            ServerEvent::RoundOver { outcomes, chips } => {
+                Ok(self.handle_round_over_event(&outcomes, &chips)?)
+            }
+            ServerEvent::GameOver { winner, chips } => {
+                self.handle_game_over_event(winner, chips).await
+            }
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+    use crate::game::cards::{Rank, Suit};
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        BlackjackClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = BlackjackClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let mut hand = Hand::new();
+        for rank in ranks {
+            hand.add(Card::new(*rank, Suit::Hearts));
+        }
+
+        hand
+    }
+
+    /// A server dealt the given hands, drawing any further cards from an unshuffled deck, which
+    /// deals the ace of spades first and then the king.
+    fn get_test_server(
+        player_one: &[Rank],
+        player_two: &[Rank],
+        dealer: &[Rank],
+    ) -> (BlackjackServer, Receiver<GameServerEvent>) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = BlackjackServer::new(sender);
+        server.deck = Deck::standard();
+        server.hands = BTreeMap::from([
+            (PLAYER_ONE_ID, hand(player_one)),
+            (PLAYER_TWO_ID, hand(player_two)),
+        ]);
+        server.dealer = hand(dealer);
+
+        (server, receiver)
+    }
+
+    async fn send(server: &mut BlackjackServer, event: ClientEvent) {
+        server.handle_event(event).await;
+    }
+
+    fn drain_server_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<GameServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+
+        events
+    }
+
+    fn get_dispatched_events(events: &[GameServerEvent]) -> Vec<ServerEvent> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameServerEvent::DispatchToClient { event, .. } => {
+                    Some(game::deserialize_event(event.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn server_given_same_seed_deals_same_cards() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let mut one = BlackjackServer::new(sender.clone());
+        let mut two = BlackjackServer::new(sender);
+
+        one.set_rng(GameRng::from_seed(3));
+        two.set_rng(GameRng::from_seed(3));
+        assert_eq!(one.hands, two.hands);
+        assert_eq!(one.dealer, two.dealer);
+        assert_eq!(one.hands[&PLAYER_ONE_ID].len(), 2);
+    }
+
+    #[tokio::test]
+    async fn server_shows_only_dealer_up_card_and_starts_first_turn() {
+        let (server, mut receiver) = get_test_server(
+            &[Rank::Ten, Rank::Six],
+            &[Rank::Two, Rank::Three],
+            &[Rank::Seven, Rank::King],
+        );
+
+        server.begin().await;
+
+        let events = drain_server_events(&mut receiver);
+        assert!(matches!(
+            get_dispatched_events(&events)[..],
+            [
+                ServerEvent::RoundStarted {
+                    round: 1,
+                    rounds: ROUNDS,
+                    bet: BET,
+                    ..
+                },
+                ServerEvent::TableShown {
+                    dealer_up_card: Card {
+                        rank: Rank::Seven,
+                        ..
+                    },
+                    ..
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            GameServerEvent::TurnStarted {
+                player_id: PLAYER_ONE_ID
+            }
+        )));
+    }
+
+    #[tokio::test]
+    async fn server_moves_to_next_player_once_hand_busts() {
+        let (mut server, mut receiver) = get_test_server(
+            &[Rank::Ten, Rank::Six],
+            &[Rank::Two, Rank::Three],
+            &[Rank::Seven, Rank::King],
+        );
+        // Leave the king of spades on top, so that the hand goes bust
+        server.deck.deal();
+
+        send(
+            &mut server,
+            Hit {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+
+        assert_eq!(server.current_player, Some(PLAYER_TWO_ID));
+        assert!(matches!(
+            get_dispatched_events(&drain_server_events(&mut receiver))[..],
+            [
+                ServerEvent::CardDealt {
+                    player_id: PLAYER_ONE_ID,
+                    card: Card {
+                        rank: Rank::King,
+                        suit: Suit::Spades
+                    }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_moves_out_of_turn_and_hits_on_twenty_one() {
+        let (mut server, mut receiver) = get_test_server(
+            &[Rank::Ace, Rank::King],
+            &[Rank::Two, Rank::Three],
+            &[Rank::Seven, Rank::King],
+        );
+
+        send(
+            &mut server,
+            Stand {
+                player_id: PLAYER_TWO_ID,
+            },
+        )
+        .await;
+        send(
+            &mut server,
+            Hit {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+
+        assert_eq!(server.current_player, Some(PLAYER_ONE_ID));
+        assert!(matches!(
+            get_dispatched_events(&drain_server_events(&mut receiver))[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::UnexpectedPlayer
+                },
+                ServerEvent::ErrorOccurred {
+                    error: Error::HandComplete
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_plays_dealer_and_settles_bets_once_both_players_stand() {
+        let (mut server, mut receiver) = get_test_server(
+            &[Rank::Ace, Rank::King],
+            &[Rank::Ten, Rank::Eight],
+            &[Rank::Six, Rank::Four],
+        );
+
+        for player_id in PLAYER_IDS {
+            send(&mut server, Stand { player_id }).await;
+        }
+
+        let events = get_dispatched_events(&drain_server_events(&mut receiver));
+        let ServerEvent::DealerPlayed { cards } = &events[3] else {
+            panic!("Expected the dealer to play");
+        };
+        // The dealer draws the ace of spades to stand on 21
+        assert_eq!(rules::hand_total(cards), 21);
+        let ServerEvent::RoundOver { outcomes, chips } = &events[4] else {
+            panic!("Expected the round to be over");
+        };
+        assert_eq!(
+            outcomes,
+            &BTreeMap::from([
+                (PLAYER_ONE_ID, Outcome::Blackjack),
+                (PLAYER_TWO_ID, Outcome::Lost)
+            ])
+        );
+        assert_eq!(
+            chips,
+            &BTreeMap::from([(PLAYER_ONE_ID, 115), (PLAYER_TWO_ID, 90)])
+        );
+        assert!(matches!(
+            events[5],
+            ServerEvent::RoundStarted { round: 2, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_after_last_round_with_chip_leader_as_winner() {
+        let (mut server, mut receiver) = get_test_server(
+            &[Rank::Ten, Rank::Nine],
+            &[Rank::Ten, Rank::Seven],
+            &[Rank::Ten, Rank::Eight],
+        );
+        server.round = ROUNDS;
+
+        for player_id in PLAYER_IDS {
+            send(&mut server, Stand { player_id }).await;
+        }
+
+        assert!(server.game_over);
+        assert_eq!(server.chips[&PLAYER_ONE_ID], STARTING_CHIPS + BET);
+        assert!(matches!(
+            drain_server_events(&mut receiver).last(),
+            Some(GameServerEvent::GameOver {
+                winner: Some(PLAYER_ONE_ID)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_stands_player_who_runs_out_of_time() {
+        let (mut server, mut receiver) = get_test_server(
+            &[Rank::Ten, Rank::Six],
+            &[Rank::Two, Rank::Three],
+            &[Rank::Seven, Rank::King],
+        );
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+
+        assert_eq!(server.current_player, Some(PLAYER_TWO_ID));
+        assert!(matches!(
+            get_dispatched_events(&drain_server_events(&mut receiver))[..],
+            [
+                ServerEvent::TurnTimedOut {
+                    player_id: PLAYER_ONE_ID,
+                    action: TimeoutAction::SkipTurn
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_shows_table_and_cards_dealt() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+        let hands = BTreeMap::from([
+            (
+                PLAYER_ONE_ID,
+                hand(&[Rank::Ten, Rank::Six]).cards().to_vec(),
+            ),
+            (
+                PLAYER_TWO_ID,
+                hand(&[Rank::Ace, Rank::Two]).cards().to_vec(),
+            ),
+        ]);
+
+        client
+            .handle_table_shown_event(hands, Card::new(Rank::Seven, Suit::Spades))
+            .unwrap();
+        client
+            .handle_card_dealt_event(PLAYER_ONE_ID, Card::new(Rank::King, Suit::Clubs))
+            .unwrap();
+        client.set_output_style(OutputStyle::Terse);
+        client
+            .handle_card_dealt_event(PLAYER_TWO_ID, Card::new(Rank::King, Suit::Clubs))
+            .unwrap();
+        client
+            .handle_dealer_played_event(&[
+                Card::new(Rank::Seven, Suit::Spades),
+                Card::new(Rank::King, Suit::Diamonds),
+            ])
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Dealer: 7♠ ??\nPlayer X: 10♥ 6♥ (16)\nPlayer O: A♥ 2♥ (13)\n\
+            Player X drew K♣, making 26, bust!\n\
+            O drew K♣ (13).\n\
+            D: 7♠ K♦ (17).\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_shows_round_outcomes_and_chips() {
+        let (client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        client
+            .handle_round_over_event(
+                &BTreeMap::from([
+                    (PLAYER_ONE_ID, Outcome::Blackjack),
+                    (PLAYER_TWO_ID, Outcome::Busted),
+                ]),
+                &BTreeMap::from([(PLAYER_ONE_ID, 115), (PLAYER_TWO_ID, 90)]),
+            )
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Player X wins with blackjack, +15 chips. Player O went bust, -10 chips. \
+            Chips: X 115, O 90.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_round_over_event(
+                &BTreeMap::from([(PLAYER_ONE_ID, Outcome::Won)]),
+                &BTreeMap::from([(PLAYER_ONE_ID, 110), (PLAYER_TWO_ID, 100)]),
+            )
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::HandComplete)
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador X gana, +10 fichas. Fichas: X 110, O 100.\n\
+            Error: Tu mano ya vale 21 o más, así que debes plantarte.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_move_handles_errors_until_move_provided() {
+        let input = "fold\nSTAND\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert!(!client.get_move().await.unwrap());
+        assert_client_output(
+            output,
+            "Hit or stand (h/s):\nInvalid move.\nHit or stand (h/s):\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_stands_without_asking_on_twenty_one() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(&[], OnlineClient { id: PLAYER_TWO_ID }).await;
+        client.hands = BTreeMap::from([(
+            PLAYER_TWO_ID,
+            hand(&[Rank::Ace, Rank::Queen]).cards().to_vec(),
+        )]);
+
+        client
+            .handle_player_turn_event(PLAYER_TWO_ID)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(Stand {
+                    player_id: PLAYER_TWO_ID
+                })
+            })
+        );
+        assert_client_output(output, "It's your turn!\nStanding on 21.\n");
+    }
+
+    #[tokio::test]
+    async fn bot_hits_below_seventeen() {
+        let (mut client, _, mut receiver) = get_test_client_and_output(
+            &[],
+            BotClient {
+                id: PLAYER_ONE_ID,
+                move_delay: std::time::Duration::ZERO,
+            },
+        )
+        .await;
+        client.hands = BTreeMap::from([(
+            PLAYER_ONE_ID,
+            hand(&[Rank::Ten, Rank::Six]).cards().to_vec(),
+        )]);
+
+        client
+            .handle_player_turn_event(PLAYER_ONE_ID)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(Hit {
+                    player_id: PLAYER_ONE_ID
+                })
+            })
+        );
+    }
+}
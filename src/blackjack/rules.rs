@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::cards::{Card, Rank};
+
+/// The best total a hand can have, beyond which it is bust.
+pub const BEST_TOTAL: u8 = 21;
+/// The total at which the dealer stops drawing cards.
+pub const DEALER_STANDS_ON: u8 = 17;
+
+/// The value of the hand, counting each ace as 11 unless that would take the hand over
+/// `BEST_TOTAL`, in which case it counts as 1.
+pub fn hand_total(cards: &[Card]) -> u8 {
+    let mut total: u8 = cards.iter().map(|card| card_value(card.rank)).sum();
+    let mut soft_aces = cards.iter().filter(|card| card.rank == Rank::Ace).count();
+    while total > BEST_TOTAL && soft_aces > 0 {
+        total -= 10;
+        soft_aces -= 1;
+    }
+
+    total
+}
+
+fn card_value(rank: Rank) -> u8 {
+    match rank {
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        Rank::Ace => 11,
+    }
+}
+
+pub fn is_bust(cards: &[Card]) -> bool {
+    hand_total(cards) > BEST_TOTAL
+}
+
+/// Whether the hand is worth `BEST_TOTAL` from the two cards first dealt to it.
+pub fn is_blackjack(cards: &[Card]) -> bool {
+    cards.len() == 2 && hand_total(cards) == BEST_TOTAL
+}
+
+/// Whether the dealer draws another card, which they do until the hand is worth
+/// `DEALER_STANDS_ON` or more.
+pub fn dealer_hits(cards: &[Card]) -> bool {
+    hand_total(cards) < DEALER_STANDS_ON
+}
+
+/// How a player's hand fared against the dealer's at the end of a round.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    /// Won with a blackjack that the dealer did not match, paying out half as much again.
+    Blackjack,
+    Won,
+    /// Tied with the dealer, keeping the bet.
+    Push,
+    Lost,
+    /// Went over `BEST_TOTAL`, losing whatever the dealer went on to do.
+    Busted,
+}
+
+impl Outcome {
+    /// Settles the player's hand against the dealer's, once the dealer has finished drawing.
+    pub fn settle(player: &[Card], dealer: &[Card]) -> Outcome {
+        if is_bust(player) {
+            return Outcome::Busted;
+        }
+        match (is_blackjack(player), is_blackjack(dealer)) {
+            (true, false) => return Outcome::Blackjack,
+            (false, true) => return Outcome::Lost,
+            (true, true) => return Outcome::Push,
+            (false, false) => {}
+        }
+        if is_bust(dealer) {
+            return Outcome::Won;
+        }
+
+        match hand_total(player).cmp(&hand_total(dealer)) {
+            std::cmp::Ordering::Greater => Outcome::Won,
+            std::cmp::Ordering::Equal => Outcome::Push,
+            std::cmp::Ordering::Less => Outcome::Lost,
+        }
+    }
+
+    /// The chips won, or lost if negative, on the given bet.
+    pub fn payout(self, bet: u32) -> i32 {
+        let bet = bet as i32;
+        match self {
+            Outcome::Blackjack => bet * 3 / 2,
+            Outcome::Won => bet,
+            Outcome::Push => 0,
+            Outcome::Lost | Outcome::Busted => -bet,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::cards::Suit;
+
+    fn cards(ranks: &[Rank]) -> Vec<Card> {
+        ranks
+            .iter()
+            .map(|rank| Card::new(*rank, Suit::Spades))
+            .collect()
+    }
+
+    #[test]
+    fn aces_count_as_one_only_when_eleven_would_bust() {
+        assert_eq!(hand_total(&cards(&[Rank::Ace, Rank::Six])), 17);
+        assert_eq!(hand_total(&cards(&[Rank::Ace, Rank::Six, Rank::Nine])), 16);
+        assert_eq!(hand_total(&cards(&[Rank::Ace, Rank::Ace, Rank::King])), 12);
+        assert_eq!(
+            hand_total(&cards(&[Rank::King, Rank::Queen, Rank::Two])),
+            22
+        );
+    }
+
+    #[test]
+    fn dealer_hits_below_seventeen_and_stands_on_soft_seventeen() {
+        assert!(dealer_hits(&cards(&[Rank::Ten, Rank::Six])));
+        assert!(!dealer_hits(&cards(&[Rank::Ten, Rank::Seven])));
+        assert!(!dealer_hits(&cards(&[Rank::Ace, Rank::Six])));
+    }
+
+    #[test]
+    fn settle_compares_hands_with_blackjack_and_busts_first() {
+        let twenty = cards(&[Rank::King, Rank::Queen]);
+        let blackjack = cards(&[Rank::Ace, Rank::King]);
+        let bust = cards(&[Rank::King, Rank::Queen, Rank::Five]);
+        let twenty_one = cards(&[Rank::Seven, Rank::Seven, Rank::Seven]);
+
+        assert_eq!(Outcome::settle(&blackjack, &twenty_one), Outcome::Blackjack);
+        assert_eq!(Outcome::settle(&twenty_one, &blackjack), Outcome::Lost);
+        assert_eq!(Outcome::settle(&blackjack, &blackjack), Outcome::Push);
+        assert_eq!(Outcome::settle(&bust, &bust), Outcome::Busted);
+        assert_eq!(Outcome::settle(&twenty, &bust), Outcome::Won);
+        assert_eq!(Outcome::settle(&twenty, &twenty), Outcome::Push);
+        assert_eq!(Outcome::settle(&twenty, &twenty_one), Outcome::Lost);
+    }
+
+    #[test]
+    fn blackjack_pays_half_as_much_again() {
+        assert_eq!(Outcome::Blackjack.payout(10), 15);
+        assert_eq!(Outcome::Won.payout(10), 10);
+        assert_eq!(Outcome::Push.payout(10), 0);
+        assert_eq!(Outcome::Busted.payout(10), -10);
+    }
+}
@@ -0,0 +1,1029 @@
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::checkers::board::Board;
+pub use crate::checkers::board::{Piece, Square, BOARD_SIZE};
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod board;
+mod bot;
+
+/// Identifies Checkers among the games in `game::GAMES`.
+pub const GAME_ID: &str = "checkers";
+
+/// Checkers, also known as English draughts, played on an 8x8 board. Captures are compulsory and
+/// a piece that can keep capturing must do so, while men reaching the far side are crowned.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Checkers;
+
+impl GameDescriptor for Checkers {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(CheckersServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(CheckersClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(CheckersClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(CheckersClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(CheckersClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Outcome {
+    Draw,
+    WinnerFound { player_id: u8 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// The piece on each square, going along each row from the top of the board.
+    BoardUpdated {
+        cells: Vec<Option<Piece>>,
+    },
+    /// Sent before the board showing the move, so that it can be pointed out.
+    MoveMade {
+        player_id: u8,
+        path: Vec<Square>,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    GameOver {
+        outcome: Outcome,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("A move needs the square the piece starts on and at least one square it moves to.")]
+    IncompleteMove,
+    #[error("Pieces only stand on the dark squares, which {square} is not.")]
+    UnplayableSquare { square: Square },
+    #[error("You do not have a piece on {square}.")]
+    NotYourPiece { square: Square },
+    #[error("Pieces move one square diagonally, forwards unless crowned, or jump over an opposing piece onto the empty square beyond.")]
+    IllegalMove,
+    #[error("One of your pieces can capture, so you must capture.")]
+    CaptureRequired,
+    #[error("Your piece can capture again, so must keep jumping.")]
+    CaptureIncomplete,
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct CheckersServer {
+    current_player: u8,
+    board: Board,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl CheckersServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> CheckersServer {
+        CheckersServer {
+            current_player: PLAYER_ONE_ID,
+            board: Board::new(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    fn swap_player(&mut self) {
+        self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_board_updated_event(&self, dispatch_mode: DispatchMode) {
+        let cells = self.board.get_cells();
+        self.dispatch_event(dispatch_mode, ServerEvent::BoardUpdated { cells })
+            .await
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, outcome: Outcome) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { outcome })
+            .await;
+
+        let winner = match outcome {
+            Outcome::Draw => None,
+            Outcome::WinnerFound { player_id } => Some(player_id),
+        };
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    fn handle_move_made_event(&mut self, player_id: u8, path: &[Square]) -> Result<(), Error> {
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.board.make_move(player_id, path)
+    }
+}
+
+#[async_trait]
+impl GameServer for CheckersServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    async fn begin(&self) {
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::MoveMade { player_id, path } = event;
+
+        if let Err(error) = self.handle_move_made_event(player_id, &path) {
+            self.dispatch_event(
+                DispatchMode::SinglePlayer {
+                    player_id: self.current_player,
+                },
+                ServerEvent::ErrorOccurred { error },
+            )
+            .await;
+
+            if self
+                .rejected_moves
+                .reject(&self.server_channel, self.current_player, player_id)
+                .await
+            {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await;
+                return;
+            }
+
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                player_id: self.current_player,
+            })
+            .await;
+
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::MoveMade { player_id, path },
+        )
+        .await;
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        match self
+            .board
+            .determine_outcome(get_alternative_player_id(player_id))
+        {
+            None => {
+                self.swap_player();
+                self.start_turn().await;
+            }
+            Some(outcome) => self.dispatch_game_over_event(outcome).await,
+        }
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
+            }
+            TimeoutAction::SkipTurn => {
+                self.swap_player();
+                self.start_turn().await;
+            }
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_board_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_board_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// Moves a piece through each square in turn, starting from the square it is on.
+    MoveMade { player_id: u8, path: Vec<Square> },
+}
+
+pub struct CheckersClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    cells: Vec<Option<Piece>>,
+}
+
+impl<F, C> CheckersClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> CheckersClient<F, C> {
+        CheckersClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            cells: Board::new().get_cells(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    /// Men are shown by the lowercase icon of their player, and kings by the uppercase icon.
+    fn get_piece_icon(&self, piece: Piece) -> char {
+        let icon = self.get_player_icon_by_id(piece.player_id);
+        if piece.king {
+            icon
+        } else {
+            icon.to_ascii_lowercase()
+        }
+    }
+
+    /// How each square is shown on the board, with empty dark squares marked so that the squares
+    /// pieces can move to stand out.
+    fn get_square_icon(&self, square: Square) -> String {
+        let index = (square.row * BOARD_SIZE + square.column) as usize;
+        match self.cells[index] {
+            Some(piece) => {
+                let paint = match piece.player_id {
+                    PLAYER_ONE_ID => Paint::PlayerOne,
+                    _ => Paint::PlayerTwo,
+                };
+                self.user_output.paint(self.get_piece_icon(piece), paint)
+            }
+            None if square.is_playable() => String::from("."),
+            None => String::from(" "),
+        }
+    }
+
+    async fn handle_board_updated_event(
+        &mut self,
+        cells: Vec<Option<Piece>>,
+    ) -> Result<(), GameClientError> {
+        self.cells = cells;
+
+        let board_output = match self.user_output.output_style() {
+            OutputStyle::Standard => {
+                let columns: String = (0..BOARD_SIZE)
+                    .map(|column| format!(" {}", char::from(b'a' + column)))
+                    .collect();
+                let rows: Vec<String> = (0..BOARD_SIZE)
+                    .map(|row| {
+                        let rank = BOARD_SIZE - row;
+                        let squares: String = (0..BOARD_SIZE)
+                            .map(|column| {
+                                format!(" {}", self.get_square_icon(Square::new(row, column)))
+                            })
+                            .collect();
+                        format!("{}{} {}", rank, squares, rank)
+                    })
+                    .collect();
+                format!(" {}\n{}\n {}\n", columns, rows.join("\n"), columns)
+            }
+            // Every square is shown, so that a square's position along the row gives its column
+            OutputStyle::Terse => self
+                .cells
+                .chunks(BOARD_SIZE as usize)
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| match cell {
+                            Some(piece) => self.get_piece_icon(*piece),
+                            None => '.',
+                        })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+        };
+
+        self.user_output.write_line(board_output)?;
+
+        Ok(())
+    }
+
+    /// Points out the moves of the other player, as a player already knows where they moved.
+    async fn handle_move_made_event(
+        &self,
+        player_id: u8,
+        path: Vec<Square>,
+    ) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            return Ok(());
+        }
+
+        let notation = describe_move(&path);
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output.write_message(
+            "checkers-move-made",
+            &[("player", &player_icon), ("move", &notation)],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, outcome: Outcome) -> Result<(), GameClientError> {
+        match outcome {
+            Outcome::Draw => self.user_output.write_message("game-draw", &[])?,
+            Outcome::WinnerFound { player_id } => {
+                let player_icon = self.get_player_icon_by_id(player_id);
+                self.user_output
+                    .write_message("game-won", &[("player", &player_icon)])?
+            }
+        }
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::IncompleteMove => self
+                .user_output
+                .write_error("checkers-error-incomplete-move", &[]),
+            Error::UnplayableSquare { square } => self
+                .user_output
+                .write_error("checkers-error-unplayable-square", &[("square", &square)]),
+            Error::NotYourPiece { square } => self
+                .user_output
+                .write_error("checkers-error-not-your-piece", &[("square", &square)]),
+            Error::IllegalMove => self
+                .user_output
+                .write_error("checkers-error-illegal-move", &[]),
+            Error::CaptureRequired => self
+                .user_output
+                .write_error("checkers-error-capture-required", &[]),
+            Error::CaptureIncomplete => self
+                .user_output
+                .write_error("checkers-error-capture-incomplete", &[]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("checkers-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let path = self.get_move().await?;
+        self.send_move(player_id, path).await
+    }
+
+    async fn send_move(&mut self, player_id: u8, path: Vec<Square>) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::MoveMade { player_id, path },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the squares the player's piece moves through, leaving the server to decide whether
+    /// the move is allowed.
+    async fn get_move(&mut self) -> Result<Vec<Square>, io::Error> {
+        loop {
+            self.user_output
+                .write_message("checkers-move-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match parse_move(&input_text) {
+                None => self.user_output.write_message("checkers-not-a-move", &[])?,
+                Some(path) => return Ok(path),
+            };
+        }
+    }
+}
+
+/// Reads a move written as the squares a piece moves through, separated by "-", "x" or spaces.
+fn parse_move(text: &str) -> Option<Vec<Square>> {
+    let path = text
+        .split(|c: char| c == '-' || c.eq_ignore_ascii_case(&'x') || c.is_whitespace())
+        .filter(|square| !square.is_empty())
+        .map(|square| square.parse().ok())
+        .collect::<Option<Vec<Square>>>()?;
+
+    (path.len() >= 2).then_some(path)
+}
+
+/// Writes a move in the usual notation, with captures joined by "x" and other moves by "-".
+fn describe_move(path: &[Square]) -> String {
+    let separator = if board::is_capture(path) { "x" } else { "-" };
+    path.iter()
+        .map(Square::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for CheckersClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "checkers-game-started-local",
+            &[("player", &self.get_player_icon_by_id(PLAYER_ONE_ID))],
+        )
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for CheckersClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let key = match self.client_type.id {
+            PLAYER_ONE_ID => "checkers-game-started-online-bottom",
+            _ => "checkers-game-started-online-top",
+        };
+        self.user_output.message(key, &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for CheckersClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for CheckersClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(path) = bot::choose_move(&self.cells, player_id) {
+            self.send_move(player_id, path).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for CheckersClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::BoardUpdated { cells } => self.handle_board_updated_event(cells).await,
+            ServerEvent::MoveMade { player_id, path } => {
+                self.handle_move_made_event(player_id, path).await
+            }
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { outcome } => self.handle_game_over_event(outcome).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::error::TryRecvError;
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+    use crate::connection::{self, Codec, SUPPORTED_CODECS};
+    use crate::server::OutgoingEvent;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        CheckersClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = CheckersClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    fn path(squares: &[&str]) -> Vec<Square> {
+        squares
+            .iter()
+            .map(|square| square.parse().unwrap())
+            .collect()
+    }
+
+    async fn send_move(server: &mut CheckersServer, player_id: u8, squares: &[&str]) {
+        server
+            .handle_event(ClientEvent::MoveMade {
+                player_id,
+                path: path(squares),
+            })
+            .await;
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn full_board_fits_within_event_length_limit() {
+        let event = OutgoingEvent::Game {
+            event: game::serialize_event(ServerEvent::BoardUpdated {
+                cells: vec![
+                    Some(Piece {
+                        player_id: PLAYER_ONE_ID,
+                        king: true,
+                    });
+                    (BOARD_SIZE * BOARD_SIZE) as usize
+                ],
+            }),
+        };
+
+        for codec in SUPPORTED_CODECS {
+            assert!(codec.encode(&event).unwrap().len() <= connection::MAX_EVENT_LENGTH);
+        }
+    }
+
+    #[test]
+    fn parse_move_reads_squares_between_separators() {
+        assert_eq!(parse_move("c3-d4\n"), Some(path(&["c3", "d4"])));
+        assert_eq!(parse_move("A3xC5xE7"), Some(path(&["a3", "c5", "e7"])));
+        assert_eq!(parse_move(" c3 d4 "), Some(path(&["c3", "d4"])));
+        assert_eq!(parse_move("c3"), None);
+        assert_eq!(parse_move("c3-z9"), None);
+    }
+
+    #[tokio::test]
+    async fn server_moves_piece_and_passes_turn() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = CheckersServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, &["c3", "d4"]).await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert!(matches!(
+            &drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::MoveMade {
+                    player_id: PLAYER_ONE_ID,
+                    ..
+                },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_illegal_move_and_gives_turn_back() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = CheckersServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, &["c3", "c4"]).await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::UnplayableSquare { .. }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_forfeits_game_of_player_exceeding_invalid_moves() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = CheckersServer::new(sender);
+
+        for _ in 0..RejectedMoves::LIMIT {
+            send_move(&mut server, PLAYER_ONE_ID, &["c3", "e5"]).await;
+        }
+
+        let events = drain_dispatched_events(&mut receiver);
+        assert!(matches!(
+            events[..],
+            [
+                ..,
+                ServerEvent::ErrorOccurred { .. },
+                ServerEvent::GameOver {
+                    outcome: Outcome::WinnerFound {
+                        player_id: PLAYER_TWO_ID
+                    }
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_skips_turn_of_timed_out_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = CheckersServer::new(sender);
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::DispatchToClient { .. })
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::TurnStarted {
+                player_id: PLAYER_TWO_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_board_updated_event(Board::new().get_cells())
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "  a b c d e f g h\n\
+             8   o   o   o   o 8\n\
+             7 o   o   o   o   7\n\
+             6   o   o   o   o 6\n\
+             5 .   .   .   .   5\n\
+             4   .   .   .   . 4\n\
+             3 x   x   x   x   3\n\
+             2   x   x   x   x 2\n\
+             1 x   x   x   x   1\n  \
+             a b c d e f g h\n\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let mut cells = Board::new().get_cells();
+        cells[1] = Some(Piece {
+            player_id: PLAYER_ONE_ID,
+            king: true,
+        });
+        client.handle_board_updated_event(cells).await.unwrap();
+        assert_client_output(
+            output,
+            ".X.o.o.o/o.o.o.o./.o.o.o.o/......../......../x.x.x.x./.x.x.x.x/x.x.x.x.\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_points_out_opponent_moves_but_not_own() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_move_made_event(PLAYER_ONE_ID, path(&["c3", "d4"]))
+            .await
+            .unwrap();
+        client
+            .handle_move_made_event(PLAYER_TWO_ID, path(&["f6", "d4", "b2"]))
+            .await
+            .unwrap();
+        assert_client_output(output, "Player O moved f6xd4xb2.\n");
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_move_made_event(PLAYER_TWO_ID, path(&["f6", "e5"]))
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::NotYourPiece {
+                square: "e5".parse().unwrap(),
+            })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador O ha movido f6-e5.\nError: No tienes ninguna pieza en e5.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_move_handles_errors_until_valid_move_provided() {
+        let input = "c3\nc3-d4".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let path = client.get_move().await.unwrap();
+        assert_eq!(path, super::tests::path(&["c3", "d4"]));
+        assert_client_output(
+            output,
+            "Move (e.g. c3-d4):\nInvalid move.\nMove (e.g. c3-d4):\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_online_client_current_player() {
+        let input = "c3-d4".as_bytes();
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(input, OnlineClient { id: 1 }).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(ClientEvent::MoveMade {
+                    player_id: 1,
+                    path: path(&["c3", "d4"]),
+                })
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_spectator_client() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        client.handle_player_turn_event(2).await.unwrap();
+        assert_client_output(output, "Player O's turn!\n");
+
+        assert_eq!(Err(TryRecvError::Empty), receiver.try_recv());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 2,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_player_turn_event(2).await.unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer { .. })
+        ));
+    }
+}
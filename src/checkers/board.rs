@@ -0,0 +1,547 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::checkers::{Error, Outcome};
+use crate::server::{get_alternative_player_id, PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+/// The width and height of the board.
+pub const BOARD_SIZE: u8 = 8;
+/// How many moves may be made in a row without a capture or a man moving before the game is
+/// drawn, which is forty by each player.
+const DRAW_MOVE_LIMIT: u8 = 80;
+
+/// A square on the board, counted from the top left as player one sees it.
+///
+/// Squares are written as a player would enter them, with a letter for the column and a number
+/// for the row counting up from player one's side, such as "c3".
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Square {
+    pub row: u8,
+    pub column: u8,
+}
+
+impl Square {
+    pub const fn new(row: u8, column: u8) -> Square {
+        Square { row, column }
+    }
+
+    /// Whether pieces may stand on the square, which only the dark squares allow.
+    pub fn is_playable(&self) -> bool {
+        self.row < BOARD_SIZE && self.column < BOARD_SIZE && (self.row + self.column) % 2 == 1
+    }
+
+    fn index(&self) -> usize {
+        (self.row * BOARD_SIZE + self.column) as usize
+    }
+
+    /// The square the given number of rows and columns away, if it is on the board.
+    fn offset(&self, rows: i8, columns: i8) -> Option<Square> {
+        let row = self.row.checked_add_signed(rows)?;
+        let column = self.column.checked_add_signed(columns)?;
+        let square = Square::new(row, column);
+
+        square.is_playable().then_some(square)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = char::from(b'a' + self.column);
+        write!(f, "{}{}", column, BOARD_SIZE - self.row)
+    }
+}
+
+impl FromStr for Square {
+    type Err = InvalidSquare;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(column), Some(row), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(InvalidSquare);
+        };
+        let column = column.to_ascii_lowercase();
+        let row = row.to_digit(10).ok_or(InvalidSquare)? as u8;
+        if !('a'..='h').contains(&column) || !(1..=BOARD_SIZE).contains(&row) {
+            return Err(InvalidSquare);
+        }
+
+        Ok(Square::new(BOARD_SIZE - row, column as u8 - b'a'))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidSquare;
+
+/// Sent as a single number, the player's ID doubled plus one for a king, to keep a full board
+/// within the length allowed for an event.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "u8", from = "u8")]
+pub struct Piece {
+    pub player_id: u8,
+    /// Set once the piece has reached the far side of the board, letting it move backwards.
+    pub king: bool,
+}
+
+impl Piece {
+    const fn man(player_id: u8) -> Piece {
+        Piece {
+            player_id,
+            king: false,
+        }
+    }
+
+    /// The directions the piece may move in, as rows and columns. Player one starts at the
+    /// bottom of the board, so moves up it.
+    fn directions(&self) -> &'static [(i8, i8)] {
+        match (self.king, self.player_id) {
+            (true, _) => &[(-1, -1), (-1, 1), (1, -1), (1, 1)],
+            (false, PLAYER_ONE_ID) => &[(-1, -1), (-1, 1)],
+            (false, _) => &[(1, -1), (1, 1)],
+        }
+    }
+
+    /// The row on which a man of the player is crowned.
+    fn crowning_row(&self) -> u8 {
+        match self.player_id {
+            PLAYER_ONE_ID => 0,
+            _ => BOARD_SIZE - 1,
+        }
+    }
+}
+
+impl From<Piece> for u8 {
+    fn from(piece: Piece) -> u8 {
+        piece.player_id << 1 | piece.king as u8
+    }
+}
+
+impl From<u8> for Piece {
+    fn from(value: u8) -> Piece {
+        Piece {
+            player_id: value >> 1,
+            king: value & 1 == 1,
+        }
+    }
+}
+
+pub struct Board {
+    cells: Vec<Option<Piece>>,
+    /// The number of moves made since the last capture or move by a man.
+    moves_without_progress: u8,
+}
+
+impl Board {
+    /// Sets up the board with each player's men on the dark squares of the three rows nearest
+    /// them.
+    pub(crate) fn new() -> Board {
+        let cells = (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |column| Square::new(row, column)))
+            .map(|square| match square.row {
+                _ if !square.is_playable() => None,
+                0..=2 => Some(Piece::man(PLAYER_TWO_ID)),
+                5.. => Some(Piece::man(PLAYER_ONE_ID)),
+                _ => None,
+            })
+            .collect();
+
+        Board::from_cells(cells)
+    }
+
+    /// Holds the pieces as they were sent by the server, so that clients can work out moves.
+    pub(crate) fn from_cells(cells: Vec<Option<Piece>>) -> Board {
+        Board {
+            cells,
+            moves_without_progress: 0,
+        }
+    }
+
+    pub(crate) fn get_cells(&self) -> Vec<Option<Piece>> {
+        self.cells.clone()
+    }
+
+    pub(crate) fn get_piece(&self, square: Square) -> Option<Piece> {
+        self.cells[square.index()]
+    }
+
+    /// Finds every move the player may make, as the squares each piece would move through.
+    ///
+    /// Captures are compulsory, so only captures are returned while any piece can make one. Each
+    /// capture carries on jumping for as long as the piece is able to.
+    pub(crate) fn legal_moves(&self, player_id: u8) -> Vec<Vec<Square>> {
+        let pieces: Vec<(Square, Piece)> = (0..BOARD_SIZE)
+            .flat_map(|row| (0..BOARD_SIZE).map(move |column| Square::new(row, column)))
+            .filter_map(|square| Some((square, self.get_piece(square)?)))
+            .filter(|(_, piece)| piece.player_id == player_id)
+            .collect();
+
+        let captures: Vec<Vec<Square>> = pieces
+            .iter()
+            .flat_map(|&(square, piece)| {
+                let mut cells = self.cells.clone();
+                cells[square.index()] = None;
+                jump_sequences(&cells, square, piece)
+            })
+            .collect();
+        if !captures.is_empty() {
+            return captures;
+        }
+
+        pieces
+            .iter()
+            .flat_map(|&(square, piece)| {
+                piece
+                    .directions()
+                    .iter()
+                    .filter_map(move |&(rows, columns)| square.offset(rows, columns))
+                    .filter(|to| self.get_piece(*to).is_none())
+                    .map(move |to| vec![square, to])
+            })
+            .collect()
+    }
+
+    /// Moves the piece along the path of squares, removing any it jumps over and crowning it if
+    /// it reaches the far side of the board.
+    pub(crate) fn make_move(&mut self, player_id: u8, path: &[Square]) -> Result<(), Error> {
+        let (&from, &to) = match (path.first(), path.last()) {
+            (Some(from), Some(to)) if path.len() >= 2 => (from, to),
+            _ => return Err(Error::IncompleteMove),
+        };
+        if let Some(&square) = path.iter().find(|square| !square.is_playable()) {
+            return Err(Error::UnplayableSquare { square });
+        }
+        let mut piece = match self.get_piece(from) {
+            Some(piece) if piece.player_id == player_id => piece,
+            _ => return Err(Error::NotYourPiece { square: from }),
+        };
+
+        let legal_moves = self.legal_moves(player_id);
+        if !legal_moves.iter().any(|legal_move| legal_move == path) {
+            let must_capture = legal_moves
+                .first()
+                .is_some_and(|legal_move| is_capture(legal_move));
+            let error = if legal_moves
+                .iter()
+                .any(|legal_move| legal_move.starts_with(path))
+            {
+                Error::CaptureIncomplete
+            } else if must_capture {
+                Error::CaptureRequired
+            } else {
+                Error::IllegalMove
+            };
+            return Err(error);
+        }
+
+        let mut captured = false;
+        for step in path.windows(2) {
+            if is_jump(step[0], step[1]) {
+                let jumped = Square::new(
+                    (step[0].row + step[1].row) / 2,
+                    (step[0].column + step[1].column) / 2,
+                );
+                self.cells[jumped.index()] = None;
+                captured = true;
+            }
+        }
+        if captured || !piece.king {
+            self.moves_without_progress = 0;
+        } else {
+            self.moves_without_progress += 1;
+        }
+        if to.row == piece.crowning_row() {
+            piece.king = true;
+        }
+        self.cells[from.index()] = None;
+        self.cells[to.index()] = Some(piece);
+
+        Ok(())
+    }
+
+    /// Determines whether the game has ended, now that it is the given player's turn.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Outcome)` if the player has no move left to make, so loses, or if neither player
+    ///   has made progress for too long.
+    /// - `None` if the game continues.
+    pub(crate) fn determine_outcome(&self, next_player_id: u8) -> Option<Outcome> {
+        if self.legal_moves(next_player_id).is_empty() {
+            return Some(Outcome::WinnerFound {
+                player_id: get_alternative_player_id(next_player_id),
+            });
+        }
+        if self.moves_without_progress >= DRAW_MOVE_LIMIT {
+            return Some(Outcome::Draw);
+        }
+
+        None
+    }
+}
+
+/// Whether the move jumps over a piece, rather than stepping to the next square.
+pub(crate) fn is_capture(path: &[Square]) -> bool {
+    path.len() >= 2 && is_jump(path[0], path[1])
+}
+
+fn is_jump(from: Square, to: Square) -> bool {
+    from.row.abs_diff(to.row) == 2
+}
+
+/// Finds every sequence of jumps the piece could make from the square, each continuing for as
+/// long as another capture is available. A man that is crowned stops jumping.
+///
+/// # Parameters
+///
+/// - `cells`: The pieces on the board, without the piece that is jumping.
+fn jump_sequences(cells: &[Option<Piece>], from: Square, piece: Piece) -> Vec<Vec<Square>> {
+    let mut sequences = Vec::new();
+
+    for &(rows, columns) in piece.directions() {
+        let (Some(over), Some(to)) = (
+            from.offset(rows, columns),
+            from.offset(rows * 2, columns * 2),
+        ) else {
+            continue;
+        };
+        let is_opponent =
+            cells[over.index()].is_some_and(|other| other.player_id != piece.player_id);
+        if !is_opponent || cells[to.index()].is_some() {
+            continue;
+        }
+
+        let mut remaining = cells.to_vec();
+        remaining[over.index()] = None;
+        let continuations = if !piece.king && to.row == piece.crowning_row() {
+            Vec::new()
+        } else {
+            jump_sequences(&remaining, to, piece)
+        };
+
+        if continuations.is_empty() {
+            sequences.push(vec![from, to]);
+        }
+        for continuation in continuations {
+            sequences.push([vec![from], continuation].concat());
+        }
+    }
+
+    sequences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(name: &str) -> Square {
+        name.parse().unwrap()
+    }
+
+    fn path(names: &[&str]) -> Vec<Square> {
+        names.iter().map(|name| square(name)).collect()
+    }
+
+    /// Sets up a board holding only the given pieces.
+    fn board_with(pieces: &[(&str, Piece)]) -> Board {
+        let mut cells = vec![None; (BOARD_SIZE * BOARD_SIZE) as usize];
+        for (name, piece) in pieces {
+            cells[square(name).index()] = Some(*piece);
+        }
+
+        Board::from_cells(cells)
+    }
+
+    #[test]
+    fn square_is_parsed_from_how_it_is_displayed() {
+        assert_eq!(square("a1"), Square::new(7, 0));
+        assert_eq!(square("H8"), Square::new(0, 7));
+        assert_eq!(Square::new(5, 2).to_string(), "c3");
+        assert_eq!("i1".parse::<Square>(), Err(InvalidSquare));
+        assert_eq!("a9".parse::<Square>(), Err(InvalidSquare));
+        assert_eq!("a10".parse::<Square>(), Err(InvalidSquare));
+    }
+
+    #[test]
+    fn new_board_has_twelve_men_for_each_player_on_dark_squares() {
+        let cells = Board::new().get_cells();
+
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let men = cells
+                .iter()
+                .filter(|cell| **cell == Some(Piece::man(player_id)))
+                .count();
+            assert_eq!(men, 12);
+        }
+        assert_eq!(cells[square("a1").index()], Some(Piece::man(PLAYER_ONE_ID)));
+        assert_eq!(cells[square("b8").index()], Some(Piece::man(PLAYER_TWO_ID)));
+        assert_eq!(cells[square("b1").index()], None);
+    }
+
+    #[test]
+    fn men_only_move_forwards_onto_empty_squares() {
+        let board = Board::new();
+
+        let mut moves = board.legal_moves(PLAYER_ONE_ID);
+        moves.sort_by_key(|legal_move| (legal_move[0].column, legal_move[1].column));
+        assert_eq!(
+            moves,
+            vec![
+                path(&["a3", "b4"]),
+                path(&["c3", "b4"]),
+                path(&["c3", "d4"]),
+                path(&["e3", "d4"]),
+                path(&["e3", "f4"]),
+                path(&["g3", "f4"]),
+                path(&["g3", "h4"]),
+            ]
+        );
+        assert_eq!(board.legal_moves(PLAYER_TWO_ID).len(), 7);
+    }
+
+    #[test]
+    fn kings_move_in_every_direction() {
+        let king = Piece {
+            player_id: PLAYER_ONE_ID,
+            king: true,
+        };
+        let board = board_with(&[("d4", king)]);
+
+        assert_eq!(board.legal_moves(PLAYER_ONE_ID).len(), 4);
+    }
+
+    #[test]
+    fn captures_are_compulsory() {
+        let mut board = board_with(&[
+            ("c3", Piece::man(PLAYER_ONE_ID)),
+            ("g3", Piece::man(PLAYER_ONE_ID)),
+            ("d4", Piece::man(PLAYER_TWO_ID)),
+        ]);
+
+        assert_eq!(board.legal_moves(PLAYER_ONE_ID), vec![path(&["c3", "e5"])]);
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["g3", "h4"])),
+            Err(Error::CaptureRequired)
+        ));
+
+        board
+            .make_move(PLAYER_ONE_ID, &path(&["c3", "e5"]))
+            .unwrap();
+        assert_eq!(board.get_piece(square("d4")), None);
+        assert_eq!(
+            board.get_piece(square("e5")),
+            Some(Piece::man(PLAYER_ONE_ID))
+        );
+    }
+
+    #[test]
+    fn multi_jump_must_be_completed() {
+        let mut board = board_with(&[
+            ("a1", Piece::man(PLAYER_ONE_ID)),
+            ("b2", Piece::man(PLAYER_TWO_ID)),
+            ("d4", Piece::man(PLAYER_TWO_ID)),
+            ("h8", Piece::man(PLAYER_TWO_ID)),
+        ]);
+
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["a1", "c3"])),
+            Err(Error::CaptureIncomplete)
+        ));
+
+        board
+            .make_move(PLAYER_ONE_ID, &path(&["a1", "c3", "e5"]))
+            .unwrap();
+        assert_eq!(board.get_piece(square("b2")), None);
+        assert_eq!(board.get_piece(square("d4")), None);
+    }
+
+    #[test]
+    fn man_reaching_far_side_is_crowned_and_stops_jumping() {
+        let mut board = board_with(&[
+            ("b6", Piece::man(PLAYER_ONE_ID)),
+            ("c7", Piece::man(PLAYER_TWO_ID)),
+            ("e7", Piece::man(PLAYER_TWO_ID)),
+        ]);
+
+        // Carrying on as a king would capture e7 as well, but being crowned ends the move
+        assert_eq!(board.legal_moves(PLAYER_ONE_ID), vec![path(&["b6", "d8"])]);
+
+        board
+            .make_move(PLAYER_ONE_ID, &path(&["b6", "d8"]))
+            .unwrap();
+        assert_eq!(
+            board.get_piece(square("d8")),
+            Some(Piece {
+                player_id: PLAYER_ONE_ID,
+                king: true
+            })
+        );
+    }
+
+    #[test]
+    fn moving_piece_that_is_not_own_is_refused() {
+        let mut board = Board::new();
+
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["b6", "a5"])),
+            Err(Error::NotYourPiece { .. })
+        ));
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["c3", "c4"])),
+            Err(Error::UnplayableSquare { .. })
+        ));
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["c3", "e5"])),
+            Err(Error::IllegalMove)
+        ));
+        assert!(matches!(
+            board.make_move(PLAYER_ONE_ID, &path(&["c3"])),
+            Err(Error::IncompleteMove)
+        ));
+    }
+
+    #[test]
+    fn player_without_moves_loses() {
+        let board = board_with(&[("a1", Piece::man(PLAYER_ONE_ID))]);
+
+        assert_eq!(
+            board.determine_outcome(PLAYER_TWO_ID),
+            Some(Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID
+            })
+        );
+        assert_eq!(board.determine_outcome(PLAYER_ONE_ID), None);
+    }
+
+    #[test]
+    fn game_is_drawn_once_kings_have_moved_back_and_forth_too_long() {
+        let king = |player_id| Piece {
+            player_id,
+            king: true,
+        };
+        let mut board = board_with(&[("a1", king(PLAYER_ONE_ID)), ("h8", king(PLAYER_TWO_ID))]);
+
+        for turn in 0..DRAW_MOVE_LIMIT / 4 {
+            assert_eq!(
+                board.determine_outcome(PLAYER_ONE_ID),
+                None,
+                "turn {}",
+                turn
+            );
+            board
+                .make_move(PLAYER_ONE_ID, &path(&["a1", "b2"]))
+                .unwrap();
+            board
+                .make_move(PLAYER_TWO_ID, &path(&["h8", "g7"]))
+                .unwrap();
+            board
+                .make_move(PLAYER_ONE_ID, &path(&["b2", "a1"]))
+                .unwrap();
+            board
+                .make_move(PLAYER_TWO_ID, &path(&["g7", "h8"]))
+                .unwrap();
+        }
+
+        assert_eq!(board.determine_outcome(PLAYER_ONE_ID), Some(Outcome::Draw));
+    }
+}
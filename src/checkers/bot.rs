@@ -0,0 +1,120 @@
+use crate::checkers::board::{self, Board, Piece, Square};
+use crate::server::get_alternative_player_id;
+
+/// Chooses a move for the given player based on the pieces on the board.
+///
+/// Moves are prioritised as follows; the capture taking the most pieces, crowning a man, moving
+/// without leaving a piece open to capture, and finally any remaining move.
+///
+/// # Returns
+///
+/// - `Some(Vec<Square>)` containing the squares the chosen piece moves through.
+/// - `None` if the player has no move to make.
+pub(crate) fn choose_move(cells: &[Option<Piece>], player_id: u8) -> Option<Vec<Square>> {
+    let board = Board::from_cells(cells.to_vec());
+    let legal_moves = board.legal_moves(player_id);
+    let board_after = |legal_move: &Vec<Square>| {
+        let mut board = Board::from_cells(cells.to_vec());
+        board.make_move(player_id, legal_move).unwrap();
+        board
+    };
+
+    let longest_capture = legal_moves
+        .iter()
+        .filter(|legal_move| board::is_capture(legal_move))
+        .max_by_key(|legal_move| legal_move.len());
+    let crowning = legal_moves.iter().find(|legal_move| {
+        let is_man = board
+            .get_piece(legal_move[0])
+            .is_some_and(|piece| !piece.king);
+        let destination = *legal_move.last().unwrap();
+        is_man
+            && board_after(legal_move)
+                .get_piece(destination)
+                .is_some_and(|piece| piece.king)
+    });
+    let safe = legal_moves.iter().find(|legal_move| {
+        !board_after(legal_move)
+            .legal_moves(get_alternative_player_id(player_id))
+            .iter()
+            .any(|reply| board::is_capture(reply))
+    });
+
+    longest_capture
+        .or(crowning)
+        .or(safe)
+        .or(legal_moves.first())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+    use super::*;
+
+    fn square(name: &str) -> Square {
+        name.parse().unwrap()
+    }
+
+    fn cells_with(pieces: &[(&str, u8)]) -> Vec<Option<Piece>> {
+        let mut cells = vec![None; 64];
+        for (name, player_id) in pieces {
+            let square = square(name);
+            cells[(square.row * 8 + square.column) as usize] = Some(Piece {
+                player_id: *player_id,
+                king: false,
+            });
+        }
+
+        cells
+    }
+
+    #[test]
+    fn choose_move_takes_most_pieces() {
+        let cells = cells_with(&[
+            ("a1", PLAYER_ONE_ID),
+            ("b2", PLAYER_TWO_ID),
+            ("d4", PLAYER_TWO_ID),
+            ("g3", PLAYER_ONE_ID),
+            ("h4", PLAYER_TWO_ID),
+        ]);
+
+        assert_eq!(
+            choose_move(&cells, PLAYER_ONE_ID),
+            Some(vec![square("a1"), square("c3"), square("e5")])
+        );
+    }
+
+    #[test]
+    fn choose_move_crowns_man() {
+        let cells = cells_with(&[
+            ("a5", PLAYER_ONE_ID),
+            ("g7", PLAYER_ONE_ID),
+            ("a7", PLAYER_TWO_ID),
+        ]);
+
+        assert_eq!(
+            choose_move(&cells, PLAYER_ONE_ID),
+            Some(vec![square("g7"), square("f8")])
+        );
+    }
+
+    #[test]
+    fn choose_move_avoids_leaving_piece_open_to_capture() {
+        let cells = cells_with(&[("c3", PLAYER_ONE_ID), ("a5", PLAYER_TWO_ID)]);
+
+        // Moving to b4 would let the piece on a5 jump it
+        assert_eq!(
+            choose_move(&cells, PLAYER_ONE_ID),
+            Some(vec![square("c3"), square("d4")])
+        );
+    }
+
+    #[test]
+    fn choose_move_returns_none_without_moves() {
+        let cells = cells_with(&[("a1", PLAYER_TWO_ID)]);
+
+        assert_eq!(choose_move(&cells, PLAYER_TWO_ID), None);
+    }
+}
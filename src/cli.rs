@@ -0,0 +1,1733 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use clap::builder::RangedU64ValueParser;
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use rust_game_server::lobby::{JoinToken, RateLimit, RoomChoice, RoomCode};
+use rust_game_server::locale::Locale;
+use rust_game_server::server::{
+    self, FirstPlayer, Handicap, InvalidMessagePolicy, PlayerMark, PlayerName, SpectatorConfig,
+    TimeoutAction, TurnTimer,
+};
+use rust_game_server::stats::{ExportDestination, ExportFormat, Exporter};
+use rust_game_server::tic_tac_toe::{
+    BoardConfig, Variant, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE, MIN_BOARD_SIZE,
+};
+use rust_game_server::tls::HostCertificate;
+use rust_game_server::trivia::{
+    QuestionBank, QuestionBankError, TriviaConfig, DEFAULT_ANSWER_TIME, DEFAULT_ROUNDS,
+};
+use tracing::level_filters::LevelFilter;
+
+use crate::{GameMode, DEFAULT_CONNECT_ATTEMPTS, DEFAULT_PORT};
+
+/// Play Tic Tac Toe, Checkers, Battleship, Othello, Rock Paper Scissors, Mancala, Nim, Dots and
+/// Boxes, Hangman, Trivia or Blackjack locally, or host, join, and spectate games over the network.
+///
+/// If no subcommand is given the game mode is selected interactively.
+///
+/// Exits with 1 if the game could not be set up, 8 if the port could not be listened on and 9 if
+/// the game could not be reached. A game cut short exits with 3 if an opponent left, 4 if a player
+/// broke the protocol, 5 if the server stopped it, 6 if an opponent stopped responding and 7 if
+/// the game could not otherwise carry on.
+#[derive(Debug, Parser)]
+#[command(version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Keep output to short single lines, for slow connections and small terminals.
+    #[arg(long, global = true)]
+    pub terse: bool,
+    /// Write plain text without colours, which are otherwise used when writing to a terminal.
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Show every event exchanged with the server on stderr, with timestamps, as it happens.
+    #[arg(long, global = true)]
+    pub debug_protocol: bool,
+    /// The language to show messages in, "en" for English or "es" for Spanish.
+    #[arg(long, global = true, value_name = "LANGUAGE")]
+    pub locale: Option<Locale>,
+    /// Describe the board in words rather than drawing it, and write without colours, for use
+    /// with a screen reader.
+    #[arg(long, global = true)]
+    pub screen_reader: bool,
+    /// Play in a full-screen interface, with the board, moves made and messages in separate panes.
+    #[cfg(feature = "tui")]
+    #[arg(long, global = true, conflicts_with_all = ["terse", "debug_protocol", "screen_reader"])]
+    pub tui: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Play a game against someone sharing this terminal.
+    Local {
+        /// The game to play.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        #[command(flatten)]
+        board: BoardArgs,
+        #[command(flatten)]
+        trivia: TriviaArgs,
+    },
+    /// Host an online game and play as player X.
+    Host {
+        /// The port to accept connections on, "0" picks any free port.
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Limit the time each player has to make a move.
+        #[arg(long, value_name = "SECONDS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+        turn_time: Option<u64>,
+        /// Give the joining player a different time to make a move, as a handicap for one player.
+        #[arg(long, value_name = "SECONDS", value_parser = RangedU64ValueParser::<u64>::new().range(1..), requires = "turn_time")]
+        opponent_turn_time: Option<u64>,
+        /// What happens to a player who runs out of time.
+        #[arg(long, value_enum, requires = "turn_time")]
+        on_timeout: Option<OnTimeout>,
+        /// Allow up to this many spectators to watch the game.
+        #[arg(long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        max_spectators: Option<usize>,
+        /// Tell players when someone starts or stops watching.
+        #[arg(long, requires = "max_spectators")]
+        notify_players: bool,
+        /// Who makes the first move.
+        #[arg(long, value_enum, default_value_t = FirstMove::Host)]
+        first_move: FirstMove,
+        /// Show your moves in Tic Tac Toe with this symbol rather than X.
+        #[arg(long, conflicts_with = "hot_seat")]
+        mark: Option<PlayerMark>,
+        /// The name to be rated under, once the game is over, when both players give one.
+        #[arg(long, requires = "stats", conflicts_with = "hot_seat")]
+        name: Option<PlayerName>,
+        /// Encrypt connections with TLS, generating a self-signed certificate unless one is given.
+        #[arg(long)]
+        tls: bool,
+        /// The certificate to present when using TLS, in PEM format.
+        #[arg(long, value_name = "FILE", requires_all = ["tls", "tls_key"])]
+        tls_cert: Option<PathBuf>,
+        /// The private key of the TLS certificate, in PEM format.
+        #[arg(long, value_name = "FILE", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Accept connections over WebSocket rather than plain TCP, allowing browsers to connect.
+        #[arg(long)]
+        websocket: bool,
+        /// Refuse connections from any address opening more than this many in a minute.
+        #[arg(long, value_name = "COUNT", default_value_t = RateLimit::default().max_connections, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        max_connections_per_minute: usize,
+        /// The game to play.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        #[command(flatten)]
+        board: BoardArgs,
+        #[command(flatten)]
+        trivia: TriviaArgs,
+        /// Record the outcome of the game in this SQLite database, creating it if needed.
+        #[arg(long, value_name = "FILE")]
+        stats: Option<PathBuf>,
+        /// Record the game to this replay file, for watching back with "replay".
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+        /// Leave the game's chance, such as a random first move, to this seed rather than a random
+        /// one, to play out a game again from the seed it logged or recorded.
+        #[arg(long)]
+        seed: Option<u64>,
+        #[command(flatten)]
+        export: ExportArgs,
+        /// Rather than playing, let a single player join and take both sides of the game.
+        #[arg(long, conflicts_with = "max_spectators")]
+        hot_seat: bool,
+        /// Let a player save the game to this file by entering "save" on their turn, ending it
+        /// until it is carried on with "resume".
+        #[arg(long, value_name = "FILE", conflicts_with = "hot_seat")]
+        save: Option<PathBuf>,
+        /// Have a bot take over from a player who leaves the game, so that the other can carry on.
+        #[arg(long, conflicts_with = "hot_seat")]
+        bot_takeover: bool,
+        /// Ask whether to keep waiting each time this long passes without anyone joining.
+        #[arg(long, value_name = "MINUTES", value_parser = RangedU64ValueParser::<u64>::new().range(1..), conflicts_with = "hot_seat")]
+        wait_timeout: Option<u64>,
+        /// Offer a rematch once the game is over, in which you may switch to another game.
+        #[arg(long, conflicts_with = "hot_seat")]
+        rematch: bool,
+        /// Let the player joining choose which game to play, with the one given by --game played
+        /// unless they choose another.
+        #[arg(long, conflicts_with = "hot_seat")]
+        offer_games: bool,
+        /// Also serve a web page on this port, through which people can join the game from their
+        /// browser without installing anything.
+        #[cfg(feature = "web")]
+        #[arg(long, value_name = "PORT", conflicts_with = "hot_seat")]
+        web_port: Option<u16>,
+    },
+    /// Host a game saved with "host --save" again, carrying it on once its players have rejoined.
+    ///
+    /// Players rejoin with the join token the game was first hosted with, and the host plays as
+    /// player X as before. The game may be saved to the same file again.
+    Resume {
+        /// The save file written when the game was saved.
+        file: PathBuf,
+        /// Show your moves in Tic Tac Toe with this symbol rather than X.
+        #[arg(long)]
+        mark: Option<PlayerMark>,
+        /// The port to accept connections on, "0" picks any free port.
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Encrypt connections with TLS, generating a self-signed certificate unless one is given.
+        #[arg(long)]
+        tls: bool,
+        /// The certificate to present when using TLS, in PEM format.
+        #[arg(long, value_name = "FILE", requires_all = ["tls", "tls_key"])]
+        tls_cert: Option<PathBuf>,
+        /// The private key of the TLS certificate, in PEM format.
+        #[arg(long, value_name = "FILE", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Accept connections over WebSocket rather than plain TCP, allowing browsers to connect.
+        #[arg(long)]
+        websocket: bool,
+    },
+    /// Run a dedicated server hosting games for every pair of players that joins, without playing.
+    Serve {
+        /// The port to accept connections on, "0" picks any free port.
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// Limit the time each player has to make a move.
+        #[arg(long, value_name = "SECONDS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+        turn_time: Option<u64>,
+        /// What happens to a player who runs out of time.
+        #[arg(long, value_enum, requires = "turn_time")]
+        on_timeout: Option<OnTimeout>,
+        /// Treat a player as disconnected once they have sent nothing for this long on their turn,
+        /// giving them the chance to reconnect.
+        #[arg(long, value_name = "SECONDS", value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+        read_timeout: Option<u64>,
+        /// Refuse messages a player sends that cannot be read and carry on with the game, ending
+        /// it only once they have sent this many, rather than at the first.
+        #[arg(long, value_name = "COUNT", value_parser = RangedU64ValueParser::<u32>::new().range(1..))]
+        max_invalid_messages: Option<u32>,
+        /// Encrypt connections with TLS, generating a self-signed certificate unless one is given.
+        #[arg(long)]
+        tls: bool,
+        /// The certificate to present when using TLS, in PEM format.
+        #[arg(long, value_name = "FILE", requires_all = ["tls", "tls_key"])]
+        tls_cert: Option<PathBuf>,
+        /// The private key of the TLS certificate, in PEM format.
+        #[arg(long, value_name = "FILE", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Accept connections over WebSocket rather than plain TCP, allowing browsers to connect.
+        #[arg(long)]
+        websocket: bool,
+        /// Refuse connections from any address opening more than this many in a minute.
+        #[arg(long, value_name = "COUNT", default_value_t = RateLimit::default().max_connections, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        max_connections_per_minute: usize,
+        /// The game to play.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        #[command(flatten)]
+        board: BoardArgs,
+        #[command(flatten)]
+        trivia: TriviaArgs,
+        /// Record the outcome of each game in this SQLite database, creating it if needed, rating
+        /// the players who give their name.
+        #[arg(long, value_name = "FILE")]
+        stats: Option<PathBuf>,
+        #[command(flatten)]
+        export: ExportArgs,
+        /// Run a single elimination tournament between this many players, who must join with
+        /// "--name", then stop once it has been won.
+        #[arg(long, value_name = "PLAYERS", value_parser = RangedU64ValueParser::<usize>::new().range(2..))]
+        tournament: Option<usize>,
+        /// Have a bot take over from a player who leaves a game and does not come back, so that
+        /// their opponent can carry on.
+        #[arg(long, conflicts_with = "tournament")]
+        bot_takeover: bool,
+        /// Accept commands controlling the running games on this Unix socket, try "help" once
+        /// connected.
+        #[arg(long, value_name = "FILE")]
+        admin_socket: Option<PathBuf>,
+        /// The least severe messages to log, unless overridden by the RUST_LOG variable.
+        #[arg(long, value_enum, default_value_t = LogLevel::Info)]
+        log_level: LogLevel,
+        /// Log each message as a JSON object, for collection by log processing tools.
+        #[arg(long)]
+        log_json: bool,
+    },
+    /// Join an online game and play as player O.
+    Join {
+        /// The address of the game, for example "127.0.0.1:22222".
+        address: String,
+        /// The join token shared by the host of the game.
+        join_token: JoinToken,
+        /// The game to play, when the host offers a choice of games. Otherwise the game chosen by
+        /// the host is played, and you will be asked to choose if they offer a choice.
+        #[arg(long, value_enum)]
+        game: Option<GameKind>,
+        /// Connect using TLS, trusting only the host's certificate found in this PEM file.
+        #[arg(long, value_name = "FILE")]
+        tls_cert: Option<PathBuf>,
+        /// Connect over WebSocket, for games hosted with the same option.
+        #[arg(long)]
+        websocket: bool,
+        /// Show your moves in Tic Tac Toe with this symbol rather than O, which must differ from
+        /// the one chosen by the host.
+        #[arg(long)]
+        mark: Option<PlayerMark>,
+        /// The name to be rated under, if the host is recording statistics and gave one too.
+        #[arg(long)]
+        name: Option<PlayerName>,
+        /// Join the game waiting for players in this room of a dedicated server, as listed by
+        /// "browse".
+        #[arg(long, value_name = "CODE")]
+        room: Option<RoomCode>,
+        /// Open a game of your own on a dedicated server, rather than joining one waiting for
+        /// players.
+        #[arg(long, conflicts_with = "room")]
+        new_room: bool,
+        /// Meet another player in a private room of a dedicated server, using a code of up to 4
+        /// hexadecimal digits agreed between you. Lets players who cannot host a game, such as
+        /// those behind NAT, play through a public server instead.
+        #[arg(long, value_name = "CODE", conflicts_with_all = ["room", "new_room"])]
+        private_room: Option<RoomCode>,
+        /// Try this many times to reach the game before giving up, waiting twice as long after
+        /// each failed attempt, starting from a second.
+        #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_CONNECT_ATTEMPTS, value_parser = RangedU64ValueParser::<u32>::new().range(1..))]
+        connect_attempts: u32,
+    },
+    /// List the games waiting for players on a dedicated server, then join the one chosen.
+    Browse {
+        /// The address of the server, for example "127.0.0.1:22222".
+        address: String,
+        /// The join token shared by the server.
+        join_token: JoinToken,
+        /// The game to play if you open a game of your own, which must be the one the server
+        /// hosts.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        /// Connect using TLS, trusting only the server's certificate found in this PEM file.
+        #[arg(long, value_name = "FILE")]
+        tls_cert: Option<PathBuf>,
+        /// Connect over WebSocket, for servers hosting with the same option.
+        #[arg(long)]
+        websocket: bool,
+        /// Show your moves in Tic Tac Toe with this symbol, which must differ from those of the
+        /// other players.
+        #[arg(long)]
+        mark: Option<PlayerMark>,
+        /// The name shown to others browsing for a game, and to be rated under.
+        #[arg(long)]
+        name: Option<PlayerName>,
+    },
+    /// Watch an online game that is accepting spectators.
+    Spectate {
+        /// The address of the game, for example "127.0.0.1:22222".
+        address: String,
+        /// The join token shared by the host of the game.
+        join_token: JoinToken,
+        /// The game being played, which must be the one the host chose.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        /// Connect using TLS, trusting only the host's certificate found in this PEM file.
+        #[arg(long, value_name = "FILE")]
+        tls_cert: Option<PathBuf>,
+        /// Connect over WebSocket, for games hosted with the same option.
+        #[arg(long)]
+        websocket: bool,
+    },
+    /// Host an online game played between two bots for spectators to watch.
+    Exhibition {
+        /// The port to accept connections on, "0" picks any free port.
+        #[arg(long, default_value_t = DEFAULT_PORT)]
+        port: u16,
+        /// The game to play.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        #[command(flatten)]
+        board: BoardArgs,
+        #[command(flatten)]
+        trivia: TriviaArgs,
+    },
+    /// Join an online game as a bot, for testing servers without anyone at the keyboard.
+    ///
+    /// The game is written to stderr, leaving stdout to a single JSON line reporting whether the
+    /// game was played to the end, which is also given by the exit status.
+    Bot {
+        /// The address of the game, for example "127.0.0.1:22222".
+        address: String,
+        /// The join token shared by the host of the game.
+        join_token: JoinToken,
+        /// The game being played, which must be the one the host chose.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        /// Enter the moves in this file, one to a line as a player would type them, rather than
+        /// choosing them. Needed to play both sides of a hot-seat game.
+        #[arg(long, value_name = "FILE")]
+        script: Option<PathBuf>,
+        /// Connect using TLS, trusting only the host's certificate found in this PEM file.
+        #[arg(long, value_name = "FILE")]
+        tls_cert: Option<PathBuf>,
+        /// Connect over WebSocket, for games hosted with the same option.
+        #[arg(long)]
+        websocket: bool,
+    },
+    /// Have bots play each other on a running server, many games at once, to see how it holds up.
+    ///
+    /// Every bot connects from this address, so the server must allow twice as many connections a
+    /// minute as there are games, using "--max-connections-per-minute".
+    Stress {
+        /// The address of the server, for example "127.0.0.1:22222".
+        address: String,
+        /// The join token shared by the server.
+        join_token: JoinToken,
+        /// The number of games to play at once.
+        #[arg(long, default_value_t = 10, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+        games: usize,
+        /// The game being played, which must be the one the server hosts.
+        #[arg(long, value_enum, default_value_t = GameKind::TicTacToe)]
+        game: GameKind,
+        /// Connect using TLS, trusting only the server's certificate found in this PEM file.
+        #[arg(long, value_name = "FILE")]
+        tls_cert: Option<PathBuf>,
+        /// Connect over WebSocket, for servers hosting with the same option.
+        #[arg(long)]
+        websocket: bool,
+    },
+    /// Print your record from the games hosted while recording statistics.
+    Stats {
+        /// The SQLite database given to "host --stats".
+        file: PathBuf,
+    },
+    /// Print the rating of each player who has given a name in games hosted while recording
+    /// statistics, highest first.
+    Ratings {
+        /// The SQLite database given to "host --stats".
+        file: PathBuf,
+    },
+    /// Watch back a game recorded with "host --record".
+    Replay {
+        /// The replay file to play.
+        file: PathBuf,
+    },
+    /// Open a window for hosting or joining a game, and play it there.
+    #[cfg(feature = "gui")]
+    Gui,
+}
+
+impl Cli {
+    /// Checks the combinations of arguments that clap is unable to check itself.
+    pub fn validate(&self) -> Result<(), clap::Error> {
+        let board = match &self.command {
+            Some(Command::Local { board, .. })
+            | Some(Command::Host { board, .. })
+            | Some(Command::Serve { board, .. })
+            | Some(Command::Exhibition { board, .. }) => board,
+            _ => return Ok(()),
+        };
+
+        if board
+            .win_length
+            .is_some_and(|length| length > board.board_size)
+        {
+            return Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "the win length cannot be longer than the board size",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The games that can be played.
+#[derive(Debug, Copy, Clone, PartialEq, ValueEnum)]
+pub enum GameKind {
+    /// Get a line of cells on a board of any size, to the board options given.
+    TicTacToe,
+    /// Capture all of the other player's pieces on an 8x8 board.
+    Checkers,
+    /// Sink the other player's hidden fleet before they sink yours.
+    Battleship,
+    /// Flip the other player's discs to hold the most of them once the board is full.
+    Othello,
+    /// Win the best of three rounds by choosing a hand at the same time as the other player.
+    RockPaperScissors,
+    /// Sow seeds around the pits of a Kalah board to gather the most of them in your store.
+    Mancala,
+    /// Take the last counter from the heaps, taking up to three at a time.
+    Nim,
+    /// Complete the most boxes by drawing the edges between dots on a grid of the board size.
+    DotsAndBoxes,
+    /// Guess the secret word chosen by the other player a letter at a time, or choose one for them.
+    Hangman,
+    /// Score the most points by answering the same questions as the other player against the clock.
+    Trivia,
+    /// Get closer to 21 than the dealer without going over, betting chips over several rounds.
+    Blackjack,
+}
+
+/// The board to play on, for the game modes that host a game of Tic Tac Toe or Dots and Boxes.
+#[derive(Debug, Args)]
+pub struct BoardArgs {
+    /// The width and height of the board, in boxes when playing Dots and Boxes.
+    #[arg(long, value_name = "CELLS", default_value_t = DEFAULT_BOARD_SIZE, value_parser = board_length_parser())]
+    board_size: usize,
+    /// How many cells in a row are needed to win, the board size unless given.
+    #[arg(long, value_name = "CELLS", value_parser = board_length_parser())]
+    win_length: Option<usize>,
+    /// The rules deciding who wins once a line is completed.
+    #[arg(long, value_enum, default_value_t = RuleVariant::Standard)]
+    variant: RuleVariant,
+}
+
+impl From<BoardArgs> for BoardConfig {
+    fn from(board: BoardArgs) -> Self {
+        BoardConfig {
+            size: board.board_size,
+            win_length: board.win_length.unwrap_or(board.board_size),
+            variant: board.variant.into(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum RuleVariant {
+    /// Completing a line of your own mark wins.
+    Standard,
+    /// Completing a line of your own mark loses.
+    Misere,
+    /// Either player places either mark, and completing a line of either wins.
+    Wild,
+}
+
+impl From<RuleVariant> for Variant {
+    fn from(variant: RuleVariant) -> Self {
+        match variant {
+            RuleVariant::Standard => Variant::Standard,
+            RuleVariant::Misere => Variant::Misere,
+            RuleVariant::Wild => Variant::Wild,
+        }
+    }
+}
+
+/// Where to export the result of each game, for the game modes that host a game.
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Write the result of each game to a new file in this directory, for league software to
+    /// import.
+    #[arg(long, value_name = "DIR", conflicts_with = "export_url")]
+    export_dir: Option<PathBuf>,
+    /// POST the result of each game to this HTTP endpoint.
+    #[arg(long, value_name = "URL")]
+    export_url: Option<String>,
+    /// The format to export results in.
+    #[arg(long, value_enum, default_value_t = ResultFormat::Json)]
+    export_format: ResultFormat,
+}
+
+impl From<ExportArgs> for Option<Exporter> {
+    fn from(export: ExportArgs) -> Self {
+        let destination = match (export.export_dir, export.export_url) {
+            (Some(directory), _) => ExportDestination::Directory(directory),
+            (_, Some(url)) => ExportDestination::Http(url),
+            (None, None) => return None,
+        };
+
+        Some(Exporter {
+            destination,
+            format: export.export_format.into(),
+        })
+    }
+}
+
+/// The questions to ask, for the game modes that host a game of Trivia.
+#[derive(Debug, Args)]
+pub struct TriviaArgs {
+    /// The number of questions asked when playing Trivia.
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_ROUNDS, value_parser = RangedU64ValueParser::<u8>::new().range(1..))]
+    rounds: u8,
+    /// The time players have to answer each question when playing Trivia.
+    #[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_ANSWER_TIME.as_secs(), value_parser = RangedU64ValueParser::<u64>::new().range(1..))]
+    answer_time: u64,
+    /// Ask the questions in this TOML or JSON file when playing Trivia, rather than the built-in
+    /// questions.
+    #[arg(long, value_name = "FILE", value_parser = question_bank_parser)]
+    questions: Option<Arc<QuestionBank>>,
+}
+
+impl From<TriviaArgs> for TriviaConfig {
+    fn from(trivia: TriviaArgs) -> Self {
+        TriviaConfig {
+            rounds: trivia.rounds,
+            answer_time: Duration::from_secs(trivia.answer_time),
+            questions: trivia.questions,
+        }
+    }
+}
+
+/// Reads the question bank as the arguments are parsed, so that one that cannot be used is
+/// reported before any game is hosted.
+fn question_bank_parser(path: &str) -> Result<Arc<QuestionBank>, QuestionBankError> {
+    QuestionBank::load(Path::new(path)).map(Arc::new)
+}
+
+fn board_length_parser() -> RangedU64ValueParser<usize> {
+    RangedU64ValueParser::<usize>::new().range(MIN_BOARD_SIZE as u64..=MAX_BOARD_SIZE as u64)
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum FirstMove {
+    /// The host, playing as player X.
+    Host,
+    /// The player joining the game.
+    Joiner,
+    /// Either player, chosen by tossing a coin as the game begins.
+    Random,
+}
+
+impl From<FirstMove> for FirstPlayer {
+    fn from(first_move: FirstMove) -> Self {
+        match first_move {
+            FirstMove::Host => FirstPlayer::PlayerOne,
+            FirstMove::Joiner => FirstPlayer::PlayerTwo,
+            FirstMove::Random => FirstPlayer::Random,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum OnTimeout {
+    /// The player forfeits the game.
+    Forfeit,
+    /// The player loses their turn.
+    Skip,
+}
+
+impl From<OnTimeout> for TimeoutAction {
+    fn from(on_timeout: OnTimeout) -> Self {
+        match on_timeout {
+            OnTimeout::Forfeit => TimeoutAction::Forfeit,
+            OnTimeout::Skip => TimeoutAction::SkipTurn,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(log_level: LogLevel) -> Self {
+        match log_level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub enum ResultFormat {
+    /// A JSON object holding the result.
+    Json,
+    /// A CSV header row followed by a row holding the result.
+    Csv,
+}
+
+impl From<ResultFormat> for ExportFormat {
+    fn from(result_format: ResultFormat) -> Self {
+        match result_format {
+            ResultFormat::Json => ExportFormat::Json,
+            ResultFormat::Csv => ExportFormat::Csv,
+        }
+    }
+}
+
+impl From<Command> for GameMode {
+    fn from(command: Command) -> Self {
+        match command {
+            Command::Local {
+                game,
+                board,
+                trivia,
+            } => GameMode::Local {
+                game,
+                board_config: board.into(),
+                trivia_config: trivia.into(),
+            },
+            Command::Host {
+                port,
+                turn_time,
+                opponent_turn_time,
+                on_timeout,
+                max_spectators,
+                notify_players,
+                first_move,
+                mark,
+                name,
+                tls,
+                tls_cert,
+                tls_key,
+                websocket,
+                max_connections_per_minute,
+                game,
+                board,
+                trivia,
+                stats,
+                record,
+                seed,
+                export,
+                hot_seat,
+                save,
+                bot_takeover,
+                wait_timeout,
+                rematch,
+                offer_games,
+                #[cfg(feature = "web")]
+                web_port,
+            } => GameMode::OnlineHost {
+                port,
+                turn_timer: turn_time.map(|seconds| TurnTimer {
+                    duration: Duration::from_secs(seconds),
+                    action: on_timeout.unwrap_or(OnTimeout::Forfeit).into(),
+                    handicap: opponent_turn_time.map(|seconds| Handicap {
+                        player_id: server::PLAYER_TWO_ID,
+                        duration: Duration::from_secs(seconds),
+                    }),
+                }),
+                spectator_config: max_spectators.map(|max_spectators| SpectatorConfig {
+                    max_spectators,
+                    notify_players,
+                }),
+                first_player: first_move.into(),
+                mark,
+                name,
+                tls_certificate: tls.then_some(host_certificate(tls_cert, tls_key)),
+                websocket,
+                rate_limit: rate_limit_per_minute(max_connections_per_minute),
+                game,
+                board_config: board.into(),
+                trivia_config: trivia.into(),
+                stats_path: stats,
+                replay_path: record,
+                rng_seed: seed,
+                exporter: export.into(),
+                hot_seat,
+                save_path: save,
+                bot_takeover,
+                wait_timeout: wait_timeout.map(|minutes| Duration::from_secs(minutes * 60)),
+                rematch,
+                offer_games,
+                #[cfg(feature = "web")]
+                web_port,
+            },
+            Command::Resume {
+                file,
+                mark,
+                port,
+                tls,
+                tls_cert,
+                tls_key,
+                websocket,
+            } => GameMode::Resume {
+                save_path: file,
+                mark,
+                port,
+                tls_certificate: tls.then_some(host_certificate(tls_cert, tls_key)),
+                websocket,
+            },
+            Command::Serve {
+                port,
+                turn_time,
+                on_timeout,
+                read_timeout,
+                max_invalid_messages,
+                tls,
+                tls_cert,
+                tls_key,
+                websocket,
+                max_connections_per_minute,
+                game,
+                board,
+                trivia,
+                stats,
+                export,
+                tournament,
+                bot_takeover,
+                admin_socket,
+                log_level,
+                log_json,
+            } => GameMode::Serve {
+                port,
+                turn_timer: turn_time.map(|seconds| TurnTimer {
+                    duration: Duration::from_secs(seconds),
+                    action: on_timeout.unwrap_or(OnTimeout::Forfeit).into(),
+                    handicap: None,
+                }),
+                read_timeout: read_timeout.map(Duration::from_secs),
+                invalid_message_policy: max_invalid_messages
+                    .map_or(InvalidMessagePolicy::Disconnect, |limit| {
+                        InvalidMessagePolicy::Tolerate { limit }
+                    }),
+                tls_certificate: tls.then_some(host_certificate(tls_cert, tls_key)),
+                websocket,
+                rate_limit: rate_limit_per_minute(max_connections_per_minute),
+                game,
+                board_config: board.into(),
+                trivia_config: trivia.into(),
+                stats_path: stats,
+                exporter: export.into(),
+                tournament,
+                bot_takeover,
+                admin_socket,
+                log_level: log_level.into(),
+                log_json,
+            },
+            Command::Join {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+                mark,
+                name,
+                room,
+                new_room,
+                private_room,
+                connect_attempts,
+            } => GameMode::OnlineJoin {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+                mark,
+                name,
+                room: match (room, private_room) {
+                    (Some(room), _) => RoomChoice::Join(room),
+                    (None, Some(room)) => RoomChoice::Private(room),
+                    (None, None) if new_room => RoomChoice::New,
+                    (None, None) => RoomChoice::Any,
+                },
+                connect_attempts,
+            },
+            Command::Browse {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+                mark,
+                name,
+            } => GameMode::Browse {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+                mark,
+                name,
+            },
+            Command::Spectate {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+            } => GameMode::OnlineSpectate {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+            },
+            Command::Exhibition {
+                port,
+                game,
+                board,
+                trivia,
+            } => GameMode::OnlineExhibition {
+                port,
+                game,
+                board_config: board.into(),
+                trivia_config: trivia.into(),
+            },
+            Command::Bot {
+                address,
+                join_token,
+                game,
+                script,
+                tls_cert,
+                websocket,
+            } => GameMode::Bot {
+                address,
+                join_token,
+                game,
+                script_path: script,
+                tls_cert,
+                websocket,
+            },
+            Command::Stress {
+                address,
+                join_token,
+                games,
+                game,
+                tls_cert,
+                websocket,
+            } => GameMode::Stress {
+                address,
+                join_token,
+                games,
+                game,
+                tls_cert,
+                websocket,
+            },
+            Command::Stats { file } => GameMode::Stats { stats_path: file },
+            Command::Ratings { file } => GameMode::Ratings { stats_path: file },
+            Command::Replay { file } => GameMode::Replay { replay_path: file },
+            #[cfg(feature = "gui")]
+            Command::Gui => GameMode::Gui,
+        }
+    }
+}
+
+fn rate_limit_per_minute(max_connections: usize) -> RateLimit {
+    RateLimit {
+        max_connections,
+        period: Duration::from_secs(60),
+    }
+}
+
+/// The certificate to host with, loaded from the given files or otherwise self-signed.
+fn host_certificate(cert_path: Option<PathBuf>, key_path: Option<PathBuf>) -> HostCertificate {
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => HostCertificate::Load {
+            cert_path,
+            key_path,
+        },
+        _ => HostCertificate::SelfSigned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_game_mode(args: &[&str]) -> GameMode {
+        let cli = Cli::try_parse_from(args).unwrap();
+        cli.command.unwrap().into()
+    }
+
+    #[test]
+    fn no_subcommand_parses_to_none() {
+        let cli = Cli::try_parse_from(["game-server"]).unwrap();
+
+        assert!(cli.command.is_none());
+        assert!(!cli.terse);
+        assert!(!cli.no_color);
+        assert!(!cli.debug_protocol);
+        assert!(cli.locale.is_none());
+        assert!(!cli.screen_reader);
+    }
+
+    #[test]
+    fn terse_can_be_given_before_or_after_subcommand() {
+        let before = Cli::try_parse_from(["game-server", "--terse", "local"]).unwrap();
+        let after =
+            Cli::try_parse_from(["game-server", "join", "127.0.0.1:22222", "4d2", "--terse"])
+                .unwrap();
+
+        assert!(before.terse);
+        assert!(after.terse);
+    }
+
+    #[test]
+    fn no_color_can_be_given_before_or_after_subcommand() {
+        let before = Cli::try_parse_from(["game-server", "--no-color", "local"]).unwrap();
+        let after = Cli::try_parse_from([
+            "game-server",
+            "spectate",
+            "127.0.0.1:22222",
+            "4d2",
+            "--no-color",
+        ])
+        .unwrap();
+
+        assert!(before.no_color);
+        assert!(after.no_color);
+    }
+
+    #[test]
+    fn locale_is_read_from_language_tag() {
+        let cli = Cli::try_parse_from(["game-server", "local", "--locale", "es"]).unwrap();
+        assert_eq!(cli.locale, Some(Locale::Spanish));
+
+        let result = Cli::try_parse_from(["game-server", "--locale", "xx", "local"]);
+        assert!(result.is_err());
+    }
+
+    #[cfg(feature = "tui")]
+    #[test]
+    fn tui_cannot_be_combined_with_line_based_output() {
+        let tui = Cli::try_parse_from(["game-server", "local", "--tui"]).unwrap();
+        assert!(tui.tui);
+
+        for flag in ["--terse", "--debug-protocol", "--screen-reader"] {
+            let result = Cli::try_parse_from(["game-server", "--tui", flag, "local"]);
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn debug_protocol_can_be_given_before_or_after_subcommand() {
+        let before = Cli::try_parse_from(["game-server", "--debug-protocol", "local"]).unwrap();
+        let after = Cli::try_parse_from([
+            "game-server",
+            "join",
+            "127.0.0.1:22222",
+            "4d2",
+            "--debug-protocol",
+        ])
+        .unwrap();
+
+        assert!(before.debug_protocol);
+        assert!(after.debug_protocol);
+    }
+
+    #[test]
+    fn host_uses_defaults_when_no_options_given() {
+        match parse_game_mode(&["game-server", "host"]) {
+            GameMode::OnlineHost {
+                port,
+                turn_timer,
+                spectator_config,
+                first_player,
+                mark,
+                name,
+                tls_certificate,
+                websocket,
+                rate_limit,
+                game,
+                board_config,
+                trivia_config,
+                stats_path,
+                replay_path,
+                rng_seed,
+                exporter,
+                hot_seat,
+                save_path,
+                bot_takeover,
+                wait_timeout,
+                rematch,
+                offer_games,
+                #[cfg(feature = "web")]
+                web_port,
+            } => {
+                assert_eq!(port, DEFAULT_PORT);
+                assert!(turn_timer.is_none());
+                assert!(spectator_config.is_none());
+                assert_eq!(first_player, FirstPlayer::PlayerOne);
+                assert!(mark.is_none());
+                assert!(name.is_none());
+                assert!(tls_certificate.is_none());
+                assert!(!websocket);
+                assert_eq!(rate_limit, RateLimit::default());
+                assert_eq!(game, GameKind::TicTacToe);
+                assert_eq!(board_config, BoardConfig::default());
+                assert_eq!(trivia_config.rounds, DEFAULT_ROUNDS);
+                assert_eq!(trivia_config.answer_time, DEFAULT_ANSWER_TIME);
+                assert!(stats_path.is_none());
+                assert!(replay_path.is_none());
+                assert!(rng_seed.is_none());
+                assert!(exporter.is_none());
+                assert!(!hot_seat);
+                assert!(save_path.is_none());
+                assert!(!bot_takeover);
+                assert!(wait_timeout.is_none());
+                assert!(!rematch);
+                assert!(!offer_games);
+                #[cfg(feature = "web")]
+                assert!(web_port.is_none());
+            }
+            _ => panic!("Expected the online host game mode"),
+        }
+    }
+
+    #[test]
+    fn serve_parses_turn_timer_and_board_options() {
+        let args = [
+            "game-server",
+            "serve",
+            "--port",
+            "0",
+            "--turn-time",
+            "20",
+            "--on-timeout",
+            "skip",
+            "--board-size",
+            "4",
+            "--max-connections-per-minute",
+            "100",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::Serve {
+                port,
+                turn_timer,
+                tls_certificate,
+                websocket,
+                rate_limit,
+                board_config,
+                exporter,
+                admin_socket,
+                log_level,
+                log_json,
+                ..
+            } => {
+                assert_eq!(port, 0);
+                let turn_timer = turn_timer.unwrap();
+                assert_eq!(turn_timer.duration, Duration::from_secs(20));
+                assert_eq!(turn_timer.action, TimeoutAction::SkipTurn);
+                assert!(tls_certificate.is_none());
+                assert!(!websocket);
+                assert_eq!(rate_limit.max_connections, 100);
+                assert_eq!(board_config.size, 4);
+                assert!(exporter.is_none());
+                assert!(admin_socket.is_none());
+                assert_eq!(log_level, LevelFilter::INFO);
+                assert!(!log_json);
+            }
+            _ => panic!("Expected the serve game mode"),
+        }
+    }
+
+    #[test]
+    fn serve_parses_read_timeout() {
+        let args = ["game-server", "serve", "--read-timeout", "45"];
+        match parse_game_mode(&args) {
+            GameMode::Serve { read_timeout, .. } => {
+                assert_eq!(read_timeout, Some(Duration::from_secs(45)));
+            }
+            _ => panic!("Expected the serve game mode"),
+        }
+
+        let args = ["game-server", "serve", "--read-timeout", "0"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn serve_parses_max_invalid_messages() {
+        let args = ["game-server", "serve", "--max-invalid-messages", "3"];
+        match parse_game_mode(&args) {
+            GameMode::Serve {
+                invalid_message_policy,
+                ..
+            } => {
+                assert_eq!(
+                    invalid_message_policy,
+                    InvalidMessagePolicy::Tolerate { limit: 3 }
+                );
+            }
+            _ => panic!("Expected the serve game mode"),
+        }
+        match parse_game_mode(&["game-server", "serve"]) {
+            GameMode::Serve {
+                invalid_message_policy,
+                ..
+            } => assert_eq!(invalid_message_policy, InvalidMessagePolicy::Disconnect),
+            _ => panic!("Expected the serve game mode"),
+        }
+
+        let args = ["game-server", "serve", "--max-invalid-messages", "0"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_parses_wait_timeout_in_minutes() {
+        match parse_game_mode(&["game-server", "host", "--wait-timeout", "5"]) {
+            GameMode::OnlineHost { wait_timeout, .. } => {
+                assert_eq!(wait_timeout, Some(Duration::from_secs(300)));
+            }
+            _ => panic!("Expected the online host game mode"),
+        }
+
+        let args = ["game-server", "host", "--wait-timeout", "5", "--hot-seat"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_parses_rematch() {
+        match parse_game_mode(&["game-server", "host", "--rematch"]) {
+            GameMode::OnlineHost { rematch, .. } => assert!(rematch),
+            _ => panic!("Expected the online host game mode"),
+        }
+
+        let args = ["game-server", "host", "--rematch", "--hot-seat"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_parses_seed() {
+        match parse_game_mode(&["game-server", "host", "--seed", "1234"]) {
+            GameMode::OnlineHost { rng_seed, .. } => assert_eq!(rng_seed, Some(1234)),
+            _ => panic!("Expected the online host game mode"),
+        }
+
+        let args = ["game-server", "host", "--seed", "-1"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_parses_offer_games() {
+        match parse_game_mode(&["game-server", "host", "--offer-games"]) {
+            GameMode::OnlineHost { offer_games, .. } => assert!(offer_games),
+            _ => panic!("Expected the online host game mode"),
+        }
+
+        let args = ["game-server", "host", "--offer-games", "--hot-seat"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[cfg(feature = "web")]
+    #[test]
+    fn host_parses_web_port() {
+        match parse_game_mode(&["game-server", "host", "--web-port", "8080"]) {
+            GameMode::OnlineHost { web_port, .. } => assert_eq!(web_port, Some(8080)),
+            _ => panic!("Expected the online host game mode"),
+        }
+
+        let args = ["game-server", "host", "--web-port", "8080", "--hot-seat"];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_and_serve_parse_bot_takeover() {
+        match parse_game_mode(&["game-server", "host", "--bot-takeover"]) {
+            GameMode::OnlineHost { bot_takeover, .. } => assert!(bot_takeover),
+            _ => panic!("Expected the online host game mode"),
+        }
+        match parse_game_mode(&["game-server", "serve", "--bot-takeover"]) {
+            GameMode::Serve { bot_takeover, .. } => assert!(bot_takeover),
+            _ => panic!("Expected the serve game mode"),
+        }
+
+        let args = [
+            "game-server",
+            "serve",
+            "--bot-takeover",
+            "--tournament",
+            "4",
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn serve_parses_tournament_with_stats() {
+        let args = [
+            "game-server",
+            "serve",
+            "--tournament",
+            "8",
+            "--stats",
+            "stats.db",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::Serve {
+                tournament,
+                stats_path,
+                ..
+            } => {
+                assert_eq!(tournament, Some(8));
+                assert_eq!(stats_path, Some(PathBuf::from("stats.db")));
+            }
+            _ => panic!("Expected the serve game mode"),
+        }
+    }
+
+    #[test]
+    fn tournament_of_one_player_rejected() {
+        let result = Cli::try_parse_from(["game-server", "serve", "--tournament", "1"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn serve_parses_export_options() {
+        let args = [
+            "game-server",
+            "serve",
+            "--export-url",
+            "http://127.0.0.1:8080/results",
+            "--export-format",
+            "csv",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::Serve { exporter, .. } => assert_eq!(
+                exporter,
+                Some(Exporter {
+                    destination: ExportDestination::Http(String::from(
+                        "http://127.0.0.1:8080/results"
+                    )),
+                    format: ExportFormat::Csv,
+                })
+            ),
+            _ => panic!("Expected the serve game mode"),
+        }
+    }
+
+    #[test]
+    fn serve_parses_log_options() {
+        let args = ["game-server", "serve", "--log-level", "debug", "--log-json"];
+        match parse_game_mode(&args) {
+            GameMode::Serve {
+                log_level,
+                log_json,
+                ..
+            } => {
+                assert_eq!(log_level, LevelFilter::DEBUG);
+                assert!(log_json);
+            }
+            _ => panic!("Expected the serve game mode"),
+        }
+    }
+
+    #[test]
+    fn host_rejects_exporting_to_directory_and_url() {
+        let result = Cli::try_parse_from([
+            "game-server",
+            "host",
+            "--export-dir",
+            "results",
+            "--export-url",
+            "http://127.0.0.1:8080/results",
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn host_parses_turn_timer_and_spectator_options() {
+        let args = [
+            "game-server",
+            "host",
+            "--port",
+            "0",
+            "--turn-time",
+            "30",
+            "--on-timeout",
+            "skip",
+            "--max-spectators",
+            "5",
+            "--notify-players",
+        ];
+
+        match parse_game_mode(&args) {
+            GameMode::OnlineHost {
+                port,
+                turn_timer: Some(turn_timer),
+                spectator_config: Some(spectator_config),
+                ..
+            } => {
+                assert_eq!(port, 0);
+                assert_eq!(turn_timer.duration, Duration::from_secs(30));
+                assert_eq!(turn_timer.action, TimeoutAction::SkipTurn);
+                assert_eq!(spectator_config.max_spectators, 5);
+                assert!(spectator_config.notify_players);
+            }
+            _ => panic!("Expected the online host game mode with all options set"),
+        }
+    }
+
+    #[test]
+    fn host_rejects_on_timeout_without_turn_time() {
+        let result = Cli::try_parse_from(["game-server", "host", "--on-timeout", "skip"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn host_gives_joining_player_opponent_turn_time() {
+        let args = [
+            "game-server",
+            "host",
+            "--turn-time",
+            "60",
+            "--opponent-turn-time",
+            "180",
+        ];
+
+        match parse_game_mode(&args) {
+            GameMode::OnlineHost {
+                turn_timer: Some(turn_timer),
+                ..
+            } => {
+                assert_eq!(
+                    turn_timer.duration_for(server::PLAYER_ONE_ID),
+                    Duration::from_secs(60)
+                );
+                assert_eq!(
+                    turn_timer.duration_for(server::PLAYER_TWO_ID),
+                    Duration::from_secs(180)
+                );
+            }
+            _ => panic!("Expected the online host game mode with a turn timer"),
+        }
+    }
+
+    #[test]
+    fn host_rejects_opponent_turn_time_without_turn_time() {
+        let result = Cli::try_parse_from(["game-server", "host", "--opponent-turn-time", "60"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn host_rejects_spectators_for_hot_seat_game() {
+        let result =
+            Cli::try_parse_from(["game-server", "host", "--hot-seat", "--max-spectators", "2"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn host_parses_first_move() {
+        for (first_move, expected) in [
+            ("joiner", FirstPlayer::PlayerTwo),
+            ("random", FirstPlayer::Random),
+        ] {
+            match parse_game_mode(&["game-server", "host", "--first-move", first_move]) {
+                GameMode::OnlineHost { first_player, .. } => assert_eq!(first_player, expected),
+                _ => panic!("Expected the online host game mode"),
+            }
+        }
+    }
+
+    #[test]
+    fn host_rejects_saving_hot_seat_game() {
+        let result =
+            Cli::try_parse_from(["game-server", "host", "--hot-seat", "--save", "game.json"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn resume_parses_save_file() {
+        match parse_game_mode(&["game-server", "resume", "game.json", "--port", "0"]) {
+            GameMode::Resume {
+                save_path, port, ..
+            } => {
+                assert_eq!(save_path, PathBuf::from("game.json"));
+                assert_eq!(port, 0);
+            }
+            _ => panic!("Expected resume game mode"),
+        }
+    }
+
+    #[test]
+    fn host_rejects_zero_turn_time() {
+        let result = Cli::try_parse_from(["game-server", "host", "--turn-time", "0"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn join_parses_address_and_join_token() {
+        match parse_game_mode(&["game-server", "join", "1.2.3.4:22222", "4d2"]) {
+            GameMode::OnlineJoin {
+                address,
+                join_token,
+                game,
+                tls_cert,
+                websocket,
+                mark,
+                name,
+                room,
+                connect_attempts,
+            } => {
+                assert_eq!(address, "1.2.3.4:22222");
+                assert!(game.is_none());
+                assert_eq!(join_token.to_string(), "00000000000004d2");
+                assert!(tls_cert.is_none());
+                assert!(!websocket);
+                assert!(mark.is_none());
+                assert!(name.is_none());
+                assert_eq!(room, RoomChoice::Any);
+                assert_eq!(connect_attempts, DEFAULT_CONNECT_ATTEMPTS);
+            }
+            _ => panic!("Expected the online join game mode"),
+        }
+    }
+
+    #[test]
+    fn join_parses_connect_attempts() {
+        let args = [
+            "game-server",
+            "join",
+            "1.2.3.4:22222",
+            "4d2",
+            "--connect-attempts",
+            "5",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::OnlineJoin {
+                connect_attempts, ..
+            } => assert_eq!(connect_attempts, 5),
+            _ => panic!("Expected the online join game mode"),
+        }
+
+        let args = [
+            "game-server",
+            "join",
+            "1.2.3.4:22222",
+            "4d2",
+            "--connect-attempts",
+            "0",
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn host_and_join_parse_mark() {
+        match parse_game_mode(&["game-server", "host", "--mark", "★"]) {
+            GameMode::OnlineHost { mark, .. } => assert_eq!(mark, Some("★".parse().unwrap())),
+            _ => panic!("Expected the online host game mode"),
+        }
+        match parse_game_mode(&["game-server", "join", "1.2.3.4:22222", "4d2", "--mark", "☆"]) {
+            GameMode::OnlineJoin { mark, .. } => assert_eq!(mark, Some("☆".parse().unwrap())),
+            _ => panic!("Expected the online join game mode"),
+        }
+    }
+
+    #[test]
+    fn host_name_requires_stats_to_rate_players_with() {
+        let result = Cli::try_parse_from(["game-server", "host", "--name", "Ada"]);
+        assert!(result.is_err());
+
+        let args = [
+            "game-server",
+            "host",
+            "--name",
+            " Ada ",
+            "--stats",
+            "stats.db",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::OnlineHost { name, .. } => assert_eq!(name, Some("Ada".parse().unwrap())),
+            _ => panic!("Expected the online host game mode"),
+        }
+    }
+
+    #[test]
+    fn join_rejects_blank_name() {
+        let args = ["game-server", "join", "1.2.3.4:22222", "4d2", "--name", " "];
+        let result = Cli::try_parse_from(args);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn mark_wider_than_single_character_rejected() {
+        for mark in ["XO", "🐱", "3"] {
+            let result = Cli::try_parse_from(["game-server", "host", "--mark", mark]);
+
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn join_parses_room_to_be_seated_in() {
+        let join = |extra: &[&str]| {
+            let args = [
+                &["game-server", "join", "1.2.3.4:22222", "0123456789abcdef"],
+                extra,
+            ];
+            match parse_game_mode(&args.concat()) {
+                GameMode::OnlineJoin { room, .. } => room,
+                _ => panic!("Expected the online join game mode"),
+            }
+        };
+
+        assert_eq!(
+            join(&["--room", "3f2a"]),
+            RoomChoice::Join("3F2A".parse().unwrap())
+        );
+        assert_eq!(join(&["--new-room"]), RoomChoice::New);
+        assert_eq!(
+            join(&["--private-room", "beef"]),
+            RoomChoice::Private("BEEF".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn join_rejects_both_room_and_new_room() {
+        let result = Cli::try_parse_from([
+            "game-server",
+            "join",
+            "1.2.3.4:22222",
+            "0123456789abcdef",
+            "--room",
+            "3f2a",
+            "--new-room",
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn browse_parses_name_shown_to_others() {
+        let args = [
+            "game-server",
+            "browse",
+            "1.2.3.4:22222",
+            "0123456789abcdef",
+            "--name",
+            "Ada",
+        ];
+        match parse_game_mode(&args) {
+            GameMode::Browse { address, name, .. } => {
+                assert_eq!(address, "1.2.3.4:22222");
+                assert_eq!(name, Some("Ada".parse().unwrap()));
+            }
+            _ => panic!("Expected the browse game mode"),
+        }
+    }
+
+    #[test]
+    fn join_rejects_invalid_join_token() {
+        let result = Cli::try_parse_from(["game-server", "join", "1.2.3.4:22222", "not-a-token"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn host_generates_self_signed_certificate_when_none_given() {
+        match parse_game_mode(&["game-server", "host", "--tls"]) {
+            GameMode::OnlineHost {
+                tls_certificate: Some(HostCertificate::SelfSigned),
+                ..
+            } => {}
+            _ => panic!("Expected the online host game mode with a self-signed certificate"),
+        }
+    }
+
+    #[test]
+    fn local_parses_board_options() {
+        match parse_game_mode(&[
+            "game-server",
+            "local",
+            "--board-size",
+            "5",
+            "--win-length",
+            "4",
+        ]) {
+            GameMode::Local { board_config, .. } => assert_eq!(
+                board_config,
+                BoardConfig {
+                    size: 5,
+                    win_length: 4,
+                    ..BoardConfig::DEFAULT
+                }
+            ),
+            _ => panic!("Expected the local game mode"),
+        }
+    }
+
+    #[test]
+    fn join_and_host_parse_game() {
+        match parse_game_mode(&["game-server", "host", "--game", "checkers"]) {
+            GameMode::OnlineHost { game, .. } => assert_eq!(game, GameKind::Checkers),
+            _ => panic!("Expected the online host game mode"),
+        }
+        match parse_game_mode(&[
+            "game-server",
+            "join",
+            "1.2.3.4:22222",
+            "4d2",
+            "--game",
+            "checkers",
+        ]) {
+            GameMode::OnlineJoin { game, .. } => assert_eq!(game, Some(GameKind::Checkers)),
+            _ => panic!("Expected the online join game mode"),
+        }
+    }
+
+    #[test]
+    fn host_parses_rule_variant() {
+        match parse_game_mode(&["game-server", "host", "--variant", "misere"]) {
+            GameMode::OnlineHost { board_config, .. } => {
+                assert_eq!(board_config.variant, Variant::Misere)
+            }
+            _ => panic!("Expected the online host game mode"),
+        }
+    }
+
+    #[test]
+    fn win_length_defaults_to_board_size() {
+        match parse_game_mode(&["game-server", "host", "--board-size", "4"]) {
+            GameMode::OnlineHost { board_config, .. } => assert_eq!(board_config.win_length, 4),
+            _ => panic!("Expected the online host game mode"),
+        }
+    }
+
+    #[test]
+    fn local_parses_trivia_options() {
+        match parse_game_mode(&[
+            "game-server",
+            "local",
+            "--game",
+            "trivia",
+            "--rounds",
+            "3",
+            "--answer-time",
+            "10",
+        ]) {
+            GameMode::Local { trivia_config, .. } => {
+                assert_eq!(trivia_config.rounds, 3);
+                assert_eq!(trivia_config.answer_time, Duration::from_secs(10));
+                assert!(trivia_config.questions.is_none());
+            }
+            _ => panic!("Expected the local game mode"),
+        }
+    }
+
+    #[test]
+    fn missing_question_bank_rejected() {
+        let result = Cli::try_parse_from(["game-server", "host", "--questions", "missing.toml"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn board_size_outside_supported_range_rejected() {
+        let result = Cli::try_parse_from(["game-server", "local", "--board-size", "11"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn win_length_longer_than_board_size_rejected() {
+        let args = [
+            "game-server",
+            "host",
+            "--board-size",
+            "3",
+            "--win-length",
+            "4",
+        ];
+        let cli = Cli::try_parse_from(args).unwrap();
+
+        assert!(cli.validate().is_err());
+    }
+
+    #[test]
+    fn host_rejects_tls_certificate_without_key() {
+        let args = ["game-server", "host", "--tls", "--tls-cert", "cert.pem"];
+
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+}
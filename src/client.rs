@@ -1,15 +1,172 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
 use std::io;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::Receiver;
+use tokio::time::{self, Instant};
 
-use crate::connection::{Connection, ReadError, WriteError};
-use crate::game::{Game, GameClient, GameClientEvent};
-use crate::server;
-use crate::tic_tac_toe::TicTacToeClient;
+use crate::connection::{Connection, ProtocolTrace, ReadError, WriteError};
+use crate::game::{
+    self, AnyGameClient, ClientContext, ClientKind, ErasedEvent, GameClientError, GameClientEvent,
+    GameDescriptor,
+};
+use crate::lobby::{self, BracketMatch, BracketSlot, Session, RECONNECTION_GRACE_PERIOD};
+use crate::locale::Locale;
+use crate::server::{self, RematchResponse, ShutdownReason, ThinkTime};
+use crate::stats::RatingChange;
 
-pub trait ClientType {}
+pub use frontend::{Frontend, TerminalFrontend};
+pub use input::UserInput;
+pub use output::{Paint, UserOutput};
+
+pub(crate) mod command;
+mod frontend;
+mod input;
+mod output;
+
+/// The code the process exits with when the client could not carry on with the game itself, as
+/// opposed to the server ending it.
+pub const UNRECOVERABLE_EXIT_CODE: i32 = 7;
+
+/// How much is written to the user while playing.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum OutputStyle {
+    #[default]
+    Standard,
+    /// Single line updates with no decorative text, for slow connections and small terminals.
+    Terse,
+}
+
+/// Sets up clients whose channel for the game's events is sized other than by default, for games
+/// that send many events at once.
+#[derive(Debug, Copy, Clone)]
+pub struct ClientBuilder {
+    game_channel_capacity: usize,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        ClientBuilder {
+            game_channel_capacity: 10,
+        }
+    }
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder::default()
+    }
+
+    /// How many events the game may send before waiting for the client to handle them, which is
+    /// at least one.
+    pub fn game_channel_capacity(mut self, game_channel_capacity: usize) -> Self {
+        self.game_channel_capacity = game_channel_capacity.max(1);
+        self
+    }
+
+    /// Sets up a client played through the given frontend, as `Client::new_local` does through
+    /// a terminal.
+    pub fn local<'a>(
+        self,
+        connection: Connection,
+        frontend: impl Frontend + 'a,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let kind = ClientKind::Local;
+        Client::new(self, connection, None, Arc::new(frontend), game, kind)
+    }
+
+    /// Sets up a client played through the given frontend, as `Client::new_online` does through
+    /// a terminal.
+    pub fn online<'a>(
+        self,
+        connection: Connection,
+        session: Session,
+        id: u8,
+        frontend: impl Frontend + 'a,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let kind = ClientKind::Online { id };
+        Client::new(
+            self,
+            connection,
+            Some(session),
+            Arc::new(frontend),
+            game,
+            kind,
+        )
+    }
+
+    /// Sets up a client played through the given frontend for the seats granted on joining a
+    /// game, playing both in turn when the host offers hot-seat play.
+    pub fn joined<'a>(
+        self,
+        connection: Connection,
+        session: Session,
+        frontend: impl Frontend + 'a,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        if session.seats == server::BOTH_SEATS {
+            return self.local(connection, frontend, game);
+        }
+
+        // A dedicated server seats whoever joins first as player one
+        let id = match session.seats.contains(server::PLAYER_ONE_ID) {
+            true => server::PLAYER_ONE_ID,
+            false => server::PLAYER_TWO_ID,
+        };
+        self.online(connection, session, id, frontend, game)
+    }
+
+    /// Sets up a client shown through the given frontend, as `Client::new_spectator` does through
+    /// a terminal. Nothing is read from the frontend, as spectators make no moves.
+    pub fn spectator<'a>(
+        self,
+        connection: Connection,
+        frontend: impl Frontend + 'a,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let kind = ClientKind::Spectator;
+        Client::new(self, connection, None, Arc::new(frontend), game, kind)
+    }
+
+    /// Sets up a client shown through the given frontend, as `Client::new_bot` does through a
+    /// terminal. Nothing is read from the frontend, as the bot makes its own moves.
+    pub fn bot<'a>(
+        self,
+        connection: Connection,
+        session: Session,
+        id: u8,
+        move_delay: Duration,
+        frontend: impl Frontend + 'a,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let kind = ClientKind::Bot { id, move_delay };
+        Client::new(
+            self,
+            connection,
+            Some(session),
+            Arc::new(frontend),
+            game,
+            kind,
+        )
+    }
+}
+
+/// Plays through a terminal reading from the input and writing to the output.
+fn terminal<O: io::Write + Send>(input: impl Into<UserInput>, output: O) -> TerminalFrontend<O> {
+    TerminalFrontend::new(input, Arc::new(Mutex::new(output)))
+}
+
+pub trait ClientType {
+    /// The ID of the player this client makes moves for, if it is only one of them.
+    fn get_own_player_id(&self) -> Option<u8> {
+        None
+    }
+}
 
 pub struct LocalClient {}
 
@@ -19,82 +176,208 @@ pub struct OnlineClient {
     pub(crate) id: u8,
 }
 
-impl ClientType for OnlineClient {}
+impl ClientType for OnlineClient {
+    fn get_own_player_id(&self) -> Option<u8> {
+        Some(self.id)
+    }
+}
+
+pub struct SpectatorClient {}
+
+impl ClientType for SpectatorClient {}
+
+pub struct BotClient {
+    pub(crate) id: u8,
+    pub(crate) move_delay: Duration,
+}
+
+impl ClientType for BotClient {
+    fn get_own_player_id(&self) -> Option<u8> {
+        Some(self.id)
+    }
+}
+
+impl BotClient {
+    /// Waits before the bot moves, giving anyone watching a chance to follow the game.
+    pub(crate) async fn wait_to_move(&self) {
+        time::sleep(self.move_delay).await;
+    }
+}
 
-pub struct Client<'a, O>
-where
-    O: io::Write + Send + Sync + 'a,
-{
+pub struct Client<'a> {
     running: bool,
     server_connection: Connection,
-    user_output: Arc<Mutex<O>>,
-    game: Box<dyn GameClient + 'a>,
+    session: Option<Session>,
+    kind: ClientKind,
+    /// Shared with the game being played, and handed on to the next should there be a rematch.
+    frontend: Arc<dyn Frontend + 'a>,
+    user_output: UserOutput<dyn Frontend + 'a>,
+    game: Box<dyn AnyGameClient + 'a>,
+    /// The ID of the game being played, as in `game::GAMES`.
+    game_id: &'static str,
     game_receiver: Receiver<GameClientEvent>,
+    /// How many events the game may send before waiting, kept for the game played next.
+    game_channel_capacity: usize,
+    output_style: OutputStyle,
+    color: bool,
+    locale: Locale,
+    screen_reader: bool,
+    game_over: bool,
+    /// The code for the process to exit with, which is only other than zero once the game has
+    /// ended early.
+    exit_code: i32,
+    protocol_trace: Option<ProtocolTrace>,
+    /// Events read from the server while the game was waiting on its user, yet to be handled.
+    pending_events: VecDeque<Result<server::OutgoingEvent, ReadError>>,
 }
 
-impl<'a, O> Client<'a, O>
-where
-    O: io::Write + Send + Sync + 'a,
-{
-    pub fn new_local<I: io::BufRead + Send + Sync + 'a>(
+impl<'a> Client<'a> {
+    pub fn new_local<O: io::Write + Send + 'a>(
         connection: Connection,
-        input: I,
+        input: impl Into<UserInput>,
         output: O,
-        game: Game,
-    ) -> Client<'a, O> {
-        let (game_sender, game_receiver) = mpsc::channel(10);
-        let output = Arc::new(Mutex::new(output));
-        let output_clone = Arc::clone(&output);
-
-        let game = match game {
-            Game::TicTacToe => Box::new(TicTacToeClient::new(
-                input,
-                output_clone,
-                game_sender,
-                LocalClient {},
-            )),
-        };
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        ClientBuilder::new().local(connection, terminal(input, output), game)
+    }
 
-        Client {
-            running: true,
-            server_connection: connection,
-            user_output: output,
-            game,
-            game_receiver,
-        }
+    pub fn new_online<O: io::Write + Send + 'a>(
+        connection: Connection,
+        session: Session,
+        id: u8,
+        input: impl Into<UserInput>,
+        output: O,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let frontend = terminal(input, output);
+        ClientBuilder::new().online(connection, session, id, frontend, game)
+    }
+
+    pub fn new_spectator<O: io::Write + Send + 'a>(
+        connection: Connection,
+        output: O,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let frontend = terminal(UserInput::empty(), output);
+        ClientBuilder::new().spectator(connection, frontend, game)
     }
 
-    pub fn new_online<I: io::BufRead + Send + Sync + 'a>(
+    pub fn new_bot<O: io::Write + Send + 'a>(
         connection: Connection,
+        session: Session,
         id: u8,
-        input: I,
+        move_delay: Duration,
         output: O,
-        game: Game,
-    ) -> Client<'a, O> {
-        let (game_sender, game_receiver) = mpsc::channel(10);
-        let output = Arc::new(Mutex::new(output));
-        let output_clone = Arc::clone(&output);
-
-        let game = match game {
-            Game::TicTacToe => Box::new(TicTacToeClient::new(
-                input,
-                output_clone,
-                game_sender,
-                OnlineClient { id },
-            )),
-        };
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let frontend = terminal(UserInput::empty(), output);
+        ClientBuilder::new().bot(connection, session, id, move_delay, frontend, game)
+    }
+
+    /// Creates a bot playing the seat of a player who has left the game, over a connection made
+    /// by the server itself. There is no session to reconnect with, as the connection never leaves
+    /// the process.
+    pub(crate) fn new_stand_in<O: io::Write + Send + 'a>(
+        connection: Connection,
+        id: u8,
+        move_delay: Duration,
+        output: O,
+        game: &dyn GameDescriptor,
+    ) -> Client<'a> {
+        let kind = ClientKind::Bot { id, move_delay };
+        let frontend = Arc::new(terminal(UserInput::empty(), output));
+        Client::new(ClientBuilder::new(), connection, None, frontend, game, kind)
+    }
+
+    fn new(
+        builder: ClientBuilder,
+        connection: Connection,
+        session: Option<Session>,
+        frontend: Arc<dyn Frontend + 'a>,
+        game: &dyn GameDescriptor,
+        kind: ClientKind,
+    ) -> Client<'a> {
+        let (game_sender, game_receiver) = mpsc::channel(builder.game_channel_capacity);
+
+        let game_id = game.id();
+        let game = game.make_client(ClientContext {
+            frontend: Arc::clone(&frontend),
+            channel: game_sender,
+            kind,
+        });
 
         Client {
             running: true,
             server_connection: connection,
-            user_output: output,
+            session,
+            kind,
+            frontend: Arc::clone(&frontend),
+            user_output: UserOutput::new(frontend),
             game,
+            game_id,
             game_receiver,
+            game_channel_capacity: builder.game_channel_capacity,
+            output_style: OutputStyle::default(),
+            color: false,
+            locale: Locale::default(),
+            screen_reader: false,
+            game_over: false,
+            exit_code: 0,
+            protocol_trace: None,
+            pending_events: VecDeque::new(),
         }
     }
 
+    pub fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.output_style = output_style;
+        self.game.set_output_style(output_style);
+    }
+
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+        self.user_output.set_color(color);
+        self.game.set_color(color);
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+        self.game.set_locale(locale);
+    }
+
+    pub fn set_screen_reader(&mut self, screen_reader: bool) {
+        self.screen_reader = screen_reader;
+        self.game.set_screen_reader(screen_reader);
+    }
+
+    /// Whether the game has been played to its end, rather than the client stopping early.
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// The code for the process to exit with once the game has been played, telling whoever
+    /// started it why the game ended early, if it did.
+    pub fn exit_code(&self) -> i32 {
+        self.exit_code
+    }
+
+    /// Records every event exchanged with the server from now on, including after reconnecting.
+    pub fn set_protocol_trace(&mut self, trace: ProtocolTrace) {
+        self.server_connection.set_trace(trace.clone());
+        self.protocol_trace = Some(trace);
+    }
+
     async fn get_next_incoming_event(&mut self) -> Result<IncomingEvent, ReadError> {
+        // Game events are checked first so that the game ending is noticed before the server
+        // closing the connection, which would otherwise be treated as the connection being lost
+        if let Ok(event) = self.game_receiver.try_recv() {
+            return Ok(IncomingEvent::Game(event));
+        }
+        if let Some(result) = self.pending_events.pop_front() {
+            return result.map(IncomingEvent::Server);
+        }
+
         tokio::select! {
+            biased;
             result = self.game_receiver.recv() => Ok(IncomingEvent::Game(result.unwrap())),
             result = self.server_connection.read_event() => result.map(IncomingEvent::Server),
         }
@@ -102,12 +385,28 @@ where
 
     pub async fn play_game(&mut self) {
         while self.running {
-            match self.get_next_incoming_event().await {
+            let frontend = Arc::clone(&self.frontend);
+            let next_event = tokio::select! {
+                biased;
+                next_event = self.get_next_incoming_event() => next_event,
+                // The user's input is closed on leaving, which the game only otherwise notices
+                // once it next asks for their move
+                () = frontend.closed() => {
+                    let error = io::Error::from(io::ErrorKind::UnexpectedEof);
+                    self.handle_failure(error.into()).await;
+                    continue;
+                }
+            };
+
+            match next_event {
                 Ok(event) => {
-                    if (self.handle_event(event).await).is_err() {
-                        self.handle_shutdown().await
+                    if let Err(error) = self.handle_event(event).await {
+                        self.handle_failure(error).await
                     }
                 }
+                // Once the game is over the server closing the connection is expected
+                Err(ReadError::Read(_)) if self.game_over => self.shutdown().await,
+                Err(ReadError::Read(_)) => self.handle_connection_lost().await,
                 Err(_) => self.handle_shutdown().await,
             }
         }
@@ -116,31 +415,386 @@ where
     pub async fn handle_event(&mut self, event: IncomingEvent) -> Result<(), Error> {
         match event {
             IncomingEvent::Server(server_event) => match server_event {
-                server::OutgoingEvent::ErrorOccurred(error) => self.handle_error(error),
-                server::OutgoingEvent::GameStarted => self.game.handle_game_started_event().await,
-                server::OutgoingEvent::Shutdown => self.handle_shutdown().await,
-                server::OutgoingEvent::Game { event } => self.game.handle_event(event).await?,
+                server::OutgoingEvent::ErrorOccurred(error) => {
+                    self.handle_error(error)?;
+                    // Being removed from the game is final, so there is no point reconnecting
+                    if let server::Error::Kicked = error {
+                        self.shutdown().await
+                    }
+                }
+                server::OutgoingEvent::GameStarted {
+                    game,
+                    marks,
+                    config,
+                } => {
+                    // The host may play another game than the one expected, if players chose it
+                    if game != self.game_id {
+                        self.switch_game(&game)?;
+                    }
+                    self.game.set_player_marks(marks);
+                    self.game.handle_game_started_event(config).await?
+                }
+                server::OutgoingEvent::Shutdown { reason } => {
+                    self.handle_server_shutdown(reason).await
+                }
+                server::OutgoingEvent::Game { event } => self.handle_game_event(event).await?,
+                server::OutgoingEvent::GameSummary {
+                    think_times,
+                    ratings,
+                    rematch_games,
+                } => {
+                    self.handle_game_summary(think_times, ratings)?;
+                    if rematch_games.is_empty() {
+                        self.shutdown().await
+                    } else {
+                        self.answer_rematch(&rematch_games).await?
+                    }
+                }
+                server::OutgoingEvent::RematchDeclined => {
+                    self.write_message("rematch-declined", &[])?;
+                    self.shutdown().await
+                }
+                server::OutgoingEvent::NextGame { game } => self.start_next_game(&game)?,
+                server::OutgoingEvent::Suspended => {
+                    self.write_message("game-suspended", &[])?;
+                    self.shutdown().await
+                }
+                server::OutgoingEvent::TournamentStandings { rounds } => {
+                    self.handle_tournament_standings(rounds)?
+                }
+                server::OutgoingEvent::RejectedMovesExceeded { player_id } => {
+                    let player = self.game.get_player_name(player_id);
+                    self.write_message("rejected-moves-exceeded", &[("player", &player)])?
+                }
+                status_event @ (server::OutgoingEvent::OpponentDisconnected { .. }
+                | server::OutgoingEvent::OpponentReconnected
+                | server::OutgoingEvent::OpponentReplaced
+                | server::OutgoingEvent::SpectatorJoined { .. }
+                | server::OutgoingEvent::SpectatorLeft { .. }
+                | server::OutgoingEvent::Notice { .. }
+                | server::OutgoingEvent::RejectedMovesWarning { .. }) => {
+                    if let Some(message) =
+                        status_message(&status_event, self.output_style, self.locale)
+                    {
+                        self.user_output.write_line(message)?
+                    }
+                }
             },
             IncomingEvent::Game(game_event) => match game_event {
                 GameClientEvent::DispatchToServer { event } => {
                     self.server_connection.write_event(&event).await?
                 }
-                GameClientEvent::GameOver => self.shutdown().await,
+                // The client keeps running until the server sends its summary of the game
+                GameClientEvent::GameOver => self.game_over = true,
+                GameClientEvent::Quit => {
+                    self.write_message("game-quit", &[])?;
+                    self.shutdown().await
+                }
             },
         };
 
         Ok(())
     }
 
-    fn handle_error(&self, error: server::Error) {
-        writeln!(&mut self.user_output.lock().unwrap(), "Error: {}", error).unwrap();
+    /// Passes the event to the game, carrying on reading from the server while the game waits on
+    /// its user so that they hear of their opponent disconnecting and the like straight away.
+    ///
+    /// Events for the game are held until it has finished with this one. Should the server shut
+    /// the game down or the connection be lost, the wait for the user is abandoned instead.
+    async fn handle_game_event(&mut self, event: ErasedEvent) -> Result<(), Error> {
+        let handled = self.game.handle_event(event);
+        tokio::pin!(handled);
+
+        loop {
+            let result = tokio::select! {
+                biased;
+                result = &mut handled => return Ok(result?),
+                result = self.server_connection.read_event() => result,
+            };
+
+            match result {
+                Ok(event) => match status_message(&event, self.output_style, self.locale) {
+                    Some(message) => self.user_output.write_line(message)?,
+                    None => {
+                        let ends_game = ends_game(&event);
+                        self.pending_events.push_back(Ok(event));
+                        if ends_game {
+                            return Ok(());
+                        }
+                    }
+                },
+                Err(error) => {
+                    self.pending_events.push_back(Err(error));
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Asks the user whether to play again, letting player one choose another of the games
+    /// offered, and sends their answer to the server. Spectators carry on watching in case the
+    /// players agree to a rematch, while bots only ever play the one game.
+    async fn answer_rematch(&mut self, games: &[String]) -> Result<(), Error> {
+        let player_id = match self.kind {
+            ClientKind::Online { id } => id,
+            ClientKind::Local => server::PLAYER_ONE_ID,
+            ClientKind::Spectator => return Ok(()),
+            ClientKind::Bot { id, .. } => {
+                let response = RematchResponse::Declined { player_id: id };
+                self.server_connection
+                    .write_event(&game::serialize_event(response))
+                    .await?;
+                self.shutdown().await;
+                return Ok(());
+            }
+        };
+        let other_games = match player_id {
+            server::PLAYER_ONE_ID => &games[1..],
+            _ => &[],
+        };
+
+        let response = loop {
+            if other_games.is_empty() {
+                self.write_message("rematch-prompt", &[])?;
+            } else {
+                let other_games = other_games.join(", ");
+                self.write_message("rematch-prompt-choose", &[("games", &other_games)])?;
+            }
+
+            // The user closing their input is taken as them having had enough
+            let answer = match self.read_user_line().await {
+                Some(Ok(answer)) => answer,
+                Some(Err(_)) => String::from("n"),
+                None => return Ok(()),
+            };
+            match parse_rematch_answer(&answer, player_id, other_games) {
+                Some(response) => break response,
+                None => self.write_message("invalid-option", &[])?,
+            }
+        };
+
+        let accepted = matches!(response, RematchResponse::Accepted { .. });
+        self.server_connection
+            .write_event(&game::serialize_event(response))
+            .await?;
+        if accepted {
+            self.write_message("rematch-waiting", &[])?;
+        } else {
+            self.shutdown().await;
+        }
+
+        Ok(())
+    }
+
+    /// Waits for the user to enter a line, carrying on reading from the server in the meantime as
+    /// `handle_game_event` does.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(io::Result<String>)` containing what the user entered.
+    /// - `None` if the wait was abandoned, as the server ended the game or the connection was
+    ///   lost.
+    async fn read_user_line(&mut self) -> Option<io::Result<String>> {
+        loop {
+            let result = tokio::select! {
+                biased;
+                line = self.frontend.read_line() => return Some(line),
+                result = self.server_connection.read_event() => result,
+            };
+
+            match result {
+                Ok(event) => match status_message(&event, self.output_style, self.locale) {
+                    Some(message) => {
+                        if let Err(error) = self.user_output.write_line(message) {
+                            return Some(Err(error));
+                        }
+                    }
+                    None => {
+                        let ends_game = ends_game(&event);
+                        self.pending_events.push_back(Ok(event));
+                        if ends_game {
+                            return None;
+                        }
+                    }
+                },
+                Err(error) => {
+                    self.pending_events.push_back(Err(error));
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Swaps the game that was played for the one the players agreed to play next, handing it
+    /// the user's input and output along with the settings chosen for the last.
+    fn start_next_game(&mut self, game_id: &str) -> Result<(), Error> {
+        self.switch_game(game_id)?;
+        self.game_over = false;
+
+        Ok(self.write_message("rematch-starting", &[("game", &game_id)])?)
+    }
+
+    /// Replaces the game client with one for the game with the given ID, carrying over the
+    /// settings it was given.
+    fn switch_game(&mut self, game_id: &str) -> Result<(), Error> {
+        let game = game::find_game(game_id).ok_or_else(|| Error::UnknownGame(game_id.into()))?;
+        let (game_sender, game_receiver) = mpsc::channel(self.game_channel_capacity);
+
+        self.game = game.make_client(ClientContext {
+            frontend: Arc::clone(&self.frontend),
+            channel: game_sender,
+            kind: self.kind,
+        });
+        self.game_receiver = game_receiver;
+        self.game.set_output_style(self.output_style);
+        self.game.set_color(self.color);
+        self.game.set_locale(self.locale);
+        self.game.set_screen_reader(self.screen_reader);
+        self.game_id = game.id();
+
+        Ok(())
+    }
+
+    /// Writes the message with the given key, worded for the output style in use.
+    fn write_message(&self, key: &str, args: &[(&str, &dyn Display)]) -> Result<(), io::Error> {
+        self.user_output
+            .write_line(self.locale.styled_message(key, self.output_style, args))
+    }
+
+    fn handle_game_summary(
+        &self,
+        think_times: BTreeMap<u8, ThinkTime>,
+        ratings: BTreeMap<u8, RatingChange>,
+    ) -> Result<(), io::Error> {
+        for (player_id, think_time) in think_times {
+            let name = self.game.get_player_name(player_id);
+            let average = format!("{:.1}", think_time.average().as_secs_f64());
+            let longest = format!("{:.1}", think_time.longest.as_secs_f64());
+
+            self.write_message(
+                "think-time-summary",
+                &[
+                    ("player", &name),
+                    ("average", &average),
+                    ("longest", &longest),
+                ],
+            )?;
+        }
+        for rating in ratings.into_values() {
+            self.write_message(
+                "rating-changed",
+                &[
+                    ("name", &rating.name),
+                    ("rating", &rating.rating),
+                    ("change", &format!("{:+}", rating.change)),
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Shows the bracket round by round, with the result of each match played so far.
+    fn handle_tournament_standings(&self, rounds: Vec<Vec<BracketMatch>>) -> Result<(), io::Error> {
+        let player_name = |slot: &BracketSlot| match slot {
+            BracketSlot::Player(name) => name.clone(),
+            BracketSlot::Bye | BracketSlot::Undecided => {
+                self.locale
+                    .styled_message("tournament-undecided", self.output_style, &[])
+            }
+        };
+
+        for (round, matches) in rounds.iter().enumerate() {
+            self.write_message("tournament-round", &[("round", &(round + 1))])?;
+            for game in matches {
+                let [first, second] = &game.players;
+                match (first, second, game.winner) {
+                    (player, BracketSlot::Bye, _) | (BracketSlot::Bye, player, _) => {
+                        self.write_message("tournament-bye", &[("player", &player_name(player))])?
+                    }
+                    (_, _, Some(winner)) => self.write_message(
+                        "tournament-match-won",
+                        &[
+                            ("winner", &player_name(&game.players[winner])),
+                            ("loser", &player_name(&game.players[1 - winner])),
+                        ],
+                    )?,
+                    (_, _, None) => self.write_message(
+                        "tournament-match",
+                        &[
+                            ("first", &player_name(first)),
+                            ("second", &player_name(second)),
+                        ],
+                    )?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_error(&self, error: server::Error) -> Result<(), io::Error> {
+        let error = self.locale.message(server_error_key(error), &[]);
+        self.user_output.write_painted_line(
+            self.locale.message("error", &[("error", &error)]),
+            Paint::Error,
+        )
+    }
+
+    /// Ends the game once the client is unable to carry on, telling the user why. Closing the
+    /// connection lets the server know the client has gone.
+    async fn handle_failure(&mut self, error: Error) {
+        let message = self.locale.message("error", &[("error", &error)]);
+        if self
+            .user_output
+            .write_painted_line(&message, Paint::Error)
+            .is_err()
+        {
+            // Writing to the user may be what failed, so the error is shown where it can be seen
+            eprintln!("{}", message);
+        }
+
+        self.handle_shutdown().await
+    }
+
+    /// Attempts to rejoin the game after the connection to the server has been lost, giving up
+    /// once the reconnection grace period has passed.
+    async fn handle_connection_lost(&mut self) {
+        let Some(session) = self.session.clone() else {
+            return self.handle_shutdown().await;
+        };
+
+        let _ = self.write_message("connection-lost", &[]);
+
+        let deadline = Instant::now() + RECONNECTION_GRACE_PERIOD;
+        while Instant::now() < deadline {
+            if let Ok(mut connection) = lobby::reconnect_to_game(&session).await {
+                if let Some(trace) = &self.protocol_trace {
+                    connection.set_trace(trace.clone());
+                }
+                self.server_connection = connection;
+                let _ = self.write_message("reconnected", &[]);
+
+                return;
+            }
+
+            time::sleep(Duration::from_secs(1)).await;
+        }
+
+        self.handle_shutdown().await
     }
 
     async fn handle_shutdown(&mut self) {
-        let _ = writeln!(
-            &mut self.user_output.lock().unwrap(),
-            "An unrecoverable error has occurred, game terminating."
-        );
+        let _ = self.write_message("unrecoverable-error", &[]);
+        self.exit_code = UNRECOVERABLE_EXIT_CODE;
+
+        self.shutdown().await
+    }
+
+    /// Ends the game once the server has ended it early, telling the user why.
+    async fn handle_server_shutdown(&mut self, reason: ShutdownReason) {
+        let _ = self.write_message(shutdown_reason_key(reason), &[]);
+        self.exit_code = shutdown_exit_code(reason);
 
         self.shutdown().await
     }
@@ -151,6 +805,108 @@ where
     }
 }
 
+/// The message telling the user of a change around the game that needs nothing more from the
+/// client, such as their opponent disconnecting or a notice from the server.
+fn status_message(
+    event: &server::OutgoingEvent,
+    output_style: OutputStyle,
+    locale: Locale,
+) -> Option<String> {
+    let message =
+        |key, args: &[(&str, &dyn Display)]| Some(locale.styled_message(key, output_style, args));
+
+    match event {
+        server::OutgoingEvent::OpponentDisconnected { grace_period_secs } => {
+            message("opponent-disconnected", &[("seconds", grace_period_secs)])
+        }
+        server::OutgoingEvent::OpponentReconnected => message("opponent-reconnected", &[]),
+        server::OutgoingEvent::OpponentReplaced => message("opponent-replaced", &[]),
+        server::OutgoingEvent::SpectatorJoined { spectator_count } => {
+            message("spectator-joined", &[("count", spectator_count)])
+        }
+        server::OutgoingEvent::SpectatorLeft { spectator_count } => {
+            message("spectator-left", &[("count", spectator_count)])
+        }
+        server::OutgoingEvent::Notice { message: notice } => {
+            message("server-notice", &[("message", notice)])
+        }
+        server::OutgoingEvent::RejectedMovesWarning { attempts_remaining } => message(
+            "rejected-moves-warning",
+            &[("attempts", attempts_remaining)],
+        ),
+        _ => None,
+    }
+}
+
+/// Whether the event ends the game, so that the client stops waiting on its user to handle it.
+fn ends_game(event: &server::OutgoingEvent) -> bool {
+    matches!(
+        event,
+        server::OutgoingEvent::Shutdown { .. }
+            | server::OutgoingEvent::Suspended
+            | server::OutgoingEvent::RematchDeclined
+            | server::OutgoingEvent::ErrorOccurred(server::Error::Kicked)
+    )
+}
+
+/// Reads the user's answer to the offer of a rematch, which may name one of the other games to
+/// switch to.
+///
+/// # Returns
+///
+/// - `Some(RematchResponse)` containing the answer to send.
+/// - `None` if the answer could not be understood.
+fn parse_rematch_answer(
+    answer: &str,
+    player_id: u8,
+    other_games: &[String],
+) -> Option<RematchResponse> {
+    let answer = answer.trim().to_ascii_lowercase();
+    match answer.as_str() {
+        "" | "y" | "yes" => Some(RematchResponse::Accepted {
+            player_id,
+            game: None,
+        }),
+        "n" | "no" => Some(RematchResponse::Declined { player_id }),
+        _ if other_games.contains(&answer) => Some(RematchResponse::Accepted {
+            player_id,
+            game: Some(answer),
+        }),
+        _ => None,
+    }
+}
+
+/// The key of the message describing the error in the catalog.
+fn server_error_key(error: server::Error) -> &'static str {
+    match error {
+        server::Error::InvalidMessage => "server-error-invalid-message",
+        server::Error::SpectatingUnavailable => "server-error-spectating-unavailable",
+        server::Error::Kicked => "server-error-kicked",
+        server::Error::UnexpectedPlayer => "server-error-unexpected-player",
+        server::Error::SavingUnavailable => "server-error-saving-unavailable",
+    }
+}
+
+fn shutdown_reason_key(reason: ShutdownReason) -> &'static str {
+    match reason {
+        ShutdownReason::OpponentDisconnected => "shutdown-opponent-disconnected",
+        ShutdownReason::ProtocolViolation => "shutdown-protocol-violation",
+        ShutdownReason::ServerShutdown => "shutdown-server-shutdown",
+        ShutdownReason::Timeout => "shutdown-timeout",
+    }
+}
+
+/// The code the process exits with when the server ends the game early for the given reason, each
+/// reason having its own so that scripts can tell them apart.
+fn shutdown_exit_code(reason: ShutdownReason) -> i32 {
+    match reason {
+        ShutdownReason::OpponentDisconnected => 3,
+        ShutdownReason::ProtocolViolation => 4,
+        ShutdownReason::ServerShutdown => 5,
+        ShutdownReason::Timeout => 6,
+    }
+}
+
 pub enum IncomingEvent {
     Server(server::OutgoingEvent),
     Game(GameClientEvent),
@@ -162,28 +918,68 @@ pub enum Error {
     Write(#[from] WriteError),
     #[error("Failed to read or write from user")]
     Read(#[from] io::Error),
+    #[error(transparent)]
+    Game(#[from] GameClientError),
+    #[error("The server started a game this client does not know: {0}")]
+    UnknownGame(String),
 }
 
 #[cfg(test)]
 mod tests {
     use std::net::Ipv4Addr;
+    use std::thread;
+
+    use async_trait::async_trait;
 
-    use tokio::net::{TcpListener, TcpStream};
+    use crate::game::Seats;
+    use crate::tic_tac_toe::TicTacToe;
 
     use super::*;
 
-    async fn get_test_stream() -> TcpStream {
-        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
-        let local_addr = listener.local_addr().unwrap();
-        TcpStream::connect(local_addr).await.unwrap()
+    /// A session for the client to hold, which is only used in reconnecting to the server.
+    fn get_test_session() -> Session {
+        Session {
+            address: (Ipv4Addr::LOCALHOST, 0).into(),
+            join_token: lobby::JoinToken::generate(),
+            session_token: 1,
+            seats: Seats::single(server::PLAYER_ONE_ID),
+            options: lobby::ConnectionOptions::default(),
+        }
+    }
+
+    /// Input that the user never gets round to entering anything into.
+    struct IdleInput;
+
+    impl io::Read for IdleInput {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            loop {
+                thread::park();
+            }
+        }
+    }
+
+    /// A frontend the user has already left, without having entered anything.
+    struct LeftFrontend;
+
+    #[async_trait]
+    impl Frontend for LeftFrontend {
+        fn show_message(&self, _message: &str, _paint: Option<Paint>) -> io::Result<()> {
+            Ok(())
+        }
+
+        async fn read_line(&self) -> io::Result<String> {
+            std::future::pending().await
+        }
+
+        async fn closed(&self) {}
     }
 
-    async fn get_local_test_client<'a>(output: &'a mut Vec<u8>) -> Client<'a, &'a mut Vec<u8>> {
-        Client::<'a, &'a mut Vec<u8>>::new_local(
-            Connection::new(get_test_stream().await),
+    fn get_local_test_client<'a>(output: &'a mut Vec<u8>) -> Client<'a> {
+        Client::new_local(
+            Connection::pair().0,
             &b""[..],
             output,
-            Game::TicTacToe,
+            &TicTacToe::default(),
         )
     }
 
@@ -192,35 +988,427 @@ mod tests {
         let mut output = Vec::new();
 
         {
-            let mut client = get_local_test_client(&mut output).await;
+            let mut client = get_local_test_client(&mut output);
             client
-                .handle_event(IncomingEvent::Server(server::OutgoingEvent::Shutdown))
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::Shutdown {
+                    reason: ShutdownReason::OpponentDisconnected,
+                }))
                 .await
                 .unwrap();
-            assert_eq!(client.running, false);
+            assert!(!client.running);
+            assert_eq!(client.exit_code(), 3);
         }
 
         assert_eq!(
             output,
-            b"An unrecoverable error has occurred, game terminating.\n"
+            b"Your opponent has left the game, game terminating.\n"
         )
     }
 
+    #[tokio::test]
+    async fn client_explains_each_shutdown_reason_with_its_own_exit_code() {
+        let reasons = [
+            ShutdownReason::OpponentDisconnected,
+            ShutdownReason::ProtocolViolation,
+            ShutdownReason::ServerShutdown,
+            ShutdownReason::Timeout,
+        ];
+        let mut messages = Vec::new();
+        let mut exit_codes = Vec::new();
+        for reason in reasons {
+            let mut output = Vec::new();
+            {
+                let mut client = get_local_test_client(&mut output);
+                client
+                    .handle_event(IncomingEvent::Server(server::OutgoingEvent::Shutdown {
+                        reason,
+                    }))
+                    .await
+                    .unwrap();
+                exit_codes.push(client.exit_code());
+            }
+            messages.push(output);
+        }
+
+        messages.sort();
+        messages.dedup();
+        exit_codes.sort();
+        exit_codes.dedup();
+        assert_eq!(messages.len(), reasons.len());
+        assert_eq!(exit_codes.len(), reasons.len());
+        assert!(!exit_codes.contains(&0));
+        assert!(!exit_codes.contains(&UNRECOVERABLE_EXIT_CODE));
+    }
+
+    #[tokio::test]
+    async fn client_leaves_once_frontend_closed_while_waiting_on_server() {
+        let (connection, _server_connection) = Connection::pair();
+        let mut client = ClientBuilder::new().online(
+            connection,
+            get_test_session(),
+            server::PLAYER_ONE_ID,
+            LeftFrontend,
+            &TicTacToe::default(),
+        );
+
+        time::timeout(Duration::from_secs(1), client.play_game())
+            .await
+            .unwrap();
+
+        assert!(!client.running);
+        assert_eq!(client.exit_code(), UNRECOVERABLE_EXIT_CODE);
+    }
+
     #[tokio::test]
     async fn generic_client_handles_error_event_from_server() {
         let mut output = Vec::new();
 
         {
-            let mut client = get_local_test_client(&mut output).await;
+            let mut client = get_local_test_client(&mut output);
             client
                 .handle_event(IncomingEvent::Server(server::OutgoingEvent::ErrorOccurred(
                     server::Error::InvalidMessage,
                 )))
                 .await
                 .unwrap();
-            assert_eq!(client.running, true);
+            assert!(client.running);
         }
 
         assert_eq!(output, b"Error: Invalid message sent.\n")
     }
+
+    #[tokio::test]
+    async fn generic_client_stops_once_kicked_from_game() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            client
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::ErrorOccurred(
+                    server::Error::Kicked,
+                )))
+                .await
+                .unwrap();
+            assert!(!client.running);
+        }
+
+        assert_eq!(
+            output,
+            b"Error: You have been removed from the game by the server operator.\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn generic_client_shows_notice_from_server() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            client
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::Notice {
+                    message: String::from("Restarting in 5 minutes."),
+                }))
+                .await
+                .unwrap();
+            assert!(client.running);
+        }
+
+        assert_eq!(
+            output,
+            b"Message from the server: Restarting in 5 minutes.\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn generic_client_reports_failure_then_stops() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            client
+                .handle_failure(Error::Game(GameClientError::ClientStopped))
+                .await;
+            assert!(!client.running);
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Error: The client running the game has stopped\n\
+            An unrecoverable error has occurred, game terminating.\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn generic_client_shows_game_summary_then_stops() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            let think_times = BTreeMap::from([(
+                server::PLAYER_ONE_ID,
+                ThinkTime {
+                    turns: 2,
+                    total: Duration::from_secs(5),
+                    longest: Duration::from_millis(3500),
+                },
+            )]);
+            client
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::GameSummary {
+                    think_times,
+                    ratings: BTreeMap::new(),
+                    rematch_games: Vec::new(),
+                }))
+                .await
+                .unwrap();
+            assert!(!client.running);
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Player X thought for 2.5 seconds per turn on average, and 3.5 seconds at most.\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn generic_client_shows_new_ratings_in_game_summary() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            let rating = |name: &str, rating, change| RatingChange {
+                name: String::from(name),
+                rating,
+                change,
+            };
+            let ratings = BTreeMap::from([
+                (server::PLAYER_ONE_ID, rating("Ada", 1516, 16)),
+                (server::PLAYER_TWO_ID, rating("Alan", 1484, -16)),
+            ]);
+            client
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::GameSummary {
+                    think_times: BTreeMap::new(),
+                    ratings,
+                    rematch_games: Vec::new(),
+                }))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Ada is now rated 1516 (+16).\nAlan is now rated 1484 (-16).\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn generic_client_stops_once_game_suspended() {
+        let mut output = Vec::new();
+
+        {
+            let mut client = get_local_test_client(&mut output);
+            client
+                .handle_event(IncomingEvent::Server(server::OutgoingEvent::Suspended))
+                .await
+                .unwrap();
+            assert!(!client.running);
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "The game has been saved, it can be carried on once the host resumes it.\n"
+        )
+    }
+
+    #[tokio::test]
+    async fn online_client_does_not_reconnect_when_server_closes_connection_after_game_over() {
+        let (connection, mut server_connection) = Connection::pair();
+
+        // Server announces the game is over, then closes the connection straight away
+        server_connection
+            .write_event(&server::OutgoingEvent::Game {
+                event: crate::game::serialize_event(crate::tic_tac_toe::ServerEvent::GameOver {
+                    outcome: crate::tic_tac_toe::Outcome::Draw,
+                }),
+            })
+            .await
+            .unwrap();
+        server_connection.shutdown().await.unwrap();
+
+        let mut output = Vec::new();
+        {
+            let mut client = Client::new_online(
+                connection,
+                get_test_session(),
+                server::PLAYER_ONE_ID,
+                &b""[..],
+                &mut output,
+                &TicTacToe::default(),
+            );
+            time::timeout(Duration::from_secs(1), client.play_game())
+                .await
+                .unwrap();
+        }
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Game over! There was a draw!"));
+        assert!(!output.contains("attempting to reconnect"));
+    }
+
+    #[test]
+    fn parse_rematch_answer_only_switches_to_games_offered() {
+        let other_games = [String::from("checkers")];
+        let accepted = |game: Option<&str>| {
+            Some(RematchResponse::Accepted {
+                player_id: server::PLAYER_ONE_ID,
+                game: game.map(String::from),
+            })
+        };
+
+        assert_eq!(parse_rematch_answer("\n", 1, &other_games), accepted(None));
+        assert_eq!(parse_rematch_answer("Y\n", 1, &other_games), accepted(None));
+        assert_eq!(
+            parse_rematch_answer("Checkers\n", 1, &other_games),
+            accepted(Some("checkers"))
+        );
+        assert_eq!(
+            parse_rematch_answer("no", 2, &[]),
+            Some(RematchResponse::Declined { player_id: 2 })
+        );
+        assert_eq!(parse_rematch_answer("othello", 1, &other_games), None);
+        assert_eq!(parse_rematch_answer("checkers", 2, &[]), None);
+    }
+
+    #[tokio::test]
+    async fn online_client_answers_rematch_then_plays_game_chosen() {
+        let (connection, mut server_connection) = Connection::pair();
+        let server = async move {
+            let rematch_games = vec![String::from("tic-tac-toe"), String::from("checkers")];
+            server_connection
+                .write_event(&server::OutgoingEvent::GameSummary {
+                    think_times: BTreeMap::new(),
+                    ratings: BTreeMap::new(),
+                    rematch_games,
+                })
+                .await
+                .unwrap();
+
+            let response: ErasedEvent = server_connection.read_event().await.unwrap();
+            assert_eq!(
+                game::deserialize_event::<RematchResponse>(response),
+                RematchResponse::Accepted {
+                    player_id: server::PLAYER_ONE_ID,
+                    game: Some(String::from("checkers")),
+                }
+            );
+            for event in [
+                server::OutgoingEvent::NextGame {
+                    game: String::from("checkers"),
+                },
+                server::OutgoingEvent::Shutdown {
+                    reason: ShutdownReason::ServerShutdown,
+                },
+            ] {
+                server_connection.write_event(&event).await.unwrap();
+            }
+        };
+
+        let mut output = Vec::new();
+        {
+            let mut client = Client::new_online(
+                connection,
+                get_test_session(),
+                server::PLAYER_ONE_ID,
+                &b"chess\ncheckers\n"[..],
+                &mut output,
+                &TicTacToe::default(),
+            );
+            let played = async { time::timeout(Duration::from_secs(1), client.play_game()).await };
+            let (played, ()) = tokio::join!(played, server);
+            played.unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Play again Y/n? Or enter the name of another game to play it instead: checkers\n\
+            That is not a valid option, please try again!\n\
+            Play again Y/n? Or enter the name of another game to play it instead: checkers\n\
+            Waiting for everyone to agree to play again.\n\
+            Starting a new game of checkers.\n\
+            The server has stopped the game, game terminating.\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn client_switches_to_game_started_by_host() {
+        let (connection, _server_connection) = Connection::pair();
+        let mut client = Client::new_online(
+            connection,
+            get_test_session(),
+            server::PLAYER_TWO_ID,
+            &b""[..],
+            Vec::new(),
+            &TicTacToe::default(),
+        );
+        let game_started = |game: &str| {
+            IncomingEvent::Server(server::OutgoingEvent::GameStarted {
+                game: String::from(game),
+                marks: server::PlayerMarks::default(),
+                config: Vec::new(),
+            })
+        };
+
+        client.handle_event(game_started("checkers")).await.unwrap();
+        assert_eq!(client.game_id, "checkers");
+
+        assert!(matches!(
+            client.handle_event(game_started("chess")).await,
+            Err(Error::UnknownGame(game)) if game == "chess"
+        ));
+        assert_eq!(client.game_id, "checkers");
+    }
+
+    #[tokio::test]
+    async fn online_client_handles_server_events_while_waiting_for_move() {
+        let (connection, mut server_connection) = Connection::pair();
+
+        // Server asks for a move, then carries on without waiting for it
+        for event in [
+            server::OutgoingEvent::Game {
+                event: crate::game::serialize_event(crate::tic_tac_toe::ServerEvent::PlayerTurn {
+                    player_id: server::PLAYER_ONE_ID,
+                }),
+            },
+            server::OutgoingEvent::Notice {
+                message: String::from("Restarting in 5 minutes."),
+            },
+            server::OutgoingEvent::Shutdown {
+                reason: ShutdownReason::ServerShutdown,
+            },
+        ] {
+            server_connection.write_event(&event).await.unwrap();
+        }
+
+        let mut output = Vec::new();
+        {
+            let mut client = Client::new_online(
+                connection,
+                get_test_session(),
+                server::PLAYER_ONE_ID,
+                io::BufReader::new(IdleInput),
+                &mut output,
+                &TicTacToe::default(),
+            );
+            time::timeout(Duration::from_secs(1), client.play_game())
+                .await
+                .unwrap();
+        }
+
+        let output = String::from_utf8(output).unwrap();
+        let prompt = output.find("to make your move:").unwrap();
+        let notice = output
+            .find("Message from the server: Restarting in 5 minutes.")
+            .unwrap();
+        assert!(prompt < notice);
+        assert!(output.ends_with("The server has stopped the game, game terminating.\n"));
+    }
 }
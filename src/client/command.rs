@@ -0,0 +1,124 @@
+use crate::client::OutputStyle;
+use crate::locale::Locale;
+
+/// Starts every command, telling commands apart from moves.
+const COMMAND_PREFIX: char = '/';
+
+/// A command a game understands on top of those understood in every game, along with the key of
+/// the message describing it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GameCommand {
+    /// The name entered after the prefix, in lowercase.
+    pub name: &'static str,
+    pub description_key: &'static str,
+}
+
+/// What the user entered in place of a move.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Command<'a> {
+    /// Lists the commands that can be entered.
+    Help,
+    /// Shows the board again, such as once it has scrolled out of view.
+    Board,
+    /// Leaves the game.
+    Quit,
+    /// One of the game's own commands.
+    Game(GameCommand),
+    Unknown(&'a str),
+}
+
+/// The commands understood in every game.
+const COMMON_COMMANDS: [(&str, &str); 3] = [
+    ("help", "command-help"),
+    ("board", "command-board"),
+    ("quit", "command-quit"),
+];
+
+/// Reads the command entered by the user, ignoring case.
+///
+/// # Returns
+///
+/// - `Some(Command)` containing the command.
+/// - `None` if the text is not a command, leaving the game to read it as a move.
+pub fn parse<'a>(text: &'a str, game_commands: &[GameCommand]) -> Option<Command<'a>> {
+    let text = text.trim();
+    let name = text.strip_prefix(COMMAND_PREFIX)?;
+
+    let command = match name.to_ascii_lowercase().as_str() {
+        "help" => Command::Help,
+        "board" => Command::Board,
+        "quit" => Command::Quit,
+        name => game_commands
+            .iter()
+            .find(|command| command.name == name)
+            .map_or(Command::Unknown(text), |&command| Command::Game(command)),
+    };
+
+    Some(command)
+}
+
+/// The lines listing every command that can be entered in the game, each with what it does.
+pub fn describe(
+    game_commands: &[GameCommand],
+    locale: Locale,
+    output_style: OutputStyle,
+) -> Vec<String> {
+    let commands = COMMON_COMMANDS.into_iter().chain(
+        game_commands
+            .iter()
+            .map(|command| (command.name, command.description_key)),
+    );
+
+    std::iter::once(locale.styled_message("command-list", output_style, &[]))
+        .chain(commands.map(|(name, description_key)| {
+            format!(
+                "{}{}: {}",
+                COMMAND_PREFIX,
+                name,
+                locale.message(description_key, &[])
+            )
+        }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UNDO: GameCommand = GameCommand {
+        name: "undo",
+        description_key: "command-undo",
+    };
+
+    #[test]
+    fn parse_reads_common_and_game_commands_ignoring_case() {
+        assert_eq!(parse("/help\n", &[]), Some(Command::Help));
+        assert_eq!(parse(" /Board", &[]), Some(Command::Board));
+        assert_eq!(parse("/QUIT", &[UNDO]), Some(Command::Quit));
+        assert_eq!(parse("/Undo\n", &[UNDO]), Some(Command::Game(UNDO)));
+    }
+
+    #[test]
+    fn parse_leaves_moves_and_reports_unknown_commands() {
+        assert_eq!(parse("5\n", &[UNDO]), None);
+        assert_eq!(parse("undo", &[UNDO]), None);
+        assert_eq!(parse("/undo", &[]), Some(Command::Unknown("/undo")));
+        assert_eq!(parse("/", &[UNDO]), Some(Command::Unknown("/")));
+    }
+
+    #[test]
+    fn describe_lists_common_commands_before_game_commands() {
+        let lines = describe(&[UNDO], Locale::default(), OutputStyle::Standard);
+
+        assert_eq!(
+            lines,
+            [
+                "Enter one of these commands in place of your move:",
+                "/help: list the commands you can enter.",
+                "/board: show the board again.",
+                "/quit: leave the game.",
+                "/undo: ask to take back your last move.",
+            ]
+        );
+    }
+}
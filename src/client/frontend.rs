@@ -0,0 +1,125 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+
+use crate::client::{Paint, UserInput};
+
+/// What the user sees the game through and makes their moves with, so that the client and its
+/// games can be played other than in a terminal.
+#[async_trait]
+pub trait Frontend: Send + Sync {
+    /// Shows the user a message, with the paint applied if given.
+    fn show_message(&self, message: &str, paint: Option<Paint>) -> io::Result<()>;
+
+    /// Shows the user the board as it now stands, already laid out by the game.
+    fn render_board(&self, board: &str) -> io::Result<()> {
+        self.show_message(board, None)
+    }
+
+    /// Waits for the next line entered by the user, failing once no more can be entered.
+    ///
+    /// This must be cancel safe, as the client races it against events sent by the server.
+    async fn read_line(&self) -> io::Result<String>;
+
+    /// Asks the user for their move, returning the line they answer with.
+    async fn prompt_for_move(&self, prompt: &str) -> io::Result<String> {
+        self.show_message(prompt, None)?;
+        self.read_line().await
+    }
+
+    /// Formats text to be shown with the paint applied, for paint within a message such as
+    /// players' marks on the board. Left as it is by frontends without a way to mark it.
+    fn paint(&self, text: &str, _paint: Paint) -> String {
+        text.to_string()
+    }
+
+    /// Waits for the user to leave the frontend part way through the game, which never happens
+    /// for frontends that cannot be left other than by ending the program.
+    ///
+    /// This must be cancel safe, as the client races it against the game's events.
+    async fn closed(&self) {
+        std::future::pending().await
+    }
+}
+
+/// Lets a frontend chosen at run time, such as the terminal or a full-screen interface, be played
+/// through.
+#[async_trait]
+impl<F: Frontend + ?Sized> Frontend for Box<F> {
+    fn show_message(&self, message: &str, paint: Option<Paint>) -> io::Result<()> {
+        (**self).show_message(message, paint)
+    }
+
+    fn render_board(&self, board: &str) -> io::Result<()> {
+        (**self).render_board(board)
+    }
+
+    async fn read_line(&self) -> io::Result<String> {
+        (**self).read_line().await
+    }
+
+    async fn prompt_for_move(&self, prompt: &str) -> io::Result<String> {
+        (**self).prompt_for_move(prompt).await
+    }
+
+    fn paint(&self, text: &str, paint: Paint) -> String {
+        (**self).paint(text, paint)
+    }
+
+    async fn closed(&self) {
+        (**self).closed().await
+    }
+}
+
+/// Plays through a terminal, reading the user's input line by line and writing text to them,
+/// painted with ANSI escape codes.
+pub struct TerminalFrontend<O: ?Sized> {
+    input: UserInput,
+    writer: Arc<Mutex<O>>,
+}
+
+impl<O: io::Write + Send + ?Sized> TerminalFrontend<O> {
+    pub fn new(input: impl Into<UserInput>, writer: Arc<Mutex<O>>) -> TerminalFrontend<O> {
+        TerminalFrontend {
+            input: input.into(),
+            writer,
+        }
+    }
+}
+
+#[async_trait]
+impl<O: io::Write + Send + ?Sized> Frontend for TerminalFrontend<O> {
+    fn show_message(&self, message: &str, paint: Option<Paint>) -> io::Result<()> {
+        let mut writer = self.writer.lock().unwrap();
+        match paint {
+            Some(paint) => writeln!(writer, "{}", self.paint(message, paint)),
+            None => writeln!(writer, "{}", message),
+        }
+    }
+
+    async fn read_line(&self) -> io::Result<String> {
+        self.input.read_line().await
+    }
+
+    fn paint(&self, text: &str, paint: Paint) -> String {
+        let (start, end) = paint.escape_codes();
+        format!("{}{}{}", start, text, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn prompt_written_before_answer_read() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let frontend = TerminalFrontend::new(&b"5\n"[..], Arc::clone(&writer));
+
+        let answer = frontend.prompt_for_move("Enter a cell:").await.unwrap();
+
+        assert_eq!(answer, "5\n");
+        assert_eq!(writer.lock().unwrap().as_slice(), b"Enter a cell:\n");
+    }
+}
@@ -0,0 +1,87 @@
+use std::io;
+use std::sync::Arc;
+use std::thread;
+
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::Mutex;
+
+/// Lines entered by the user, read on a thread of their own so that waiting on the user never
+/// holds up the client from handling events sent by the server.
+///
+/// Clones share the lines, each going to whichever clone reads it first, so that the client can
+/// hand the input on to each game played in turn.
+#[derive(Clone)]
+pub struct UserInput {
+    lines: Arc<Mutex<Receiver<io::Result<String>>>>,
+}
+
+impl UserInput {
+    /// Reads lines from the reader as the user enters them. The thread reading them stops once
+    /// the reader is closed, or once the input is dropped and the next line has been read.
+    pub fn spawn(mut reader: impl io::BufRead + Send + 'static) -> UserInput {
+        let (sender, lines) = mpsc::channel(1);
+
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            let result = match reader.read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => Ok(line),
+                Err(error) => Err(error),
+            };
+            let failed = result.is_err();
+            if sender.blocking_send(result).is_err() || failed {
+                break;
+            }
+        });
+
+        UserInput {
+            lines: Arc::new(Mutex::new(lines)),
+        }
+    }
+
+    /// Input that the user never enters anything into, for clients that do not play as them.
+    pub fn empty() -> UserInput {
+        let (_, lines) = mpsc::channel(1);
+
+        UserInput {
+            lines: Arc::new(Mutex::new(lines)),
+        }
+    }
+
+    /// Waits for the next line entered by the user, failing if they have closed their input
+    /// rather than waiting on input that will never arrive.
+    pub async fn read_line(&self) -> io::Result<String> {
+        match self.lines.lock().await.recv().await {
+            Some(line) => line,
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+}
+
+/// Reads lines from the reader as the user enters them, in the same way as `UserInput::spawn`.
+impl<R: io::BufRead + Send + 'static> From<R> for UserInput {
+    fn from(reader: R) -> Self {
+        UserInput::spawn(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn read_line_returns_each_line_then_fails_once_closed() {
+        let input = UserInput::spawn(&b"5\nundo\n"[..]);
+
+        assert_eq!(input.read_line().await.unwrap(), "5\n");
+        assert_eq!(input.read_line().await.unwrap(), "undo\n");
+        assert_eq!(
+            input.read_line().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(
+            UserInput::empty().read_line().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt::Display;
+use std::io;
+use std::sync::Arc;
+
+use crate::client::{Frontend, OutputStyle};
+use crate::locale::Locale;
+
+/// The ways in which text written to the user can be coloured or emphasised.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Paint {
+    PlayerOne,
+    PlayerTwo,
+    Error,
+    /// Draws the user's attention, such as to it being their turn.
+    Highlight,
+    /// Marks part of the board that has changed, shown in inverse video.
+    Changed,
+}
+
+impl Paint {
+    /// The ANSI escape codes that switch the paint on and off. Each only resets what it set, so
+    /// that paints can be nested.
+    pub(crate) fn escape_codes(&self) -> (&'static str, &'static str) {
+        match self {
+            Paint::PlayerOne => ("\x1b[36m", "\x1b[39m"),
+            Paint::PlayerTwo => ("\x1b[33m", "\x1b[39m"),
+            Paint::Error => ("\x1b[31m", "\x1b[39m"),
+            Paint::Highlight => ("\x1b[1m", "\x1b[22m"),
+            Paint::Changed => ("\x1b[7m", "\x1b[27m"),
+        }
+    }
+}
+
+/// Writes lines of text to the user through their frontend, worded for the output style and
+/// locale in use and painted when colour is enabled. Every game writes to its user through one,
+/// so that each output style is handled in a single place.
+///
+/// Colour is off until enabled, as not every frontend the user might see the game through can
+/// show it.
+pub struct UserOutput<F: Frontend + ?Sized> {
+    frontend: Arc<F>,
+    color: bool,
+    output_style: OutputStyle,
+    locale: Locale,
+}
+
+impl<F: Frontend + ?Sized> UserOutput<F> {
+    pub fn new(frontend: Arc<F>) -> UserOutput<F> {
+        UserOutput {
+            frontend,
+            color: false,
+            output_style: OutputStyle::default(),
+            locale: Locale::default(),
+        }
+    }
+
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
+    pub fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.output_style = output_style;
+    }
+
+    pub fn output_style(&self) -> OutputStyle {
+        self.output_style
+    }
+
+    pub fn set_locale(&mut self, locale: Locale) {
+        self.locale = locale;
+    }
+
+    pub fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// The message with the given key in the locale in use, worded for the output style.
+    pub fn message(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        self.locale.styled_message(key, self.output_style, args)
+    }
+
+    /// Formats the text with the paint applied, or as it is when colour is disabled.
+    pub fn paint(&self, text: impl Display, paint: Paint) -> String {
+        if !self.color {
+            return text.to_string();
+        }
+
+        self.frontend.paint(&text.to_string(), paint)
+    }
+
+    pub fn write_line(&self, message: impl Display) -> Result<(), io::Error> {
+        self.frontend.show_message(&message.to_string(), None)
+    }
+
+    pub fn write_painted_line(&self, message: impl Display, paint: Paint) -> Result<(), io::Error> {
+        let paint = self.color.then_some(paint);
+        self.frontend.show_message(&message.to_string(), paint)
+    }
+
+    /// Writes the message with the given key, as given by `UserOutput::message`.
+    pub fn write_message(&self, key: &str, args: &[(&str, &dyn Display)]) -> Result<(), io::Error> {
+        self.write_line(self.message(key, args))
+    }
+
+    /// Writes the message with the given key, as given by `UserOutput::message`, painted to stand
+    /// out.
+    pub fn write_painted_message(
+        &self,
+        key: &str,
+        args: &[(&str, &dyn Display)],
+        paint: Paint,
+    ) -> Result<(), io::Error> {
+        self.write_painted_line(self.message(key, args), paint)
+    }
+
+    /// Writes the error message with the given key, painted as an error.
+    pub fn write_error(&self, key: &str, args: &[(&str, &dyn Display)]) -> Result<(), io::Error> {
+        let error = self.locale.message(key, args);
+        self.write_painted_line(
+            self.locale.message("error", &[("error", &error)]),
+            Paint::Error,
+        )
+    }
+
+    pub fn write_board(&self, board: impl Display) -> Result<(), io::Error> {
+        self.frontend.render_board(&board.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::client::{TerminalFrontend, UserInput};
+
+    use super::*;
+
+    fn terminal_output(writer: Arc<Mutex<Vec<u8>>>) -> UserOutput<TerminalFrontend<Vec<u8>>> {
+        UserOutput::new(Arc::new(TerminalFrontend::new(UserInput::empty(), writer)))
+    }
+
+    #[test]
+    fn paint_leaves_text_plain_until_color_enabled() {
+        let mut output = terminal_output(Arc::new(Mutex::new(Vec::new())));
+        assert_eq!(output.paint("X", Paint::PlayerOne), "X");
+
+        output.set_color(true);
+        assert_eq!(output.paint("X", Paint::PlayerOne), "\x1b[36mX\x1b[39m");
+    }
+
+    #[test]
+    fn nested_paints_only_reset_their_own_attribute() {
+        let mut output = terminal_output(Arc::new(Mutex::new(Vec::new())));
+        output.set_color(true);
+
+        let icon = output.paint("O", Paint::PlayerTwo);
+        assert_eq!(
+            output.paint(icon, Paint::Changed),
+            "\x1b[7m\x1b[33mO\x1b[39m\x1b[27m"
+        );
+    }
+
+    #[test]
+    fn message_matches_output_style() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let mut output = terminal_output(Arc::clone(&writer));
+
+        output.write_message("your-turn", &[]).unwrap();
+        output.set_output_style(OutputStyle::Terse);
+        output.write_message("your-turn", &[]).unwrap();
+
+        assert_eq!(
+            writer.lock().unwrap().as_slice(),
+            b"It's your turn!\nYour move.\n"
+        );
+    }
+
+    #[test]
+    fn message_worded_for_locale_and_output_style() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let mut output = terminal_output(Arc::clone(&writer));
+        output.set_locale(Locale::Spanish);
+        output.set_output_style(OutputStyle::Terse);
+
+        output.write_message("your-turn", &[]).unwrap();
+        output
+            .write_error("tic-tac-toe-error-cell-occupied", &[])
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.lock().unwrap().clone()).unwrap(),
+            "Tu turno.\nError: Esta casilla ya está ocupada.\n"
+        );
+    }
+
+    #[test]
+    fn painted_line_written_to_user() {
+        let writer = Arc::new(Mutex::new(Vec::new()));
+        let mut output = terminal_output(Arc::clone(&writer));
+        output.set_color(true);
+
+        output
+            .write_painted_line("Error: Invalid message sent.", Paint::Error)
+            .unwrap();
+
+        assert_eq!(
+            writer.lock().unwrap().as_slice(),
+            b"\x1b[31mError: Invalid message sent.\x1b[39m\n"
+        );
+    }
+}
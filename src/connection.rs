@@ -1,65 +1,529 @@
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use socket2::{SockRef, TcpKeepalive};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpStream;
+use tokio::time::{self, Instant};
+use tokio_tungstenite::tungstenite::error::CapacityError;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::WebSocketStream;
+
+#[cfg(feature = "chaos")]
+pub use chaos::{Chaos, ChaosStream};
+pub use codec::{Codec, CodecError, CodecKind, Json, SUPPORTED_CODECS};
+pub use compression::{Compression, CompressionStats, DecompressError, SUPPORTED_COMPRESSION};
+pub use envelope::SCHEMA_VERSION;
+pub use trace::ProtocolTrace;
+
+pub(crate) use envelope::{Envelope, OpenError};
+use trace::Direction;
+
+#[cfg(feature = "chaos")]
+mod chaos;
+mod codec;
+mod compression;
+pub(crate) mod envelope;
+mod trace;
+
+/// The largest event, in bytes, that a connection accepts from the other end unless configured
+/// otherwise. This must leave room for a board of the largest size to be sent as JSON.
+pub const MAX_EVENT_LENGTH: usize = 4096;
+/// The largest event, in bytes, that any connection accepts over WebSocket, however high its own
+/// maximum is set, as each message is read whole before its length can be checked.
+const MAX_WEBSOCKET_EVENT_LENGTH: usize = 1 << 20;
+/// Set in the length preceding a frame sent over a stream when the frame is compressed. Frames are
+/// never long enough to need this bit for their length.
+const COMPRESSED_FLAG: u32 = 1 << 31;
+/// The number of bytes taken by the length preceding each frame sent over a stream.
+const LENGTH_PREFIX_BYTES: usize = 4;
+/// How long a connection may go without hearing from the other end before heartbeats are sent.
+const HEARTBEAT_IDLE_TIME: Duration = Duration::from_secs(3);
+// Unused on platforms that do not allow these to be configured
+#[allow(dead_code)]
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of unanswered heartbeats after which the other end is considered gone.
+#[allow(dead_code)]
+const HEARTBEAT_RETRIES: u32 = 3;
+
+/// A stream that events can be sent over, such as a TCP stream or a TLS stream wrapping one. Tests
+/// use in-memory streams instead, so that they need no network.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send + Sync {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send + Sync> Stream for S {}
 
-#[derive(Debug)]
 pub struct Connection {
-    stream: TcpStream,
+    transport: Transport,
+    codec: CodecKind,
+    compression: Option<Compression>,
+    compression_stats: CompressionStats,
+    trace: Option<ProtocolTrace>,
+    max_event_length: usize,
+    read_timeout: Option<Duration>,
+    /// When an event was last received, or the read timeout set, from which the timeout runs.
+    last_received_at: Instant,
+    /// The sequence number of the next event to be sent.
+    next_sent: u64,
+    /// The sequence number expected of the next event received.
+    next_received: u64,
+    /// Bytes read from a stream towards the next frame. Kept across reads, so that a read
+    /// abandoned partway through a frame carries on where it left off rather than losing its place.
+    read_buffer: Vec<u8>,
+}
+
+/// How events are framed on the underlying stream.
+enum Transport {
+    /// Each event is preceded by its length as a big-endian `u32`, with the top bit set if the
+    /// event is compressed.
+    Stream(Box<dyn Stream>),
+    /// Each event is sent as a single WebSocket message.
+    WebSocket(Box<WebSocketStream<Box<dyn Stream>>>),
 }
 
 impl Connection {
-    pub fn new(stream: TcpStream) -> Connection {
-        Connection { stream }
+    /// Creates a connection that encodes events as JSON until another codec is negotiated.
+    pub fn new<S: Stream + 'static>(stream: S) -> Connection {
+        Connection {
+            transport: Transport::Stream(Box::new(stream)),
+            codec: CodecKind::default(),
+            compression: None,
+            compression_stats: CompressionStats::default(),
+            trace: None,
+            max_event_length: MAX_EVENT_LENGTH,
+            read_timeout: None,
+            last_received_at: Instant::now(),
+            next_sent: 0,
+            next_received: 0,
+            read_buffer: Vec::new(),
+        }
+    }
+
+    /// Creates two connections joined to each other in memory, standing in for either end of a
+    /// network connection. Several events can be written before any has to be read.
+    pub(crate) fn pair() -> (Connection, Connection) {
+        let (one, two) = tokio::io::duplex(4 * MAX_EVENT_LENGTH);
+        (Connection::new(one), Connection::new(two))
+    }
+
+    /// Performs the server side of the WebSocket handshake on the stream, returning a connection
+    /// that sends events as WebSocket messages.
+    pub async fn accept_websocket<S: Stream + 'static>(
+        stream: S,
+    ) -> Result<Connection, tungstenite::Error> {
+        let stream: Box<dyn Stream> = Box::new(stream);
+        let websocket =
+            tokio_tungstenite::accept_async_with_config(stream, Some(websocket_config())).await?;
+
+        Ok(Connection::from_websocket(websocket))
+    }
+
+    /// Performs the client side of the WebSocket handshake on the stream, returning a connection
+    /// that sends events as WebSocket messages.
+    pub async fn connect_websocket<S: Stream + 'static>(
+        stream: S,
+        address: SocketAddr,
+    ) -> Result<Connection, tungstenite::Error> {
+        let stream: Box<dyn Stream> = Box::new(stream);
+        let (websocket, _) = tokio_tungstenite::client_async_with_config(
+            format!("ws://{}/", address),
+            stream,
+            Some(websocket_config()),
+        )
+        .await?;
+
+        Ok(Connection::from_websocket(websocket))
+    }
+
+    fn from_websocket(websocket: WebSocketStream<Box<dyn Stream>>) -> Connection {
+        Connection {
+            transport: Transport::WebSocket(Box::new(websocket)),
+            codec: CodecKind::default(),
+            compression: None,
+            compression_stats: CompressionStats::default(),
+            trace: None,
+            max_event_length: MAX_EVENT_LENGTH,
+            read_timeout: None,
+            last_received_at: Instant::now(),
+            next_sent: 0,
+            next_received: 0,
+            read_buffer: Vec::new(),
+        }
+    }
+
+    pub fn set_codec(&mut self, codec: CodecKind) {
+        self.codec = codec;
+    }
+
+    /// Compresses the events worth compressing from now on, and accepts compressed events from the
+    /// other end. Only frames sent over a stream say whether they are compressed, so over WebSocket
+    /// events are never compressed.
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.compression = compression;
+    }
+
+    /// How much compression has saved since it was turned on, for debugging.
+    pub fn compression_stats(&self) -> CompressionStats {
+        self.compression_stats
+    }
+
+    /// Sets the largest event, in bytes, that will be accepted from the other end, which defaults
+    /// to `MAX_EVENT_LENGTH`. Both ends must allow for the largest event of the game being played.
+    ///
+    /// Over WebSocket, events longer than 1 MiB are refused however high this is set.
+    pub fn set_max_event_length(&mut self, max_event_length: usize) {
+        self.max_event_length = max_event_length;
+    }
+
+    /// Fails reads once nothing has been received from the other end for the given time, counted
+    /// from now or from the last event received, whichever is later. This stops a connection whose
+    /// other end has silently gone from being waited on forever. `None` waits indefinitely, which
+    /// is the default.
+    pub fn set_read_timeout(&mut self, read_timeout: Option<Duration>) {
+        self.read_timeout = read_timeout;
+        self.last_received_at = Instant::now();
+    }
+
+    /// Records every frame sent or received from now on to the trace.
+    pub fn set_trace(&mut self, trace: ProtocolTrace) {
+        self.trace = Some(trace);
     }
 
     pub async fn write_event<T: Serialize>(&mut self, event: &T) -> Result<(), WriteError> {
-        let serialised = serde_json::to_string(event)?;
-        let len = serialised.len() as u16;
-        let bytes = len.to_be_bytes();
+        let serialised = self
+            .codec
+            .encode(&Envelope::wrap(event).with_sequence(self.next_sent))?;
+        self.next_sent += 1;
+        if let Some(trace) = &self.trace {
+            let decoded = serde_json::to_value(event).ok();
+            trace.record(Direction::Sent, &serialised, decoded.as_ref());
+        }
 
-        self.stream.write_all(&bytes[..]).await?;
-        self.stream.write_all(serialised.as_bytes()).await?;
-        self.stream.flush().await?;
+        match &mut self.transport {
+            Transport::Stream(stream) => {
+                let compressed = self
+                    .compression
+                    .and_then(|compression| compression.compress(&serialised));
+                let (frame, flag) = match &compressed {
+                    Some(compressed) => {
+                        let stats = &mut self.compression_stats;
+                        stats.frames_sent += 1;
+                        stats.bytes_before_sending += serialised.len() as u64;
+                        stats.bytes_sent += compressed.len() as u64;
+                        (compressed, COMPRESSED_FLAG)
+                    }
+                    None => (&serialised, 0),
+                };
+                let len = u32::try_from(frame.len())
+                    .ok()
+                    .filter(|len| len & COMPRESSED_FLAG == 0)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+                let bytes = (len | flag).to_be_bytes();
+
+                stream.write_all(&bytes[..]).await?;
+                stream.write_all(frame).await?;
+                stream.flush().await?;
+            }
+            Transport::WebSocket(websocket) => websocket
+                .send(Message::Binary(serialised))
+                .await
+                .map_err(io::Error::other)?,
+        }
 
         Ok(())
     }
 
+    /// Waits for the next event from the other end, skipping any of a kind added in a newer
+    /// schema than this one knows about.
+    ///
+    /// Fails if the event is not the one that was expected next, such as when an event has been
+    /// dropped, duplicated or reordered on its way. An event that cannot be read still takes its
+    /// place in the sequence, so that reading can carry on past it.
     pub async fn read_event<T: DeserializeOwned>(&mut self) -> Result<T, ReadError> {
-        // Read the length of the event
-        let mut len_bytes = [0; 2];
-        self.stream.read_exact(&mut len_bytes).await?;
-        let len = u16::from_be_bytes(len_bytes);
-        if len > 500 {
-            return Err(ReadError::InvalidMessageLength);
+        let result = self.read_sequenced_event().await;
+        if let Err(ReadError::Deserialise(_) | ReadError::Decompress(_)) = result {
+            self.next_received += 1;
         }
 
-        // Read the event
-        let mut serialised = vec![0; len as usize];
-        self.stream.read_exact(&mut serialised).await?;
+        result
+    }
+
+    async fn read_sequenced_event<T: DeserializeOwned>(&mut self) -> Result<T, ReadError> {
+        loop {
+            let serialised = self.read_frame().await?;
+            if let Some(trace) = &self.trace {
+                // Codecs that do not describe their own structure, such as bincode, cannot be
+                // decoded without knowing the event's type, leaving the event unnamed
+                let decoded = self
+                    .codec
+                    .decode::<Envelope<serde_json::Value>>(&serialised)
+                    .ok()
+                    .map(Envelope::into_payload);
+                trace.record(Direction::Received, &serialised, decoded.as_ref());
+            }
+
+            match envelope::open(&self.codec, &serialised) {
+                Ok(envelope) => {
+                    self.check_sequence(envelope.sequence())?;
+                    return Ok(envelope.into_payload());
+                }
+                Err(OpenError::Unknown {
+                    kind,
+                    version,
+                    sequence,
+                }) => {
+                    self.check_sequence(sequence)?;
+                    tracing::debug!(kind, version, "Skipping event of unknown kind");
+                }
+                Err(OpenError::Codec(error)) => return Err(error.into()),
+            }
+        }
+    }
+
+    fn check_sequence(&mut self, sequence: u64) -> Result<(), ReadError> {
+        if sequence != self.next_received {
+            return Err(ReadError::OutOfSequence {
+                expected: self.next_received,
+                received: sequence,
+            });
+        }
+        self.next_received += 1;
+
+        Ok(())
+    }
+
+    async fn read_frame(&mut self) -> Result<Vec<u8>, ReadError> {
+        // The deadline is kept across reads, as a read may be abandoned and started afresh
+        // whenever something else happens first
+        let serialised = match self.read_timeout {
+            Some(read_timeout) => time::timeout_at(
+                self.last_received_at + read_timeout,
+                self.read_untimed_frame(),
+            )
+            .await
+            .map_err(|_| ReadError::Timeout {
+                after: read_timeout,
+            })??,
+            None => self.read_untimed_frame().await?,
+        };
+        self.last_received_at = Instant::now();
 
-        Ok(serde_json::from_slice(&serialised)?)
+        Ok(serialised)
+    }
+
+    async fn read_untimed_frame(&mut self) -> Result<Vec<u8>, ReadError> {
+        let serialised = match &mut self.transport {
+            Transport::Stream(stream) => {
+                // Read the length of the event, refusing it before reading any further if too long
+                fill_read_buffer(stream, &mut self.read_buffer, LENGTH_PREFIX_BYTES).await?;
+                let mut len_bytes = [0; LENGTH_PREFIX_BYTES];
+                len_bytes.copy_from_slice(&self.read_buffer[..LENGTH_PREFIX_BYTES]);
+                let mut len = u32::from_be_bytes(len_bytes);
+                // Without compression the flag is taken as part of the length, which is too long
+                let compressed = self.compression.filter(|_| len & COMPRESSED_FLAG != 0);
+                if compressed.is_some() {
+                    len &= !COMPRESSED_FLAG;
+                }
+                check_event_length(len as usize, self.max_event_length)?;
+
+                // Read the event
+                let frame_end = LENGTH_PREFIX_BYTES + len as usize;
+                fill_read_buffer(stream, &mut self.read_buffer, frame_end).await?;
+                let serialised: Vec<u8> = self
+                    .read_buffer
+                    .drain(..frame_end)
+                    .skip(LENGTH_PREFIX_BYTES)
+                    .collect();
+                match compressed {
+                    Some(compression) => {
+                        let decompressed =
+                            compression.decompress(&serialised, self.max_event_length)?;
+                        let stats = &mut self.compression_stats;
+                        stats.frames_received += 1;
+                        stats.bytes_received += serialised.len() as u64;
+                        stats.bytes_after_receiving += decompressed.len() as u64;
+                        decompressed
+                    }
+                    None => serialised,
+                }
+            }
+            Transport::WebSocket(websocket) => {
+                let serialised = read_websocket_message(websocket).await?;
+                check_event_length(serialised.len(), self.max_event_length)?;
+                serialised
+            }
+        };
+
+        Ok(serialised)
     }
 
     pub async fn shutdown(&mut self) -> std::io::Result<()> {
-        self.stream.shutdown().await
+        if self.compression.is_some() {
+            tracing::debug!(stats = ?self.compression_stats, "Closing compressed connection");
+        }
+        match &mut self.transport {
+            Transport::Stream(stream) => stream.shutdown().await,
+            Transport::WebSocket(websocket) => {
+                websocket.close(None).await.map_err(io::Error::other)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let transport = match self.transport {
+            Transport::Stream(_) => "Stream",
+            Transport::WebSocket(_) => "WebSocket",
+        };
+
+        f.debug_struct("Connection")
+            .field("transport", &transport)
+            .field("codec", &self.codec)
+            .field("compression", &self.compression)
+            .field("traced", &self.trace.is_some())
+            .finish()
+    }
+}
+
+/// Has the operating system send heartbeats over the TCP stream whenever it goes quiet, so that
+/// the other end silently disappearing is noticed within a few seconds.
+///
+/// Heartbeats are answered by the other end's operating system rather than by the game itself,
+/// meaning they continue to be answered while a client is busy waiting on its user. Once they go
+/// unanswered, or sent events go unacknowledged for as long, reading from or writing to the
+/// stream fails with an IO error.
+///
+/// Not every platform allows each of these to be configured, in which case the platform's own
+/// defaults are used for the rest.
+pub fn enable_heartbeats(stream: &TcpStream) -> io::Result<()> {
+    let keepalive = TcpKeepalive::new().with_time(HEARTBEAT_IDLE_TIME);
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "illumos",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+        target_os = "windows",
+    ))]
+    let keepalive = keepalive.with_interval(HEARTBEAT_INTERVAL);
+    #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "illumos",
+        target_os = "ios",
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "netbsd",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))]
+    let keepalive = keepalive.with_retries(HEARTBEAT_RETRIES);
+
+    let socket = SockRef::from(stream);
+    socket.set_tcp_keepalive(&keepalive)?;
+    #[cfg(any(target_os = "android", target_os = "fuchsia", target_os = "linux"))]
+    socket.set_tcp_user_timeout(Some(
+        HEARTBEAT_IDLE_TIME + HEARTBEAT_INTERVAL * HEARTBEAT_RETRIES,
+    ))?;
+
+    Ok(())
+}
+
+fn websocket_config() -> WebSocketConfig {
+    WebSocketConfig {
+        max_message_size: Some(MAX_WEBSOCKET_EVENT_LENGTH),
+        max_frame_size: Some(MAX_WEBSOCKET_EVENT_LENGTH),
+        ..Default::default()
+    }
+}
+
+/// Reads from the stream until the buffer holds at least `length` bytes. Whatever is read is kept
+/// in the buffer, so this can be abandoned at any point and started afresh without losing bytes.
+async fn fill_read_buffer(
+    stream: &mut Box<dyn Stream>,
+    buffer: &mut Vec<u8>,
+    length: usize,
+) -> io::Result<()> {
+    while buffer.len() < length {
+        buffer.reserve(length - buffer.len());
+        if stream.read_buf(buffer).await? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_event_length(length: usize, max_length: usize) -> Result<(), ReadError> {
+    if length > max_length {
+        return Err(ReadError::InvalidMessageLength { length, max_length });
+    }
+
+    Ok(())
+}
+
+/// Waits for the next message containing an event, skipping control messages such as pings.
+///
+/// Text messages are accepted alongside binary ones, as browsers find these easier to send.
+async fn read_websocket_message(
+    websocket: &mut WebSocketStream<Box<dyn Stream>>,
+) -> Result<Vec<u8>, ReadError> {
+    loop {
+        match websocket.next().await {
+            Some(Ok(Message::Binary(bytes))) => return Ok(bytes),
+            Some(Ok(Message::Text(text))) => return Ok(text.into_bytes()),
+            Some(Ok(Message::Close(_))) | None => {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into())
+            }
+            Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => continue,
+            Some(Err(tungstenite::Error::Capacity(CapacityError::MessageTooLong {
+                size,
+                max_size,
+            }))) => {
+                return Err(ReadError::InvalidMessageLength {
+                    length: size,
+                    max_length: max_size,
+                })
+            }
+            Some(Err(error)) => return Err(io::Error::other(error).into()),
+        }
     }
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum ReadError {
     #[error("Failed to serialise message")]
-    Deserialise(#[from] serde_json::Error),
+    Deserialise(#[from] CodecError),
     #[error("Failed to read from stream")]
     Read(#[from] std::io::Error),
-    #[error("Received length parameter exceeds expected bounds")]
-    InvalidMessageLength,
+    #[error("Received event of {length} bytes exceeds the maximum of {max_length} bytes")]
+    InvalidMessageLength { length: usize, max_length: usize },
+    #[error("Received event {received} out of sequence, expecting event {expected}")]
+    OutOfSequence { expected: u64, received: u64 },
+    #[error("Failed to decompress event")]
+    Decompress(#[from] DecompressError),
+    #[error("Received nothing for {after:?}")]
+    Timeout { after: Duration },
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum WriteError {
     #[error("Failed to serialise Event")]
-    Serialise(#[from] serde_json::Error),
+    Serialise(#[from] CodecError),
     #[error("Failed to write to stream")]
     Write(#[from] std::io::Error),
 }
@@ -70,6 +534,10 @@ pub enum ErrorCategory {
     Deserialisation,
     ReadWrite,
     InvalidParameters,
+    /// The other end broke the rules of the protocol, such as by sending events out of sequence.
+    Protocol,
+    /// The other end sent nothing for longer than it was given, suggesting it has gone.
+    Timeout,
 }
 
 pub trait HasErrorCategory {
@@ -79,9 +547,11 @@ pub trait HasErrorCategory {
 impl HasErrorCategory for ReadError {
     fn category(&self) -> ErrorCategory {
         match self {
-            ReadError::Deserialise(_) => ErrorCategory::Deserialisation,
+            ReadError::Deserialise(_) | ReadError::Decompress(_) => ErrorCategory::Deserialisation,
             ReadError::Read(_) => ErrorCategory::ReadWrite,
-            ReadError::InvalidMessageLength => ErrorCategory::InvalidParameters,
+            ReadError::InvalidMessageLength { .. } => ErrorCategory::InvalidParameters,
+            ReadError::OutOfSequence { .. } => ErrorCategory::Protocol,
+            ReadError::Timeout { .. } => ErrorCategory::Timeout,
         }
     }
 }
@@ -94,3 +564,375 @@ impl HasErrorCategory for WriteError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use serde::Deserialize;
+    use tokio::net::{TcpListener, TcpStream};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct TestEvent {
+        content: String,
+    }
+
+    async fn get_websocket_connections() -> (Connection, Connection) {
+        let (server_stream, client_stream) = tokio::io::duplex(4 * MAX_EVENT_LENGTH);
+        // Only named in the handshake, as the streams are already joined
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, 0));
+
+        let server = async { Connection::accept_websocket(server_stream).await.unwrap() };
+        let client = async {
+            Connection::connect_websocket(client_stream, address)
+                .await
+                .unwrap()
+        };
+
+        tokio::join!(server, client)
+    }
+
+    #[tokio::test]
+    async fn enable_heartbeats_configures_keepalive_on_stream() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+
+        enable_heartbeats(&stream).unwrap();
+
+        let socket = SockRef::from(&stream);
+        assert!(socket.keepalive().unwrap());
+        assert_eq!(socket.keepalive_time().unwrap(), HEARTBEAT_IDLE_TIME);
+        assert_eq!(socket.keepalive_retries().unwrap(), HEARTBEAT_RETRIES);
+    }
+
+    #[tokio::test]
+    async fn websocket_connections_exchange_events() {
+        let (mut server, mut client) = get_websocket_connections().await;
+        server.set_codec(CodecKind::Bincode);
+        client.set_codec(CodecKind::Bincode);
+
+        client
+            .write_event(&TestEvent {
+                content: String::from("Content sent over WebSocket"),
+            })
+            .await
+            .unwrap();
+        let event: TestEvent = server.read_event().await.unwrap();
+        assert_eq!(event.content, "Content sent over WebSocket");
+    }
+
+    #[tokio::test]
+    async fn traced_connection_records_frames_in_both_directions() {
+        #[derive(Serialize, Deserialize)]
+        enum TracedEvent {
+            MoveMade { cell: u8 },
+            Shutdown,
+        }
+
+        let (mut server, mut client) = Connection::pair();
+        let output = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        client.set_trace(ProtocolTrace::new(std::sync::Arc::clone(&output) as _));
+
+        client
+            .write_event(&TracedEvent::MoveMade { cell: 4 })
+            .await
+            .unwrap();
+        let _: TracedEvent = server.read_event().await.unwrap();
+        server.write_event(&TracedEvent::Shutdown).await.unwrap();
+        let _: TracedEvent = client.read_event().await.unwrap();
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with(
+            r#"] -> MoveMade (78 bytes) {"kind":"MoveMade","version":7,"sequence":0,"payload":{"MoveMade":{"cell":4}}}"#
+        ));
+        assert!(lines[1].ends_with(
+            r#"] <- Shutdown (65 bytes) {"kind":"Shutdown","version":7,"sequence":0,"payload":"Shutdown"}"#
+        ));
+    }
+
+    #[tokio::test]
+    async fn websocket_connection_rejects_oversized_messages() {
+        let (mut server, mut client) = get_websocket_connections().await;
+
+        client
+            .write_event(&TestEvent {
+                content: "a".repeat(MAX_EVENT_LENGTH),
+            })
+            .await
+            .unwrap();
+        let result: Result<TestEvent, ReadError> = server.read_event().await;
+        assert!(matches!(
+            result,
+            Err(ReadError::InvalidMessageLength {
+                max_length: MAX_EVENT_LENGTH,
+                ..
+            })
+        ));
+    }
+
+    /// Writes the frame to the stream as the other end of a connection would, however malformed.
+    async fn write_frame(stream: &mut tokio::io::DuplexStream, frame: &str) {
+        stream
+            .write_all(&(frame.len() as u32).to_be_bytes())
+            .await
+            .unwrap();
+        stream.write_all(frame.as_bytes()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn connection_refuses_events_out_of_sequence() {
+        let shutdown = |sequence| {
+            format!(
+                r#"{{"kind":"Shutdown","version":{},"sequence":{},"payload":"Shutdown"}}"#,
+                SCHEMA_VERSION, sequence
+            )
+        };
+
+        // A duplicated event
+        let (stream, mut other) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        let mut connection = Connection::new(stream);
+        write_frame(&mut other, &shutdown(0)).await;
+        write_frame(&mut other, &shutdown(0)).await;
+        connection.read_event::<serde_json::Value>().await.unwrap();
+        let error = connection
+            .read_event::<serde_json::Value>()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ReadError::OutOfSequence {
+                expected: 1,
+                received: 0
+            }
+        ));
+        assert_eq!(error.category(), ErrorCategory::Protocol);
+
+        // An event arriving before those sent ahead of it
+        let (stream, mut other) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        let mut connection = Connection::new(stream);
+        write_frame(&mut other, &shutdown(1)).await;
+        assert!(matches!(
+            connection.read_event::<serde_json::Value>().await,
+            Err(ReadError::OutOfSequence {
+                expected: 0,
+                received: 1
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn connection_reads_on_past_event_it_cannot_read() {
+        let (stream, mut other) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        let mut connection = Connection::new(stream);
+        write_frame(&mut other, "not an event").await;
+        let shutdown = format!(
+            r#"{{"kind":"Shutdown","version":{},"sequence":1,"payload":"Shutdown"}}"#,
+            SCHEMA_VERSION
+        );
+        write_frame(&mut other, &shutdown).await;
+
+        let error = connection
+            .read_event::<serde_json::Value>()
+            .await
+            .unwrap_err();
+        assert_eq!(error.category(), ErrorCategory::Deserialisation);
+        assert_eq!(
+            connection.read_event::<serde_json::Value>().await.unwrap(),
+            "Shutdown"
+        );
+    }
+
+    #[tokio::test]
+    async fn read_abandoned_partway_through_frame_resumes_where_it_left_off() {
+        let (stream, mut other) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        let mut connection = Connection::new(stream);
+        let frame = format!(
+            r#"{{"kind":"","version":{},"sequence":0,"payload":{{"content":"Split"}}}}"#,
+            SCHEMA_VERSION
+        );
+        let (start, end) = frame.split_at(frame.len() / 2);
+
+        other
+            .write_all(&(frame.len() as u32).to_be_bytes())
+            .await
+            .unwrap();
+        other.write_all(start.as_bytes()).await.unwrap();
+        let abandoned = time::timeout(
+            Duration::from_millis(10),
+            connection.read_event::<TestEvent>(),
+        )
+        .await;
+        assert!(abandoned.is_err());
+
+        other.write_all(end.as_bytes()).await.unwrap();
+        let event: TestEvent = connection.read_event().await.unwrap();
+        assert_eq!(event.content, "Split");
+    }
+
+    #[tokio::test]
+    async fn connection_skips_events_of_kind_added_by_newer_schema() {
+        let (stream, mut other) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        let mut connection = Connection::new(stream);
+        let frames = [
+            format!(
+                r#"{{"kind":"Chatted","version":{},"sequence":0,"payload":{{"Chatted":{{"message":"Hi"}}}}}}"#,
+                SCHEMA_VERSION + 1
+            ),
+            format!(
+                r#"{{"kind":"MoveMade","version":{},"sequence":1,"payload":{{"MoveMade":{{"cell":4}}}}}}"#,
+                SCHEMA_VERSION + 1
+            ),
+        ];
+        for frame in frames {
+            write_frame(&mut other, &frame).await;
+        }
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum KnownEvent {
+            MoveMade { cell: u8 },
+        }
+        assert_eq!(
+            connection.read_event::<KnownEvent>().await.unwrap(),
+            KnownEvent::MoveMade { cell: 4 }
+        );
+    }
+
+    #[tokio::test]
+    async fn connection_refuses_event_over_max_length_with_its_length() {
+        let (mut server, mut client) = Connection::pair();
+        server.set_max_event_length(16);
+
+        client
+            .write_event(&TestEvent {
+                content: "a".repeat(20),
+            })
+            .await
+            .unwrap();
+        let result: Result<TestEvent, ReadError> = server.read_event().await;
+        // The event is the content along with the JSON of the event and its envelope around it
+        assert!(matches!(
+            result,
+            Err(ReadError::InvalidMessageLength {
+                length: 88,
+                max_length: 16
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn connections_accept_events_longer_than_default_once_allowed() {
+        let event = TestEvent {
+            content: "a".repeat(4 * MAX_EVENT_LENGTH),
+        };
+        let (mut server, mut client) = Connection::pair();
+        let (mut websocket_server, mut websocket_client) = get_websocket_connections().await;
+
+        for (server, client) in [
+            (&mut server, &mut client),
+            (&mut websocket_server, &mut websocket_client),
+        ] {
+            server.set_max_event_length(8 * MAX_EVENT_LENGTH);
+            let (sent, received) =
+                tokio::join!(client.write_event(&event), server.read_event::<TestEvent>());
+
+            sent.unwrap();
+            assert_eq!(received.unwrap().content, event.content);
+        }
+    }
+
+    #[tokio::test]
+    async fn compressed_connections_compress_only_long_events() {
+        let (mut server, mut client) = Connection::pair();
+        server.set_compression(Some(Compression::Deflate));
+        client.set_compression(Some(Compression::Deflate));
+        let long_event = TestEvent {
+            content: "cell ".repeat(200),
+        };
+
+        client.write_event(&long_event).await.unwrap();
+        client
+            .write_event(&TestEvent {
+                content: String::from("Short"),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            server.read_event::<TestEvent>().await.unwrap().content,
+            long_event.content
+        );
+        assert_eq!(
+            server.read_event::<TestEvent>().await.unwrap().content,
+            "Short"
+        );
+
+        // Only the long event was compressed, coming out shorter on the wire
+        let sent = client.compression_stats();
+        assert_eq!(sent.frames_sent, 1);
+        assert!(sent.bytes_sent < sent.bytes_before_sending);
+        let received = server.compression_stats();
+        assert_eq!(received.frames_received, 1);
+        assert_eq!(received.bytes_received, sent.bytes_sent);
+        assert_eq!(received.bytes_after_receiving, sent.bytes_before_sending);
+    }
+
+    #[tokio::test]
+    async fn connection_without_compression_refuses_compressed_event() {
+        let (mut server, mut client) = Connection::pair();
+        client.set_compression(Some(Compression::Deflate));
+
+        client
+            .write_event(&TestEvent {
+                content: "cell ".repeat(200),
+            })
+            .await
+            .unwrap();
+        // The flag is taken as part of the length, which is far too long
+        assert!(matches!(
+            server.read_event::<TestEvent>().await,
+            Err(ReadError::InvalidMessageLength { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn read_times_out_once_nothing_received_for_timeout() {
+        let (mut server, mut client) = Connection::pair();
+        server.set_read_timeout(Some(Duration::from_secs(10)));
+
+        // Abandoning a read does not restart the time allowed
+        let abandoned = time::timeout(Duration::from_secs(6), server.read_event::<TestEvent>());
+        assert!(abandoned.await.is_err());
+        match server.read_event::<TestEvent>().await {
+            Err(error @ ReadError::Timeout { .. }) => {
+                assert_eq!(error.category(), ErrorCategory::Timeout);
+            }
+            _ => panic!("Expected the read to time out"),
+        }
+
+        // Receiving an event does
+        time::advance(Duration::from_secs(6)).await;
+        client
+            .write_event(&TestEvent {
+                content: "move".to_string(),
+            })
+            .await
+            .unwrap();
+        server.read_event::<TestEvent>().await.unwrap();
+        let early = time::timeout(Duration::from_secs(9), server.read_event::<TestEvent>());
+        assert!(early.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn websocket_connection_reports_closed_connection_as_read_error() {
+        let (mut server, mut client) = get_websocket_connections().await;
+
+        client.shutdown().await.unwrap();
+        let result: Result<TestEvent, ReadError> = server.read_event().await;
+        assert!(matches!(result, Err(ReadError::Read(_))));
+    }
+}
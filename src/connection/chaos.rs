@@ -0,0 +1,242 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::{self, Sleep};
+
+/// The faults to inject into a stream, simulating a poor network for testing how each end of a
+/// connection copes with it.
+///
+/// Random faults are decided by a generator seeded from `seed`, so the same faults happen at the
+/// same points every time.
+#[derive(Debug, Clone, Default)]
+pub struct Chaos {
+    /// How long each read and write waits before reaching the stream.
+    pub latency: Duration,
+    /// The most bytes passed to the stream by a single write, splitting events across writes.
+    pub max_write_size: Option<usize>,
+    /// Disconnects the stream once this many bytes have been written to it.
+    pub disconnect_after_bytes: Option<usize>,
+    /// The chance of each write disconnecting the stream rather than being made, from 0 to 1.
+    pub disconnect_chance: f64,
+    pub seed: u64,
+}
+
+/// Wraps a stream to inject the given `Chaos` into its reads and writes. Once disconnected, every
+/// read and write fails as if the other end reset the connection.
+pub struct ChaosStream<S> {
+    inner: S,
+    chaos: Chaos,
+    rng: StdRng,
+    read_delay: Option<Pin<Box<Sleep>>>,
+    write_delay: Option<Pin<Box<Sleep>>>,
+    written: usize,
+    disconnected: bool,
+}
+
+impl<S> ChaosStream<S> {
+    pub fn new(inner: S, chaos: Chaos) -> ChaosStream<S> {
+        ChaosStream {
+            inner,
+            rng: StdRng::seed_from_u64(chaos.seed),
+            chaos,
+            read_delay: None,
+            write_delay: None,
+            written: 0,
+            disconnected: false,
+        }
+    }
+
+    fn check_connected(&self) -> io::Result<()> {
+        if self.disconnected {
+            return Err(io::ErrorKind::ConnectionReset.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Waits out the latency of the chaos, starting the wait on the first poll of each read or write.
+fn poll_latency(
+    delay: &mut Option<Pin<Box<Sleep>>>,
+    latency: Duration,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    if latency.is_zero() {
+        return Poll::Ready(());
+    }
+
+    let sleep = delay.get_or_insert_with(|| Box::pin(time::sleep(latency)));
+    ready!(sleep.as_mut().poll(cx));
+    *delay = None;
+
+    Poll::Ready(())
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for ChaosStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check_connected()?;
+        ready!(poll_latency(&mut this.read_delay, this.chaos.latency, cx));
+
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for ChaosStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        this.check_connected()?;
+        ready!(poll_latency(&mut this.write_delay, this.chaos.latency, cx));
+
+        let remaining = this
+            .chaos
+            .disconnect_after_bytes
+            .map(|limit| limit.saturating_sub(this.written));
+        if remaining == Some(0) || this.rng.gen_bool(this.chaos.disconnect_chance) {
+            this.disconnected = true;
+            return Poll::Ready(Err(io::ErrorKind::ConnectionReset.into()));
+        }
+
+        let length = [Some(buf.len()), this.chaos.max_write_size, remaining]
+            .into_iter()
+            .flatten()
+            .min()
+            .unwrap_or(buf.len());
+        let written = ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..length]))?;
+        this.written += written;
+
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check_connected()?;
+
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+    use tokio::io::AsyncWriteExt;
+
+    use crate::connection::{Connection, ReadError, WriteError, MAX_EVENT_LENGTH};
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TestEvent {
+        content: String,
+    }
+
+    fn test_event() -> TestEvent {
+        TestEvent {
+            content: String::from("Sent through the chaos"),
+        }
+    }
+
+    /// Joins two connections in memory, with the chaos injected into the first.
+    fn chaotic_pair(chaos: Chaos) -> (Connection, Connection) {
+        let (one, two) = tokio::io::duplex(MAX_EVENT_LENGTH);
+        (
+            Connection::new(ChaosStream::new(one, chaos)),
+            Connection::new(two),
+        )
+    }
+
+    #[tokio::test]
+    async fn events_arrive_whole_despite_partial_writes() {
+        let (mut chaotic, mut other) = chaotic_pair(Chaos {
+            max_write_size: Some(3),
+            ..Chaos::default()
+        });
+
+        chaotic.write_event(&test_event()).await.unwrap();
+        other.write_event(&test_event()).await.unwrap();
+
+        assert_eq!(other.read_event::<TestEvent>().await.unwrap(), test_event());
+        assert_eq!(
+            chaotic.read_event::<TestEvent>().await.unwrap(),
+            test_event()
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn reads_and_writes_wait_out_latency() {
+        let (mut chaotic, mut other) = chaotic_pair(Chaos {
+            latency: Duration::from_millis(200),
+            ..Chaos::default()
+        });
+        let started = time::Instant::now();
+
+        chaotic.write_event(&test_event()).await.unwrap();
+        other.read_event::<TestEvent>().await.unwrap();
+
+        // Both the length and the event itself are delayed, along with the flush being free
+        assert_eq!(started.elapsed(), Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn stream_disconnects_after_writing_given_bytes() {
+        let (mut chaotic, mut other) = chaotic_pair(Chaos {
+            disconnect_after_bytes: Some(10),
+            ..Chaos::default()
+        });
+
+        assert!(matches!(
+            chaotic.write_event(&test_event()).await,
+            Err(WriteError::Write(_))
+        ));
+        assert!(matches!(
+            chaotic.read_event::<TestEvent>().await,
+            Err(ReadError::Read(_))
+        ));
+
+        // Only the bytes written before disconnecting reach the other end
+        drop(chaotic);
+        assert!(matches!(
+            other.read_event::<TestEvent>().await,
+            Err(ReadError::Read(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn random_disconnects_repeat_for_same_seed() {
+        let chaos = Chaos {
+            disconnect_chance: 0.2,
+            seed: 7,
+            ..Chaos::default()
+        };
+
+        let mut writes_made = Vec::new();
+        for _ in 0..2 {
+            let mut stream = ChaosStream::new(tokio::io::sink(), chaos.clone());
+            let mut writes = 0;
+            while stream.write_all(b"byte").await.is_ok() {
+                writes += 1;
+            }
+            writes_made.push(writes);
+        }
+
+        assert_eq!(writes_made[0], writes_made[1]);
+    }
+}
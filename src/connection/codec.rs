@@ -0,0 +1,156 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// The codecs this build can encode and decode, in order of preference.
+pub const SUPPORTED_CODECS: [CodecKind; 3] =
+    [CodecKind::Bincode, CodecKind::MessagePack, CodecKind::Json];
+
+pub trait Codec {
+    fn encode<T: Serialize>(&self, event: &T) -> Result<Vec<u8>, CodecError>;
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError>;
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Json;
+
+impl Codec for Json {
+    fn encode<T: Serialize>(&self, event: &T) -> Result<Vec<u8>, CodecError> {
+        Ok(serde_json::to_vec(event)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Bincode;
+
+impl Codec for Bincode {
+    fn encode<T: Serialize>(&self, event: &T) -> Result<Vec<u8>, CodecError> {
+        Ok(bincode::serialize(event)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct MessagePack;
+
+impl Codec for MessagePack {
+    fn encode<T: Serialize>(&self, event: &T) -> Result<Vec<u8>, CodecError> {
+        Ok(rmp_serde::to_vec(event)?)
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        Ok(rmp_serde::from_slice(bytes)?)
+    }
+}
+
+/// Identifies a codec on the wire, allowing both ends of a connection to agree on one.
+#[derive(PartialEq, Debug, Default, Copy, Clone, Serialize, Deserialize)]
+pub enum CodecKind {
+    #[default]
+    Json,
+    Bincode,
+    MessagePack,
+}
+
+impl CodecKind {
+    /// Chooses the codec to use for a connection from those offered by the other end.
+    ///
+    /// # Returns
+    ///
+    /// The first offered codec that is also supported, falling back to JSON if there are none.
+    pub fn negotiate(offered: &[CodecKind]) -> CodecKind {
+        offered
+            .iter()
+            .copied()
+            .find(|codec| SUPPORTED_CODECS.contains(codec))
+            .unwrap_or_default()
+    }
+}
+
+impl Codec for CodecKind {
+    fn encode<T: Serialize>(&self, event: &T) -> Result<Vec<u8>, CodecError> {
+        match self {
+            CodecKind::Json => Json.encode(event),
+            CodecKind::Bincode => Bincode.encode(event),
+            CodecKind::MessagePack => MessagePack.encode(event),
+        }
+    }
+
+    fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, CodecError> {
+        match self {
+            CodecKind::Json => Json.decode(bytes),
+            CodecKind::Bincode => Bincode.decode(bytes),
+            CodecKind::MessagePack => MessagePack.decode(bytes),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CodecError {
+    #[error("Invalid JSON")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid bincode")]
+    Bincode(#[from] bincode::Error),
+    #[error("Failed to encode MessagePack")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("Invalid MessagePack")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(PartialEq, Debug, Serialize, Deserialize)]
+    enum TestEvent {
+        Unit,
+        Struct { id: u8, content: Option<String> },
+        Bytes(Vec<u8>),
+    }
+
+    #[test]
+    fn every_supported_codec_round_trips_events() {
+        let events = [
+            TestEvent::Unit,
+            TestEvent::Struct {
+                id: 2,
+                content: Some(String::from("content")),
+            },
+            TestEvent::Bytes(vec![1, 2, 3]),
+        ];
+
+        for codec in SUPPORTED_CODECS {
+            for event in &events {
+                let encoded = codec.encode(event).unwrap();
+                let decoded: TestEvent = codec.decode(&encoded).unwrap();
+                assert_eq!(&decoded, event, "Round trip failed for {:?}", codec);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_rejects_invalid_bytes() {
+        for codec in SUPPORTED_CODECS {
+            let result: Result<TestEvent, CodecError> = codec.decode(b"\xff\xff\xff");
+            assert!(result.is_err(), "Expected error for {:?}", codec);
+        }
+    }
+
+    #[test]
+    fn negotiate_chooses_first_offered_codec() {
+        let offered = [CodecKind::MessagePack, CodecKind::Bincode];
+
+        assert_eq!(CodecKind::negotiate(&offered), CodecKind::MessagePack);
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_json_when_nothing_offered() {
+        assert_eq!(CodecKind::negotiate(&[]), CodecKind::Json);
+    }
+}
@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+
+/// The compression this build can apply to frames, in order of preference.
+pub const SUPPORTED_COMPRESSION: [Compression; 1] = [Compression::Deflate];
+/// The shortest frame, in bytes, that is worth compressing. Shorter frames, such as most moves,
+/// gain too little to be worth the time.
+const MIN_COMPRESSED_LENGTH: usize = 256;
+/// Favours speed over size, as frames are compressed while the other end waits on them.
+const DEFLATE_LEVEL: u8 = 3;
+
+/// Identifies how frames may be compressed on the wire, allowing both ends of a connection to
+/// agree on it.
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Compression {
+    Deflate,
+}
+
+impl Compression {
+    /// Chooses the compression to use for a connection from that offered by the other end.
+    ///
+    /// # Returns
+    ///
+    /// The first offered compression that is also supported, or `None` if there is none.
+    pub fn negotiate(offered: &[Compression]) -> Option<Compression> {
+        offered
+            .iter()
+            .copied()
+            .find(|compression| SUPPORTED_COMPRESSION.contains(compression))
+    }
+
+    /// Compresses the frame, unless it is too short to be worth it or compressing it does not make
+    /// it any shorter.
+    pub(crate) fn compress(&self, frame: &[u8]) -> Option<Vec<u8>> {
+        if frame.len() < MIN_COMPRESSED_LENGTH {
+            return None;
+        }
+        let compressed = match self {
+            Compression::Deflate => miniz_oxide::deflate::compress_to_vec(frame, DEFLATE_LEVEL),
+        };
+
+        (compressed.len() < frame.len()).then_some(compressed)
+    }
+
+    /// Decompresses the frame, failing if it is not validly compressed or would be longer than
+    /// `max_length` once it has been.
+    pub(crate) fn decompress(
+        &self,
+        frame: &[u8],
+        max_length: usize,
+    ) -> Result<Vec<u8>, DecompressError> {
+        match self {
+            Compression::Deflate => {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(frame, max_length)
+                    .map_err(|_| DecompressError)
+            }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("The event is not validly compressed, or is too long once decompressed")]
+pub struct DecompressError;
+
+/// How much compression has saved on a connection, counting only the frames it was applied to.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct CompressionStats {
+    pub frames_sent: u64,
+    /// The length of the frames sent before they were compressed, in bytes.
+    pub bytes_before_sending: u64,
+    /// The length of the frames sent as they went over the wire, in bytes.
+    pub bytes_sent: u64,
+    pub frames_received: u64,
+    /// The length of the frames received as they came over the wire, in bytes.
+    pub bytes_received: u64,
+    /// The length of the frames received once they were decompressed, in bytes.
+    pub bytes_after_receiving: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_frames_round_trip() {
+        let frame = "cell ".repeat(100).into_bytes();
+
+        for compression in SUPPORTED_COMPRESSION {
+            let compressed = compression.compress(&frame).unwrap();
+            assert!(compressed.len() < frame.len());
+            assert_eq!(
+                compression.decompress(&compressed, frame.len()).unwrap(),
+                frame
+            );
+        }
+    }
+
+    #[test]
+    fn short_or_incompressible_frames_left_as_they_are() {
+        let incompressible: Vec<u8> = (0..MIN_COMPRESSED_LENGTH * 2)
+            .map(|_| rand::random())
+            .collect();
+
+        assert_eq!(Compression::Deflate.compress(b"short"), None);
+        assert_eq!(Compression::Deflate.compress(&incompressible), None);
+    }
+
+    #[test]
+    fn frame_longer_than_limit_once_decompressed_refused() {
+        let frame = vec![0; 4096];
+        let compressed = Compression::Deflate.compress(&frame).unwrap();
+
+        assert!(Compression::Deflate.decompress(&compressed, 1024).is_err());
+        assert!(Compression::Deflate
+            .decompress(b"\xff\xff\xff", 1024)
+            .is_err());
+    }
+
+    #[test]
+    fn negotiate_chooses_offered_compression_if_supported() {
+        assert_eq!(
+            Compression::negotiate(&[Compression::Deflate]),
+            Some(Compression::Deflate)
+        );
+        assert_eq!(Compression::negotiate(&[]), None);
+    }
+}
@@ -0,0 +1,288 @@
+use serde::de::{self, DeserializeOwned, IgnoredAny, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::connection::codec::{Codec, CodecError};
+use crate::connection::trace;
+
+/// The version of the schema that events are sent in. Incremented whenever a kind of event is
+/// added, so that an event of a kind the other end does not know can be told apart from one that
+/// is malformed, and skipped rather than ending the game.
+pub const SCHEMA_VERSION: u16 = 7;
+
+/// Wraps every event sent over the wire, naming the kind of event it holds along with the schema
+/// version it was sent in.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Envelope<P> {
+    /// The name of the event's variant, worked out in the same way as for protocol traces, and
+    /// left empty where it cannot be.
+    kind: String,
+    version: u16,
+    /// Counts the events sent in one direction over a connection, so that the other end can
+    /// notice any being dropped, duplicated or reordered. Left at zero for game events, as these
+    /// are nested within an event of their own.
+    sequence: u64,
+    payload: P,
+}
+
+impl<'a, T: Serialize> Envelope<&'a T> {
+    pub(crate) fn wrap(event: &'a T) -> Envelope<&'a T> {
+        let kind = serde_json::to_value(event)
+            .ok()
+            .and_then(|decoded| trace::event_name(&decoded).map(str::to_owned))
+            .unwrap_or_default();
+
+        Envelope {
+            kind,
+            version: SCHEMA_VERSION,
+            sequence: 0,
+            payload: event,
+        }
+    }
+}
+
+impl<P> Envelope<P> {
+    pub(crate) fn with_sequence(self, sequence: u64) -> Envelope<P> {
+        Envelope { sequence, ..self }
+    }
+
+    pub(crate) fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    pub(crate) fn into_payload(self) -> P {
+        self.payload
+    }
+}
+
+/// The kind and schema version of an event, read without decoding the event itself.
+#[derive(Deserialize)]
+struct Header {
+    kind: String,
+    version: u16,
+    sequence: u64,
+}
+
+/// Why an event could not be taken out of its envelope.
+#[derive(Debug)]
+pub(crate) enum OpenError {
+    /// The event is of a kind added in a newer schema than this one, so can be skipped.
+    Unknown {
+        kind: String,
+        version: u16,
+        sequence: u64,
+    },
+    Codec(CodecError),
+}
+
+/// Decodes the envelope encoded in the bytes, along with the event it holds.
+pub(crate) fn open<T: DeserializeOwned>(
+    codec: &impl Codec,
+    bytes: &[u8],
+) -> Result<Envelope<T>, OpenError> {
+    match codec.decode::<Envelope<T>>(bytes) {
+        Ok(envelope) => Ok(envelope),
+        Err(error) => Err(classify::<T>(read_header(codec, bytes), error)),
+    }
+}
+
+/// Takes the event out of an envelope already decoded as a JSON value, as game events are carried
+/// within the events of the connection.
+pub(crate) fn open_value<T: DeserializeOwned>(
+    value: &serde_json::Value,
+) -> Result<Envelope<T>, OpenError> {
+    match Envelope::<T>::deserialize(value) {
+        Ok(envelope) => Ok(envelope),
+        Err(error) => Err(classify::<T>(
+            Header::deserialize(value).ok(),
+            CodecError::Json(error),
+        )),
+    }
+}
+
+/// Works out why an event of type `T` could not be decoded from the envelope with the given
+/// header.
+fn classify<T: DeserializeOwned>(header: Option<Header>, error: CodecError) -> OpenError {
+    // Only an event that names a variant the type lacks, and comes from a newer schema, is
+    // unknown rather than malformed
+    let variants = variant_names::<T>();
+    match header {
+        Some(Header {
+            kind,
+            version,
+            sequence,
+        }) if version > SCHEMA_VERSION
+            && !variants.is_empty()
+            && !variants.contains(&kind.as_str()) =>
+        {
+            OpenError::Unknown {
+                kind,
+                version,
+                sequence,
+            }
+        }
+        _ => OpenError::Codec(error),
+    }
+}
+
+/// Reads the header of the envelope encoded in the bytes. Self-describing codecs can skip over
+/// the event that follows it, while others can only stop reading before reaching the event.
+fn read_header(codec: &impl Codec, bytes: &[u8]) -> Option<Header> {
+    if let Ok(envelope) = codec.decode::<Envelope<IgnoredAny>>(bytes) {
+        return Some(Header {
+            kind: envelope.kind,
+            version: envelope.version,
+            sequence: envelope.sequence,
+        });
+    }
+
+    codec.decode::<Header>(bytes).ok()
+}
+
+/// The names of the variants of `T`, or none if it is not an enum, found by having `T` describe
+/// itself to a deserializer that reads nothing.
+fn variant_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    struct VariantNames<'a>(&'a mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for VariantNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not an enum"))
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(
+            self,
+            _name: &'static str,
+            variants: &'static [&'static str],
+            _visitor: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = variants;
+            Err(de::Error::custom("variants found"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+            ignored_any
+        }
+    }
+
+    let mut variants: &'static [&'static str] = &[];
+    let _ = T::deserialize(VariantNames(&mut variants));
+
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::connection::codec::SUPPORTED_CODECS;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum OldEvent {
+        Moved { cell: u8 },
+        Resigned,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum NewEvent {
+        Moved { cell: u8 },
+        Resigned,
+        Chatted { message: String },
+    }
+
+    /// Encodes the event as though sent by a build using the given schema version.
+    fn encode_from_schema(codec: &impl Codec, event: &NewEvent, version: u16) -> Vec<u8> {
+        let mut envelope = Envelope::wrap(event);
+        envelope.version = version;
+        codec.encode(&envelope).unwrap()
+    }
+
+    #[test]
+    fn wrap_names_event_by_its_variant() {
+        assert_eq!(Envelope::wrap(&OldEvent::Moved { cell: 4 }).kind, "Moved");
+        assert_eq!(Envelope::wrap(&OldEvent::Resigned).kind, "Resigned");
+        assert_eq!(Envelope::wrap(&vec![1, 2]).kind, "");
+    }
+
+    #[test]
+    fn every_codec_opens_known_events() {
+        for codec in SUPPORTED_CODECS {
+            let bytes = codec
+                .encode(&Envelope::wrap(&OldEvent::Moved { cell: 4 }))
+                .unwrap();
+
+            assert_eq!(
+                open::<OldEvent>(&codec, &bytes).unwrap().into_payload(),
+                OldEvent::Moved { cell: 4 },
+                "Failed to open event with {:?}",
+                codec
+            );
+        }
+    }
+
+    #[test]
+    fn every_codec_reports_events_from_newer_schema_as_unknown() {
+        let event = NewEvent::Chatted {
+            message: String::from("Good game"),
+        };
+
+        for codec in SUPPORTED_CODECS {
+            let bytes = encode_from_schema(&codec, &event, SCHEMA_VERSION + 1);
+
+            assert!(
+                matches!(
+                    open::<OldEvent>(&codec, &bytes),
+                    Err(OpenError::Unknown { kind, version, .. })
+                        if kind == "Chatted" && version == SCHEMA_VERSION + 1
+                ),
+                "Expected unknown event with {:?}",
+                codec
+            );
+        }
+    }
+
+    #[test]
+    fn value_from_newer_schema_reported_as_unknown() {
+        let event = NewEvent::Chatted {
+            message: String::from("Good game"),
+        };
+        let mut envelope = Envelope::wrap(&event);
+        envelope.version = SCHEMA_VERSION + 1;
+        let value = serde_json::to_value(&envelope).unwrap();
+
+        assert!(matches!(
+            open_value::<OldEvent>(&value),
+            Err(OpenError::Unknown { kind, .. }) if kind == "Chatted"
+        ));
+        assert_eq!(
+            open_value::<NewEvent>(&value).unwrap().into_payload(),
+            NewEvent::Chatted {
+                message: String::from("Good game")
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_kind_from_same_schema_is_malformed() {
+        let event = NewEvent::Chatted {
+            message: String::from("Good game"),
+        };
+
+        for codec in SUPPORTED_CODECS {
+            let bytes = encode_from_schema(&codec, &event, SCHEMA_VERSION);
+
+            assert!(matches!(
+                open::<OldEvent>(&codec, &bytes),
+                Err(OpenError::Codec(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn variant_names_lists_variants_of_enums_only() {
+        assert_eq!(variant_names::<OldEvent>(), ["Moved", "Resigned"]);
+        assert!(variant_names::<Vec<u8>>().is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Which way a traced frame travelled over the connection.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Direction {
+    Sent,
+    Received,
+}
+
+/// Writes a line describing every frame sent or received over a connection, for seeing exactly
+/// what is on the wire while developing a game.
+///
+/// Each line gives the time since tracing began, the direction of the frame, the name of the
+/// event it holds where this can be worked out, and the frame itself. Frames are shown as text
+/// when they are readable, such as when encoded as JSON, and as hex otherwise.
+#[derive(Clone)]
+pub struct ProtocolTrace {
+    output: Arc<Mutex<dyn io::Write + Send>>,
+    started: Instant,
+}
+
+impl ProtocolTrace {
+    pub fn new(output: Arc<Mutex<dyn io::Write + Send>>) -> ProtocolTrace {
+        ProtocolTrace {
+            output,
+            started: Instant::now(),
+        }
+    }
+
+    /// Records the frame, ignoring any failure to write the trace so that it never interrupts
+    /// the game.
+    pub(crate) fn record(
+        &self,
+        direction: Direction,
+        frame: &[u8],
+        decoded: Option<&serde_json::Value>,
+    ) {
+        let _ = writeln!(
+            &mut self.output.lock().unwrap(),
+            "{}",
+            describe_frame(
+                self.started.elapsed().as_secs_f64(),
+                direction,
+                frame,
+                decoded
+            )
+        );
+    }
+}
+
+fn describe_frame(
+    elapsed_secs: f64,
+    direction: Direction,
+    frame: &[u8],
+    decoded: Option<&serde_json::Value>,
+) -> String {
+    let arrow = match direction {
+        Direction::Sent => "->",
+        Direction::Received => "<-",
+    };
+    let name = decoded.and_then(event_name).unwrap_or("?");
+
+    format!(
+        "[{:>9.3}s] {} {} ({} bytes) {}",
+        elapsed_secs,
+        arrow,
+        name,
+        frame.len(),
+        format_frame(frame)
+    )
+}
+
+/// The name of the event, taken from the variant of the enum it was serialised from.
+pub(crate) fn event_name(decoded: &serde_json::Value) -> Option<&str> {
+    match decoded {
+        // Unit variants are serialised as their name alone
+        serde_json::Value::String(name) => Some(name),
+        // Other variants are serialised as an object with their name as the only key
+        serde_json::Value::Object(fields) if fields.len() == 1 => {
+            fields.keys().next().map(String::as_str)
+        }
+        _ => None,
+    }
+}
+
+fn format_frame(frame: &[u8]) -> String {
+    match std::str::from_utf8(frame) {
+        Ok(text) if !text.chars().any(char::is_control) => text.to_string(),
+        _ => frame.iter().map(|byte| format!("{:02x}", byte)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn readable_frame_described_with_event_name() {
+        let frame = br#"{"ErrorOccurred":"InvalidMessage"}"#;
+        let decoded = json!({"ErrorOccurred": "InvalidMessage"});
+
+        assert_eq!(
+            describe_frame(1.5, Direction::Received, frame, Some(&decoded)),
+            r#"[    1.500s] <- ErrorOccurred (34 bytes) {"ErrorOccurred":"InvalidMessage"}"#
+        );
+    }
+
+    #[test]
+    fn unit_variant_named_after_itself() {
+        assert_eq!(event_name(&json!("Shutdown")), Some("Shutdown"));
+    }
+
+    #[test]
+    fn binary_frame_described_as_hex_without_name() {
+        assert_eq!(
+            describe_frame(0.0, Direction::Sent, &[0, 1, 255], None),
+            "[    0.000s] -> ? (3 bytes) 0001ff"
+        );
+    }
+
+    #[test]
+    fn record_writes_line_to_output() {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let trace = ProtocolTrace::new(Arc::clone(&output) as _);
+
+        trace.record(Direction::Sent, b"\"Shutdown\"", Some(&json!("Shutdown")));
+
+        let output = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert!(output.ends_with("] -> Shutdown (10 bytes) \"Shutdown\"\n"));
+    }
+}
@@ -0,0 +1,1060 @@
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::dots_and_boxes::board::Board;
+pub use crate::dots_and_boxes::board::{
+    Edge, GridConfig, InvalidEdge, Orientation, DEFAULT_GRID_SIZE,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod board;
+mod bot;
+
+/// Identifies Dots and Boxes among the games in `game::GAMES`.
+pub const GAME_ID: &str = "dots-and-boxes";
+
+/// Dots and Boxes, played on a square grid of boxes. Players take turns to draw an edge between
+/// two neighbouring dots, and whoever draws the fourth side of a box completes it and goes again.
+/// The game ends once every box is complete, won by the player who completed the most.
+#[derive(Debug, Copy, Clone)]
+pub struct DotsAndBoxes {
+    grid_config: GridConfig,
+}
+
+impl DotsAndBoxes {
+    pub const fn new(grid_config: GridConfig) -> DotsAndBoxes {
+        DotsAndBoxes { grid_config }
+    }
+}
+
+impl GameDescriptor for DotsAndBoxes {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(DotsAndBoxesServer::new(channel, self.grid_config))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => {
+                Box::new(DotsAndBoxesClient::new(frontend, channel, LocalClient {}))
+            }
+            ClientKind::Online { id } => Box::new(DotsAndBoxesClient::new(
+                frontend,
+                channel,
+                OnlineClient { id },
+            )),
+            ClientKind::Spectator => Box::new(DotsAndBoxesClient::new(
+                frontend,
+                channel,
+                SpectatorClient {},
+            )),
+            ClientKind::Bot { id, move_delay } => Box::new(DotsAndBoxesClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Outcome {
+    Draw,
+    WinnerFound { player_id: u8 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// The ID of the player who claimed each horizontal edge going along each row of dots, each
+    /// vertical edge going along each row of boxes, and who completed each box, all from the top.
+    BoardUpdated {
+        horizontal: Vec<Option<u8>>,
+        vertical: Vec<Option<u8>>,
+        boxes: Vec<Option<u8>>,
+    },
+    /// Sent before the board showing the edge, with the number of boxes it completed. A player
+    /// who completed any goes again.
+    EdgeClaimed {
+        player_id: u8,
+        edge: Edge,
+        completed: u8,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    GameOver {
+        outcome: Outcome,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The edge {edge} has already been claimed.")]
+    EdgeTaken { edge: Edge },
+    #[error("The edge {edge} is not on the grid.")]
+    OutOfBounds { edge: Edge },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct DotsAndBoxesServer {
+    current_player: u8,
+    board: Board,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl DotsAndBoxesServer {
+    pub fn new(
+        server_channel: Sender<GameServerEvent>,
+        grid_config: GridConfig,
+    ) -> DotsAndBoxesServer {
+        DotsAndBoxesServer {
+            current_player: PLAYER_ONE_ID,
+            board: Board::new(grid_config),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_board_updated_event(&self, dispatch_mode: DispatchMode) {
+        self.dispatch_event(
+            dispatch_mode,
+            ServerEvent::BoardUpdated {
+                horizontal: self.board.get_horizontal(),
+                vertical: self.board.get_vertical(),
+                boxes: self.board.get_boxes(),
+            },
+        )
+        .await
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, outcome: Outcome) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { outcome })
+            .await;
+
+        let winner = match outcome {
+            Outcome::Draw => None,
+            Outcome::WinnerFound { player_id } => Some(player_id),
+        };
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    /// Ends the current player's turn, handing it to the other player unless the current player
+    /// completed a box or the game is over.
+    async fn advance_turn(&mut self, completed: u8) {
+        if let Some(outcome) = self.board.determine_outcome() {
+            self.dispatch_game_over_event(outcome).await;
+            return;
+        }
+
+        if completed == 0 {
+            self.current_player = get_alternative_player_id(self.current_player);
+        }
+        self.rejected_moves.reset();
+
+        self.start_turn().await;
+    }
+
+    fn handle_edge_claimed_event(&mut self, player_id: u8, edge: Edge) -> Result<u8, Error> {
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.board.claim(player_id, edge)
+    }
+}
+
+#[async_trait]
+impl GameServer for DotsAndBoxesServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    fn config(&self) -> Vec<u8> {
+        game::serialize_config(self.board.get_config())
+    }
+
+    async fn begin(&self) {
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::EdgeClaimed { player_id, edge } = event;
+
+        let completed = match self.handle_edge_claimed_event(player_id, edge) {
+            Ok(completed) => completed,
+            Err(error) => {
+                self.dispatch_event(
+                    DispatchMode::SinglePlayer {
+                        player_id: self.current_player,
+                    },
+                    ServerEvent::ErrorOccurred { error },
+                )
+                .await;
+
+                if self
+                    .rejected_moves
+                    .reject(&self.server_channel, self.current_player, player_id)
+                    .await
+                {
+                    self.dispatch_game_over_event(Outcome::WinnerFound {
+                        player_id: get_alternative_player_id(player_id),
+                    })
+                    .await;
+                    return;
+                }
+
+                self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                    player_id: self.current_player,
+                })
+                .await;
+
+                return;
+            }
+        };
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::EdgeClaimed {
+                player_id,
+                edge,
+                completed,
+            },
+        )
+        .await;
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.advance_turn(completed).await;
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
+            }
+            TimeoutAction::SkipTurn => self.advance_turn(0).await,
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_board_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_board_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    EdgeClaimed { player_id: u8, edge: Edge },
+}
+
+pub struct DotsAndBoxesClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    board: Board,
+}
+
+impl<F, C> DotsAndBoxesClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> DotsAndBoxesClient<F, C> {
+        DotsAndBoxesClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            board: Board::new(GridConfig::DEFAULT),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    /// Paints the text in the colour of the player, or leaves blank space of the same width if
+    /// there is no player.
+    fn paint_owned(&self, owner: Option<u8>, text: impl Into<String>) -> String {
+        let text = text.into();
+        match owner {
+            Some(PLAYER_ONE_ID) => self.user_output.paint(text, Paint::PlayerOne),
+            Some(_) => self.user_output.paint(text, Paint::PlayerTwo),
+            None => " ".repeat(text.len()),
+        }
+    }
+
+    /// Shows the grid of dots with the edges claimed and the boxes completed by each player,
+    /// followed by the number of boxes each has completed.
+    async fn handle_board_updated_event(
+        &mut self,
+        horizontal: Vec<Option<u8>>,
+        vertical: Vec<Option<u8>>,
+        boxes: Vec<Option<u8>>,
+    ) -> Result<(), GameClientError> {
+        let config = self.board.get_config();
+        self.board = Board::from_lines(config, horizontal, vertical, boxes);
+
+        let size = config.size as u8;
+        let player_one_boxes = board::count_boxes(&self.board.get_boxes(), PLAYER_ONE_ID);
+        let player_two_boxes = board::count_boxes(&self.board.get_boxes(), PLAYER_TWO_ID);
+
+        let dot_row = |row: u8, edge: &str| -> String {
+            (0..size)
+                .map(|column| {
+                    let edge_owner =
+                        self.board
+                            .edge_owner(Edge::new(Orientation::Horizontal, row, column));
+                    format!("+{}", self.paint_owned(edge_owner, edge))
+                })
+                .collect::<String>()
+                + "+"
+        };
+        let box_row = |row: u8, edge: &str, pad: &str| -> String {
+            (0..=size)
+                .map(|column| {
+                    let edge_owner =
+                        self.board
+                            .edge_owner(Edge::new(Orientation::Vertical, row, column));
+                    let edge = self.paint_owned(edge_owner, edge);
+                    if column == size {
+                        return edge;
+                    }
+
+                    let box_icon = match self.board.box_owner(row, column) {
+                        Some(player_id) => self.paint_owned(
+                            Some(player_id),
+                            format!("{}{}{}", pad, self.get_player_icon_by_id(player_id), pad),
+                        ),
+                        None => format!("{} {}", pad, pad),
+                    };
+                    format!("{}{}", edge, box_icon)
+                })
+                .collect()
+        };
+
+        let board_output = match self.user_output.output_style() {
+            OutputStyle::Standard => {
+                let columns: Vec<String> = (0..=size)
+                    .map(|column| char::from(b'a' + column).to_string())
+                    .collect();
+                let mut lines = vec![format!("   {}", columns.join("   "))];
+                for row in 0..=size {
+                    lines.push(format!("{:>2} {}", row + 1, dot_row(row, "---")));
+                    if row < size {
+                        lines.push(format!("   {}", box_row(row, "|", " ")));
+                    }
+                }
+                lines.push(self.user_output.message(
+                    "dots-and-boxes-score",
+                    &[("first", &player_one_boxes), ("second", &player_two_boxes)],
+                ));
+
+                lines
+                    .iter()
+                    .map(|line| line.trim_end())
+                    .collect::<Vec<&str>>()
+                    .join("\n")
+            }
+            OutputStyle::Terse => {
+                let mut rows = Vec::new();
+                for row in 0..=size {
+                    rows.push(dot_row(row, "-"));
+                    if row < size {
+                        rows.push(box_row(row, "|", ""));
+                    }
+                }
+                format!(
+                    "{} X{}-{}O",
+                    rows.join("/"),
+                    player_one_boxes,
+                    player_two_boxes
+                )
+            }
+        };
+
+        self.user_output.write_line(board_output)?;
+
+        Ok(())
+    }
+
+    /// Points out the edges claimed by the other player, as a player already knows which they
+    /// claimed.
+    async fn handle_edge_claimed_event(
+        &self,
+        player_id: u8,
+        edge: Edge,
+        completed: u8,
+    ) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match completed {
+            0 => "dots-and-boxes-edge-claimed",
+            _ => "dots-and-boxes-edge-claimed-completing",
+        };
+        self.user_output.write_message(
+            key,
+            &[
+                ("player", &player_icon),
+                ("edge", &edge),
+                ("completed", &completed),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, outcome: Outcome) -> Result<(), GameClientError> {
+        match outcome {
+            Outcome::Draw => self.user_output.write_message("game-draw", &[])?,
+            Outcome::WinnerFound { player_id } => {
+                let player_icon = self.get_player_icon_by_id(player_id);
+                self.user_output
+                    .write_message("game-won", &[("player", &player_icon)])?
+            }
+        }
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::EdgeTaken { edge } => self
+                .user_output
+                .write_error("dots-and-boxes-error-edge-taken", &[("edge", &edge)]),
+            Error::OutOfBounds { edge } => self
+                .user_output
+                .write_error("dots-and-boxes-error-out-of-bounds", &[("edge", &edge)]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("dots-and-boxes-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let edge = self.get_edge().await?;
+        self.send_move(player_id, edge).await
+    }
+
+    async fn send_move(&mut self, player_id: u8, edge: Edge) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::EdgeClaimed { player_id, edge },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the edge the player claims, leaving the server to decide whether it can be claimed.
+    async fn get_edge(&mut self) -> Result<Edge, io::Error> {
+        loop {
+            self.user_output
+                .write_message("dots-and-boxes-move-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.parse() {
+                Err(_) => self
+                    .user_output
+                    .write_message("dots-and-boxes-not-an-edge", &[])?,
+                Ok(edge) => return Ok(edge),
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for DotsAndBoxesClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let player_icon = self.get_player_icon_by_id(PLAYER_ONE_ID);
+        self.user_output
+            .message("game-started-local-first", &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for DotsAndBoxesClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let player_icon = self.get_player_icon_by_id(self.client_type.id);
+        self.user_output
+            .message("game-started-online-as", &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for DotsAndBoxesClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for DotsAndBoxesClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(edge) = bot::choose_move(&self.board, player_id) {
+            self.send_move(player_id, edge).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for DotsAndBoxesClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, config: Vec<u8>) -> Result<(), GameClientError> {
+        // The grid is laid out as soon as the game starts, ahead of it first being sent
+        if let Some(grid_config) = game::deserialize_config::<GridConfig>(&config) {
+            self.board = Board::new(grid_config);
+        }
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::BoardUpdated {
+                horizontal,
+                vertical,
+                boxes,
+            } => {
+                self.handle_board_updated_event(horizontal, vertical, boxes)
+                    .await
+            }
+            ServerEvent::EdgeClaimed {
+                player_id,
+                edge,
+                completed,
+            } => {
+                self.handle_edge_claimed_event(player_id, edge, completed)
+                    .await
+            }
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { outcome } => self.handle_game_over_event(outcome).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        DotsAndBoxesClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = DotsAndBoxesClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    fn edge(name: &str) -> Edge {
+        name.parse().unwrap()
+    }
+
+    async fn send_move(server: &mut DotsAndBoxesServer, player_id: u8, name: &str) {
+        server
+            .handle_event(ClientEvent::EdgeClaimed {
+                player_id,
+                edge: edge(name),
+            })
+            .await;
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    /// Sets up a board with the edges claimed by player one.
+    fn board_with(config: GridConfig, edges: &[&str]) -> Board {
+        let mut board = Board::new(config);
+        for name in edges {
+            board.claim(PLAYER_ONE_ID, edge(name)).unwrap();
+        }
+
+        board
+    }
+
+    #[tokio::test]
+    async fn server_claims_edge_and_passes_turn() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = DotsAndBoxesServer::new(sender, GridConfig::DEFAULT);
+
+        send_move(&mut server, PLAYER_ONE_ID, "a1-b1").await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::EdgeClaimed {
+                    player_id: PLAYER_ONE_ID,
+                    completed: 0,
+                    ..
+                },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_gives_another_turn_for_completing_box() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = DotsAndBoxesServer::new(sender, GridConfig::DEFAULT);
+        server.board = board_with(GridConfig::DEFAULT, &["a1-b1", "a1-a2", "b1-b2"]);
+        server.current_player = PLAYER_TWO_ID;
+
+        send_move(&mut server, PLAYER_TWO_ID, "a2-b2").await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::EdgeClaimed {
+                    player_id: PLAYER_TWO_ID,
+                    completed: 1,
+                    ..
+                },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_claimed_edge() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = DotsAndBoxesServer::new(sender, GridConfig::DEFAULT);
+        server.board = board_with(GridConfig::DEFAULT, &["a1-b1"]);
+
+        send_move(&mut server, PLAYER_ONE_ID, "b1-a1").await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::EdgeTaken { .. }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_every_box_completed() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let config = GridConfig { size: 1 };
+        let mut server = DotsAndBoxesServer::new(sender, config);
+        server.board = board_with(config, &["a1-b1", "a1-a2", "b1-b2"]);
+
+        send_move(&mut server, PLAYER_ONE_ID, "a2-b2").await;
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver).last(),
+            Some(ServerEvent::GameOver {
+                outcome: Outcome::WinnerFound {
+                    player_id: PLAYER_ONE_ID
+                }
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_sends_grid_size_as_config() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(10);
+        let server = DotsAndBoxesServer::new(sender, GridConfig { size: 5 });
+
+        assert_eq!(
+            game::deserialize_config::<GridConfig>(&server.config()),
+            Some(GridConfig { size: 5 })
+        );
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_with_score() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client
+            .handle_game_started_event(game::serialize_config(GridConfig { size: 2 }))
+            .await
+            .unwrap();
+        output.lock().unwrap().clear();
+
+        let board = board_with(
+            GridConfig { size: 2 },
+            &["a1-b1", "a1-a2", "b1-b2", "a2-b2", "b2-c2"],
+        );
+        client
+            .handle_board_updated_event(
+                board.get_horizontal(),
+                board.get_vertical(),
+                board.get_boxes(),
+            )
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "   a   b   c\n 1 +---+   +\n   | X |\n 2 +---+---+\n\n 3 +   +   +\nBoxes: X 1 - 0 O\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+        client
+            .handle_game_started_event(game::serialize_config(GridConfig { size: 1 }))
+            .await
+            .unwrap();
+        output.lock().unwrap().clear();
+
+        let board = board_with(GridConfig { size: 1 }, &["a1-b1", "a1-a2"]);
+        client
+            .handle_board_updated_event(
+                board.get_horizontal(),
+                board.get_vertical(),
+                board.get_boxes(),
+            )
+            .await
+            .unwrap();
+        assert_client_output(output, "+-+/|  /+ + X0-0O\n")
+    }
+
+    #[tokio::test]
+    async fn client_points_out_opponent_edges_but_not_own() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_edge_claimed_event(PLAYER_ONE_ID, edge("a1-b1"), 0)
+            .await
+            .unwrap();
+        client
+            .handle_edge_claimed_event(PLAYER_TWO_ID, edge("b2-b1"), 2)
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "Player O claimed b1-b2, completing 2 box(es), and goes again.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_edge_claimed_event(PLAYER_TWO_ID, edge("a1-b1"), 0)
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::EdgeTaken {
+                edge: edge("a1-b1"),
+            })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador O ha trazado a1-b1.\nError: La línea a1-b1 ya está trazada.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_edge_handles_errors_until_valid_edge_provided() {
+        let input = "a1-c1\na1\nb2-b3\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert_eq!(client.get_edge().await.unwrap(), edge("b2-b3"));
+        assert_client_output(
+            output,
+            "Edge (e.g. a1-b1):\nInvalid edge.\nEdge (e.g. a1-b1):\nInvalid edge.\nEdge (e.g. a1-b1):\n",
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 1,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(ClientEvent::EdgeClaimed {
+                    player_id: 1,
+                    edge: edge("a1-b1")
+                })
+            })
+        );
+    }
+}
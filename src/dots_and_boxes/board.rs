@@ -0,0 +1,395 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dots_and_boxes::{Error, Outcome};
+use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+pub const DEFAULT_GRID_SIZE: usize = 3;
+
+/// The number of boxes along each side of the grid, which has one more dot along each side.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridConfig {
+    pub size: usize,
+}
+
+impl GridConfig {
+    pub const DEFAULT: GridConfig = GridConfig {
+        size: DEFAULT_GRID_SIZE,
+    };
+}
+
+impl Default for GridConfig {
+    fn default() -> Self {
+        GridConfig::DEFAULT
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    /// Joins a dot to the dot to its right.
+    Horizontal,
+    /// Joins a dot to the dot below it.
+    Vertical,
+}
+
+/// The line between two neighbouring dots, given by the dot it starts from counting from the top
+/// left of the grid.
+///
+/// Edges are written as a player would enter them, naming the dots at either end with a letter
+/// for the column and a number for the row counting down from the top of the grid, such as
+/// "a1-b1".
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edge {
+    pub orientation: Orientation,
+    pub row: u8,
+    pub column: u8,
+}
+
+impl Edge {
+    pub const fn new(orientation: Orientation, row: u8, column: u8) -> Edge {
+        Edge {
+            orientation,
+            row,
+            column,
+        }
+    }
+
+    /// The dot at the other end of the edge.
+    fn end(&self) -> (u8, u8) {
+        match self.orientation {
+            Orientation::Horizontal => (self.row, self.column + 1),
+            Orientation::Vertical => (self.row + 1, self.column),
+        }
+    }
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let dot = |(row, column): (u8, u8)| format!("{}{}", char::from(b'a' + column), row + 1);
+        write!(f, "{}-{}", dot((self.row, self.column)), dot(self.end()))
+    }
+}
+
+impl FromStr for Edge {
+    type Err = InvalidEdge;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.trim().split_once('-').ok_or(InvalidEdge)?;
+        let ((row, column), (other_row, other_column)) = (parse_dot(start)?, parse_dot(end)?);
+
+        // Either dot may be given first
+        match (other_row.abs_diff(row), other_column.abs_diff(column)) {
+            (0, 1) => Ok(Edge::new(
+                Orientation::Horizontal,
+                row,
+                column.min(other_column),
+            )),
+            (1, 0) => Ok(Edge::new(Orientation::Vertical, row.min(other_row), column)),
+            _ => Err(InvalidEdge),
+        }
+    }
+}
+
+/// Reads a dot written as a column letter followed by a row number counting from 1.
+fn parse_dot(s: &str) -> Result<(u8, u8), InvalidEdge> {
+    let s = s.trim();
+    let column = s.chars().next().ok_or(InvalidEdge)?;
+    if !column.is_ascii_alphabetic() {
+        return Err(InvalidEdge);
+    }
+    let row: u8 = s[1..].parse().map_err(|_| InvalidEdge)?;
+    let row = row.checked_sub(1).ok_or(InvalidEdge)?;
+
+    Ok((row, column.to_ascii_lowercase() as u8 - b'a'))
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidEdge;
+
+/// The edges claimed by each player, and the boxes completed by them.
+#[derive(Clone)]
+pub struct Board {
+    config: GridConfig,
+    /// The ID of the player who claimed each horizontal edge, going along each row of dots from
+    /// the top.
+    horizontal: Vec<Option<u8>>,
+    /// The ID of the player who claimed each vertical edge, going along each row of boxes from the
+    /// top.
+    vertical: Vec<Option<u8>>,
+    /// The ID of the player who completed each box, going along each row from the top.
+    boxes: Vec<Option<u8>>,
+}
+
+impl Board {
+    pub(crate) fn new(config: GridConfig) -> Board {
+        let size = config.size;
+        Board::from_lines(
+            config,
+            vec![None; (size + 1) * size],
+            vec![None; size * (size + 1)],
+            vec![None; size * size],
+        )
+    }
+
+    /// Holds the edges and boxes as they were sent by the server, so that clients can work out
+    /// moves.
+    pub(crate) fn from_lines(
+        config: GridConfig,
+        horizontal: Vec<Option<u8>>,
+        vertical: Vec<Option<u8>>,
+        boxes: Vec<Option<u8>>,
+    ) -> Board {
+        Board {
+            config,
+            horizontal,
+            vertical,
+            boxes,
+        }
+    }
+
+    pub(crate) fn get_config(&self) -> GridConfig {
+        self.config
+    }
+
+    pub(crate) fn get_horizontal(&self) -> Vec<Option<u8>> {
+        self.horizontal.clone()
+    }
+
+    pub(crate) fn get_vertical(&self) -> Vec<Option<u8>> {
+        self.vertical.clone()
+    }
+
+    pub(crate) fn get_boxes(&self) -> Vec<Option<u8>> {
+        self.boxes.clone()
+    }
+
+    /// The player who claimed the edge, or `None` if it is unclaimed or not on the grid.
+    pub(crate) fn edge_owner(&self, edge: Edge) -> Option<u8> {
+        self.edge_index(edge)
+            .and_then(|index| match edge.orientation {
+                Orientation::Horizontal => self.horizontal[index],
+                Orientation::Vertical => self.vertical[index],
+            })
+    }
+
+    /// The player who completed the box at the row and column, if any.
+    pub(crate) fn box_owner(&self, row: u8, column: u8) -> Option<u8> {
+        self.boxes[row as usize * self.config.size + column as usize]
+    }
+
+    /// Every edge on the grid yet to be claimed, horizontal edges first.
+    pub(crate) fn unclaimed_edges(&self) -> Vec<Edge> {
+        let size = self.config.size as u8;
+        let horizontal = (0..=size).flat_map(|row| {
+            (0..size).map(move |column| Edge::new(Orientation::Horizontal, row, column))
+        });
+        let vertical = (0..size).flat_map(|row| {
+            (0..=size).map(move |column| Edge::new(Orientation::Vertical, row, column))
+        });
+
+        horizontal
+            .chain(vertical)
+            .filter(|&edge| self.edge_owner(edge).is_none())
+            .collect()
+    }
+
+    /// The number of the four edges around the box at the row and column that have been claimed.
+    pub(crate) fn claimed_sides(&self, row: u8, column: u8) -> usize {
+        [
+            Edge::new(Orientation::Horizontal, row, column),
+            Edge::new(Orientation::Horizontal, row + 1, column),
+            Edge::new(Orientation::Vertical, row, column),
+            Edge::new(Orientation::Vertical, row, column + 1),
+        ]
+        .into_iter()
+        .filter(|&edge| self.edge_owner(edge).is_some())
+        .count()
+    }
+
+    /// Claims the edge for the player, completing any box it closes for them.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(u8)` containing the number of boxes completed, which earns the player another turn
+    ///   if any were.
+    /// - `Err(Error)` if the edge is not on the grid or has already been claimed.
+    pub(crate) fn claim(&mut self, player_id: u8, edge: Edge) -> Result<u8, Error> {
+        let index = self.edge_index(edge).ok_or(Error::OutOfBounds { edge })?;
+        let owner = match edge.orientation {
+            Orientation::Horizontal => &mut self.horizontal[index],
+            Orientation::Vertical => &mut self.vertical[index],
+        };
+        if owner.is_some() {
+            return Err(Error::EdgeTaken { edge });
+        }
+        *owner = Some(player_id);
+
+        let mut completed = 0;
+        for (row, column) in self.boxes_beside(edge) {
+            if self.claimed_sides(row, column) == 4 {
+                self.boxes[row as usize * self.config.size + column as usize] = Some(player_id);
+                completed += 1;
+            }
+        }
+
+        Ok(completed)
+    }
+
+    /// Determines whether the game has ended, which happens once every box has been completed.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Outcome)` with the player who completed the most boxes as the winner, or a draw if
+    ///   both completed the same number.
+    /// - `None` if there are boxes left to complete.
+    pub(crate) fn determine_outcome(&self) -> Option<Outcome> {
+        if self.boxes.iter().any(Option::is_none) {
+            return None;
+        }
+
+        let outcome = match count_boxes(&self.boxes, PLAYER_ONE_ID)
+            .cmp(&count_boxes(&self.boxes, PLAYER_TWO_ID))
+        {
+            Ordering::Greater => Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID,
+            },
+            Ordering::Less => Outcome::WinnerFound {
+                player_id: PLAYER_TWO_ID,
+            },
+            Ordering::Equal => Outcome::Draw,
+        };
+
+        Some(outcome)
+    }
+
+    /// The index of the edge among those of its orientation, if it is on the grid.
+    fn edge_index(&self, edge: Edge) -> Option<usize> {
+        let size = self.config.size;
+        let (row, column) = (edge.row as usize, edge.column as usize);
+        match edge.orientation {
+            Orientation::Horizontal if row <= size && column < size => Some(row * size + column),
+            Orientation::Vertical if row < size && column <= size => {
+                Some(row * (size + 1) + column)
+            }
+            _ => None,
+        }
+    }
+
+    /// The boxes on either side of the edge, of which edges along the outside of the grid have
+    /// only one.
+    fn boxes_beside(&self, edge: Edge) -> Vec<(u8, u8)> {
+        let size = self.config.size as u8;
+        let Edge { row, column, .. } = edge;
+        match edge.orientation {
+            Orientation::Horizontal => [row.checked_sub(1), (row < size).then_some(row)]
+                .into_iter()
+                .flatten()
+                .map(|row| (row, column))
+                .collect(),
+            Orientation::Vertical => [column.checked_sub(1), (column < size).then_some(column)]
+                .into_iter()
+                .flatten()
+                .map(|column| (row, column))
+                .collect(),
+        }
+    }
+}
+
+/// The number of boxes the player has completed.
+pub(crate) fn count_boxes(boxes: &[Option<u8>], player_id: u8) -> u8 {
+    boxes
+        .iter()
+        .filter(|&&owner| owner == Some(player_id))
+        .count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edge(name: &str) -> Edge {
+        name.parse().unwrap()
+    }
+
+    #[test]
+    fn edge_parsed_from_either_end() {
+        assert_eq!(edge("a1-b1"), Edge::new(Orientation::Horizontal, 0, 0));
+        assert_eq!(edge("C2-B2"), Edge::new(Orientation::Horizontal, 1, 1));
+        assert_eq!(edge("b3-b2"), Edge::new(Orientation::Vertical, 1, 1));
+        assert_eq!(edge("d10-d11"), Edge::new(Orientation::Vertical, 9, 3));
+        assert_eq!(edge("b3-b2").to_string(), "b2-b3");
+    }
+
+    #[test]
+    fn edge_between_dots_that_are_not_neighbours_refused() {
+        for name in [
+            "a1-c1", "a1-b2", "a1-a1", "a1", "a0-b0", "1a-1b", "a1-b1-c1",
+        ] {
+            assert_eq!(name.parse::<Edge>(), Err(InvalidEdge), "{}", name);
+        }
+    }
+
+    #[test]
+    fn claiming_fourth_side_completes_box() {
+        let mut board = Board::new(GridConfig::DEFAULT);
+
+        for name in ["a1-b1", "a1-a2", "b1-b2"] {
+            assert_eq!(board.claim(PLAYER_ONE_ID, edge(name)).unwrap(), 0);
+        }
+        assert_eq!(board.claim(PLAYER_TWO_ID, edge("a2-b2")).unwrap(), 1);
+        assert_eq!(board.box_owner(0, 0), Some(PLAYER_TWO_ID));
+        assert_eq!(board.edge_owner(edge("a1-b1")), Some(PLAYER_ONE_ID));
+    }
+
+    #[test]
+    fn claiming_edge_between_two_boxes_can_complete_both() {
+        let mut board = Board::new(GridConfig::DEFAULT);
+
+        for name in ["a1-b1", "b1-c1", "a2-b2", "b2-c2", "a1-a2", "c1-c2"] {
+            board.claim(PLAYER_ONE_ID, edge(name)).unwrap();
+        }
+        assert_eq!(board.claim(PLAYER_TWO_ID, edge("b1-b2")).unwrap(), 2);
+        assert_eq!(count_boxes(&board.get_boxes(), PLAYER_TWO_ID), 2);
+    }
+
+    #[test]
+    fn taken_or_missing_edge_refused() {
+        let mut board = Board::new(GridConfig::DEFAULT);
+        board.claim(PLAYER_ONE_ID, edge("a1-b1")).unwrap();
+
+        assert!(matches!(
+            board.claim(PLAYER_TWO_ID, edge("b1-a1")),
+            Err(Error::EdgeTaken { .. })
+        ));
+        assert!(matches!(
+            board.claim(PLAYER_TWO_ID, edge("d1-e1")),
+            Err(Error::OutOfBounds { .. })
+        ));
+        assert!(matches!(
+            board.claim(PLAYER_TWO_ID, edge("a4-a5")),
+            Err(Error::OutOfBounds { .. })
+        ));
+    }
+
+    #[test]
+    fn game_ends_once_every_box_completed() {
+        let mut board = Board::new(GridConfig { size: 1 });
+
+        for name in ["a1-b1", "a1-a2", "b1-b2"] {
+            board.claim(PLAYER_ONE_ID, edge(name)).unwrap();
+        }
+        assert_eq!(board.determine_outcome(), None);
+        assert_eq!(board.unclaimed_edges(), vec![edge("a2-b2")]);
+
+        board.claim(PLAYER_TWO_ID, edge("a2-b2")).unwrap();
+        assert_eq!(
+            board.determine_outcome(),
+            Some(Outcome::WinnerFound {
+                player_id: PLAYER_TWO_ID
+            })
+        );
+    }
+}
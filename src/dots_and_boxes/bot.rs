@@ -0,0 +1,83 @@
+use crate::dots_and_boxes::board::{Board, Edge};
+
+/// Chooses an edge for the given player to claim.
+///
+/// An edge completing a box is always claimed, as the player then goes again. Otherwise an edge
+/// that leaves no box with three sides claimed is preferred, so that the other player cannot
+/// complete a box with their next move.
+///
+/// # Returns
+///
+/// - `Some(Edge)` containing the edge to claim.
+/// - `None` if every edge has been claimed.
+pub(crate) fn choose_move(board: &Board, player_id: u8) -> Option<Edge> {
+    let edges = board.unclaimed_edges();
+    let size = board.get_config().size as u8;
+
+    let completes_box = |&edge: &Edge| {
+        let mut board = board.clone();
+        board.claim(player_id, edge).unwrap() > 0
+    };
+    let leaves_box_open = |&edge: &Edge| {
+        let mut board = board.clone();
+        board.claim(player_id, edge).unwrap();
+        (0..size)
+            .flat_map(|row| (0..size).map(move |column| (row, column)))
+            .all(|(row, column)| board.claimed_sides(row, column) != 3)
+    };
+
+    edges
+        .iter()
+        .find(|edge| completes_box(edge))
+        .or_else(|| edges.iter().find(|edge| leaves_box_open(edge)))
+        .or_else(|| edges.first())
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dots_and_boxes::board::GridConfig;
+    use crate::server::PLAYER_ONE_ID;
+
+    use super::*;
+
+    fn board_with(edges: &[&str]) -> Board {
+        let mut board = Board::new(GridConfig::DEFAULT);
+        for name in edges {
+            board.claim(PLAYER_ONE_ID, name.parse().unwrap()).unwrap();
+        }
+
+        board
+    }
+
+    #[test]
+    fn choose_move_completes_box() {
+        let board = board_with(&["a1-b1", "b2-c2", "b1-c1", "c1-c2"]);
+
+        assert_eq!(
+            choose_move(&board, PLAYER_ONE_ID),
+            Some("b1-b2".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn choose_move_avoids_third_side_of_box() {
+        // The first edge left, a1-b1, would give the top left box a third side
+        let board = board_with(&["a1-a2", "b1-b2"]);
+
+        assert_eq!(
+            choose_move(&board, PLAYER_ONE_ID),
+            Some("b1-c1".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn choose_move_returns_none_once_every_edge_claimed() {
+        let mut board = Board::new(GridConfig { size: 1 });
+        for name in ["a1-b1", "a2-b2", "a1-a2", "b1-b2"] {
+            board.claim(PLAYER_ONE_ID, name.parse().unwrap()).unwrap();
+        }
+
+        assert_eq!(choose_move(&board, PLAYER_ONE_ID), None);
+    }
+}
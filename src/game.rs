@@ -1,45 +1,1073 @@
 use async_trait::async_trait;
 use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::Sender;
 
-use crate::server::DispatchMode;
+use crate::battleship::Battleship;
+use crate::blackjack::Blackjack;
+use crate::checkers::Checkers;
+use crate::client::{Frontend, OutputStyle};
+use crate::connection::envelope::{self, Envelope, OpenError};
+use crate::connection::{Codec, CodecError, Json};
+use crate::dots_and_boxes::{DotsAndBoxes, GridConfig};
+use crate::game::rand::GameRng;
+use crate::hangman::Hangman;
+use crate::locale::Locale;
+use crate::mancala::Mancala;
+use crate::nim::Nim;
+use crate::othello::Othello;
+use crate::rock_paper_scissors::RockPaperScissors;
+use crate::server::{DispatchMode, FirstPlayer, PlayerMarks, TimeoutAction, PLAYER_ONE_ID};
+use crate::tic_tac_toe::{BoardConfig, TicTacToe};
+use crate::trivia::{Trivia, TriviaConfig};
 
-pub enum Game {
-    TicTacToe,
+pub mod cards;
+pub mod commitment;
+mod erased;
+pub mod rand;
+
+pub use erased::ErasedEvent;
+
+/// Every game that can be played, in its default configuration.
+pub static GAMES: &[&dyn GameDescriptor] = &[
+    &TicTacToe::new(BoardConfig::DEFAULT),
+    &Checkers,
+    &Battleship,
+    &Othello,
+    &RockPaperScissors,
+    &Mancala,
+    &Nim,
+    &DotsAndBoxes::new(GridConfig::DEFAULT),
+    &Hangman,
+    &Trivia::new(TriviaConfig::DEFAULT),
+    &Blackjack,
+];
+
+/// Creates the server and client sides of a game, so that neither the server nor the client need
+/// to know which games exist.
+pub trait GameDescriptor: Send + Sync {
+    /// Uniquely identifies the game among those in `GAMES`.
+    fn id(&self) -> &'static str;
+    fn make_server(&self, channel: Sender<GameServerEvent>)
+        -> Box<dyn AnyGameServer + Send + Sync>;
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn AnyGameClient + 'a>;
+
+    /// The number of players the game is played by, which the lobby waits for before starting it.
+    fn player_count(&self) -> u8 {
+        2
+    }
+
+    /// Rebuilds the server side of a game from the state saved by `GameServer::save_state`, for
+    /// resuming a suspended game. `None` if the state cannot be carried on from, or if the game
+    /// cannot be saved at all.
+    fn restore_server(
+        &self,
+        _channel: Sender<GameServerEvent>,
+        _state: &serde_json::Value,
+    ) -> Option<Box<dyn AnyGameServer + Send + Sync>> {
+        None
+    }
+}
+
+impl<G: GameDescriptor + ?Sized> GameDescriptor for Box<G> {
+    fn id(&self) -> &'static str {
+        (**self).id()
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn AnyGameServer + Send + Sync> {
+        (**self).make_server(channel)
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn AnyGameClient + 'a> {
+        (**self).make_client(context)
+    }
+
+    fn player_count(&self) -> u8 {
+        (**self).player_count()
+    }
+
+    fn restore_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+        state: &serde_json::Value,
+    ) -> Option<Box<dyn AnyGameServer + Send + Sync>> {
+        (**self).restore_server(channel, state)
+    }
 }
 
+/// Lets the games in `GAMES` be handed on as they are, such as to a lobby.
+impl<G: GameDescriptor + ?Sized> GameDescriptor for &G {
+    fn id(&self) -> &'static str {
+        (**self).id()
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn AnyGameServer + Send + Sync> {
+        (**self).make_server(channel)
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn AnyGameClient + 'a> {
+        (**self).make_client(context)
+    }
+
+    fn player_count(&self) -> u8 {
+        (**self).player_count()
+    }
+
+    fn restore_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+        state: &serde_json::Value,
+    ) -> Option<Box<dyn AnyGameServer + Send + Sync>> {
+        (**self).restore_server(channel, state)
+    }
+}
+
+/// Finds the game with the given ID, in its default configuration.
+pub fn find_game(id: &str) -> Option<&'static dyn GameDescriptor> {
+    GAMES.iter().copied().find(|game| game.id() == id)
+}
+
+/// Everything a `GameClient` is given to interact with its user and the client running it.
+pub struct ClientContext<'a> {
+    /// Shared with the client, which reads from it while the game is not waiting on its user.
+    pub frontend: Arc<dyn Frontend + 'a>,
+    pub channel: Sender<GameClientEvent>,
+    pub kind: ClientKind,
+}
+
+/// Who a `GameClient` is being created for, deciding how it gets its moves and what it shows.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ClientKind {
+    Local,
+    Online { id: u8 },
+    Spectator,
+    Bot { id: u8, move_delay: Duration },
+}
+
+/// The seats a connection is allowed to act for, held as a flag for each player ID.
+///
+/// Usually a connection holds a single seat, but one connection may hold both so that a single
+/// client can play each side of a game in turn.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Seats(u8);
+
+impl Seats {
+    pub const fn single(player_id: u8) -> Seats {
+        Seats(1 << player_id)
+    }
+
+    pub const fn with(self, player_id: u8) -> Seats {
+        Seats(self.0 | 1 << player_id)
+    }
+
+    /// The seats of every player in a game played by the given number of players.
+    pub const fn every(player_count: u8) -> Seats {
+        let mut seats = Seats(0);
+        let mut player_id = PLAYER_ONE_ID;
+        while player_id < PLAYER_ONE_ID + player_count {
+            seats = seats.with(player_id);
+            player_id += 1;
+        }
+
+        seats
+    }
+
+    pub fn contains(&self, player_id: u8) -> bool {
+        player_id < u8::BITS as u8 && self.0 & 1 << player_id != 0
+    }
+}
+
+/// An event sent between the server and client sides of a game. Each is serialised as it leaves
+/// one side and deserialised as it reaches the other, leaving games to deal only in their own
+/// event types.
+pub trait GameEvent: Sized + Send {
+    fn encode(&self) -> ErasedEvent;
+    /// # Returns
+    ///
+    /// - `Ok(Some(Self))` containing the event.
+    /// - `Ok(None)` if the event is of a kind added by a newer version of the game than this one.
+    /// - `Err(CodecError)` if the event is malformed.
+    fn decode(event: &ErasedEvent) -> Result<Option<Self>, CodecError>;
+}
+
+impl<T: Serialize + DeserializeOwned + Send> GameEvent for T {
+    fn encode(&self) -> ErasedEvent {
+        serialize_event(self)
+    }
+
+    fn decode(event: &ErasedEvent) -> Result<Option<T>, CodecError> {
+        deserialize_known_event(event)
+    }
+}
+
+/// Sent by a `GameServer` to have the server act on its behalf, holding the game's own events
+/// until `ServerChannel` serialises them for the server.
 #[derive(Debug)]
-pub enum GameServerEvent {
+pub enum GameServerEvent<E = ErasedEvent> {
     DispatchToClient {
         dispatch_mode: DispatchMode,
-        event: Vec<u8>,
+        event: E,
     },
-    GameOver,
+    /// Sends each player their own view of the same update, such as one showing their own hand
+    /// but not the others', along with the view for spectators, which should give away no
+    /// player's secrets. Players without a view are sent nothing.
+    DispatchViews {
+        views: BTreeMap<u8, E>,
+        spectator_view: E,
+    },
+    /// Ends the setup phase of a game that began with one, once every player has finished their
+    /// setup, so that turns can begin.
+    SetupComplete,
+    TurnStarted {
+        player_id: u8,
+    },
+    /// Has `GameServer::handle_timer_elapsed` called with the given ID once the duration has
+    /// passed, replacing any timer the game started before. Games give each timer its own ID, so
+    /// that one elapsing just as it is replaced can be told apart from the one replacing it.
+    TimerStarted {
+        id: u32,
+        duration: Duration,
+    },
+    GameOver {
+        /// The ID of the winning player, `None` if the game was a draw.
+        winner: Option<u8>,
+    },
+    /// Asks for the game to be saved and every client sent away, at the request of the player.
+    SuspendRequested {
+        player_id: u8,
+    },
+    /// Tells the server of an invalid move made by the player whose turn it is, as counted by
+    /// `RejectedMoves`, so that it can warn them before their last attempt and tell everyone once
+    /// they have forfeited.
+    MoveRejected {
+        player_id: u8,
+        attempts_remaining: u8,
+    },
+}
+
+impl<E: GameEvent> GameServerEvent<E> {
+    fn encode(self) -> GameServerEvent {
+        match self {
+            GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            } => GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event: event.encode(),
+            },
+            GameServerEvent::DispatchViews {
+                views,
+                spectator_view,
+            } => GameServerEvent::DispatchViews {
+                views: views
+                    .into_iter()
+                    .map(|(player_id, view)| (player_id, view.encode()))
+                    .collect(),
+                spectator_view: spectator_view.encode(),
+            },
+            GameServerEvent::SetupComplete => GameServerEvent::SetupComplete,
+            GameServerEvent::TurnStarted { player_id } => {
+                GameServerEvent::TurnStarted { player_id }
+            }
+            GameServerEvent::TimerStarted { id, duration } => {
+                GameServerEvent::TimerStarted { id, duration }
+            }
+            GameServerEvent::GameOver { winner } => GameServerEvent::GameOver { winner },
+            GameServerEvent::SuspendRequested { player_id } => {
+                GameServerEvent::SuspendRequested { player_id }
+            }
+            GameServerEvent::MoveRejected {
+                player_id,
+                attempts_remaining,
+            } => GameServerEvent::MoveRejected {
+                player_id,
+                attempts_remaining,
+            },
+        }
+    }
+}
+
+/// The channel a `GameServer` sends its `GameServerEvent`s to the server through, serialising
+/// the game's `ServerEvent`s on the way so that the server need not know their type.
+#[derive(Debug)]
+pub struct ServerChannel<E> {
+    sender: Sender<GameServerEvent>,
+    event: PhantomData<fn(E)>,
 }
 
+impl<E: GameEvent> ServerChannel<E> {
+    pub fn new(sender: Sender<GameServerEvent>) -> ServerChannel<E> {
+        ServerChannel {
+            sender,
+            event: PhantomData,
+        }
+    }
+
+    /// Sends an event to the server. The server only stops receiving them once it has stopped
+    /// running the game, such as when it is replaced by the game chosen for a rematch, so an
+    /// event sent after that has no one to reach and is dropped.
+    pub async fn send(&self, event: GameServerEvent<E>) {
+        if self.sender.send(event.encode()).await.is_err() {
+            tracing::debug!("Dropped an event sent after the server stopped running the game");
+        }
+    }
+}
+
+/// The server side of a game, deciding how it responds to the events sent by players.
 #[async_trait]
 pub trait GameServer {
+    /// The events sent by the game's clients.
+    type ClientEvent: GameEvent;
+    /// The events sent to the game's clients, through a `ServerChannel`.
+    type ServerEvent: GameEvent;
+
+    /// Chooses who takes the first turn, before the game begins. Games whose rules decide who
+    /// goes first keep to them.
+    fn set_first_player(&mut self, _first_player: FirstPlayer) {}
+    /// Hands the game the generator to draw its chance from, such as dice rolls and shuffles, as
+    /// soon as the game is made and again whenever the server is given a seed. Games left to
+    /// chance draw from nothing else, so that a game can be played out again from its seed.
+    fn set_rng(&mut self, _rng: GameRng) {}
+    /// The options the game is played with, such as the size of its board, as serialised by
+    /// `serialize_config`. Sent to clients as the game starts, so that they need not assume the
+    /// same options as the server. Games without options send none.
+    fn config(&self) -> Vec<u8> {
+        Vec::new()
+    }
+    /// Whether the game begins with a setup phase, such as each player placing their fleet, during
+    /// which players send their events independently of one another rather than taking turns.
+    /// Checked as the game begins, after which the setup phase lasts until the game sends
+    /// `GameServerEvent::SetupComplete`. Games carried on from a save file past their setup begin
+    /// without one.
+    fn is_setting_up(&self) -> bool {
+        false
+    }
+    async fn begin(&self);
+    /// Handles an event sent by a client, which the server has already checked names a player
+    /// whose seat the client holds.
+    async fn handle_event(&mut self, event: Self::ClientEvent);
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction);
+    /// Handles a timer started with `GameServerEvent::TimerStarted` running out. Games that start
+    /// no timers of their own are never called.
+    async fn handle_timer_elapsed(&mut self, _id: u32) {}
+    async fn handle_player_reconnected(&self, player_id: u8);
+    async fn handle_spectator_joined(&self);
+
+    /// Everything needed to carry on the game later, for writing to a save file. Games that cannot
+    /// be saved return `None`, leaving their players to finish them.
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// A `GameServer` as run by the server, which knows the game's events only as the bytes they are
+/// sent in. Every `GameServer` is one, deserialising the events sent by clients before handing
+/// them to the game.
+#[async_trait]
+pub trait AnyGameServer {
+    fn set_first_player(&mut self, first_player: FirstPlayer);
+    fn set_rng(&mut self, rng: GameRng);
+    fn config(&self) -> Vec<u8>;
+    fn is_setting_up(&self) -> bool;
     async fn begin(&self);
-    async fn handle_event(&mut self, event: Vec<u8>);
+    /// Handles an event sent by a client, skipping events of a kind added by a newer version of
+    /// the game than this one.
+    ///
+    /// # Returns
+    ///
+    /// `Err(CodecError)` if the event is malformed, leaving the game as it was.
+    async fn handle_event(&mut self, event: ErasedEvent) -> Result<(), CodecError>;
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction);
+    async fn handle_timer_elapsed(&mut self, id: u32);
+    async fn handle_player_reconnected(&self, player_id: u8);
+    async fn handle_spectator_joined(&self);
+    fn save_state(&self) -> Option<serde_json::Value>;
 }
 
+#[async_trait]
+impl<G: GameServer + Send + Sync> AnyGameServer for G {
+    fn set_first_player(&mut self, first_player: FirstPlayer) {
+        GameServer::set_first_player(self, first_player)
+    }
+
+    fn set_rng(&mut self, rng: GameRng) {
+        GameServer::set_rng(self, rng)
+    }
+
+    fn config(&self) -> Vec<u8> {
+        GameServer::config(self)
+    }
+
+    fn is_setting_up(&self) -> bool {
+        GameServer::is_setting_up(self)
+    }
+
+    async fn begin(&self) {
+        GameServer::begin(self).await
+    }
+
+    async fn handle_event(&mut self, event: ErasedEvent) -> Result<(), CodecError> {
+        match G::ClientEvent::decode(&event)? {
+            Some(event) => GameServer::handle_event(self, event).await,
+            None => tracing::debug!("Skipping client event of unknown kind"),
+        }
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        GameServer::handle_turn_timed_out(self, player_id, action).await
+    }
+
+    async fn handle_timer_elapsed(&mut self, id: u32) {
+        GameServer::handle_timer_elapsed(self, id).await
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        GameServer::handle_player_reconnected(self, player_id).await
+    }
+
+    async fn handle_spectator_joined(&self) {
+        GameServer::handle_spectator_joined(self).await
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        GameServer::save_state(self)
+    }
+}
+
+/// Sent by a `GameClient` to have the client act on its behalf, holding the game's own events
+/// until `ClientChannel` serialises them for the client.
 #[derive(Debug, PartialEq)]
-pub enum GameClientEvent {
-    DispatchToServer { event: Vec<u8> },
+pub enum GameClientEvent<E = ErasedEvent> {
+    DispatchToServer {
+        event: E,
+    },
     GameOver,
+    /// Leaves the game before it is over, as the user asked to.
+    Quit,
+}
+
+impl<E: GameEvent> GameClientEvent<E> {
+    fn encode(self) -> GameClientEvent {
+        match self {
+            GameClientEvent::DispatchToServer { event } => GameClientEvent::DispatchToServer {
+                event: event.encode(),
+            },
+            GameClientEvent::GameOver => GameClientEvent::GameOver,
+            GameClientEvent::Quit => GameClientEvent::Quit,
+        }
+    }
+}
+
+/// The channel a `GameClient` sends its `GameClientEvent`s to the client through, serialising
+/// the game's `ClientEvent`s on the way so that the client need not know their type.
+#[derive(Debug)]
+pub struct ClientChannel<E> {
+    sender: Sender<GameClientEvent>,
+    event: PhantomData<fn(E)>,
 }
 
+impl<E: GameEvent> ClientChannel<E> {
+    pub fn new(sender: Sender<GameClientEvent>) -> ClientChannel<E> {
+        ClientChannel {
+            sender,
+            event: PhantomData,
+        }
+    }
+
+    pub async fn send(&self, event: GameClientEvent<E>) -> Result<(), SendError<GameClientEvent>> {
+        self.sender.send(event.encode()).await
+    }
+}
+
+/// The client side of a game, presenting its events to the user and sending back their moves.
 #[async_trait]
 pub trait GameClient {
-    async fn handle_game_started_event(&self);
-    async fn handle_event(&mut self, event: Vec<u8>) -> Result<(), io::Error>;
+    /// The events sent by the game's server.
+    type ServerEvent: GameEvent;
+    /// The events sent to the game's server, through a `ClientChannel`.
+    type ClientEvent: GameEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle);
+    /// Whether output to the user may be coloured, such as when writing to a terminal.
+    fn set_color(&mut self, color: bool);
+    /// The language messages are written to the user in.
+    fn set_locale(&mut self, locale: Locale);
+    /// Whether the board should be described in words for a screen reader rather than drawn.
+    /// Games without a description of their board keep to their usual output.
+    fn set_screen_reader(&mut self, _screen_reader: bool) {}
+    /// The mark each player is shown with, as sent when the game starts. Games that do not show
+    /// players by a mark ignore them.
+    fn set_player_marks(&mut self, _marks: PlayerMarks) {}
+    /// How the player with the given ID is referred to when shown to the user.
+    fn get_player_name(&self, player_id: u8) -> String;
+    /// Handles the game starting, given the options it is played with as sent by
+    /// `GameServer::config`.
+    async fn handle_game_started_event(&mut self, config: Vec<u8>) -> Result<(), GameClientError>;
+    async fn handle_event(&mut self, event: Self::ServerEvent) -> Result<(), GameClientError>;
+}
+
+/// A `GameClient` as run by the client, which knows the game's events only as the bytes they are
+/// sent in. Every `GameClient` is one, deserialising the events sent by the server before
+/// handing them to the game.
+#[async_trait]
+pub trait AnyGameClient {
+    fn set_output_style(&mut self, output_style: OutputStyle);
+    fn set_color(&mut self, color: bool);
+    fn set_locale(&mut self, locale: Locale);
+    fn set_screen_reader(&mut self, screen_reader: bool);
+    fn set_player_marks(&mut self, marks: PlayerMarks);
+    fn get_player_name(&self, player_id: u8) -> String;
+    async fn handle_game_started_event(&mut self, config: Vec<u8>) -> Result<(), GameClientError>;
+    /// Handles an event sent by the server, skipping events of a kind added by a newer version of
+    /// the game than this one.
+    async fn handle_event(&mut self, event: ErasedEvent) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<G: GameClient + Send> AnyGameClient for G {
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        GameClient::set_output_style(self, output_style)
+    }
+
+    fn set_color(&mut self, color: bool) {
+        GameClient::set_color(self, color)
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        GameClient::set_locale(self, locale)
+    }
+
+    fn set_screen_reader(&mut self, screen_reader: bool) {
+        GameClient::set_screen_reader(self, screen_reader)
+    }
+
+    fn set_player_marks(&mut self, marks: PlayerMarks) {
+        GameClient::set_player_marks(self, marks)
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        GameClient::get_player_name(self, player_id)
+    }
+
+    async fn handle_game_started_event(&mut self, config: Vec<u8>) -> Result<(), GameClientError> {
+        GameClient::handle_game_started_event(self, config).await
+    }
+
+    async fn handle_event(&mut self, event: ErasedEvent) -> Result<(), GameClientError> {
+        match G::ServerEvent::decode(&event)? {
+            Some(event) => GameClient::handle_event(self, event).await,
+            None => {
+                tracing::debug!("Skipping server event of unknown kind");
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Why a `GameClient` was unable to handle an event, leaving it unable to carry on with the game.
+#[derive(thiserror::Error, Debug)]
+pub enum GameClientError {
+    #[error("Failed to read from or write to the user")]
+    User(#[from] io::Error),
+    #[error("The client running the game has stopped")]
+    ClientStopped,
+    #[error("The server sent a game event that could not be read")]
+    InvalidEvent(#[from] CodecError),
+}
+
+/// Determines whether neither player can win a game played by occupying every cell along a line,
+/// as each line already holds cells occupied by more than one player.
+///
+/// Games can use this to declare a draw as soon as one is certain, rather than having the players
+/// fill the remaining cells.
+///
+/// # Parameters
+///
+/// - `lines`: The indexes of the cells in each line that would win the game.
+/// - `cells`: The ID of the player occupying each cell, if any.
+pub fn is_dead_position(lines: &[Vec<usize>], cells: &[Option<u8>]) -> bool {
+    lines.iter().all(|line| {
+        let mut occupiers = line.iter().filter_map(|&cell| cells[cell]);
+        occupiers
+            .next()
+            .is_some_and(|first| occupiers.any(|player_id| player_id != first))
+    })
+}
+
+/// Collects a move from each player in games where players move at the same time, rather than
+/// taking turns, holding every move back until all are in so that no player can react to another.
+/// Games that should not trust the server with the moves either use `commitment::CommittedMoves`.
+#[derive(Debug)]
+pub struct SimultaneousMoves<M> {
+    player_ids: Vec<u8>,
+    moves: BTreeMap<u8, M>,
+}
+
+impl<M> SimultaneousMoves<M> {
+    pub fn new(player_ids: &[u8]) -> SimultaneousMoves<M> {
+        SimultaneousMoves {
+            player_ids: player_ids.to_vec(),
+            moves: BTreeMap::new(),
+        }
+    }
+
+    pub fn has_moved(&self, player_id: u8) -> bool {
+        self.moves.contains_key(&player_id)
+    }
+
+    /// The players who are yet to move this round.
+    pub fn waiting_on(&self) -> Vec<u8> {
+        self.player_ids
+            .iter()
+            .copied()
+            .filter(|player_id| !self.has_moved(*player_id))
+            .collect()
+    }
+
+    /// Records the player's move for the round.
+    ///
+    /// # Returns
+    ///
+    /// Whether the move was recorded, which it is not if the player has already moved this round
+    /// or is not one of the players.
+    pub fn commit(&mut self, player_id: u8, player_move: M) -> bool {
+        if !self.player_ids.contains(&player_id) || self.has_moved(player_id) {
+            return false;
+        }
+
+        self.moves.insert(player_id, player_move);
+        true
+    }
+
+    /// Takes the moves of every player once all of them have moved, ready for the next round.
+    pub fn reveal(&mut self) -> Option<BTreeMap<u8, M>> {
+        self.waiting_on().is_empty().then(|| self.take())
+    }
+
+    /// Takes the moves made so far, for ending a round before every player has moved, such as
+    /// when one runs out of time.
+    pub fn take(&mut self) -> BTreeMap<u8, M> {
+        std::mem::take(&mut self.moves)
+    }
+}
+
+/// Counts the invalid moves made by the player whose turn it is, who forfeits the game once they
+/// have made `RejectedMoves::LIMIT` of them in a single turn.
+#[derive(Debug, Default)]
+pub struct RejectedMoves {
+    count: u8,
+}
+
+impl RejectedMoves {
+    /// The number of invalid moves a player may attempt in a single turn before forfeiting.
+    pub const LIMIT: u8 = 5;
+
+    /// The invalid moves made so far this turn.
+    pub fn count(&self) -> u8 {
+        self.count
+    }
+
+    /// Starts counting afresh, as each turn begins.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    /// Counts an invalid move made by the player, telling the server of it. Only the current
+    /// player's attempts count towards the limit, so that the other player cannot force them to
+    /// forfeit.
+    ///
+    /// # Returns
+    ///
+    /// - `true` if the current player has run out of attempts, leaving the game to end with them
+    ///   forfeiting.
+    /// - `false` if the player may try again, or it was not their turn.
+    pub async fn reject<E: GameEvent>(
+        &mut self,
+        channel: &ServerChannel<E>,
+        current_player: u8,
+        player_id: u8,
+    ) -> bool {
+        if player_id != current_player {
+            return false;
+        }
+
+        self.count = self.count.saturating_add(1);
+        let attempts_remaining = RejectedMoves::LIMIT.saturating_sub(self.count);
+        channel
+            .send(GameServerEvent::MoveRejected {
+                player_id,
+                attempts_remaining,
+            })
+            .await;
+
+        attempts_remaining == 0
+    }
+}
+
+/// Sends every player their own view of an update as one `GameServerEvent::DispatchViews`, so that
+/// games with hidden information need not send each view separately.
+///
+/// # Parameters
+///
+/// - `channel`: The game's channel to the server.
+/// - `player_ids`: The players to send a view to.
+/// - `make_view`: Builds the game's event as seen by the player with the given ID, or by the
+///   spectators if given `None`.
+pub async fn dispatch_views<E: GameEvent>(
+    channel: &ServerChannel<E>,
+    player_ids: impl IntoIterator<Item = u8>,
+    make_view: impl Fn(Option<u8>) -> E,
+) {
+    let views = player_ids
+        .into_iter()
+        .map(|player_id| (player_id, make_view(Some(player_id))))
+        .collect();
+
+    channel
+        .send(GameServerEvent::DispatchViews {
+            views,
+            spectator_view: make_view(None),
+        })
+        .await
+}
+
+/// Client events common to every game that opts into them, sent alongside the game's own.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Copy, Clone)]
+pub enum ClientEvent {
+    /// Concedes the game to the player's opponent.
+    Resign {
+        player_id: u8,
+    },
+    /// Offers the player's opponent a draw, which ends the game once they accept it.
+    DrawOffer {
+        player_id: u8,
+    },
+    DrawResponse {
+        player_id: u8,
+        accepted: bool,
+    },
+}
+
+/// A client event sent to a game that opts into the common `ClientEvent`s.
+#[derive(Debug, PartialEq)]
+pub enum AnyClientEvent<T> {
+    Common(ClientEvent),
+    Game(T),
+}
+
+impl<T: GameEvent> GameEvent for AnyClientEvent<T> {
+    fn encode(&self) -> ErasedEvent {
+        match self {
+            AnyClientEvent::Common(event) => event.encode(),
+            AnyClientEvent::Game(event) => event.encode(),
+        }
+    }
+
+    fn decode(event: &ErasedEvent) -> Result<Option<AnyClientEvent<T>>, CodecError> {
+        if let Ok(envelope) = envelope::open_value::<ClientEvent>(&event.0) {
+            return Ok(Some(AnyClientEvent::Common(envelope.into_payload())));
+        }
+
+        Ok(T::decode(event)?.map(AnyClientEvent::Game))
+    }
+}
+
+/// Serialises the options a game is played with as JSON, for sending to clients as it starts.
+pub fn serialize_config(config: impl Serialize) -> Vec<u8> {
+    Json.encode(&config).unwrap()
+}
+
+/// Deserialises the options a game is played with, as sent when it started.
+///
+/// # Returns
+///
+/// - `Some(T)` containing the options.
+/// - `None` if no options were sent, or they could not be read, leaving the game to its defaults.
+pub fn deserialize_config<T: DeserializeOwned>(config: &[u8]) -> Option<T> {
+    if config.is_empty() {
+        return None;
+    }
+
+    Json.decode(config)
+        .inspect_err(|error| tracing::debug!(?error, "Ignoring game options that cannot be read"))
+        .ok()
+}
+
+/// Erases the type of a game event, wrapping it in an envelope naming its kind so that a side of
+/// the game that does not know the kind can skip it.
+pub fn serialize_event(event: impl Serialize) -> ErasedEvent {
+    ErasedEvent(serde_json::to_value(Envelope::wrap(&event)).unwrap())
+}
+
+/// Deserialises an event serialised by `serialize_event`, for tests to read what a game sent.
+#[cfg(test)]
+pub fn deserialize_event<T: DeserializeOwned>(event: ErasedEvent) -> T {
+    deserialize_known_event(&event)
+        .expect("Malformed event")
+        .expect("Event of unknown kind")
+}
+
+/// Deserialises an event sent by the other side of the game, skipping events of a kind added by a
+/// newer version of the game than this one.
+///
+/// # Returns
+///
+/// - `Ok(Some(T))` containing the event.
+/// - `Ok(None)` if the event is of an unknown kind.
+/// - `Err(CodecError)` if the event is malformed.
+fn deserialize_known_event<T: DeserializeOwned>(
+    event: &ErasedEvent,
+) -> Result<Option<T>, CodecError> {
+    match envelope::open_value(&event.0) {
+        Ok(envelope) => Ok(Some(envelope.into_payload())),
+        Err(OpenError::Unknown { kind, version, .. }) => {
+            tracing::debug!(kind, version, "Skipping game event of unknown kind");
+            Ok(None)
+        }
+        Err(OpenError::Codec(error)) => Err(error),
+    }
 }
 
-pub fn serialize_event(event: impl Serialize) -> Vec<u8> {
-    serde_json::to_vec(&event).unwrap()
+/// Reads the ID of the player that a client event acts for, so that the server can check it
+/// against the seats of the client that sent it without knowing which game is being played.
+///
+/// Every game's client events must therefore name the player in a `player_id` field.
+///
+/// # Returns
+///
+/// - `Some(u8)` containing the ID of the player.
+/// - `None` if the event does not name a player.
+pub fn get_event_player_id(event: &ErasedEvent) -> Option<u8> {
+    #[derive(Deserialize)]
+    struct NamedPlayer {
+        player_id: u8,
+    }
+
+    // Events are enums, which serialise to a map holding the fields of their single variant
+    let variant: BTreeMap<String, NamedPlayer> =
+        envelope::open_value(&event.0).ok()?.into_payload();
+    let mut players = variant.into_values();
+    match (players.next(), players.next()) {
+        (Some(NamedPlayer { player_id }), None) => Some(player_id),
+        _ => None,
+    }
 }
 
-pub fn deserialize_event<T: DeserializeOwned>(event: Vec<u8>) -> T {
-    serde_json::from_slice(&event).unwrap()
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tic_tac_toe;
+
+    #[test]
+    fn find_game_returns_game_with_matching_id() {
+        let game = find_game(tic_tac_toe::GAME_ID).unwrap();
+
+        assert_eq!(game.id(), tic_tac_toe::GAME_ID);
+    }
+
+    #[test]
+    fn find_game_returns_none_for_unknown_id() {
+        assert!(find_game("chess").is_none());
+    }
+
+    #[test]
+    fn is_dead_position_when_every_line_is_blocked() {
+        let lines = vec![vec![0, 1, 2], vec![3, 4, 5]];
+        let cells = [Some(1), None, Some(2), Some(2), Some(1), None];
+
+        assert!(is_dead_position(&lines, &cells));
+    }
+
+    #[test]
+    fn is_not_dead_position_while_a_line_can_be_completed() {
+        let lines = vec![vec![0, 1, 2], vec![3, 4, 5]];
+        let cells = [Some(1), None, Some(2), Some(2), Some(2), None];
+
+        assert!(!is_dead_position(&lines, &cells));
+        assert!(!is_dead_position(&lines, &[None; 6]));
+    }
+
+    #[test]
+    fn seats_contain_only_the_players_added() {
+        let seats = Seats::single(1).with(3);
+
+        assert!(seats.contains(1));
+        assert!(seats.contains(3));
+        assert!(!seats.contains(2));
+        assert!(!seats.contains(200));
+        assert_eq!(Seats::every(2), Seats::single(1).with(2));
+    }
+
+    #[test]
+    fn simultaneous_moves_are_revealed_once_every_player_moves() {
+        let mut moves = SimultaneousMoves::new(&[1, 2]);
+
+        assert!(moves.commit(2, "rock"));
+        assert!(!moves.commit(2, "paper"));
+        assert!(!moves.commit(3, "paper"));
+        assert_eq!(moves.waiting_on(), vec![1]);
+        assert!(moves.reveal().is_none());
+
+        assert!(moves.commit(1, "scissors"));
+        assert_eq!(
+            moves.reveal(),
+            Some(BTreeMap::from([(1, "scissors"), (2, "rock")]))
+        );
+        assert_eq!(moves.waiting_on(), vec![1, 2]);
+    }
+
+    #[test]
+    fn get_event_player_id_reads_player_named_by_event() {
+        let event = serialize_event(tic_tac_toe::ClientEvent::UndoAnswered {
+            player_id: 2,
+            approved: true,
+        });
+
+        assert_eq!(get_event_player_id(&event), Some(2));
+        assert_eq!(
+            get_event_player_id(&serialize_event(json!({"MoveMade": {"move_index": 4}}))),
+            None
+        );
+        assert_eq!(get_event_player_id(&serialize_event("Resigned")), None);
+        assert_eq!(
+            get_event_player_id(&ErasedEvent(json!("not an event"))),
+            None
+        );
+    }
+
+    #[test]
+    fn decode_skips_kind_added_by_newer_version() {
+        let event = ErasedEvent(json!({
+            "kind": "Resigned",
+            "version": crate::connection::SCHEMA_VERSION + 1,
+            "sequence": 0,
+            "payload": "Resigned",
+        }));
+
+        assert!(tic_tac_toe::ClientEvent::decode(&event).unwrap().is_none());
+        assert!(tic_tac_toe::ClientEvent::decode(&ErasedEvent(json!("not an event"))).is_err());
+    }
+
+    #[test]
+    fn any_client_event_tells_common_events_from_game_events() {
+        let resign = serialize_event(ClientEvent::Resign { player_id: 1 });
+        let move_made = serialize_event(tic_tac_toe::ClientEvent::MoveMade {
+            player_id: 2,
+            move_index: 4,
+            mark: None,
+        });
+
+        assert_eq!(
+            AnyClientEvent::<tic_tac_toe::ClientEvent>::decode(&resign).unwrap(),
+            Some(AnyClientEvent::Common(ClientEvent::Resign { player_id: 1 }))
+        );
+        assert_eq!(
+            AnyClientEvent::decode(&move_made).unwrap(),
+            Some(AnyClientEvent::Game(tic_tac_toe::ClientEvent::MoveMade {
+                player_id: 2,
+                move_index: 4,
+                mark: None,
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn event_sent_once_server_stops_running_game_is_dropped() {
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let channel = ServerChannel::<u8>::new(sender);
+        drop(receiver);
+
+        channel
+            .send(GameServerEvent::GameOver { winner: None })
+            .await;
+    }
+
+    #[tokio::test]
+    async fn rejected_moves_run_out_at_limit_until_reset() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(RejectedMoves::LIMIT as usize + 1);
+        let channel = ServerChannel::<u8>::new(sender);
+        let mut rejected_moves = RejectedMoves::default();
+
+        for _ in 1..RejectedMoves::LIMIT {
+            assert!(
+                !rejected_moves
+                    .reject(&channel, PLAYER_ONE_ID, PLAYER_ONE_ID)
+                    .await
+            );
+        }
+        // Moves made out of turn are not counted
+        assert!(!rejected_moves.reject(&channel, PLAYER_ONE_ID, 2).await);
+        assert!(
+            rejected_moves
+                .reject(&channel, PLAYER_ONE_ID, PLAYER_ONE_ID)
+                .await
+        );
+        rejected_moves.reset();
+        assert!(
+            !rejected_moves
+                .reject(&channel, PLAYER_ONE_ID, PLAYER_ONE_ID)
+                .await
+        );
+
+        let mut attempts = Vec::new();
+        while let Ok(GameServerEvent::MoveRejected {
+            player_id: PLAYER_ONE_ID,
+            attempts_remaining,
+        }) = receiver.try_recv()
+        {
+            attempts.push(attempts_remaining);
+        }
+        assert_eq!(attempts, [4, 3, 2, 1, 0, 4]);
+    }
+
+    #[tokio::test]
+    async fn dispatch_views_sends_each_player_their_own_view() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+
+        dispatch_views(&ServerChannel::new(sender), [PLAYER_ONE_ID, 2], |viewer| {
+            viewer
+        })
+        .await;
+
+        let Some(GameServerEvent::DispatchViews {
+            views,
+            spectator_view,
+        }) = receiver.recv().await
+        else {
+            panic!("Expected the views to be sent together");
+        };
+        assert_eq!(
+            views,
+            BTreeMap::from([
+                (PLAYER_ONE_ID, serialize_event(Some(PLAYER_ONE_ID))),
+                (2, serialize_event(Some(2))),
+            ])
+        );
+        assert_eq!(spectator_view, serialize_event(None::<u8>));
+    }
+
+    #[test]
+    fn game_ids_are_unique() {
+        for (index, game) in GAMES.iter().enumerate() {
+            assert!(GAMES[index + 1..]
+                .iter()
+                .all(|other| other.id() != game.id()));
+        }
+    }
 }
@@ -0,0 +1,340 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::rand::GameRng;
+use crate::game::{self, GameEvent, ServerChannel};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Suit {
+    Clubs,
+    Diamonds,
+    Hearts,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+    pub fn symbol(self) -> char {
+        match self {
+            Suit::Clubs => '♣',
+            Suit::Diamonds => '♦',
+            Suit::Hearts => '♥',
+            Suit::Spades => '♠',
+        }
+    }
+}
+
+/// The rank of a card, ordered from lowest to highest with aces high. Games counting aces low
+/// look for them by rank.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    /// How the rank is written on a card, such as "10" or "K".
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl Card {
+    pub const fn new(rank: Rank, suit: Suit) -> Card {
+        Card { rank, suit }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.rank.symbol(), self.suit.symbol())
+    }
+}
+
+/// The cards yet to be dealt, dealt from the top.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Deck {
+    cards: Vec<Card>,
+}
+
+impl Deck {
+    /// The 52 cards of a standard deck, in order by suit then rank.
+    pub fn standard() -> Deck {
+        let cards = Suit::ALL
+            .iter()
+            .flat_map(|suit| Rank::ALL.iter().map(|rank| Card::new(*rank, *suit)))
+            .collect();
+
+        Deck { cards }
+    }
+
+    /// A standard deck shuffled using the game's generator, ready to deal from.
+    pub fn shuffled(rng: &mut GameRng) -> Deck {
+        let mut deck = Deck::standard();
+        deck.shuffle(rng);
+
+        deck
+    }
+
+    pub fn shuffle(&mut self, rng: &mut GameRng) {
+        rng.shuffle(&mut self.cards);
+    }
+
+    /// Deals the card on top of the deck, or `None` if the deck has run out.
+    pub fn deal(&mut self) -> Option<Card> {
+        self.cards.pop()
+    }
+
+    /// Deals a hand of the given number of cards from the top of the deck.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Hand)` containing the cards, in the order dealt.
+    /// - `None` if the deck has too few cards left, in which case none are dealt.
+    pub fn deal_hand(&mut self, count: usize) -> Option<Hand> {
+        if count > self.cards.len() {
+            return None;
+        }
+
+        let cards = self.cards.split_off(self.cards.len() - count);
+        Some(Hand {
+            cards: cards.into_iter().rev().collect(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
+
+/// The cards a player holds, in the order they were given them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Hand {
+    cards: Vec<Card>,
+}
+
+impl Hand {
+    pub fn new() -> Hand {
+        Hand::default()
+    }
+
+    pub fn add(&mut self, card: Card) {
+        self.cards.push(card);
+    }
+
+    /// Takes the card at the given index out of the hand, or `None` if there is no such card.
+    pub fn remove(&mut self, index: usize) -> Option<Card> {
+        (index < self.cards.len()).then(|| self.cards.remove(index))
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The hand as seen by someone, in full if it may be shown to them and otherwise only by how
+    /// many cards it holds.
+    pub fn view(&self, shown: bool) -> HandView {
+        match shown {
+            true => HandView::Shown(self.cards.clone()),
+            false => HandView::Hidden {
+                count: self.cards.len(),
+            },
+        }
+    }
+}
+
+/// A hand as sent to a client, which sees the cards of its own player's hand and only the size of
+/// anyone else's.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum HandView {
+    Shown(Vec<Card>),
+    Hidden { count: usize },
+}
+
+/// Sends the hands in play to everyone in the game as views, each player seeing their own hand in
+/// full and only the size of anyone else's, and spectators only the size of each.
+///
+/// # Parameters
+///
+/// - `channel`: The game's channel to the server.
+/// - `hands`: The hand of each player, keyed by their ID.
+/// - `make_event`: Builds the game's event showing the hands as seen by one player, or by the
+///   spectators.
+pub async fn dispatch_hidden_hands<E: GameEvent>(
+    channel: &ServerChannel<E>,
+    hands: &BTreeMap<u8, Hand>,
+    make_event: impl Fn(BTreeMap<u8, HandView>) -> E,
+) {
+    game::dispatch_views(channel, hands.keys().copied(), |viewer| {
+        let views = hands
+            .iter()
+            .map(|(player_id, hand)| (*player_id, hand.view(viewer == Some(*player_id))))
+            .collect();
+        make_event(views)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::game::GameServerEvent;
+    use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+    #[test]
+    fn standard_deck_holds_every_card_once() {
+        let deck = Deck::standard();
+
+        assert_eq!(deck.len(), 52);
+        assert_eq!(deck.cards.iter().collect::<HashSet<_>>().len(), 52);
+    }
+
+    #[test]
+    fn deck_shuffled_with_same_seed_deals_same_cards() {
+        let mut one = Deck::shuffled(&mut GameRng::from_seed(5));
+        let mut two = Deck::shuffled(&mut GameRng::from_seed(5));
+
+        assert_ne!(one, Deck::standard());
+        assert_eq!(one.deal_hand(5), two.deal_hand(5));
+        assert_eq!(one.deal(), two.deal());
+        assert_eq!(one.len(), 46);
+    }
+
+    #[test]
+    fn deal_hand_deals_nothing_unless_enough_cards_remain() {
+        let mut deck = Deck::standard();
+
+        let hand = deck.deal_hand(50).unwrap();
+        assert_eq!(hand.cards()[0], Card::new(Rank::Ace, Suit::Spades));
+        assert!(deck.deal_hand(3).is_none());
+        assert_eq!(deck.len(), 2);
+    }
+
+    #[test]
+    fn hand_removes_only_cards_it_holds() {
+        let mut hand = Hand::new();
+        hand.add(Card::new(Rank::Ten, Suit::Hearts));
+
+        assert_eq!(hand.remove(1), None);
+        assert_eq!(hand.remove(0), Some(Card::new(Rank::Ten, Suit::Hearts)));
+        assert!(hand.is_empty());
+    }
+
+    #[test]
+    fn card_shown_by_rank_and_suit() {
+        assert_eq!(Card::new(Rank::Ten, Suit::Hearts).to_string(), "10♥");
+        assert_eq!(Card::new(Rank::Queen, Suit::Spades).to_string(), "Q♠");
+    }
+
+    #[tokio::test]
+    async fn dispatch_hidden_hands_shows_each_player_only_their_own_hand() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut deck = Deck::standard();
+        let hands = BTreeMap::from([
+            (PLAYER_ONE_ID, deck.deal_hand(2).unwrap()),
+            (PLAYER_TWO_ID, deck.deal_hand(3).unwrap()),
+        ]);
+
+        dispatch_hidden_hands(&ServerChannel::new(sender), &hands, |views| views).await;
+
+        let Ok(GameServerEvent::DispatchViews {
+            views,
+            spectator_view,
+        }) = receiver.try_recv()
+        else {
+            panic!("Expected the hands to be sent as views");
+        };
+        let views: BTreeMap<u8, BTreeMap<u8, HandView>> = views
+            .into_iter()
+            .map(|(player_id, event)| (player_id, game::deserialize_event(event)))
+            .collect();
+        let hidden = |count| HandView::Hidden { count };
+        assert_eq!(
+            views,
+            BTreeMap::from([
+                (
+                    PLAYER_ONE_ID,
+                    BTreeMap::from([
+                        (PLAYER_ONE_ID, hands[&PLAYER_ONE_ID].view(true)),
+                        (PLAYER_TWO_ID, hidden(3)),
+                    ])
+                ),
+                (
+                    PLAYER_TWO_ID,
+                    BTreeMap::from([
+                        (PLAYER_ONE_ID, hidden(2)),
+                        (PLAYER_TWO_ID, hands[&PLAYER_TWO_ID].view(true)),
+                    ])
+                ),
+            ])
+        );
+        assert_eq!(
+            game::deserialize_event::<BTreeMap<u8, HandView>>(spectator_view),
+            BTreeMap::from([(PLAYER_ONE_ID, hidden(2)), (PLAYER_TWO_ID, hidden(3))])
+        );
+    }
+}
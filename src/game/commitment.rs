@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rand::RngCore;
+use ring::digest::{self, SHA256};
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{Codec, Json};
+use crate::game::SimultaneousMoves;
+
+/// The number of random bytes a player adds to their move before committing to it, so that moves
+/// with few possible values cannot be found by trying each one against the commitment.
+pub const SALT_LENGTH: usize = 16;
+
+/// A player's sealed move, the SHA-256 digest of the move followed by a salt only they know. It
+/// binds them to the move without giving it away, until they open it by sending both.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Commitment([u8; 32]);
+
+impl Commitment {
+    /// Seals the move with the given salt, which the player keeps to open it later.
+    pub fn seal<M: Serialize>(player_move: &M, salt: &[u8]) -> Commitment {
+        let mut context = digest::Context::new(&SHA256);
+        context.update(&Json.encode(player_move).unwrap());
+        context.update(salt);
+
+        let mut commitment = [0; 32];
+        commitment.copy_from_slice(context.finish().as_ref());
+        Commitment(commitment)
+    }
+
+    /// Whether the move and salt are those the commitment was sealed with.
+    pub fn is_opened_by<M: Serialize>(&self, player_move: &M, salt: &[u8]) -> bool {
+        *self == Commitment::seal(player_move, salt)
+    }
+}
+
+impl fmt::Debug for Commitment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Commitment(")?;
+        for byte in self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Chooses a salt for a player to seal their move with, at random so that nobody else can guess
+/// it.
+pub fn generate_salt() -> [u8; SALT_LENGTH] {
+    let mut salt = [0; SALT_LENGTH];
+    rand::thread_rng().fill_bytes(&mut salt);
+
+    salt
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, thiserror::Error)]
+pub enum OpenError {
+    #[error("Moves cannot be opened until every player has committed to one.")]
+    StillCommitting,
+    #[error("The player has not committed to a move.")]
+    NotCommitted,
+    #[error("The player has already opened their move.")]
+    AlreadyOpened,
+    #[error("The move does not match the one the player committed to.")]
+    Mismatch,
+}
+
+/// Collects a sealed move from each player in games where players move at the same time, then has
+/// them open their moves once every player has committed to one. No move is seen by anyone, the
+/// server included, until it is too late for any other player to change theirs.
+///
+/// Games trusting the server to hold moves back use `SimultaneousMoves`, which takes each move
+/// as it is made.
+#[derive(Debug)]
+pub struct CommittedMoves<M> {
+    player_ids: Vec<u8>,
+    commitments: BTreeMap<u8, Commitment>,
+    opened: SimultaneousMoves<M>,
+}
+
+impl<M: Serialize> CommittedMoves<M> {
+    pub fn new(player_ids: &[u8]) -> CommittedMoves<M> {
+        CommittedMoves {
+            player_ids: player_ids.to_vec(),
+            commitments: BTreeMap::new(),
+            opened: SimultaneousMoves::new(player_ids),
+        }
+    }
+
+    pub fn has_committed(&self, player_id: u8) -> bool {
+        self.commitments.contains_key(&player_id)
+    }
+
+    /// The players who are yet to commit to a move this round, or once all have, yet to open it.
+    pub fn waiting_on(&self) -> Vec<u8> {
+        match self.all_committed() {
+            true => self.opened.waiting_on(),
+            false => self
+                .player_ids
+                .iter()
+                .copied()
+                .filter(|player_id| !self.has_committed(*player_id))
+                .collect(),
+        }
+    }
+
+    pub fn all_committed(&self) -> bool {
+        self.player_ids
+            .iter()
+            .all(|player_id| self.has_committed(*player_id))
+    }
+
+    /// Records the player's sealed move for the round.
+    ///
+    /// # Returns
+    ///
+    /// Whether the commitment was recorded, which it is not if the player has already committed
+    /// this round or is not one of the players.
+    pub fn commit(&mut self, player_id: u8, commitment: Commitment) -> bool {
+        if !self.player_ids.contains(&player_id) || self.has_committed(player_id) {
+            return false;
+        }
+
+        self.commitments.insert(player_id, commitment);
+        true
+    }
+
+    /// Records the move the player committed to, once every player has committed, checking it
+    /// against their commitment.
+    pub fn open(&mut self, player_id: u8, player_move: M, salt: &[u8]) -> Result<(), OpenError> {
+        let commitment = self
+            .commitments
+            .get(&player_id)
+            .ok_or(OpenError::NotCommitted)?;
+        if !self.all_committed() {
+            return Err(OpenError::StillCommitting);
+        }
+        if self.opened.has_moved(player_id) {
+            return Err(OpenError::AlreadyOpened);
+        }
+        if !commitment.is_opened_by(&player_move, salt) {
+            return Err(OpenError::Mismatch);
+        }
+
+        self.opened.commit(player_id, player_move);
+        Ok(())
+    }
+
+    /// Takes the moves of every player once all of them have been opened, ready for the next
+    /// round.
+    pub fn reveal(&mut self) -> Option<BTreeMap<u8, M>> {
+        let moves = self.opened.reveal()?;
+        self.commitments.clear();
+
+        Some(moves)
+    }
+
+    /// Takes the moves opened so far, for ending a round before every player has opened theirs,
+    /// such as when one runs out of time.
+    pub fn take(&mut self) -> BTreeMap<u8, M> {
+        self.commitments.clear();
+        self.opened.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+    fn committed_moves() -> CommittedMoves<u8> {
+        CommittedMoves::new(&[PLAYER_ONE_ID, PLAYER_TWO_ID])
+    }
+
+    #[test]
+    fn commitment_opened_only_by_same_move_and_salt() {
+        let salt = generate_salt();
+        let commitment = Commitment::seal(&"rock", &salt);
+
+        assert!(commitment.is_opened_by(&"rock", &salt));
+        assert!(!commitment.is_opened_by(&"paper", &salt));
+        assert!(!commitment.is_opened_by(&"rock", &generate_salt()));
+    }
+
+    #[test]
+    fn moves_revealed_once_every_player_has_committed_and_opened() {
+        let mut moves = committed_moves();
+        let salts = [generate_salt(), generate_salt()];
+
+        assert!(moves.commit(PLAYER_ONE_ID, Commitment::seal(&1, &salts[0])));
+        assert_eq!(
+            moves.open(PLAYER_ONE_ID, 1, &salts[0]),
+            Err(OpenError::StillCommitting)
+        );
+        assert!(moves.commit(PLAYER_TWO_ID, Commitment::seal(&2, &salts[1])));
+        assert_eq!(moves.open(PLAYER_ONE_ID, 1, &salts[0]), Ok(()));
+        assert_eq!(moves.waiting_on(), [PLAYER_TWO_ID]);
+        assert_eq!(moves.reveal(), None);
+        assert_eq!(moves.open(PLAYER_TWO_ID, 2, &salts[1]), Ok(()));
+
+        assert_eq!(
+            moves.reveal(),
+            Some(BTreeMap::from([(PLAYER_ONE_ID, 1), (PLAYER_TWO_ID, 2)]))
+        );
+        assert_eq!(moves.waiting_on(), [PLAYER_ONE_ID, PLAYER_TWO_ID]);
+    }
+
+    #[test]
+    fn moves_refused_unless_they_match_a_single_commitment() {
+        let mut moves = committed_moves();
+        let salt = generate_salt();
+        moves.commit(PLAYER_ONE_ID, Commitment::seal(&1, &salt));
+        moves.commit(PLAYER_TWO_ID, Commitment::seal(&2, &salt));
+
+        assert!(!moves.commit(PLAYER_ONE_ID, Commitment::seal(&3, &salt)));
+        assert!(!moves.commit(3, Commitment::seal(&3, &salt)));
+        assert_eq!(moves.open(3, 3, &salt), Err(OpenError::NotCommitted));
+        assert_eq!(
+            moves.open(PLAYER_ONE_ID, 3, &salt),
+            Err(OpenError::Mismatch)
+        );
+        assert_eq!(moves.open(PLAYER_ONE_ID, 1, &salt), Ok(()));
+        assert_eq!(
+            moves.open(PLAYER_ONE_ID, 1, &salt),
+            Err(OpenError::AlreadyOpened)
+        );
+    }
+}
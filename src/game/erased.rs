@@ -0,0 +1,116 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+
+/// A game event as the server and client carry it, without knowing which game it belongs to.
+///
+/// The event is encoded only once, by the codec of the connection it is sent over. Codecs that
+/// are human readable show it as it is, while binary codecs, which cannot decode values of no
+/// known type, are given it as a tree naming the type of each of its values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErasedEvent(pub(crate) Value);
+
+impl Serialize for ErasedEvent {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            self.0.serialize(serializer)
+        } else {
+            Node::from(&self.0).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ErasedEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            Value::deserialize(deserializer).map(ErasedEvent)
+        } else {
+            Node::deserialize(deserializer).map(|node| ErasedEvent(node.into()))
+        }
+    }
+}
+
+/// A JSON value that describes itself to binary codecs.
+#[derive(Serialize, Deserialize)]
+enum Node {
+    Null,
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(String, Node)>),
+}
+
+impl From<&Value> for Node {
+    fn from(value: &Value) -> Node {
+        match value {
+            Value::Null => Node::Null,
+            Value::Bool(value) => Node::Bool(*value),
+            Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+                (Some(number), _) => Node::Unsigned(number),
+                (None, Some(number)) => Node::Signed(number),
+                // Numbers that fit neither are always floats, as JSON values hold nothing larger
+                (None, None) => Node::Float(number.as_f64().unwrap_or_default()),
+            },
+            Value::String(value) => Node::String(value.clone()),
+            Value::Array(values) => Node::Array(values.iter().map(Node::from).collect()),
+            Value::Object(fields) => Node::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), Node::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<Node> for Value {
+    fn from(node: Node) -> Value {
+        match node {
+            Node::Null => Value::Null,
+            Node::Bool(value) => Value::Bool(value),
+            Node::Unsigned(number) => Value::Number(number.into()),
+            Node::Signed(number) => Value::Number(number.into()),
+            Node::Float(number) => Number::from_f64(number).map_or(Value::Null, Value::Number),
+            Node::String(value) => Value::String(value),
+            Node::Array(nodes) => Value::Array(nodes.into_iter().map(Value::from).collect()),
+            Node::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, node)| (name, Value::from(node)))
+                    .collect::<Map<_, _>>(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::connection::{Codec, Json, SUPPORTED_CODECS};
+
+    use super::*;
+
+    #[test]
+    fn every_codec_round_trips_event() {
+        let event = ErasedEvent(json!({
+            "kind": "Moved",
+            "payload": {"Moved": {"cells": [0, -1, 2.5], "name": "X", "won": null, "last": true}},
+        }));
+
+        for codec in SUPPORTED_CODECS {
+            let bytes = codec.encode(&event).unwrap();
+
+            assert_eq!(codec.decode::<ErasedEvent>(&bytes).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn json_shows_event_as_it_is() {
+        let event = ErasedEvent(json!({"Moved": {"cell": 4}}));
+
+        assert_eq!(Json.encode(&event).unwrap(), br#"{"Moved":{"cell":4}}"#);
+    }
+}
@@ -0,0 +1,117 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+
+/// The generator a game's chance is drawn from, such as its dice rolls and shuffles, handed to
+/// each `GameServer` by the server so that clients have no say in it.
+///
+/// Every number it gives follows from its seed, so a game given the same seed and the same moves
+/// plays out the same way again, as when reproducing a game from the seed logged as it began.
+#[derive(Clone, Debug)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    /// Creates a generator from a seed chosen at random.
+    pub fn new() -> GameRng {
+        GameRng::from_seed(rand::random())
+    }
+
+    pub fn from_seed(seed: u64) -> GameRng {
+        GameRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed the generator was created from, for creating another that gives the same numbers.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Rolls a die with the given number of sides, numbered from one.
+    ///
+    /// # Panics
+    ///
+    /// If the die has no sides.
+    pub fn roll_die(&mut self, sides: u8) -> u8 {
+        self.rng.gen_range(1..=sides)
+    }
+
+    /// Rolls the given number of dice, each with the given number of sides.
+    pub fn roll_dice(&mut self, count: usize, sides: u8) -> Vec<u8> {
+        (0..count).map(|_| self.roll_die(sides)).collect()
+    }
+
+    /// Puts the items in a random order, such as when shuffling a deck of cards.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        items.shuffle(&mut self.rng);
+    }
+
+    /// Picks one of the items at random, or `None` if there are none.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.choose(&mut self.rng)
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> GameRng {
+        GameRng::new()
+    }
+}
+
+/// Lets the generator be used wherever `rand` expects one, drawing from the same sequence.
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_same_rolls_and_shuffles() {
+        let mut one = GameRng::from_seed(42);
+        let mut two = GameRng::from_seed(one.seed());
+
+        assert_eq!(one.roll_dice(10, 6), two.roll_dice(10, 6));
+        let (mut deck_one, mut deck_two): (Vec<u8>, Vec<u8>) =
+            ((0..52).collect(), (0..52).collect());
+        one.shuffle(&mut deck_one);
+        two.shuffle(&mut deck_two);
+        assert_eq!(deck_one, deck_two);
+        assert_eq!(one.choose(&deck_one), two.choose(&deck_two));
+    }
+
+    #[test]
+    fn roll_die_stays_within_its_sides() {
+        let mut rng = GameRng::new();
+
+        let rolls = rng.roll_dice(100, 6);
+        assert!(rolls.iter().all(|roll| (1..=6).contains(roll)));
+        assert_eq!(rng.roll_die(1), 1);
+    }
+
+    #[test]
+    fn choose_from_nothing_gives_none() {
+        let mut rng = GameRng::new();
+
+        assert_eq!(rng.choose::<u8>(&[]), None);
+    }
+}
@@ -0,0 +1,261 @@
+use std::io;
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use async_trait::async_trait;
+use eframe::egui;
+use tokio::net::TcpListener;
+use tokio::runtime;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use crate::client::{Client, ClientBuilder, Frontend, Paint};
+use crate::game::GameDescriptor;
+use crate::gui::app::GuiApp;
+use crate::lobby::{self, ConnectionOptions, JoinToken, Lobby};
+use crate::locale::Locale;
+use crate::server::{PlayerName, PLAYER_ONE_ID};
+
+mod app;
+
+/// Opens a desktop window for hosting or joining a game and playing it, returning once the user
+/// closes the window.
+///
+/// Games are played by the same client as in a terminal, through a `GuiFrontend` that sorts what
+/// the game shows into the window's panes. Cells of the board can be clicked to make a move, and
+/// anything else the game asks for, such as commands, is typed into the message pane.
+pub fn run(locale: Locale) -> eframe::Result {
+    eframe::run_native(
+        "Rust Game Server",
+        eframe::NativeOptions::default(),
+        Box::new(move |creation| Ok(Box::new(GuiApp::new(creation.egui_ctx.clone(), locale)))),
+    )
+}
+
+/// What the game has given the window to show, shared between the frontend the client plays
+/// through and the window drawing it.
+#[derive(Debug, Default)]
+pub(crate) struct Screen {
+    /// The board as last laid out by the game.
+    pub(crate) board: Option<String>,
+    pub(crate) messages: Vec<(String, Option<Paint>)>,
+    /// What the user is being asked for, while the game waits on their move.
+    pub(crate) prompt: Option<String>,
+    /// Shown in the status bar while there is no prompt, such as how to join the game being
+    /// hosted.
+    pub(crate) status: String,
+}
+
+/// Plays through the window, showing what the game writes in its panes and reading the lines the
+/// user enters, whether typed or by clicking the board.
+pub struct GuiFrontend {
+    screen: Arc<Mutex<Screen>>,
+    lines: tokio::sync::Mutex<UnboundedReceiver<String>>,
+    context: egui::Context,
+}
+
+impl GuiFrontend {
+    /// Creates the frontend along with the sender for lines entered by the user, which are read
+    /// until the sender is dropped.
+    pub(crate) fn new(
+        screen: Arc<Mutex<Screen>>,
+        context: egui::Context,
+    ) -> (GuiFrontend, UnboundedSender<String>) {
+        let (sender, lines) = mpsc::unbounded_channel();
+        let frontend = GuiFrontend {
+            screen,
+            lines: tokio::sync::Mutex::new(lines),
+            context,
+        };
+
+        (frontend, sender)
+    }
+
+    /// Changes what is shown on the screen, redrawing the window to show it.
+    fn update(&self, change: impl FnOnce(&mut Screen)) {
+        change(&mut self.screen.lock().unwrap());
+        self.context.request_repaint();
+    }
+}
+
+#[async_trait]
+impl Frontend for GuiFrontend {
+    fn show_message(&self, message: &str, paint: Option<Paint>) -> io::Result<()> {
+        self.update(|screen| screen.messages.push((String::from(message), paint)));
+        Ok(())
+    }
+
+    fn render_board(&self, board: &str) -> io::Result<()> {
+        self.update(|screen| screen.board = Some(String::from(board)));
+        Ok(())
+    }
+
+    async fn read_line(&self) -> io::Result<String> {
+        match self.lines.lock().await.recv().await {
+            Some(line) => Ok(format!("{}\n", line)),
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    async fn prompt_for_move(&self, prompt: &str) -> io::Result<String> {
+        self.update(|screen| screen.prompt = Some(String::from(prompt)));
+        let line = self.read_line().await;
+        self.update(|screen| screen.prompt = None);
+
+        line
+    }
+}
+
+/// How the user has asked to play, as chosen in the window's dialogs.
+pub(crate) enum Setup {
+    Host {
+        port: u16,
+        game: &'static dyn GameDescriptor,
+    },
+    Join {
+        address: String,
+        join_token: JoinToken,
+        name: Option<PlayerName>,
+        game: &'static dyn GameDescriptor,
+    },
+}
+
+/// Plays the game set up as asked through the frontend, telling the user how it went in the
+/// status bar once it is over.
+///
+/// Game clients can only be run on the thread that created them, so the game is given a thread
+/// of its own rather than holding up the window.
+pub(crate) fn spawn(setup: Setup, frontend: GuiFrontend, locale: Locale) {
+    thread::spawn(move || {
+        let screen = Arc::clone(&frontend.screen);
+        let context = frontend.context.clone();
+        let played = match runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime.block_on(async {
+                match setup {
+                    Setup::Host { port, game } => host(port, game, frontend, locale).await,
+                    Setup::Join {
+                        address,
+                        join_token,
+                        name,
+                        game,
+                    } => join(&address, join_token, name, game, frontend, locale).await,
+                }
+            }),
+            Err(error) => Err(error.to_string()),
+        };
+
+        screen.lock().unwrap().status = match played {
+            Ok(()) => String::from("The game has finished, close the window to exit."),
+            Err(message) => message,
+        };
+        context.request_repaint();
+    });
+}
+
+/// Hosts the game on the port, playing it as player one once someone has joined. Fails with the
+/// message to show the user should the game not be able to start.
+async fn host(
+    port: u16,
+    game: &'static dyn GameDescriptor,
+    frontend: GuiFrontend,
+    locale: Locale,
+) -> Result<(), String> {
+    let bound = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+        .await
+        .and_then(|listener| Ok((listener.local_addr()?, listener)));
+    let (address, listener) = bound
+        .map_err(|error| locale.message("bind-failed", &[("port", &port), ("error", &error)]))?;
+    // In the case that the port given was "0", a random port will have been allocated
+    let port = address.port();
+    let mut lobby = Lobby::new(listener);
+    let join_token = lobby.join_token();
+    frontend.update(|screen| {
+        screen.status = locale.message("host-details", &[("port", &port), ("token", &join_token)])
+    });
+
+    tokio::spawn(async move {
+        lobby.set_game(game);
+        if let Some(mut server) = lobby.set_up_online_server().await {
+            server.init().await;
+        }
+    });
+
+    let options = ConnectionOptions {
+        game: Some(String::from(game.id())),
+        ..ConnectionOptions::default()
+    };
+    let (connection, session) =
+        lobby::connect_to_game((Ipv4Addr::LOCALHOST, port), join_token, options)
+            .await
+            .map_err(|error| error.to_string())?;
+    let client = ClientBuilder::new().online(connection, session, PLAYER_ONE_ID, frontend, game);
+    play(client, locale).await;
+
+    Ok(())
+}
+
+/// Joins the game at the address, taking whichever seat the host grants. Fails with the message
+/// to show the user should the game not be able to be joined.
+async fn join(
+    address: &str,
+    join_token: JoinToken,
+    name: Option<PlayerName>,
+    game: &'static dyn GameDescriptor,
+    frontend: GuiFrontend,
+    locale: Locale,
+) -> Result<(), String> {
+    frontend.update(|screen| screen.status = format!("Joining the game at {}...", address));
+    let options = ConnectionOptions {
+        name,
+        game: Some(String::from(game.id())),
+        ..ConnectionOptions::default()
+    };
+    let (connection, session) = lobby::connect_to_game(address, join_token, options)
+        .await
+        .map_err(|error| error.to_string())?;
+    frontend.update(|screen| screen.status.clear());
+
+    let client = ClientBuilder::new().joined(connection, session, frontend, game);
+    play(client, locale).await;
+
+    Ok(())
+}
+
+async fn play(mut client: Client<'_>, locale: Locale) {
+    // The window shows paint as colours of its own, which any screen can show
+    client.set_color(true);
+    client.set_locale(locale);
+    client.play_game().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn move_prompt_shown_until_user_answers() {
+        let screen = Arc::new(Mutex::new(Screen::default()));
+        let (frontend, lines) = GuiFrontend::new(Arc::clone(&screen), egui::Context::default());
+
+        let answer = tokio::spawn(async move {
+            let answer = frontend.prompt_for_move("Cell (1-9):").await;
+            (frontend, answer)
+        });
+        tokio::task::yield_now().await;
+        assert_eq!(
+            screen.lock().unwrap().prompt.as_deref(),
+            Some("Cell (1-9):")
+        );
+
+        lines.send(String::from("5")).unwrap();
+        let (frontend, answer) = answer.await.unwrap();
+        assert_eq!(answer.unwrap(), "5\n");
+        assert_eq!(screen.lock().unwrap().prompt, None);
+
+        drop(lines);
+        assert_eq!(
+            frontend.read_line().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
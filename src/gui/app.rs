@@ -0,0 +1,367 @@
+use std::sync::{Arc, Mutex};
+
+use eframe::egui::{
+    self, Align, Button, Color32, ComboBox, Context, Key, Layout, RichText, ScrollArea, Ui, Vec2,
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::client::Paint;
+use crate::game::{self, GameDescriptor};
+use crate::gui::{self, GuiFrontend, Screen, Setup};
+use crate::locale::Locale;
+
+/// The size of each cell of a board that can be clicked.
+const CELL_SIZE: Vec2 = Vec2::splat(64.0);
+
+/// What the window is showing, from choosing how to play through to the game itself.
+enum Stage {
+    Menu,
+    Host(HostDialog),
+    Join(JoinDialog),
+    Playing(Playing),
+}
+
+struct HostDialog {
+    port: String,
+    game: &'static dyn GameDescriptor,
+    error: Option<String>,
+}
+
+struct JoinDialog {
+    address: String,
+    join_token: String,
+    name: String,
+    game: &'static dyn GameDescriptor,
+    error: Option<String>,
+}
+
+struct Playing {
+    screen: Arc<Mutex<Screen>>,
+    /// Lines entered by the user, read by the game through its frontend.
+    lines: UnboundedSender<String>,
+    /// The line being typed into the message pane.
+    input: String,
+}
+
+pub(crate) struct GuiApp {
+    context: Context,
+    locale: Locale,
+    stage: Stage,
+}
+
+impl GuiApp {
+    pub(crate) fn new(context: Context, locale: Locale) -> GuiApp {
+        GuiApp {
+            context,
+            locale,
+            stage: Stage::Menu,
+        }
+    }
+
+    /// Starts playing the game set up in a dialog, switching the window over to show it.
+    fn start(&mut self, setup: Setup) {
+        let screen = Arc::new(Mutex::new(Screen::default()));
+        let (frontend, lines) = GuiFrontend::new(Arc::clone(&screen), self.context.clone());
+        gui::spawn(setup, frontend, self.locale);
+
+        self.stage = Stage::Playing(Playing {
+            screen,
+            lines,
+            input: String::new(),
+        });
+    }
+}
+
+impl eframe::App for GuiApp {
+    fn update(&mut self, context: &Context, _frame: &mut eframe::Frame) {
+        let next = match &mut self.stage {
+            Stage::Menu => show_menu(context),
+            Stage::Host(dialog) => show_host_dialog(context, dialog),
+            Stage::Join(dialog) => show_join_dialog(context, dialog),
+            Stage::Playing(playing) => {
+                show_game(context, playing);
+                None
+            }
+        };
+
+        match next {
+            Some(Next::Stage(stage)) => self.stage = stage,
+            Some(Next::Start(setup)) => self.start(setup),
+            None => {}
+        }
+    }
+}
+
+/// Where the window goes following what the user did in a menu or dialog.
+enum Next {
+    Stage(Stage),
+    Start(Setup),
+}
+
+fn show_menu(context: &Context) -> Option<Next> {
+    let mut next = None;
+    egui::CentralPanel::default().show(context, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.heading("Rust Game Server");
+            ui.add_space(16.0);
+            if ui.button("Host a game").clicked() {
+                next = Some(Next::Stage(Stage::Host(HostDialog {
+                    port: String::from("0"),
+                    game: game::GAMES[0],
+                    error: None,
+                })));
+            }
+            if ui.button("Join a game").clicked() {
+                next = Some(Next::Stage(Stage::Join(JoinDialog {
+                    address: String::new(),
+                    join_token: String::new(),
+                    name: String::new(),
+                    game: game::GAMES[0],
+                    error: None,
+                })));
+            }
+        });
+    });
+
+    next
+}
+
+fn show_host_dialog(context: &Context, dialog: &mut HostDialog) -> Option<Next> {
+    let mut next = None;
+    egui::CentralPanel::default().show(context, |ui| {
+        ui.heading("Host a game");
+        egui::Grid::new("host").num_columns(2).show(ui, |ui| {
+            ui.label("Port");
+            ui.text_edit_singleline(&mut dialog.port)
+                .on_hover_text("0 to have a free port chosen");
+            ui.end_row();
+            ui.label("Game");
+            choose_game(ui, "host-game", &mut dialog.game);
+            ui.end_row();
+        });
+        show_error(ui, &dialog.error);
+
+        ui.horizontal(|ui| {
+            if ui.button("Back").clicked() {
+                next = Some(Next::Stage(Stage::Menu));
+            }
+            if ui.button("Host").clicked() {
+                match dialog.port.trim().parse() {
+                    Ok(port) => {
+                        let game = dialog.game;
+                        next = Some(Next::Start(Setup::Host { port, game }));
+                    }
+                    Err(_) => dialog.error = Some(String::from("The port must be a number.")),
+                }
+            }
+        });
+    });
+
+    next
+}
+
+fn show_join_dialog(context: &Context, dialog: &mut JoinDialog) -> Option<Next> {
+    let mut next = None;
+    egui::CentralPanel::default().show(context, |ui| {
+        ui.heading("Join a game");
+        egui::Grid::new("join").num_columns(2).show(ui, |ui| {
+            ui.label("Address");
+            ui.text_edit_singleline(&mut dialog.address)
+                .on_hover_text("Such as 192.168.0.2:8000");
+            ui.end_row();
+            ui.label("Join token");
+            ui.text_edit_singleline(&mut dialog.join_token);
+            ui.end_row();
+            ui.label("Name");
+            ui.text_edit_singleline(&mut dialog.name)
+                .on_hover_text("Only needed if the host is keeping ratings");
+            ui.end_row();
+            ui.label("Game");
+            choose_game(ui, "join-game", &mut dialog.game);
+            ui.end_row();
+        });
+        show_error(ui, &dialog.error);
+
+        ui.horizontal(|ui| {
+            if ui.button("Back").clicked() {
+                next = Some(Next::Stage(Stage::Menu));
+            }
+            if ui.button("Join").clicked() {
+                match read_join_dialog(dialog) {
+                    Ok(setup) => next = Some(Next::Start(setup)),
+                    Err(error) => dialog.error = Some(error),
+                }
+            }
+        });
+    });
+
+    next
+}
+
+/// Checks what was entered into the join dialog, failing with what the user needs to correct.
+fn read_join_dialog(dialog: &JoinDialog) -> Result<Setup, String> {
+    let join_token = dialog
+        .join_token
+        .parse()
+        .map_err(|_| String::from("The join token should be as given by the host."))?;
+    let name = match dialog.name.trim() {
+        "" => None,
+        name => Some(name.parse().map_err(|error| format!("{}", error))?),
+    };
+
+    Ok(Setup::Join {
+        address: String::from(dialog.address.trim()),
+        join_token,
+        name,
+        game: dialog.game,
+    })
+}
+
+fn choose_game(ui: &mut Ui, id: &str, chosen: &mut &'static dyn GameDescriptor) {
+    ComboBox::from_id_salt(id)
+        .selected_text(chosen.id())
+        .show_ui(ui, |ui| {
+            for &game in game::GAMES {
+                if ui
+                    .selectable_label(chosen.id() == game.id(), game.id())
+                    .clicked()
+                {
+                    *chosen = game;
+                }
+            }
+        });
+}
+
+fn show_error(ui: &mut Ui, error: &Option<String>) {
+    if let Some(error) = error {
+        ui.colored_label(Color32::RED, error);
+    }
+}
+
+/// Shows the game with the board in the middle, messages to one side and the status bar below,
+/// passing on what the user enters to the game.
+fn show_game(context: &Context, playing: &mut Playing) {
+    let screen = playing.screen.lock().unwrap();
+    let mut entered = None;
+
+    egui::TopBottomPanel::bottom("status").show(context, |ui| {
+        match &screen.prompt {
+            Some(prompt) => ui.strong(prompt),
+            None => ui.label(&screen.status),
+        };
+    });
+
+    egui::SidePanel::right("messages")
+        .resizable(true)
+        .default_width(320.0)
+        .show(context, |ui| {
+            ui.with_layout(Layout::bottom_up(Align::LEFT), |ui| {
+                let field = ui.text_edit_singleline(&mut playing.input);
+                if field.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter)) {
+                    entered = Some(std::mem::take(&mut playing.input));
+                    field.request_focus();
+                }
+                ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                    ui.with_layout(Layout::top_down(Align::LEFT), |ui| {
+                        for (message, paint) in &screen.messages {
+                            ui.label(painted(message, *paint));
+                        }
+                    });
+                });
+            });
+        });
+
+    egui::CentralPanel::default().show(context, |ui| match &screen.board {
+        Some(board) => match board_cells(board) {
+            Some(rows) => {
+                let clickable = screen.prompt.is_some();
+                if let Some(cell) = show_board(ui, &rows, clickable) {
+                    entered = Some(cell.to_string());
+                }
+            }
+            None => {
+                ui.monospace(board);
+            }
+        },
+        None => {
+            ui.label("The board will be shown once the game has started.");
+        }
+    });
+
+    drop(screen);
+    if let Some(line) = entered {
+        // The game may have finished, in which case there is nothing left to read the line
+        let _ = playing.lines.send(line);
+    }
+}
+
+/// Shows the board as a grid of buttons, returning the number of the cell clicked, if any.
+fn show_board(ui: &mut Ui, rows: &[Vec<String>], clickable: bool) -> Option<usize> {
+    let mut clicked = None;
+    egui::Grid::new("board").spacing([4.0, 4.0]).show(ui, |ui| {
+        for (row_index, row) in rows.iter().enumerate() {
+            for (column_index, cell) in row.iter().enumerate() {
+                let button = Button::new(RichText::new(cell).size(32.0)).min_size(CELL_SIZE);
+                if ui.add_enabled(clickable, button).clicked() {
+                    clicked = Some(row_index * row.len() + column_index + 1);
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    clicked
+}
+
+/// Reads the cells of a board drawn as rows of cells separated by `|`, between a pair of rules
+/// made up of underscores. `None` for a board drawn in any other way, which is shown as it is.
+fn board_cells(board: &str) -> Option<Vec<Vec<String>>> {
+    let rows: Vec<Vec<String>> = board
+        .lines()
+        .filter(|line| !line.is_empty() && !line.chars().all(|c| c == '_'))
+        .map(|line| {
+            line.split('|')
+                .map(|cell| cell.trim().to_string())
+                .collect()
+        })
+        .collect();
+
+    let width = rows.first()?.len();
+    (width > 1 && rows.iter().all(|row| row.len() == width)).then_some(rows)
+}
+
+fn painted(message: &str, paint: Option<Paint>) -> RichText {
+    let text = RichText::new(message);
+    match paint {
+        Some(Paint::PlayerOne) => text.color(Color32::from_rgb(0, 170, 190)),
+        Some(Paint::PlayerTwo) => text.color(Color32::from_rgb(200, 160, 0)),
+        Some(Paint::Error) => text.color(Color32::RED),
+        Some(Paint::Highlight) => text.strong(),
+        Some(Paint::Changed) => text.underline(),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_board_read_as_rows_of_cells() {
+        let board = "_________\n  | X | X\nO |   |  \nO |   | X\n_________\n";
+
+        assert_eq!(
+            board_cells(board).unwrap(),
+            [["", "X", "X"], ["O", "", ""], ["O", "", "X"]]
+        );
+    }
+
+    #[test]
+    fn board_drawn_otherwise_left_as_it_is() {
+        assert_eq!(board_cells("1XX/O56/O8X"), None);
+        assert_eq!(
+            board_cells("Row one has X in the middle.\nRow two | is empty."),
+            None
+        );
+    }
+}
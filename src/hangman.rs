@@ -0,0 +1,1350 @@
+use std::fmt::Display;
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::hangman::puzzle::Puzzle;
+pub use crate::hangman::puzzle::{MAX_MISSES, MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+use crate::hangman::ClientEvent::{LetterGuessed, WordChosen};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod bot;
+mod puzzle;
+
+/// Identifies Hangman among the games in `game::GAMES`.
+pub const GAME_ID: &str = "hangman";
+
+/// The player who chooses the secret word.
+pub const SETTER_ID: u8 = PLAYER_ONE_ID;
+/// The player who guesses the secret word, taking every turn once it has been chosen.
+pub const GUESSER_ID: u8 = PLAYER_TWO_ID;
+
+/// Hangman, where one player chooses a secret word and the other guesses it a letter at a time.
+/// The word is only ever sent to the player who chose it until the game is over. The guesser wins
+/// by revealing every letter before making `MAX_MISSES` wrong guesses.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Hangman;
+
+impl GameDescriptor for Hangman {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(HangmanServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(HangmanClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(HangmanClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(HangmanClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(HangmanClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// Asks the setter to choose the secret word, before any letters are guessed.
+    WordRequested {
+        player_id: u8,
+    },
+    /// The setter has chosen a word of the given number of letters.
+    WordChosen {
+        player_id: u8,
+        length: u8,
+    },
+    /// The secret word, sent only to the player who chose it.
+    SecretWord {
+        word: String,
+    },
+    /// The letters of the word guessed so far, with `None` in place of the others, along with the
+    /// letters guessed that the word does not contain.
+    PuzzleUpdated {
+        letters: Vec<Option<char>>,
+        misses: Vec<char>,
+        misses_remaining: u8,
+    },
+    /// Sent before the puzzle showing the guess, with the number of times the letter appears.
+    LetterGuessed {
+        player_id: u8,
+        letter: char,
+        found: u8,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    /// Reveals the secret word to every player now that the game is over.
+    GameOver {
+        winner: u8,
+        word: String,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The secret word must be 3 to 16 letters from a to z.")]
+    InvalidWord,
+    #[error("The secret word has already been chosen.")]
+    WordAlreadyChosen,
+    #[error("No letters can be guessed until the secret word has been chosen.")]
+    WordNotChosen,
+    #[error("{letter} is not a letter from a to z.")]
+    InvalidLetter { letter: char },
+    #[error("{letter} has already been guessed.")]
+    AlreadyGuessed { letter: char },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct HangmanServer {
+    /// Set once the setter has chosen the secret word.
+    puzzle: Option<Puzzle>,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the winner has been sent, after which any guesses still arriving are ignored.
+    game_over: bool,
+}
+
+impl HangmanServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> HangmanServer {
+        HangmanServer {
+            puzzle: None,
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_error_event(&self, player_id: u8, error: Error) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::ErrorOccurred { error },
+        )
+        .await
+    }
+
+    async fn dispatch_word_requested_event(&self) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer {
+                player_id: SETTER_ID,
+            },
+            ServerEvent::WordRequested {
+                player_id: SETTER_ID,
+            },
+        )
+        .await
+    }
+
+    /// Reminds the setter of their word, if they have chosen it.
+    async fn dispatch_secret_word_event(&self) {
+        if let Some(puzzle) = &self.puzzle {
+            let word = puzzle.get_word();
+            self.dispatch_event(
+                DispatchMode::SinglePlayer {
+                    player_id: SETTER_ID,
+                },
+                ServerEvent::SecretWord { word },
+            )
+            .await
+        }
+    }
+
+    /// Sends the letters guessed so far, never those yet to be guessed, if the word has been
+    /// chosen.
+    async fn dispatch_puzzle_updated_event(&self, dispatch_mode: DispatchMode) {
+        if let Some(puzzle) = &self.puzzle {
+            self.dispatch_event(
+                dispatch_mode,
+                ServerEvent::PuzzleUpdated {
+                    letters: puzzle.get_letters(),
+                    misses: puzzle.get_misses(),
+                    misses_remaining: puzzle.misses_remaining(),
+                },
+            )
+            .await
+        }
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        self.dispatch_event(
+            dispatch_mode,
+            ServerEvent::PlayerTurn {
+                player_id: GUESSER_ID,
+            },
+        )
+        .await
+    }
+
+    async fn start_turn(&mut self) {
+        self.rejected_moves.reset();
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: GUESSER_ID,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, winner: u8) {
+        self.game_over = true;
+        let word = self
+            .puzzle
+            .as_ref()
+            .map(Puzzle::get_word)
+            .unwrap_or_default();
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::GameOver { winner, word },
+        )
+        .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver {
+                winner: Some(winner),
+            })
+            .await
+    }
+
+    /// Ends the guesser's turn, ending the game if the word has been revealed or the guesser has
+    /// run out of wrong guesses.
+    async fn advance_turn(&mut self) {
+        let Some(puzzle) = &self.puzzle else {
+            return;
+        };
+
+        if puzzle.is_solved() {
+            self.dispatch_game_over_event(GUESSER_ID).await;
+        } else if puzzle.misses_remaining() == 0 {
+            self.dispatch_game_over_event(SETTER_ID).await;
+        } else {
+            self.start_turn().await;
+        }
+    }
+
+    async fn handle_word_chosen_event(&mut self, player_id: u8, word: &str) {
+        if player_id != SETTER_ID {
+            self.dispatch_error_event(player_id, Error::UnexpectedPlayer)
+                .await;
+            return;
+        }
+        if self.puzzle.is_some() {
+            self.dispatch_error_event(player_id, Error::WordAlreadyChosen)
+                .await;
+            return;
+        }
+
+        let puzzle = match Puzzle::new(word) {
+            Ok(puzzle) => puzzle,
+            Err(error) => {
+                self.dispatch_error_event(player_id, error).await;
+                self.dispatch_word_requested_event().await;
+                return;
+            }
+        };
+
+        let length = puzzle.get_letters().len() as u8;
+        self.puzzle = Some(puzzle);
+        self.dispatch_secret_word_event().await;
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::WordChosen { player_id, length },
+        )
+        .await;
+        self.dispatch_puzzle_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.server_channel
+            .send(GameServerEvent::SetupComplete)
+            .await;
+        self.start_turn().await;
+    }
+
+    fn handle_letter_guessed_event(&mut self, player_id: u8, letter: char) -> Result<u8, Error> {
+        let Some(puzzle) = &mut self.puzzle else {
+            return Err(Error::WordNotChosen);
+        };
+        if player_id != GUESSER_ID {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        puzzle.guess(letter)
+    }
+}
+
+#[async_trait]
+impl GameServer for HangmanServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    fn is_setting_up(&self) -> bool {
+        self.puzzle.is_none()
+    }
+
+    async fn begin(&self) {
+        self.dispatch_word_requested_event().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        match event {
+            WordChosen { player_id, word } => self.handle_word_chosen_event(player_id, &word).await,
+            LetterGuessed { player_id, letter } => {
+                let found = match self.handle_letter_guessed_event(player_id, letter) {
+                    Ok(found) => found,
+                    Err(error) => {
+                        self.dispatch_error_event(player_id, error).await;
+
+                        // Only the guesser is asked to guess again, once the word is chosen
+                        if self.puzzle.is_none() || player_id != GUESSER_ID {
+                            return;
+                        }
+                        if self
+                            .rejected_moves
+                            .reject(&self.server_channel, GUESSER_ID, player_id)
+                            .await
+                        {
+                            self.dispatch_game_over_event(SETTER_ID).await;
+                            return;
+                        }
+
+                        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                            .await;
+
+                        return;
+                    }
+                };
+
+                self.dispatch_event(
+                    DispatchMode::AllPlayers,
+                    ServerEvent::LetterGuessed {
+                        player_id,
+                        letter: letter.to_ascii_lowercase(),
+                        found,
+                    },
+                )
+                .await;
+                self.dispatch_puzzle_updated_event(DispatchMode::AllPlayers)
+                    .await;
+                self.advance_turn().await;
+            }
+        }
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != GUESSER_ID {
+            return;
+        }
+        let Some(puzzle) = &mut self.puzzle else {
+            return;
+        };
+
+        // The guesser takes every turn, so a turn they lose counts as a wrong guess
+        if action == TimeoutAction::SkipTurn {
+            puzzle.forfeit_guess();
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => self.dispatch_game_over_event(SETTER_ID).await,
+            TimeoutAction::SkipTurn => {
+                self.dispatch_puzzle_updated_event(DispatchMode::AllPlayers)
+                    .await;
+                self.advance_turn().await;
+            }
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        if self.puzzle.is_none() {
+            if player_id == SETTER_ID {
+                self.dispatch_word_requested_event().await;
+            }
+            return;
+        }
+
+        if player_id == SETTER_ID {
+            self.dispatch_secret_word_event().await;
+        }
+        self.dispatch_puzzle_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        if self.puzzle.is_none() {
+            return;
+        }
+
+        self.dispatch_puzzle_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// The secret word as the setter typed it, which the server checks.
+    WordChosen {
+        player_id: u8,
+        word: String,
+    },
+    LetterGuessed {
+        player_id: u8,
+        letter: char,
+    },
+}
+
+pub struct HangmanClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    /// The letters of the word revealed so far, for a bot to choose its next guess.
+    letters: Vec<Option<char>>,
+    /// The letters guessed that the word does not contain, for a bot to choose its next guess.
+    misses: Vec<char>,
+}
+
+impl<F, C> HangmanClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> HangmanClient<F, C> {
+        HangmanClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            letters: Vec::new(),
+            misses: Vec::new(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn is_own_player(&self, player_id: u8) -> bool {
+        self.client_type.get_own_player_id() == Some(player_id)
+    }
+
+    /// Points out that the setter has chosen their word, to everyone but the setter.
+    async fn handle_word_chosen_event(
+        &self,
+        player_id: u8,
+        length: u8,
+    ) -> Result<(), GameClientError> {
+        if self.is_own_player(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output.write_message(
+            "hangman-word-chosen",
+            &[("player", &player_icon), ("length", &length)],
+        )?;
+
+        Ok(())
+    }
+
+    /// Reminds the setter of their word. Local games are played by both players at once, so the
+    /// word is kept from the guesser there.
+    async fn handle_secret_word_event(&self, word: String) -> Result<(), GameClientError> {
+        if !self.is_own_player(SETTER_ID) {
+            return Ok(());
+        }
+
+        self.user_output
+            .write_message("hangman-secret-word", &[("word", &word)])?;
+
+        Ok(())
+    }
+
+    /// Shows the letters of the word revealed so far with a gap for each of the others, followed
+    /// by the wrong guesses made.
+    async fn handle_puzzle_updated_event(
+        &mut self,
+        letters: Vec<Option<char>>,
+        misses: Vec<char>,
+        misses_remaining: u8,
+    ) -> Result<(), GameClientError> {
+        self.letters = letters;
+        self.misses = misses;
+
+        let word = |separator: &str| -> String {
+            self.letters
+                .iter()
+                .map(|letter| letter.unwrap_or('_').to_string())
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+        let misses = |separator: &str| -> String {
+            self.misses
+                .iter()
+                .map(char::to_string)
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+
+        let output_style = self.user_output.output_style();
+        let (word, misses) = match output_style {
+            OutputStyle::Standard => {
+                let misses = match self.misses.is_empty() {
+                    true => self.user_output.message("hangman-no-misses", &[]),
+                    false => misses(", "),
+                };
+                (word(" "), misses)
+            }
+            OutputStyle::Terse => (word(""), misses(",")),
+        };
+
+        let args: &[(&str, &dyn Display)] = &[
+            ("word", &word),
+            ("misses", &misses),
+            ("remaining", &misses_remaining),
+        ];
+        self.user_output.write_message("hangman-puzzle", args)?;
+        // Terse output fits the misses onto the same line as the word
+        if output_style == OutputStyle::Standard {
+            self.user_output
+                .write_message("hangman-puzzle-misses", args)?;
+        }
+
+        Ok(())
+    }
+
+    /// Points out the letters guessed by the other player, as a player already knows which they
+    /// guessed.
+    async fn handle_letter_guessed_event(
+        &self,
+        player_id: u8,
+        letter: char,
+        found: u8,
+    ) -> Result<(), GameClientError> {
+        if self.is_own_player(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        match found {
+            0 => self.user_output.write_message(
+                "hangman-letter-missed",
+                &[("player", &player_icon), ("letter", &letter)],
+            )?,
+            _ => self.user_output.write_message(
+                "hangman-letter-found",
+                &[
+                    ("player", &player_icon),
+                    ("letter", &letter),
+                    ("found", &found),
+                ],
+            )?,
+        };
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(
+        &mut self,
+        winner: u8,
+        word: String,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(winner);
+        let key = match winner {
+            GUESSER_ID => "hangman-game-won-guessed",
+            _ => "hangman-game-won-hanged",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon), ("word", &word)])?;
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::InvalidWord => self.user_output.write_error(
+                "hangman-error-invalid-word",
+                &[("min", &MIN_WORD_LENGTH), ("max", &MAX_WORD_LENGTH)],
+            ),
+            Error::WordAlreadyChosen => self
+                .user_output
+                .write_error("hangman-error-word-already-chosen", &[]),
+            Error::WordNotChosen => self
+                .user_output
+                .write_error("hangman-error-word-not-chosen", &[]),
+            Error::InvalidLetter { letter } => self
+                .user_output
+                .write_error("hangman-error-invalid-letter", &[("letter", &letter)]),
+            Error::AlreadyGuessed { letter } => self
+                .user_output
+                .write_error("hangman-error-already-guessed", &[("letter", &letter)]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("hangman-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "hangman-turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn choose_player_word(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let word = self.get_word().await?;
+        self.send_event(WordChosen { player_id, word }).await
+    }
+
+    async fn guess_player_letter(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let letter = self.get_letter().await?;
+        self.send_event(LetterGuessed { player_id, letter }).await
+    }
+
+    async fn send_event(&mut self, event: ClientEvent) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer { event })
+            .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the secret word as typed, leaving the server to decide whether it can be used.
+    async fn get_word(&mut self) -> Result<String, io::Error> {
+        self.user_output.write_message(
+            "hangman-word-prompt",
+            &[("min", &MIN_WORD_LENGTH), ("max", &MAX_WORD_LENGTH)],
+        )?;
+
+        let input_text = self.frontend.read_line().await?;
+        Ok(input_text.trim().to_string())
+    }
+
+    /// Reads the letter to guess, leaving the server to decide whether it has been guessed before.
+    async fn get_letter(&mut self) -> Result<char, io::Error> {
+        loop {
+            self.user_output
+                .write_message("hangman-letter-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            let mut chars = input_text.trim().chars();
+            match (chars.next(), chars.next()) {
+                (Some(letter), None) if letter.is_ascii_alphabetic() => return Ok(letter),
+                _ => self
+                    .user_output
+                    .write_message("hangman-not-a-letter", &[])?,
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_word_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for HangmanClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "hangman-game-started-local",
+            &[
+                ("setter", &self.get_player_icon_by_id(SETTER_ID)),
+                ("guesser", &self.get_player_icon_by_id(GUESSER_ID)),
+            ],
+        )
+    }
+
+    async fn handle_word_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output.write_message(
+            "hangman-word-requested",
+            &[
+                ("player", &player_icon),
+                (
+                    "other",
+                    &self.get_player_icon_by_id(get_alternative_player_id(player_id)),
+                ),
+            ],
+        )?;
+
+        self.choose_player_word(player_id).await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.guess_player_letter(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for HangmanClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let key = match self.client_type.id {
+            SETTER_ID => "hangman-game-started-online-setter",
+            _ => "hangman-game-started-online-guesser",
+        };
+        self.user_output.message(
+            key,
+            &[("player", &self.get_player_icon_by_id(self.client_type.id))],
+        )
+    }
+
+    async fn handle_word_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.choose_player_word(player_id).await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("hangman-waiting-for-guess", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.guess_player_letter(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for HangmanClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_word_requested_event(&mut self, _player_id: u8) -> Result<(), GameClientError> {
+        Ok(())
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for HangmanClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    /// Bots choose their word at random.
+    async fn handle_word_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.send_event(WordChosen {
+            player_id,
+            word: String::from(bot::choose_word()),
+        })
+        .await
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(letter) = bot::choose_letter(&self.letters, &self.misses) {
+            self.send_event(LetterGuessed { player_id, letter }).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for HangmanClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::WordRequested { player_id } => {
+                self.handle_word_requested_event(player_id).await
+            }
+            ServerEvent::WordChosen { player_id, length } => {
+                self.handle_word_chosen_event(player_id, length).await
+            }
+            ServerEvent::SecretWord { word } => self.handle_secret_word_event(word).await,
+            ServerEvent::PuzzleUpdated {
+                letters,
+                misses,
+                misses_remaining,
+            } => {
+                self.handle_puzzle_updated_event(letters, misses, misses_remaining)
+                    .await
+            }
+            ServerEvent::LetterGuessed {
+                player_id,
+                letter,
+                found,
+            } => {
+                self.handle_letter_guessed_event(player_id, letter, found)
+                    .await
+            }
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { winner, word } => {
+                self.handle_game_over_event(winner, word).await
+            }
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        HangmanClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = HangmanClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    async fn send_client_event(server: &mut HangmanServer, event: ClientEvent) {
+        server.handle_event(event).await;
+    }
+
+    async fn choose_word(server: &mut HangmanServer, word: &str) {
+        send_client_event(
+            server,
+            WordChosen {
+                player_id: SETTER_ID,
+                word: String::from(word),
+            },
+        )
+        .await;
+    }
+
+    async fn guess(server: &mut HangmanServer, letter: char) {
+        send_client_event(
+            server,
+            LetterGuessed {
+                player_id: GUESSER_ID,
+                letter,
+            },
+        )
+        .await;
+    }
+
+    /// Collects the events dispatched by the server, along with who each was sent to.
+    fn drain_dispatched_events(
+        receiver: &mut Receiver<GameServerEvent>,
+    ) -> Vec<(DispatchMode, ServerEvent)> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            } = event
+            {
+                events.push((dispatch_mode, game::deserialize_event(event)));
+            }
+        }
+
+        events
+    }
+
+    #[tokio::test]
+    async fn server_asks_setter_for_word_before_guesses_start() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+
+        server.begin().await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [(
+                DispatchMode::SinglePlayer {
+                    player_id: SETTER_ID
+                },
+                ServerEvent::WordRequested {
+                    player_id: SETTER_ID
+                }
+            )]
+        ));
+
+        guess(&mut server, 'e').await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [(
+                DispatchMode::SinglePlayer {
+                    player_id: GUESSER_ID
+                },
+                ServerEvent::ErrorOccurred {
+                    error: Error::WordNotChosen
+                }
+            )]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_sends_word_only_to_setter() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+
+        choose_word(&mut server, "Apple").await;
+
+        let events = drain_dispatched_events(&mut receiver);
+        for (dispatch_mode, event) in &events {
+            match event {
+                ServerEvent::SecretWord { word } => {
+                    assert_eq!(word, "apple");
+                    assert!(matches!(
+                        dispatch_mode,
+                        DispatchMode::SinglePlayer {
+                            player_id: SETTER_ID
+                        }
+                    ));
+                }
+                ServerEvent::PuzzleUpdated { letters, .. } => {
+                    assert_eq!(letters, &vec![None; 5]);
+                }
+                _ => {}
+            }
+        }
+        assert!(matches!(
+            events.last(),
+            Some((
+                DispatchMode::AllPlayers,
+                ServerEvent::PlayerTurn {
+                    player_id: GUESSER_ID
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_asks_again_for_invalid_word() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+
+        choose_word(&mut server, "no").await;
+
+        assert!(server.puzzle.is_none());
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                (
+                    _,
+                    ServerEvent::ErrorOccurred {
+                        error: Error::InvalidWord
+                    }
+                ),
+                (_, ServerEvent::WordRequested { .. }),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_reveals_guessed_letters() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+        choose_word(&mut server, "apple").await;
+        drain_dispatched_events(&mut receiver);
+
+        guess(&mut server, 'p').await;
+
+        let events = drain_dispatched_events(&mut receiver);
+        assert!(matches!(
+            events[0],
+            (
+                DispatchMode::AllPlayers,
+                ServerEvent::LetterGuessed {
+                    player_id: GUESSER_ID,
+                    letter: 'p',
+                    found: 2
+                }
+            )
+        ));
+        let (_, ServerEvent::PuzzleUpdated { letters, .. }) = &events[1] else {
+            panic!("Expected the puzzle to be updated");
+        };
+        assert_eq!(letters, &vec![None, Some('p'), Some('p'), None, None]);
+    }
+
+    #[tokio::test]
+    async fn server_refuses_letter_guessed_twice() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+        choose_word(&mut server, "apple").await;
+        guess(&mut server, 'p').await;
+        drain_dispatched_events(&mut receiver);
+
+        guess(&mut server, 'P').await;
+
+        assert_eq!(server.rejected_moves.count(), 1);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                (
+                    _,
+                    ServerEvent::ErrorOccurred {
+                        error: Error::AlreadyGuessed { letter: 'p' }
+                    }
+                ),
+                (
+                    DispatchMode::SinglePlayer {
+                        player_id: GUESSER_ID
+                    },
+                    ServerEvent::PlayerTurn { .. }
+                ),
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_word_guessed() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+        choose_word(&mut server, "bob").await;
+
+        guess(&mut server, 'b').await;
+        guess(&mut server, 'o').await;
+
+        assert!(server.game_over);
+        let events = drain_dispatched_events(&mut receiver);
+        let Some((_, ServerEvent::GameOver { winner, word })) = events.last() else {
+            panic!("Expected the game to be over");
+        };
+        assert_eq!(*winner, GUESSER_ID);
+        assert_eq!(word, "bob");
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_guesser_out_of_misses() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(40);
+        let mut server = HangmanServer::new(sender);
+        choose_word(&mut server, "bob").await;
+
+        for letter in "acdefg".chars() {
+            guess(&mut server, letter).await;
+        }
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver).last(),
+            Some((
+                _,
+                ServerEvent::GameOver {
+                    winner: SETTER_ID,
+                    ..
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_counts_skipped_turn_as_miss() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = HangmanServer::new(sender);
+        choose_word(&mut server, "bob").await;
+
+        server
+            .handle_turn_timed_out(GUESSER_ID, TimeoutAction::SkipTurn)
+            .await;
+
+        assert_eq!(
+            server.puzzle.as_ref().unwrap().misses_remaining(),
+            MAX_MISSES - 1
+        );
+        assert!(!server.game_over);
+    }
+
+    #[tokio::test]
+    async fn client_shows_puzzle_with_misses() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_puzzle_updated_event(vec![None, Some('p'), Some('p'), None, None], vec![], 6)
+            .await
+            .unwrap();
+        client.set_output_style(OutputStyle::Terse);
+        client
+            .handle_puzzle_updated_event(vec![Some('a'), None], vec!['z', 'q'], 4)
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "Word: _ p p _ _\nMisses: none (6 wrong guess(es) left)\na_ z,q 4\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_shows_secret_word_only_to_online_setter() {
+        let (setter, setter_output, _) =
+            get_test_client_and_output(&[], OnlineClient { id: SETTER_ID }).await;
+        let (local, local_output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        setter
+            .handle_secret_word_event(String::from("apple"))
+            .await
+            .unwrap();
+        local
+            .handle_secret_word_event(String::from("apple"))
+            .await
+            .unwrap();
+        assert_client_output(setter_output, "Your secret word is \"apple\".\n");
+        assert_client_output(local_output, "");
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) =
+            get_test_client_and_output(&[], OnlineClient { id: SETTER_ID }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_puzzle_updated_event(vec![Some('a'), None], vec![], 6)
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::AlreadyGuessed { letter: 'a' })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "Palabra: a _\nFallos: ninguno (quedan 6 fallo(s))\nError: a ya se ha probado.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_letter_handles_errors_until_letter_provided() {
+        let input = "ab\n3\nE\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert_eq!(client.get_letter().await.unwrap(), 'E');
+        assert_client_output(
+            output,
+            "Letter:\nNot a letter.\nLetter:\nNot a letter.\nLetter:\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_sends_word_as_typed_for_server_to_check() {
+        let input = " two words \n".as_bytes();
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(input, OnlineClient { id: SETTER_ID }).await;
+
+        client.handle_word_requested_event(SETTER_ID).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(WordChosen {
+                    player_id: SETTER_ID,
+                    word: String::from("two words")
+                })
+            })
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn bot_client_chooses_word_and_guesses() {
+        let bot_client = BotClient {
+            id: GUESSER_ID,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client
+            .handle_puzzle_updated_event(vec![Some('e'), None], vec!['t'], 5)
+            .await
+            .unwrap();
+        client.handle_player_turn_event(GUESSER_ID).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(LetterGuessed {
+                    player_id: GUESSER_ID,
+                    letter: 'a'
+                })
+            })
+        );
+    }
+}
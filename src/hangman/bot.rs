@@ -0,0 +1,57 @@
+use rand::seq::SliceRandom;
+
+/// The words a bot chooses between when setting the puzzle.
+const WORDS: [&str; 12] = [
+    "anchor", "biscuit", "compass", "dolphin", "emerald", "falcon", "glacier", "harbour", "island",
+    "jigsaw", "lantern", "meadow",
+];
+
+/// Letters in the order they are most often found in English words.
+const LETTERS_BY_FREQUENCY: &str = "etaoinshrdlcumwfgypbvkjxqz";
+
+/// Chooses a secret word at random for the other player to guess.
+pub(crate) fn choose_word() -> &'static str {
+    WORDS.choose(&mut rand::thread_rng()).unwrap()
+}
+
+/// Chooses the most common letter yet to be guessed, from the letters of the word revealed so far
+/// and those guessed that it does not contain.
+///
+/// # Returns
+///
+/// - `Some(char)` containing the letter to guess.
+/// - `None` if every letter has been guessed.
+pub(crate) fn choose_letter(letters: &[Option<char>], misses: &[char]) -> Option<char> {
+    LETTERS_BY_FREQUENCY
+        .chars()
+        .find(|letter| !letters.contains(&Some(*letter)) && !misses.contains(letter))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hangman::puzzle::{MAX_WORD_LENGTH, MIN_WORD_LENGTH};
+
+    use super::*;
+
+    #[test]
+    fn choose_word_chooses_word_that_can_be_set() {
+        for word in WORDS {
+            assert!((MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word.len()));
+            assert!(word.chars().all(|letter| letter.is_ascii_lowercase()));
+        }
+        assert!(WORDS.contains(&choose_word()));
+    }
+
+    #[test]
+    fn choose_letter_skips_letters_already_guessed() {
+        assert_eq!(choose_letter(&[None, None], &[]), Some('e'));
+        assert_eq!(choose_letter(&[Some('e'), None], &['t', 'a']), Some('o'));
+        assert_eq!(
+            choose_letter(
+                &[None],
+                &LETTERS_BY_FREQUENCY.chars().collect::<Vec<char>>()
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use crate::hangman::Error;
+
+/// The fewest letters a secret word may have.
+pub const MIN_WORD_LENGTH: usize = 3;
+/// The most letters a secret word may have.
+pub const MAX_WORD_LENGTH: usize = 16;
+/// The number of wrong guesses the guesser may make before losing.
+pub const MAX_MISSES: u8 = 6;
+
+/// The secret word along with the letters guessed at it so far.
+pub struct Puzzle {
+    word: Vec<char>,
+    guesses: Vec<char>,
+    /// Guesses that were never made, such as when the guesser ran out of time, which count as
+    /// misses.
+    forfeited: u8,
+}
+
+impl Puzzle {
+    /// Takes the secret word chosen by the setter, ignoring the case of its letters.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Puzzle)` for the word.
+    /// - `Err(Error)` if the word is not made of the letters a to z, or is too short or long.
+    pub(crate) fn new(word: &str) -> Result<Puzzle, Error> {
+        let word: Vec<char> = word.trim().to_ascii_lowercase().chars().collect();
+        if !(MIN_WORD_LENGTH..=MAX_WORD_LENGTH).contains(&word.len())
+            || !word.iter().all(char::is_ascii_lowercase)
+        {
+            return Err(Error::InvalidWord);
+        }
+
+        Ok(Puzzle {
+            word,
+            guesses: Vec::new(),
+            forfeited: 0,
+        })
+    }
+
+    pub(crate) fn get_word(&self) -> String {
+        self.word.iter().collect()
+    }
+
+    /// Guesses a letter, ignoring its case.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(u8)` containing the number of times the letter appears in the word, zero for a miss.
+    /// - `Err(Error)` if it is not a letter, or has already been guessed.
+    pub(crate) fn guess(&mut self, letter: char) -> Result<u8, Error> {
+        if !letter.is_ascii_alphabetic() {
+            return Err(Error::InvalidLetter { letter });
+        }
+
+        let letter = letter.to_ascii_lowercase();
+        if self.guesses.contains(&letter) {
+            return Err(Error::AlreadyGuessed { letter });
+        }
+        self.guesses.push(letter);
+
+        Ok(self.word.iter().filter(|&&c| c == letter).count() as u8)
+    }
+
+    /// The letters of the word that have been guessed, with `None` in place of the others.
+    pub(crate) fn get_letters(&self) -> Vec<Option<char>> {
+        self.word
+            .iter()
+            .map(|letter| self.guesses.contains(letter).then_some(*letter))
+            .collect()
+    }
+
+    /// The letters guessed that are not in the word, in the order they were guessed.
+    pub(crate) fn get_misses(&self) -> Vec<char> {
+        self.guesses
+            .iter()
+            .filter(|letter| !self.word.contains(letter))
+            .copied()
+            .collect()
+    }
+
+    /// Counts a guess that was not made, such as when the guesser runs out of time, as a miss.
+    pub(crate) fn forfeit_guess(&mut self) {
+        self.forfeited += 1;
+    }
+
+    /// The number of wrong guesses the guesser may still make before losing.
+    pub(crate) fn misses_remaining(&self) -> u8 {
+        let misses = self.get_misses().len() as u8 + self.forfeited;
+
+        MAX_MISSES.saturating_sub(misses)
+    }
+
+    pub(crate) fn is_solved(&self) -> bool {
+        self.word.iter().all(|letter| self.guesses.contains(letter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_must_be_letters_of_allowed_length() {
+        assert_eq!(Puzzle::new(" Apple\n").unwrap().get_word(), "apple");
+        for word in ["ab", "abcdefghijklmnopq", "two words", "café", "r2d2"] {
+            assert!(
+                matches!(Puzzle::new(word), Err(Error::InvalidWord)),
+                "{}",
+                word
+            );
+        }
+    }
+
+    #[test]
+    fn guesses_reveal_letters_and_count_misses() {
+        let mut puzzle = Puzzle::new("apple").unwrap();
+
+        assert_eq!(puzzle.guess('P').unwrap(), 2);
+        assert_eq!(puzzle.guess('z').unwrap(), 0);
+        assert_eq!(
+            puzzle.get_letters(),
+            vec![None, Some('p'), Some('p'), None, None]
+        );
+        assert_eq!(puzzle.get_misses(), vec!['z']);
+        assert_eq!(puzzle.misses_remaining(), MAX_MISSES - 1);
+    }
+
+    #[test]
+    fn repeated_or_invalid_guess_refused() {
+        let mut puzzle = Puzzle::new("apple").unwrap();
+        puzzle.guess('a').unwrap();
+
+        assert!(matches!(
+            puzzle.guess('A'),
+            Err(Error::AlreadyGuessed { letter: 'a' })
+        ));
+        assert!(matches!(
+            puzzle.guess('3'),
+            Err(Error::InvalidLetter { letter: '3' })
+        ));
+    }
+
+    #[test]
+    fn puzzle_solved_once_every_letter_guessed() {
+        let mut puzzle = Puzzle::new("bob").unwrap();
+
+        puzzle.guess('b').unwrap();
+        assert!(!puzzle.is_solved());
+        puzzle.guess('o').unwrap();
+        assert!(puzzle.is_solved());
+    }
+
+    #[test]
+    fn forfeited_guess_counts_as_miss() {
+        let mut puzzle = Puzzle::new("bob").unwrap();
+
+        puzzle.forfeit_guess();
+        assert_eq!(puzzle.misses_remaining(), MAX_MISSES - 1);
+        assert!(puzzle.get_misses().is_empty());
+    }
+}
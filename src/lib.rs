@@ -0,0 +1,44 @@
+//! A server and clients for playing turn based games, such as Tic Tac Toe, [`checkers`],
+//! [`battleship`], [`othello`], [`rock_paper_scissors`], [`mancala`], [`nim`], [`dots_and_boxes`],
+//! [`hangman`], [`trivia`] and [`blackjack`], over a network.
+//!
+//! The [`lobby`] accepts player connections and hands them to a [`server::Server`], which relays
+//! events between each [`client::Client`] and the [`game`] being played. Events are sent over a
+//! [`connection::Connection`], optionally encrypted using [`tls`].
+//! Completed games can be recorded with [`stats`] to keep track of how players have fared, or to
+//! export their results to league software, and watched back using [`replay`].
+//! A dedicated server can be controlled while it runs through the [`admin`] socket, and tested
+//! under load with bots playing each other on it using [`stress`].
+//! With the `tui` feature, games can be played in a full-screen terminal interface from [`tui`],
+//! and with the `gui` feature in a desktop window from [`gui`]. With the `web` feature, a host can
+//! also let people join from their browser through [`web`].
+//! Messages shown to players are looked up by key in the catalog of the chosen [`locale`].
+
+pub mod admin;
+pub mod battleship;
+pub mod blackjack;
+pub mod checkers;
+pub mod client;
+pub mod connection;
+pub mod dots_and_boxes;
+pub mod game;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod hangman;
+pub mod lobby;
+pub mod locale;
+pub mod mancala;
+pub mod nim;
+pub mod othello;
+pub mod replay;
+pub mod rock_paper_scissors;
+pub mod server;
+pub mod stats;
+pub mod stress;
+pub mod tic_tac_toe;
+pub mod tls;
+pub mod trivia;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "web")]
+pub mod web;
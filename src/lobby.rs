@@ -1,84 +1,1344 @@
+use std::collections::HashMap;
 use std::fmt;
+use std::future::{self, Future};
+use std::io;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::task::Poll;
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
-use thiserror::__private::AsDisplay;
 use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::task::JoinSet;
+use tokio::time;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tokio_tungstenite::tungstenite;
+use tracing::Instrument;
 
-use crate::connection::{self, Connection};
-use crate::game::Game;
-use crate::server::{OnlineConnection, Player, Server};
+use crate::admin::ActiveGames;
+use crate::connection::{
+    self, CodecKind, Compression, Connection, ReadError, Stream, SUPPORTED_CODECS,
+    SUPPORTED_COMPRESSION,
+};
+use crate::game::{GameDescriptor, Seats};
+use crate::server::{
+    self, EventSender, LocalConnection, OnlineConnection, OutgoingEvent, Player, PlayerMark,
+    PlayerName, RestoredGame, SendError, Server, ServerBuilder, ServerEvent, ShutdownReason,
+    MAX_PLAYERS, PLAYER_ONE_ID,
+};
+use crate::tic_tac_toe::TicTacToe;
 
-const GAME_ID: u16 = 12345;
+pub use rate_limit::RateLimit;
+pub use retry::{ConnectError, RetryPolicy};
+pub use tournament::{BracketMatch, BracketSlot};
+pub use waiting::WaitingHost;
+
+pub(crate) use rate_limit::RateLimiter;
+use waiting::{WaitOutcome, WaitTimer};
+
+mod rate_limit;
+mod retry;
+mod tournament;
+mod waiting;
+
+/// Incremented whenever a change is made to the events sent between the server and clients.
+pub const PROTOCOL_VERSION: u16 = 28;
+pub const RECONNECTION_GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// How long to wait before accepting connections again after failing to, such as when the process
+/// has run out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+/// How long a new connection is given to complete its handshakes and send its request before it
+/// is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The secret a lobby requires every connection to present, which the host shares with the people
+/// they want to let in. Each lobby generates its own, so only those it has been shared with can join.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct JoinToken(u64);
+
+impl JoinToken {
+    /// Generates a token using a cryptographically secure random number generator, so that it
+    /// cannot be guessed.
+    pub fn generate() -> JoinToken {
+        JoinToken(rand::random())
+    }
+}
+
+impl fmt::Display for JoinToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for JoinToken {
+    type Err = InvalidJoinToken;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u64::from_str_radix(s.trim(), 16)
+            .map(JoinToken)
+            .map_err(|_| InvalidJoinToken)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("A join token is made up of up to 16 hexadecimal digits.")]
+pub struct InvalidJoinToken;
+
+/// The code of a game on a dedicated server waiting for more players, which players name to join
+/// that game in particular.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RoomCode(u16);
+
+impl fmt::Display for RoomCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}", self.0)
+    }
+}
+
+impl FromStr for RoomCode {
+    type Err = InvalidRoomCode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        u16::from_str_radix(s.trim(), 16)
+            .map(RoomCode)
+            .map_err(|_| InvalidRoomCode)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("A room code is made up of up to 4 hexadecimal digits.")]
+pub struct InvalidRoomCode;
+
+/// Which game a player joining a dedicated server is seated in.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum RoomChoice {
+    /// The game that has been waiting longest for players, or a new one if none are.
+    #[default]
+    Any,
+    /// The game waiting for players in the room with the given code.
+    Join(RoomCode),
+    /// A new game of the player's own, for others to join.
+    New,
+    /// The game in the private room with the given code, opening it if nobody is waiting there.
+    /// Players agree on the code between themselves, so that they can meet on the server.
+    Private(RoomCode),
+}
+
+/// A game on a dedicated server waiting for more players, as listed to players browsing for one
+/// to join.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenGame {
+    pub room: RoomCode,
+    /// The name of the player who opened the game, if they gave one.
+    pub host: Option<PlayerName>,
+    /// The ID of the game being played, as in `game::GAMES`.
+    pub game: String,
+    pub players_needed: u8,
+}
+
+/// The players seated in a game on a dedicated server that is waiting for more.
+struct PendingGame {
+    room: RoomCode,
+    /// Whether the room was opened with a code chosen by its players, keeping it to themselves.
+    private: bool,
+    players: Vec<Player>,
+}
 
 pub struct Lobby {
     listener: TcpListener,
+    join_token: JoinToken,
+    tls_acceptor: Option<TlsAcceptor>,
+    websocket: bool,
+    game: Box<dyn GameDescriptor>,
+    /// The games players joining may choose between, when they are given a choice.
+    offered_games: Vec<Box<dyn GameDescriptor>>,
+    /// The index of the offered game chosen by the player last seated, if any has been.
+    chosen_game: Option<usize>,
+    active_games: ActiveGames,
+    rate_limiter: RateLimiter,
+    wait_timer: Option<WaitTimer>,
+    server_builder: ServerBuilder,
+    /// The connections accepted that are still completing their handshakes, each in a task of its
+    /// own so that a peer slow to complete them holds up no one else.
+    handshakes: JoinSet<Option<(Connection, ConnectionRequest)>>,
 }
 
 impl Lobby {
     pub fn new(listener: TcpListener) -> Self {
-        Lobby { listener }
+        Lobby {
+            listener,
+            join_token: JoinToken::generate(),
+            tls_acceptor: None,
+            websocket: false,
+            game: Box::new(TicTacToe::default()),
+            offered_games: Vec::new(),
+            chosen_game: None,
+            active_games: ActiveGames::default(),
+            rate_limiter: RateLimiter::new(RateLimit::default()),
+            wait_timer: None,
+            server_builder: ServerBuilder::default(),
+            handshakes: JoinSet::new(),
+        }
+    }
+
+    /// The token that people must present to join the lobby, for the host to share with them.
+    pub fn join_token(&self) -> JoinToken {
+        self.join_token
+    }
+
+    /// Requires connections to present the given token rather than the one generated, such as so
+    /// that players can rejoin a resumed game with the token they were first given.
+    pub fn set_join_token(&mut self, join_token: JoinToken) {
+        self.join_token = join_token;
+    }
+
+    /// Requires every connection to the lobby to be encrypted using the provided acceptor.
+    pub fn set_tls_acceptor(&mut self, tls_acceptor: TlsAcceptor) {
+        self.tls_acceptor = Some(tls_acceptor);
+    }
+
+    /// Requires every connection to the lobby to be made over WebSocket rather than plain TCP,
+    /// allowing browsers to connect.
+    pub fn set_websocket(&mut self, websocket: bool) {
+        self.websocket = websocket;
+    }
+
+    /// Refuses connections from addresses opening more than the provided `RateLimit` allows,
+    /// protecting the lobby from being flooded.
+    pub fn set_rate_limit(&mut self, rate_limit: RateLimit) {
+        self.rate_limiter = RateLimiter::new(rate_limit);
+    }
+
+    /// Tells the host through the returned handle whenever the given time passes without anyone
+    /// joining while the lobby waits on players, letting them decide whether to keep waiting.
+    pub fn set_wait_timeout(&mut self, wait_timeout: Duration) -> WaitingHost {
+        let (timer, host) = WaitTimer::new(wait_timeout);
+        self.wait_timer = Some(timer);
+        host
     }
 
-    pub async fn set_up_online_server(&mut self) -> Server<OnlineConnection> {
-        let connection_one = self.get_connection().await;
-        let connection_two = self.get_connection().await;
+    /// Sets up the servers for games started by the lobby using the given `ServerBuilder`, such
+    /// as to give games more room for events when many are played at once. The lobby's own game
+    /// is played, rather than any given to the builder.
+    pub fn set_server_builder(&mut self, server_builder: ServerBuilder) {
+        self.server_builder = server_builder;
+    }
 
-        let player_one = Player::new_player_one(connection_one);
-        let player_two = Player::new_player_two(connection_two);
+    /// Sets the game that the server will host once all of its players have connected.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the game is played by more than `MAX_PLAYERS` players.
+    pub fn set_game(&mut self, game: impl GameDescriptor + 'static) {
+        assert!(
+            game.player_count() <= MAX_PLAYERS,
+            "A game can have at most {} players",
+            MAX_PLAYERS
+        );
+        self.game = Box::new(game);
+    }
 
-        Server::<OnlineConnection>::new(player_one, player_two, Game::TicTacToe)
+    /// Lets players joining choose which of the given games is played, with the game chosen by
+    /// the last of them to join being played. Players who name none of the games are sent them
+    /// to choose from. Games played by a different number of players than the game set are left
+    /// out, so should be offered after setting it.
+    pub fn offer_games(&mut self, games: Vec<Box<dyn GameDescriptor>>) {
+        let player_count = self.game.player_count();
+        self.offered_games = games
+            .into_iter()
+            .filter(|game| game.player_count() == player_count)
+            .collect();
     }
 
-    async fn get_connection(&mut self) -> Connection {
+    /// The games being played once the lobby is serving them, for the server operator to control.
+    pub fn active_games(&self) -> ActiveGames {
+        self.active_games.clone()
+    }
+
+    /// Waits for as many players as the game is played by to connect, then sets up a server for
+    /// them. Players take their turns in the order they connect, and may not choose a mark chosen
+    /// by a player who connected before them.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Server)` ready to play the game.
+    /// - `None` if the host stopped the lobby waiting before every player connected.
+    pub async fn set_up_online_server(mut self) -> Option<Server<OnlineConnection>> {
+        let player_ids: Vec<u8> =
+            (PLAYER_ONE_ID..PLAYER_ONE_ID + self.game.player_count()).collect();
+        let players = self.seat_players(&player_ids).await?;
+        if let Some(index) = self.chosen_game.take() {
+            self.game = self.offered_games.swap_remove(index);
+        }
+
+        let mut server = self.server_builder.online(players, self.game.as_ref());
+        server.keep_lobby_open(self, RECONNECTION_GRACE_PERIOD);
+        Some(server)
+    }
+
+    /// Waits for the players of a suspended game to reconnect, then sets up a server carrying it
+    /// on. Players are seated in the order they connect, so the host keeps their seat by
+    /// connecting first as they did when the game began.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Server)` ready to carry on the game.
+    /// - `None` if the host stopped the lobby waiting before every player reconnected.
+    pub async fn set_up_resumed_server(
+        mut self,
+        game: RestoredGame,
+    ) -> Option<Server<OnlineConnection>> {
+        // The game being resumed was chosen when it began
+        self.offered_games.clear();
+        let players = self.seat_players(&game.player_ids).await?;
+
+        let mut server = Server::<OnlineConnection>::resume(players, game);
+        server.keep_lobby_open(self, RECONNECTION_GRACE_PERIOD);
+        Some(server)
+    }
+
+    /// Sets up a server for a single connection that plays both sides of the game, taking turns
+    /// as each player in the same way as a local game.
+    pub async fn set_up_hot_seat_server(mut self) -> Server<LocalConnection> {
+        let seats = Seats::every(self.game.player_count());
+        let (connection, _, _) = self.get_connection(seats, &[]).await;
+
+        self.server_builder.local(connection, self.game.as_ref())
+    }
+
+    /// Accepts connections from spectators and from players rejoining a game in progress,
+    /// forwarding them to the server. Players must present the session token of one of the
+    /// players in the game.
+    ///
+    /// # Parameters
+    ///
+    /// - `sessions`: The ID and session token of each player in the game.
+    /// - `server_channel`: The channel used to hand connections over to the server.
+    pub(crate) async fn accept_late_connections(
+        mut self,
+        sessions: Vec<(u8, u64)>,
+        server_channel: EventSender,
+    ) {
         loop {
-            let (stream, _) = self.listener.accept().await.unwrap();
-            let mut connection = Connection::new(stream);
+            let (mut connection, request) = self.get_connection_request().await;
+            if request.list_games {
+                reject_connection(&mut connection, RejectionReason::ListingUnavailable).await;
+                continue;
+            }
+            if request.spectator {
+                if send_connection_response(
+                    &mut connection,
+                    ConnectionResponse::Spectating {
+                        codec: CodecKind::negotiate(&request.codecs),
+                        compression: Compression::negotiate(&request.compression),
+                    },
+                )
+                .await
+                .is_ok()
+                    && server_channel
+                        .send(ServerEvent::SpectatorJoined { connection })
+                        .await
+                        == Err(SendError::Closed)
+                {
+                    break;
+                }
+
+                continue;
+            }
 
-            if let Ok(ConnectionRequest { game_id }) = connection.read_event().await {
-                if game_id == GAME_ID {
-                    break connection;
+            let session = sessions
+                .iter()
+                .find(|(_, session_token)| request.session_token == Some(*session_token));
+
+            match session {
+                Some(&(player_id, session_token)) => {
+                    let player = (player_id, session_token);
+                    if !hand_back_player(connection, &request, player, &server_channel).await {
+                        break;
+                    }
+                }
+                None => {
+                    reject_connection(&mut connection, RejectionReason::UnknownSession).await;
+                }
+            }
+        }
+    }
+
+    /// Hosts games for as many groups of players as connect, never returning. Players are seated
+    /// in the game that has been waiting longest for players unless they name a room to join or
+    /// ask to open one of their own, with the first in each game playing first. Players can ask
+    /// for the games waiting for players before choosing one to join. Players who cannot host a
+    /// game themselves, such as those behind NAT, can meet in a private room whose code they have
+    /// agreed on, which is neither listed nor filled by anyone else.
+    ///
+    /// Each game is set up by `configure` then played in a task of its own, with the start and
+    /// end of each being logged. A game that panics is ended on its own, leaving the rest to be
+    /// played. Games are numbered in the order they start, and can be controlled through
+    /// `active_games` while they are played. Players who lose their connection are handed back to
+    /// the game they were playing within the reconnection grace period. Spectators are turned
+    /// away, as there is no way for them to say which game they want to watch.
+    pub async fn serve<F>(mut self, configure: F)
+    where
+        F: Fn(&mut Server<OnlineConnection>),
+    {
+        let mut pending_games: Vec<PendingGame> = Vec::new();
+        // The seat and game of each player in a game that is still being played
+        let mut sessions: HashMap<u64, (u8, EventSender)> = HashMap::new();
+        let mut games_started: u64 = 0;
+
+        loop {
+            let (mut connection, request) = tokio::select! {
+                accepted = self.get_connection_request() => accepted,
+                (index, player_id, error) = pending_player_left(&mut pending_games) => {
+                    tracing::info!(
+                        player_id,
+                        ?error,
+                        "Player left before the game began, reopening their seat"
+                    );
+                    let pending = &mut pending_games[index];
+                    let position = pending.players.iter().position(|player| player.id == player_id);
+                    if let Some(position) = position {
+                        let mut player = pending.players.remove(position);
+                        let _ = player.connection.shutdown().await;
+                    }
+                    // A game no one is waiting in any more is no longer listed
+                    if pending.players.is_empty() {
+                        pending_games.remove(index);
+                    }
+                    continue;
+                }
+            };
+            sessions.retain(|_, (_, server_channel)| !server_channel.is_closed());
+
+            if request.list_games {
+                let games = pending_games
+                    .iter()
+                    .filter(|pending| !pending.private)
+                    .map(|pending| OpenGame {
+                        room: pending.room,
+                        host: pending.players[0].name.clone(),
+                        game: String::from(self.game.id()),
+                        players_needed: self.game.player_count() - pending.players.len() as u8,
+                    })
+                    .collect();
+                // Answered in the background, so that a slow connection holds up nobody joining
+                tokio::spawn(send_game_list(connection, games));
+                continue;
+            }
+            if request.spectator {
+                reject_connection(&mut connection, RejectionReason::SpectatingUnavailable).await;
+                continue;
+            }
+            if let Some(session_token) = request.session_token {
+                if let Some((player_id, server_channel)) = sessions.get(&session_token) {
+                    let player = (*player_id, session_token);
+                    hand_back_player(connection, &request, player, server_channel).await;
+                    continue;
+                }
+            }
+
+            // Every pending game has a seat free, as each starts as soon as its last is taken
+            let index = match request.room {
+                RoomChoice::Any => pending_games.iter().position(|pending| !pending.private),
+                RoomChoice::Join(room) => {
+                    match pending_games
+                        .iter()
+                        .position(|pending| !pending.private && pending.room == room)
+                    {
+                        Some(index) => Some(index),
+                        None => {
+                            reject_connection(&mut connection, RejectionReason::RoomNotFound).await;
+                            continue;
+                        }
+                    }
+                }
+                RoomChoice::New => None,
+                RoomChoice::Private(room) => pending_games
+                    .iter()
+                    .position(|pending| pending.private && pending.room == room),
+            };
+            let seated = index.map_or(&[][..], |index| &pending_games[index].players);
+            // Seats reopened by players who left are taken first
+            let player_id = (PLAYER_ONE_ID..)
+                .find(|&player_id| seated.iter().all(|player| player.id != player_id))
+                .unwrap();
+            let taken_marks = taken_marks(seated);
+            let Some(session_token) = accept_new_player(
+                &mut connection,
+                &request,
+                Seats::single(player_id),
+                &taken_marks,
+            )
+            .await
+            else {
+                continue;
+            };
+            let player = Player::new(player_id, connection, session_token)
+                .with_mark(request.mark)
+                .with_name(request.name);
+            let index = index.unwrap_or_else(|| {
+                let (room, private) = match request.room {
+                    RoomChoice::Private(room) => (room, true),
+                    _ => (new_room_code(&pending_games), false),
+                };
+                pending_games.push(PendingGame {
+                    room,
+                    private,
+                    players: Vec::new(),
+                });
+                pending_games.len() - 1
+            });
+            pending_games[index].players.push(player);
+            if pending_games[index].players.len() < self.game.player_count() as usize {
+                continue;
+            }
+
+            let mut players = pending_games.remove(index).players;
+            players.sort_by_key(|player| player.id);
+            let player_sessions: Vec<(u8, u64)> = players
+                .iter()
+                .map(|player| (player.id, player.session_token))
+                .collect();
+            let mut server = self.server_builder.online(players, self.game.as_ref());
+            let server_channel = server.accept_reconnections(RECONNECTION_GRACE_PERIOD);
+            for (player_id, session_token) in player_sessions {
+                sessions.insert(session_token, (player_id, server_channel.clone()));
+            }
+            configure(&mut server);
+
+            games_started += 1;
+            let game_number = games_started;
+            self.active_games
+                .insert(game_number, self.game.id(), server_channel);
+            let active_games = self.active_games.clone();
+            let span = tracing::info_span!("game", id = game_number, game = self.game.id());
+            tokio::spawn(
+                async move {
+                    tracing::info!("Game started");
+                    server.init().await;
+                    active_games.remove(game_number);
+                    tracing::info!("Game finished");
+                }
+                .instrument(span),
+            );
+        }
+    }
+
+    /// Seats a player with each of the given IDs, taking the first seat free as each connects.
+    /// When players are offered a choice of games, each seated chooses the game to be played in
+    /// turn, and those who choose none are sent the games offered and turned away. Players who
+    /// leave before the rest have connected, whether by closing their connection or by it going
+    /// quiet with heartbeats unanswered, are dropped and their seat reopened.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Vec<Player>)` containing the players in the order of their IDs.
+    /// - `None` if the host stopped the lobby waiting, after sending away those already seated.
+    async fn seat_players(&mut self, player_ids: &[u8]) -> Option<Vec<Player>> {
+        let mut players: Vec<Player> = Vec::new();
+        let mut wait_timer = self.wait_timer.take();
+
+        while let Some(&player_id) = player_ids
+            .iter()
+            .find(|&&player_id| players.iter().all(|player| player.id != player_id))
+        {
+            tokio::select! {
+                (mut connection, request) = self.get_connection_request() => {
+                    let chosen_game = match self.find_chosen_game(&request) {
+                        Ok(chosen_game) => chosen_game,
+                        Err(games) => {
+                            offer_games(&mut connection, games).await;
+                            continue;
+                        }
+                    };
+                    if let Some(player) = admit_player(connection, request, player_id, &players).await {
+                        players.push(player);
+                        if chosen_game.is_some() {
+                            self.chosen_game = chosen_game;
+                        }
+                        if let Some(timer) = &mut wait_timer {
+                            timer.restart();
+                        }
+                    }
+                }
+                // Players send nothing until the game begins, so reading only ends with them gone
+                Err((error, player_id)) = server::player_event_received(&mut players) => {
+                    tracing::info!(player_id, ?error, "Player left before the game began, reopening their seat");
+                    if let Some(index) = players.iter().position(|player| player.id == player_id) {
+                        let mut player = players.remove(index);
+                        let _ = player.connection.shutdown().await;
+                    }
+                }
+                outcome = waiting::run_out(&mut wait_timer) => {
+                    if outcome == WaitOutcome::Cancelled {
+                        tracing::info!("Host stopped waiting for players");
+                        for mut player in players {
+                            let _ = player
+                                .connection
+                                .write_event(&OutgoingEvent::Shutdown {
+                                    reason: ShutdownReason::ServerShutdown,
+                                })
+                                .await;
+                            let _ = player.connection.shutdown().await;
+                        }
+                        return None;
+                    }
                 }
             }
+        }
 
-            let _ = connection.shutdown().await;
-            continue;
+        players.sort_by_key(|player| player.id);
+        Some(players)
+    }
+
+    /// Finds the offered game named in a player's request, when players are given a choice.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Some(usize))` containing the index of the game chosen.
+    /// - `Ok(None)` if there is no choice to make, or the request is not to play.
+    /// - `Err(Vec<String>)` containing the IDs of the games offered, if the player named none of
+    ///   them.
+    fn find_chosen_game(&self, request: &ConnectionRequest) -> Result<Option<usize>, Vec<String>> {
+        if self.offered_games.len() < 2 || request.spectator || request.list_games {
+            return Ok(None);
         }
+
+        request
+            .game
+            .as_deref()
+            .and_then(|id| self.offered_games.iter().position(|game| game.id() == id))
+            .map(Some)
+            .ok_or_else(|| {
+                self.offered_games
+                    .iter()
+                    .map(|game| String::from(game.id()))
+                    .collect()
+            })
     }
+
+    /// Accepts the next new player, granting their connection the given seats.
+    ///
+    /// # Returns
+    ///
+    /// The connection, the session token the player was given and the request they connected
+    /// with.
+    async fn get_connection(
+        &mut self,
+        seats: Seats,
+        taken_marks: &[PlayerMark],
+    ) -> (Connection, u64, ConnectionRequest) {
+        loop {
+            let (mut connection, request) = self.get_connection_request().await;
+
+            if refuse_before_game_starts(&mut connection, &request).await {
+                continue;
+            }
+            if let Some(session_token) =
+                accept_new_player(&mut connection, &request, seats, taken_marks).await
+            {
+                break (connection, session_token, request);
+            }
+        }
+    }
+
+    /// Waits for the next connection to complete its handshakes and present a valid request.
+    /// Cancel safe, so that it can be raced against other events without losing a connection
+    /// part way through, as any still completing their handshakes are kept for the next call.
+    async fn get_connection_request(&mut self) -> (Connection, ConnectionRequest) {
+        loop {
+            tokio::select! {
+                accepted = self.listener.accept() => self.start_handshake(accepted).await,
+                Some(handshake) = self.handshakes.join_next() => {
+                    if let Ok(Some(accepted)) = handshake {
+                        return accepted;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Starts the handshakes of a stream just accepted by the listener in a task of its own,
+    /// turning it away if the peer has exceeded the rate limit.
+    async fn start_handshake(&mut self, accepted: io::Result<(TcpStream, SocketAddr)>) {
+        let (stream, peer) = match accepted {
+            Ok(accepted) => accepted,
+            Err(error) => {
+                tracing::warn!(?error, "Failed to accept a connection");
+                time::sleep(ACCEPT_RETRY_DELAY).await;
+                return;
+            }
+        };
+        let span = tracing::info_span!("connection", %peer);
+        // Refused before any handshake, so that a flood costs as little as possible to turn away
+        if !self.rate_limiter.allow(peer.ip()) {
+            span.in_scope(|| tracing::info!("Refused a connection exceeding the rate limit"));
+            return;
+        }
+
+        let handshake = Handshake {
+            tls_acceptor: self.tls_acceptor.clone(),
+            websocket: self.websocket,
+            join_token: self.join_token,
+        };
+        let handshake = async move {
+            match time::timeout(HANDSHAKE_TIMEOUT, handshake.read_connection_request(stream)).await
+            {
+                Ok(accepted) => accepted,
+                Err(_) => {
+                    tracing::info!("Dropped a connection that did not send its request in time");
+                    None
+                }
+            }
+        };
+        self.handshakes.spawn(handshake.instrument(span));
+    }
+}
+
+/// What a connection newly accepted by the lobby must go through before it can join.
+struct Handshake {
+    tls_acceptor: Option<TlsAcceptor>,
+    websocket: bool,
+    join_token: JoinToken,
+}
+
+impl Handshake {
+    /// Reads the request sent over a newly accepted stream, turning it away if it does not present
+    /// the lobby's join token.
+    async fn read_connection_request(
+        &self,
+        stream: TcpStream,
+    ) -> Option<(Connection, ConnectionRequest)> {
+        let mut connection = self.accept_connection(stream).await?;
+
+        let request = match connection.read_event::<ConnectionRequest>().await {
+            Ok(request) => request,
+            Err(error) => {
+                tracing::warn!(?error, "Failed to read the connection request");
+                let _ = connection.shutdown().await;
+                return None;
+            }
+        };
+
+        if request.join_token != self.join_token {
+            tracing::info!("Rejected a request with an unknown join token");
+            reject_connection(&mut connection, RejectionReason::UnknownJoinToken).await;
+        } else if request.protocol_version != PROTOCOL_VERSION {
+            tracing::info!(
+                protocol_version = request.protocol_version,
+                "Rejected a request using a different protocol version"
+            );
+            let reason = RejectionReason::ProtocolVersionMismatch {
+                server_version: PROTOCOL_VERSION,
+            };
+            reject_connection(&mut connection, reason).await;
+        } else {
+            tracing::debug!(
+                spectator = request.spectator,
+                rejoining = request.session_token.is_some(),
+                "Received a connection request"
+            );
+            return Some((connection, request));
+        }
+
+        None
+    }
+
+    /// Enables heartbeats on the stream and performs the TLS and WebSocket handshakes required by
+    /// the lobby, if any.
+    async fn accept_connection(&self, stream: TcpStream) -> Option<Connection> {
+        if let Err(error) = connection::enable_heartbeats(&stream) {
+            tracing::warn!(?error, "Failed to enable heartbeats");
+            return None;
+        }
+        let stream: Box<dyn Stream> = match &self.tls_acceptor {
+            Some(tls_acceptor) => match tls_acceptor.accept(stream).await {
+                Ok(stream) => Box::new(stream),
+                Err(error) => {
+                    tracing::warn!(?error, "TLS handshake failed");
+                    return None;
+                }
+            },
+            None => Box::new(stream),
+        };
+
+        if !self.websocket {
+            return Some(Connection::new(stream));
+        }
+        match Connection::accept_websocket(stream).await {
+            Ok(connection) => Some(connection),
+            Err(error) => {
+                tracing::warn!(?error, "WebSocket handshake failed");
+                None
+            }
+        }
+    }
+}
+
+/// How to connect to a game, which must match how the game is being hosted.
+#[derive(Clone, Default)]
+pub struct ConnectionOptions {
+    pub tls_connector: Option<TlsConnector>,
+    pub websocket: bool,
+    /// The mark to be shown with, rather than the default one for the seat given.
+    pub mark: Option<PlayerMark>,
+    /// The name to be rated under, if the host is keeping ratings.
+    pub name: Option<PlayerName>,
+    /// Which game to be seated in, when joining a dedicated server.
+    pub room: RoomChoice,
+    /// How persistently to try reaching the game, which is only tried once by default.
+    pub retry: RetryPolicy,
+    /// The ID of the game to play, when the host offers a choice of games.
+    pub game: Option<String>,
+}
+
+/// The details required for a player to rejoin a game after losing their connection.
+#[derive(Clone)]
+pub struct Session {
+    pub address: SocketAddr,
+    pub join_token: JoinToken,
+    pub session_token: u64,
+    /// The seats the player was granted, which is both of them when playing hot-seat.
+    pub seats: Seats,
+    pub options: ConnectionOptions,
+}
+
+pub async fn connect_to_game<A: ToSocketAddrs>(
+    addr: A,
+    join_token: JoinToken,
+    options: ConnectionOptions,
+) -> Result<(Connection, Session), Error> {
+    let stream = reach_game(addr, &options.retry).await?;
+    let address = stream.peer_addr()?;
+    let mut connection = open_connection(stream, &options).await?;
+    let request = ConnectionRequest {
+        mark: options.mark.clone(),
+        name: options.name.clone(),
+        room: options.room,
+        game: options.game.clone(),
+        ..ConnectionRequest::new(join_token)
+    };
+    let (session_token, seats) = match send_connection_request(&mut connection, request).await? {
+        ConnectionResponse::Accepted {
+            session_token,
+            seats,
+            ..
+        } => (session_token, seats),
+        ConnectionResponse::Spectating { .. } | ConnectionResponse::GameList { .. } => {
+            return Err(Error::UnexpectedResponse)
+        }
+        ConnectionResponse::Rejected { reason } => return Err(Error::Rejected(reason)),
+        ConnectionResponse::GameOffer { games } => return Err(Error::ChooseGame(games)),
+    };
+
+    Ok((
+        connection,
+        Session {
+            address,
+            join_token,
+            session_token,
+            seats,
+            options,
+        },
+    ))
+}
+
+pub async fn reconnect_to_game(session: &Session) -> Result<Connection, Error> {
+    let stream = TcpStream::connect(session.address).await?;
+    let mut connection = open_connection(stream, &session.options).await?;
+    let request = ConnectionRequest {
+        session_token: Some(session.session_token),
+        ..ConnectionRequest::new(session.join_token)
+    };
+    match send_connection_request(&mut connection, request).await? {
+        ConnectionResponse::Accepted { .. } => Ok(connection),
+        ConnectionResponse::Spectating { .. }
+        | ConnectionResponse::GameList { .. }
+        | ConnectionResponse::GameOffer { .. } => Err(Error::UnexpectedResponse),
+        ConnectionResponse::Rejected { reason } => Err(Error::Rejected(reason)),
+    }
+}
+
+pub async fn spectate_game<A: ToSocketAddrs>(
+    addr: A,
+    join_token: JoinToken,
+    options: ConnectionOptions,
+) -> Result<Connection, Error> {
+    let stream = reach_game(addr, &options.retry).await?;
+    let mut connection = open_connection(stream, &options).await?;
+    let request = ConnectionRequest {
+        spectator: true,
+        ..ConnectionRequest::new(join_token)
+    };
+    match send_connection_request(&mut connection, request).await? {
+        ConnectionResponse::Spectating { .. } => Ok(connection),
+        ConnectionResponse::Accepted { .. }
+        | ConnectionResponse::GameList { .. }
+        | ConnectionResponse::GameOffer { .. } => Err(Error::UnexpectedResponse),
+        ConnectionResponse::Rejected { reason } => Err(Error::Rejected(reason)),
+    }
+}
+
+/// Asks a dedicated server for the games waiting for players, in the order they were opened.
+pub async fn list_games<A: ToSocketAddrs>(
+    addr: A,
+    join_token: JoinToken,
+    options: &ConnectionOptions,
+) -> Result<Vec<OpenGame>, Error> {
+    let stream = reach_game(addr, &options.retry).await?;
+    let mut connection = open_connection(stream, options).await?;
+    let request = ConnectionRequest {
+        list_games: true,
+        ..ConnectionRequest::new(join_token)
+    };
+    let response = send_connection_request(&mut connection, request).await?;
+    let _ = connection.shutdown().await;
+    match response {
+        ConnectionResponse::GameList { games } => Ok(games),
+        ConnectionResponse::Accepted { .. }
+        | ConnectionResponse::Spectating { .. }
+        | ConnectionResponse::GameOffer { .. } => Err(Error::UnexpectedResponse),
+        ConnectionResponse::Rejected { reason } => Err(Error::Rejected(reason)),
+    }
+}
+
+/// Connects to the game as persistently as the policy allows.
+async fn reach_game<A: ToSocketAddrs>(addr: A, retry: &RetryPolicy) -> Result<TcpStream, Error> {
+    retry::connect(addr, retry)
+        .await
+        .map_err(|(cause, attempts)| Error::Unreachable { cause, attempts })
+}
+
+/// Enables heartbeats on the stream and performs the TLS and WebSocket handshakes required by the
+/// options, if any.
+async fn open_connection(
+    stream: TcpStream,
+    options: &ConnectionOptions,
+) -> Result<Connection, Error> {
+    connection::enable_heartbeats(&stream)?;
+    let address = stream.peer_addr()?;
+    let stream: Box<dyn Stream> = match &options.tls_connector {
+        Some(tls_connector) => {
+            let server_name = ServerName::IpAddress(address.ip().into());
+            Box::new(tls_connector.connect(server_name, stream).await?)
+        }
+        None => Box::new(stream),
+    };
+
+    if options.websocket {
+        Ok(Connection::connect_websocket(stream, address).await?)
+    } else {
+        Ok(Connection::new(stream))
+    }
+}
+
+/// Sends a connection request, switching the connection over to the codec chosen by the host once
+/// it responds.
+async fn send_connection_request(
+    connection: &mut Connection,
+    request: ConnectionRequest,
+) -> Result<ConnectionResponse, Error> {
+    connection.write_event(&request).await?;
+
+    let response: ConnectionResponse = connection.read_event().await?;
+    connection.set_codec(response.codec());
+    connection.set_compression(response.compression());
+
+    Ok(response)
+}
+
+/// Sends the response to a connection request, then switches the connection over to the codec it
+/// names. The handshake itself is always encoded as JSON.
+async fn send_connection_response(
+    connection: &mut Connection,
+    response: ConnectionResponse,
+) -> Result<(), connection::WriteError> {
+    connection.write_event(&response).await?;
+    connection.set_codec(response.codec());
+    connection.set_compression(response.compression());
+
+    Ok(())
+}
+
+/// Turns away connections asking for what cannot be given until the game begins, being the games
+/// listed or to spectate.
+///
+/// # Returns
+///
+/// Whether the connection was turned away.
+async fn refuse_before_game_starts(
+    connection: &mut Connection,
+    request: &ConnectionRequest,
+) -> bool {
+    let reason = if request.list_games {
+        RejectionReason::ListingUnavailable
+    } else if request.spectator {
+        RejectionReason::GameNotStarted
+    } else {
+        return false;
+    };
+
+    reject_connection(connection, reason).await;
+    true
+}
+
+/// Seats a player joining a lobby that is waiting on players, with the given ID.
+///
+/// # Parameters
+///
+/// - `seated`: The players already seated, whose marks the new player may not choose.
+///
+/// # Returns
+///
+/// - `Some(Player)` containing the player seated.
+/// - `None` if the connection was turned away.
+async fn admit_player(
+    mut connection: Connection,
+    request: ConnectionRequest,
+    player_id: u8,
+    seated: &[Player],
+) -> Option<Player> {
+    if refuse_before_game_starts(&mut connection, &request).await {
+        return None;
+    }
+    let session_token = accept_new_player(
+        &mut connection,
+        &request,
+        Seats::single(player_id),
+        &taken_marks(seated),
+    )
+    .await?;
+
+    Some(
+        Player::new(player_id, connection, session_token)
+            .with_mark(request.mark)
+            .with_name(request.name),
+    )
 }
 
-pub async fn connect_to_game<A: ToSocketAddrs>(addr: A) -> Result<Connection, Error> {
-    let stream = TcpStream::connect(addr).await?;
-    let mut connection = Connection::new(stream);
-    connection
-        .write_event(&ConnectionRequest { game_id: GAME_ID })
-        .await?;
+/// Accepts a player joining a game for the first time, granting their connection the given seats.
+///
+/// # Parameters
+///
+/// - `taken_marks`: The marks chosen by players already in the game, which the player may not
+///   choose.
+///
+/// # Returns
+///
+/// - `Some(u64)` containing the session token the player was given.
+/// - `None` if the player asked to rejoin a game or chose a mark that was taken, which are
+///   refused, or could not be told they were accepted. The connection has been shut down.
+async fn accept_new_player(
+    connection: &mut Connection,
+    request: &ConnectionRequest,
+    seats: Seats,
+    taken_marks: &[PlayerMark],
+) -> Option<u64> {
+    if request.session_token.is_some() {
+        reject_connection(connection, RejectionReason::UnknownSession).await;
+        return None;
+    }
+    if request
+        .mark
+        .as_ref()
+        .is_some_and(|mark| taken_marks.contains(mark))
+    {
+        reject_connection(connection, RejectionReason::MarkTaken).await;
+        return None;
+    }
+
+    let session_token = rand::random();
+    let response = ConnectionResponse::Accepted {
+        session_token,
+        codec: CodecKind::negotiate(&request.codecs),
+        compression: Compression::negotiate(&request.compression),
+        seats,
+    };
+    if send_connection_response(connection, response)
+        .await
+        .is_err()
+    {
+        let _ = connection.shutdown().await;
+        return None;
+    }
+
+    Some(session_token)
+}
+
+/// The marks chosen by the given players.
+fn taken_marks(players: &[Player]) -> Vec<PlayerMark> {
+    players
+        .iter()
+        .filter_map(|player| player.mark.clone())
+        .collect()
+}
 
-    Ok(connection)
+/// Hands the connection of a player rejoining a game over to the server playing it.
+///
+/// # Parameters
+///
+/// - `request`: The request the player rejoined with, offering the codecs and compression they
+///   support.
+/// - `player`: The ID and session token of the player rejoining.
+///
+/// # Returns
+///
+/// Whether the server is still playing the game, which it is not once it has stopped taking
+/// connections. A player rejoining a game that is not keeping up may be disconnected instead,
+/// as its `BackpressurePolicy` decides.
+async fn hand_back_player(
+    mut connection: Connection,
+    request: &ConnectionRequest,
+    (player_id, session_token): (u8, u64),
+    server_channel: &EventSender,
+) -> bool {
+    let response = ConnectionResponse::Accepted {
+        session_token,
+        codec: CodecKind::negotiate(&request.codecs),
+        compression: Compression::negotiate(&request.compression),
+        seats: Seats::single(player_id),
+    };
+    if send_connection_response(&mut connection, response)
+        .await
+        .is_err()
+    {
+        return true;
+    }
+
+    let event = ServerEvent::PlayerReconnected {
+        player_id,
+        connection,
+    };
+    server_channel.send(event).await != Err(SendError::Closed)
+}
+
+/// Sends the games waiting for players to a connection that asked for them, then shuts it down.
+async fn send_game_list(mut connection: Connection, games: Vec<OpenGame>) {
+    let _ = send_connection_response(&mut connection, ConnectionResponse::GameList { games }).await;
+    let _ = connection.shutdown().await;
+}
+
+/// Sends the games offered by the host to a player who chose none of them, then shuts the
+/// connection down for them to join again once they have chosen.
+async fn offer_games(connection: &mut Connection, games: Vec<String>) {
+    let _ = connection
+        .write_event(&ConnectionResponse::GameOffer { games })
+        .await;
+    let _ = connection.shutdown().await;
+}
+
+/// A code for a new public room, differing from that of every game waiting for players.
+fn new_room_code(pending_games: &[PendingGame]) -> RoomCode {
+    loop {
+        let room = RoomCode(rand::random());
+        if pending_games.iter().all(|pending| pending.room != room) {
+            break room;
+        }
+    }
+}
+
+/// Waits for a player seated in any of the pending games to leave, as `seat_players` watches for.
+/// Players send nothing until their game begins, so reading only ends with them gone. Never
+/// completes while no game is pending.
+///
+/// # Returns
+///
+/// The index of the pending game the player was seated in, along with their ID and the error
+/// reading from them.
+async fn pending_player_left(pending_games: &mut [PendingGame]) -> (usize, u8, ReadError) {
+    let mut departures: Vec<_> = pending_games
+        .iter_mut()
+        .enumerate()
+        .map(|(index, pending)| {
+            Box::pin(async move {
+                loop {
+                    let read = server::player_event_received(&mut pending.players).await;
+                    if let Err((error, player_id)) = read {
+                        return (index, player_id, error);
+                    }
+                }
+            })
+        })
+        .collect();
+
+    future::poll_fn(|cx| {
+        departures
+            .iter_mut()
+            .find_map(|departure| match departure.as_mut().poll(cx) {
+                Poll::Ready(departed) => Some(Poll::Ready(departed)),
+                Poll::Pending => None,
+            })
+            .unwrap_or(Poll::Pending)
+    })
+    .await
+}
+
+/// Tells the other end of a connection why it was refused, before shutting the connection down.
+async fn reject_connection(connection: &mut Connection, reason: RejectionReason) {
+    let _ = connection
+        .write_event(&ConnectionResponse::Rejected { reason })
+        .await;
+    let _ = connection.shutdown().await;
 }
 
 #[derive(Serialize, Deserialize)]
 struct ConnectionRequest {
-    game_id: u16,
+    join_token: JoinToken,
+    /// Requests from clients that predate protocol versioning are treated as version 0.
+    #[serde(default)]
+    protocol_version: u16,
+    session_token: Option<u64>,
+    #[serde(default)]
+    spectator: bool,
+    #[serde(default)]
+    codecs: Vec<CodecKind>,
+    #[serde(default)]
+    compression: Vec<Compression>,
+    #[serde(default)]
+    mark: Option<PlayerMark>,
+    #[serde(default)]
+    name: Option<PlayerName>,
+    /// Asks for the games waiting for players rather than to join one.
+    #[serde(default)]
+    list_games: bool,
+    #[serde(default)]
+    room: RoomChoice,
+    /// The ID of the game the player chooses to play, when the host offers a choice.
+    #[serde(default)]
+    game: Option<String>,
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum Error {
-    StreamError(#[from] std::io::Error),
-    WriteError(#[from] connection::WriteError),
+impl ConnectionRequest {
+    /// A request to join a game for the first time, offering every supported codec.
+    fn new(join_token: JoinToken) -> ConnectionRequest {
+        ConnectionRequest {
+            join_token,
+            protocol_version: PROTOCOL_VERSION,
+            session_token: None,
+            spectator: false,
+            codecs: SUPPORTED_CODECS.to_vec(),
+            compression: SUPPORTED_COMPRESSION.to_vec(),
+            mark: None,
+            name: None,
+            list_games: false,
+            room: RoomChoice::Any,
+            game: None,
+        }
+    }
 }
 
-impl fmt::Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.as_display())
+#[derive(Serialize, Deserialize)]
+enum ConnectionResponse {
+    Accepted {
+        session_token: u64,
+        #[serde(default)]
+        codec: CodecKind,
+        #[serde(default)]
+        compression: Option<Compression>,
+        seats: Seats,
+    },
+    Spectating {
+        #[serde(default)]
+        codec: CodecKind,
+        #[serde(default)]
+        compression: Option<Compression>,
+    },
+    Rejected {
+        reason: RejectionReason,
+    },
+    /// The games waiting for players, sent in answer to a request to list them.
+    GameList {
+        games: Vec<OpenGame>,
+    },
+    /// The IDs of the games the host offers, sent in answer to a request naming none of them.
+    GameOffer {
+        games: Vec<String>,
+    },
+}
+
+impl ConnectionResponse {
+    fn codec(&self) -> CodecKind {
+        match self {
+            ConnectionResponse::Accepted { codec, .. }
+            | ConnectionResponse::Spectating { codec, .. } => *codec,
+            ConnectionResponse::Rejected { .. }
+            | ConnectionResponse::GameList { .. }
+            | ConnectionResponse::GameOffer { .. } => CodecKind::default(),
+        }
+    }
+
+    fn compression(&self) -> Option<Compression> {
+        match self {
+            ConnectionResponse::Accepted { compression, .. }
+            | ConnectionResponse::Spectating { compression, .. } => *compression,
+            ConnectionResponse::Rejected { .. }
+            | ConnectionResponse::GameList { .. }
+            | ConnectionResponse::GameOffer { .. } => None,
+        }
     }
 }
 
+#[derive(PartialEq, Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum RejectionReason {
+    #[error("The join token does not match the one shared by the host.")]
+    UnknownJoinToken,
+    #[error(
+        "The game requires protocol version {server_version} but this client uses version {}, please make sure both players are using the same release.",
+        PROTOCOL_VERSION
+    )]
+    ProtocolVersionMismatch { server_version: u16 },
+    #[error("The game does not recognise this session.")]
+    UnknownSession,
+    #[error("The game has not started yet, try spectating again once it has.")]
+    GameNotStarted,
+    #[error("The server hosts many games at once, so is unable to tell which one to spectate.")]
+    SpectatingUnavailable,
+    #[error("Another player has already chosen this mark, please choose a different one.")]
+    MarkTaken,
+    #[error("Players must give their name to enter the tournament.")]
+    NameRequired,
+    #[error("Another player has already entered the tournament under this name.")]
+    NameTaken,
+    #[error("There is no match left in the tournament for a player of this name.")]
+    NotInTournament,
+    #[error("There is no game waiting for players in this room, it may have started already.")]
+    RoomNotFound,
+    #[error("The host is not offering a list of games, join using their address instead.")]
+    ListingUnavailable,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to connect to the game.")]
+    Stream(#[from] std::io::Error),
+    #[error(
+        "Failed to reach the game after {attempts} {}, as {cause}.",
+        if *attempts == 1 { "attempt" } else { "attempts" }
+    )]
+    Unreachable { cause: ConnectError, attempts: u32 },
+    #[error("Failed to send the connection request.")]
+    Write(#[from] connection::WriteError),
+    #[error("Failed to read the connection response.")]
+    Read(#[from] connection::ReadError),
+    #[error("Failed to open a WebSocket connection to the game.")]
+    WebSocket(#[from] tungstenite::Error),
+    #[error("The game sent an unexpected response.")]
+    UnexpectedResponse,
+    #[error("{0}")]
+    Rejected(RejectionReason),
+    /// The host offers a choice of games, so the player must name one of them to join.
+    #[error("The host offers a choice of games, so one must be chosen to join.")]
+    ChooseGame(Vec<String>),
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::game;
+    use crate::game::{self, AnyGameClient, AnyGameServer, ClientContext, GameServerEvent};
     use std::net::Ipv4Addr;
+    use tokio::sync::mpsc::Sender;
 
-    use crate::server::{IncomingEvent, ServerGameMode};
+    use crate::server::{IncomingEvent, ServerGameMode, BOTH_SEATS, PLAYER_TWO_ID};
     use crate::tic_tac_toe::ClientEvent;
 
     use super::*;
@@ -88,34 +1348,67 @@ mod tests {
         content: String,
     }
 
+    #[test]
+    fn test_join_token_is_parsed_from_how_it_is_displayed() {
+        let join_token = JoinToken(0x0123_4567_89ab_cdef);
+
+        assert_eq!(join_token.to_string(), "0123456789abcdef");
+        assert_eq!("0123456789abcdef".parse(), Ok(join_token));
+        assert_eq!("123456789ABCDEF".parse(), Ok(join_token));
+        assert_eq!("not a token".parse::<JoinToken>(), Err(InvalidJoinToken));
+        assert_eq!(
+            "10123456789abcdef".parse::<JoinToken>(),
+            Err(InvalidJoinToken)
+        );
+    }
+
+    #[test]
+    fn test_room_code_is_parsed_from_how_it_is_displayed() {
+        let room = RoomCode(0x3f2a);
+
+        assert_eq!(room.to_string(), "3F2A");
+        assert_eq!(room.to_string().parse(), Ok(room));
+        assert_eq!("3f2a".parse(), Ok(room));
+        assert_eq!("room".parse::<RoomCode>(), Err(InvalidRoomCode));
+    }
+
     #[tokio::test]
     async fn test_set_up_online_server_returns_server_with_expected_player_connections() {
         // Set up listener and Lobby
         let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
         let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
 
         // Create thread for lobby to process within
         let lobby_handle = tokio::spawn(async move {
-            let mut lobby = Lobby { listener };
-            let mut server = lobby.set_up_online_server().await;
+            let mut server = lobby.set_up_online_server().await.unwrap();
 
             // Assert that player connections are as expected based on received messages
             // It's not possible to predict the order that the messages will be received in, so we conditionally assert
             for _i in 0..1 {
                 match server.get_next_incoming_event().await.unwrap() {
-                    IncomingEvent::Client(event) => {
+                    IncomingEvent::Client {
+                        event,
+                        player_id: sender_id,
+                        seats,
+                    } => {
                         let deserialized_event: ClientEvent = game::deserialize_event(event);
                         match deserialized_event {
                             ClientEvent::MoveMade {
                                 player_id,
                                 move_index,
+                                ..
                             } => {
+                                assert_eq!(sender_id, player_id);
+                                assert_eq!(seats, Seats::single(player_id));
                                 if player_id == 1 {
                                     assert_eq!(move_index, 5)
                                 } else {
                                     assert_eq!(move_index, 8)
                                 }
                             }
+                            _ => panic!("Unexpected event received from player connection"),
                         }
                     }
                     _ => panic!("Unexpected event received from player one connection"),
@@ -127,13 +1420,26 @@ mod tests {
         let stream_one = TcpStream::connect(local_addr).await.unwrap();
         let mut connection_one = Connection::new(stream_one);
         connection_one
-            .write_event(&ConnectionRequest { game_id: 12345 })
+            .write_event(&ConnectionRequest {
+                join_token,
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
             .await
             .unwrap();
         connection_one
             .write_event(&game::serialize_event(ClientEvent::MoveMade {
                 player_id: 1,
                 move_index: 5,
+                mark: None,
             }))
             .await
             .unwrap();
@@ -142,13 +1448,26 @@ mod tests {
         let bogus_stream = TcpStream::connect(local_addr).await.unwrap();
         let mut bogus_connection = Connection::new(bogus_stream);
         bogus_connection
-            .write_event(&ConnectionRequest { game_id: 999 })
+            .write_event(&ConnectionRequest {
+                join_token: JoinToken(999),
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
             .await
             .unwrap();
         bogus_connection
             .write_event(&game::serialize_event(ClientEvent::MoveMade {
                 player_id: 2,
                 move_index: 2,
+                mark: None,
             }))
             .await
             .unwrap();
@@ -157,13 +1476,26 @@ mod tests {
         let stream_two = TcpStream::connect(local_addr).await.unwrap();
         let mut connection_two = Connection::new(stream_two);
         connection_two
-            .write_event(&ConnectionRequest { game_id: 12345 })
+            .write_event(&ConnectionRequest {
+                join_token,
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
             .await
             .unwrap();
         connection_two
             .write_event(&game::serialize_event(ClientEvent::MoveMade {
                 player_id: 2,
                 move_index: 8,
+                mark: None,
             }))
             .await
             .unwrap();
@@ -176,11 +1508,15 @@ mod tests {
         // Set up listener and Lobby
         let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
         let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
 
         // Create thread for lobby to process within
         let lobby_handle = tokio::spawn(async move {
-            let mut lobby = Lobby { listener };
-            let mut connection = lobby.get_connection().await;
+            let mut lobby = lobby;
+            let (mut connection, _, _) = lobby
+                .get_connection(Seats::single(PLAYER_ONE_ID), &[])
+                .await;
 
             // Assert that connection is as expected based on sent message
             let event: TestEvent = connection.read_event().await.unwrap();
@@ -191,7 +1527,19 @@ mod tests {
         let stream_one = TcpStream::connect(local_addr).await.unwrap();
         let mut connection_one = Connection::new(stream_one);
         connection_one
-            .write_event(&ConnectionRequest { game_id: 999 })
+            .write_event(&ConnectionRequest {
+                join_token: JoinToken(999),
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
             .await
             .unwrap();
         connection_one
@@ -205,7 +1553,19 @@ mod tests {
         let stream_two = TcpStream::connect(local_addr).await.unwrap();
         let mut connection_two = Connection::new(stream_two);
         connection_two
-            .write_event(&ConnectionRequest { game_id: 12345 })
+            .write_event(&ConnectionRequest {
+                join_token,
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
             .await
             .unwrap();
         connection_two
@@ -217,4 +1577,704 @@ mod tests {
 
         lobby_handle.await.unwrap()
     }
+
+    #[tokio::test]
+    async fn test_get_connection_negotiates_codec_offered_by_client() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let mut lobby = lobby;
+            let (mut connection, _, _) = lobby
+                .get_connection(Seats::single(PLAYER_ONE_ID), &[])
+                .await;
+
+            // Assert that events sent with the negotiated codec can be read
+            let event: TestEvent = connection.read_event().await.unwrap();
+            assert_eq!(
+                event.content,
+                String::from("Content encoded as MessagePack")
+            )
+        });
+
+        // Simulate Client connecting and offering only MessagePack
+        let stream = TcpStream::connect(local_addr).await.unwrap();
+        let mut connection = Connection::new(stream);
+        connection
+            .write_event(&ConnectionRequest {
+                join_token,
+                protocol_version: PROTOCOL_VERSION,
+                session_token: None,
+                spectator: false,
+                codecs: vec![CodecKind::MessagePack],
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
+            .await
+            .unwrap();
+
+        let response: ConnectionResponse = connection.read_event().await.unwrap();
+        assert_eq!(response.codec(), CodecKind::MessagePack);
+
+        connection.set_codec(response.codec());
+        connection.set_compression(response.compression());
+        connection
+            .write_event(&TestEvent {
+                content: String::from("Content encoded as MessagePack"),
+            })
+            .await
+            .unwrap();
+
+        lobby_handle.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_connection_rejects_mismatched_protocol_version() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let mut lobby = lobby;
+            lobby
+                .get_connection(Seats::single(PLAYER_ONE_ID), &[])
+                .await;
+        });
+
+        // Simulate Client connecting with an older protocol version
+        let stream = TcpStream::connect(local_addr).await.unwrap();
+        let mut connection = Connection::new(stream);
+        connection
+            .write_event(&ConnectionRequest {
+                join_token,
+                protocol_version: PROTOCOL_VERSION - 1,
+                session_token: None,
+                spectator: false,
+                codecs: Vec::new(),
+                compression: Vec::new(),
+                mark: None,
+                name: None,
+                list_games: false,
+                room: RoomChoice::Any,
+                game: None,
+            })
+            .await
+            .unwrap();
+
+        let response: ConnectionResponse = connection.read_event().await.unwrap();
+        assert!(matches!(
+            response,
+            ConnectionResponse::Rejected {
+                reason: RejectionReason::ProtocolVersionMismatch {
+                    server_version: PROTOCOL_VERSION
+                }
+            }
+        ));
+
+        // Simulate Client connecting with the current protocol version
+        connect_to_game(local_addr, join_token, ConnectionOptions::default())
+            .await
+            .unwrap();
+
+        lobby_handle.await.unwrap()
+    }
+
+    // Other loopback addresses can only be connected from on Linux
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_get_connection_refuses_flooding_address_while_accepting_others() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let mut lobby = lobby;
+            lobby.set_rate_limit(RateLimit {
+                max_connections: 5,
+                period: Duration::from_secs(60),
+            });
+            lobby.set_up_online_server().await;
+        });
+
+        // Simulate another address hammering the lobby with connections, which are closed without
+        // a response once it has gone over its limit
+        let mut refused = 0;
+        for _ in 0..50 {
+            let socket = tokio::net::TcpSocket::new_v4().unwrap();
+            socket
+                .bind((Ipv4Addr::new(127, 0, 0, 2), 0).into())
+                .unwrap();
+            let mut connection = Connection::new(socket.connect(local_addr).await.unwrap());
+            let _ = connection
+                .write_event(&ConnectionRequest {
+                    join_token: JoinToken(999),
+                    protocol_version: PROTOCOL_VERSION,
+                    session_token: None,
+                    spectator: false,
+                    codecs: Vec::new(),
+                    compression: Vec::new(),
+                    mark: None,
+                    name: None,
+                    list_games: false,
+                    room: RoomChoice::Any,
+                    game: None,
+                })
+                .await;
+
+            match connection.read_event::<ConnectionResponse>().await {
+                Ok(ConnectionResponse::Rejected {
+                    reason: RejectionReason::UnknownJoinToken,
+                }) => {}
+                Err(_) => refused += 1,
+                Ok(_) => panic!("Unexpected response to flooding connection"),
+            }
+        }
+        assert_eq!(refused, 45);
+
+        // Simulate legitimate Clients joining from their own address, staying connected so that
+        // their seats are not reopened before the game begins
+        let mut connections = Vec::new();
+        for _ in 0..2 {
+            let (connection, _) =
+                connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                    .await
+                    .unwrap();
+            connections.push(connection);
+        }
+
+        lobby_handle.await.unwrap()
+    }
+
+    /// Tic Tac Toe as if it were played by three players, to check that the lobby waits for them.
+    struct ThreePlayerGame;
+
+    impl GameDescriptor for ThreePlayerGame {
+        fn id(&self) -> &'static str {
+            "three-player"
+        }
+
+        fn make_server(
+            &self,
+            channel: Sender<GameServerEvent>,
+        ) -> Box<dyn AnyGameServer + Send + Sync> {
+            TicTacToe::default().make_server(channel)
+        }
+
+        fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn AnyGameClient + 'a> {
+            TicTacToe::default().make_client(context)
+        }
+
+        fn player_count(&self) -> u8 {
+            3
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_up_online_server_waits_for_every_player_of_game() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        lobby.set_game(ThreePlayerGame);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let server = lobby.set_up_online_server().await.unwrap();
+
+            assert_eq!(
+                server.get_status().players,
+                vec![(1, true), (2, true), (3, true)]
+            );
+        });
+
+        // Simulate three Clients joining, one at a time so each is given the expected seat
+        let mut connections = Vec::new();
+        for expected_player_id in 1..=3 {
+            let (connection, session) =
+                connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                    .await
+                    .unwrap();
+            assert_eq!(session.seats, Seats::single(expected_player_id));
+            connections.push(connection);
+        }
+
+        lobby_handle.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serve_seats_players_while_another_connection_sends_nothing() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let _lobby_handle = tokio::spawn(lobby.serve(|_| {}));
+
+        // Connected first, but never sends its request
+        let _idle = TcpStream::connect(local_addr).await.unwrap();
+        let joining = async {
+            let (_first, first_session) =
+                connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                    .await
+                    .unwrap();
+            let (_second, second_session) =
+                connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                    .await
+                    .unwrap();
+            (first_session.seats, second_session.seats)
+        };
+
+        // Well within the time the idle connection is given to send its request
+        let seats = time::timeout(HANDSHAKE_TIMEOUT / 5, joining).await.unwrap();
+        assert_eq!(
+            seats,
+            (Seats::single(PLAYER_ONE_ID), Seats::single(PLAYER_TWO_ID))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_up_online_server_reopens_seat_of_player_who_leaves() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        lobby.set_game(ThreePlayerGame);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(lobby.set_up_online_server());
+
+        // The first player leaves before the game begins, so the next to join takes their seat
+        let (first, _) = connect_to_game(local_addr, join_token, ConnectionOptions::default())
+            .await
+            .unwrap();
+        let (_second, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+        drop(first);
+        time::sleep(Duration::from_millis(100)).await;
+        let (_third, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_ONE_ID));
+        let (_fourth, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(3));
+
+        let server = lobby_handle.await.unwrap().unwrap();
+        assert_eq!(
+            server.get_status().players,
+            vec![(1, true), (2, true), (3, true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_serve_reopens_seat_of_player_who_leaves() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        lobby.set_game(ThreePlayerGame);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(lobby.serve(|_| {}));
+
+        // The first player leaves while waiting to be paired, so the next to join takes their seat
+        let (first, _) = connect_to_game(local_addr, join_token, ConnectionOptions::default())
+            .await
+            .unwrap();
+        let (_second, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+        drop(first);
+        time::sleep(Duration::from_millis(100)).await;
+        let (_third, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_ONE_ID));
+        let (_fourth, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(3));
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_set_up_online_server_sets_up_nothing_once_host_stops_waiting() {
+        // Set up listener and Lobby, telling the host as soon as no one has joined for a moment
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let mut waiting_host = lobby.set_wait_timeout(Duration::from_millis(50));
+        let lobby_handle = tokio::spawn(lobby.set_up_online_server());
+
+        let (mut connection, _) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert!(waiting_host.timed_out().await.is_some());
+        waiting_host.cancel();
+
+        assert!(lobby_handle.await.unwrap().is_none());
+        assert!(matches!(
+            connection.read_event().await,
+            Ok(OutgoingEvent::Shutdown {
+                reason: ShutdownReason::ServerShutdown
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_set_up_online_server_rejects_mark_already_taken() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(lobby.set_up_online_server());
+
+        // Simulate two Clients choosing the same mark, then the second choosing another
+        let options = |mark: &str| ConnectionOptions {
+            mark: Some(mark.parse().unwrap()),
+            ..ConnectionOptions::default()
+        };
+        let _player_one = connect_to_game(local_addr, join_token, options("★"))
+            .await
+            .unwrap();
+        assert!(matches!(
+            connect_to_game(local_addr, join_token, options("★")).await,
+            Err(Error::Rejected(RejectionReason::MarkTaken))
+        ));
+        let (_, session) = connect_to_game(local_addr, join_token, options("☆"))
+            .await
+            .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+
+        lobby_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_up_online_server_plays_game_chosen_by_last_player_to_join() {
+        // Set up listener and Lobby, offering only the games played by two players
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        lobby.offer_games(vec![
+            Box::new(TicTacToe::default()),
+            Box::new(crate::checkers::Checkers),
+            Box::new(ThreePlayerGame),
+        ]);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(async move {
+            lobby.set_up_online_server().await.unwrap().init().await;
+        });
+        let options = |game: Option<&str>| ConnectionOptions {
+            game: game.map(String::from),
+            ..ConnectionOptions::default()
+        };
+
+        // Simulate the host choosing their game, then a Client choosing another once offered them
+        let (mut player_one, _) =
+            connect_to_game(local_addr, join_token, options(Some("tic-tac-toe")))
+                .await
+                .unwrap();
+        match connect_to_game(local_addr, join_token, options(None)).await {
+            Err(Error::ChooseGame(games)) => assert_eq!(games, ["tic-tac-toe", "checkers"]),
+            _ => panic!("Expected to be offered the games"),
+        }
+        let (mut player_two, session) =
+            connect_to_game(local_addr, join_token, options(Some("checkers")))
+                .await
+                .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+
+        for connection in [&mut player_one, &mut player_two] {
+            match connection.read_event().await.unwrap() {
+                OutgoingEvent::GameStarted { game, .. } => assert_eq!(game, "checkers"),
+                event => panic!("Expected the game to start, received {:?}", event),
+            }
+        }
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_serve_lists_open_games_and_seats_players_in_room_chosen() {
+        // Set up listener and Lobby, hosting games for any number of players
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(lobby.serve(|_| {}));
+        let options = |name: &str, room: RoomChoice| ConnectionOptions {
+            name: Some(name.parse().unwrap()),
+            room,
+            ..ConnectionOptions::default()
+        };
+
+        // Two players open games of their own, which are listed in the order they were opened
+        let _ada = connect_to_game(local_addr, join_token, options("Ada", RoomChoice::New))
+            .await
+            .unwrap();
+        let _alan = connect_to_game(local_addr, join_token, options("Alan", RoomChoice::New))
+            .await
+            .unwrap();
+        let open_games = list_games(local_addr, join_token, &ConnectionOptions::default())
+            .await
+            .unwrap();
+        let hosts: Vec<String> = open_games
+            .iter()
+            .map(|open_game| open_game.host.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(hosts, ["Ada", "Alan"]);
+        assert!(open_games
+            .iter()
+            .all(|open_game| open_game.game == "tic-tac-toe" && open_game.players_needed == 1));
+
+        // A third player joins the second game, which starts and is no longer listed
+        let room = RoomChoice::Join(open_games[1].room);
+        let (_, session) = connect_to_game(local_addr, join_token, options("Grace", room))
+            .await
+            .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+        let open_games = list_games(local_addr, join_token, &ConnectionOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(open_games.len(), 1);
+        assert_eq!(open_games[0].host, Some("Ada".parse().unwrap()));
+
+        // Naming a room with no game waiting is refused
+        assert!(matches!(
+            connect_to_game(local_addr, join_token, options("Linus", room)).await,
+            Err(Error::Rejected(RejectionReason::RoomNotFound))
+        ));
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_serve_keeps_private_rooms_to_players_who_agreed_on_code() {
+        // Set up listener and Lobby, hosting games for any number of players
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let lobby_handle = tokio::spawn(lobby.serve(|_| {}));
+        let options = |room: RoomChoice| ConnectionOptions {
+            room,
+            ..ConnectionOptions::default()
+        };
+        let private_room = RoomChoice::Private("BEEF".parse().unwrap());
+
+        // The first player waits in a private room, which is not listed to others
+        let _first = connect_to_game(local_addr, join_token, options(private_room))
+            .await
+            .unwrap();
+        let open_games = list_games(local_addr, join_token, &ConnectionOptions::default())
+            .await
+            .unwrap();
+        assert!(open_games.is_empty());
+
+        // A player joining any game opens a game of their own rather than entering the private room
+        let (_second, session) = connect_to_game(local_addr, join_token, options(RoomChoice::Any))
+            .await
+            .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_ONE_ID));
+
+        // The player who agreed on the code joins the first in the private room
+        let (_, session) = connect_to_game(local_addr, join_token, options(private_room))
+            .await
+            .unwrap();
+        assert_eq!(session.seats, Seats::single(PLAYER_TWO_ID));
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_set_up_hot_seat_server_grants_both_seats_to_one_connection() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let mut server = lobby.set_up_hot_seat_server().await;
+
+            for _ in 0..2 {
+                match server.get_next_incoming_event().await.unwrap() {
+                    IncomingEvent::Client { seats, .. } => assert_eq!(seats, BOTH_SEATS),
+                    _ => panic!("Unexpected event received from hot-seat connection"),
+                }
+            }
+        });
+
+        // Simulate a Client joining and making a move as each player
+        let (mut connection, session) =
+            connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                .await
+                .unwrap();
+        assert_eq!(session.seats, BOTH_SEATS);
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            connection
+                .write_event(&game::serialize_event(ClientEvent::MoveMade {
+                    player_id,
+                    move_index: 5,
+                    mark: None,
+                }))
+                .await
+                .unwrap();
+        }
+
+        lobby_handle.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_serve_pairs_players_and_hands_back_reconnections() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(lobby.serve(|_| {}));
+
+        // Simulate two pairs of Clients joining, one at a time so each is given the expected seat
+        let mut sessions = Vec::new();
+        for expected_player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID, PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let (connection, session) =
+                connect_to_game(local_addr, join_token, ConnectionOptions::default())
+                    .await
+                    .unwrap();
+            assert_eq!(session.seats, Seats::single(expected_player_id));
+            sessions.push((connection, session));
+        }
+
+        // Simulate the first Client reconnecting to the game it was playing
+        reconnect_to_game(&sessions[0].1).await.unwrap();
+
+        // Simulate a Client attempting to spectate
+        assert!(matches!(
+            spectate_game(local_addr, join_token, ConnectionOptions::default()).await,
+            Err(Error::Rejected(RejectionReason::SpectatingUnavailable))
+        ));
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_spectate_game_returns_rejection_reason_before_game_starts() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+
+        // Create thread for lobby to process within
+        let lobby_handle = tokio::spawn(async move {
+            let mut lobby = lobby;
+            lobby
+                .get_connection(Seats::single(PLAYER_ONE_ID), &[])
+                .await;
+        });
+
+        // Simulate Client attempting to spectate before both players have joined
+        assert!(matches!(
+            spectate_game(local_addr, join_token, ConnectionOptions::default()).await,
+            Err(Error::Rejected(RejectionReason::GameNotStarted))
+        ));
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_accept_late_connections_forwards_connections_with_known_session_token() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let (sender, mut receiver) = server::event_queue(1, Default::default());
+
+        // Create thread for lobby to process within
+        let lobby_handle =
+            tokio::spawn(lobby.accept_late_connections(vec![(1, 111), (2, 222)], sender));
+
+        // Simulate Client attempting to reconnect with an unknown session token
+        let unknown_session = Session {
+            address: local_addr,
+            join_token,
+            session_token: 999,
+            seats: Seats::single(PLAYER_ONE_ID),
+            options: ConnectionOptions::default(),
+        };
+        assert!(reconnect_to_game(&unknown_session).await.is_err());
+
+        // Simulate Client reconnecting with a known session token
+        let known_session = Session {
+            address: local_addr,
+            join_token,
+            session_token: 222,
+            seats: Seats::single(PLAYER_TWO_ID),
+            options: ConnectionOptions::default(),
+        };
+        reconnect_to_game(&known_session).await.unwrap();
+
+        match receiver.recv().await {
+            ServerEvent::PlayerReconnected { player_id, .. } => assert_eq!(player_id, 2),
+            event => panic!("Unexpected event received: {:?}", event),
+        }
+
+        lobby_handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_accept_late_connections_forwards_spectators() {
+        // Set up listener and Lobby
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+        let lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        let (sender, mut receiver) = server::event_queue(1, Default::default());
+
+        // Create thread for lobby to process within
+        let lobby_handle =
+            tokio::spawn(lobby.accept_late_connections(vec![(1, 111), (2, 222)], sender));
+
+        // Simulate Client connecting as a spectator
+        spectate_game(local_addr, join_token, ConnectionOptions::default())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            ServerEvent::SpectatorJoined { .. }
+        ));
+
+        lobby_handle.abort();
+    }
 }
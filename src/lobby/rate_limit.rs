@@ -0,0 +1,120 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// How many connections a single address may open within a period, beyond which the lobby
+/// refuses them.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RateLimit {
+    pub max_connections: usize,
+    pub period: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            max_connections: 30,
+            period: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Keeps track of the connections recently opened by each address, to enforce a `RateLimit`.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    attempts: HashMap<IpAddr, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> RateLimiter {
+        RateLimiter {
+            limit,
+            attempts: HashMap::new(),
+        }
+    }
+
+    /// Counts a connection being opened by the address.
+    ///
+    /// # Returns
+    ///
+    /// Whether the address is within its limit, so the connection may be accepted. Refused
+    /// connections still count towards the limit, so an address must stop trying to be let back in.
+    pub(crate) fn allow(&mut self, address: IpAddr) -> bool {
+        let now = Instant::now();
+        let period = self.limit.period;
+        let is_recent = |attempt: &Instant| now.duration_since(*attempt) < period;
+
+        // Forget addresses that have gone quiet, so that a flood from many addresses does not
+        // leave them all behind
+        self.attempts
+            .retain(|_, attempts| attempts.back().is_some_and(is_recent));
+
+        let attempts = self.attempts.entry(address).or_default();
+        while attempts.front().is_some_and(|attempt| !is_recent(attempt)) {
+            attempts.pop_front();
+        }
+        // Only enough attempts are kept to tell whether the limit has been reached
+        if attempts.len() > self.limit.max_connections {
+            attempts.pop_front();
+        }
+        attempts.push_back(now);
+
+        attempts.len() <= self.limit.max_connections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::time;
+
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit {
+        max_connections: 2,
+        period: Duration::from_secs(10),
+    };
+
+    #[tokio::test(start_paused = true)]
+    async fn connections_beyond_limit_are_refused_until_period_passes() {
+        let mut rate_limiter = RateLimiter::new(LIMIT);
+        let address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        assert!(rate_limiter.allow(address));
+        assert!(rate_limiter.allow(address));
+        assert!(!rate_limiter.allow(address));
+
+        time::advance(Duration::from_secs(11)).await;
+        assert!(rate_limiter.allow(address));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refused_connections_count_towards_limit() {
+        let mut rate_limiter = RateLimiter::new(LIMIT);
+        let address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        // Keeps trying every few seconds, never waiting out the period
+        for _ in 0..5 {
+            rate_limiter.allow(address);
+            time::advance(Duration::from_secs(4)).await;
+        }
+
+        assert!(!rate_limiter.allow(address));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn each_address_has_its_own_limit() {
+        let mut rate_limiter = RateLimiter::new(LIMIT);
+        let flooding_address = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+        for _ in 0..10 {
+            rate_limiter.allow(flooding_address);
+        }
+
+        assert!(rate_limiter.allow(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    }
+}
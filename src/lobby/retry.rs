@@ -0,0 +1,156 @@
+use std::io;
+use std::time::Duration;
+
+use tokio::net::{self, TcpStream, ToSocketAddrs};
+use tokio::time;
+
+/// How long to wait for a single attempt to connect to be answered.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How persistently to try reaching a game that cannot be connected to, waiting twice as long
+/// after each failed attempt as after the one before.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The most attempts to make, including the first.
+    pub attempts: u32,
+    /// How long to wait after the first failed attempt.
+    pub initial_delay: Duration,
+    /// The longest to wait between attempts, however many have failed.
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Makes a single attempt, giving up as soon as it fails.
+    pub const ONCE: RetryPolicy = RetryPolicy {
+        attempts: 1,
+        initial_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(16),
+    };
+
+    /// Makes up to the given number of attempts, with the default delays between them.
+    pub fn attempts(attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            attempts,
+            ..RetryPolicy::ONCE
+        }
+    }
+
+    /// The time to wait after the given failed attempt, counting from 1, before the next.
+    fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy::ONCE
+    }
+}
+
+/// Why a game could not be reached, told apart so that the player knows what to check.
+#[derive(thiserror::Error, Debug)]
+pub enum ConnectError {
+    #[error("the address could not be found")]
+    AddressNotFound(#[source] io::Error),
+    #[error("the connection was refused, so the game may not be running on that port")]
+    Refused,
+    #[error("nothing answered within {} seconds", CONNECT_TIMEOUT.as_secs())]
+    TimedOut,
+    #[error("{0}")]
+    Other(#[source] io::Error),
+}
+
+impl ConnectError {
+    /// Whether trying again may succeed. An address that cannot be found is most likely mistyped,
+    /// so is not worth waiting on.
+    fn is_transient(&self) -> bool {
+        !matches!(self, ConnectError::AddressNotFound(_))
+    }
+}
+
+/// Connects to the address, trying again as the policy allows while the failure may be transient.
+///
+/// # Returns
+///
+/// The stream, or why the last attempt failed along with the number of attempts made.
+pub(super) async fn connect<A: ToSocketAddrs>(
+    addr: A,
+    retry: &RetryPolicy,
+) -> Result<TcpStream, (ConnectError, u32)> {
+    let mut attempt = 1;
+    loop {
+        match connect_once(&addr).await {
+            Ok(stream) => return Ok(stream),
+            Err(error) if error.is_transient() && attempt < retry.attempts => {
+                let delay = retry.delay_after(attempt);
+                tracing::info!(attempt, ?delay, %error, "Failed to reach the game, retrying");
+                time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(error) => return Err((error, attempt)),
+        }
+    }
+}
+
+/// Tries each address the given one resolves to in turn, returning the first to connect.
+async fn connect_once<A: ToSocketAddrs>(addr: A) -> Result<TcpStream, ConnectError> {
+    let addresses = net::lookup_host(addr)
+        .await
+        .map_err(ConnectError::AddressNotFound)?;
+
+    let mut last_error =
+        ConnectError::AddressNotFound(io::Error::from(io::ErrorKind::AddrNotAvailable));
+    for address in addresses {
+        last_error = match time::timeout(CONNECT_TIMEOUT, TcpStream::connect(address)).await {
+            Ok(Ok(stream)) => return Ok(stream),
+            Ok(Err(error)) if error.kind() == io::ErrorKind::ConnectionRefused => {
+                ConnectError::Refused
+            }
+            Ok(Err(error)) => ConnectError::Other(error),
+            Err(_) => ConnectError::TimedOut,
+        };
+    }
+
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+    use tokio::time::Instant;
+
+    use super::*;
+
+    #[test]
+    fn delay_doubles_after_each_attempt_up_to_max() {
+        let retry = RetryPolicy::attempts(10);
+
+        let delays: Vec<u64> = (1..=7)
+            .map(|attempt| retry.delay_after(attempt).as_secs())
+            .collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 16, 16]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refused_connection_retried_until_attempts_run_out() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+        let started_at = Instant::now();
+
+        let result = connect(address, &RetryPolicy::attempts(3)).await;
+        assert!(matches!(result, Err((ConnectError::Refused, 3))));
+        assert_eq!(started_at.elapsed().as_secs(), 3);
+    }
+
+    #[tokio::test]
+    async fn address_not_found_given_up_on_straight_away() {
+        let result = connect("not an address", &RetryPolicy::attempts(3)).await;
+        assert!(matches!(result, Err((ConnectError::AddressNotFound(_), 1))));
+    }
+}
@@ -0,0 +1,510 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, Sender};
+use tracing::Instrument;
+
+use crate::connection::Connection;
+use crate::game::Seats;
+use crate::lobby::{
+    accept_new_player, hand_back_player, reject_connection, ConnectionRequest, Lobby,
+    RejectionReason, RECONNECTION_GRACE_PERIOD,
+};
+use crate::server::{
+    EventSender, GameEnd, OnlineConnection, OutgoingEvent, Player, PlayerName, Server,
+    PLAYER_ONE_ID,
+};
+
+/// Identifies a match by the index of its round, counted from the first, and its index within
+/// the round.
+type MatchId = (usize, usize);
+
+/// Who fills one side of a match in the bracket.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Slot {
+    /// The entrant with the given index, in the order they registered.
+    Entrant(usize),
+    /// Nobody, so the entrant on the other side goes through without playing.
+    Bye,
+    /// The winner of a match in the previous round, which has yet to be played.
+    Undecided,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Match {
+    slots: [Slot; 2],
+    /// The side of the match that won it, once it has been decided.
+    winner: Option<usize>,
+}
+
+/// A single elimination bracket, in which the winner of each match goes through to the next round
+/// until only one entrant is left.
+#[derive(Debug)]
+pub(crate) struct Bracket {
+    entrants: Vec<PlayerName>,
+    /// The matches of each round, the first round first.
+    rounds: Vec<Vec<Match>>,
+}
+
+impl Bracket {
+    /// Draws the bracket for the entrants, seeded in the order they registered. When the number
+    /// of entrants is not a power of two, the highest seeds are given a bye through the first
+    /// round, so that byes never face each other.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there are fewer than two entrants.
+    pub(crate) fn new(entrants: Vec<PlayerName>) -> Bracket {
+        assert!(
+            entrants.len() >= 2,
+            "A tournament needs at least two entrants"
+        );
+
+        let size = entrants.len().next_power_of_two();
+        let first_round = seed_positions(size)
+            .chunks(2)
+            .map(|seeds| Match {
+                slots: [0, 1].map(|side| match seeds[side] {
+                    seed if seed <= entrants.len() => Slot::Entrant(seed - 1),
+                    _ => Slot::Bye,
+                }),
+                winner: None,
+            })
+            .collect();
+        let mut rounds = vec![first_round];
+        let mut match_count = size / 2;
+        while match_count > 1 {
+            match_count /= 2;
+            let round = vec![
+                Match {
+                    slots: [Slot::Undecided; 2],
+                    winner: None,
+                };
+                match_count
+            ];
+            rounds.push(round);
+        }
+
+        let mut bracket = Bracket { entrants, rounds };
+        // The lower seed is always drawn on the first side, so any bye is on the second
+        for index in 0..bracket.rounds[0].len() {
+            if bracket.rounds[0][index].slots[1] == Slot::Bye {
+                bracket.record_winner((0, index), 0);
+            }
+        }
+
+        bracket
+    }
+
+    /// The index of the entrant going by the given name, if they entered.
+    pub(crate) fn find_entrant(&self, name: &PlayerName) -> Option<usize> {
+        self.entrants.iter().position(|entrant| entrant == name)
+    }
+
+    /// The match the entrant is due to play next along with their side of it, `None` once they
+    /// have been knocked out or have won the tournament.
+    pub(crate) fn next_match(&self, entrant: usize) -> Option<(MatchId, usize)> {
+        self.rounds.iter().enumerate().find_map(|(round, matches)| {
+            matches.iter().enumerate().find_map(|(index, game)| {
+                let side = game
+                    .slots
+                    .iter()
+                    .position(|&slot| slot == Slot::Entrant(entrant))?;
+                game.winner.is_none().then_some(((round, index), side))
+            })
+        })
+    }
+
+    /// Records the result of a match, putting its winner through to the next round.
+    pub(crate) fn record_winner(&mut self, (round, index): MatchId, side: usize) {
+        let game = &mut self.rounds[round][index];
+        game.winner = Some(side);
+        let winner = game.slots[side];
+
+        if let Some(next_round) = self.rounds.get_mut(round + 1) {
+            next_round[index / 2].slots[index % 2] = winner;
+        }
+    }
+
+    /// The name of the entrant on the given side of a match, if it has been decided.
+    pub(crate) fn entrant_name(&self, (round, index): MatchId, side: usize) -> Option<&PlayerName> {
+        match self.rounds[round][index].slots[side] {
+            Slot::Entrant(entrant) => Some(&self.entrants[entrant]),
+            Slot::Bye | Slot::Undecided => None,
+        }
+    }
+
+    /// The winner of the final, once it has been played.
+    pub(crate) fn champion(&self) -> Option<&PlayerName> {
+        let last_round = self.rounds.len() - 1;
+        let side = self.rounds[last_round][0].winner?;
+
+        self.entrant_name((last_round, 0), side)
+    }
+
+    /// The bracket as sent to players, showing who has played who and who won.
+    pub(crate) fn standings(&self) -> Vec<Vec<BracketMatch>> {
+        self.rounds
+            .iter()
+            .map(|matches| {
+                matches
+                    .iter()
+                    .map(|game| BracketMatch {
+                        players: game.slots.map(|slot| match slot {
+                            Slot::Entrant(entrant) => {
+                                BracketSlot::Player(self.entrants[entrant].to_string())
+                            }
+                            Slot::Bye => BracketSlot::Bye,
+                            Slot::Undecided => BracketSlot::Undecided,
+                        }),
+                        winner: game.winner,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The seed placed at each position of the first round of a bracket of the given size, which
+/// must be a power of two. The top two seeds can only meet in the final, the top four only in
+/// the semi-finals and so on.
+fn seed_positions(size: usize) -> Vec<usize> {
+    let mut positions = vec![1];
+    while positions.len() < size {
+        let seed_count = positions.len() * 2;
+        positions = positions
+            .iter()
+            .flat_map(|&seed| [seed, seed_count + 1 - seed])
+            .collect();
+    }
+
+    positions
+}
+
+/// A match in the bracket, as sent to players whenever the standings change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BracketMatch {
+    pub players: [BracketSlot; 2],
+    /// The index of the winning player in `players`, once the match has been decided.
+    pub winner: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BracketSlot {
+    Player(String),
+    /// Nobody, so the other player goes through without playing.
+    Bye,
+    /// The winner of a match yet to be played.
+    Undecided,
+}
+
+impl Lobby {
+    /// Runs a single elimination tournament between the given number of entrants, returning once
+    /// it has been won.
+    ///
+    /// Players register by joining with a name, the bracket being drawn once enough have. Each
+    /// match is played as soon as both of its players have joined, so the winner of a match joins
+    /// again with the same name to play their next one, and a drawn match is played again.
+    /// Players waiting on their opponent are sent the standings whenever they change.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if there are fewer than two entrants, or the game is not played
+    /// by two players.
+    pub async fn run_tournament<F>(mut self, entrant_count: usize, configure: F)
+    where
+        F: Fn(&mut Server<OnlineConnection>),
+    {
+        assert!(
+            entrant_count >= 2,
+            "A tournament needs at least two entrants"
+        );
+        assert_eq!(
+            self.game.player_count(),
+            2,
+            "A tournament can only be played by two players at a time"
+        );
+
+        let mut registrations: Vec<(Connection, ConnectionRequest, PlayerName)> = Vec::new();
+        let mut bracket: Option<Bracket> = None;
+        // The players who have joined each match that has yet to start, by their side of it
+        let mut waiting: HashMap<MatchId, [Option<Player>; 2]> = HashMap::new();
+        let mut in_progress: HashSet<MatchId> = HashSet::new();
+        // The seat and game of each player in a match that is still being played
+        let mut sessions: HashMap<u64, (u8, EventSender)> = HashMap::new();
+        let (result_sender, mut results) = mpsc::channel::<(MatchId, Option<GameEnd>)>(8);
+        let mut games_started: u64 = 0;
+
+        loop {
+            let (mut connection, request) = tokio::select! {
+                accepted = self.get_connection_request() => accepted,
+                Some((match_id, game_end)) = results.recv() => {
+                    in_progress.remove(&match_id);
+                    let Some(bracket) = bracket.as_mut() else {
+                        continue;
+                    };
+                    match game_end {
+                        Some(GameEnd::Won { winner }) => {
+                            bracket.record_winner(match_id, usize::from(winner - PLAYER_ONE_ID));
+                        }
+                        Some(GameEnd::Abandoned { player_id }) => {
+                            let side = usize::from(player_id - PLAYER_ONE_ID);
+                            bracket.record_winner(match_id, 1 - side);
+                        }
+                        // Without a winner the match is played again once both players rejoin
+                        Some(GameEnd::Drawn) | None => {}
+                    }
+                    if let Some(champion) = bracket.champion() {
+                        tracing::info!(%champion, "Tournament won");
+                        return;
+                    }
+                    send_standings(&mut waiting, bracket).await;
+                    continue;
+                }
+            };
+            sessions.retain(|_, (_, server_channel)| !server_channel.is_closed());
+
+            if request.list_games {
+                reject_connection(&mut connection, RejectionReason::ListingUnavailable).await;
+                continue;
+            }
+            if request.spectator {
+                reject_connection(&mut connection, RejectionReason::SpectatingUnavailable).await;
+                continue;
+            }
+            if let Some(session_token) = request.session_token {
+                if let Some((player_id, server_channel)) = sessions.get(&session_token) {
+                    let player = (*player_id, session_token);
+                    hand_back_player(connection, &request, player, server_channel).await;
+                    continue;
+                }
+            }
+            let Some(name) = request.name.clone() else {
+                reject_connection(&mut connection, RejectionReason::NameRequired).await;
+                continue;
+            };
+
+            // Entrants are held until the bracket is drawn, as until then their seat is not known
+            let Some(bracket) = bracket.as_mut() else {
+                if registrations.iter().any(|(_, _, entrant)| *entrant == name) {
+                    reject_connection(&mut connection, RejectionReason::NameTaken).await;
+                    continue;
+                }
+                tracing::info!(%name, "Entrant registered");
+                registrations.push((connection, request, name));
+                if registrations.len() < entrant_count {
+                    continue;
+                }
+
+                let entrants = registrations.iter().map(|(_, _, name)| name.clone());
+                let drawn = bracket.insert(Bracket::new(entrants.collect()));
+                tracing::info!("Bracket drawn");
+                for (mut connection, request, name) in registrations.drain(..) {
+                    let entrant = drawn.find_entrant(&name).unwrap();
+                    let (match_id, side) = drawn.next_match(entrant).unwrap();
+                    let player_id = PLAYER_ONE_ID + side as u8;
+                    if let Some(session_token) =
+                        accept_new_player(&mut connection, &request, Seats::single(player_id), &[])
+                            .await
+                    {
+                        let player = Player::new(player_id, connection, session_token)
+                            .with_mark(request.mark)
+                            .with_name(Some(name));
+                        waiting.entry(match_id).or_default()[side] = Some(player);
+                    }
+                }
+                send_standings(&mut waiting, drawn).await;
+                for match_id in ready_matches(&waiting) {
+                    let players = waiting.remove(&match_id).unwrap();
+                    games_started += 1;
+                    self.start_match(
+                        match_id,
+                        players,
+                        games_started,
+                        &configure,
+                        &mut sessions,
+                        result_sender.clone(),
+                    );
+                    in_progress.insert(match_id);
+                }
+                continue;
+            };
+
+            let next_match = bracket
+                .find_entrant(&name)
+                .and_then(|entrant| bracket.next_match(entrant));
+            let Some((match_id, side)) = next_match else {
+                reject_connection(&mut connection, RejectionReason::NotInTournament).await;
+                continue;
+            };
+            let seated = waiting
+                .get(&match_id)
+                .is_some_and(|sides| sides[side].is_some());
+            if seated || in_progress.contains(&match_id) {
+                reject_connection(&mut connection, RejectionReason::NameTaken).await;
+                continue;
+            }
+
+            let player_id = PLAYER_ONE_ID + side as u8;
+            let Some(session_token) =
+                accept_new_player(&mut connection, &request, Seats::single(player_id), &[]).await
+            else {
+                continue;
+            };
+            let player = Player::new(player_id, connection, session_token)
+                .with_mark(request.mark)
+                .with_name(Some(name));
+            waiting.entry(match_id).or_default()[side] = Some(player);
+
+            if ready_matches(&waiting).contains(&match_id) {
+                let players = waiting.remove(&match_id).unwrap();
+                games_started += 1;
+                self.start_match(
+                    match_id,
+                    players,
+                    games_started,
+                    &configure,
+                    &mut sessions,
+                    result_sender.clone(),
+                );
+                in_progress.insert(match_id);
+            } else {
+                send_standings(&mut waiting, bracket).await;
+            }
+        }
+    }
+
+    /// Plays a match between the two players in the background, sending how it ended back to the
+    /// tournament once it is over.
+    fn start_match<F>(
+        &self,
+        match_id: MatchId,
+        players: [Option<Player>; 2],
+        game_number: u64,
+        configure: &F,
+        sessions: &mut HashMap<u64, (u8, EventSender)>,
+        result_sender: Sender<(MatchId, Option<GameEnd>)>,
+    ) where
+        F: Fn(&mut Server<OnlineConnection>),
+    {
+        let players: Vec<Player> = players.into_iter().flatten().collect();
+        let player_sessions: Vec<(u8, u64)> = players
+            .iter()
+            .map(|player| (player.id, player.session_token))
+            .collect();
+        let mut server = self.server_builder.online(players, self.game.as_ref());
+        let server_channel = server.accept_reconnections(RECONNECTION_GRACE_PERIOD);
+        for (player_id, session_token) in player_sessions {
+            sessions.insert(session_token, (player_id, server_channel.clone()));
+        }
+        configure(&mut server);
+
+        self.active_games
+            .insert(game_number, self.game.id(), server_channel);
+        let active_games = self.active_games.clone();
+        let (round, index) = match_id;
+        let span = tracing::info_span!("match", id = game_number, round = round + 1, index);
+        tokio::spawn(
+            async move {
+                tracing::info!("Match started");
+                server.init().await;
+                active_games.remove(game_number);
+                let game_end = server.game_end();
+                tracing::info!(?game_end, "Match finished");
+                let _ = result_sender.send((match_id, game_end)).await;
+            }
+            .instrument(span),
+        );
+    }
+}
+
+/// The matches that both players have joined, which are ready to be played.
+fn ready_matches(waiting: &HashMap<MatchId, [Option<Player>; 2]>) -> Vec<MatchId> {
+    waiting
+        .iter()
+        .filter(|(_, sides)| sides.iter().all(Option::is_some))
+        .map(|(&match_id, _)| match_id)
+        .collect()
+}
+
+/// Sends the standings to every player waiting on their opponent. Players who can no longer be
+/// reached are found out once their match starts, so failing to send to them is ignored.
+async fn send_standings(waiting: &mut HashMap<MatchId, [Option<Player>; 2]>, bracket: &Bracket) {
+    let event = OutgoingEvent::TournamentStandings {
+        rounds: bracket.standings(),
+    };
+    for player in waiting.values_mut().flatten().flatten() {
+        let _ = player.connection.write_event(&event).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bracket_of(names: &[&str]) -> Bracket {
+        Bracket::new(names.iter().map(|name| name.parse().unwrap()).collect())
+    }
+
+    fn player(name: &str) -> BracketSlot {
+        BracketSlot::Player(String::from(name))
+    }
+
+    #[test]
+    fn top_seeds_only_meet_in_later_rounds() {
+        assert_eq!(seed_positions(2), [1, 2]);
+        assert_eq!(seed_positions(4), [1, 4, 2, 3]);
+        assert_eq!(seed_positions(8), [1, 8, 4, 5, 2, 7, 3, 6]);
+    }
+
+    #[test]
+    fn top_seeds_given_byes_through_first_round() {
+        let bracket = bracket_of(&["Ada", "Alan", "Grace"]);
+        let standings = bracket.standings();
+
+        assert_eq!(
+            standings[0],
+            [
+                BracketMatch {
+                    players: [player("Ada"), BracketSlot::Bye],
+                    winner: Some(0),
+                },
+                BracketMatch {
+                    players: [player("Alan"), player("Grace")],
+                    winner: None,
+                },
+            ]
+        );
+        assert_eq!(
+            standings[1][0].players,
+            [player("Ada"), BracketSlot::Undecided]
+        );
+    }
+
+    #[test]
+    fn winners_go_through_until_champion_found() {
+        let mut bracket = bracket_of(&["Ada", "Alan", "Grace", "Linus"]);
+        let [ada, alan, grace, linus] = [0, 1, 2, 3];
+
+        assert_eq!(bracket.next_match(ada), Some(((0, 0), 0)));
+        assert_eq!(bracket.next_match(linus), Some(((0, 0), 1)));
+        bracket.record_winner((0, 0), 1);
+        bracket.record_winner((0, 1), 0);
+
+        assert_eq!(bracket.next_match(ada), None);
+        assert_eq!(bracket.next_match(linus), Some(((1, 0), 0)));
+        assert_eq!(bracket.next_match(alan), Some(((1, 0), 1)));
+        assert_eq!(bracket.next_match(grace), None);
+        assert_eq!(bracket.champion(), None);
+
+        bracket.record_winner((1, 0), 1);
+        assert_eq!(bracket.champion(), Some(&"Alan".parse().unwrap()));
+        assert_eq!(bracket.next_match(alan), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bracket_needs_more_than_one_entrant() {
+        bracket_of(&["Ada"]);
+    }
+}
@@ -0,0 +1,134 @@
+use std::future;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, watch};
+use tokio::time::{self, Instant};
+
+/// Held by the host while the lobby waits on players to join, hearing whenever no one has joined
+/// for the wait timeout and able to stop the lobby waiting.
+pub struct WaitingHost {
+    timeouts: mpsc::Receiver<Duration>,
+    cancel: watch::Sender<bool>,
+}
+
+impl WaitingHost {
+    /// Waits for the lobby to go the wait timeout without anyone joining.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Duration)` containing how long it has been since anyone last joined.
+    /// - `None` once the lobby has stopped waiting, as every seat has been taken.
+    pub async fn timed_out(&mut self) -> Option<Duration> {
+        self.timeouts.recv().await
+    }
+
+    /// Stops the lobby waiting, so that the players already seated are sent away and no game is
+    /// set up.
+    pub fn cancel(self) {
+        let _ = self.cancel.send(true);
+    }
+}
+
+/// The lobby's side of a `WaitingHost`, timing how long it has waited since anyone last joined.
+pub(super) struct WaitTimer {
+    wait_timeout: Duration,
+    last_joined_at: Instant,
+    /// When to next tell the host that no one has joined.
+    deadline: Instant,
+    timeouts: mpsc::Sender<Duration>,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl WaitTimer {
+    /// Creates a timer along with the host's handle on it, starting from now.
+    pub(super) fn new(wait_timeout: Duration) -> (WaitTimer, WaitingHost) {
+        let (timeouts_sender, timeouts) = mpsc::channel(1);
+        let (cancel, cancelled) = watch::channel(false);
+        let now = Instant::now();
+        let timer = WaitTimer {
+            wait_timeout,
+            last_joined_at: now,
+            deadline: now + wait_timeout,
+            timeouts: timeouts_sender,
+            cancelled,
+        };
+
+        (timer, WaitingHost { timeouts, cancel })
+    }
+
+    /// Starts the wait over, as someone has just joined.
+    pub(super) fn restart(&mut self) {
+        self.last_joined_at = Instant::now();
+        self.deadline = self.last_joined_at + self.wait_timeout;
+    }
+
+    /// Waits until the wait timeout passes without anyone joining, telling the host once it has,
+    /// or until the host stops the lobby waiting. The host is told again each time the wait
+    /// timeout passes after that, for as long as no one joins.
+    pub(super) async fn run_out(&mut self) -> WaitOutcome {
+        let cancelled = &mut self.cancelled;
+        tokio::select! {
+            () = time::sleep_until(self.deadline) => {
+                self.deadline += self.wait_timeout;
+                // A host still deciding whether to keep waiting need not be told again
+                let _ = self.timeouts.try_send(self.last_joined_at.elapsed());
+
+                WaitOutcome::HostTold
+            }
+            () = async {
+                // A host who lets go of their handle without cancelling leaves the lobby waiting
+                if cancelled.wait_for(|&cancelled| cancelled).await.is_err() {
+                    future::pending::<()>().await
+                }
+            } => WaitOutcome::Cancelled,
+        }
+    }
+}
+
+/// What became of the lobby's wait for players.
+#[derive(Debug, PartialEq)]
+pub(super) enum WaitOutcome {
+    HostTold,
+    Cancelled,
+}
+
+/// Waits for the timer to run out, never completing if there is no timer.
+pub(super) async fn run_out(timer: &mut Option<WaitTimer>) -> WaitOutcome {
+    match timer {
+        Some(timer) => timer.run_out().await,
+        None => future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn host_told_each_time_timeout_passes_without_anyone_joining() {
+        let (timer, mut host) = WaitTimer::new(Duration::from_secs(60));
+        let mut timer = Some(timer);
+
+        assert_eq!(run_out(&mut timer).await, WaitOutcome::HostTold);
+        assert_eq!(host.timed_out().await, Some(Duration::from_secs(60)));
+
+        time::advance(Duration::from_secs(30)).await;
+        timer.as_mut().unwrap().restart();
+        assert_eq!(run_out(&mut timer).await, WaitOutcome::HostTold);
+        assert_eq!(host.timed_out().await, Some(Duration::from_secs(60)));
+        assert_eq!(run_out(&mut timer).await, WaitOutcome::HostTold);
+        assert_eq!(host.timed_out().await, Some(Duration::from_secs(120)));
+
+        drop(timer);
+        assert_eq!(host.timed_out().await, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn host_can_stop_the_wait() {
+        let (timer, host) = WaitTimer::new(Duration::from_secs(60));
+        let mut timer = Some(timer);
+
+        host.cancel();
+        assert_eq!(run_out(&mut timer).await, WaitOutcome::Cancelled);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::client::OutputStyle;
+
+/// The attribute holding a message's wording for terse output, where it differs.
+const TERSE_ATTRIBUTE: &str = "terse";
+
+/// A language the messages shown to the user are written in.
+///
+/// Messages are kept in a catalog for each locale, written in a subset of the Fluent syntax: one
+/// `key = value` message per line, optionally followed by indented `.attribute = value` lines,
+/// with arguments substituted for `{ $name }` placeables. Messages missing from a catalog fall
+/// back to English, so that a translation can be partial.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum Locale {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Locale {
+    /// Every supported locale, English first.
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    /// The language tag naming the locale, as given on the command line.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Spanish => "es",
+        }
+    }
+
+    /// The message with the given key, with the arguments substituted in.
+    pub fn message(self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let template = self
+            .catalog()
+            .get(key)
+            .or_else(|| Locale::English.catalog().get(key))
+            .map_or(key, String::as_str);
+
+        substitute(template, args)
+    }
+
+    /// The message with the given key worded for the output style, using its terse attribute
+    /// when there is one and terse output is in use.
+    pub fn styled_message(
+        self,
+        key: &str,
+        output_style: OutputStyle,
+        args: &[(&str, &dyn Display)],
+    ) -> String {
+        match output_style {
+            OutputStyle::Standard => self.message(key, args),
+            OutputStyle::Terse => {
+                let terse_key = format!("{}.{}", key, TERSE_ATTRIBUTE);
+                if self.has_message(&terse_key) {
+                    self.message(&terse_key, args)
+                } else {
+                    self.message(key, args)
+                }
+            }
+        }
+    }
+
+    fn has_message(self, key: &str) -> bool {
+        self.catalog().contains_key(key) || Locale::English.catalog().contains_key(key)
+    }
+
+    fn catalog(self) -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        static SPANISH: OnceLock<Catalog> = OnceLock::new();
+
+        match self {
+            Locale::English => ENGLISH.get_or_init(|| parse(include_str!("../locales/en.ftl"))),
+            Locale::Spanish => SPANISH.get_or_init(|| parse(include_str!("../locales/es.ftl"))),
+        }
+    }
+}
+
+impl FromStr for Locale {
+    type Err = UnsupportedLocale;
+
+    /// Reads a language tag such as `es` or a POSIX locale such as `es_ES.UTF-8`, going by the
+    /// language alone.
+    fn from_str(value: &str) -> Result<Locale, UnsupportedLocale> {
+        let language = value.split(['_', '-', '.', '@']).next().unwrap_or_default();
+
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(language))
+            .ok_or(UnsupportedLocale)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+#[error("Unsupported locale, expected one of: en, es.")]
+pub struct UnsupportedLocale;
+
+/// The messages of a locale, with each attribute kept under its message's key followed by a dot
+/// and the attribute's name.
+type Catalog = HashMap<String, String>;
+
+fn parse(source: &str) -> Catalog {
+    let mut catalog = Catalog::new();
+    let mut message_key = None;
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            panic!("Malformed line in message catalog: {}", line);
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        match key.strip_prefix('.') {
+            Some(attribute) => {
+                let message_key = message_key
+                    .as_ref()
+                    .unwrap_or_else(|| panic!("Attribute outside of a message: {}", line));
+                catalog.insert(format!("{}.{}", message_key, attribute), value.to_owned());
+            }
+            None => {
+                catalog.insert(key.to_owned(), value.to_owned());
+                message_key = Some(key.to_owned());
+            }
+        }
+    }
+
+    catalog
+}
+
+/// Replaces each `{ $name }` placeable in the template with the matching argument, leaving any
+/// without one as they are.
+fn substitute(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let placeable = &rest[start..=start + length];
+        let name = placeable[1..placeable.len() - 1]
+            .trim()
+            .trim_start_matches('$');
+
+        match args.iter().find(|(arg_name, _)| *arg_name == name) {
+            Some((_, value)) => output.push_str(&value.to_string()),
+            None => output.push_str(placeable),
+        }
+        rest = &rest[start + length + 1..];
+    }
+    output.push_str(rest);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeables(template: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}'))
+            .map(|(name, _)| name.trim())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn message_substitutes_arguments() {
+        assert_eq!(
+            Locale::English.message("opponent-disconnected", &[("seconds", &30)]),
+            "Your opponent has disconnected, waiting up to 30 seconds for them to reconnect."
+        );
+    }
+
+    #[test]
+    fn styled_message_uses_terse_attribute_when_terse() {
+        assert_eq!(
+            Locale::English.styled_message("game-draw", OutputStyle::Terse, &[]),
+            "Draw."
+        );
+        assert_eq!(
+            Locale::English.styled_message("game-draw", OutputStyle::Standard, &[]),
+            "Game over! There was a draw!"
+        );
+    }
+
+    #[test]
+    fn styled_message_without_terse_attribute_uses_message() {
+        assert_eq!(
+            Locale::English.styled_message("invalid-option", OutputStyle::Terse, &[]),
+            "That is not a valid option, please try again!"
+        );
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_key() {
+        assert_eq!(
+            Locale::Spanish.message("no-such-message", &[]),
+            "no-such-message"
+        );
+    }
+
+    #[test]
+    fn substitute_leaves_unknown_placeables() {
+        assert_eq!(
+            substitute("{ $known } and { $unknown }", &[("known", &1)]),
+            "1 and { $unknown }"
+        );
+    }
+
+    #[test]
+    fn parse_reads_messages_attributes_and_comments() {
+        let catalog = parse("# A comment\n\ngreeting = Hello { $name }!\n    .terse = Hi.\n");
+
+        assert_eq!(catalog["greeting"], "Hello { $name }!");
+        assert_eq!(catalog["greeting.terse"], "Hi.");
+        assert_eq!(catalog.len(), 2);
+    }
+
+    #[test]
+    fn locales_parse_from_tags_and_posix_names() {
+        assert_eq!("es".parse(), Ok(Locale::Spanish));
+        assert_eq!("es_ES.UTF-8".parse(), Ok(Locale::Spanish));
+        assert_eq!("en-GB".parse(), Ok(Locale::English));
+        assert_eq!("C".parse::<Locale>(), Err(UnsupportedLocale));
+    }
+
+    #[test]
+    fn every_translation_has_every_english_message_with_the_same_arguments() {
+        let english = Locale::English.catalog();
+
+        for locale in &Locale::ALL[1..] {
+            let catalog = locale.catalog();
+            for (key, template) in english {
+                let translation = catalog
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", locale.tag(), key));
+                assert_eq!(
+                    placeables(translation),
+                    placeables(template),
+                    "{} has different arguments for {}",
+                    locale.tag(),
+                    key
+                );
+            }
+        }
+    }
+}
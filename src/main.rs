@@ -1,163 +1,1689 @@
-use std::io;
-use std::net::Ipv4Addr;
+use std::fs::File;
+use std::io::{self, IsTerminal};
+use std::net::{Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use tokio::net::{TcpListener, TcpStream};
+use clap::{Parser, ValueEnum};
+use tokio::join;
+use tokio::net::{lookup_host, TcpListener, TcpStream};
+#[cfg(feature = "web")]
+use tokio::sync::oneshot;
+use tokio::task::JoinError;
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
 
-use crate::client::Client;
-use crate::connection::Connection;
-use crate::game::Game;
-use crate::lobby::Lobby;
-use crate::server::LocalConnection;
+use rust_game_server::admin::{self, ActiveGames};
+use rust_game_server::battleship::Battleship;
+use rust_game_server::blackjack::Blackjack;
+use rust_game_server::checkers::Checkers;
+use rust_game_server::client::{
+    self, Client, ClientBuilder, Frontend, OutputStyle, TerminalFrontend,
+};
+use rust_game_server::connection::{Connection, ProtocolTrace};
+use rust_game_server::dots_and_boxes::{DotsAndBoxes, GridConfig};
+use rust_game_server::game::{self, GameDescriptor};
+#[cfg(feature = "gui")]
+use rust_game_server::gui;
+use rust_game_server::hangman::Hangman;
+use rust_game_server::lobby::{
+    self, ConnectionOptions, JoinToken, Lobby, OpenGame, RateLimit, RetryPolicy, RoomChoice,
+    WaitingHost,
+};
+use rust_game_server::locale::Locale;
+use rust_game_server::mancala::Mancala;
+use rust_game_server::nim::Nim;
+use rust_game_server::othello::Othello;
+use rust_game_server::replay;
+use rust_game_server::rock_paper_scissors::RockPaperScissors;
+use rust_game_server::server::{
+    self, ClientConnectionType, FirstPlayer, InvalidMessagePolicy, LocalConnection,
+    OnlineConnection, PlayerMark, PlayerName, SaveError, SavedGame, Server, ServerGameMode,
+    SpectatorConfig, TimeoutAction, TurnTimer,
+};
+use rust_game_server::stats::{self, Exporter, SqliteStats, StatsStore};
+use rust_game_server::stress;
+use rust_game_server::tic_tac_toe::{BoardConfig, TicTacToe};
+use rust_game_server::tls::{self, HostCertificate};
+use rust_game_server::trivia::{Trivia, TriviaConfig};
+#[cfg(feature = "tui")]
+use rust_game_server::tui::Tui;
+#[cfg(feature = "web")]
+use rust_game_server::web;
 
-mod client;
-mod connection;
-mod game;
-mod lobby;
-mod server;
-mod tic_tac_toe;
+use crate::cli::{Cli, GameKind};
+
+mod cli;
 
 const DEFAULT_PORT: u16 = 22222;
+/// Enough for a joiner on a flaky network to ride out a brief outage, or a host still starting up.
+const DEFAULT_CONNECT_ATTEMPTS: u32 = 3;
+const EXHIBITION_MOVE_DELAY: Duration = Duration::from_secs(2);
+/// The code the process exits with when the game could not be set up, such as when a file it
+/// needs could not be opened.
+const SETUP_FAILED_EXIT_CODE: u8 = 1;
+const BIND_FAILED_EXIT_CODE: u8 = 8;
+const CONNECT_FAILED_EXIT_CODE: u8 = 9;
 
 #[tokio::main]
-async fn main() {
-    println!("Hello from Rust Game Server, welcome to Tic Tac Toe!");
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(error) = cli.validate() {
+        error.exit();
+    }
+    let output_style = if cli.terse {
+        OutputStyle::Terse
+    } else {
+        OutputStyle::Standard
+    };
+    #[cfg(feature = "tui")]
+    let tui = cli.tui;
+    #[cfg(not(feature = "tui"))]
+    let tui = false;
+    let locale = cli.locale.unwrap_or_default();
+    let settings = ClientSettings {
+        output_style,
+        locale,
+        screen_reader: cli.screen_reader,
+        // Colours are left out when asked, including through the NO_COLOR convention, for screen
+        // readers that would read out their escape codes, and when the output is not a terminal
+        // that would understand them
+        color: !cli.no_color
+            && !cli.screen_reader
+            && !tui
+            && std::env::var_os("NO_COLOR").is_none()
+            && io::stdout().is_terminal(),
+        // Written to stderr so that the trace can be kept apart from the game
+        protocol_trace: cli
+            .debug_protocol
+            .then(|| ProtocolTrace::new(Arc::new(Mutex::new(io::stderr())))),
+        #[cfg(feature = "tui")]
+        tui,
+    };
 
-    match get_game_mode() {
-        GameMode::Local => {
-            let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, DEFAULT_PORT))
-                .await
-                .unwrap();
-            let address = listener.local_addr().unwrap();
+    // Bots keep stdout for their report, so that it can be read by whatever is testing them
+    let bot = matches!(cli.command, Some(cli::Command::Bot { .. }));
+    if output_style == OutputStyle::Standard && !bot {
+        println!("{}", locale.message("welcome", &[]));
+    }
+
+    let game_mode = match cli.command {
+        Some(command) => command.into(),
+        None => get_game_mode(locale),
+    };
+
+    // Dedicated servers report failures in their log, along with everything else
+    let logged = matches!(game_mode, GameMode::Serve { .. });
+    match run(game_mode, &settings).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(failure) => {
+            if let Some(message) = failure.message(locale) {
+                match logged {
+                    true => tracing::error!("{}", message),
+                    false => eprintln!("{}", message),
+                }
+            }
+            failure.exit_code()
+        }
+    }
+}
+
+/// Carries out the game mode chosen by the user, until the game is over or cannot go on.
+async fn run(game_mode: GameMode, settings: &ClientSettings) -> Result<(), Failure> {
+    let locale = settings.locale;
+    match game_mode {
+        GameMode::Local {
+            game,
+            board_config,
+            trivia_config,
+        } => {
+            // Any free port will do, as only this process connects to it
+            let (listener, address) = bind(Ipv4Addr::LOCALHOST, 0).await?;
+            let client_game = make_game(game, board_config, &trivia_config);
 
             // Spawn the server thread
             let server_handle = tokio::spawn(async move {
-                // Wait for a client connection
-                let (stream_one, _) = listener.accept().await.unwrap();
+                // Wait for a client connection, which can only fail to arrive if the client
+                // could not connect
+                let Ok((stream_one, _)) = listener.accept().await else {
+                    return;
+                };
 
                 // Create connection
                 let connection = Connection::new(stream_one);
 
                 // Play the game
-                let mut server =
-                    server::Server::<LocalConnection>::new(connection, Game::TicTacToe);
+                let mut server = server::Server::<LocalConnection>::new(
+                    connection,
+                    &*make_game(game, board_config, &trivia_config),
+                );
                 server.init().await;
             });
 
             // Set up client connection
-            let stream = TcpStream::connect(address).await.unwrap();
-            let mut client = Client::<io::Stdout>::new_local(
-                Connection::new(stream),
-                io::BufReader::new(io::stdin()),
-                io::stdout(),
-                Game::TicTacToe,
-            );
-            client.play_game().await;
+            let stream = TcpStream::connect(address)
+                .await
+                .map_err(lobby::Error::Stream)?;
+            let (frontend, tui) = settings.open_frontend();
+            let client =
+                ClientBuilder::new().local(Connection::new(stream), frontend, &*client_game);
+            let played = settings.play(client, tui).await;
 
             // Wait for server thread to finish
-            server_handle.await.unwrap();
+            server_handle.await?;
+            played
         }
-        GameMode::OnlineHost => {
+        GameMode::OnlineHost {
+            port,
+            turn_timer,
+            spectator_config,
+            first_player,
+            mark,
+            name,
+            tls_certificate,
+            websocket,
+            rate_limit,
+            game,
+            board_config,
+            trivia_config,
+            stats_path,
+            replay_path,
+            rng_seed,
+            exporter,
+            hot_seat,
+            save_path,
+            bot_takeover,
+            wait_timeout,
+            rematch,
+            offer_games,
+            #[cfg(feature = "web")]
+            web_port,
+        } => {
+            let stats_store = match stats_path.as_deref().map(SqliteStats::open).transpose() {
+                Ok(stats_store) => stats_store,
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("stats-open-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+            let replay_file = match replay_path.map(File::create).transpose() {
+                Ok(replay_file) => replay_file,
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("replay-create-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+            let (tls_acceptor, tls_connector) = match tls_certificate
+                .as_ref()
+                .map(|certificate| set_up_host_tls(certificate, locale))
+                .transpose()
+            {
+                Ok(tls) => tls.unzip(),
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("tls-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+
+            let (listener, address) = bind(Ipv4Addr::UNSPECIFIED, port).await?;
+            let mut lobby = Lobby::new(listener);
+            let join_token = lobby.join_token();
+            let waiting_host =
+                wait_timeout.map(|wait_timeout| lobby.set_wait_timeout(wait_timeout));
+            // In the case that the supplied port was "0", a random port will be allocated
             println!(
-                "Do you wish to specify a port to bind to (the default is {}) y/N?",
-                DEFAULT_PORT
+                "{}",
+                locale.message(
+                    "host-details",
+                    &[("port", &address.port()), ("token", &join_token)]
+                )
             );
-            let port = loop {
-                match read_string().as_str() {
-                    "" | "n" | "no" => break DEFAULT_PORT,
-                    "y" | "yes" => {
-                        println!("Please provide the port:");
-                        match read_string().parse::<u16>() {
-                            Err(_) => println!("That is not a valid number, try again."),
-                            Ok(port) => break port,
-                        }
+
+            let client_game = make_game(game, board_config, &trivia_config);
+
+            #[cfg(feature = "web")]
+            let web_server = if let Some(web_port) = web_port {
+                let (web_listener, web_address) = bind(Ipv4Addr::UNSPECIFIED, web_port).await?;
+                println!(
+                    "{}",
+                    locale.message(
+                        "web-details",
+                        &[("port", &web_address.port()), ("token", &join_token)]
+                    )
+                );
+                // Browsers join through clients run here, which reach the lobby as any other player
+                let options = ConnectionOptions {
+                    tls_connector: tls_connector.clone(),
+                    websocket,
+                    ..ConnectionOptions::default()
+                };
+                let (stop, stopped) = oneshot::channel::<()>();
+                let lobby_access = web::LobbyAccess {
+                    address,
+                    join_token,
+                    options,
+                };
+                let web_server = tokio::spawn(web::serve(
+                    web_listener,
+                    lobby_access,
+                    Arc::from(make_game(game, board_config, &trivia_config)),
+                    locale,
+                    rate_limit,
+                    async {
+                        let _ = stopped.await;
+                    },
+                ));
+                Some((stop, web_server))
+            } else {
+                None
+            };
+
+            // Spawn the server thread
+            let server_handle = tokio::spawn(async move {
+                if let Some(tls_acceptor) = tls_acceptor {
+                    lobby.set_tls_acceptor(tls_acceptor);
+                }
+                lobby.set_websocket(websocket);
+                lobby.set_rate_limit(rate_limit);
+                lobby.set_game(make_game(game, board_config, &trivia_config));
+                if offer_games {
+                    lobby.offer_games(make_every_game(board_config, &trivia_config));
+                }
+                if hot_seat {
+                    let mut server = lobby.set_up_hot_seat_server().await;
+                    configure_server(
+                        &mut server,
+                        turn_timer,
+                        stats_store,
+                        replay_file,
+                        rng_seed,
+                        exporter,
+                    );
+                    server.set_first_player(first_player);
+                    server.init().await;
+                } else {
+                    let Some(mut server) = lobby.set_up_online_server().await else {
+                        return;
+                    };
+                    configure_server(
+                        &mut server,
+                        turn_timer,
+                        stats_store,
+                        replay_file,
+                        rng_seed,
+                        exporter,
+                    );
+                    if let Some(spectator_config) = spectator_config {
+                        server.set_spectator_config(spectator_config);
                     }
-                    _ => println!("That is not a valid option, please try again!"),
+                    server.set_first_player(first_player);
+                    if let Some(save_path) = save_path {
+                        server.set_save_path(save_path);
+                    }
+                    server.set_bot_takeover(bot_takeover);
+                    if rematch {
+                        server.offer_rematches(make_every_game(board_config, &trivia_config));
+                    }
+                    server.init().await;
+                }
+            });
+
+            let played = if hot_seat {
+                if settings.output_style == OutputStyle::Standard {
+                    println!("{}", locale.message("waiting-for-hot-seat", &[]));
+                }
+                Ok(())
+            } else {
+                // Set up client connection, choosing the host's game should players be offered a
+                // choice
+                let options = ConnectionOptions {
+                    tls_connector,
+                    websocket,
+                    mark,
+                    name,
+                    game: Some(String::from(client_game.id())),
+                    ..ConnectionOptions::default()
                 };
+                let (connection, session) =
+                    lobby::connect_to_game(address, join_token, options).await?;
+                let (frontend, tui) = settings.open_frontend();
+                if let Some(waiting_host) = waiting_host {
+                    if !wait_for_players(waiting_host, &*frontend, locale).await {
+                        // The interface is told it is no longer needed by its frontend being
+                        // dropped
+                        drop(frontend);
+                        if let Some(tui) = tui {
+                            let _ = tui.wait();
+                        }
+                        server_handle.await?;
+                        return Ok(());
+                    }
+                }
+                let client = ClientBuilder::new().online(
+                    connection,
+                    session,
+                    server::PLAYER_ONE_ID,
+                    frontend,
+                    &*client_game,
+                );
+                settings.play(client, tui).await
             };
 
-            let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
-                .await
-                .unwrap();
-            let address = listener.local_addr().unwrap();
-            // In the case that the supplied port was "0", a random port will be allocated
-            if port == 0 {
-                println!("People can join you on port {}!", address.port());
+            // Wait for server thread to finish
+            server_handle.await?;
+            #[cfg(feature = "web")]
+            if let Some((stop, web_server)) = web_server {
+                // Players who joined from their browser are still to be shown how the game ended
+                let _ = stop.send(());
+                let _ = web_server.await;
             }
+            played
+        }
+        GameMode::Resume {
+            save_path,
+            mark,
+            port,
+            tls_certificate,
+            websocket,
+        } => {
+            let saved_game = match File::open(&save_path)
+                .map_err(SaveError::from)
+                .and_then(SavedGame::read)
+            {
+                Ok(saved_game) => saved_game,
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("save-open-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+            let join_token = saved_game.join_token;
+            let restored_game = match saved_game.restore() {
+                Ok(restored_game) => restored_game,
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("save-open-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+            let game = restored_game.game();
+            let (tls_acceptor, tls_connector) = match tls_certificate
+                .as_ref()
+                .map(|certificate| set_up_host_tls(certificate, locale))
+                .transpose()
+            {
+                Ok(tls) => tls.unzip(),
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("tls-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+
+            let (listener, address) = bind(Ipv4Addr::UNSPECIFIED, port).await?;
+            let mut lobby = Lobby::new(listener);
+            if let Some(join_token) = join_token {
+                lobby.set_join_token(join_token);
+            }
+            let join_token = lobby.join_token();
+            println!(
+                "{}",
+                locale.message(
+                    "host-details",
+                    &[("port", &address.port()), ("token", &join_token)]
+                )
+            );
 
-            // Spawn the server thread
             let server_handle = tokio::spawn(async move {
-                let mut lobby = Lobby::new(listener);
-                let mut server = lobby.set_up_online_server().await;
+                if let Some(tls_acceptor) = tls_acceptor {
+                    lobby.set_tls_acceptor(tls_acceptor);
+                }
+                lobby.set_websocket(websocket);
+                let Some(mut server) = lobby.set_up_resumed_server(restored_game).await else {
+                    return;
+                };
+                server.set_save_path(save_path);
                 server.init().await;
             });
 
-            // Set up client connection
-            let connection = lobby::connect_to_game(address).await.unwrap();
-            let mut client = Client::<io::Stdout>::new_online(
+            let options = ConnectionOptions {
+                tls_connector,
+                websocket,
+                mark,
+                ..ConnectionOptions::default()
+            };
+            let (connection, session) =
+                lobby::connect_to_game(address, join_token, options).await?;
+            let (frontend, tui) = settings.open_frontend();
+            let client = ClientBuilder::new().online(
                 connection,
+                session,
                 server::PLAYER_ONE_ID,
-                io::BufReader::new(io::stdin()),
-                io::stdout(),
-                Game::TicTacToe,
+                frontend,
+                game,
+            );
+            let played = settings.play(client, tui).await;
+
+            server_handle.await?;
+            played
+        }
+        GameMode::Serve {
+            port,
+            turn_timer,
+            read_timeout,
+            invalid_message_policy,
+            tls_certificate,
+            websocket,
+            rate_limit,
+            game,
+            board_config,
+            trivia_config,
+            stats_path,
+            exporter,
+            tournament,
+            bot_takeover,
+            admin_socket,
+            log_level,
+            log_json,
+        } => {
+            init_logging(log_level, log_json);
+            // Opened once up front so that a database that cannot be used is reported straight away
+            if let Some(Err(error)) = stats_path.as_deref().map(SqliteStats::open) {
+                let message = locale.message("stats-open-failed", &[("error", &error)]);
+                return Err(Failure::Setup(message));
+            }
+            let tls_acceptor = match tls_certificate
+                .as_ref()
+                .map(|certificate| set_up_host_tls(certificate, locale))
+                .transpose()
+            {
+                Ok(tls) => tls.map(|(tls_acceptor, _)| tls_acceptor),
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("tls-failed", &[("error", &error)]),
+                    ))
+                }
+            };
+
+            let (listener, address) = bind(Ipv4Addr::UNSPECIFIED, port).await?;
+            let mut lobby = Lobby::new(listener);
+            println!(
+                "{}",
+                locale.message(
+                    "host-details",
+                    &[("port", &address.port()), ("token", &lobby.join_token())]
+                )
+            );
+
+            if let Some(tls_acceptor) = tls_acceptor {
+                lobby.set_tls_acceptor(tls_acceptor);
+            }
+            lobby.set_websocket(websocket);
+            lobby.set_rate_limit(rate_limit);
+            lobby.set_game(make_game(game, board_config, &trivia_config));
+            if let Some(admin_socket) = admin_socket {
+                if let Err(error) = start_admin_socket(&admin_socket, lobby.active_games()) {
+                    let message = locale.message("admin-socket-failed", &[("error", &error)]);
+                    return Err(Failure::Setup(message));
+                }
+            }
+            // Each game records to a connection of its own, as games are played at the same time
+            let configure = |server: &mut Server<OnlineConnection>| {
+                let stats_store = stats_path.as_deref().and_then(|path| {
+                    SqliteStats::open(path)
+                        .inspect_err(|error| {
+                            tracing::warn!(?error, "Failed to open the statistics database");
+                        })
+                        .ok()
+                });
+                configure_server(
+                    server,
+                    turn_timer,
+                    stats_store,
+                    None,
+                    None,
+                    exporter.clone(),
+                );
+                if let Some(read_timeout) = read_timeout {
+                    server.set_read_timeout(read_timeout);
+                }
+                server.set_invalid_message_policy(invalid_message_policy);
+                server.set_bot_takeover(bot_takeover);
+            };
+            match tournament {
+                Some(entrant_count) => lobby.run_tournament(entrant_count, configure).await,
+                None => lobby.serve(configure).await,
+            }
+
+            Ok(())
+        }
+        GameMode::OnlineJoin {
+            address,
+            join_token,
+            game,
+            tls_cert,
+            websocket,
+            mark,
+            name,
+            room,
+            connect_attempts,
+        } => {
+            let mut options = get_connection_options(tls_cert, websocket, locale)?;
+            options.mark = mark;
+            options.name = name;
+            options.room = room;
+            options.retry = RetryPolicy::attempts(connect_attempts);
+            // The board is sent by the host, so the size given here is never used
+            let game =
+                game.map(|game| make_game(game, BoardConfig::default(), &TriviaConfig::default()));
+            options.game = game.as_ref().map(|game| String::from(game.id()));
+            let game = game.unwrap_or_else(|| {
+                make_game(
+                    GameKind::TicTacToe,
+                    BoardConfig::default(),
+                    &TriviaConfig::default(),
+                )
+            });
+
+            join_online_game(settings, &address, join_token, &*game, options).await
+        }
+        GameMode::Browse {
+            address,
+            join_token,
+            game,
+            tls_cert,
+            websocket,
+            mark,
+            name,
+        } => {
+            let mut options = get_connection_options(tls_cert, websocket, locale)?;
+            options.mark = mark;
+            options.name = name;
+
+            let open_games = lobby::list_games(&address, join_token, &options).await?;
+            let new_game = make_game(game, BoardConfig::default(), &TriviaConfig::default());
+            let game: &dyn GameDescriptor = match choose_open_game(&open_games, locale) {
+                // The server says which game it hosts, so the one given is only for new games
+                Some(open_game) => match game::find_game(&open_game.game) {
+                    Some(found) => {
+                        options.room = RoomChoice::Join(open_game.room);
+                        found
+                    }
+                    None => {
+                        let message =
+                            locale.message("browse-unknown-game", &[("game", &open_game.game)]);
+                        return Err(Failure::Setup(message));
+                    }
+                },
+                None => {
+                    options.room = RoomChoice::New;
+                    &*new_game
+                }
+            };
+
+            join_online_game(settings, &address, join_token, game, options).await
+        }
+        GameMode::OnlineExhibition {
+            port,
+            game,
+            board_config,
+            trivia_config,
+        } => {
+            let (listener, address) = bind(Ipv4Addr::UNSPECIFIED, port).await?;
+            let mut lobby = Lobby::new(listener);
+            let join_token = lobby.join_token();
+            println!(
+                "{}",
+                locale.message(
+                    "exhibition-details",
+                    &[("port", &address.port()), ("token", &join_token)]
+                )
             );
-            client.play_game().await;
+
+            // Spawn the server thread, allowing any number of spectators
+            let server_handle = tokio::spawn(async move {
+                lobby.set_game(make_game(game, board_config, &trivia_config));
+                let Some(mut server) = lobby.set_up_online_server().await else {
+                    return;
+                };
+                server.set_spectator_config(SpectatorConfig {
+                    max_spectators: usize::MAX,
+                    notify_players: false,
+                });
+                server.init().await;
+            });
+
+            // Set up bot connections, one at a time so that each is given the expected ID
+            let mut bot_one =
+                connect_exhibition_bot(address, join_token, game, server::PLAYER_ONE_ID).await?;
+            let mut bot_two =
+                connect_exhibition_bot(address, join_token, game, server::PLAYER_TWO_ID).await?;
+            join!(bot_one.play_game(), bot_two.play_game());
 
             // Wait for server thread to finish
-            server_handle.await.unwrap();
-        }
-        GameMode::OnlineJoin => {
-            println!("Please enter the address of the game to join:");
-            let address = read_string();
-
-            match lobby::connect_to_game(address).await {
-                Ok(connection) => {
-                    let mut client = Client::<io::Stdout>::new_online(
-                        connection,
-                        server::PLAYER_TWO_ID,
-                        io::BufReader::new(io::stdin()),
+            server_handle.await?;
+            println!("{}", locale.message("exhibition-finished", &[]));
+
+            Ok(())
+        }
+        GameMode::OnlineSpectate {
+            address,
+            join_token,
+            game,
+            tls_cert,
+            websocket,
+        } => {
+            let options = get_connection_options(tls_cert, websocket, locale)?;
+
+            let connection = lobby::spectate_game(address, join_token, options).await?;
+            let (frontend, tui) = settings.open_frontend();
+            let client = ClientBuilder::new().spectator(
+                connection,
+                frontend,
+                &*make_game(game, BoardConfig::default(), &TriviaConfig::default()),
+            );
+            settings.play(client, tui).await
+        }
+        GameMode::Bot {
+            address,
+            join_token,
+            game,
+            script_path,
+            tls_cert,
+            websocket,
+        } => {
+            let game = make_game(game, BoardConfig::default(), &TriviaConfig::default());
+            let played = match get_connection_options(tls_cert, websocket, locale) {
+                Ok(mut options) => {
+                    // Bots play the game they were given, should the host offer a choice
+                    options.game = Some(String::from(game.id()));
+                    play_as_bot(settings, address, join_token, &*game, script_path, options).await
+                }
+                Err(failure) => Err(failure),
+            };
+
+            println!(
+                "{}",
+                serde_json::json!({
+                    "result": if played.is_ok() { "pass" } else { "fail" },
+                    "game": game.id(),
+                })
+            );
+            played
+        }
+        GameMode::Stress {
+            address,
+            join_token,
+            games,
+            game,
+            tls_cert,
+            websocket,
+        } => {
+            let mut options = get_connection_options(tls_cert, websocket, locale)?;
+            let game: Rc<dyn GameDescriptor> = Rc::from(make_game(
+                game,
+                BoardConfig::default(),
+                &TriviaConfig::default(),
+            ));
+            options.game = Some(String::from(game.id()));
+            let address = match lookup_host(&address).await.map(|mut found| found.next()) {
+                Ok(Some(address)) => address,
+                Ok(None) => {
+                    return Err(Failure::Setup(
+                        locale.message("server-address-missing", &[("address", &address)]),
+                    ));
+                }
+                Err(error) => {
+                    return Err(Failure::Setup(
+                        locale.message("server-lookup-failed", &[("error", &error)]),
+                    ));
+                }
+            };
+
+            print!(
+                "{}",
+                stress::run(address, join_token, options, game, games).await
+            );
+
+            Ok(())
+        }
+        GameMode::Stats { stats_path } => print_stats(&stats_path, locale),
+        GameMode::Ratings { stats_path } => print_ratings(&stats_path, locale),
+        GameMode::Replay { replay_path } => {
+            let result = match File::open(replay_path) {
+                Ok(file) => {
+                    replay::play_replay(
+                        io::BufReader::new(file),
                         io::stdout(),
-                        Game::TicTacToe,
-                    );
-                    client.play_game().await;
+                        settings.output_style,
+                        settings.color,
+                        settings.locale,
+                        settings.screen_reader,
+                    )
+                    .await
                 }
-                Err(_) => eprintln!("Error connecting to game. Aborting."),
+                Err(error) => Err(error.into()),
+            };
+            result.map_err(|error| {
+                Failure::Setup(locale.message("replay-play-failed", &[("error", &error)]))
+            })
+        }
+        // The window has to be run on the main thread on some platforms, which this is
+        #[cfg(feature = "gui")]
+        GameMode::Gui => tokio::task::block_in_place(|| gui::run(locale))
+            .map_err(|error| Failure::Setup(locale.message("gui-failed", &[("error", &error)]))),
+    }
+}
+
+/// How the user's own client is set up, whichever game mode it is playing in.
+struct ClientSettings {
+    output_style: OutputStyle,
+    color: bool,
+    locale: Locale,
+    screen_reader: bool,
+    protocol_trace: Option<ProtocolTrace>,
+    #[cfg(feature = "tui")]
+    tui: bool,
+}
+
+/// Stands in for the full-screen interface when built without it, so that there is never one.
+#[cfg(not(feature = "tui"))]
+enum Tui {}
+
+#[cfg(not(feature = "tui"))]
+impl Tui {
+    fn wait(self) -> io::Result<()> {
+        match self {}
+    }
+}
+
+impl ClientSettings {
+    /// Gives the frontend the user plays through, taking over the terminal with the full-screen
+    /// interface when asked to.
+    fn open_frontend(&self) -> (Box<dyn Frontend>, Option<Tui>) {
+        #[cfg(feature = "tui")]
+        if self.tui {
+            match Tui::start(self.locale) {
+                Ok((tui, frontend)) => return (Box::new(frontend), Some(tui)),
+                Err(error) => eprintln!(
+                    "{}",
+                    self.locale
+                        .message("tui-start-failed", &[("error", &error)])
+                ),
             }
         }
+
+        let output = Arc::new(Mutex::new(io::stdout()));
+        let frontend = TerminalFrontend::new(io::BufReader::new(io::stdin()), output);
+        (Box::new(frontend), None)
+    }
+
+    /// Plays the game through the client, failing if it ended early.
+    async fn play(&self, mut client: Client<'_>, tui: Option<Tui>) -> Result<(), Failure> {
+        client.set_output_style(self.output_style);
+        client.set_color(self.color);
+        client.set_locale(self.locale);
+        client.set_screen_reader(self.screen_reader);
+        if let Some(trace) = &self.protocol_trace {
+            client.set_protocol_trace(trace.clone());
+        }
+        client.play_game().await;
+        let exit_code = client.exit_code();
+
+        // The interface is told the game is over by the client, and its frontend, being dropped
+        drop(client);
+        if let Some(tui) = tui {
+            if let Err(error) = tui.wait() {
+                eprintln!(
+                    "{}",
+                    self.locale.message("tui-failed", &[("error", &error)])
+                );
+            }
+        }
+
+        match exit_code {
+            0 => Ok(()),
+            exit_code => Err(Failure::Aborted { exit_code }),
+        }
+    }
+}
+
+/// Why the program could not carry out the game mode it was given, each kind of failure exiting
+/// with a code of its own so that scripts can react to it.
+#[derive(Debug)]
+enum Failure {
+    /// The port could not be listened on, such as when another program already is.
+    Bind { port: u16, error: io::Error },
+    /// The game could not be reached, or refused to let the player join.
+    Connect(Box<lobby::Error>),
+    /// The game was cut short partway through, with the code the client gave for why. The user has
+    /// already been told what happened.
+    Aborted { exit_code: i32 },
+    /// Anything else stopping the game from being set up, with the message telling the user why.
+    Setup(String),
+}
+
+impl Failure {
+    /// What to tell the user about the failure, or `None` if they have already been told.
+    fn message(&self, locale: Locale) -> Option<String> {
+        match self {
+            Failure::Bind { port, error } if error.kind() == io::ErrorKind::AddrInUse => {
+                Some(locale.message("port-in-use", &[("port", port)]))
+            }
+            Failure::Bind { port, error } => {
+                Some(locale.message("bind-failed", &[("port", port), ("error", error)]))
+            }
+            Failure::Connect(error) => Some(locale.message("connect-failed", &[("error", error)])),
+            Failure::Aborted { .. } => None,
+            Failure::Setup(message) => Some(message.clone()),
+        }
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        match self {
+            Failure::Bind { .. } => ExitCode::from(BIND_FAILED_EXIT_CODE),
+            Failure::Connect(_) => ExitCode::from(CONNECT_FAILED_EXIT_CODE),
+            Failure::Aborted { exit_code } => {
+                ExitCode::from(u8::try_from(*exit_code).unwrap_or(SETUP_FAILED_EXIT_CODE))
+            }
+            Failure::Setup(_) => ExitCode::from(SETUP_FAILED_EXIT_CODE),
+        }
+    }
+}
+
+impl From<lobby::Error> for Failure {
+    fn from(error: lobby::Error) -> Failure {
+        Failure::Connect(Box::new(error))
+    }
+}
+
+impl From<JoinError> for Failure {
+    /// The server's task only fails by panicking, which has already been reported as it did.
+    fn from(_: JoinError) -> Failure {
+        Failure::Aborted {
+            exit_code: client::UNRECOVERABLE_EXIT_CODE,
+        }
+    }
+}
+
+/// Listens for connections on the port, "0" picking any free port.
+///
+/// # Returns
+///
+/// The listener along with the address it is listening on, which gives the port picked.
+async fn bind(ip: Ipv4Addr, port: u16) -> Result<(TcpListener, SocketAddr), Failure> {
+    let bound = TcpListener::bind((ip, port))
+        .await
+        .and_then(|listener| Ok((listener.local_addr()?, listener)));
+    match bound {
+        Ok((address, listener)) => Ok((listener, address)),
+        Err(error) => Err(Failure::Bind { port, error }),
     }
 }
 
 enum GameMode {
-    Local,
-    OnlineHost,
-    OnlineJoin,
+    Local {
+        game: GameKind,
+        board_config: BoardConfig,
+        trivia_config: TriviaConfig,
+    },
+    OnlineHost {
+        port: u16,
+        turn_timer: Option<TurnTimer>,
+        spectator_config: Option<SpectatorConfig>,
+        first_player: FirstPlayer,
+        mark: Option<PlayerMark>,
+        name: Option<PlayerName>,
+        tls_certificate: Option<HostCertificate>,
+        websocket: bool,
+        rate_limit: RateLimit,
+        game: GameKind,
+        board_config: BoardConfig,
+        trivia_config: TriviaConfig,
+        stats_path: Option<PathBuf>,
+        replay_path: Option<PathBuf>,
+        /// The seed to draw the game's chance from, rather than one chosen at random.
+        rng_seed: Option<u64>,
+        exporter: Option<Exporter>,
+        hot_seat: bool,
+        save_path: Option<PathBuf>,
+        bot_takeover: bool,
+        /// How long to wait for a player to join before asking the host whether to keep waiting.
+        wait_timeout: Option<Duration>,
+        rematch: bool,
+        /// Whether the player joining may choose which game is played.
+        offer_games: bool,
+        /// The port to serve the web page for joining from a browser on, if any.
+        #[cfg(feature = "web")]
+        web_port: Option<u16>,
+    },
+    Resume {
+        save_path: PathBuf,
+        mark: Option<PlayerMark>,
+        port: u16,
+        tls_certificate: Option<HostCertificate>,
+        websocket: bool,
+    },
+    Serve {
+        port: u16,
+        turn_timer: Option<TurnTimer>,
+        read_timeout: Option<Duration>,
+        invalid_message_policy: InvalidMessagePolicy,
+        tls_certificate: Option<HostCertificate>,
+        websocket: bool,
+        rate_limit: RateLimit,
+        game: GameKind,
+        board_config: BoardConfig,
+        trivia_config: TriviaConfig,
+        stats_path: Option<PathBuf>,
+        exporter: Option<Exporter>,
+        /// The number of players to run a tournament between, rather than hosting games for
+        /// anyone who connects.
+        tournament: Option<usize>,
+        bot_takeover: bool,
+        admin_socket: Option<PathBuf>,
+        log_level: LevelFilter,
+        log_json: bool,
+    },
+    OnlineJoin {
+        address: String,
+        join_token: JoinToken,
+        /// The game to choose, should the host offer a choice of games.
+        game: Option<GameKind>,
+        tls_cert: Option<PathBuf>,
+        websocket: bool,
+        mark: Option<PlayerMark>,
+        name: Option<PlayerName>,
+        room: RoomChoice,
+        /// The most attempts to make at reaching the game, backing off between them.
+        connect_attempts: u32,
+    },
+    Browse {
+        address: String,
+        join_token: JoinToken,
+        game: GameKind,
+        tls_cert: Option<PathBuf>,
+        websocket: bool,
+        mark: Option<PlayerMark>,
+        name: Option<PlayerName>,
+    },
+    OnlineSpectate {
+        address: String,
+        join_token: JoinToken,
+        game: GameKind,
+        tls_cert: Option<PathBuf>,
+        websocket: bool,
+    },
+    OnlineExhibition {
+        port: u16,
+        game: GameKind,
+        board_config: BoardConfig,
+        trivia_config: TriviaConfig,
+    },
+    Bot {
+        address: String,
+        join_token: JoinToken,
+        game: GameKind,
+        script_path: Option<PathBuf>,
+        tls_cert: Option<PathBuf>,
+        websocket: bool,
+    },
+    Stress {
+        address: String,
+        join_token: JoinToken,
+        games: usize,
+        game: GameKind,
+        tls_cert: Option<PathBuf>,
+        websocket: bool,
+    },
+    Stats {
+        stats_path: PathBuf,
+    },
+    Ratings {
+        stats_path: PathBuf,
+    },
+    Replay {
+        replay_path: PathBuf,
+    },
+    #[cfg(feature = "gui")]
+    Gui,
 }
 
-fn get_game_mode() -> GameMode {
+fn get_game_mode(locale: Locale) -> GameMode {
     loop {
-        println!("Please select your game mode; local or online.");
+        println!("{}", locale.message("mode-prompt", &[]));
         match read_string().as_str() {
             "local" => {
-                return GameMode::Local;
+                return GameMode::Local {
+                    game: GameKind::TicTacToe,
+                    board_config: BoardConfig::default(),
+                    trivia_config: TriviaConfig::default(),
+                };
             }
             "online" => {
-                println!("Do you want to host or join a game?");
+                println!("{}", locale.message("online-mode-prompt", &[]));
                 match read_string().as_str() {
-                    "host" => return GameMode::OnlineHost,
-                    "join" => return GameMode::OnlineJoin,
+                    "host" => {
+                        return GameMode::OnlineHost {
+                            port: get_port(locale),
+                            turn_timer: get_turn_timer(locale),
+                            spectator_config: get_spectator_config(locale),
+                            first_player: get_first_player(locale),
+                            mark: None,
+                            name: None,
+                            tls_certificate: None,
+                            websocket: false,
+                            rate_limit: RateLimit::default(),
+                            game: GameKind::TicTacToe,
+                            board_config: BoardConfig::default(),
+                            trivia_config: TriviaConfig::default(),
+                            stats_path: None,
+                            replay_path: None,
+                            rng_seed: None,
+                            exporter: None,
+                            hot_seat: false,
+                            save_path: None,
+                            bot_takeover: false,
+                            wait_timeout: None,
+                            rematch: false,
+                            offer_games: false,
+                            #[cfg(feature = "web")]
+                            web_port: None,
+                        }
+                    }
+                    "join" => {
+                        println!("{}", locale.message("join-address-prompt", &[]));
+                        return GameMode::OnlineJoin {
+                            address: read_string(),
+                            join_token: get_join_token(locale),
+                            game: None,
+                            tls_cert: None,
+                            websocket: false,
+                            mark: None,
+                            name: None,
+                            room: RoomChoice::Any,
+                            connect_attempts: DEFAULT_CONNECT_ATTEMPTS,
+                        };
+                    }
+                    "spectate" => {
+                        println!("{}", locale.message("spectate-address-prompt", &[]));
+                        return GameMode::OnlineSpectate {
+                            address: read_string(),
+                            join_token: get_join_token(locale),
+                            game: GameKind::TicTacToe,
+                            tls_cert: None,
+                            websocket: false,
+                        };
+                    }
+                    "exhibition" => {
+                        return GameMode::OnlineExhibition {
+                            port: get_port(locale),
+                            game: GameKind::TicTacToe,
+                            board_config: BoardConfig::default(),
+                            trivia_config: TriviaConfig::default(),
+                        }
+                    }
                     _ => {
-                        println!("That is not a valid option, please try again!");
+                        println!("{}", locale.message("invalid-option", &[]));
                         continue;
                     }
                 }
             }
             _ => {
-                println!("That is not a valid option, please try again!");
+                println!("{}", locale.message("invalid-option", &[]));
                 continue;
             }
         }
     }
 }
 
+/// Joins the online game at the address, playing it once the host has seated the user. Should the
+/// host offer a choice of games that the options do not make, the user is asked to choose one
+/// before joining again. The host tells the client which game is played as it begins, so `game`
+/// need only be a guess.
+async fn join_online_game(
+    settings: &ClientSettings,
+    address: &str,
+    join_token: JoinToken,
+    game: &dyn GameDescriptor,
+    mut options: ConnectionOptions,
+) -> Result<(), Failure> {
+    let joined = loop {
+        match lobby::connect_to_game(address, join_token, options.clone()).await {
+            Err(lobby::Error::ChooseGame(games)) => {
+                options.game = Some(choose_game(&games, settings.locale));
+            }
+            joined => break joined,
+        }
+    };
+
+    let (connection, session) = joined?;
+    let (frontend, tui) = settings.open_frontend();
+    let client = ClientBuilder::new().joined(connection, session, frontend, game);
+    settings.play(client, tui).await
+}
+
+/// Lists the games waiting for players as a menu, returning the one the user chooses or `None`
+/// if they would rather open a game of their own.
+fn choose_open_game(open_games: &[OpenGame], locale: Locale) -> Option<&OpenGame> {
+    if open_games.is_empty() {
+        println!("{}", locale.message("browse-empty", &[]));
+    }
+    for (index, open_game) in open_games.iter().enumerate() {
+        let host = match &open_game.host {
+            Some(name) => name.to_string(),
+            None => locale.message("browse-unnamed-host", &[]),
+        };
+        println!(
+            "{}",
+            locale.message(
+                "browse-game",
+                &[
+                    ("number", &(index + 1)),
+                    ("room", &open_game.room),
+                    ("game", &open_game.game),
+                    ("host", &host),
+                    ("count", &open_game.players_needed),
+                ]
+            )
+        );
+    }
+
+    loop {
+        println!("{}", locale.message("browse-prompt", &[]));
+        let choice = read_string();
+        if choice == "new" {
+            return None;
+        }
+        match choice.parse::<usize>() {
+            Ok(number) if (1..=open_games.len()).contains(&number) => {
+                return Some(&open_games[number - 1])
+            }
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        }
+    }
+}
+
+/// Lists the games offered by the host as a menu, returning the ID of the one the user chooses.
+fn choose_game(games: &[String], locale: Locale) -> String {
+    println!("{}", locale.message("game-offer", &[]));
+    for (index, game) in games.iter().enumerate() {
+        println!(
+            "{}",
+            locale.message(
+                "game-offer-entry",
+                &[("number", &(index + 1)), ("game", game)]
+            )
+        );
+    }
+
+    loop {
+        println!("{}", locale.message("game-offer-prompt", &[]));
+        match read_string().parse::<usize>() {
+            Ok(number) if (1..=games.len()).contains(&number) => return games[number - 1].clone(),
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        }
+    }
+}
+
+/// Creates every game, for the players to choose between, on the board given for Tic Tac Toe and
+/// with the questions given for Trivia.
+fn make_every_game(
+    board_config: BoardConfig,
+    trivia_config: &TriviaConfig,
+) -> Vec<Box<dyn GameDescriptor>> {
+    GameKind::value_variants()
+        .iter()
+        .map(|&game| make_game(game, board_config, trivia_config))
+        .collect()
+}
+
+/// Creates the game chosen by the user, on the board given when playing Tic Tac Toe or sized to it
+/// when playing Dots and Boxes, and with the questions given when playing Trivia.
+fn make_game(
+    game: GameKind,
+    board_config: BoardConfig,
+    trivia_config: &TriviaConfig,
+) -> Box<dyn GameDescriptor> {
+    match game {
+        GameKind::TicTacToe => Box::new(TicTacToe::new(board_config)),
+        GameKind::Checkers => Box::new(Checkers),
+        GameKind::Battleship => Box::new(Battleship),
+        GameKind::Othello => Box::new(Othello),
+        GameKind::RockPaperScissors => Box::new(RockPaperScissors),
+        GameKind::Mancala => Box::new(Mancala),
+        GameKind::Nim => Box::new(Nim),
+        GameKind::DotsAndBoxes => Box::new(DotsAndBoxes::new(GridConfig {
+            size: board_config.size,
+        })),
+        GameKind::Hangman => Box::new(Hangman),
+        GameKind::Trivia => Box::new(Trivia::new(trivia_config.clone())),
+        GameKind::Blackjack => Box::new(Blackjack),
+    }
+}
+
+/// Applies the host's choices that are common to every kind of server.
+fn configure_server<C>(
+    server: &mut Server<C>,
+    turn_timer: Option<TurnTimer>,
+    stats_store: Option<SqliteStats>,
+    replay_file: Option<File>,
+    rng_seed: Option<u64>,
+    exporter: Option<Exporter>,
+) where
+    C: ClientConnectionType,
+    Server<C>: ServerGameMode,
+{
+    if let Some(turn_timer) = turn_timer {
+        server.set_turn_timer(turn_timer);
+    }
+    if let Some(stats_store) = stats_store {
+        server.set_stats_store(stats_store);
+    }
+    if let Some(replay_file) = replay_file {
+        server.set_replay_writer(replay_file);
+    }
+    if let Some(rng_seed) = rng_seed {
+        server.set_rng_seed(rng_seed);
+    }
+    if let Some(exporter) = exporter {
+        server.set_result_exporter(exporter);
+    }
+}
+
+/// Logs to stderr at the given level, unless the RUST_LOG variable says otherwise.
+fn init_logging(log_level: LevelFilter, json: bool) {
+    let filter = EnvFilter::builder()
+        .with_default_directive(log_level.into())
+        .from_env_lossy();
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr);
+
+    if json {
+        subscriber.json().init();
+    } else {
+        subscriber.init();
+    }
+}
+
+/// Listens for the server operator's commands at the path, in the background.
+#[cfg(unix)]
+fn start_admin_socket(path: &Path, active_games: ActiveGames) -> io::Result<()> {
+    let listener = admin::bind(path)?;
+    tokio::spawn(admin::run(listener, active_games));
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn start_admin_socket(_path: &Path, _active_games: ActiveGames) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "admin sockets are only available on Unix",
+    ))
+}
+
+/// Creates the acceptor used by the lobby, along with a connector for the host's own client that
+/// trusts the certificate being presented.
+fn set_up_host_tls(
+    certificate: &HostCertificate,
+    locale: Locale,
+) -> Result<(TlsAcceptor, TlsConnector), tls::Error> {
+    let tls_acceptor = tls::acceptor(certificate)?;
+    if let HostCertificate::SelfSigned = certificate {
+        println!(
+            "{}",
+            locale.message(
+                "certificate-saved",
+                &[("path", &certificate.cert_path().display())]
+            )
+        );
+    }
+
+    Ok((tls_acceptor, tls::connector(certificate.cert_path())?))
+}
+
+/// Builds the options for connecting to a game, trusting the host's certificate if one was
+/// provided.
+///
+/// Fails if the certificate could not be loaded.
+fn get_connection_options(
+    cert_path: Option<PathBuf>,
+    websocket: bool,
+    locale: Locale,
+) -> Result<ConnectionOptions, Failure> {
+    match cert_path.as_deref().map(tls::connector).transpose() {
+        Ok(tls_connector) => Ok(ConnectionOptions {
+            tls_connector,
+            websocket,
+            ..ConnectionOptions::default()
+        }),
+        Err(error) => Err(Failure::Setup(
+            locale.message("tls-failed", &[("error", &error)]),
+        )),
+    }
+}
+
+async fn connect_exhibition_bot(
+    address: SocketAddr,
+    join_token: JoinToken,
+    game: GameKind,
+    player_id: u8,
+) -> Result<Client<'static>, Failure> {
+    let (connection, session) =
+        lobby::connect_to_game(address, join_token, ConnectionOptions::default()).await?;
+    Ok(Client::new_bot(
+        connection,
+        session,
+        player_id,
+        EXHIBITION_MOVE_DELAY,
+        io::sink(),
+        &*make_game(game, BoardConfig::default(), &TriviaConfig::default()),
+    ))
+}
+
+/// Joins the game as a bot writing the game to stderr, entering the moves in the script if one is
+/// given or otherwise choosing its own.
+///
+/// Fails unless the game was played to its end.
+async fn play_as_bot(
+    settings: &ClientSettings,
+    address: String,
+    join_token: JoinToken,
+    game: &dyn GameDescriptor,
+    script_path: Option<PathBuf>,
+    options: ConnectionOptions,
+) -> Result<(), Failure> {
+    let script = match script_path.map(File::open).transpose() {
+        Ok(script) => script.map(io::BufReader::new),
+        Err(error) => {
+            let message = settings
+                .locale
+                .message("script-open-failed", &[("error", &error)]);
+            return Err(Failure::Setup(message));
+        }
+    };
+    let (connection, session) = lobby::connect_to_game(address, join_token, options).await?;
+
+    let player_id = if session.seats.contains(server::PLAYER_ONE_ID) {
+        server::PLAYER_ONE_ID
+    } else {
+        server::PLAYER_TWO_ID
+    };
+    let mut client = match script {
+        Some(script) if session.seats == server::BOTH_SEATS => {
+            Client::new_local(connection, script, io::stderr(), game)
+        }
+        Some(script) => {
+            Client::new_online(connection, session, player_id, script, io::stderr(), game)
+        }
+        None if session.seats == server::BOTH_SEATS => {
+            let message = settings.locale.message("bot-needs-script", &[]);
+            return Err(Failure::Setup(message));
+        }
+        None => Client::new_bot(
+            connection,
+            session,
+            player_id,
+            Duration::ZERO,
+            io::stderr(),
+            game,
+        ),
+    };
+    client.set_output_style(settings.output_style);
+    client.set_locale(settings.locale);
+    client.set_screen_reader(settings.screen_reader);
+    if let Some(trace) = &settings.protocol_trace {
+        client.set_protocol_trace(trace.clone());
+    }
+    client.play_game().await;
+
+    match client.is_game_over() {
+        true => Ok(()),
+        // A bot's game ending early is a failure whatever the reason, even one the client
+        // gave no code of its own for
+        false => Err(Failure::Aborted {
+            exit_code: match client.exit_code() {
+                0 => client::UNRECOVERABLE_EXIT_CODE,
+                exit_code => exit_code,
+            },
+        }),
+    }
+}
+
+/// Prints the host's record in each game played while recording to the statistics database.
+fn print_stats(stats_path: &Path, locale: Locale) -> Result<(), Failure> {
+    let games = match SqliteStats::open(stats_path).and_then(|stats| stats.get_games()) {
+        Ok(games) => games,
+        Err(error) => {
+            return Err(Failure::Setup(
+                locale.message("stats-read-failed", &[("error", &error)]),
+            ));
+        }
+    };
+
+    if games.is_empty() {
+        println!("{}", locale.message("stats-empty", &[]));
+        return Ok(());
+    }
+
+    for (game, record) in stats::get_records_by_game(&games, server::PLAYER_ONE_ID) {
+        let think_time = format!("{:.1}", record.think_time.average().as_secs_f64());
+        println!(
+            "{}",
+            locale.message(
+                "stats-record",
+                &[
+                    ("game", &game),
+                    ("played", &record.played()),
+                    ("won", &record.won),
+                    ("lost", &record.lost),
+                    ("drawn", &record.drawn),
+                    ("seconds", &record.time_played.as_secs()),
+                    ("think_time", &think_time),
+                ]
+            )
+        );
+    }
+
+    Ok(())
+}
+
+fn print_ratings(stats_path: &Path, locale: Locale) -> Result<(), Failure> {
+    let ratings = match SqliteStats::open(stats_path).and_then(|stats| stats.get_ratings()) {
+        Ok(ratings) => ratings,
+        Err(error) => {
+            return Err(Failure::Setup(
+                locale.message("stats-read-failed", &[("error", &error)]),
+            ));
+        }
+    };
+
+    if ratings.is_empty() {
+        println!("{}", locale.message("ratings-empty", &[]));
+        return Ok(());
+    }
+
+    for ratings in ratings.chunk_by(|a, b| a.game == b.game) {
+        println!(
+            "{}",
+            locale.message("ratings-game", &[("game", &ratings[0].game)])
+        );
+        for (rank, rating) in ratings.iter().enumerate() {
+            println!(
+                "{}",
+                locale.message(
+                    "ratings-entry",
+                    &[
+                        ("rank", &(rank + 1)),
+                        ("name", &rating.name),
+                        ("rating", &rating.rating),
+                        ("games", &rating.games),
+                    ]
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn get_port(locale: Locale) -> u16 {
+    println!(
+        "{}",
+        locale.message("port-question", &[("port", &DEFAULT_PORT)])
+    );
+    loop {
+        match read_string().as_str() {
+            "" | "n" | "no" => break DEFAULT_PORT,
+            "y" | "yes" => {
+                println!("{}", locale.message("port-prompt", &[]));
+                match read_string().parse::<u16>() {
+                    Err(_) => println!("{}", locale.message("invalid-number", &[])),
+                    Ok(port) => break port,
+                }
+            }
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        };
+    }
+}
+
+fn get_join_token(locale: Locale) -> JoinToken {
+    println!("{}", locale.message("join-token-prompt", &[]));
+    loop {
+        match read_string().parse() {
+            Err(error) => println!(
+                "{}",
+                locale.message("join-token-invalid", &[("error", &error)])
+            ),
+            Ok(join_token) => break join_token,
+        }
+    }
+}
+
+fn get_turn_timer(locale: Locale) -> Option<TurnTimer> {
+    println!("{}", locale.message("turn-limit-question", &[]));
+    loop {
+        match read_string().as_str() {
+            "" | "n" | "no" => return None,
+            "y" | "yes" => break,
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        };
+    }
+
+    println!("{}", locale.message("turn-limit-prompt", &[]));
+    let duration = loop {
+        match read_string().parse::<u64>() {
+            Ok(seconds) if seconds > 0 => break Duration::from_secs(seconds),
+            _ => println!("{}", locale.message("invalid-number", &[])),
+        }
+    };
+
+    println!("{}", locale.message("timeout-action-prompt", &[]));
+    let action = loop {
+        match read_string().as_str() {
+            "forfeit" => break TimeoutAction::Forfeit,
+            "skip" => break TimeoutAction::SkipTurn,
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        }
+    };
+
+    Some(TurnTimer {
+        duration,
+        action,
+        handicap: None,
+    })
+}
+
+fn get_first_player(locale: Locale) -> FirstPlayer {
+    println!("{}", locale.message("first-move-prompt", &[]));
+    loop {
+        match read_string().as_str() {
+            "" | "host" => return FirstPlayer::PlayerOne,
+            "joiner" => return FirstPlayer::PlayerTwo,
+            "random" => return FirstPlayer::Random,
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        }
+    }
+}
+
+fn get_spectator_config(locale: Locale) -> Option<SpectatorConfig> {
+    println!("{}", locale.message("spectators-question", &[]));
+    loop {
+        match read_string().as_str() {
+            "" | "n" | "no" => return None,
+            "y" | "yes" => break,
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        };
+    }
+
+    println!("{}", locale.message("max-spectators-prompt", &[]));
+    let max_spectators = loop {
+        match read_string().parse::<usize>() {
+            Ok(max_spectators) if max_spectators > 0 => break max_spectators,
+            _ => println!("{}", locale.message("invalid-number", &[])),
+        }
+    };
+
+    println!("{}", locale.message("notify-players-question", &[]));
+    let notify_players = loop {
+        match read_string().as_str() {
+            "" | "n" | "no" => break false,
+            "y" | "yes" => break true,
+            _ => println!("{}", locale.message("invalid-option", &[])),
+        };
+    };
+
+    Some(SpectatorConfig {
+        max_spectators,
+        notify_players,
+    })
+}
+
+/// Asks the host whether to keep waiting each time the lobby goes its wait timeout without anyone
+/// joining, stopping the lobby waiting if they would rather not.
+///
+/// # Returns
+///
+/// Whether every seat was taken, rather than the host giving up.
+async fn wait_for_players(
+    mut waiting_host: WaitingHost,
+    frontend: &dyn Frontend,
+    locale: Locale,
+) -> bool {
+    while let Some(waited) = waiting_host.timed_out().await {
+        let minutes = waited.as_secs() / 60;
+        let message = locale.message("wait-timed-out", &[("minutes", &minutes)]);
+        let _ = frontend.show_message(&message, None);
+
+        // Players may still join while the host decides
+        let answer = loop {
+            tokio::select! {
+                answer = frontend.read_line() => break answer,
+                waited = waiting_host.timed_out() => if waited.is_none() {
+                    return true;
+                },
+            }
+        };
+        let answer = answer.map(|answer| answer.trim().to_ascii_lowercase());
+        // The host leaving the interface closes their input, which is taken as not waiting
+        if matches!(answer.as_deref(), Ok("n" | "no") | Err(_)) {
+            waiting_host.cancel();
+            let _ = frontend.show_message(&locale.message("wait-cancelled", &[]), None);
+            return false;
+        }
+    }
+
+    true
+}
+
 fn read_string() -> String {
     let mut input_text = String::new();
     io::stdin()
@@ -0,0 +1,961 @@
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::mancala::board::Board;
+pub use crate::mancala::board::{PITS_PER_PLAYER, SEEDS_PER_PIT};
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod board;
+mod bot;
+
+/// Identifies Mancala among the games in `game::GAMES`.
+pub const GAME_ID: &str = "mancala";
+
+/// Mancala, played by the rules of Kalah with six pits and a store for each player. A player sows
+/// the seeds of one of their pits into the holes that follow, going again if the last lands in
+/// their store and capturing the pit opposite if it lands in an empty pit of their own. The game
+/// ends once either side is empty, won by the player with the most seeds in their store.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Mancala;
+
+impl GameDescriptor for Mancala {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(MancalaServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(MancalaClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(MancalaClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(MancalaClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(MancalaClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Outcome {
+    Draw,
+    WinnerFound { player_id: u8 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// The number of seeds in each of player one's pits followed by their store, then each of
+    /// player two's pits followed by theirs.
+    BoardUpdated {
+        holes: Vec<u8>,
+    },
+    /// Sent before the board showing the seeds sown, with the number of seeds the sowing captured.
+    SeedsSown {
+        player_id: u8,
+        pit: u8,
+        captured: u8,
+    },
+    /// The last seed sown by the player landed in their store, so they move again.
+    ExtraTurn {
+        player_id: u8,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    GameOver {
+        outcome: Outcome,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("There is no pit {pit}, the pits are numbered from 1 to 6.")]
+    InvalidPit { pit: u8 },
+    #[error("Pit {pit} is empty, so there are no seeds to sow.")]
+    EmptyPit { pit: u8 },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct MancalaServer {
+    current_player: u8,
+    board: Board,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl MancalaServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> MancalaServer {
+        MancalaServer {
+            current_player: PLAYER_ONE_ID,
+            board: Board::new(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    fn swap_player(&mut self) {
+        self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_board_updated_event(&self, dispatch_mode: DispatchMode) {
+        let holes = self.board.get_holes();
+        self.dispatch_event(dispatch_mode, ServerEvent::BoardUpdated { holes })
+            .await
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, outcome: Outcome) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { outcome })
+            .await;
+
+        let winner = match outcome {
+            Outcome::Draw => None,
+            Outcome::WinnerFound { player_id } => Some(player_id),
+        };
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    /// Ends the current player's turn, handing it to the other player unless the current player
+    /// earned another turn or the game is over.
+    async fn advance_turn(&mut self, extra_turn: bool) {
+        if let Some(outcome) = self.board.determine_outcome() {
+            self.dispatch_game_over_event(outcome).await;
+            return;
+        }
+
+        if extra_turn {
+            self.rejected_moves.reset();
+            self.dispatch_event(
+                DispatchMode::AllPlayers,
+                ServerEvent::ExtraTurn {
+                    player_id: self.current_player,
+                },
+            )
+            .await;
+        } else {
+            self.swap_player();
+        }
+
+        self.start_turn().await;
+    }
+
+    fn handle_seeds_sown_event(&mut self, player_id: u8, pit: u8) -> Result<board::Sowing, Error> {
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.board.sow(player_id, pit)
+    }
+}
+
+#[async_trait]
+impl GameServer for MancalaServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    async fn begin(&self) {
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::SeedsSown { player_id, pit } = event;
+
+        let sowing = match self.handle_seeds_sown_event(player_id, pit) {
+            Ok(sowing) => sowing,
+            Err(error) => {
+                self.dispatch_event(
+                    DispatchMode::SinglePlayer {
+                        player_id: self.current_player,
+                    },
+                    ServerEvent::ErrorOccurred { error },
+                )
+                .await;
+
+                if self
+                    .rejected_moves
+                    .reject(&self.server_channel, self.current_player, player_id)
+                    .await
+                {
+                    self.dispatch_game_over_event(Outcome::WinnerFound {
+                        player_id: get_alternative_player_id(player_id),
+                    })
+                    .await;
+                    return;
+                }
+
+                self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                    player_id: self.current_player,
+                })
+                .await;
+
+                return;
+            }
+        };
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::SeedsSown {
+                player_id,
+                pit,
+                captured: sowing.captured,
+            },
+        )
+        .await;
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.advance_turn(sowing.extra_turn).await;
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
+            }
+            TimeoutAction::SkipTurn => self.advance_turn(false).await,
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_board_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_board_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// The player sows the seeds of their pit, numbered from 1 in the direction they are sown.
+    SeedsSown { player_id: u8, pit: u8 },
+}
+
+pub struct MancalaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    holes: Vec<u8>,
+}
+
+impl<F, C> MancalaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> MancalaClient<F, C> {
+        MancalaClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            holes: Board::new().get_holes(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn get_player_paint(&self, id: u8) -> Paint {
+        match id {
+            PLAYER_ONE_ID => Paint::PlayerOne,
+            _ => Paint::PlayerTwo,
+        }
+    }
+
+    /// The seeds in each of the player's pits, in the order they are sown.
+    fn get_pit_seeds(&self, player_id: u8) -> Vec<u8> {
+        (1..=PITS_PER_PLAYER)
+            .map(|pit| self.holes[board::pit_index(player_id, pit)])
+            .collect()
+    }
+
+    /// Shows the pits in two rows between the stores, with player two's along the top going
+    /// right to left and player one's along the bottom going left to right, so that seeds are
+    /// sown anticlockwise. Each player's store is at the end of their row.
+    async fn handle_board_updated_event(&mut self, holes: Vec<u8>) -> Result<(), GameClientError> {
+        self.holes = holes;
+
+        let player_one_store = board::store_seeds(&self.holes, PLAYER_ONE_ID);
+        let player_two_store = board::store_seeds(&self.holes, PLAYER_TWO_ID);
+        let mut player_one_pits = self.get_pit_seeds(PLAYER_ONE_ID);
+        let mut player_two_pits = self.get_pit_seeds(PLAYER_TWO_ID);
+        player_two_pits.reverse();
+
+        let board_output = match self.user_output.output_style() {
+            OutputStyle::Standard => {
+                let pit_row = |player_id: u8, pits: &[u8]| -> String {
+                    let pits: String = pits
+                        .iter()
+                        .map(|seeds| {
+                            let pit = format!("({:>2})", seeds);
+                            self.user_output
+                                .paint(pit, self.get_player_paint(player_id))
+                        })
+                        .collect();
+                    format!("       {}", pits)
+                };
+                let numbers = |numbers: &mut dyn Iterator<Item = u8>| -> String {
+                    let numbers: String = numbers.map(|pit| format!("  {} ", pit)).collect();
+                    format!("       {}", numbers.trim_end())
+                };
+                let stores = format!(
+                    "{} [{:>2}]{}[{:>2}] {}",
+                    self.get_player_icon_by_id(PLAYER_TWO_ID),
+                    player_two_store,
+                    " ".repeat(4 * PITS_PER_PLAYER as usize + 2),
+                    player_one_store,
+                    self.get_player_icon_by_id(PLAYER_ONE_ID),
+                );
+
+                [
+                    numbers(&mut (1..=PITS_PER_PLAYER).rev()),
+                    pit_row(PLAYER_TWO_ID, &player_two_pits),
+                    stores,
+                    pit_row(PLAYER_ONE_ID, &player_one_pits),
+                    numbers(&mut (1..=PITS_PER_PLAYER)),
+                ]
+                .join("\n")
+                    + "\n"
+            }
+            OutputStyle::Terse => {
+                let join = |pits: &[u8]| -> String {
+                    pits.iter()
+                        .map(u8::to_string)
+                        .collect::<Vec<String>>()
+                        .join(",")
+                };
+                player_one_pits.truncate(PITS_PER_PLAYER as usize);
+                format!(
+                    "O{} {}/{} {}X",
+                    player_two_store,
+                    join(&player_two_pits),
+                    join(&player_one_pits),
+                    player_one_store
+                )
+            }
+        };
+
+        self.user_output.write_line(board_output)?;
+
+        Ok(())
+    }
+
+    /// Points out the pits sown by the other player, as a player already knows which they sowed.
+    async fn handle_seeds_sown_event(
+        &self,
+        player_id: u8,
+        pit: u8,
+        captured: u8,
+    ) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match captured {
+            0 => "mancala-seeds-sown",
+            _ => "mancala-seeds-sown-capturing",
+        };
+        self.user_output.write_message(
+            key,
+            &[
+                ("player", &player_icon),
+                ("pit", &pit),
+                ("captured", &captured),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_extra_turn_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("mancala-extra-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, outcome: Outcome) -> Result<(), GameClientError> {
+        match outcome {
+            Outcome::Draw => self.user_output.write_message("game-draw", &[])?,
+            Outcome::WinnerFound { player_id } => {
+                let player_icon = self.get_player_icon_by_id(player_id);
+                self.user_output
+                    .write_message("game-won", &[("player", &player_icon)])?
+            }
+        }
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::InvalidPit { pit } => self.user_output.write_error(
+                "mancala-error-invalid-pit",
+                &[("pit", &pit), ("pits", &PITS_PER_PLAYER)],
+            ),
+            Error::EmptyPit { pit } => self
+                .user_output
+                .write_error("mancala-error-empty-pit", &[("pit", &pit)]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("mancala-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let pit = self.get_pit().await?;
+        self.send_move(player_id, pit).await
+    }
+
+    async fn send_move(&mut self, player_id: u8, pit: u8) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::SeedsSown { player_id, pit },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the pit the player sows, leaving the server to decide whether the move is allowed.
+    async fn get_pit(&mut self) -> Result<u8, io::Error> {
+        loop {
+            self.user_output
+                .write_message("mancala-move-prompt", &[("pits", &PITS_PER_PLAYER)])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.trim().parse() {
+                Err(_) => self.user_output.write_message("not-a-number", &[])?,
+                Ok(pit) => return Ok(pit),
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for MancalaClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let player_icon = self.get_player_icon_by_id(PLAYER_ONE_ID);
+        self.user_output
+            .message("mancala-game-started-local", &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for MancalaClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let key = match self.client_type.id {
+            PLAYER_ONE_ID => "mancala-game-started-online-bottom",
+            _ => "mancala-game-started-online-top",
+        };
+        let player_icon = self.get_player_icon_by_id(self.client_type.id);
+        self.user_output.message(key, &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for MancalaClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for MancalaClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(pit) = bot::choose_move(&self.holes, player_id) {
+            self.send_move(player_id, pit).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for MancalaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::BoardUpdated { holes } => self.handle_board_updated_event(holes).await,
+            ServerEvent::SeedsSown {
+                player_id,
+                pit,
+                captured,
+            } => self.handle_seeds_sown_event(player_id, pit, captured).await,
+            ServerEvent::ExtraTurn { player_id } => self.handle_extra_turn_event(player_id).await,
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { outcome } => self.handle_game_over_event(outcome).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        MancalaClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = MancalaClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    async fn send_move(server: &mut MancalaServer, player_id: u8, pit: u8) {
+        server
+            .handle_event(ClientEvent::SeedsSown { player_id, pit })
+            .await;
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    #[tokio::test]
+    async fn server_sows_seeds_and_passes_turn() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = MancalaServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, 1).await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::SeedsSown {
+                    player_id: PLAYER_ONE_ID,
+                    pit: 1,
+                    captured: 0
+                },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_gives_extra_turn_when_last_seed_lands_in_store() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = MancalaServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, 3).await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::SeedsSown { .. },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::ExtraTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_empty_pit() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = MancalaServer::new(sender);
+        server.board = Board::from_holes(vec![0, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0]);
+
+        send_move(&mut server, PLAYER_ONE_ID, 1).await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::EmptyPit { pit: 1 }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_a_side_is_empty() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = MancalaServer::new(sender);
+        server.board = Board::from_holes(vec![0, 0, 0, 0, 0, 1, 20, 1, 2, 3, 0, 0, 0, 10]);
+
+        send_move(&mut server, PLAYER_ONE_ID, 6).await;
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver).last(),
+            Some(ServerEvent::GameOver {
+                outcome: Outcome::WinnerFound {
+                    player_id: PLAYER_ONE_ID
+                }
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_with_pits_and_stores() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        let mut holes = Board::new().get_holes();
+        holes[0] = 12;
+        holes[13] = 3;
+        client.handle_board_updated_event(holes).await.unwrap();
+        assert_client_output(
+            output,
+            "         6   5   4   3   2   1\n       \
+             ( 4)( 4)( 4)( 4)( 4)( 4)\n\
+             O [ 3]                          [ 0] X\n       \
+             (12)( 4)( 4)( 4)( 4)( 4)\n         \
+             1   2   3   4   5   6\n\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let mut holes = Board::new().get_holes();
+        holes[6] = 2;
+        holes[12] = 0;
+        client.handle_board_updated_event(holes).await.unwrap();
+        assert_client_output(output, "O0 0,4,4,4,4,4/4,4,4,4,4,4 2X\n")
+    }
+
+    #[tokio::test]
+    async fn client_points_out_opponent_sowing_but_not_own() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_seeds_sown_event(PLAYER_ONE_ID, 3, 0)
+            .await
+            .unwrap();
+        client
+            .handle_seeds_sown_event(PLAYER_TWO_ID, 2, 5)
+            .await
+            .unwrap();
+        assert_client_output(output, "Player O sowed pit 2, capturing 5 seed(s).\n");
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client.handle_extra_turn_event(PLAYER_TWO_ID).await.unwrap();
+        client
+            .handle_error_occurred_event(Error::EmptyPit { pit: 3 })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "La última semilla del jugador O ha caído en su almacén, así que vuelve a jugar.\nError: El hoyo 3 está vacío, así que no hay semillas que sembrar.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_pit_handles_errors_until_number_provided() {
+        let input = "first\n4\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert_eq!(client.get_pit().await.unwrap(), 4);
+        assert_client_output(output, "Pit (1-6):\nNot a number.\nPit (1-6):\n");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 1,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(ClientEvent::SeedsSown {
+                    player_id: 1,
+                    pit: 3
+                })
+            })
+        );
+    }
+}
@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+
+use crate::mancala::{Error, Outcome};
+use crate::server::{get_alternative_player_id, PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+/// The number of pits on each player's side of the board.
+pub const PITS_PER_PLAYER: u8 = 6;
+/// The number of seeds in each pit as the game begins.
+pub const SEEDS_PER_PIT: u8 = 4;
+
+/// The number of pits and stores on the board.
+const HOLE_COUNT: usize = 2 * (PITS_PER_PLAYER as usize + 1);
+
+/// What happened when a player sowed the seeds of one of their pits.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct Sowing {
+    /// The number of seeds moved into the player's store by the last seed landing in one of their
+    /// own empty pits, counting the last seed itself.
+    pub(crate) captured: u8,
+    /// Whether the last seed landed in the player's store, giving them another turn.
+    pub(crate) extra_turn: bool,
+}
+
+/// The pits and stores of a game of Kalah, holding the number of seeds in each.
+///
+/// Holes are counted anticlockwise from player one's first pit, so player one's pits are followed
+/// by their store, then player two's pits and their store. Seeds are sown in that order.
+#[derive(Clone)]
+pub struct Board {
+    holes: Vec<u8>,
+}
+
+impl Board {
+    /// Sets up the board with `SEEDS_PER_PIT` seeds in every pit and both stores empty.
+    pub(crate) fn new() -> Board {
+        let mut holes = vec![SEEDS_PER_PIT; HOLE_COUNT];
+        holes[store_index(PLAYER_ONE_ID)] = 0;
+        holes[store_index(PLAYER_TWO_ID)] = 0;
+
+        Board::from_holes(holes)
+    }
+
+    /// Holds the seeds as they were sent by the server, so that clients can work out moves.
+    pub(crate) fn from_holes(holes: Vec<u8>) -> Board {
+        Board { holes }
+    }
+
+    pub(crate) fn get_holes(&self) -> Vec<u8> {
+        self.holes.clone()
+    }
+
+    /// The pits the player may sow, numbered from 1, which are those holding any seeds.
+    pub(crate) fn legal_moves(&self, player_id: u8) -> Vec<u8> {
+        (1..=PITS_PER_PLAYER)
+            .filter(|&pit| self.holes[pit_index(player_id, pit)] > 0)
+            .collect()
+    }
+
+    /// Picks up every seed in the player's pit and sows them one at a time into each following
+    /// hole, skipping the other player's store.
+    ///
+    /// If the last seed lands in an empty pit of the player's own while the pit opposite holds
+    /// seeds, both are captured into the player's store. Once either side of the board is empty
+    /// the seeds left on the other side are moved into its owner's store, ending the game.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Sowing)` describing the captures made and whether the player goes again.
+    /// - `Err(Error)` if there is no such pit or it is empty.
+    pub(crate) fn sow(&mut self, player_id: u8, pit: u8) -> Result<Sowing, Error> {
+        if !(1..=PITS_PER_PLAYER).contains(&pit) {
+            return Err(Error::InvalidPit { pit });
+        }
+
+        let start = pit_index(player_id, pit);
+        let mut seeds = std::mem::take(&mut self.holes[start]);
+        if seeds == 0 {
+            return Err(Error::EmptyPit { pit });
+        }
+
+        let opponent_store = store_index(get_alternative_player_id(player_id));
+        let mut index = start;
+        while seeds > 0 {
+            index = (index + 1) % HOLE_COUNT;
+            if index == opponent_store {
+                continue;
+            }
+            self.holes[index] += 1;
+            seeds -= 1;
+        }
+
+        let own_store = store_index(player_id);
+        let mut captured = 0;
+        if owner(index) == Some(player_id) && self.holes[index] == 1 {
+            // Pits face each other across the board, so the first of one side faces the last of
+            // the other
+            let opposite = HOLE_COUNT - 2 - index;
+            if self.holes[opposite] > 0 {
+                captured = 1 + std::mem::take(&mut self.holes[opposite]);
+                self.holes[index] = 0;
+                self.holes[own_store] += captured;
+            }
+        }
+
+        self.sweep_if_side_empty();
+
+        Ok(Sowing {
+            captured,
+            extra_turn: index == own_store,
+        })
+    }
+
+    /// Moves the seeds left on each side into its owner's store once either side is empty.
+    fn sweep_if_side_empty(&mut self) {
+        let side_empty = [PLAYER_ONE_ID, PLAYER_TWO_ID]
+            .into_iter()
+            .any(|player_id| self.legal_moves(player_id).is_empty());
+        if !side_empty {
+            return;
+        }
+
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let seeds: u8 = (1..=PITS_PER_PLAYER)
+                .map(|pit| std::mem::take(&mut self.holes[pit_index(player_id, pit)]))
+                .sum();
+            self.holes[store_index(player_id)] += seeds;
+        }
+    }
+
+    /// Determines whether the game has ended, which happens once every seed is in a store.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Outcome)` with the player holding the most seeds in their store as the winner, or a
+    ///   draw if they hold the same number.
+    /// - `None` if there are seeds left to sow.
+    pub(crate) fn determine_outcome(&self) -> Option<Outcome> {
+        let can_move = [PLAYER_ONE_ID, PLAYER_TWO_ID]
+            .into_iter()
+            .any(|player_id| !self.legal_moves(player_id).is_empty());
+        if can_move {
+            return None;
+        }
+
+        let outcome = match store_seeds(&self.holes, PLAYER_ONE_ID)
+            .cmp(&store_seeds(&self.holes, PLAYER_TWO_ID))
+        {
+            Ordering::Greater => Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID,
+            },
+            Ordering::Less => Outcome::WinnerFound {
+                player_id: PLAYER_TWO_ID,
+            },
+            Ordering::Equal => Outcome::Draw,
+        };
+
+        Some(outcome)
+    }
+}
+
+/// The index of the player's pit, numbered from 1 in the order seeds are sown.
+pub(crate) fn pit_index(player_id: u8, pit: u8) -> usize {
+    store_index(player_id) - PITS_PER_PLAYER as usize + pit as usize - 1
+}
+
+/// The index of the player's store, which follows their last pit.
+pub(crate) fn store_index(player_id: u8) -> usize {
+    match player_id {
+        PLAYER_ONE_ID => PITS_PER_PLAYER as usize,
+        _ => HOLE_COUNT - 1,
+    }
+}
+
+/// The number of seeds in the player's store among the holes.
+pub(crate) fn store_seeds(holes: &[u8], player_id: u8) -> u8 {
+    holes[store_index(player_id)]
+}
+
+/// The player whose pit is at the index, or `None` for either store.
+fn owner(index: usize) -> Option<u8> {
+    match index {
+        _ if index == store_index(PLAYER_ONE_ID) || index == store_index(PLAYER_TWO_ID) => None,
+        _ if index < store_index(PLAYER_ONE_ID) => Some(PLAYER_ONE_ID),
+        _ => Some(PLAYER_TWO_ID),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Sets up a board holding the given seeds in player one's pits, their store, player two's
+    /// pits and their store.
+    fn board_with(one: [u8; 6], one_store: u8, two: [u8; 6], two_store: u8) -> Board {
+        let mut holes = one.to_vec();
+        holes.push(one_store);
+        holes.extend(two);
+        holes.push(two_store);
+
+        Board::from_holes(holes)
+    }
+
+    #[test]
+    fn new_board_has_seeds_in_every_pit() {
+        let board = Board::new();
+
+        assert_eq!(
+            board.get_holes(),
+            vec![4, 4, 4, 4, 4, 4, 0, 4, 4, 4, 4, 4, 4, 0]
+        );
+        assert_eq!(board.legal_moves(PLAYER_TWO_ID), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn sowing_into_store_gives_extra_turn() {
+        let mut board = Board::new();
+
+        let sowing = board.sow(PLAYER_ONE_ID, 3).unwrap();
+        assert_eq!(
+            sowing,
+            Sowing {
+                captured: 0,
+                extra_turn: true
+            }
+        );
+        assert_eq!(
+            board.get_holes(),
+            vec![4, 4, 0, 5, 5, 5, 1, 4, 4, 4, 4, 4, 4, 0]
+        );
+    }
+
+    #[test]
+    fn sowing_skips_opponents_store() {
+        let mut board = board_with([1, 1, 0, 0, 0, 9], 0, [1, 0, 0, 0, 0, 0], 0);
+
+        board.sow(PLAYER_ONE_ID, 6).unwrap();
+        assert_eq!(
+            board.get_holes(),
+            vec![2, 2, 0, 0, 0, 0, 1, 2, 1, 1, 1, 1, 1, 0]
+        );
+    }
+
+    #[test]
+    fn last_seed_in_own_empty_pit_captures_opposite_pit() {
+        let mut board = board_with([1, 0, 0, 0, 0, 1], 0, [1, 1, 1, 1, 5, 1], 0);
+
+        let sowing = board.sow(PLAYER_ONE_ID, 1).unwrap();
+        assert_eq!(sowing.captured, 6);
+        assert!(!sowing.extra_turn);
+        assert_eq!(
+            board.get_holes(),
+            vec![0, 0, 0, 0, 0, 1, 6, 1, 1, 1, 1, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn last_seed_in_own_empty_pit_opposite_empty_pit_captures_nothing() {
+        let mut board = board_with([1, 0, 0, 0, 0, 1], 0, [1, 1, 1, 1, 0, 1], 0);
+
+        assert_eq!(board.sow(PLAYER_ONE_ID, 1).unwrap().captured, 0);
+    }
+
+    #[test]
+    fn empty_or_missing_pit_refused() {
+        let mut board = board_with([0, 1, 1, 1, 1, 1], 0, [1, 1, 1, 1, 1, 1], 0);
+
+        assert!(matches!(
+            board.sow(PLAYER_ONE_ID, 1),
+            Err(Error::EmptyPit { pit: 1 })
+        ));
+        assert!(matches!(
+            board.sow(PLAYER_ONE_ID, 7),
+            Err(Error::InvalidPit { pit: 7 })
+        ));
+    }
+
+    #[test]
+    fn emptying_a_side_sweeps_remaining_seeds_and_ends_game() {
+        let mut board = board_with([0, 0, 0, 0, 0, 1], 20, [1, 2, 3, 0, 0, 0], 10);
+
+        board.sow(PLAYER_ONE_ID, 6).unwrap();
+        assert_eq!(
+            board.get_holes(),
+            vec![0, 0, 0, 0, 0, 0, 21, 0, 0, 0, 0, 0, 0, 16]
+        );
+        assert_eq!(
+            board.determine_outcome(),
+            Some(Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID
+            })
+        );
+        assert_eq!(Board::new().determine_outcome(), None);
+    }
+}
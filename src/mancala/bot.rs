@@ -0,0 +1,59 @@
+use crate::mancala::board::{self, Board};
+
+/// Chooses a pit for the given player to sow, based on the seeds on the board.
+///
+/// Pits whose last seed lands in the player's store are sown first, as the player then goes
+/// again. Otherwise the pit adding the most seeds to the player's store is chosen.
+///
+/// # Returns
+///
+/// - `Some(u8)` containing the pit to sow, numbered from 1.
+/// - `None` if the player has no pit to sow.
+pub(crate) fn choose_move(holes: &[u8], player_id: u8) -> Option<u8> {
+    let board = Board::from_holes(holes.to_vec());
+    let seeds_stored = board::store_seeds(holes, player_id);
+
+    // The last of equally good pits is kept, so they are tried in reverse to keep the first
+    board
+        .legal_moves(player_id)
+        .into_iter()
+        .rev()
+        .max_by_key(|&pit| {
+            let mut board = board.clone();
+            let sowing = board.sow(player_id, pit).unwrap();
+            let gained = board::store_seeds(&board.get_holes(), player_id) - seeds_stored;
+
+            (sowing.extra_turn, gained)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+    use super::*;
+
+    #[test]
+    fn choose_move_takes_extra_turn() {
+        let holes = Board::new().get_holes();
+
+        assert_eq!(choose_move(&holes, PLAYER_ONE_ID), Some(3));
+    }
+
+    #[test]
+    fn choose_move_captures_most_seeds() {
+        // Sowing pit 1 captures the five seeds opposite pit 2, while pit 6 only drops one seed in
+        // the store on its way past
+        let holes = vec![1, 0, 0, 0, 0, 2, 0, 1, 1, 1, 1, 5, 1, 0];
+
+        assert_eq!(choose_move(&holes, PLAYER_ONE_ID), Some(1));
+    }
+
+    #[test]
+    fn choose_move_returns_none_without_moves() {
+        let holes = vec![0, 0, 0, 0, 0, 0, 10, 1, 0, 0, 0, 0, 0, 5];
+
+        assert_eq!(choose_move(&holes, PLAYER_ONE_ID), None);
+        assert_eq!(choose_move(&holes, PLAYER_TWO_ID), Some(1));
+    }
+}
@@ -0,0 +1,914 @@
+//! Nim, kept as small as a game can be so that it doubles as the example to follow when adding a
+//! game.
+//!
+//! A game is made up of:
+//!
+//! 1. A [`GameDescriptor`], here [`Nim`], naming the game and creating its server and client. It
+//!    is listed in [`game::GAMES`] so that the lobby can find it by its ID.
+//! 2. A [`GameServer`], here [`NimServer`], holding the state of the game. It checks each move a
+//!    client sends as a [`ClientEvent`] and tells clients what happened by sending [`ServerEvent`]s
+//!    to the players chosen by a [`DispatchMode`]. `GameServerEvent::TurnStarted` and
+//!    `GameServerEvent::GameOver` tell the server whose turn it is and when the game has ended.
+//!    Both sides deal only in these types, which are serialised on their way between them.
+//! 3. A [`GameClient`], here [`NimClient`], showing those events to its user and reading their
+//!    moves. Everything it writes goes through a [`UserOutput`], which looks each message up by
+//!    its key in the catalogs under `locales`, worded for the [`OutputStyle`] and [`Locale`] set
+//!    on the client. What differs between local, online, spectating and bot clients is kept to an
+//!    implementation of `ClientTypeEvent` for each [`ClientType`].
+//! 4. The rules in a `board` module and the moves chosen by bots in a `bot` module, each tested
+//!    alongside the code as the server and client are.
+//!
+//! To play it from the command line, the game also needs a `GameKind` in `cli.rs` and to be
+//! created for it in `make_game` in `main.rs`. Its messages go in a section of their own in each
+//! catalog, where the welcome message lists the games, as does `tests/cli.rs` with a local game of
+//! each.
+
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::nim::board::Board;
+pub use crate::nim::board::{MAX_TAKE, STARTING_HEAPS};
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod board;
+mod bot;
+
+/// Identifies Nim among the games in `game::GAMES`.
+pub const GAME_ID: &str = "nim";
+
+/// Nim, where players take turns to take from one to three counters from one of the heaps. The
+/// player who takes the last counter wins.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Nim;
+
+impl GameDescriptor for Nim {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(NimServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(NimClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(NimClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(NimClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(NimClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// The number of counters left in each heap.
+    HeapsUpdated {
+        heaps: Vec<u8>,
+    },
+    /// Sent before the heaps the counters were taken from.
+    CountersTaken {
+        player_id: u8,
+        heap: u8,
+        count: u8,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    /// Nim cannot be drawn, so the game always ends with a winner.
+    GameOver {
+        winner: u8,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("There is no heap {heap}.")]
+    InvalidHeap { heap: u8 },
+    #[error("You can only take from 1 to 3 counters, not {count}.")]
+    InvalidCount { count: u8 },
+    #[error("Heap {heap} only has {remaining} counter(s) left.")]
+    NotEnoughCounters { heap: u8, remaining: u8 },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct NimServer {
+    current_player: u8,
+    board: Board,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the winner has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl NimServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> NimServer {
+        NimServer {
+            current_player: PLAYER_ONE_ID,
+            board: Board::new(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_heaps_updated_event(&self, dispatch_mode: DispatchMode) {
+        let heaps = self.board.get_heaps();
+        self.dispatch_event(dispatch_mode, ServerEvent::HeapsUpdated { heaps })
+            .await
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn pass_turn(&mut self) {
+        self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+        self.start_turn().await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, winner: u8) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { winner })
+            .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver {
+                winner: Some(winner),
+            })
+            .await
+    }
+
+    fn handle_counters_taken_event(
+        &mut self,
+        player_id: u8,
+        heap: u8,
+        count: u8,
+    ) -> Result<(), Error> {
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.board.take(heap, count)
+    }
+}
+
+#[async_trait]
+impl GameServer for NimServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    async fn begin(&self) {
+        self.dispatch_heaps_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::CountersTaken {
+            player_id,
+            heap,
+            count,
+        } = event;
+
+        if let Err(error) = self.handle_counters_taken_event(player_id, heap, count) {
+            self.dispatch_event(
+                DispatchMode::SinglePlayer {
+                    player_id: self.current_player,
+                },
+                ServerEvent::ErrorOccurred { error },
+            )
+            .await;
+
+            if self
+                .rejected_moves
+                .reject(&self.server_channel, self.current_player, player_id)
+                .await
+            {
+                self.dispatch_game_over_event(get_alternative_player_id(player_id))
+                    .await;
+                return;
+            }
+
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                player_id: self.current_player,
+            })
+            .await;
+
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::CountersTaken {
+                player_id,
+                heap,
+                count,
+            },
+        )
+        .await;
+        self.dispatch_heaps_updated_event(DispatchMode::AllPlayers)
+            .await;
+
+        // Whoever takes the last counter wins
+        if self.board.is_empty() {
+            self.dispatch_game_over_event(player_id).await;
+            return;
+        }
+
+        self.pass_turn().await;
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(get_alternative_player_id(player_id))
+                    .await
+            }
+            TimeoutAction::SkipTurn => self.pass_turn().await,
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_heaps_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_heaps_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// The player takes counters from a heap, numbered from 1.
+    CountersTaken { player_id: u8, heap: u8, count: u8 },
+}
+
+pub struct NimClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    heaps: Vec<u8>,
+}
+
+impl<F, C> NimClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> NimClient<F, C> {
+        NimClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            heaps: STARTING_HEAPS.to_vec(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            // The server is not trusted to only name players of the game
+            _ => '?',
+        }
+    }
+
+    /// Shows each heap on its own line as a row of counters, or just the counts in terse style.
+    async fn handle_heaps_updated_event(&mut self, heaps: Vec<u8>) -> Result<(), GameClientError> {
+        self.heaps = heaps;
+
+        let heaps_output = match self.user_output.output_style() {
+            OutputStyle::Standard => self
+                .heaps
+                .iter()
+                .enumerate()
+                .map(|(index, &counters)| {
+                    format!(
+                        "{}: {} ({})",
+                        index + 1,
+                        "|".repeat(counters as usize),
+                        counters
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            OutputStyle::Terse => self
+                .heaps
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<String>>()
+                .join(","),
+        };
+
+        self.user_output.write_line(heaps_output)?;
+
+        Ok(())
+    }
+
+    /// Points out the counters taken by the other player, as a player already knows what they took.
+    async fn handle_counters_taken_event(
+        &self,
+        player_id: u8,
+        heap: u8,
+        count: u8,
+    ) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output.write_message(
+            "nim-counters-taken",
+            &[("player", &player_icon), ("count", &count), ("heap", &heap)],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, winner: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(winner);
+        self.user_output
+            .write_message("nim-game-won", &[("player", &player_icon)])?;
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::InvalidHeap { heap } => self
+                .user_output
+                .write_error("nim-error-invalid-heap", &[("heap", &heap)]),
+            Error::InvalidCount { count } => self.user_output.write_error(
+                "nim-error-invalid-count",
+                &[("count", &count), ("max", &MAX_TAKE)],
+            ),
+            Error::NotEnoughCounters { heap, remaining } => self.user_output.write_error(
+                "nim-error-not-enough-counters",
+                &[("heap", &heap), ("remaining", &remaining)],
+            ),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("nim-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let (heap, count) = self.get_heap_and_count().await?;
+        self.send_move(player_id, heap, count).await
+    }
+
+    async fn send_move(
+        &mut self,
+        player_id: u8,
+        heap: u8,
+        count: u8,
+    ) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::CountersTaken {
+                player_id,
+                heap,
+                count,
+            },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the heap to take from and the number of counters to take, leaving the server to
+    /// decide whether the move is allowed.
+    async fn get_heap_and_count(&mut self) -> Result<(u8, u8), io::Error> {
+        loop {
+            self.user_output
+                .write_message("nim-move-prompt", &[("max", &MAX_TAKE)])?;
+
+            let input_text = self.frontend.read_line().await?;
+            let numbers: Vec<Result<u8, _>> =
+                input_text.split_whitespace().map(str::parse).collect();
+            match numbers[..] {
+                [Ok(heap), Ok(count)] => return Ok((heap, count)),
+                _ => self.user_output.write_message("nim-not-a-move", &[])?,
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for NimClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let player_icon = self.get_player_icon_by_id(PLAYER_ONE_ID);
+        self.user_output
+            .message("game-started-local-first", &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for NimClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        let player_icon = self.get_player_icon_by_id(self.client_type.id);
+        self.user_output
+            .message("game-started-online-as", &[("player", &player_icon)])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for NimClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for NimClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some((heap, count)) = bot::choose_move(&self.heaps) {
+            self.send_move(player_id, heap, count).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for NimClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::HeapsUpdated { heaps } => self.handle_heaps_updated_event(heaps).await,
+            ServerEvent::CountersTaken {
+                player_id,
+                heap,
+                count,
+            } => {
+                self.handle_counters_taken_event(player_id, heap, count)
+                    .await
+            }
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { winner } => self.handle_game_over_event(winner).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        NimClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = NimClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    async fn send_move(server: &mut NimServer, player_id: u8, heap: u8, count: u8) {
+        server
+            .handle_event(ClientEvent::CountersTaken {
+                player_id,
+                heap,
+                count,
+            })
+            .await;
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    #[tokio::test]
+    async fn server_takes_counters_and_passes_turn() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = NimServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, 3, 2).await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert_eq!(server.board.get_heaps(), vec![3, 4, 3]);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::CountersTaken {
+                    player_id: PLAYER_ONE_ID,
+                    heap: 3,
+                    count: 2
+                },
+                ServerEvent::HeapsUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_taking_too_many_counters() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = NimServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, 1, 4).await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::InvalidCount { count: 4 }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_move_from_other_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = NimServer::new(sender);
+
+        send_move(&mut server, PLAYER_TWO_ID, 1, 1).await;
+
+        assert_eq!(server.rejected_moves.count(), 0);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::UnexpectedPlayer
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_declares_player_taking_last_counter_winner() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = NimServer::new(sender);
+        server.board = Board::from_heaps(vec![0, 2, 0]);
+
+        send_move(&mut server, PLAYER_ONE_ID, 2, 2).await;
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver).last(),
+            Some(ServerEvent::GameOver {
+                winner: PLAYER_ONE_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_skips_turn_of_timed_out_player() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = NimServer::new(sender);
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        assert!(!server.game_over);
+    }
+
+    #[tokio::test]
+    async fn client_handles_heaps_updated_event() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_heaps_updated_event(vec![3, 0, 5])
+            .await
+            .unwrap();
+        assert_client_output(output, "1: ||| (3)\n2:  (0)\n3: ||||| (5)\n")
+    }
+
+    #[tokio::test]
+    async fn client_handles_heaps_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        client
+            .handle_heaps_updated_event(vec![3, 0, 5])
+            .await
+            .unwrap();
+        assert_client_output(output, "3,0,5\n")
+    }
+
+    #[tokio::test]
+    async fn client_points_out_opponent_move_but_not_own() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_counters_taken_event(PLAYER_ONE_ID, 1, 2)
+            .await
+            .unwrap();
+        client
+            .handle_counters_taken_event(PLAYER_TWO_ID, 3, 1)
+            .await
+            .unwrap();
+        assert_client_output(output, "Player O took 1 counter(s) from heap 3.\n");
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_counters_taken_event(PLAYER_TWO_ID, 3, 1)
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::InvalidHeap { heap: 4 })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador O ha cogido 1 ficha(s) del montón 3.\nError: No existe el montón 4.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_heap_and_count_handles_errors_until_numbers_provided() {
+        let input = "2\nfirst 1\n2 3\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert_eq!(client.get_heap_and_count().await.unwrap(), (2, 3));
+        assert_client_output(
+            output,
+            "Heap and count:\nNot a heap and count.\nHeap and count:\nNot a heap and count.\nHeap and count:\n",
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 1,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(ClientEvent::CountersTaken {
+                    player_id: 1,
+                    heap: 1,
+                    count: 2
+                })
+            })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::nim::Error;
+
+/// The number of counters in each heap as the game begins.
+pub const STARTING_HEAPS: [u8; 3] = [3, 4, 5];
+/// The most counters a player may take from a heap in a single turn.
+pub const MAX_TAKE: u8 = 3;
+
+/// The heaps of counters left to take from.
+pub struct Board {
+    heaps: Vec<u8>,
+}
+
+impl Board {
+    pub(crate) fn new() -> Board {
+        Board::from_heaps(STARTING_HEAPS.to_vec())
+    }
+
+    pub(crate) fn from_heaps(heaps: Vec<u8>) -> Board {
+        Board { heaps }
+    }
+
+    pub(crate) fn get_heaps(&self) -> Vec<u8> {
+        self.heaps.clone()
+    }
+
+    /// Takes counters from a heap, numbered from 1.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` if the counters were taken.
+    /// - `Err(Error)` if there is no such heap, or the count is not one the player may take.
+    pub(crate) fn take(&mut self, heap: u8, count: u8) -> Result<(), Error> {
+        if !(1..=MAX_TAKE).contains(&count) {
+            return Err(Error::InvalidCount { count });
+        }
+
+        let counters = heap
+            .checked_sub(1)
+            .and_then(|index| self.heaps.get_mut(index as usize))
+            .ok_or(Error::InvalidHeap { heap })?;
+        if *counters < count {
+            return Err(Error::NotEnoughCounters {
+                heap,
+                remaining: *counters,
+            });
+        }
+
+        *counters -= count;
+
+        Ok(())
+    }
+
+    /// Whether every counter has been taken, which ends the game.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.heaps.iter().all(|&counters| counters == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_removes_counters_from_heap() {
+        let mut board = Board::new();
+
+        board.take(2, 3).unwrap();
+        assert_eq!(board.get_heaps(), vec![3, 1, 5]);
+        assert!(!board.is_empty());
+    }
+
+    #[test]
+    fn take_refuses_invalid_moves() {
+        let mut board = Board::from_heaps(vec![2, 0]);
+
+        assert!(matches!(
+            board.take(1, 4),
+            Err(Error::InvalidCount { count: 4 })
+        ));
+        assert!(matches!(
+            board.take(1, 0),
+            Err(Error::InvalidCount { count: 0 })
+        ));
+        assert!(matches!(
+            board.take(0, 1),
+            Err(Error::InvalidHeap { heap: 0 })
+        ));
+        assert!(matches!(
+            board.take(3, 1),
+            Err(Error::InvalidHeap { heap: 3 })
+        ));
+        assert!(matches!(
+            board.take(1, 3),
+            Err(Error::NotEnoughCounters {
+                heap: 1,
+                remaining: 2
+            })
+        ));
+        assert_eq!(board.get_heaps(), vec![2, 0]);
+    }
+
+    #[test]
+    fn board_is_empty_once_every_counter_taken() {
+        let mut board = Board::from_heaps(vec![0, 2]);
+
+        board.take(2, 2).unwrap();
+        assert!(board.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use crate::nim::board::MAX_TAKE;
+
+/// Chooses the heap to take from and the number of counters to take.
+///
+/// As at most `MAX_TAKE` counters may be taken at once, a heap counts for its remainder after
+/// dividing by one more than that. A move leaving those remainders with a nim-sum of zero wins, so
+/// one is made whenever there is one, otherwise a single counter is taken to drag the game out.
+///
+/// # Returns
+///
+/// - `Some((u8, u8))` containing the heap, numbered from 1, and the number of counters to take.
+/// - `None` if every heap is empty.
+pub(crate) fn choose_move(heaps: &[u8]) -> Option<(u8, u8)> {
+    let remainder = |counters: u8| counters % (MAX_TAKE + 1);
+    let nim_sum = heaps
+        .iter()
+        .fold(0, |nim_sum, &counters| nim_sum ^ remainder(counters));
+
+    let winning_move = heaps.iter().enumerate().find_map(|(index, &counters)| {
+        let target = remainder(counters) ^ nim_sum;
+        (target < remainder(counters)).then(|| (index as u8 + 1, remainder(counters) - target))
+    });
+
+    winning_move.or_else(|| {
+        heaps
+            .iter()
+            .position(|&counters| counters > 0)
+            .map(|index| (index as u8 + 1, 1))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose_move_leaves_zero_nim_sum() {
+        // The remainders 3, 0 and 1 have a nim-sum of 2, cleared by leaving 1 in the first heap
+        assert_eq!(choose_move(&[3, 4, 5]), Some((1, 2)));
+    }
+
+    #[test]
+    fn choose_move_takes_last_counters() {
+        assert_eq!(choose_move(&[0, 3, 0]), Some((2, 3)));
+    }
+
+    #[test]
+    fn choose_move_takes_one_counter_when_losing() {
+        assert_eq!(choose_move(&[0, 4, 1, 1]), Some((2, 1)));
+        assert_eq!(choose_move(&[0, 0]), None);
+    }
+}
@@ -0,0 +1,1016 @@
+use std::io;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::othello::board::Board;
+pub use crate::othello::board::{Square, BOARD_SIZE};
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+mod board;
+mod bot;
+
+/// Identifies Othello among the games in `game::GAMES`.
+pub const GAME_ID: &str = "othello";
+
+/// Othello, also known as Reversi, played on an 8x8 board. Each disc placed must enclose a line
+/// of the other player's discs, which are flipped to become the player's own. A player who cannot
+/// place a disc passes, and the game ends once neither player can.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Othello;
+
+impl GameDescriptor for Othello {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(OthelloServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(OthelloClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(OthelloClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(OthelloClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(OthelloClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum Outcome {
+    Draw,
+    WinnerFound { player_id: u8 },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// The ID of the player whose disc is on each square, going along each row from the top of
+    /// the board.
+    BoardUpdated {
+        cells: Vec<Option<u8>>,
+    },
+    /// Sent before the board showing the disc, with the discs it flipped as worked out by the
+    /// server.
+    DiscPlaced {
+        player_id: u8,
+        square: Square,
+        flipped: Vec<Square>,
+    },
+    /// The player has nowhere to place a disc, so the other player moves again.
+    TurnPassed {
+        player_id: u8,
+    },
+    PlayerTurn {
+        player_id: u8,
+    },
+    GameOver {
+        outcome: Outcome,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("There is already a disc on {square}.")]
+    SquareTaken { square: Square },
+    #[error("A disc on {square} would not enclose any of the other player's discs, so cannot be placed there.")]
+    NothingFlipped { square: Square },
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct OthelloServer {
+    current_player: u8,
+    board: Board,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+}
+
+impl OthelloServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> OthelloServer {
+        OthelloServer {
+            current_player: PLAYER_ONE_ID,
+            board: Board::new(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            game_over: false,
+        }
+    }
+
+    fn swap_player(&mut self) {
+        self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_board_updated_event(&self, dispatch_mode: DispatchMode) {
+        let cells = self.board.get_cells();
+        self.dispatch_event(dispatch_mode, ServerEvent::BoardUpdated { cells })
+            .await
+    }
+
+    async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
+        let player_id = self.current_player;
+        self.dispatch_event(dispatch_mode, ServerEvent::PlayerTurn { player_id })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, outcome: Outcome) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { outcome })
+            .await;
+
+        let winner = match outcome {
+            Outcome::Draw => None,
+            Outcome::WinnerFound { player_id } => Some(player_id),
+        };
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    /// Ends the current player's turn, handing it to the other player if they can place a disc.
+    /// Otherwise the other player passes and the current player goes again, unless neither can
+    /// move and the game is over.
+    async fn advance_turn(&mut self) {
+        if let Some(outcome) = self.board.determine_outcome() {
+            self.dispatch_game_over_event(outcome).await;
+            return;
+        }
+
+        let next_player = get_alternative_player_id(self.current_player);
+        if self.board.legal_moves(next_player).is_empty() {
+            self.rejected_moves.reset();
+            self.dispatch_event(
+                DispatchMode::AllPlayers,
+                ServerEvent::TurnPassed {
+                    player_id: next_player,
+                },
+            )
+            .await;
+        } else {
+            self.swap_player();
+        }
+
+        self.start_turn().await;
+    }
+
+    fn handle_disc_placed_event(
+        &mut self,
+        player_id: u8,
+        square: Square,
+    ) -> Result<Vec<Square>, Error> {
+        if player_id != self.current_player {
+            return Err(Error::UnexpectedPlayer);
+        }
+
+        self.board.place_disc(player_id, square)
+    }
+}
+
+#[async_trait]
+impl GameServer for OthelloServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    async fn begin(&self) {
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::DiscPlaced { player_id, square } = event;
+
+        let flipped = match self.handle_disc_placed_event(player_id, square) {
+            Ok(flipped) => flipped,
+            Err(error) => {
+                self.dispatch_event(
+                    DispatchMode::SinglePlayer {
+                        player_id: self.current_player,
+                    },
+                    ServerEvent::ErrorOccurred { error },
+                )
+                .await;
+
+                if self
+                    .rejected_moves
+                    .reject(&self.server_channel, self.current_player, player_id)
+                    .await
+                {
+                    self.dispatch_game_over_event(Outcome::WinnerFound {
+                        player_id: get_alternative_player_id(player_id),
+                    })
+                    .await;
+                    return;
+                }
+
+                self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                    player_id: self.current_player,
+                })
+                .await;
+
+                return;
+            }
+        };
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::DiscPlaced {
+                player_id,
+                square,
+                flipped,
+            },
+        )
+        .await;
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.advance_turn().await;
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
+            }
+            TimeoutAction::SkipTurn => self.advance_turn().await,
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_board_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_board_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    DiscPlaced { player_id: u8, square: Square },
+}
+
+pub struct OthelloClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    cells: Vec<Option<u8>>,
+}
+
+impl<F, C> OthelloClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> OthelloClient<F, C> {
+        OthelloClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            cells: Board::new().get_cells(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn get_square_icon(&self, square: Square) -> String {
+        match self.cells[square.index()] {
+            Some(player_id) => {
+                let paint = match player_id {
+                    PLAYER_ONE_ID => Paint::PlayerOne,
+                    _ => Paint::PlayerTwo,
+                };
+                self.user_output
+                    .paint(self.get_player_icon_by_id(player_id), paint)
+            }
+            None => String::from("."),
+        }
+    }
+
+    /// Shows the board followed by the number of discs each player holds, which decides the
+    /// winner once the game is over.
+    async fn handle_board_updated_event(
+        &mut self,
+        cells: Vec<Option<u8>>,
+    ) -> Result<(), GameClientError> {
+        self.cells = cells;
+
+        let player_one_discs = board::count_discs(&self.cells, PLAYER_ONE_ID);
+        let player_two_discs = board::count_discs(&self.cells, PLAYER_TWO_ID);
+        let discs = self.user_output.message(
+            "othello-discs",
+            &[("first", &player_one_discs), ("second", &player_two_discs)],
+        );
+        let board_output = match self.user_output.output_style() {
+            OutputStyle::Standard => {
+                let columns: String = (0..BOARD_SIZE)
+                    .map(|column| format!(" {}", char::from(b'a' + column)))
+                    .collect();
+                let rows: Vec<String> = (0..BOARD_SIZE)
+                    .map(|row| {
+                        let rank = BOARD_SIZE - row;
+                        let squares: String = (0..BOARD_SIZE)
+                            .map(|column| {
+                                format!(" {}", self.get_square_icon(Square::new(row, column)))
+                            })
+                            .collect();
+                        format!("{}{} {}", rank, squares, rank)
+                    })
+                    .collect();
+                format!(
+                    " {}\n{}\n {}\n{}\n",
+                    columns,
+                    rows.join("\n"),
+                    columns,
+                    discs
+                )
+            }
+            OutputStyle::Terse => {
+                let rows: Vec<String> = self
+                    .cells
+                    .chunks(BOARD_SIZE as usize)
+                    .map(|row| {
+                        row.iter()
+                            .map(|cell| match cell {
+                                Some(player_id) => self.get_player_icon_by_id(*player_id),
+                                None => '.',
+                            })
+                            .collect()
+                    })
+                    .collect();
+                format!("{} {}", rows.join("/"), discs)
+            }
+        };
+
+        self.user_output.write_line(board_output)?;
+
+        Ok(())
+    }
+
+    /// Points out the discs placed by the other player, as a player already knows where they
+    /// placed theirs.
+    async fn handle_disc_placed_event(
+        &self,
+        player_id: u8,
+        square: Square,
+        flipped: Vec<Square>,
+    ) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            return Ok(());
+        }
+
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output.write_message(
+            "othello-disc-placed",
+            &[
+                ("player", &player_icon),
+                ("square", &square),
+                ("flipped", &flipped.len()),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_passed_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("othello-turn-passed", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, outcome: Outcome) -> Result<(), GameClientError> {
+        match outcome {
+            Outcome::Draw => self.user_output.write_message("game-draw", &[])?,
+            Outcome::WinnerFound { player_id } => {
+                let player_icon = self.get_player_icon_by_id(player_id);
+                self.user_output
+                    .write_message("game-won", &[("player", &player_icon)])?
+            }
+        }
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::SquareTaken { square } => self
+                .user_output
+                .write_error("othello-error-square-taken", &[("square", &square)]),
+            Error::NothingFlipped { square } => self
+                .user_output
+                .write_error("othello-error-nothing-flipped", &[("square", &square)]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("othello-error-unexpected-player", &[]),
+        }?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let square = self.get_square().await?;
+        self.send_move(player_id, square).await
+    }
+
+    async fn send_move(&mut self, player_id: u8, square: Square) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::DiscPlaced { player_id, square },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the square the player places their disc on, leaving the server to decide whether
+    /// the move is allowed.
+    async fn get_square(&mut self) -> Result<Square, io::Error> {
+        loop {
+            self.user_output.write_message("othello-move-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.parse() {
+                Err(_) => self
+                    .user_output
+                    .write_message("othello-not-a-square", &[])?,
+                Ok(square) => return Ok(square),
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for OthelloClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "othello-game-started-local",
+            &[("player", &self.get_player_icon_by_id(PLAYER_ONE_ID))],
+        )
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for OthelloClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "game-started-online-as",
+            &[("player", &self.get_player_icon_by_id(self.client_type.id))],
+        )
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
+
+            return Ok(());
+        }
+
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.make_player_move(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for OthelloClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for OthelloClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(square) = bot::choose_move(&self.cells, player_id) {
+            self.send_move(player_id, square).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for OthelloClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::BoardUpdated { cells } => self.handle_board_updated_event(cells).await,
+            ServerEvent::DiscPlaced {
+                player_id,
+                square,
+                flipped,
+            } => {
+                self.handle_disc_placed_event(player_id, square, flipped)
+                    .await
+            }
+            ServerEvent::TurnPassed { player_id } => self.handle_turn_passed_event(player_id).await,
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::GameOver { outcome } => self.handle_game_over_event(outcome).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        OthelloClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = OthelloClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    fn square(name: &str) -> Square {
+        name.parse().unwrap()
+    }
+
+    async fn send_move(server: &mut OthelloServer, player_id: u8, name: &str) {
+        server
+            .handle_event(ClientEvent::DiscPlaced {
+                player_id,
+                square: square(name),
+            })
+            .await;
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    /// Sets up a board holding only the given discs.
+    fn board_with(discs: &[(&str, u8)]) -> Board {
+        let mut cells = vec![None; (BOARD_SIZE * BOARD_SIZE) as usize];
+        for (name, player_id) in discs {
+            cells[square(name).index()] = Some(*player_id);
+        }
+
+        Board::from_cells(cells)
+    }
+
+    #[tokio::test]
+    async fn server_places_disc_with_flips_and_passes_turn() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = OthelloServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, "d6").await;
+
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+        let events = drain_dispatched_events(&mut receiver);
+        let ServerEvent::DiscPlaced { flipped, .. } = &events[0] else {
+            panic!("Expected the disc to be placed");
+        };
+        assert_eq!(flipped, &vec![square("d5")]);
+        assert!(matches!(
+            events[1..],
+            [
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_placement_that_flips_nothing() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = OthelloServer::new(sender);
+
+        send_move(&mut server, PLAYER_ONE_ID, "a1").await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::NothingFlipped { .. }
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_passes_turn_of_player_without_legal_move() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = OthelloServer::new(sender);
+        // Once X takes f1 the only disc of O left is on a2, which cannot enclose X's disc in the
+        // corner, while X can still take a3
+        server.board = board_with(&[
+            ("a1", PLAYER_ONE_ID),
+            ("a2", PLAYER_TWO_ID),
+            ("g1", PLAYER_TWO_ID),
+            ("h1", PLAYER_ONE_ID),
+        ]);
+
+        send_move(&mut server, PLAYER_ONE_ID, "f1").await;
+
+        assert_eq!(server.current_player, PLAYER_ONE_ID);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::DiscPlaced { .. },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::TurnPassed {
+                    player_id: PLAYER_TWO_ID
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_once_neither_player_can_move() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = OthelloServer::new(sender);
+        server.board = board_with(&[("a1", PLAYER_ONE_ID), ("a2", PLAYER_TWO_ID)]);
+
+        send_move(&mut server, PLAYER_ONE_ID, "a3").await;
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver).last(),
+            Some(ServerEvent::GameOver {
+                outcome: Outcome::WinnerFound {
+                    player_id: PLAYER_ONE_ID
+                }
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_skips_turn_of_timed_out_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = OthelloServer::new(sender);
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::DispatchToClient { .. })
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::TurnStarted {
+                player_id: PLAYER_TWO_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_with_disc_counts() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_board_updated_event(Board::new().get_cells())
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "  a b c d e f g h\n\
+             8 . . . . . . . . 8\n\
+             7 . . . . . . . . 7\n\
+             6 . . . . . . . . 6\n\
+             5 . . . O X . . . 5\n\
+             4 . . . X O . . . 4\n\
+             3 . . . . . . . . 3\n\
+             2 . . . . . . . . 2\n\
+             1 . . . . . . . . 1\n  \
+             a b c d e f g h\n\
+             Discs: X 2 - 2 O\n\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_board_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let mut cells = Board::new().get_cells();
+        cells[0] = Some(PLAYER_ONE_ID);
+        client.handle_board_updated_event(cells).await.unwrap();
+        assert_client_output(
+            output,
+            "X......./......../......../...OX.../...XO.../......../......../........ X3-2O\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_points_out_opponent_discs_but_not_own() {
+        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_disc_placed_event(PLAYER_ONE_ID, square("d6"), vec![square("d5")])
+            .await
+            .unwrap();
+        client
+            .handle_disc_placed_event(PLAYER_TWO_ID, square("c6"), vec![square("d5")])
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "Player O placed a disc on c6, flipping 1 disc(s).\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_turn_passed_event(PLAYER_TWO_ID)
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::SquareTaken {
+                square: square("d4"),
+            })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador O no tiene dónde colocar una ficha, así que pasa.\n\
+             Error: Ya hay una ficha en d4.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_square_handles_errors_until_valid_square_provided() {
+        let input = "z9\nd3\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert_eq!(client.get_square().await.unwrap(), square("d3"));
+        assert_client_output(
+            output,
+            "Square (e.g. d3):\nInvalid square.\nSquare (e.g. d3):\n",
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 1,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer { .. })
+        ));
+    }
+}
@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::othello::{Error, Outcome};
+use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+/// The width and height of the board.
+pub const BOARD_SIZE: u8 = 8;
+
+/// The eight directions a line of discs can run in from a square, as rows and columns.
+const DIRECTIONS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A square on the board, counted from the top left.
+///
+/// Squares are written as a player would enter them, with a letter for the column and a number
+/// for the row counting up from the bottom of the board, such as "d3".
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Square {
+    pub row: u8,
+    pub column: u8,
+}
+
+impl Square {
+    pub const fn new(row: u8, column: u8) -> Square {
+        Square { row, column }
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        (self.row * BOARD_SIZE + self.column) as usize
+    }
+
+    pub(crate) fn from_index(index: usize) -> Square {
+        Square::new(index as u8 / BOARD_SIZE, index as u8 % BOARD_SIZE)
+    }
+
+    /// Whether the square is one of the four corners, where a disc can never be flipped.
+    pub(crate) fn is_corner(&self) -> bool {
+        [0, BOARD_SIZE - 1].contains(&self.row) && [0, BOARD_SIZE - 1].contains(&self.column)
+    }
+
+    /// The next square in the given direction, if it is on the board.
+    fn step(&self, (rows, columns): (i8, i8)) -> Option<Square> {
+        let row = self.row.checked_add_signed(rows)?;
+        let column = self.column.checked_add_signed(columns)?;
+
+        (row < BOARD_SIZE && column < BOARD_SIZE).then_some(Square::new(row, column))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = char::from(b'a' + self.column);
+        write!(f, "{}{}", column, BOARD_SIZE - self.row)
+    }
+}
+
+impl FromStr for Square {
+    type Err = InvalidSquare;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        let (Some(column), Some(row), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(InvalidSquare);
+        };
+        let column = column.to_ascii_lowercase();
+        let row = row.to_digit(10).ok_or(InvalidSquare)? as u8;
+        if !('a'..='h').contains(&column) || !(1..=BOARD_SIZE).contains(&row) {
+            return Err(InvalidSquare);
+        }
+
+        Ok(Square::new(BOARD_SIZE - row, column as u8 - b'a'))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidSquare;
+
+pub struct Board {
+    /// The ID of the player whose disc is on each square, going along each row from the top.
+    cells: Vec<Option<u8>>,
+}
+
+impl Board {
+    /// Sets up the board with two discs for each player on the four centre squares, placed
+    /// diagonally from each other.
+    pub(crate) fn new() -> Board {
+        let mut cells = vec![None; (BOARD_SIZE * BOARD_SIZE) as usize];
+        let centre = BOARD_SIZE / 2;
+        cells[Square::new(centre - 1, centre - 1).index()] = Some(PLAYER_TWO_ID);
+        cells[Square::new(centre, centre).index()] = Some(PLAYER_TWO_ID);
+        cells[Square::new(centre - 1, centre).index()] = Some(PLAYER_ONE_ID);
+        cells[Square::new(centre, centre - 1).index()] = Some(PLAYER_ONE_ID);
+
+        Board::from_cells(cells)
+    }
+
+    /// Holds the discs as they were sent by the server, so that clients can work out moves.
+    pub(crate) fn from_cells(cells: Vec<Option<u8>>) -> Board {
+        Board { cells }
+    }
+
+    pub(crate) fn get_cells(&self) -> Vec<Option<u8>> {
+        self.cells.clone()
+    }
+
+    /// The number of discs the player has on the board.
+    pub(crate) fn count_discs(&self, player_id: u8) -> u8 {
+        count_discs(&self.cells, player_id)
+    }
+
+    /// Finds the discs that would be flipped by the player placing a disc on the square, being
+    /// every line of the other player's discs that the new disc and another of the player's own
+    /// would enclose.
+    pub(crate) fn flips(&self, player_id: u8, square: Square) -> Vec<Square> {
+        if self.cells[square.index()].is_some() {
+            return Vec::new();
+        }
+
+        DIRECTIONS
+            .iter()
+            .flat_map(|&direction| {
+                let mut line = Vec::new();
+                let mut next = square.step(direction);
+                while let Some(current) = next {
+                    match self.cells[current.index()] {
+                        Some(owner) if owner == player_id => return line,
+                        Some(_) => line.push(current),
+                        None => break,
+                    }
+                    next = current.step(direction);
+                }
+
+                Vec::new()
+            })
+            .collect()
+    }
+
+    /// Finds every square the player may place a disc on, which are those that flip at least one
+    /// of the other player's discs.
+    pub(crate) fn legal_moves(&self, player_id: u8) -> Vec<Square> {
+        (0..self.cells.len())
+            .map(Square::from_index)
+            .filter(|square| !self.flips(player_id, *square).is_empty())
+            .collect()
+    }
+
+    /// Places the player's disc on the square, flipping the discs it encloses.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(Vec<Square>)` containing the squares of the discs flipped.
+    /// - `Err(Error)` if the square is taken or the disc would not flip any others.
+    pub(crate) fn place_disc(
+        &mut self,
+        player_id: u8,
+        square: Square,
+    ) -> Result<Vec<Square>, Error> {
+        if self.cells[square.index()].is_some() {
+            return Err(Error::SquareTaken { square });
+        }
+
+        let flipped = self.flips(player_id, square);
+        if flipped.is_empty() {
+            return Err(Error::NothingFlipped { square });
+        }
+
+        self.cells[square.index()] = Some(player_id);
+        for disc in &flipped {
+            self.cells[disc.index()] = Some(player_id);
+        }
+
+        Ok(flipped)
+    }
+
+    /// Determines whether the game has ended, which happens once neither player can place a
+    /// disc.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Outcome)` with the player holding the most discs as the winner, or a draw if they
+    ///   hold the same number.
+    /// - `None` if either player can still move.
+    pub(crate) fn determine_outcome(&self) -> Option<Outcome> {
+        let can_move = [PLAYER_ONE_ID, PLAYER_TWO_ID]
+            .iter()
+            .any(|player_id| !self.legal_moves(*player_id).is_empty());
+        if can_move {
+            return None;
+        }
+
+        let outcome = match self
+            .count_discs(PLAYER_ONE_ID)
+            .cmp(&self.count_discs(PLAYER_TWO_ID))
+        {
+            Ordering::Greater => Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID,
+            },
+            Ordering::Less => Outcome::WinnerFound {
+                player_id: PLAYER_TWO_ID,
+            },
+            Ordering::Equal => Outcome::Draw,
+        };
+
+        Some(outcome)
+    }
+}
+
+/// The number of discs the player has among the cells.
+pub(crate) fn count_discs(cells: &[Option<u8>], player_id: u8) -> u8 {
+    cells
+        .iter()
+        .filter(|cell| **cell == Some(player_id))
+        .count() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(name: &str) -> Square {
+        name.parse().unwrap()
+    }
+
+    fn squares(names: &[&str]) -> Vec<Square> {
+        names.iter().map(|name| square(name)).collect()
+    }
+
+    /// Sets up a board holding only the given discs.
+    fn board_with(discs: &[(&str, u8)]) -> Board {
+        let mut cells = vec![None; (BOARD_SIZE * BOARD_SIZE) as usize];
+        for (name, player_id) in discs {
+            cells[square(name).index()] = Some(*player_id);
+        }
+
+        Board::from_cells(cells)
+    }
+
+    #[test]
+    fn square_is_parsed_from_how_it_is_displayed() {
+        assert_eq!(square("a1"), Square::new(7, 0));
+        assert_eq!(square("H8"), Square::new(0, 7));
+        assert_eq!(Square::new(5, 3).to_string(), "d3");
+        assert_eq!("i1".parse::<Square>(), Err(InvalidSquare));
+        assert_eq!("a0".parse::<Square>(), Err(InvalidSquare));
+    }
+
+    #[test]
+    fn new_board_has_four_legal_moves_for_first_player() {
+        let board = Board::new();
+
+        assert_eq!(board.count_discs(PLAYER_ONE_ID), 2);
+        assert_eq!(board.count_discs(PLAYER_TWO_ID), 2);
+        assert_eq!(
+            board.legal_moves(PLAYER_ONE_ID),
+            squares(&["d6", "c5", "f4", "e3"])
+        );
+    }
+
+    #[test]
+    fn placing_disc_flips_every_enclosed_line() {
+        let mut board = board_with(&[
+            ("a1", PLAYER_ONE_ID),
+            ("b2", PLAYER_TWO_ID),
+            ("c3", PLAYER_TWO_ID),
+            ("d2", PLAYER_ONE_ID),
+            ("d3", PLAYER_TWO_ID),
+            ("e4", PLAYER_TWO_ID),
+            ("f4", PLAYER_TWO_ID),
+        ]);
+
+        // The line towards f4 is not closed by a disc of player one, so stays as it is
+        let mut flipped = board.place_disc(PLAYER_ONE_ID, square("d4")).unwrap();
+        flipped.sort_by_key(Square::index);
+        assert_eq!(flipped, squares(&["c3", "d3", "b2"]));
+        assert_eq!(board.count_discs(PLAYER_ONE_ID), 6);
+        assert_eq!(board.count_discs(PLAYER_TWO_ID), 2);
+    }
+
+    #[test]
+    fn placing_disc_that_flips_nothing_is_refused() {
+        let mut board = Board::new();
+
+        assert!(matches!(
+            board.place_disc(PLAYER_ONE_ID, square("a1")),
+            Err(Error::NothingFlipped { .. })
+        ));
+        assert!(matches!(
+            board.place_disc(PLAYER_ONE_ID, square("d4")),
+            Err(Error::SquareTaken { .. })
+        ));
+        assert_eq!(board.get_cells(), Board::new().get_cells());
+    }
+
+    #[test]
+    fn game_ends_once_neither_player_can_move() {
+        let board = board_with(&[("a1", PLAYER_ONE_ID), ("h8", PLAYER_TWO_ID)]);
+        assert_eq!(board.determine_outcome(), Some(Outcome::Draw));
+
+        let board = board_with(&[("a1", PLAYER_ONE_ID), ("a2", PLAYER_ONE_ID)]);
+        assert_eq!(
+            board.determine_outcome(),
+            Some(Outcome::WinnerFound {
+                player_id: PLAYER_ONE_ID
+            })
+        );
+
+        assert_eq!(Board::new().determine_outcome(), None);
+    }
+}
@@ -0,0 +1,77 @@
+use crate::othello::board::{Board, Square};
+
+/// Chooses a square for the given player to place a disc on, based on the discs on the board.
+///
+/// Corners are taken whenever possible, as a disc there can never be flipped back. Otherwise the
+/// move flipping the most discs is chosen.
+///
+/// # Returns
+///
+/// - `Some(Square)` containing the square to place a disc on.
+/// - `None` if the player has no move to make.
+pub(crate) fn choose_move(cells: &[Option<u8>], player_id: u8) -> Option<Square> {
+    let board = Board::from_cells(cells.to_vec());
+    let legal_moves = board.legal_moves(player_id);
+
+    let corner = legal_moves.iter().find(|square| square.is_corner());
+    let most_flips = legal_moves
+        .iter()
+        .max_by_key(|square| board.flips(player_id, **square).len());
+
+    corner.or(most_flips).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::othello::board::BOARD_SIZE;
+    use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+    use super::*;
+
+    fn square(name: &str) -> Square {
+        name.parse().unwrap()
+    }
+
+    fn cells_with(discs: &[(&str, u8)]) -> Vec<Option<u8>> {
+        let mut cells = vec![None; (BOARD_SIZE * BOARD_SIZE) as usize];
+        for (name, player_id) in discs {
+            cells[square(name).index()] = Some(*player_id);
+        }
+
+        cells
+    }
+
+    #[test]
+    fn choose_move_takes_corner() {
+        let cells = cells_with(&[
+            ("c3", PLAYER_ONE_ID),
+            ("b2", PLAYER_TWO_ID),
+            ("h5", PLAYER_ONE_ID),
+            ("g5", PLAYER_TWO_ID),
+            ("f5", PLAYER_TWO_ID),
+            ("e5", PLAYER_TWO_ID),
+        ]);
+
+        assert_eq!(choose_move(&cells, PLAYER_ONE_ID), Some(square("a1")));
+    }
+
+    #[test]
+    fn choose_move_flips_most_discs() {
+        let cells = cells_with(&[
+            ("b2", PLAYER_ONE_ID),
+            ("b3", PLAYER_TWO_ID),
+            ("h5", PLAYER_ONE_ID),
+            ("g5", PLAYER_TWO_ID),
+            ("f5", PLAYER_TWO_ID),
+        ]);
+
+        assert_eq!(choose_move(&cells, PLAYER_ONE_ID), Some(square("e5")));
+    }
+
+    #[test]
+    fn choose_move_returns_none_without_moves() {
+        let cells = cells_with(&[("a1", PLAYER_TWO_ID)]);
+
+        assert_eq!(choose_move(&cells, PLAYER_TWO_ID), None);
+    }
+}
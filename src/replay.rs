@@ -0,0 +1,343 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::{self, Instant};
+
+use crate::client::{Frontend, OutputStyle, TerminalFrontend, UserInput};
+use crate::game::{self, ClientContext, ClientKind, ErasedEvent, GameClientError};
+use crate::locale::Locale;
+
+/// Incremented whenever a change is made to the layout of replay files.
+pub const REPLAY_VERSION: u16 = 6;
+/// The longest pause made between events during playback, so that slow moves are not sat through.
+const MAX_REPLAY_PAUSE: Duration = Duration::from_secs(2);
+
+/// The first line of a replay file, identifying what was recorded.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ReplayHeader {
+    version: u16,
+    game: String,
+    /// The options the game was played with, as sent to clients when it started, or null if it
+    /// has none.
+    #[serde(default)]
+    config: serde_json::Value,
+    /// The seed the game's chance was drawn from, for playing it out again with `--seed`.
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+/// A line of a replay file following the header, holding a single game event or rejected move.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ReplayEntry {
+    /// Milliseconds since the game began.
+    elapsed_ms: u64,
+    #[serde(flatten)]
+    recorded: Recorded,
+}
+
+/// What happened at a point in a replay, keyed by its kind within the line.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Recorded {
+    Event(ErasedEvent),
+    /// An invalid move made by the player whose turn it was, leaving them the given number of
+    /// attempts before forfeiting the game.
+    RejectedMove {
+        player_id: u8,
+        attempts_remaining: u8,
+    },
+}
+
+/// Writes the game events seen by spectators to a replay file, one JSON line per event.
+pub(crate) struct ReplayRecorder<W: io::Write> {
+    writer: W,
+    started_at: Instant,
+}
+
+impl<W: io::Write> ReplayRecorder<W> {
+    pub(crate) fn new(writer: W) -> ReplayRecorder<W> {
+        ReplayRecorder {
+            writer,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Writes the header of the replay, timing each following event from this point.
+    ///
+    /// # Parameters
+    ///
+    /// - `config`: The options the game is played with, as given by `GameServer::config`.
+    /// - `seed`: The seed the game's chance is drawn from.
+    pub(crate) fn begin(
+        &mut self,
+        game_id: &str,
+        config: &[u8],
+        seed: u64,
+    ) -> Result<(), ReplayError> {
+        self.started_at = Instant::now();
+        self.write_line(&ReplayHeader {
+            version: REPLAY_VERSION,
+            game: String::from(game_id),
+            // Games without options send none, which is recorded as null
+            config: serde_json::from_slice(config).unwrap_or_default(),
+            seed: Some(seed),
+        })
+    }
+
+    pub(crate) fn record(&mut self, event: &ErasedEvent) -> Result<(), ReplayError> {
+        self.write_entry(Recorded::Event(event.clone()))
+    }
+
+    pub(crate) fn record_rejected_move(
+        &mut self,
+        player_id: u8,
+        attempts_remaining: u8,
+    ) -> Result<(), ReplayError> {
+        self.write_entry(Recorded::RejectedMove {
+            player_id,
+            attempts_remaining,
+        })
+    }
+
+    fn write_entry(&mut self, recorded: Recorded) -> Result<(), ReplayError> {
+        self.write_line(&ReplayEntry {
+            elapsed_ms: self.started_at.elapsed().as_millis() as u64,
+            recorded,
+        })
+    }
+
+    fn write_line(&mut self, line: &impl Serialize) -> Result<(), ReplayError> {
+        serde_json::to_writer(&mut self.writer, line)?;
+        writeln!(self.writer)?;
+        Ok(self.writer.flush()?)
+    }
+}
+
+/// Plays back a recorded game, showing it as a spectator would have seen it.
+///
+/// Events are shown with the same pauses between them as when the game was played, up to a limit
+/// so that the replay does not dwell on slow moves.
+pub async fn play_replay<O: io::Write + Send + Sync>(
+    input: impl io::BufRead,
+    output: O,
+    output_style: OutputStyle,
+    color: bool,
+    locale: Locale,
+    screen_reader: bool,
+) -> Result<(), ReplayError> {
+    let mut lines = input.lines();
+    let header: ReplayHeader = match lines.next() {
+        Some(line) => serde_json::from_str(&line?)?,
+        None => return Err(ReplayError::Empty),
+    };
+    if header.version != REPLAY_VERSION {
+        return Err(ReplayError::UnsupportedVersion(header.version));
+    }
+    let game = game::find_game(&header.game).ok_or(ReplayError::UnknownGame(header.game))?;
+
+    // The receiver is held so that the game client is able to announce the game being over
+    let (game_sender, _game_receiver) = mpsc::channel(10);
+    let frontend = Arc::new(TerminalFrontend::new(
+        UserInput::empty(),
+        Arc::new(Mutex::new(output)),
+    ));
+    let mut client = game.make_client(ClientContext {
+        frontend: Arc::clone(&frontend) as _,
+        channel: game_sender,
+        kind: ClientKind::Spectator,
+    });
+    client.set_output_style(output_style);
+    client.set_color(color);
+    client.set_locale(locale);
+    client.set_screen_reader(screen_reader);
+    let config = match header.config {
+        serde_json::Value::Null => Vec::new(),
+        config => serde_json::to_vec(&config)?,
+    };
+    client.handle_game_started_event(config).await?;
+
+    let mut previous_elapsed_ms = 0;
+    for line in lines {
+        let entry: ReplayEntry = serde_json::from_str(&line?)?;
+        let pause = Duration::from_millis(entry.elapsed_ms.saturating_sub(previous_elapsed_ms));
+        time::sleep(pause.min(MAX_REPLAY_PAUSE)).await;
+        previous_elapsed_ms = entry.elapsed_ms;
+
+        match entry.recorded {
+            Recorded::Event(event) => client.handle_event(event).await?,
+            // Spectators are only told of a player running out of attempts, not each attempt
+            Recorded::RejectedMove {
+                player_id,
+                attempts_remaining: 0,
+            } => {
+                let player = client.get_player_name(player_id);
+                let message = locale.styled_message(
+                    "rejected-moves-exceeded",
+                    output_style,
+                    &[("player", &player)],
+                );
+                frontend.show_message(&message, None)?;
+            }
+            Recorded::RejectedMove { .. } => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ReplayError {
+    #[error("Failed to read or write the replay")]
+    Io(#[from] io::Error),
+    #[error("The replay is not in the expected format")]
+    Format(#[from] serde_json::Error),
+    #[error("Failed to show the game")]
+    Game(#[from] GameClientError),
+    #[error("The replay is empty")]
+    Empty,
+    #[error("Replays of version {0} are not supported, only version {REPLAY_VERSION}")]
+    UnsupportedVersion(u16),
+    #[error("The game \"{0}\" is not known")]
+    UnknownGame(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::PLAYER_ONE_ID;
+    use crate::tic_tac_toe::{self, BoardConfig, ServerEvent};
+
+    fn record_game(board_config: BoardConfig, events: &[ServerEvent]) -> Vec<u8> {
+        let mut recorder = ReplayRecorder::new(Vec::new());
+        let config = game::serialize_config(board_config);
+        recorder.begin(tic_tac_toe::GAME_ID, &config, 42).unwrap();
+        for event in events {
+            recorder.record(&game::serialize_event(event)).unwrap();
+        }
+
+        recorder.writer
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recorder_writes_header_then_timed_events() {
+        let mut recorder = ReplayRecorder::new(Vec::new());
+        recorder
+            .begin(
+                tic_tac_toe::GAME_ID,
+                &game::serialize_config(BoardConfig::default()),
+                42,
+            )
+            .unwrap();
+        time::advance(Duration::from_millis(1500)).await;
+        recorder
+            .record(&game::serialize_event(ServerEvent::PlayerTurn {
+                player_id: PLAYER_ONE_ID,
+            }))
+            .unwrap();
+
+        let replay = String::from_utf8(recorder.writer).unwrap();
+        let lines: Vec<&str> = replay.lines().collect();
+        assert_eq!(
+            lines[0],
+            r#"{"version":6,"game":"tic-tac-toe","config":{"size":3,"variant":"Standard","win_length":3},"seed":42}"#
+        );
+        assert_eq!(
+            lines[1],
+            r#"{"elapsed_ms":1500,"event":{"kind":"PlayerTurn","payload":{"PlayerTurn":{"player_id":1}},"sequence":0,"version":7}}"#
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_shows_recorded_game_to_user() {
+        let mut board_cells = vec![None; 9];
+        board_cells[4] = Some(PLAYER_ONE_ID);
+        let replay = record_game(
+            BoardConfig::default(),
+            &[ServerEvent::BoardUpdated {
+                board_config: BoardConfig::default(),
+                board_cells,
+            }],
+        );
+
+        let mut output = Vec::new();
+        play_replay(
+            &replay[..],
+            &mut output,
+            OutputStyle::Terse,
+            false,
+            Locale::English,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Started.\n123/4X6/789\n"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn replay_shows_player_running_out_of_attempts() {
+        let mut recorder = ReplayRecorder::new(Vec::new());
+        let config = game::serialize_config(BoardConfig::default());
+        recorder.begin(tic_tac_toe::GAME_ID, &config, 42).unwrap();
+        for attempts_remaining in [1, 0] {
+            recorder
+                .record_rejected_move(PLAYER_ONE_ID, attempts_remaining)
+                .unwrap();
+        }
+
+        let mut output = Vec::new();
+        play_replay(
+            &recorder.writer[..],
+            &mut output,
+            OutputStyle::Terse,
+            false,
+            Locale::English,
+            false,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Started.\nX forfeits, too many invalid moves.\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_of_unknown_game_rejected() {
+        let replay = br#"{"version":6,"game":"chess","config":null}"#;
+
+        let result = play_replay(
+            &replay[..],
+            Vec::new(),
+            OutputStyle::Standard,
+            false,
+            Locale::English,
+            false,
+        )
+        .await;
+        assert!(matches!(result, Err(ReplayError::UnknownGame(game)) if game == "chess"));
+    }
+
+    #[tokio::test]
+    async fn replay_of_unsupported_version_rejected() {
+        let replay = br#"{"version":99,"game":"tic-tac-toe"}"#;
+
+        let result = play_replay(
+            &replay[..],
+            Vec::new(),
+            OutputStyle::Standard,
+            false,
+            Locale::English,
+            false,
+        )
+        .await;
+        assert!(matches!(result, Err(ReplayError::UnsupportedVersion(99))));
+    }
+}
@@ -0,0 +1,963 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, ServerChannel, SimultaneousMoves,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+
+/// Identifies Rock Paper Scissors among the games in `game::GAMES`.
+pub const GAME_ID: &str = "rock-paper-scissors";
+
+/// The number of rounds a player must win to win the game, making it the best of three.
+pub const ROUNDS_TO_WIN: u8 = 2;
+
+/// Rock Paper Scissors, where both players choose their hand at the same time each round. Drawn
+/// rounds are played again until one player has won enough rounds to win the game.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RockPaperScissors;
+
+impl GameDescriptor for RockPaperScissors {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(RockPaperScissorsServer::new(channel))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(RockPaperScissorsClient::new(
+                frontend,
+                channel,
+                LocalClient {},
+            )),
+            ClientKind::Online { id } => Box::new(RockPaperScissorsClient::new(
+                frontend,
+                channel,
+                OnlineClient { id },
+            )),
+            ClientKind::Spectator => Box::new(RockPaperScissorsClient::new(
+                frontend,
+                channel,
+                SpectatorClient {},
+            )),
+            ClientKind::Bot { id, move_delay } => Box::new(RockPaperScissorsClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Hand {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Hand {
+    pub const ALL: [Hand; 3] = [Hand::Rock, Hand::Paper, Hand::Scissors];
+
+    pub fn beats(&self, other: Hand) -> bool {
+        matches!(
+            (self, other),
+            (Hand::Rock, Hand::Scissors)
+                | (Hand::Paper, Hand::Rock)
+                | (Hand::Scissors, Hand::Paper)
+        )
+    }
+}
+
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Hand::Rock => "rock",
+            Hand::Paper => "paper",
+            Hand::Scissors => "scissors",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Hand {
+    type Err = InvalidHand;
+
+    /// Reads a hand by its name, or just its first letter.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rock" | "r" => Ok(Hand::Rock),
+            "paper" | "p" => Ok(Hand::Paper),
+            "scissors" | "s" => Ok(Hand::Scissors),
+            _ => Err(InvalidHand),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidHand;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// Asks the players to choose their hand for the round, given the rounds each has won so far.
+    RoundStarted {
+        round: u8,
+        scores: BTreeMap<u8, u8>,
+    },
+    /// Tells everyone a player has chosen, without revealing their hand until every player has.
+    HandChosen {
+        player_id: u8,
+    },
+    /// Reveals the hands chosen once the round is over. A player who ran out of time before
+    /// choosing has no hand.
+    RoundPlayed {
+        hands: BTreeMap<u8, Hand>,
+        winner: Option<u8>,
+    },
+    GameOver {
+        winner: u8,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("You have already chosen your hand for this round.")]
+    AlreadyChosen,
+    #[error("It's not your turn.")]
+    UnexpectedPlayer,
+}
+
+pub struct RockPaperScissorsServer {
+    round: u8,
+    scores: BTreeMap<u8, u8>,
+    hands: SimultaneousMoves<Hand>,
+    server_channel: ServerChannel<ServerEvent>,
+    /// Set once the outcome has been sent, after which any hands still arriving are ignored.
+    game_over: bool,
+}
+
+impl RockPaperScissorsServer {
+    pub fn new(server_channel: Sender<GameServerEvent>) -> RockPaperScissorsServer {
+        let player_ids = [PLAYER_ONE_ID, PLAYER_TWO_ID];
+        RockPaperScissorsServer {
+            round: 1,
+            scores: player_ids.iter().map(|player_id| (*player_id, 0)).collect(),
+            hands: SimultaneousMoves::new(&player_ids),
+            server_channel: ServerChannel::new(server_channel),
+            game_over: false,
+        }
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_round_started_event(&self, dispatch_mode: DispatchMode) {
+        let event = ServerEvent::RoundStarted {
+            round: self.round,
+            scores: self.scores.clone(),
+        };
+        self.dispatch_event(dispatch_mode, event).await
+    }
+
+    async fn dispatch_game_over_event(&mut self, winner: u8) {
+        self.game_over = true;
+        self.dispatch_event(DispatchMode::AllPlayers, ServerEvent::GameOver { winner })
+            .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver {
+                winner: Some(winner),
+            })
+            .await
+    }
+
+    /// Reveals the hands chosen and scores the round, then either starts the next round or ends
+    /// the game.
+    async fn finish_round(&mut self, hands: BTreeMap<u8, Hand>, winner: Option<u8>) {
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::RoundPlayed { hands, winner },
+        )
+        .await;
+
+        if let Some(winner) = winner {
+            let score = self.scores.entry(winner).or_default();
+            *score += 1;
+            if *score >= ROUNDS_TO_WIN {
+                self.dispatch_game_over_event(winner).await;
+                return;
+            }
+        }
+
+        self.round += 1;
+        self.dispatch_round_started_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn handle_hand_chosen_event(&mut self, player_id: u8, hand: Hand) {
+        if !self.hands.commit(player_id, hand) {
+            self.dispatch_event(
+                DispatchMode::SinglePlayer { player_id },
+                ServerEvent::ErrorOccurred {
+                    error: Error::AlreadyChosen,
+                },
+            )
+            .await;
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::HandChosen { player_id },
+        )
+        .await;
+
+        match self.hands.reveal() {
+            Some(hands) => {
+                let winner = hands
+                    .iter()
+                    .find(|(player_id, hand)| {
+                        hand.beats(hands[&get_alternative_player_id(**player_id)])
+                    })
+                    .map(|(player_id, _)| *player_id);
+                self.finish_round(hands, winner).await;
+            }
+            // The turn clock is only started on the player holding up the round, so that neither
+            // player has to rush while both are still choosing
+            None => {
+                for player_id in self.hands.waiting_on() {
+                    self.server_channel
+                        .send(GameServerEvent::TurnStarted { player_id })
+                        .await;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl GameServer for RockPaperScissorsServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    async fn begin(&self) {
+        self.dispatch_round_started_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::HandChosen { player_id, hand } = event;
+
+        self.handle_hand_chosen_event(player_id, hand).await;
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || self.hands.has_moved(player_id) {
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::TurnTimedOut { player_id, action },
+        )
+        .await;
+
+        let opponent_id = get_alternative_player_id(player_id);
+        match action {
+            TimeoutAction::Forfeit => self.dispatch_game_over_event(opponent_id).await,
+            // Skipping a round that both players must play loses it
+            TimeoutAction::SkipTurn => {
+                let hands = self.hands.take();
+                self.finish_round(hands, Some(opponent_id)).await;
+            }
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        if !self.hands.has_moved(player_id) {
+            self.dispatch_round_started_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+        }
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_round_started_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    HandChosen { player_id: u8, hand: Hand },
+}
+
+pub struct RockPaperScissorsClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+}
+
+impl<F, C> RockPaperScissorsClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> RockPaperScissorsClient<F, C> {
+        RockPaperScissorsClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    fn get_hand_name(&self, hand: Hand) -> String {
+        let key = match hand {
+            Hand::Rock => "rock-paper-scissors-hand-rock",
+            Hand::Paper => "rock-paper-scissors-hand-paper",
+            Hand::Scissors => "rock-paper-scissors-hand-scissors",
+        };
+        self.user_output.locale().message(key, &[])
+    }
+
+    fn write_round_started(&self, round: u8, scores: &BTreeMap<u8, u8>) -> Result<(), io::Error> {
+        let score = |player_id| scores.get(&player_id).copied().unwrap_or_default();
+        let (player_one_score, player_two_score) = (score(PLAYER_ONE_ID), score(PLAYER_TWO_ID));
+        self.user_output.write_message(
+            "rock-paper-scissors-round-started",
+            &[
+                ("round", &round),
+                ("first", &player_one_score),
+                ("second", &player_two_score),
+            ],
+        )
+    }
+
+    async fn handle_hand_chosen_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            self.user_output
+                .write_message("rock-paper-scissors-waiting-for-hand", &[])?;
+        } else {
+            let player_icon = self.get_player_icon_by_id(player_id);
+            self.user_output.write_message(
+                "rock-paper-scissors-hand-chosen",
+                &[("player", &player_icon)],
+            )?;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_round_played_event(
+        &self,
+        hands: BTreeMap<u8, Hand>,
+        winner: Option<u8>,
+    ) -> Result<(), GameClientError> {
+        let hands = hands
+            .iter()
+            .map(|(player_id, hand)| {
+                self.user_output.message(
+                    "rock-paper-scissors-hand-shown",
+                    &[
+                        ("player", &self.get_player_icon_by_id(*player_id)),
+                        ("hand", &self.get_hand_name(*hand)),
+                    ],
+                )
+            })
+            .reduce(|first, second| {
+                self.user_output.message(
+                    "rock-paper-scissors-hands-shown",
+                    &[("first", &first), ("second", &second)],
+                )
+            });
+        let result = match winner {
+            None => self
+                .user_output
+                .message("rock-paper-scissors-round-drawn", &[]),
+            Some(winner) => self.user_output.message(
+                "rock-paper-scissors-round-won",
+                &[("player", &self.get_player_icon_by_id(winner))],
+            ),
+        };
+
+        match hands {
+            Some(hands) => self.user_output.write_message(
+                "rock-paper-scissors-round-played",
+                &[("hands", &hands), ("result", &result)],
+            )?,
+            None => self.user_output.write_message(
+                "rock-paper-scissors-round-played-unseen",
+                &[("result", &result)],
+            )?,
+        };
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(&mut self, winner: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(winner);
+        self.user_output
+            .write_message("game-won", &[("player", &player_icon)])?;
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        let key = match error {
+            Error::AlreadyChosen => "rock-paper-scissors-error-already-chosen",
+            Error::UnexpectedPlayer => "rock-paper-scissors-error-unexpected-player",
+        };
+        self.user_output.write_error(key, &[])?;
+
+        Ok(())
+    }
+
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "rock-paper-scissors-turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn choose_player_hand(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let hand = self.get_hand().await?;
+        self.send_hand(player_id, hand).await
+    }
+
+    async fn send_hand(&mut self, player_id: u8, hand: Hand) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::HandChosen { player_id, hand },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    async fn get_hand(&mut self) -> Result<Hand, io::Error> {
+        loop {
+            self.user_output
+                .write_message("rock-paper-scissors-hand-prompt", &[])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.parse() {
+                Err(_) => self
+                    .user_output
+                    .write_message("rock-paper-scissors-not-a-hand", &[])?,
+                Ok(hand) => return Ok(hand),
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_round_started_event(
+        &mut self,
+        round: u8,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for RockPaperScissorsClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "rock-paper-scissors-game-started-local",
+            &[("rounds", &ROUNDS_TO_WIN)],
+        )
+    }
+
+    async fn handle_round_started_event(
+        &mut self,
+        round: u8,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        self.write_round_started(round, &scores)?;
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let player_icon = self.get_player_icon_by_id(player_id);
+            self.user_output
+                .write_message("player-turn", &[("player", &player_icon)])?;
+            self.choose_player_hand(player_id).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for RockPaperScissorsClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message(
+            "rock-paper-scissors-game-started-online",
+            &[("rounds", &ROUNDS_TO_WIN)],
+        )
+    }
+
+    async fn handle_round_started_event(
+        &mut self,
+        round: u8,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        self.write_round_started(round, &scores)?;
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.choose_player_hand(self.client_type.id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for RockPaperScissorsClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_round_started_event(
+        &mut self,
+        round: u8,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        self.write_round_started(round, &scores)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for RockPaperScissorsClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    /// Bots choose their hand at random, as there is no better strategy.
+    async fn handle_round_started_event(
+        &mut self,
+        _round: u8,
+        _scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        self.client_type.wait_to_move().await;
+        let hand = *Hand::ALL.choose(&mut rand::thread_rng()).unwrap();
+        self.send_hand(self.client_type.id, hand).await
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for RockPaperScissorsClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::RoundStarted { round, scores } => {
+                self.handle_round_started_event(round, scores).await
+            }
+            ServerEvent::HandChosen { player_id } => self.handle_hand_chosen_event(player_id).await,
+            ServerEvent::RoundPlayed { hands, winner } => {
+                self.handle_round_played_event(hands, winner).await
+            }
+            ServerEvent::GameOver { winner } => self.handle_game_over_event(winner).await,
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        RockPaperScissorsClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(2);
+        let client = RockPaperScissorsClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    async fn choose(server: &mut RockPaperScissorsServer, player_id: u8, hand: Hand) {
+        server
+            .handle_event(ClientEvent::HandChosen { player_id, hand })
+            .await;
+    }
+
+    /// Collects everything sent by the server, leaving out who each event was dispatched to.
+    fn drain_server_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<GameServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+
+        events
+    }
+
+    fn dispatched_events(events: &[GameServerEvent]) -> Vec<ServerEvent> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameServerEvent::DispatchToClient { event, .. } => {
+                    Some(game::deserialize_event(event.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn each_hand_beats_one_other() {
+        for hand in Hand::ALL {
+            let beaten = Hand::ALL.iter().filter(|other| hand.beats(**other)).count();
+            assert_eq!(beaten, 1, "{} beats {} hands", hand, beaten);
+        }
+        assert!(Hand::Rock.beats(Hand::Scissors));
+        assert_eq!("P\n".parse(), Ok(Hand::Paper));
+    }
+
+    #[tokio::test]
+    async fn server_keeps_hand_hidden_until_both_chosen() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = RockPaperScissorsServer::new(sender);
+
+        choose(&mut server, PLAYER_ONE_ID, Hand::Rock).await;
+        let events = drain_server_events(&mut receiver);
+        assert!(matches!(
+            dispatched_events(&events)[..],
+            [ServerEvent::HandChosen {
+                player_id: PLAYER_ONE_ID
+            }]
+        ));
+        // The clock starts on the player yet to choose
+        assert!(matches!(
+            events.last(),
+            Some(GameServerEvent::TurnStarted {
+                player_id: PLAYER_TWO_ID
+            })
+        ));
+        assert!(!events
+            .iter()
+            .any(|event| format!("{:?}", event).contains("Rock")));
+
+        choose(&mut server, PLAYER_TWO_ID, Hand::Scissors).await;
+        let events = drain_server_events(&mut receiver);
+        let ServerEvent::RoundPlayed { hands, winner } = &dispatched_events(&events)[1] else {
+            panic!("Expected the round to be played");
+        };
+        assert_eq!(hands[&PLAYER_ONE_ID], Hand::Rock);
+        assert_eq!(*winner, Some(PLAYER_ONE_ID));
+        assert_eq!(server.scores[&PLAYER_ONE_ID], 1);
+    }
+
+    #[tokio::test]
+    async fn server_refuses_second_hand_in_round() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = RockPaperScissorsServer::new(sender);
+
+        choose(&mut server, PLAYER_ONE_ID, Hand::Rock).await;
+        drain_server_events(&mut receiver);
+        choose(&mut server, PLAYER_ONE_ID, Hand::Paper).await;
+
+        assert!(matches!(
+            dispatched_events(&drain_server_events(&mut receiver))[..],
+            [ServerEvent::ErrorOccurred {
+                error: Error::AlreadyChosen
+            }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_replays_drawn_round_and_ends_game_after_rounds_won() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(50);
+        let mut server = RockPaperScissorsServer::new(sender);
+
+        choose(&mut server, PLAYER_ONE_ID, Hand::Rock).await;
+        choose(&mut server, PLAYER_TWO_ID, Hand::Rock).await;
+        assert_eq!(server.round, 2);
+        assert_eq!(server.scores[&PLAYER_ONE_ID], 0);
+
+        for _ in 0..ROUNDS_TO_WIN {
+            choose(&mut server, PLAYER_ONE_ID, Hand::Rock).await;
+            choose(&mut server, PLAYER_TWO_ID, Hand::Paper).await;
+        }
+
+        assert!(server.game_over);
+        assert!(matches!(
+            drain_server_events(&mut receiver).last(),
+            Some(GameServerEvent::GameOver {
+                winner: Some(PLAYER_TWO_ID)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_gives_round_to_opponent_of_player_skipped_for_time() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = RockPaperScissorsServer::new(sender);
+
+        choose(&mut server, PLAYER_ONE_ID, Hand::Rock).await;
+        // Only the player yet to choose can run out of time
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+        assert_eq!(server.round, 1);
+        server
+            .handle_turn_timed_out(PLAYER_TWO_ID, TimeoutAction::SkipTurn)
+            .await;
+
+        assert_eq!(server.round, 2);
+        assert_eq!(server.scores[&PLAYER_ONE_ID], 1);
+        assert!(!server.hands.has_moved(PLAYER_ONE_ID));
+        drain_server_events(&mut receiver);
+    }
+
+    #[tokio::test]
+    async fn client_handles_round_played_event() {
+        let (client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        let hands = BTreeMap::from([(PLAYER_ONE_ID, Hand::Rock), (PLAYER_TWO_ID, Hand::Paper)]);
+        client
+            .handle_round_played_event(hands.clone(), Some(PLAYER_TWO_ID))
+            .await
+            .unwrap();
+        client.handle_round_played_event(hands, None).await.unwrap();
+
+        assert_client_output(
+            output,
+            "Player X chose rock and Player O chose paper. Player O wins the round!\n\
+             Player X chose rock and Player O chose paper. It's a draw, so the round will be played again.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_handles_round_played_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let hands = BTreeMap::from([(PLAYER_ONE_ID, Hand::Scissors)]);
+        client
+            .handle_round_played_event(hands, Some(PLAYER_ONE_ID))
+            .await
+            .unwrap();
+
+        assert_client_output(output, "X:scissors, X wins round.\n");
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+        client.set_locale(Locale::Spanish);
+
+        let hands = BTreeMap::from([(PLAYER_ONE_ID, Hand::Rock), (PLAYER_TWO_ID, Hand::Paper)]);
+        client
+            .handle_round_played_event(hands, Some(PLAYER_TWO_ID))
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::AlreadyChosen)
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "X eligió piedra y O eligió papel. ¡El jugador O gana la ronda!\n\
+             Error: Ya has elegido tu mano en esta ronda.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_asks_each_local_player_for_their_hand() {
+        let input = "rock\nlizard\ns\n".as_bytes();
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        client
+            .handle_round_started_event(1, BTreeMap::new())
+            .await
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Round 1, X 0-0 O.\n\
+             X to move.\nHand (r/p/s):\n\
+             O to move.\nHand (r/p/s):\nInvalid hand.\nHand (r/p/s):\n",
+        );
+        for (player_id, hand) in [(PLAYER_ONE_ID, Hand::Rock), (PLAYER_TWO_ID, Hand::Scissors)] {
+            assert_eq!(
+                receiver.recv().await,
+                Some(GameClientEvent::DispatchToServer {
+                    event: game::serialize_event(ClientEvent::HandChosen { player_id, hand })
+                })
+            );
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_round_started_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 2,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+
+        client
+            .handle_round_started_event(1, BTreeMap::new())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer { .. })
+        ));
+    }
+}
@@ -1,113 +1,566 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
 use async_trait::async_trait;
+use futures_util::FutureExt;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use tokio::join;
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
+use crate::connection::envelope::{self, Envelope};
 use crate::connection::{Connection, ErrorCategory, HasErrorCategory, ReadError, WriteError};
-use crate::game::{Game, GameServer, GameServerEvent};
-pub use crate::server::player::{get_alternative_player_id, Player, PLAYER_ONE_ID, PLAYER_TWO_ID};
-use crate::tic_tac_toe::TicTacToeServer;
+use crate::game::rand::GameRng;
+use crate::game::{self, AnyGameServer, ErasedEvent, GameDescriptor, GameServerEvent, Seats};
+use crate::lobby::{BracketMatch, JoinToken, Lobby};
+use crate::replay::ReplayRecorder;
+pub use crate::server::builder::{
+    BuildError, ChannelCapacities, ServerBuilder, ServerHandle, ServerStopped, Timeouts, Transport,
+};
+use crate::server::game_events::GameEvents;
+pub use crate::server::mark::{InvalidMark, PlayerMark, PlayerMarks};
+use crate::server::pacing::Pacing;
+pub use crate::server::pacing::ThinkTime;
+pub(crate) use crate::server::player::player_event_received;
+pub use crate::server::player::{
+    get_alternative_player_id, get_next_player_id, FirstPlayer, InvalidName, Player, PlayerName,
+    BOTH_SEATS, MAX_NAME_LENGTH, MAX_PLAYERS, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+pub use crate::server::queue::BackpressurePolicy;
+pub(crate) use crate::server::queue::{event_queue, EventReceiver, EventSender, SendError};
+use crate::server::rematch::Rematch;
+pub use crate::server::rematch::RematchResponse;
+pub use crate::server::save::{RestoredGame, SaveError, SavedGame, SAVE_VERSION};
+use crate::server::spectator::Spectator;
+pub use crate::server::spectator::SpectatorConfig;
+use crate::server::timer::{Countdown, GameTimer};
+pub use crate::server::timer::{Handicap, TimeoutAction, TurnTimer};
+use crate::stats::{self, GameRecord, MatchResult, RatingChange, ResultExporter, StatsStore};
 
+mod builder;
+mod game_events;
+mod mark;
+mod pacing;
 mod player;
+mod queue;
+mod rematch;
+mod save;
+mod spectator;
+mod stand_in;
+mod timer;
 
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub enum State {
     PreInitialise,
+    /// The game has begun but its players are yet to take turns, each preparing on their own,
+    /// such as by placing their fleet.
+    Setup,
     InProgress,
     GameOver,
+    /// The game is over and its players have been offered a rematch, which is yet to be agreed.
+    RematchOffered,
+    /// The game has been saved to be resumed later, sending its players away.
+    Suspended,
+    /// The game panicked while handling an event, and was ended with no player to blame.
+    Crashed,
     Error {
         category: ErrorCategory,
         player_id: u8,
     },
 }
 
-#[derive(Debug, Deserialize)]
+/// How a game came to an end, once it has.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameEnd {
+    Won {
+        winner: u8,
+    },
+    Drawn,
+    /// The game was cut short by the player with the given ID leaving it or being removed.
+    Abandoned {
+        player_id: u8,
+    },
+}
+
+/// What becomes of a player who sends messages the server cannot read.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum InvalidMessagePolicy {
+    /// The game ends as soon as the player sends a message that cannot be read at all, though
+    /// events the game cannot make sense of are only refused.
+    #[default]
+    Disconnect,
+    /// Each message that cannot be read is refused and the game carries on, until the player has
+    /// sent the given number of them and the game ends.
+    Tolerate { limit: u32 },
+}
+
+#[derive(Debug)]
 pub enum ServerEvent {
     BeginGame,
-    PlayerDisconnected,
+    PlayerReconnected {
+        player_id: u8,
+        connection: Connection,
+    },
+    SpectatorJoined {
+        connection: Connection,
+    },
+    /// Asks for the current status of the game, which is sent back on the reply channel.
+    GetStatus {
+        reply: oneshot::Sender<GameStatus>,
+    },
+    /// Removes the player from the game at the request of the server operator, ending the game.
+    KickPlayer {
+        player_id: u8,
+    },
+    /// Passes a message from the server operator on to everyone in the game.
+    Notice {
+        message: String,
+    },
+}
+
+/// Who is taking part in a game, as reported to the server operator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameStatus {
+    /// The ID of each player, along with whether they are currently connected.
+    pub players: Vec<(u8, bool)>,
+    pub spectators: usize,
 }
 
 pub trait ClientConnectionType {}
 
 pub struct LocalConnection {
     connection: Connection,
+    /// Every seat in the game, as the one connection plays as each player in turn.
+    seats: Seats,
+    player_count: u8,
 }
 
 impl ClientConnectionType for LocalConnection {}
 
 pub struct OnlineConnection {
-    player_one: Player,
-    player_two: Player,
+    /// Each player in the game, in the order they take their turns.
+    players: Vec<Player>,
+    lobby: Option<LobbyTask>,
+    spectator_config: Option<SpectatorConfig>,
+    spectators: Vec<Spectator>,
+    next_spectator_id: u32,
 }
 
 impl ClientConnectionType for OnlineConnection {}
 
+impl OnlineConnection {
+    fn get_player_mut(&mut self, player_id: u8) -> Option<&mut Player> {
+        self.players
+            .iter_mut()
+            .find(|player| player.id == player_id)
+    }
+
+    fn get_player_ids(&self) -> Vec<u8> {
+        self.players.iter().map(|player| player.id).collect()
+    }
+}
+
+/// The lobby task accepting dropped players back into the game, along with how long they have to
+/// do so. There is no task of the game's own when the lobby is shared between many games.
+struct LobbyTask {
+    grace_period: Duration,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for LobbyTask {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
+
 pub struct Server<C>
 where
     C: ClientConnectionType,
 {
     state: State,
     client_connection: C,
-    channel: (Sender<ServerEvent>, Receiver<ServerEvent>),
-    game: Box<dyn GameServer + Send + Sync>,
-    game_receiver: Receiver<GameServerEvent>,
+    channel: (EventSender, EventReceiver),
+    game: Box<dyn AnyGameServer + Send + Sync>,
+    game_events: GameEvents,
+    /// How many events the game may send before waiting for them to be handled, kept for the game
+    /// played in a rematch.
+    game_events_capacity: usize,
+    turn_timer: Option<TurnTimer>,
+    turn_clock: Option<Countdown>,
+    /// The timer the game has started for itself, if any.
+    game_timer: Option<GameTimer>,
+    /// How long the player whose turn it is may send nothing before their connection is treated
+    /// as lost.
+    read_timeout: Option<Duration>,
+    reconnection_countdown: Option<Countdown>,
+    game_id: &'static str,
+    started_at: Option<Instant>,
+    pacing: Pacing,
+    stats_store: Option<Mutex<Box<dyn StatsStore + Send>>>,
+    result_exporter: Option<Mutex<Box<dyn ResultExporter + Send>>>,
+    replay_recorder: Option<ReplayRecorder<Box<dyn io::Write + Send + Sync>>>,
+    save_path: Option<PathBuf>,
+    /// The token players joined with, kept in the save file so that they can rejoin with it.
+    join_token: Option<JoinToken>,
+    /// The mark each player is shown with, sent to every client as the game starts.
+    marks: PlayerMarks,
+    /// The name of each player who gave one, keyed by their ID.
+    names: BTreeMap<u8, PlayerName>,
+    /// The ID of the player who won the game, once it is over.
+    winner: Option<u8>,
+    /// Whether a bot takes over the seat of a player who leaves, rather than the game ending.
+    bot_takeover: bool,
+    invalid_message_policy: InvalidMessagePolicy,
+    /// The number of messages each player has sent that could not be read, keyed by their ID.
+    invalid_messages: BTreeMap<u8, u32>,
+    /// The IDs of the players whose seats have been taken over by a bot.
+    stand_ins: Vec<u8>,
+    first_player: Option<FirstPlayer>,
+    /// The seed the game's chance is drawn from, logged as the game begins and kept in any replay
+    /// of it, so that the game can be played out again the same way.
+    rng_seed: u64,
+    /// The games that may be played once a game is over, for a rematch over the same connections.
+    rematch_games: Vec<Box<dyn GameDescriptor>>,
+    /// The answers to the rematch on offer, if there is one.
+    rematch: Option<Rematch>,
 }
 
 impl Server<LocalConnection> {
-    pub fn new(connection: Connection, game: Game) -> Server<LocalConnection> {
-        let (game_sender, game_receiver) = mpsc::channel(10);
-        let game: Box<dyn GameServer + Send + Sync> = match game {
-            Game::TicTacToe => Box::new(TicTacToeServer::new(game_sender)),
-        };
+    pub fn new(connection: Connection, game: &dyn GameDescriptor) -> Server<LocalConnection> {
+        ServerBuilder::new().local(connection, game)
+    }
+
+    fn with_builder(
+        connection: Connection,
+        game: &dyn GameDescriptor,
+        builder: &ServerBuilder,
+    ) -> Server<LocalConnection> {
+        let capacities = builder.channel_capacities;
+        let game_events_capacity = capacities.game_events.max(1);
+        let (game_sender, game_receiver) = mpsc::channel(game_events_capacity);
+        let game_id = game.id();
+        let player_count = game.player_count();
+        let mut game = game.make_server(game_sender);
+        let rng_seed = rand::random();
+        game.set_rng(GameRng::from_seed(rng_seed));
 
         Server {
             state: State::PreInitialise,
-            client_connection: LocalConnection { connection },
-            channel: mpsc::channel(1),
+            client_connection: LocalConnection {
+                connection,
+                seats: Seats::every(player_count),
+                player_count,
+            },
+            channel: event_queue(capacities.server_events, builder.backpressure_policy),
             game,
-            game_receiver,
+            game_events: GameEvents::new(game_receiver),
+            game_events_capacity,
+            turn_timer: builder.timeouts.turn,
+            turn_clock: None,
+            game_timer: None,
+            read_timeout: builder.timeouts.read,
+            reconnection_countdown: None,
+            game_id,
+            started_at: None,
+            pacing: Pacing::default(),
+            stats_store: None,
+            result_exporter: None,
+            replay_recorder: None,
+            save_path: None,
+            join_token: None,
+            marks: PlayerMarks::assign(
+                (PLAYER_ONE_ID..PLAYER_ONE_ID + player_count).map(|id| (id, None)),
+            ),
+            names: BTreeMap::new(),
+            winner: None,
+            bot_takeover: false,
+            invalid_message_policy: InvalidMessagePolicy::default(),
+            invalid_messages: BTreeMap::new(),
+            stand_ins: Vec::new(),
+            first_player: None,
+            rng_seed,
+            rematch_games: Vec::new(),
+            rematch: None,
         }
     }
 }
 
 impl Server<OnlineConnection> {
-    pub fn new(player_one: Player, player_two: Player, game: Game) -> Server<OnlineConnection> {
-        let (game_sender, game_receiver) = mpsc::channel(10);
-        let game: Box<dyn GameServer + Send + Sync> = match game {
-            Game::TicTacToe => Box::new(TicTacToeServer::new(game_sender)),
-        };
+    /// Sets up a server for the game played by the given players, who take their turns in the
+    /// order of their IDs.
+    pub fn new(players: Vec<Player>, game: &dyn GameDescriptor) -> Server<OnlineConnection> {
+        ServerBuilder::new().online(players, game)
+    }
+
+    /// Starts building a server to be spawned in a task of its own, for applications hosting
+    /// games themselves.
+    pub fn builder() -> ServerBuilder {
+        ServerBuilder::new()
+    }
+
+    fn with_builder(
+        players: Vec<Player>,
+        game: &dyn GameDescriptor,
+        builder: &ServerBuilder,
+    ) -> Server<OnlineConnection> {
+        let game_events_capacity = builder.channel_capacities.game_events.max(1);
+        let (game_sender, game_receiver) = mpsc::channel(game_events_capacity);
+        let game_server = game.make_server(game_sender);
+
+        Server::with_game_server(players, game.id(), game_server, game_receiver, builder)
+    }
+
+    /// Sets up a server carrying on a suspended game with the given players, who must have the
+    /// IDs of those who were playing it.
+    pub fn resume(players: Vec<Player>, game: RestoredGame) -> Server<OnlineConnection> {
+        Server::with_game_server(
+            players,
+            game.game.id(),
+            game.game_server,
+            game.game_receiver,
+            &ServerBuilder::new(),
+        )
+    }
+
+    fn with_game_server(
+        players: Vec<Player>,
+        game_id: &'static str,
+        mut game: Box<dyn AnyGameServer + Send + Sync>,
+        game_receiver: Receiver<GameServerEvent>,
+        builder: &ServerBuilder,
+    ) -> Server<OnlineConnection> {
+        let capacities = builder.channel_capacities;
+        let game_events_capacity = capacities.game_events.max(1);
+        let rng_seed = rand::random();
+        game.set_rng(GameRng::from_seed(rng_seed));
+        let marks = PlayerMarks::assign(
+            players
+                .iter()
+                .map(|player| (player.id, player.mark.clone())),
+        );
+        let names = players
+            .iter()
+            .filter_map(|player| Some((player.id, player.name.clone()?)))
+            .collect();
 
         Server {
             state: State::PreInitialise,
             client_connection: OnlineConnection {
-                player_one,
-                player_two,
+                players,
+                lobby: None,
+                spectator_config: None,
+                spectators: Vec::new(),
+                next_spectator_id: 0,
             },
-            channel: mpsc::channel(1),
+            channel: event_queue(capacities.server_events, builder.backpressure_policy),
             game,
-            game_receiver,
+            game_events: GameEvents::new(game_receiver),
+            game_events_capacity,
+            turn_timer: builder.timeouts.turn,
+            turn_clock: None,
+            game_timer: None,
+            read_timeout: builder.timeouts.read,
+            reconnection_countdown: None,
+            game_id,
+            started_at: None,
+            pacing: Pacing::default(),
+            stats_store: None,
+            result_exporter: None,
+            replay_recorder: None,
+            save_path: None,
+            join_token: None,
+            marks,
+            names,
+            winner: None,
+            bot_takeover: false,
+            invalid_message_policy: InvalidMessagePolicy::default(),
+            invalid_messages: BTreeMap::new(),
+            stand_ins: Vec::new(),
+            first_player: None,
+            rng_seed,
+            rematch_games: Vec::new(),
+            rematch: None,
         }
     }
+
+    /// Hands the `Lobby` over to the server so that players who lose their connection can rejoin
+    /// the game using their session token within the provided grace period, and so that
+    /// spectators can join the game once it has been set up.
+    pub fn keep_lobby_open(&mut self, lobby: Lobby, grace_period: Duration) {
+        let sessions = self
+            .client_connection
+            .players
+            .iter()
+            .map(|player| (player.id, player.session_token))
+            .collect();
+        self.join_token = Some(lobby.join_token());
+        let handle = tokio::spawn(lobby.accept_late_connections(sessions, self.channel.0.clone()));
+
+        self.client_connection.lobby = Some(LobbyTask {
+            grace_period,
+            handle: Some(handle),
+        });
+    }
+
+    /// Lets players who lose their connection rejoin the game within the provided grace period,
+    /// returning the channel their new connections are to be handed over on. This is for a lobby
+    /// shared between many games, which works out which game each connection belongs to.
+    pub(crate) fn accept_reconnections(&mut self, grace_period: Duration) -> EventSender {
+        self.client_connection.lobby = Some(LobbyTask {
+            grace_period,
+            handle: None,
+        });
+
+        self.channel.0.clone()
+    }
+
+    /// Allows spectators to watch the game, within the limits of the provided `SpectatorConfig`.
+    pub fn set_spectator_config(&mut self, spectator_config: SpectatorConfig) {
+        self.client_connection.spectator_config = Some(spectator_config);
+    }
+
+    /// Offers the players a rematch each time a game is over, letting player one choose any of
+    /// the given games played by the same number of players. A game can only be played again if
+    /// it is among them.
+    pub fn offer_rematches(&mut self, games: Vec<Box<dyn GameDescriptor>>) {
+        self.rematch_games = games;
+    }
 }
 
 pub enum IncomingEvent {
     Server(ServerEvent),
     Game(GameServerEvent),
-    Client(Vec<u8>),
+    /// An event sent by the player with the given ID, whose connection holds the given seats.
+    Client {
+        event: ErasedEvent,
+        player_id: u8,
+        seats: Seats,
+    },
+    TurnTimedOut {
+        player_id: u8,
+    },
+    GameTimerElapsed {
+        id: u32,
+    },
+    ReconnectionTimedOut {
+        player_id: u8,
+    },
+    SpectatorLeft {
+        spectator_id: u32,
+    },
+    /// The game has dropped its end of the channel, so will send no more events.
+    GameDropped,
+}
+
+/// Why the server ended a game before it was over.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+pub enum ShutdownReason {
+    /// An opponent's connection was lost, or they were removed from the game, and they did not
+    /// come back.
+    OpponentDisconnected,
+    /// A player sent messages the server could not accept.
+    ProtocolViolation,
+    /// The server could not carry on with the game, such as when its host stopped it.
+    ServerShutdown,
+    /// An opponent sent nothing for longer than they were given.
+    Timeout,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 pub enum OutgoingEvent {
     ErrorOccurred(Error),
-    GameStarted,
-    Shutdown,
-    Game { event: Vec<u8> },
+    /// Sent as the game begins, with the ID of the game being played, as in `game::GAMES`, the
+    /// mark each player is shown with, and the options the game is played with as given by
+    /// `GameServer::config`.
+    GameStarted {
+        game: String,
+        marks: PlayerMarks,
+        config: Vec<u8>,
+    },
+    /// Sent as the server ends the game early, after which it closes.
+    Shutdown {
+        reason: ShutdownReason,
+    },
+    Game {
+        event: ErasedEvent,
+    },
+    OpponentDisconnected {
+        grace_period_secs: u64,
+    },
+    OpponentReconnected,
+    /// Sent once a bot has taken over the seat of an opponent who left, for the game to carry on.
+    OpponentReplaced,
+    SpectatorJoined {
+        spectator_count: usize,
+    },
+    SpectatorLeft {
+        spectator_count: usize,
+    },
+    /// Sent once the game is over, with the think times of each player keyed by their ID, along
+    /// with the new rating of each if they were rated.
+    ///
+    /// Players offered a rematch are also sent the IDs of the games that may be played next, the
+    /// one just played first, and are to answer with a `RematchResponse`.
+    GameSummary {
+        think_times: BTreeMap<u8, ThinkTime>,
+        #[serde(default)]
+        ratings: BTreeMap<u8, RatingChange>,
+        #[serde(default)]
+        rematch_games: Vec<String>,
+    },
+    /// Sent once a player has declined the rematch, or left before answering, after which the
+    /// server closes.
+    RematchDeclined,
+    /// Sent once every player has agreed to a rematch, with the ID of the game to be played next,
+    /// ahead of it starting.
+    NextGame {
+        game: String,
+    },
+    /// A message from the server operator, such as a warning that the server is shutting down.
+    Notice {
+        message: String,
+    },
+    /// Sent to the player whose turn it is once they have a single attempt left at making a valid
+    /// move, before they forfeit the game.
+    RejectedMovesWarning {
+        attempts_remaining: u8,
+    },
+    /// Sent once the player with the given ID has made too many invalid moves in a single turn,
+    /// ahead of the game ending with them forfeiting it.
+    RejectedMovesExceeded {
+        player_id: u8,
+    },
+    /// Sent once the game has been saved to be resumed later, after which the server closes.
+    Suspended,
+    /// Sent to tournament players waiting on their opponent whenever the bracket changes, with
+    /// the matches of each round, the first round first.
+    TournamentStandings {
+        rounds: Vec<Vec<BracketMatch>>,
+    },
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
 pub enum Error {
     #[error("Invalid message sent.")]
     InvalidMessage,
+    #[error("This game is not accepting spectators.")]
+    SpectatingUnavailable,
+    #[error("You have been removed from the game by the server operator.")]
+    Kicked,
+    #[error("You cannot act for another player.")]
+    UnexpectedPlayer,
+    #[error("This game cannot be saved.")]
+    SavingUnavailable,
 }
 
 #[async_trait]
@@ -122,16 +575,64 @@ pub trait ServerGameMode {
         &mut self,
         event: &OutgoingEvent,
     ) -> Result<(), (WriteError, u8)>;
+    /// Sends the event to every player other than the given one, such as to let them know what
+    /// has happened to that player.
+    async fn dispatch_event_to_other_players(
+        &mut self,
+        event: &OutgoingEvent,
+        player_id: u8,
+    ) -> Result<(), (WriteError, u8)>;
+    async fn dispatch_event_to_spectators(&mut self, event: &OutgoingEvent);
     async fn shutdown_all_client_connections(&mut self);
+
+    /// Lets the given connection watch the game, provided spectators are allowed and there is room
+    /// for another.
+    ///
+    /// # Returns
+    ///
+    /// Whether the spectator was added.
+    async fn add_spectator(&mut self, connection: Connection) -> Result<bool, (WriteError, u8)>;
+    async fn remove_spectator(&mut self, spectator_id: u32) -> Result<(), (WriteError, u8)>;
+
+    /// Marks the connection of the given player as lost.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Duration)` containing the grace period the player has to reconnect within.
+    /// - `None` if the player cannot reconnect, meaning the game must be terminated.
+    fn disconnect_player(&mut self, player_id: u8) -> Option<Duration>;
+
+    /// Replaces the lost connection of the given player with a new one.
+    ///
+    /// # Returns
+    ///
+    /// The connection back if the player was not awaiting reconnection.
+    fn reconnect_player(&mut self, player_id: u8, connection: Connection) -> Option<Connection>;
+
+    /// Replaces the connection of the given player with a new one, whether or not the player was
+    /// awaiting reconnection.
+    ///
+    /// # Returns
+    ///
+    /// The connection back if the seat cannot be handed to another connection.
+    fn replace_player(&mut self, player_id: u8, connection: Connection) -> Option<Connection>;
+
+    /// Times out reads from the given player after the given time, and lifts any timeout on reads
+    /// from everyone else, who have no reason to send anything while it is not their turn.
+    fn time_reads_from(&mut self, player_id: Option<u8>, read_timeout: Duration);
+
+    fn get_status(&self) -> GameStatus;
 }
 
 #[async_trait]
 impl ServerGameMode for Server<LocalConnection> {
     async fn get_next_incoming_event(&mut self) -> Result<IncomingEvent, (ReadError, u8)> {
         return tokio::select! {
-            result = self.channel.1.recv() => Ok(IncomingEvent::Server(result.unwrap())),
-            result = self.game_receiver.recv() => Ok(IncomingEvent::Game(result.unwrap())),
-            result = self.client_connection.connection.read_event() => result.map_err(|e| (e, PLAYER_ONE_ID)).map(IncomingEvent::Client),
+            event = self.channel.1.recv() => Ok(IncomingEvent::Server(event)),
+            result = self.game_events.recv() => Ok(result.map_or(IncomingEvent::GameDropped, IncomingEvent::Game)),
+            player_id = timer::countdown_elapsed(self.turn_clock) => Ok(IncomingEvent::TurnTimedOut { player_id }),
+            id = timer::game_timer_elapsed(self.game_timer) => Ok(IncomingEvent::GameTimerElapsed { id }),
+            result = self.client_connection.connection.read_event() => result.map_err(|e| (e, PLAYER_ONE_ID)).map(|event| IncomingEvent::Client { event, player_id: PLAYER_ONE_ID, seats: self.client_connection.seats }),
         };
     }
 
@@ -154,19 +655,69 @@ impl ServerGameMode for Server<LocalConnection> {
         self.dispatch_event_to_player(event, PLAYER_ONE_ID).await
     }
 
+    async fn dispatch_event_to_other_players(
+        &mut self,
+        event: &OutgoingEvent,
+        _player_id: u8,
+    ) -> Result<(), (WriteError, u8)> {
+        self.dispatch_event_to_player(event, PLAYER_ONE_ID).await
+    }
+
+    async fn dispatch_event_to_spectators(&mut self, _event: &OutgoingEvent) {}
+
     async fn shutdown_all_client_connections(&mut self) {
         let _ = self.client_connection.connection.shutdown().await;
     }
+
+    async fn add_spectator(
+        &mut self,
+        mut connection: Connection,
+    ) -> Result<bool, (WriteError, u8)> {
+        let _ = connection.shutdown().await;
+        Ok(false)
+    }
+
+    async fn remove_spectator(&mut self, _spectator_id: u32) -> Result<(), (WriteError, u8)> {
+        Ok(())
+    }
+
+    fn disconnect_player(&mut self, _player_id: u8) -> Option<Duration> {
+        None
+    }
+
+    fn reconnect_player(&mut self, _player_id: u8, connection: Connection) -> Option<Connection> {
+        Some(connection)
+    }
+
+    // Every seat is held by the one connection, so no one would be left to play with a bot
+    fn replace_player(&mut self, _player_id: u8, connection: Connection) -> Option<Connection> {
+        Some(connection)
+    }
+
+    // The client shares the process with the server, so cannot go without it noticing
+    fn time_reads_from(&mut self, _player_id: Option<u8>, _read_timeout: Duration) {}
+
+    fn get_status(&self) -> GameStatus {
+        GameStatus {
+            players: (PLAYER_ONE_ID..PLAYER_ONE_ID + self.client_connection.player_count)
+                .map(|player_id| (player_id, true))
+                .collect(),
+            spectators: 0,
+        }
+    }
 }
 
 #[async_trait]
 impl ServerGameMode for Server<OnlineConnection> {
     async fn get_next_incoming_event(&mut self) -> Result<IncomingEvent, (ReadError, u8)> {
         return tokio::select! {
-            result = self.channel.1.recv() => Ok(IncomingEvent::Server(result.unwrap())),
-            result = self.game_receiver.recv() => Ok(IncomingEvent::Game(result.unwrap())),
-            result = self.client_connection.player_one.connection.read_event() => result.map_err(|e| (e, PLAYER_ONE_ID)).map(IncomingEvent::Client),
-            result = self.client_connection.player_two.connection.read_event() => result.map_err(|e| (e, PLAYER_TWO_ID)).map(IncomingEvent::Client),
+            event = self.channel.1.recv() => Ok(IncomingEvent::Server(event)),
+            result = self.game_events.recv() => Ok(result.map_or(IncomingEvent::GameDropped, IncomingEvent::Game)),
+            player_id = timer::countdown_elapsed(self.turn_clock) => Ok(IncomingEvent::TurnTimedOut { player_id }),
+            id = timer::game_timer_elapsed(self.game_timer) => Ok(IncomingEvent::GameTimerElapsed { id }),
+            player_id = timer::countdown_elapsed(self.reconnection_countdown) => Ok(IncomingEvent::ReconnectionTimedOut { player_id }),
+            result = player::player_event_received(&mut self.client_connection.players) => result.map(|(event, player_id, seats)| IncomingEvent::Client { event, player_id, seats }),
+            spectator_id = spectator::spectator_departed(&mut self.client_connection.spectators) => Ok(IncomingEvent::SpectatorLeft { spectator_id }),
         };
     }
 
@@ -175,39 +726,198 @@ impl ServerGameMode for Server<OnlineConnection> {
         event: &OutgoingEvent,
         player_id: u8,
     ) -> Result<(), (WriteError, u8)> {
-        match player_id {
-            PLAYER_ONE_ID => {
-                self.client_connection
-                    .player_one
-                    .connection
-                    .write_event(event)
-                    .await
-            }
-            PLAYER_TWO_ID => {
-                self.client_connection
-                    .player_two
-                    .connection
-                    .write_event(event)
-                    .await
-            }
-            _ => panic!("Unexpected id provided"),
+        let Some(player) = self.client_connection.get_player_mut(player_id) else {
+            tracing::warn!(
+                player_id,
+                "Not sending event to player who is not in the game"
+            );
+            return Ok(());
+        };
+        if !player.connected {
+            // The player will be sent the current state of the game if they reconnect
+            return Ok(());
         }
-        .map_err(|e| (e, player_id))
+
+        player
+            .connection
+            .write_event(event)
+            .await
+            .map_err(|e| (e, player_id))
     }
 
     async fn dispatch_event_to_all_players(
         &mut self,
         event: &OutgoingEvent,
     ) -> Result<(), (WriteError, u8)> {
-        self.dispatch_event_to_player(event, PLAYER_ONE_ID).await?;
-        self.dispatch_event_to_player(event, PLAYER_TWO_ID).await
+        for player_id in self.client_connection.get_player_ids() {
+            self.dispatch_event_to_player(event, player_id).await?;
+        }
+        self.dispatch_event_to_spectators(event).await;
+
+        Ok(())
+    }
+
+    async fn dispatch_event_to_other_players(
+        &mut self,
+        event: &OutgoingEvent,
+        player_id: u8,
+    ) -> Result<(), (WriteError, u8)> {
+        for other_player_id in self.client_connection.get_player_ids() {
+            if other_player_id != player_id {
+                self.dispatch_event_to_player(event, other_player_id)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn dispatch_event_to_spectators(&mut self, event: &OutgoingEvent) {
+        // Spectators that can no longer be written to are dropped without affecting the game
+        let mut departed_spectators = Vec::new();
+        for spectator in self.client_connection.spectators.iter_mut() {
+            if spectator.connection.write_event(event).await.is_err() {
+                departed_spectators.push(spectator.id);
+            }
+        }
+
+        self.client_connection
+            .spectators
+            .retain(|spectator| !departed_spectators.contains(&spectator.id));
     }
 
     async fn shutdown_all_client_connections(&mut self) {
-        let _ = join!(
-            self.client_connection.player_one.connection.shutdown(),
-            self.client_connection.player_two.connection.shutdown()
-        );
+        for player in self.client_connection.players.iter_mut() {
+            let _ = player.connection.shutdown().await;
+        }
+        for spectator in self.client_connection.spectators.iter_mut() {
+            let _ = spectator.connection.shutdown().await;
+        }
+    }
+
+    async fn add_spectator(
+        &mut self,
+        mut connection: Connection,
+    ) -> Result<bool, (WriteError, u8)> {
+        let spectator_count = self.client_connection.spectators.len();
+        let spectator_config = match self.client_connection.spectator_config {
+            Some(config) if spectator_count < config.max_spectators => config,
+            _ => {
+                let _ = connection
+                    .write_event(&OutgoingEvent::ErrorOccurred(Error::SpectatingUnavailable))
+                    .await;
+                let _ = connection.shutdown().await;
+
+                return Ok(false);
+            }
+        };
+
+        if connection
+            .write_event(&OutgoingEvent::GameStarted {
+                game: String::from(self.game_id),
+                marks: self.marks.clone(),
+                config: self.game.config(),
+            })
+            .await
+            .is_err()
+        {
+            return Ok(false);
+        }
+
+        let id = self.client_connection.next_spectator_id;
+        self.client_connection.next_spectator_id += 1;
+        self.client_connection
+            .spectators
+            .push(Spectator { id, connection });
+
+        if spectator_config.notify_players {
+            let event = OutgoingEvent::SpectatorJoined {
+                spectator_count: spectator_count + 1,
+            };
+            for player_id in self.client_connection.get_player_ids() {
+                self.dispatch_event_to_player(&event, player_id).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    async fn remove_spectator(&mut self, spectator_id: u32) -> Result<(), (WriteError, u8)> {
+        self.client_connection
+            .spectators
+            .retain(|spectator| spectator.id != spectator_id);
+
+        if let Some(SpectatorConfig {
+            notify_players: true,
+            ..
+        }) = self.client_connection.spectator_config
+        {
+            let event = OutgoingEvent::SpectatorLeft {
+                spectator_count: self.client_connection.spectators.len(),
+            };
+            for player_id in self.client_connection.get_player_ids() {
+                self.dispatch_event_to_player(&event, player_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn disconnect_player(&mut self, player_id: u8) -> Option<Duration> {
+        let grace_period = self.client_connection.lobby.as_ref()?.grace_period;
+        // Only one player at a time can be waited on to reconnect
+        let other_player_disconnected = self
+            .client_connection
+            .players
+            .iter()
+            .any(|player| player.id != player_id && !player.connected);
+        if other_player_disconnected {
+            return None;
+        }
+
+        self.client_connection.get_player_mut(player_id)?.connected = false;
+        Some(grace_period)
+    }
+
+    fn reconnect_player(&mut self, player_id: u8, connection: Connection) -> Option<Connection> {
+        let Some(player) = self.client_connection.get_player_mut(player_id) else {
+            return Some(connection);
+        };
+        if player.connected {
+            return Some(connection);
+        }
+
+        player.connection = connection;
+        player.connected = true;
+        None
+    }
+
+    fn replace_player(&mut self, player_id: u8, connection: Connection) -> Option<Connection> {
+        let Some(player) = self.client_connection.get_player_mut(player_id) else {
+            return Some(connection);
+        };
+        player.connection = connection;
+        player.connected = true;
+        None
+    }
+
+    fn time_reads_from(&mut self, player_id: Option<u8>, read_timeout: Duration) {
+        for player in self.client_connection.players.iter_mut() {
+            let read_timeout = (Some(player.id) == player_id).then_some(read_timeout);
+            player.connection.set_read_timeout(read_timeout);
+        }
+    }
+
+    fn get_status(&self) -> GameStatus {
+        GameStatus {
+            players: self
+                .client_connection
+                .players
+                .iter()
+                .map(|player| (player.id, player.connected))
+                .collect(),
+            spectators: self.client_connection.spectators.len(),
+        }
     }
 }
 
@@ -216,15 +926,96 @@ where
     C: ClientConnectionType,
     Self: ServerGameMode,
 {
+    /// Limits the time each player has to make a move, applying the `TimeoutAction` of the
+    /// provided `TurnTimer` when it runs out. A player given a `Handicap` has their own limit.
+    pub fn set_turn_timer(&mut self, turn_timer: TurnTimer) {
+        self.turn_timer = Some(turn_timer);
+    }
+
+    /// Treats the connection of the player whose turn it is as lost once they have sent nothing for
+    /// the given time, rather than waiting on them indefinitely. They may then reconnect as if
+    /// their connection had dropped.
+    pub fn set_read_timeout(&mut self, read_timeout: Duration) {
+        self.read_timeout = Some(read_timeout);
+    }
+
+    /// Has a bot take over the seat of a player who leaves the game and does not come back within
+    /// the grace period, if any, so that the players remaining can carry on.
+    pub fn set_bot_takeover(&mut self, bot_takeover: bool) {
+        self.bot_takeover = bot_takeover;
+    }
+
+    /// Decides whether a player sending messages that cannot be read, such as from a buggy client,
+    /// ends the game straight away or only once they have sent too many.
+    pub fn set_invalid_message_policy(&mut self, invalid_message_policy: InvalidMessagePolicy) {
+        self.invalid_message_policy = invalid_message_policy;
+    }
+
+    /// Records every game that is played to completion in the provided store.
+    pub fn set_stats_store(&mut self, stats_store: impl StatsStore + Send + 'static) {
+        self.stats_store = Some(Mutex::new(Box::new(stats_store)));
+    }
+
+    /// Exports the result of every game that is played to completion, such as to league software.
+    pub fn set_result_exporter(&mut self, result_exporter: impl ResultExporter + Send + 'static) {
+        self.result_exporter = Some(Mutex::new(Box::new(result_exporter)));
+    }
+
+    /// Records the game events seen by spectators to the writer, so that the game can be played
+    /// back using `replay::play_replay`.
+    pub fn set_replay_writer(&mut self, writer: impl io::Write + Send + Sync + 'static) {
+        self.replay_recorder = Some(ReplayRecorder::new(Box::new(writer)));
+    }
+
+    /// Chooses who takes the first turn, for games that let it be chosen.
+    pub fn set_first_player(&mut self, first_player: FirstPlayer) {
+        self.first_player = Some(first_player);
+        self.game.set_first_player(first_player);
+    }
+
+    /// Draws the game's chance from the given seed rather than one chosen at random, such as to
+    /// play out a game again from the seed logged as it began.
+    pub fn set_rng_seed(&mut self, seed: u64) {
+        self.rng_seed = seed;
+        self.game.set_rng(GameRng::from_seed(seed));
+        // Anything already left to chance is decided again from the seed
+        if let Some(first_player) = self.first_player {
+            self.game.set_first_player(first_player);
+        }
+    }
+
+    /// Lets players suspend the game, saving it to the file at the given path so that it can be
+    /// resumed using `SavedGame::restore`.
+    pub fn set_save_path(&mut self, save_path: PathBuf) {
+        self.save_path = Some(save_path);
+    }
+
+    /// How the game ended, or `None` if it has yet to end or was suspended.
+    pub fn game_end(&self) -> Option<GameEnd> {
+        match self.state {
+            State::GameOver | State::RematchOffered => Some(match self.winner {
+                Some(winner) => GameEnd::Won { winner },
+                None => GameEnd::Drawn,
+            }),
+            State::Error { player_id, .. } => Some(GameEnd::Abandoned { player_id }),
+            State::PreInitialise
+            | State::Setup
+            | State::InProgress
+            | State::Suspended
+            | State::Crashed => None,
+        }
+    }
+
     pub async fn init(&mut self) {
-        self.channel.0.send(ServerEvent::BeginGame).await.unwrap();
+        self.handle_next_event(Ok(IncomingEvent::Server(ServerEvent::BeginGame)))
+            .await;
         self.run().await
     }
 
     async fn run(&mut self) {
         loop {
             match self.state {
-                State::GameOver => break,
+                State::GameOver | State::Suspended | State::Crashed => break,
                 State::Error {
                     category: error_type,
                     player_id,
@@ -232,22 +1023,34 @@ where
                     self.handle_error(error_type, player_id).await;
                     break;
                 }
-                _ => match self.get_next_incoming_event().await {
-                    Ok(event) => {
-                        if let Err((error, id)) = self.handle_incoming_event(event).await {
-                            self.state = State::Error {
-                                category: error.category(),
-                                player_id: id,
-                            }
-                        }
-                    }
-                    Err((error, id)) => {
-                        self.state = State::Error {
-                            category: error.category(),
-                            player_id: id,
-                        }
+                _ => {
+                    let event = self.get_next_incoming_event().await;
+                    self.handle_next_event(event).await
+                }
+            }
+        }
+    }
+
+    async fn handle_next_event(&mut self, event: Result<IncomingEvent, (ReadError, u8)>) {
+        match event {
+            Ok(event) => {
+                // A panic in the game is caught here rather than taking down the task, so that the
+                // players are told the game is over before they are sent away
+                match AssertUnwindSafe(self.handle_incoming_event(event))
+                    .catch_unwind()
+                    .await
+                {
+                    Ok(Ok(())) => {}
+                    Ok(Err((error, id))) => {
+                        tracing::warn!(player_id = id, ?error, "Failed to send to player");
+                        self.handle_connection_error(error.category(), id).await
                     }
-                },
+                    Err(payload) => self.handle_panic(panic_message(payload)).await,
+                }
+            }
+            Err((error, id)) => {
+                tracing::warn!(player_id = id, ?error, "Failed to receive from player");
+                self.handle_connection_error(error.category(), id).await
             }
         }
     }
@@ -258,60 +1061,732 @@ where
     ) -> Result<(), (WriteError, u8)> {
         match (self.state, event) {
             (State::PreInitialise, IncomingEvent::Server(ServerEvent::BeginGame)) => {
-                self.dispatch_event_to_all_players(&OutgoingEvent::GameStarted)
-                    .await?;
-
-                self.game.begin().await;
-                self.state = State::InProgress;
-                Ok(())
+                self.begin_game().await
             }
-            (State::InProgress, IncomingEvent::Client(event)) => {
-                self.game.handle_event(event).await;
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Client {
+                    event,
+                    player_id,
+                    seats,
+                },
+            ) => {
+                // An event acting for a seat the sender does not hold is refused before reaching
+                // the game, so that no player can act for another
+                match game::get_event_player_id(&event) {
+                    Some(event_player_id) if seats.contains(event_player_id) => {
+                        if let Err(error) =
+                            self.game_events.during(self.game.handle_event(event)).await
+                        {
+                            tracing::debug!(player_id, ?error, "Game event could not be read");
+                            return self.refuse_invalid_message(player_id).await;
+                        }
 
-                Ok(())
+                        Ok(())
+                    }
+                    Some(_) => {
+                        self.dispatch_event_to_player(
+                            &OutgoingEvent::ErrorOccurred(Error::UnexpectedPlayer),
+                            player_id,
+                        )
+                        .await
+                    }
+                    None => self.refuse_invalid_message(player_id).await,
+                }
             }
             (
-                State::InProgress,
+                State::Setup | State::InProgress,
                 IncomingEvent::Game(GameServerEvent::DispatchToClient {
                     dispatch_mode,
                     event,
                 }),
             ) => self.dispatch_game_event(dispatch_mode, event).await,
-            (State::InProgress, IncomingEvent::Game(GameServerEvent::GameOver)) => {
-                self.state = State::GameOver;
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Game(GameServerEvent::DispatchViews {
+                    views,
+                    spectator_view,
+                }),
+            ) => self.dispatch_game_views(views, spectator_view).await,
+            (
+                State::InProgress,
+                IncomingEvent::Game(GameServerEvent::TurnStarted { player_id }),
+            ) => {
+                self.pacing.start_turn(player_id);
+                self.turn_clock = self
+                    .turn_timer
+                    .map(|timer| Countdown::start(player_id, timer.duration_for(player_id)));
+                if let Some(read_timeout) = self.read_timeout {
+                    self.time_reads_from(Some(player_id), read_timeout);
+                }
 
                 Ok(())
             }
-            _ => panic!("Invalid state for event"),
-        }
-    }
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Game(GameServerEvent::TimerStarted { id, duration }),
+            ) => {
+                self.game_timer = Some(GameTimer::start(id, duration));
 
-    /// Handles errors that can occur when reading/writing from/to a Client connection.
-    ///
-    /// Possible errors to be handled; IO, invalid parameters, and serialisation and deserialisation
-    /// of `Event` objects.
-    ///
-    /// # Parameters
-    ///
+                Ok(())
+            }
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Game(GameServerEvent::MoveRejected {
+                    player_id,
+                    attempts_remaining,
+                }),
+            ) => {
+                self.handle_move_rejected(player_id, attempts_remaining)
+                    .await
+            }
+            (State::Setup, IncomingEvent::Game(GameServerEvent::SetupComplete)) => {
+                tracing::info!("Setup complete");
+                self.state = State::InProgress;
+
+                Ok(())
+            }
+            (
+                _,
+                IncomingEvent::Server(ServerEvent::PlayerReconnected {
+                    player_id,
+                    connection,
+                }),
+            ) => {
+                if let Some(mut connection) = self.reconnect_player(player_id, connection) {
+                    let _ = connection.shutdown().await;
+                    return Ok(());
+                }
+
+                tracing::info!(player_id, "Player reconnected");
+                self.reconnection_countdown = None;
+                if let Some(read_timeout) = self.read_timeout {
+                    self.time_reads_from(self.pacing.current_player(), read_timeout);
+                }
+                self.dispatch_event_to_other_players(
+                    &OutgoingEvent::OpponentReconnected,
+                    player_id,
+                )
+                .await?;
+                self.game_events
+                    .during(self.game.handle_player_reconnected(player_id))
+                    .await;
+
+                Ok(())
+            }
+            (_, IncomingEvent::Server(ServerEvent::SpectatorJoined { connection })) => {
+                if self.add_spectator(connection).await? {
+                    self.game_events
+                        .during(self.game.handle_spectator_joined())
+                        .await;
+                }
+
+                Ok(())
+            }
+            (_, IncomingEvent::Server(ServerEvent::GetStatus { reply })) => {
+                // The operator may have stopped waiting for the status
+                let _ = reply.send(self.get_status());
+
+                Ok(())
+            }
+            (_, IncomingEvent::Server(ServerEvent::KickPlayer { player_id })) => {
+                tracing::info!(player_id, "Player kicked by the server operator");
+                let _ = self
+                    .dispatch_event_to_player(
+                        &OutgoingEvent::ErrorOccurred(Error::Kicked),
+                        player_id,
+                    )
+                    .await;
+                self.state = State::Error {
+                    category: ErrorCategory::ReadWrite,
+                    player_id,
+                };
+
+                Ok(())
+            }
+            (_, IncomingEvent::Server(ServerEvent::Notice { message })) => {
+                self.dispatch_event_to_all_players(&OutgoingEvent::Notice { message })
+                    .await
+            }
+            (_, IncomingEvent::SpectatorLeft { spectator_id }) => {
+                self.remove_spectator(spectator_id).await
+            }
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::ReconnectionTimedOut { player_id },
+            ) => {
+                tracing::info!(player_id, "Player did not reconnect in time");
+                self.reconnection_countdown = None;
+                if self.hand_seat_to_bot(player_id).await {
+                    return Ok(());
+                }
+                self.state = State::Error {
+                    category: ErrorCategory::ReadWrite,
+                    player_id,
+                };
+
+                Ok(())
+            }
+            (State::InProgress, IncomingEvent::TurnTimedOut { player_id }) => {
+                self.turn_clock = None;
+                if let Some(timer) = self.turn_timer {
+                    self.game_events
+                        .during(self.game.handle_turn_timed_out(player_id, timer.action))
+                        .await;
+                }
+
+                Ok(())
+            }
+            (State::Setup | State::InProgress, IncomingEvent::GameTimerElapsed { id }) => {
+                self.game_timer = None;
+                self.game_events
+                    .during(self.game.handle_timer_elapsed(id))
+                    .await;
+
+                Ok(())
+            }
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Game(GameServerEvent::GameOver { winner }),
+            ) => {
+                tracing::info!(?winner, "Game over");
+                self.turn_clock = None;
+                self.game_timer = None;
+                self.state = State::GameOver;
+                self.winner = winner;
+                self.pacing.end_turn();
+                let ratings = self.record_game(winner);
+                let rematch_games = self.get_rematch_game_ids();
+                if !rematch_games.is_empty() {
+                    self.state = State::RematchOffered;
+                    self.rematch = Some(Rematch::offer(self.get_player_ids()));
+                    // Players may take their time deciding whether to play again
+                    if let Some(read_timeout) = self.read_timeout {
+                        self.time_reads_from(None, read_timeout);
+                    }
+                }
+
+                // The game has finished, so players who can no longer be reached are not a concern
+                let think_times = self.pacing.get_think_times();
+                let _ = self
+                    .dispatch_event_to_all_players(&OutgoingEvent::GameSummary {
+                        think_times,
+                        ratings,
+                        rematch_games,
+                    })
+                    .await;
+
+                Ok(())
+            }
+            (
+                State::RematchOffered,
+                IncomingEvent::Client {
+                    event,
+                    player_id,
+                    seats,
+                },
+            ) => {
+                let response = envelope::open_value::<RematchResponse>(&event.0)
+                    .ok()
+                    .map(Envelope::into_payload);
+                match response {
+                    Some(response) if seats.contains(response.player_id()) => {
+                        self.handle_rematch_response(response).await
+                    }
+                    Some(_) => {
+                        self.dispatch_event_to_player(
+                            &OutgoingEvent::ErrorOccurred(Error::UnexpectedPlayer),
+                            player_id,
+                        )
+                        .await
+                    }
+                    None => {
+                        self.dispatch_event_to_player(
+                            &OutgoingEvent::ErrorOccurred(Error::InvalidMessage),
+                            player_id,
+                        )
+                        .await
+                    }
+                }
+            }
+            // Anything the finished game still has to say is of no use to its players
+            (State::RematchOffered, IncomingEvent::Game(_)) => Ok(()),
+            (
+                State::Setup | State::InProgress,
+                IncomingEvent::Game(GameServerEvent::SuspendRequested { player_id }),
+            ) => {
+                if let Err(error) = self.save_game() {
+                    tracing::warn!(player_id, ?error, "Failed to save the game");
+                    return self
+                        .dispatch_event_to_player(
+                            &OutgoingEvent::ErrorOccurred(Error::SavingUnavailable),
+                            player_id,
+                        )
+                        .await;
+                }
+
+                tracing::info!(player_id, "Game suspended");
+                self.turn_clock = None;
+                self.game_timer = None;
+                self.state = State::Suspended;
+                // The game has been saved, so players who can no longer be reached are not a
+                // concern
+                let _ = self
+                    .dispatch_event_to_all_players(&OutgoingEvent::Suspended)
+                    .await;
+
+                Ok(())
+            }
+            // Without its events the game cannot be played on, whatever state it was in
+            (state, IncomingEvent::GameDropped) => {
+                tracing::error!(game = self.game_id, ?state, "Game stopped sending events");
+                self.end_crashed().await;
+                Ok(())
+            }
+            // A client cannot be trusted to only send what the game is ready for
+            (state, IncomingEvent::Client { player_id, .. }) => {
+                tracing::warn!(player_id, ?state, "Player sent an event out of turn");
+                self.state = State::Error {
+                    category: ErrorCategory::Protocol,
+                    player_id,
+                };
+
+                Ok(())
+            }
+            (state, _) => {
+                tracing::warn!(?state, "Ignoring event not expected in this state");
+                Ok(())
+            }
+        }
+    }
+
+    /// Tells every player the game has started, then begins it.
+    async fn begin_game(&mut self) -> Result<(), (WriteError, u8)> {
+        let event = OutgoingEvent::GameStarted {
+            game: String::from(self.game_id),
+            marks: self.marks.clone(),
+            config: self.game.config(),
+        };
+        self.dispatch_event_to_all_players(&event).await?;
+
+        tracing::info!(game = self.game_id, seed = self.rng_seed, "Game began");
+        if let Some(replay_recorder) = &mut self.replay_recorder {
+            if replay_recorder
+                .begin(self.game_id, &self.game.config(), self.rng_seed)
+                .is_err()
+            {
+                self.replay_recorder = None;
+            }
+        }
+
+        self.game_events.during(self.game.begin()).await;
+        self.state = match self.game.is_setting_up() {
+            true => State::Setup,
+            false => State::InProgress,
+        };
+        self.started_at = Some(Instant::now());
+        Ok(())
+    }
+
+    /// The IDs of the players who are to answer the offer of a rematch.
+    fn get_player_ids(&self) -> Vec<u8> {
+        self.get_status()
+            .players
+            .into_iter()
+            .map(|(player_id, _)| player_id)
+            .collect()
+    }
+
+    /// The IDs of the games a rematch may be played in, the game just played first, or none if no
+    /// rematch can be offered. Every player must still be there to play it, rather than a bot
+    /// standing in for them.
+    fn get_rematch_game_ids(&self) -> Vec<String> {
+        let players = self.get_status().players;
+        if !self.stand_ins.is_empty() || players.iter().any(|&(_, connected)| !connected) {
+            return Vec::new();
+        }
+
+        let mut games: Vec<String> = self
+            .rematch_games
+            .iter()
+            .filter(|game| usize::from(game.player_count()) == players.len())
+            .map(|game| String::from(game.id()))
+            .collect();
+        let Some(current) = games.iter().position(|game| game == self.game_id) else {
+            return Vec::new();
+        };
+        games[..=current].rotate_right(1);
+
+        games
+    }
+
+    /// Records the player's answer to the rematch on offer, starting the next game once every
+    /// player has agreed to it.
+    async fn handle_rematch_response(
+        &mut self,
+        response: RematchResponse,
+    ) -> Result<(), (WriteError, u8)> {
+        let Some(rematch) = &mut self.rematch else {
+            return Ok(());
+        };
+
+        match response {
+            RematchResponse::Accepted { player_id, game } => {
+                tracing::info!(player_id, "Player agreed to a rematch");
+                if rematch.accept(player_id, game) {
+                    self.start_next_game().await?;
+                }
+
+                Ok(())
+            }
+            RematchResponse::Declined { player_id } => {
+                self.decline_rematch(player_id).await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets up the game chosen by player one, or the same game again, over the connections of the
+    /// players who finished the last one, then begins it.
+    async fn start_next_game(&mut self) -> Result<(), (WriteError, u8)> {
+        let Some(rematch) = self.rematch.take() else {
+            return Ok(());
+        };
+        let chosen_game = rematch.chosen_game().unwrap_or(self.game_id);
+        let game_ids = self.get_rematch_game_ids();
+        let game_id = if game_ids.iter().any(|id| id == chosen_game) {
+            chosen_game
+        } else {
+            self.game_id
+        };
+        let Some(game) = self.rematch_games.iter().find(|game| game.id() == game_id) else {
+            return Ok(());
+        };
+
+        tracing::info!(game = game.id(), "Rematch agreed");
+        let (game_sender, game_receiver) = mpsc::channel(self.game_events_capacity);
+        self.game = game.make_server(game_sender);
+        self.game_events = GameEvents::new(game_receiver);
+        self.game_id = game.id();
+        // Each game is seeded from the one before, so that a series of games played from one seed
+        // can be played out again too
+        self.rng_seed = GameRng::from_seed(self.rng_seed).next_u64();
+        self.game.set_rng(GameRng::from_seed(self.rng_seed));
+        if let Some(first_player) = self.first_player {
+            self.game.set_first_player(first_player);
+        }
+        self.pacing = Pacing::default();
+        self.winner = None;
+        self.started_at = None;
+        self.state = State::PreInitialise;
+        // A replay holds a single game, which has already been recorded
+        self.replay_recorder = None;
+
+        self.dispatch_event_to_all_players(&OutgoingEvent::NextGame {
+            game: String::from(self.game_id),
+        })
+        .await?;
+        self.begin_game().await
+    }
+
+    /// Ends the rematch on offer, as the given player declined it or left before answering,
+    /// telling everyone else that there will be no rematch.
+    async fn decline_rematch(&mut self, player_id: u8) {
+        tracing::info!(player_id, "Player declined a rematch");
+        self.rematch = None;
+        self.state = State::GameOver;
+        // The server is closing, so players who can no longer be reached are not a concern
+        let _ = self
+            .dispatch_event_to_other_players(&OutgoingEvent::RematchDeclined, player_id)
+            .await;
+        self.dispatch_event_to_spectators(&OutgoingEvent::RematchDeclined)
+            .await;
+    }
+
+    /// Writes everything needed to resume the game to the save file.
+    fn save_game(&self) -> Result<(), SaveError> {
+        let save_path = self.save_path.as_ref().ok_or(SaveError::Disabled)?;
+        let state = self
+            .game
+            .save_state()
+            .ok_or(SaveError::Unsupported(self.game_id))?;
+        let player_ids = self
+            .get_status()
+            .players
+            .into_iter()
+            .map(|(player_id, _)| player_id)
+            .collect();
+
+        SavedGame::new(self.game_id, self.join_token, player_ids, state)
+            .write(File::create(save_path)?)
+    }
+
+    /// Adds the event to the replay being recorded, abandoning the replay if it cannot be written
+    /// to rather than leaving gaps in it.
+    fn record_replay_event(&mut self, event: &ErasedEvent) {
+        if let Some(replay_recorder) = &mut self.replay_recorder {
+            if replay_recorder.record(event).is_err() {
+                self.replay_recorder = None;
+            }
+        }
+    }
+
+    /// Warns the player whose turn it is once they have a single attempt left at making a valid
+    /// move, and tells everyone once they have none, recording each attempt in the replay. Ending
+    /// the game is left to the game itself, which does so once it has sent this.
+    async fn handle_move_rejected(
+        &mut self,
+        player_id: u8,
+        attempts_remaining: u8,
+    ) -> Result<(), (WriteError, u8)> {
+        tracing::debug!(player_id, attempts_remaining, "Move rejected");
+        if let Some(replay_recorder) = &mut self.replay_recorder {
+            if replay_recorder
+                .record_rejected_move(player_id, attempts_remaining)
+                .is_err()
+            {
+                self.replay_recorder = None;
+            }
+        }
+
+        match attempts_remaining {
+            0 => {
+                self.dispatch_event_to_all_players(&OutgoingEvent::RejectedMovesExceeded {
+                    player_id,
+                })
+                .await
+            }
+            1 => {
+                self.dispatch_event_to_player(
+                    &OutgoingEvent::RejectedMovesWarning { attempts_remaining },
+                    player_id,
+                )
+                .await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the game in the stats store and with the result exporter, if there are any,
+    /// updating the ratings of its players if both gave a name.
+    ///
+    /// # Returns
+    ///
+    /// The new rating of each player keyed by their ID, which is empty if they were not rated.
+    fn record_game(&self, winner: Option<u8>) -> BTreeMap<u8, RatingChange> {
+        let Some(started_at) = self.started_at else {
+            return BTreeMap::new();
+        };
+
+        let record = GameRecord {
+            game: String::from(self.game_id),
+            winner,
+            duration: started_at.elapsed(),
+            think_times: self.pacing.get_think_times(),
+        };
+        // The game has already finished, so failing to record it is not worth reporting to players
+        let mut ratings = BTreeMap::new();
+        if let Some(stats_store) = &self.stats_store {
+            let mut stats_store = stats_store.lock().unwrap();
+            let _ = stats_store.record_game(&record);
+            if let Some(players) = self.get_rated_players() {
+                match stats::update_ratings(&mut **stats_store, self.game_id, players, winner) {
+                    Ok(changes) => ratings = changes,
+                    Err(error) => tracing::warn!(?error, "Failed to update the player ratings"),
+                }
+            }
+        }
+        if let Some(result_exporter) = &self.result_exporter {
+            let result = MatchResult::new(&record, SystemTime::now());
+            if let Err(error) = result_exporter.lock().unwrap().export(&result) {
+                tracing::warn!(?error, "Failed to export the result of the game");
+            }
+        }
+
+        ratings
+    }
+
+    /// The ID and name of each player if the game can be rated, which requires there to be two
+    /// players going by different names.
+    fn get_rated_players(&self) -> Option<[(u8, &str); 2]> {
+        let players: Vec<(u8, &str)> = self
+            .names
+            .iter()
+            .map(|(&player_id, name)| (player_id, name.as_str()))
+            .collect();
+        let [one, two] = <[(u8, &str); 2]>::try_from(players).ok()?;
+        let everyone_named = self.get_status().players.len() == 2;
+
+        (everyone_named && one.1 != two.1).then_some([one, two])
+    }
+
+    /// Determines whether a failed read/write should end the game, or whether the affected player
+    /// may be given the chance to reconnect.
+    ///
+    /// Players can only reconnect to a game that has begun and is not yet over, and only if the
+    /// failure was with the connection itself rather than with the content of a message, such as
+    /// when it has gone quiet for longer than the read timeout.
+    ///
+    /// # Parameters
+    ///
     /// - `error_category`: The category of error.
     /// - `player_id`: The ID of the player associated with the error.
+    async fn handle_connection_error(&mut self, error_category: ErrorCategory, player_id: u8) {
+        // A player who leaves rather than answering the offer of a rematch has declined it
+        if self.state == State::RematchOffered {
+            return self.decline_rematch(player_id).await;
+        }
+
+        let tolerated = matches!(
+            self.invalid_message_policy,
+            InvalidMessagePolicy::Tolerate { .. }
+        );
+        if tolerated
+            && error_category == ErrorCategory::Deserialisation
+            && matches!(self.state, State::Setup | State::InProgress)
+        {
+            // Any trouble reaching the player is found on reading from them next
+            let _ = self.refuse_invalid_message(player_id).await;
+            return;
+        }
+
+        let connection_lost = matches!(
+            error_category,
+            ErrorCategory::ReadWrite | ErrorCategory::Timeout
+        );
+        if connection_lost && matches!(self.state, State::Setup | State::InProgress) {
+            if let Some(grace_period) = self.disconnect_player(player_id) {
+                tracing::info!(player_id, "Player disconnected, awaiting reconnection");
+                self.reconnection_countdown = Some(Countdown::start(player_id, grace_period));
+                let _ = self
+                    .dispatch_event_to_other_players(
+                        &OutgoingEvent::OpponentDisconnected {
+                            grace_period_secs: grace_period.as_secs(),
+                        },
+                        player_id,
+                    )
+                    .await;
+
+                return;
+            }
+            if self.hand_seat_to_bot(player_id).await {
+                return;
+            }
+        }
+
+        self.state = State::Error {
+            category: error_category,
+            player_id,
+        }
+    }
+
+    /// Tells the player that a message they sent could not be read, ending the game instead if
+    /// they have now sent as many such messages as the invalid message policy tolerates.
+    async fn refuse_invalid_message(&mut self, player_id: u8) -> Result<(), (WriteError, u8)> {
+        let invalid_messages = self.invalid_messages.entry(player_id).or_default();
+        *invalid_messages += 1;
+        if let InvalidMessagePolicy::Tolerate { limit } = self.invalid_message_policy {
+            if *invalid_messages >= limit {
+                tracing::warn!(player_id, limit, "Player sent too many invalid messages");
+                self.state = State::Error {
+                    category: ErrorCategory::Deserialisation,
+                    player_id,
+                };
+                return Ok(());
+            }
+        }
+
+        self.dispatch_event_to_player(
+            &OutgoingEvent::ErrorOccurred(Error::InvalidMessage),
+            player_id,
+        )
+        .await
+    }
+
+    /// Has a bot take over the seat of the given player, who has left the game, telling the other
+    /// players that it has.
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// This function will panic if the error category is `ErrorCategory::Serialise`, indicating
-    /// an unexpected serialisation error.
+    /// Whether the bot took over, which it only does if enabled and if someone is left to play
+    /// against it.
+    async fn hand_seat_to_bot(&mut self, player_id: u8) -> bool {
+        let someone_left =
+            self.get_status().players.iter().any(|&(id, connected)| {
+                id != player_id && connected && !self.stand_ins.contains(&id)
+            });
+        if !self.bot_takeover || !someone_left || self.stand_ins.contains(&player_id) {
+            return false;
+        }
+        let Some(game) = game::find_game(self.game_id) else {
+            return false;
+        };
+        let (connection, bot_connection) = Connection::pair();
+        if self.replace_player(player_id, connection).is_some() {
+            return false;
+        }
+
+        tracing::info!(player_id, "Bot took over the seat of the player");
+        stand_in::spawn(bot_connection, player_id, game);
+        self.stand_ins.push(player_id);
+        // A game finished by a bot says nothing of how well the player who left would have done
+        self.names.remove(&player_id);
+        let _ = self
+            .dispatch_event_to_player(
+                &OutgoingEvent::GameStarted {
+                    game: String::from(self.game_id),
+                    marks: self.marks.clone(),
+                    config: self.game.config(),
+                },
+                player_id,
+            )
+            .await;
+        self.game_events
+            .during(self.game.handle_player_reconnected(player_id))
+            .await;
+        if let Some(read_timeout) = self.read_timeout {
+            self.time_reads_from(self.pacing.current_player(), read_timeout);
+        }
+        // Players who can no longer be reached will be noticed when they are next read from
+        let _ = self
+            .dispatch_event_to_other_players(&OutgoingEvent::OpponentReplaced, player_id)
+            .await;
+
+        true
+    }
+
+    /// Handles errors that can occur when reading/writing from/to a Client connection.
+    ///
+    /// Possible errors to be handled; IO, invalid parameters, and serialisation and deserialisation
+    /// of `Event` objects.
+    ///
+    /// # Parameters
+    ///
+    /// - `error_category`: The category of error.
+    /// - `player_id`: The ID of the player associated with the error.
+    ///
+    /// A serialisation error is the server's own fault rather than the player's, so every player
+    /// is told the game is over without any of them being blamed for it.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```ignore
     /// handle_error(ErrorCategory::Deserialise, 1).await;
     /// ```
     async fn handle_error(&mut self, error_category: ErrorCategory, player_id: u8) {
         match error_category {
             ErrorCategory::Serialisation => {
-                panic!("Error occurred while attempting to serialise an event.")
+                tracing::error!(player_id, "Failed to serialise an event for player");
+                let _ = self
+                    .dispatch_event_to_all_players(&OutgoingEvent::Shutdown {
+                        reason: ShutdownReason::ServerShutdown,
+                    })
+                    .await;
+                self.shutdown_all_client_connections().await;
             }
-            ErrorCategory::Deserialisation | ErrorCategory::InvalidParameters => {
+            ErrorCategory::Deserialisation
+            | ErrorCategory::InvalidParameters
+            | ErrorCategory::Protocol => {
                 let _ = self
                     .dispatch_event_to_player(
                         &OutgoingEvent::ErrorOccurred(Error::InvalidMessage),
@@ -319,41 +1794,894 @@ where
                     )
                     .await;
                 let _ = self
-                    .dispatch_event_to_all_players(&OutgoingEvent::Shutdown)
+                    .dispatch_event_to_all_players(&OutgoingEvent::Shutdown {
+                        reason: ShutdownReason::ProtocolViolation,
+                    })
                     .await;
                 self.shutdown_all_client_connections().await;
             }
-            ErrorCategory::ReadWrite => {
+            ErrorCategory::ReadWrite | ErrorCategory::Timeout => {
+                let reason = match error_category {
+                    ErrorCategory::Timeout => ShutdownReason::Timeout,
+                    _ => ShutdownReason::OpponentDisconnected,
+                };
                 let _ = self
-                    .dispatch_event_to_player(
-                        &OutgoingEvent::Shutdown,
-                        get_alternative_player_id(player_id),
-                    )
+                    .dispatch_event_to_other_players(&OutgoingEvent::Shutdown { reason }, player_id)
                     .await;
                 self.shutdown_all_client_connections().await;
             }
         }
     }
 
+    /// Ends a game whose server panicked, telling its players and spectators the server shut it
+    /// down. The game is left as it was when it panicked, so nothing more is asked of it.
+    async fn handle_panic(&mut self, message: String) {
+        tracing::error!(game = self.game_id, panic = message, "Game panicked");
+        self.end_crashed().await
+    }
+
+    /// Ends a game that can no longer be played, telling its players and spectators the server
+    /// shut it down.
+    async fn end_crashed(&mut self) {
+        let event = OutgoingEvent::Shutdown {
+            reason: ShutdownReason::ServerShutdown,
+        };
+        // Every player is told, even once writing to one of them has failed
+        for player_id in self.get_player_ids() {
+            let _ = self.dispatch_event_to_player(&event, player_id).await;
+        }
+        self.dispatch_event_to_spectators(&event).await;
+        self.shutdown_all_client_connections().await;
+        self.state = State::Crashed;
+    }
+
     async fn dispatch_game_event(
         &mut self,
         dispatch_mode: DispatchMode,
-        event: Vec<u8>,
+        event: ErasedEvent,
     ) -> Result<(), (WriteError, u8)> {
+        if !matches!(dispatch_mode, DispatchMode::SinglePlayer { .. }) {
+            self.record_replay_event(&event);
+        }
         let event = OutgoingEvent::Game { event };
 
         match dispatch_mode {
-            DispatchMode::AllPlayers => self.dispatch_event_to_all_players(&event),
+            DispatchMode::AllPlayers => self.dispatch_event_to_all_players(&event).await,
             DispatchMode::SinglePlayer { player_id } => {
-                self.dispatch_event_to_player(&event, player_id)
+                self.dispatch_event_to_player(&event, player_id).await
+            }
+            DispatchMode::OtherPlayers { player_id } => {
+                self.dispatch_event_to_other_players(&event, player_id)
+                    .await?;
+                self.dispatch_event_to_spectators(&event).await;
+                Ok(())
+            }
+            DispatchMode::Spectators => {
+                self.dispatch_event_to_spectators(&event).await;
+                Ok(())
             }
         }
-        .await
+    }
+
+    /// Sends each player their own view of a game event, and spectators theirs. Replays record
+    /// the spectators' view, so that watching one back gives away no more than watching live.
+    async fn dispatch_game_views(
+        &mut self,
+        views: BTreeMap<u8, ErasedEvent>,
+        spectator_view: ErasedEvent,
+    ) -> Result<(), (WriteError, u8)> {
+        self.record_replay_event(&spectator_view);
+
+        for (player_id, event) in views {
+            self.dispatch_event_to_player(&OutgoingEvent::Game { event }, player_id)
+                .await?;
+        }
+        self.dispatch_event_to_spectators(&OutgoingEvent::Game {
+            event: spectator_view,
+        })
+        .await;
+
+        Ok(())
     }
 }
 
+/// Who a game event should be sent to.
 #[derive(Debug, Deserialize)]
 pub enum DispatchMode {
     AllPlayers,
-    SinglePlayer { player_id: u8 },
+    SinglePlayer {
+        player_id: u8,
+    },
+    /// Every player but the given one, along with any spectators.
+    OtherPlayers {
+        player_id: u8,
+    },
+    Spectators,
+}
+
+/// The message a panic was raised with, for logging what went wrong.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast_ref::<&str>() {
+            Some(message) => String::from(*message),
+            None => String::from("unknown cause"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::mpsc::Sender;
+    use tokio::sync::Barrier;
+
+    use super::*;
+    use crate::connection::{Codec, Json, SUPPORTED_CODECS};
+    use crate::tic_tac_toe::{BoardConfig, ClientEvent, ServerEvent, TicTacToe};
+
+    /// Starts a game of Tic Tac Toe between two players joined to the server in memory, waiting
+    /// until each has been told that it has started.
+    ///
+    /// # Returns
+    ///
+    /// The connections of player one and two.
+    async fn start_game() -> (Connection, Connection) {
+        start_game_with(|_| {}).await
+    }
+
+    /// Starts a game as `start_game` does, on a server set up by `configure` first.
+    async fn start_game_with(
+        configure: impl FnOnce(&mut Server<OnlineConnection>),
+    ) -> (Connection, Connection) {
+        start_game_of(&TicTacToe::default(), configure).await
+    }
+
+    /// Starts a game as `start_game_with` does, of the given game rather than Tic Tac Toe.
+    async fn start_game_of(
+        game: &dyn GameDescriptor,
+        configure: impl FnOnce(&mut Server<OnlineConnection>),
+    ) -> (Connection, Connection) {
+        let (server_one, mut player_one) = Connection::pair();
+        let (server_two, mut player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+        let mut server = Server::<OnlineConnection>::new(players, game);
+        configure(&mut server);
+        tokio::spawn(async move {
+            server.init().await;
+        });
+
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                connection.read_event().await.unwrap(),
+                OutgoingEvent::GameStarted { .. }
+            ));
+        }
+
+        (player_one, player_two)
+    }
+
+    #[test]
+    fn game_event_encoded_once_by_codec_of_connection() {
+        let event = OutgoingEvent::Game {
+            event: game::serialize_event(ServerEvent::PlayerTurn {
+                player_id: PLAYER_ONE_ID,
+            }),
+        };
+
+        let json = String::from_utf8(Json.encode(&event).unwrap()).unwrap();
+        assert!(
+            json.contains(r#""payload":{"PlayerTurn":{"player_id":1}}"#),
+            "Game event not shown as it is in {}",
+            json
+        );
+        for codec in SUPPORTED_CODECS {
+            let bytes = codec.encode(&event).unwrap();
+            let OutgoingEvent::Game { event } = codec.decode(&bytes).unwrap() else {
+                panic!("Game event decoded as another with {:?}", codec);
+            };
+            assert!(matches!(
+                game::deserialize_event(event),
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                }
+            ));
+        }
+    }
+
+    /// Reads events until one sent by the server itself rather than the game arrives.
+    async fn read_server_event(connection: &mut Connection) -> OutgoingEvent {
+        loop {
+            match connection.read_event().await.unwrap() {
+                OutgoingEvent::Game { .. } => continue,
+                event => return event,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn game_started_with_marks_chosen_by_players_and_game_options() {
+        let (server_one, mut player_one) = Connection::pair();
+        let (server_two, _player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1).with_mark(Some("O".parse().unwrap())),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+        let board_config = BoardConfig {
+            size: 5,
+            win_length: 4,
+            ..BoardConfig::DEFAULT
+        };
+        tokio::spawn(async move {
+            Server::<OnlineConnection>::new(players, &TicTacToe::new(board_config))
+                .init()
+                .await;
+        });
+
+        match player_one.read_event().await.unwrap() {
+            OutgoingEvent::GameStarted {
+                game,
+                marks,
+                config,
+            } => {
+                assert_eq!(game, "tic-tac-toe");
+                assert_eq!(game::deserialize_config(&config), Some(board_config));
+                assert_eq!(marks.get(PLAYER_ONE_ID), "O");
+                assert_eq!(marks.get(PLAYER_TWO_ID), "X");
+            }
+            event => panic!("Expected the game to start, received {:?}", event),
+        }
+    }
+
+    #[tokio::test]
+    async fn player_warned_then_forfeits_after_too_many_invalid_moves() {
+        let replay_path =
+            std::env::temp_dir().join(format!("game-replay-{}.jsonl", std::process::id()));
+        let replay_file = std::fs::File::create(&replay_path).unwrap();
+        let (mut player_one, mut player_two) =
+            start_game_with(move |server| server.set_replay_writer(replay_file)).await;
+
+        for _ in 0..game::RejectedMoves::LIMIT {
+            player_one
+                .write_event(&game::serialize_event(ClientEvent::MoveMade {
+                    player_id: PLAYER_ONE_ID,
+                    move_index: 99,
+                    mark: None,
+                }))
+                .await
+                .unwrap();
+        }
+
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::RejectedMovesWarning {
+                attempts_remaining: 1
+            }
+        ));
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::RejectedMovesExceeded {
+                    player_id: PLAYER_ONE_ID
+                }
+            ));
+        }
+        assert!(matches!(
+            read_server_event(&mut player_two).await,
+            OutgoingEvent::GameSummary { .. }
+        ));
+
+        let replay = std::fs::read_to_string(&replay_path).unwrap();
+        std::fs::remove_file(&replay_path).unwrap();
+        let attempts: Vec<u64> = replay
+            .lines()
+            .filter_map(|line| {
+                let line: serde_json::Value = serde_json::from_str(line).unwrap();
+                line["rejected_move"]["attempts_remaining"].as_u64()
+            })
+            .collect();
+        assert_eq!(attempts, [4, 3, 2, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn server_refuses_event_acting_for_seat_not_held() {
+        let (mut player_one, mut player_two) = start_game().await;
+
+        let move_made = |player_id| {
+            game::serialize_event(ClientEvent::MoveMade {
+                player_id,
+                move_index: 5,
+                mark: None,
+            })
+        };
+        player_two
+            .write_event(&move_made(PLAYER_ONE_ID))
+            .await
+            .unwrap();
+        assert!(matches!(
+            read_server_event(&mut player_two).await,
+            OutgoingEvent::ErrorOccurred(Error::UnexpectedPlayer)
+        ));
+
+        // The refused move never reached the game, leaving the cell free for player one
+        player_one
+            .write_event(&move_made(PLAYER_ONE_ID))
+            .await
+            .unwrap();
+        loop {
+            let OutgoingEvent::Game { event } = player_one.read_event().await.unwrap() else {
+                continue;
+            };
+            match game::deserialize_event(event) {
+                ServerEvent::BoardUpdated { board_cells, .. } if board_cells[4].is_some() => {
+                    assert_eq!(board_cells[4], Some(PLAYER_ONE_ID));
+                    break;
+                }
+                ServerEvent::ErrorOccurred { error } => panic!("Move refused: {:?}", error),
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn server_refuses_event_naming_no_player() {
+        let (mut player_one, _player_two) = start_game().await;
+
+        player_one
+            .write_event(&br#"{"MoveMade":{"move_index":5}}"#.to_vec())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::ErrorOccurred(Error::InvalidMessage)
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_event_game_cannot_read() {
+        let (mut player_one, _player_two) = start_game().await;
+
+        let missing_move = serde_json::json!({"MoveMade": {"player_id": PLAYER_ONE_ID}});
+        player_one
+            .write_event(&game::serialize_event(missing_move))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::ErrorOccurred(Error::InvalidMessage)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unreadable_messages_tolerated_until_limit_reached() {
+        let (mut player_one, mut player_two) = start_game_with(|server| {
+            server.set_invalid_message_policy(InvalidMessagePolicy::Tolerate { limit: 2 })
+        })
+        .await;
+
+        player_one.write_event(&"not an event").await.unwrap();
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::ErrorOccurred(Error::InvalidMessage)
+        ));
+
+        // The game carries on with the player's next message
+        let move_made = game::serialize_event(ClientEvent::MoveMade {
+            player_id: PLAYER_ONE_ID,
+            move_index: 5,
+            mark: None,
+        });
+        player_one.write_event(&move_made).await.unwrap();
+        loop {
+            let OutgoingEvent::Game { event } = player_two.read_event().await.unwrap() else {
+                continue;
+            };
+            match game::deserialize_event(event) {
+                ServerEvent::BoardUpdated { board_cells, .. } if board_cells[4].is_some() => {
+                    assert_eq!(board_cells[4], Some(PLAYER_ONE_ID));
+                    break;
+                }
+                _ => continue,
+            }
+        }
+
+        player_one.write_event(&"not an event").await.unwrap();
+        assert!(matches!(
+            read_server_event(&mut player_two).await,
+            OutgoingEvent::Shutdown {
+                reason: ShutdownReason::ProtocolViolation
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn player_sending_event_before_game_begins_ends_game_without_panicking() {
+        let (server_one, mut player_one) = Connection::pair();
+        let (server_two, mut player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+        let mut server = Server::<OnlineConnection>::new(players, &TicTacToe::default());
+
+        let event = IncomingEvent::Client {
+            event: game::serialize_event(ClientEvent::MoveMade {
+                player_id: PLAYER_ONE_ID,
+                move_index: 0,
+                mark: None,
+            }),
+            player_id: PLAYER_ONE_ID,
+            seats: Seats::single(PLAYER_ONE_ID),
+        };
+        assert!(server.handle_incoming_event(event).await.is_ok());
+        assert_eq!(
+            server.state,
+            State::Error {
+                category: ErrorCategory::Protocol,
+                player_id: PLAYER_ONE_ID
+            }
+        );
+
+        server.run().await;
+        assert!(matches!(
+            player_one.read_event().await.unwrap(),
+            OutgoingEvent::ErrorOccurred(Error::InvalidMessage)
+        ));
+        assert!(matches!(
+            player_two.read_event().await.unwrap(),
+            OutgoingEvent::Shutdown {
+                reason: ShutdownReason::ProtocolViolation
+            }
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn player_quiet_on_their_turn_for_read_timeout_treated_as_disconnected() {
+        let (_player_one, mut player_two) = start_game_with(|server| {
+            server.set_read_timeout(Duration::from_secs(30));
+            server.accept_reconnections(Duration::from_secs(60));
+        })
+        .await;
+
+        // Player two is just as quiet, but has nothing to send while waiting on player one
+        assert!(matches!(
+            read_server_event(&mut player_two).await,
+            OutgoingEvent::OpponentDisconnected {
+                grace_period_secs: 60
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn bot_takes_over_seat_of_player_who_leaves() {
+        let (mut player_one, player_two) =
+            start_game_with(|server| server.set_bot_takeover(true)).await;
+
+        drop(player_two);
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::OpponentReplaced
+        ));
+
+        // The bot answers the move made by player one with one of its own
+        player_one
+            .write_event(&game::serialize_event(ClientEvent::MoveMade {
+                player_id: PLAYER_ONE_ID,
+                move_index: 5,
+                mark: None,
+            }))
+            .await
+            .unwrap();
+        loop {
+            let OutgoingEvent::Game { event } = player_one.read_event().await.unwrap() else {
+                continue;
+            };
+            if let ServerEvent::BoardUpdated { board_cells, .. } = game::deserialize_event(event) {
+                if board_cells.contains(&Some(PLAYER_TWO_ID)) {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn players_set_up_independently_before_taking_turns() {
+        use crate::battleship::{self, Battleship};
+
+        let (mut player_one, mut player_two) = start_game_of(&Battleship, |_| {}).await;
+
+        // Player two places their fleet first, without waiting for a turn
+        for (connection, player_id) in [
+            (&mut player_two, PLAYER_TWO_ID),
+            (&mut player_one, PLAYER_ONE_ID),
+        ] {
+            let placements = [
+                "a1 across",
+                "a2 across",
+                "a3 across",
+                "a4 across",
+                "a5 across",
+            ]
+            .into_iter()
+            .map(|placement| placement.parse().unwrap())
+            .collect();
+            connection
+                .write_event(&game::serialize_event(
+                    battleship::ClientEvent::FleetPlaced {
+                        player_id,
+                        placements,
+                    },
+                ))
+                .await
+                .unwrap();
+        }
+
+        loop {
+            let OutgoingEvent::Game { event } = player_one.read_event().await.unwrap() else {
+                continue;
+            };
+            match game::deserialize_event(event) {
+                battleship::ServerEvent::PlayerTurn { player_id } => {
+                    assert_eq!(player_id, PLAYER_ONE_ID);
+                    break;
+                }
+                battleship::ServerEvent::ErrorOccurred { error } => {
+                    panic!("Fleet refused: {:?}", error)
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    /// Ends the game by having player one resign, then reads the summary each player is sent.
+    async fn resign_game(player_one: &mut Connection, player_two: &mut Connection) -> Vec<String> {
+        player_one
+            .write_event(&game::serialize_event(game::ClientEvent::Resign {
+                player_id: PLAYER_ONE_ID,
+            }))
+            .await
+            .unwrap();
+
+        let mut offered = Vec::new();
+        for connection in [player_one, player_two] {
+            match read_server_event(connection).await {
+                OutgoingEvent::GameSummary { rematch_games, .. } => offered = rematch_games,
+                event => panic!("Expected the game summary, received {:?}", event),
+            }
+        }
+
+        offered
+    }
+
+    fn offer_rematches(server: &mut Server<OnlineConnection>) {
+        server.offer_rematches(vec![
+            Box::new(crate::checkers::Checkers),
+            Box::new(TicTacToe::default()),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn rematch_played_over_same_connections_in_game_chosen_by_player_one() {
+        let (mut player_one, mut player_two) = start_game_with(offer_rematches).await;
+
+        let offered = resign_game(&mut player_one, &mut player_two).await;
+        assert_eq!(
+            offered,
+            [crate::tic_tac_toe::GAME_ID, crate::checkers::GAME_ID]
+        );
+
+        for (connection, player_id, game) in [
+            (&mut player_two, PLAYER_TWO_ID, Some("othello")),
+            (
+                &mut player_one,
+                PLAYER_ONE_ID,
+                Some(crate::checkers::GAME_ID),
+            ),
+        ] {
+            let response = RematchResponse::Accepted {
+                player_id,
+                game: game.map(String::from),
+            };
+            connection
+                .write_event(&game::serialize_event(response))
+                .await
+                .unwrap();
+        }
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::NextGame { game } if game == crate::checkers::GAME_ID
+            ));
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::GameStarted { .. }
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn rematch_ends_once_a_player_declines_or_leaves() {
+        let (mut player_one, mut player_two) = start_game_with(offer_rematches).await;
+        resign_game(&mut player_one, &mut player_two).await;
+
+        let response = RematchResponse::Declined {
+            player_id: PLAYER_TWO_ID,
+        };
+        player_two
+            .write_event(&game::serialize_event(response))
+            .await
+            .unwrap();
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::RematchDeclined
+        ));
+
+        let (mut player_one, mut player_two) = start_game_with(offer_rematches).await;
+        resign_game(&mut player_one, &mut player_two).await;
+
+        drop(player_two);
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::RematchDeclined
+        ));
+    }
+
+    #[tokio::test]
+    async fn no_rematch_offered_unless_enabled() {
+        let (mut player_one, mut player_two) = start_game().await;
+
+        assert!(resign_game(&mut player_one, &mut player_two)
+            .await
+            .is_empty());
+    }
+
+    fn suspend_requested(player_id: u8) -> ErasedEvent {
+        game::serialize_event(ClientEvent::SuspendRequested { player_id })
+    }
+
+    #[tokio::test]
+    async fn suspended_game_is_saved_and_players_sent_away() {
+        let save_path = std::env::temp_dir().join(format!("game-save-{}.json", std::process::id()));
+        let server_save_path = save_path.clone();
+        let (mut player_one, mut player_two) =
+            start_game_with(move |server| server.set_save_path(server_save_path)).await;
+
+        player_one
+            .write_event(&suspend_requested(PLAYER_ONE_ID))
+            .await
+            .unwrap();
+
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::Suspended
+            ));
+        }
+        let saved_game = SavedGame::read(File::open(&save_path).unwrap()).unwrap();
+        std::fs::remove_file(&save_path).unwrap();
+        assert_eq!(saved_game.game, crate::tic_tac_toe::GAME_ID);
+        assert_eq!(saved_game.player_ids, vec![PLAYER_ONE_ID, PLAYER_TWO_ID]);
+        assert!(saved_game.restore().is_ok());
+    }
+
+    #[tokio::test]
+    async fn game_carries_on_when_it_cannot_be_saved() {
+        let (mut player_one, _player_two) = start_game().await;
+
+        player_one
+            .write_event(&suspend_requested(PLAYER_ONE_ID))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            read_server_event(&mut player_one).await,
+            OutgoingEvent::ErrorOccurred(Error::SavingUnavailable)
+        ));
+        loop {
+            let OutgoingEvent::Game { event } = player_one.read_event().await.unwrap() else {
+                panic!("Expected the game to carry on");
+            };
+            if let ServerEvent::PlayerTurn { player_id } = game::deserialize_event(event) {
+                assert_eq!(player_id, PLAYER_ONE_ID);
+                break;
+            }
+        }
+    }
+
+    /// A game whose server panics on the first event a player sends it. If given a barrier, the
+    /// server waits on it both on being sent the event and before panicking.
+    #[derive(Default)]
+    struct PanickingGame {
+        barrier: Option<Arc<Barrier>>,
+    }
+
+    impl GameDescriptor for PanickingGame {
+        fn id(&self) -> &'static str {
+            "panicking"
+        }
+
+        fn make_server(
+            &self,
+            channel: Sender<GameServerEvent>,
+        ) -> Box<dyn AnyGameServer + Send + Sync> {
+            Box::new(PanickingServer {
+                _channel: channel,
+                barrier: self.barrier.clone(),
+            })
+        }
+
+        fn make_client<'a>(
+            &self,
+            context: game::ClientContext<'a>,
+        ) -> Box<dyn game::AnyGameClient + 'a> {
+            TicTacToe::default().make_client(context)
+        }
+    }
+
+    struct PanickingServer {
+        _channel: Sender<GameServerEvent>,
+        barrier: Option<Arc<Barrier>>,
+    }
+
+    #[async_trait]
+    impl game::GameServer for PanickingServer {
+        type ClientEvent = ClientEvent;
+        type ServerEvent = ServerEvent;
+
+        async fn begin(&self) {}
+
+        async fn handle_event(&mut self, _event: ClientEvent) {
+            if let Some(barrier) = &self.barrier {
+                barrier.wait().await;
+                barrier.wait().await;
+            }
+            panic!("Game went wrong");
+        }
+
+        async fn handle_turn_timed_out(&mut self, _player_id: u8, _action: TimeoutAction) {}
+
+        async fn handle_player_reconnected(&self, _player_id: u8) {}
+
+        async fn handle_spectator_joined(&self) {}
+    }
+
+    #[tokio::test]
+    async fn game_that_panics_is_shut_down_without_taking_server_with_it() {
+        let (mut player_one, mut player_two) =
+            start_game_of(&PanickingGame::default(), |_| {}).await;
+
+        let move_made = game::serialize_event(ClientEvent::MoveMade {
+            player_id: PLAYER_ONE_ID,
+            move_index: 0,
+            mark: None,
+        });
+        player_one.write_event(&move_made).await.unwrap();
+
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::Shutdown {
+                    reason: ShutdownReason::ServerShutdown
+                }
+            ));
+            assert!(connection.read_event::<OutgoingEvent>().await.is_err());
+        }
+    }
+    #[tokio::test]
+    async fn every_player_and_spectator_told_of_panic_once_despite_dead_connection() {
+        let barrier = Arc::new(Barrier::new(2));
+        let game = PanickingGame {
+            barrier: Some(Arc::clone(&barrier)),
+        };
+        let mut server_channel = None;
+        let (player_one, mut player_two) = start_game_of(&game, |server| {
+            server.set_spectator_config(SpectatorConfig {
+                max_spectators: 2,
+                notify_players: false,
+            });
+            server_channel = Some(server.channel.0.clone());
+        })
+        .await;
+        let server_channel = server_channel.unwrap();
+
+        let mut spectators = Vec::new();
+        for _ in 0..2 {
+            let (server_end, mut spectator) = Connection::pair();
+            server_channel
+                .send(super::ServerEvent::SpectatorJoined {
+                    connection: server_end,
+                })
+                .await
+                .unwrap();
+            assert!(matches!(
+                spectator.read_event().await.unwrap(),
+                OutgoingEvent::GameStarted { .. }
+            ));
+            spectators.push(spectator);
+        }
+
+        // The game panics only once player one's connection has gone, so writing to it fails
+        let move_made = game::serialize_event(ClientEvent::MoveMade {
+            player_id: PLAYER_TWO_ID,
+            move_index: 0,
+            mark: None,
+        });
+        player_two.write_event(&move_made).await.unwrap();
+        barrier.wait().await;
+        drop(player_one);
+        barrier.wait().await;
+
+        for connection in std::iter::once(&mut player_two).chain(spectators.iter_mut()) {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::Shutdown {
+                    reason: ShutdownReason::ServerShutdown
+                }
+            ));
+            assert!(connection.read_event::<OutgoingEvent>().await.is_err());
+        }
+    }
+
+    /// A game whose server drops its channel as soon as it is made, so never sends an event.
+    struct SilentGame;
+
+    impl GameDescriptor for SilentGame {
+        fn id(&self) -> &'static str {
+            "silent"
+        }
+
+        fn make_server(
+            &self,
+            channel: Sender<GameServerEvent>,
+        ) -> Box<dyn AnyGameServer + Send + Sync> {
+            drop(channel);
+            Box::new(SilentServer)
+        }
+
+        fn make_client<'a>(
+            &self,
+            context: game::ClientContext<'a>,
+        ) -> Box<dyn game::AnyGameClient + 'a> {
+            TicTacToe::default().make_client(context)
+        }
+    }
+
+    struct SilentServer;
+
+    #[async_trait]
+    impl game::GameServer for SilentServer {
+        type ClientEvent = ClientEvent;
+        type ServerEvent = ServerEvent;
+
+        async fn begin(&self) {}
+
+        async fn handle_event(&mut self, _event: ClientEvent) {}
+
+        async fn handle_turn_timed_out(&mut self, _player_id: u8, _action: TimeoutAction) {}
+
+        async fn handle_player_reconnected(&self, _player_id: u8) {}
+
+        async fn handle_spectator_joined(&self) {}
+    }
+
+    #[tokio::test]
+    async fn game_that_drops_its_channel_is_shut_down_without_panicking() {
+        let (mut player_one, mut player_two) = start_game_of(&SilentGame, |_| {}).await;
+
+        for connection in [&mut player_one, &mut player_two] {
+            assert!(matches!(
+                read_server_event(connection).await,
+                OutgoingEvent::Shutdown {
+                    reason: ShutdownReason::ServerShutdown
+                }
+            ));
+            assert!(connection.read_event::<OutgoingEvent>().await.is_err());
+        }
+    }
 }
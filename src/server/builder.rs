@@ -0,0 +1,293 @@
+use std::time::Duration;
+
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use crate::connection::Connection;
+use crate::game::GameDescriptor;
+use crate::lobby::Lobby;
+use crate::server::queue::BackpressurePolicy;
+use crate::server::timer::TurnTimer;
+use crate::server::{GameEnd, LocalConnection, OnlineConnection, Player, Server};
+
+/// How many events may wait to be handled by a game at once. Each is at least one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChannelCapacities {
+    /// Events sent by the game itself, such as those to be passed on to its players. A game may
+    /// send more than this in handling a single event, with the rest set aside until it is done.
+    pub game_events: usize,
+    /// Events sent to the game from outside it, such as players rejoining it and notices from the
+    /// server operator.
+    pub server_events: usize,
+}
+
+impl Default for ChannelCapacities {
+    fn default() -> Self {
+        ChannelCapacities {
+            game_events: 10,
+            server_events: 1,
+        }
+    }
+}
+
+/// How long players are given before the server stops waiting on them. Each is unlimited unless
+/// set.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Timeouts {
+    /// The time each player has to make their move, as with `Server::set_turn_timer`.
+    pub turn: Option<TurnTimer>,
+    /// How long the player whose turn it is may send nothing, as with `Server::set_read_timeout`.
+    pub read: Option<Duration>,
+}
+
+/// How the players of a game built by a `ServerBuilder` reach the server.
+pub enum Transport {
+    /// Players join through a lobby accepting connections on the listener, which stays open for
+    /// them to rejoin should they lose their connection.
+    Listener(TcpListener),
+    /// Players already connected, who take their turns in the order of their IDs.
+    Players(Vec<Player>),
+    /// A single connection playing every seat, as in a local game.
+    Local(Connection),
+}
+
+/// Sets up a server, for applications hosting games of their own rather than through the command
+/// line. A game and a transport must be given before the server is spawned.
+///
+/// # Example
+///
+/// ```ignore
+/// let handle = Server::builder()
+///     .game(TicTacToe::default())
+///     .transport(Transport::Listener(listener))
+///     .timeouts(Timeouts { read: Some(Duration::from_secs(60)), ..Timeouts::default() })
+///     .spawn()?;
+/// let game_end = handle.await_result().await?;
+/// ```
+#[derive(Default)]
+pub struct ServerBuilder {
+    game: Option<Box<dyn GameDescriptor>>,
+    transport: Option<Transport>,
+    pub(super) timeouts: Timeouts,
+    pub(super) channel_capacities: ChannelCapacities,
+    pub(super) backpressure_policy: BackpressurePolicy,
+}
+
+impl ServerBuilder {
+    pub fn new() -> Self {
+        ServerBuilder::default()
+    }
+
+    pub fn game(mut self, game: impl GameDescriptor + 'static) -> Self {
+        self.game = Some(Box::new(game));
+        self
+    }
+
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    pub fn timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    pub fn channel_capacities(mut self, channel_capacities: ChannelCapacities) -> Self {
+        self.channel_capacities = channel_capacities;
+        self
+    }
+
+    /// Decides what becomes of events sent to a game that has fallen behind, once as many as
+    /// `ChannelCapacities::server_events` are waiting for it.
+    pub fn backpressure_policy(mut self, backpressure_policy: BackpressurePolicy) -> Self {
+        self.backpressure_policy = backpressure_policy;
+        self
+    }
+
+    /// Plays the game in a task of its own, over the transport given. Players joining through a
+    /// listener are waited on within the task, so this returns at once.
+    pub fn spawn(mut self) -> Result<ServerHandle, BuildError> {
+        let game = self.game.take().ok_or(BuildError::NoGame)?;
+        let transport = self.transport.take().ok_or(BuildError::NoTransport)?;
+
+        let task = tokio::spawn(async move {
+            match transport {
+                Transport::Listener(listener) => {
+                    let mut lobby = Lobby::new(listener);
+                    lobby.set_game(game);
+                    lobby.set_server_builder(self);
+                    let mut server = lobby.set_up_online_server().await?;
+                    server.init().await;
+                    server.game_end()
+                }
+                Transport::Players(players) => {
+                    let mut server = self.online(players, game.as_ref());
+                    server.init().await;
+                    server.game_end()
+                }
+                Transport::Local(connection) => {
+                    let mut server = self.local(connection, game.as_ref());
+                    server.init().await;
+                    server.game_end()
+                }
+            }
+        });
+
+        Ok(ServerHandle { task })
+    }
+
+    /// Sets up a server for a game played over a single connection, as `Server::new` does. Any
+    /// game or transport given to the builder is left unused.
+    pub fn local(
+        &self,
+        connection: Connection,
+        game: &dyn GameDescriptor,
+    ) -> Server<LocalConnection> {
+        Server::<LocalConnection>::with_builder(connection, game, self)
+    }
+
+    /// Sets up a server for the game played by the given players, as `Server::new` does. Any game
+    /// or transport given to the builder is left unused.
+    pub fn online(
+        &self,
+        players: Vec<Player>,
+        game: &dyn GameDescriptor,
+    ) -> Server<OnlineConnection> {
+        Server::<OnlineConnection>::with_builder(players, game, self)
+    }
+}
+
+/// A game being played in a task of its own, as spawned by a `ServerBuilder`.
+#[derive(Debug)]
+pub struct ServerHandle {
+    task: JoinHandle<Option<GameEnd>>,
+}
+
+impl ServerHandle {
+    /// Waits for the game to finish, returning how it ended. `None` if it ended without a result,
+    /// such as by being suspended or by the lobby giving up on its players.
+    pub async fn await_result(self) -> Result<Option<GameEnd>, ServerStopped> {
+        self.task.await.map_err(|error| match error.is_cancelled() {
+            true => ServerStopped::Aborted,
+            false => ServerStopped::Panicked,
+        })
+    }
+
+    /// Stops the game at once, dropping every connection to it without telling its players why.
+    pub fn abort(&self) {
+        self.task.abort();
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq)]
+pub enum BuildError {
+    #[error("No game was given to be played")]
+    NoGame,
+    #[error("No transport was given for players to reach the server over")]
+    NoTransport,
+}
+
+/// Why a game spawned by a `ServerBuilder` stopped without finishing.
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq)]
+pub enum ServerStopped {
+    #[error("The game was aborted")]
+    Aborted,
+    #[error("The server playing the game panicked")]
+    Panicked,
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time;
+
+    use crate::server::{OutgoingEvent, PLAYER_ONE_ID, PLAYER_TWO_ID};
+    use crate::tic_tac_toe::TicTacToe;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn spawned_game_reports_how_it_ended() {
+        let (server_one, mut player_one) = Connection::pair();
+        let (server_two, player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+
+        let handle = Server::builder()
+            .game(TicTacToe::default())
+            .transport(Transport::Players(players))
+            .spawn()
+            .unwrap();
+        assert!(matches!(
+            player_one.read_event().await.unwrap(),
+            OutgoingEvent::GameStarted { .. }
+        ));
+        drop(player_two);
+
+        assert_eq!(
+            handle.await_result().await,
+            Ok(Some(GameEnd::Abandoned {
+                player_id: PLAYER_TWO_ID
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn game_sending_more_events_than_channel_holds_is_played() {
+        let (server_one, mut player_one) = Connection::pair();
+        let (server_two, _player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+
+        let _handle = Server::builder()
+            .game(TicTacToe::default())
+            .transport(Transport::Players(players))
+            .channel_capacities(ChannelCapacities {
+                game_events: 1,
+                server_events: 1,
+            })
+            .spawn()
+            .unwrap();
+
+        // The game sends the board and whose turn it is as it begins, after the game has started
+        for _ in 0..3 {
+            time::timeout(
+                Duration::from_secs(1),
+                player_one.read_event::<OutgoingEvent>(),
+            )
+            .await
+            .expect("The game stopped sending events")
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn aborted_game_reports_being_aborted() {
+        let (server_one, _player_one) = Connection::pair();
+        let (server_two, _player_two) = Connection::pair();
+        let players = vec![
+            Player::new(PLAYER_ONE_ID, server_one, 1),
+            Player::new(PLAYER_TWO_ID, server_two, 2),
+        ];
+
+        let handle = Server::builder()
+            .game(TicTacToe::default())
+            .transport(Transport::Players(players))
+            .spawn()
+            .unwrap();
+        handle.abort();
+
+        assert_eq!(handle.await_result().await, Err(ServerStopped::Aborted));
+    }
+
+    #[tokio::test]
+    async fn game_is_not_spawned_without_transport() {
+        let result = Server::builder().game(TicTacToe::default()).spawn();
+
+        assert!(matches!(result, Err(BuildError::NoTransport)));
+    }
+}
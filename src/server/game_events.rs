@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+use std::future::Future;
+
+use tokio::sync::mpsc::Receiver;
+
+use crate::game::GameServerEvent;
+
+/// The events sent by the game, received while the server is calling into the game as well as
+/// between calls.
+///
+/// Games send several events from a single call, each waiting for room in the channel, so a
+/// channel smaller than a call's events would otherwise leave the game waiting on a server that
+/// is itself waiting on the game.
+#[derive(Debug)]
+pub(crate) struct GameEvents {
+    receiver: Receiver<GameServerEvent>,
+    /// Received during a call into the game, to be handled in order once it returns.
+    set_aside: VecDeque<GameServerEvent>,
+}
+
+impl GameEvents {
+    pub(crate) fn new(receiver: Receiver<GameServerEvent>) -> GameEvents {
+        GameEvents {
+            receiver,
+            set_aside: VecDeque::new(),
+        }
+    }
+
+    /// Waits for the next event sent by the game. Cancel safe, as the events set aside are only
+    /// taken once they are returned.
+    ///
+    /// # Returns
+    ///
+    /// `None` once the game has been dropped and every event it sent handled.
+    pub(crate) async fn recv(&mut self) -> Option<GameServerEvent> {
+        match self.set_aside.pop_front() {
+            Some(event) => Some(event),
+            None => self.receiver.recv().await,
+        }
+    }
+
+    /// Runs a call into the game, making room for the events it sends by setting them aside until
+    /// the call returns.
+    pub(crate) async fn during<T>(&mut self, call: impl Future<Output = T>) -> T {
+        tokio::pin!(call);
+        loop {
+            tokio::select! {
+                biased;
+                output = &mut call => return output,
+                Some(event) = self.receiver.recv() => self.set_aside.push_back(event),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn events_sent_during_call_kept_in_order_beyond_capacity() {
+        let (sender, receiver) = mpsc::channel(1);
+        let mut game_events = GameEvents::new(receiver);
+
+        game_events
+            .during(async {
+                for player_id in 1..=3 {
+                    sender
+                        .send(GameServerEvent::TurnStarted { player_id })
+                        .await
+                        .unwrap();
+                }
+            })
+            .await;
+        drop(sender);
+
+        let mut player_ids = Vec::new();
+        while let Some(event) = game_events.recv().await {
+            match event {
+                GameServerEvent::TurnStarted { player_id } => player_ids.push(player_id),
+                event => panic!("Unexpected event {:?}", event),
+            }
+        }
+        assert_eq!(player_ids, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::server::PLAYER_ONE_ID;
+
+/// The marks given to players who did not choose their own, in the order of their IDs. There is
+/// one for as many players as a game can have.
+const DEFAULT_MARKS: [&str; 7] = ["X", "O", "+", "#", "@", "%", "&"];
+/// Characters used to draw the board or number its cells, which would be mistaken for a mark.
+const RESERVED_CHARACTERS: &str = "0123456789./|_";
+
+/// The symbol a player's moves are shown with on the board, a single character wide so that the
+/// board stays aligned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PlayerMark(String);
+
+impl PlayerMark {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PlayerMark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PlayerMark {
+    type Err = InvalidMark;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.graphemes(true).count() != 1 {
+            return Err(InvalidMark::NotSingleCharacter);
+        }
+        if s.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(InvalidMark::Blank);
+        }
+        if s.width() != 1 {
+            return Err(InvalidMark::NotSingleWidth);
+        }
+        if RESERVED_CHARACTERS.contains(s) {
+            return Err(InvalidMark::Reserved);
+        }
+
+        Ok(PlayerMark(String::from(s)))
+    }
+}
+
+impl TryFrom<String> for PlayerMark {
+    type Error = InvalidMark;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<PlayerMark> for String {
+    fn from(mark: PlayerMark) -> Self {
+        mark.0
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum InvalidMark {
+    #[error("A mark must be a single character")]
+    NotSingleCharacter,
+    #[error("A mark must be visible")]
+    Blank,
+    #[error("A mark must take up a single column, so that the board stays aligned")]
+    NotSingleWidth,
+    #[error("Digits and the characters \"./|_\" are used to draw the board, so cannot be marks")]
+    Reserved,
+}
+
+/// The mark of each player in a game, keyed by their ID.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PlayerMarks(BTreeMap<u8, PlayerMark>);
+
+impl PlayerMarks {
+    /// Gives each player the mark they chose, and those who did not the first default mark that
+    /// nobody else has.
+    ///
+    /// # Parameters
+    ///
+    /// - `players`: The ID of each player along with the mark they chose, if any. No two players
+    ///   may have chosen the same mark.
+    pub fn assign(players: impl IntoIterator<Item = (u8, Option<PlayerMark>)>) -> PlayerMarks {
+        let players: Vec<_> = players.into_iter().collect();
+        let mut marks: BTreeMap<u8, PlayerMark> = players
+            .iter()
+            .filter_map(|(player_id, mark)| Some((*player_id, mark.clone()?)))
+            .collect();
+        let mut defaults = DEFAULT_MARKS
+            .iter()
+            .map(|&mark| PlayerMark(String::from(mark)));
+        for (player_id, _) in players.iter().filter(|(_, mark)| mark.is_none()) {
+            if let Some(mark) = defaults.find(|mark| !marks.values().any(|taken| taken == mark)) {
+                marks.insert(*player_id, mark);
+            }
+        }
+
+        PlayerMarks(marks)
+    }
+
+    /// The mark of the player with the given ID, which is their default one if they have not been
+    /// given another.
+    pub fn get(&self, player_id: u8) -> &str {
+        match self.0.get(&player_id) {
+            Some(mark) => mark.as_str(),
+            None => player_id
+                .checked_sub(PLAYER_ONE_ID)
+                .and_then(|index| DEFAULT_MARKS.get(index as usize))
+                .unwrap_or(&"?"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::PLAYER_TWO_ID;
+
+    fn mark(mark: &str) -> PlayerMark {
+        mark.parse().unwrap()
+    }
+
+    #[test]
+    fn single_character_accepted_as_mark() {
+        for accepted in ["A", "★", "é", "e\u{301}"] {
+            assert_eq!(mark(accepted).as_str(), accepted);
+        }
+    }
+
+    #[test]
+    fn marks_that_would_misalign_board_rejected() {
+        assert_eq!(
+            "XO".parse::<PlayerMark>(),
+            Err(InvalidMark::NotSingleCharacter)
+        );
+        assert_eq!(
+            "".parse::<PlayerMark>(),
+            Err(InvalidMark::NotSingleCharacter)
+        );
+        assert_eq!(" ".parse::<PlayerMark>(), Err(InvalidMark::Blank));
+        assert_eq!("猫".parse::<PlayerMark>(), Err(InvalidMark::NotSingleWidth));
+        assert_eq!("🐱".parse::<PlayerMark>(), Err(InvalidMark::NotSingleWidth));
+        assert_eq!("5".parse::<PlayerMark>(), Err(InvalidMark::Reserved));
+        assert_eq!("|".parse::<PlayerMark>(), Err(InvalidMark::Reserved));
+    }
+
+    #[test]
+    fn invalid_mark_refused_when_deserialised() {
+        assert!(serde_json::from_str::<PlayerMark>(r#""XO""#).is_err());
+        assert_eq!(
+            serde_json::from_str::<PlayerMark>(r#""★""#).unwrap(),
+            mark("★")
+        );
+    }
+
+    #[test]
+    fn players_without_marks_given_defaults() {
+        let marks = PlayerMarks::assign([(PLAYER_ONE_ID, None), (PLAYER_TWO_ID, None)]);
+
+        assert_eq!(marks.get(PLAYER_ONE_ID), "X");
+        assert_eq!(marks.get(PLAYER_TWO_ID), "O");
+    }
+
+    #[test]
+    fn default_mark_taken_by_another_player_skipped() {
+        let marks = PlayerMarks::assign([(PLAYER_ONE_ID, Some(mark("O"))), (PLAYER_TWO_ID, None)]);
+
+        assert_eq!(marks.get(PLAYER_ONE_ID), "O");
+        assert_eq!(marks.get(PLAYER_TWO_ID), "X");
+    }
+
+    #[test]
+    fn unassigned_marks_fall_back_to_defaults() {
+        let marks = PlayerMarks::default();
+
+        assert_eq!(marks.get(PLAYER_ONE_ID), "X");
+        assert_eq!(marks.get(PLAYER_TWO_ID), "O");
+    }
+}
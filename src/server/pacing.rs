@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// How long a player spent thinking over their turns.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThinkTime {
+    pub turns: u32,
+    pub total: Duration,
+    pub longest: Duration,
+}
+
+impl ThinkTime {
+    pub fn average(&self) -> Duration {
+        match self.turns {
+            0 => Duration::ZERO,
+            turns => self.total / turns,
+        }
+    }
+
+    /// Combines the think times of the same player across separate games.
+    pub fn add(&mut self, other: &ThinkTime) {
+        self.turns += other.turns;
+        self.total += other.total;
+        self.longest = self.longest.max(other.longest);
+    }
+
+    fn add_turn(&mut self, duration: Duration) {
+        self.add(&ThinkTime {
+            turns: 1,
+            total: duration,
+            longest: duration,
+        });
+    }
+}
+
+/// Times each turn of a game, from it starting until the next turn starts or the game ends.
+#[derive(Debug, Default)]
+pub(crate) struct Pacing {
+    current_turn: Option<(u8, Instant)>,
+    think_times: BTreeMap<u8, ThinkTime>,
+}
+
+impl Pacing {
+    pub(crate) fn start_turn(&mut self, player_id: u8) {
+        self.end_turn();
+        self.current_turn = Some((player_id, Instant::now()));
+    }
+
+    pub(crate) fn end_turn(&mut self) {
+        if let Some((player_id, started_at)) = self.current_turn.take() {
+            self.think_times
+                .entry(player_id)
+                .or_default()
+                .add_turn(started_at.elapsed());
+        }
+    }
+
+    /// The ID of the player whose turn it is, if any.
+    pub(crate) fn current_player(&self) -> Option<u8> {
+        self.current_turn.map(|(player_id, _)| player_id)
+    }
+
+    /// The think times of each player so far, keyed by their ID.
+    pub(crate) fn get_think_times(&self) -> BTreeMap<u8, ThinkTime> {
+        self.think_times.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time;
+
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn turns_are_timed_until_the_next_turn_starts() {
+        let mut pacing = Pacing::default();
+
+        pacing.start_turn(1);
+        time::advance(Duration::from_secs(3)).await;
+        pacing.start_turn(2);
+        time::advance(Duration::from_secs(10)).await;
+        pacing.start_turn(1);
+        time::advance(Duration::from_secs(5)).await;
+        pacing.end_turn();
+
+        let think_times = pacing.get_think_times();
+        assert_eq!(
+            think_times[&1],
+            ThinkTime {
+                turns: 2,
+                total: Duration::from_secs(8),
+                longest: Duration::from_secs(5),
+            }
+        );
+        assert_eq!(think_times[&1].average(), Duration::from_secs(4));
+        assert_eq!(think_times[&2].longest, Duration::from_secs(10));
+    }
+
+    #[test]
+    fn average_of_no_turns_is_zero() {
+        assert_eq!(ThinkTime::default().average(), Duration::ZERO);
+    }
+}
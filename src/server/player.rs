@@ -1,27 +1,133 @@
-use crate::connection::Connection;
+use std::fmt;
+use std::future::{self, Future};
+use std::str::FromStr;
+use std::task::Poll;
+
+use serde::{Deserialize, Serialize};
+
+use crate::connection::{Connection, ReadError};
+use crate::game::{ErasedEvent, Seats};
+use crate::server::PlayerMark;
 
 pub const PLAYER_ONE_ID: u8 = 1;
 pub const PLAYER_TWO_ID: u8 = 2;
+/// The most players a game can have, as the seats a connection holds can only name this many.
+pub const MAX_PLAYERS: u8 = 7;
+/// Held by a connection playing both sides of a game.
+pub const BOTH_SEATS: Seats = Seats::single(PLAYER_ONE_ID).with(PLAYER_TWO_ID);
+/// The most characters a player's name may have.
+pub const MAX_NAME_LENGTH: usize = 32;
+
+/// Who takes the first turn of a game played by two players.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum FirstPlayer {
+    #[default]
+    PlayerOne,
+    PlayerTwo,
+    /// Either player, chosen at random as the game is set up.
+    Random,
+}
+
+/// The name a player goes by from one game to the next, which their rating is kept under.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PlayerName(String);
+
+impl PlayerName {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PlayerName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PlayerName {
+    type Err = InvalidName;
+
+    /// Reads the name with any surrounding whitespace removed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        if name.is_empty() {
+            return Err(InvalidName::Blank);
+        }
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(InvalidName::TooLong);
+        }
+        if name.chars().any(char::is_control) {
+            return Err(InvalidName::ControlCharacter);
+        }
+
+        Ok(PlayerName(String::from(name)))
+    }
+}
+
+impl TryFrom<String> for PlayerName {
+    type Error = InvalidName;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
 
+impl From<PlayerName> for String {
+    fn from(name: PlayerName) -> Self {
+        name.0
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum InvalidName {
+    #[error("A name must not be blank")]
+    Blank,
+    #[error("A name must be at most {MAX_NAME_LENGTH} characters long")]
+    TooLong,
+    #[error("A name must not contain control characters")]
+    ControlCharacter,
+}
+
+/// A player taking part in an online game, along with their connection to the server.
 #[derive(Debug)]
 pub struct Player {
-    id: u8,
+    pub(crate) id: u8,
     pub(crate) connection: Connection,
+    pub(crate) seats: Seats,
+    pub(crate) session_token: u64,
+    pub(crate) connected: bool,
+    /// The mark the player chose to be shown with, if any.
+    pub(crate) mark: Option<PlayerMark>,
+    /// The name the player goes by, if they gave one to be rated under.
+    pub(crate) name: Option<PlayerName>,
 }
 
 impl Player {
-    pub fn new_player_one(connection: Connection) -> Player {
+    /// Seats the player with the given ID, which is counted from `PLAYER_ONE_ID` in the order
+    /// players take their turns.
+    pub fn new(id: u8, connection: Connection, session_token: u64) -> Player {
         Player {
-            id: PLAYER_ONE_ID,
+            id,
             connection,
+            seats: Seats::single(id),
+            session_token,
+            connected: true,
+            mark: None,
+            name: None,
         }
     }
 
-    pub fn new_player_two(connection: Connection) -> Player {
-        Player {
-            id: PLAYER_TWO_ID,
-            connection,
-        }
+    /// Shows the player's moves with the given mark rather than a default one.
+    pub fn with_mark(mut self, mark: Option<PlayerMark>) -> Player {
+        self.mark = mark;
+        self
+    }
+
+    /// Rates the player under the given name once the game is over.
+    pub fn with_name(mut self, name: Option<PlayerName>) -> Player {
+        self.name = name;
+        self
     }
 }
 
@@ -31,10 +137,85 @@ impl PartialEq for Player {
     }
 }
 
+/// The ID of the player whose turn follows the given player's, going back to the first player
+/// after the last.
+pub fn get_next_player_id(player_id: u8, player_count: u8) -> u8 {
+    player_id % player_count + PLAYER_ONE_ID
+}
+
+/// The ID of the other player in a two player game.
 pub fn get_alternative_player_id(player_id: u8) -> u8 {
-    if player_id == PLAYER_ONE_ID {
-        PLAYER_TWO_ID
-    } else {
-        PLAYER_ONE_ID
+    get_next_player_id(player_id, 2)
+}
+
+/// Waits for an event from any of the provided players that are connected.
+///
+/// If no player is connected this future never completes.
+///
+/// # Returns
+///
+/// The event along with the ID and seats of the player that sent it, or the error reading it along
+/// with the ID of the player.
+pub(crate) async fn player_event_received(
+    players: &mut [Player],
+) -> Result<(ErasedEvent, u8, Seats), (ReadError, u8)> {
+    let mut reads: Vec<_> = players
+        .iter_mut()
+        .filter(|player| player.connected)
+        .map(|player| {
+            let (id, seats) = (player.id, player.seats);
+            Box::pin(async move {
+                player
+                    .connection
+                    .read_event()
+                    .await
+                    .map(|event| (event, id, seats))
+                    .map_err(|error| (error, id))
+            })
+        })
+        .collect();
+
+    future::poll_fn(|cx| {
+        reads
+            .iter_mut()
+            .find_map(|read| match read.as_mut().poll(cx) {
+                Poll::Ready(result) => Some(Poll::Ready(result)),
+                Poll::Pending => None,
+            })
+            .unwrap_or(Poll::Pending)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_player_goes_back_to_first_after_last() {
+        assert_eq!(get_next_player_id(1, 3), 2);
+        assert_eq!(get_next_player_id(2, 3), 3);
+        assert_eq!(get_next_player_id(3, 3), 1);
+        assert_eq!(get_alternative_player_id(PLAYER_ONE_ID), PLAYER_TWO_ID);
+        assert_eq!(get_alternative_player_id(PLAYER_TWO_ID), PLAYER_ONE_ID);
+    }
+
+    #[test]
+    fn name_read_without_surrounding_whitespace() {
+        assert_eq!("  Ada ".parse::<PlayerName>().unwrap().as_str(), "Ada");
+    }
+
+    #[test]
+    fn invalid_names_rejected() {
+        assert_eq!("   ".parse::<PlayerName>(), Err(InvalidName::Blank));
+        assert_eq!(
+            "a".repeat(33).parse::<PlayerName>(),
+            Err(InvalidName::TooLong)
+        );
+        assert_eq!(
+            "Ada\u{7}".parse::<PlayerName>(),
+            Err(InvalidName::ControlCharacter)
+        );
+        assert!(serde_json::from_str::<PlayerName>(r#""""#).is_err());
     }
 }
@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+use crate::server::ServerEvent;
+
+/// What becomes of an event sent to a game whose queue is full, as happens when the game handles
+/// events more slowly than they are sent to it.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum BackpressurePolicy {
+    /// The sender waits for the game to make room for the event.
+    #[default]
+    Buffer,
+    /// Events that are only shown to players, such as notices from the server operator, push out
+    /// the oldest of them still queued. Other events wait for room as with `Buffer`.
+    DropOldest,
+    /// The event is refused, so that nobody is kept waiting on the game. Players rejoining it and
+    /// spectators arriving are disconnected.
+    Disconnect,
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq)]
+pub(crate) enum SendError {
+    #[error("The game is no longer being played")]
+    Closed,
+    #[error("The game is not keeping up with the events sent to it")]
+    Full,
+}
+
+/// Creates a queue holding up to `capacity` events for a game, with `policy` deciding what is done
+/// with those sent once it is full.
+pub(crate) fn event_queue(
+    capacity: usize,
+    policy: BackpressurePolicy,
+) -> (EventSender, EventReceiver) {
+    let shared = Arc::new(Shared {
+        events: Mutex::new(VecDeque::with_capacity(capacity)),
+        capacity: capacity.max(1),
+        policy,
+        closed: AtomicBool::new(false),
+        sent: Notify::new(),
+        room: Notify::new(),
+    });
+
+    (
+        EventSender {
+            shared: Arc::clone(&shared),
+        },
+        EventReceiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared {
+    events: Mutex<VecDeque<ServerEvent>>,
+    capacity: usize,
+    policy: BackpressurePolicy,
+    /// Whether the game has stopped taking events, once its receiver has been dropped.
+    closed: AtomicBool,
+    sent: Notify,
+    room: Notify,
+}
+
+/// Sends events to a game, for the lobby and the server operator.
+#[derive(Debug, Clone)]
+pub(crate) struct EventSender {
+    shared: Arc<Shared>,
+}
+
+impl EventSender {
+    pub(crate) async fn send(&self, event: ServerEvent) -> Result<(), SendError> {
+        loop {
+            // Registered before the queue is checked, so that room made in between is not missed
+            let mut room = pin!(self.shared.room.notified());
+            room.as_mut().enable();
+
+            {
+                let mut events = self.shared.events.lock().unwrap();
+                if self.is_closed() {
+                    return Err(SendError::Closed);
+                }
+                if events.len() < self.shared.capacity {
+                    events.push_back(event);
+                    self.shared.sent.notify_one();
+                    return Ok(());
+                }
+                match self.shared.policy {
+                    BackpressurePolicy::Buffer => {}
+                    BackpressurePolicy::DropOldest => {
+                        let oldest = events.iter().position(is_display_only);
+                        if let (true, Some(oldest)) = (is_display_only(&event), oldest) {
+                            events.remove(oldest);
+                            events.push_back(event);
+                            return Ok(());
+                        }
+                    }
+                    BackpressurePolicy::Disconnect => return Err(SendError::Full),
+                }
+            }
+
+            room.await;
+        }
+    }
+
+    /// Whether the game has stopped taking events, having finished.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.closed.load(Ordering::Acquire)
+    }
+}
+
+/// Receives the events sent to a game, in the order they were sent.
+#[derive(Debug)]
+pub(crate) struct EventReceiver {
+    shared: Arc<Shared>,
+}
+
+impl EventReceiver {
+    /// Waits for the next event. Cancelling the wait loses no event, so that it can be raced
+    /// against the game's other sources of events.
+    pub(crate) async fn recv(&mut self) -> ServerEvent {
+        loop {
+            if let Some(event) = self.shared.events.lock().unwrap().pop_front() {
+                self.shared.room.notify_one();
+                return event;
+            }
+            self.shared.sent.notified().await;
+        }
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::Release);
+        // Connections still queued are closed now rather than once every sender has gone
+        self.shared.events.lock().unwrap().clear();
+        self.shared.room.notify_waiters();
+    }
+}
+
+fn is_display_only(event: &ServerEvent) -> bool {
+    matches!(event, ServerEvent::Notice { .. })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::time;
+
+    use super::*;
+
+    fn notice(message: &str) -> ServerEvent {
+        ServerEvent::Notice {
+            message: String::from(message),
+        }
+    }
+
+    #[tokio::test]
+    async fn buffered_sender_waits_for_room() {
+        let (sender, mut receiver) = event_queue(1, BackpressurePolicy::Buffer);
+        sender.send(notice("first")).await.unwrap();
+
+        let waiting = tokio::spawn(async move { sender.send(notice("second")).await });
+        time::sleep(Duration::from_millis(10)).await;
+        assert!(!waiting.is_finished());
+
+        assert!(
+            matches!(receiver.recv().await, ServerEvent::Notice { message } if message == "first")
+        );
+        assert_eq!(waiting.await.unwrap(), Ok(()));
+        assert!(
+            matches!(receiver.recv().await, ServerEvent::Notice { message } if message == "second")
+        );
+    }
+
+    #[tokio::test]
+    async fn notice_pushes_out_oldest_notice_when_full() {
+        let (sender, mut receiver) = event_queue(2, BackpressurePolicy::DropOldest);
+        sender.send(notice("first")).await.unwrap();
+        sender.send(ServerEvent::BeginGame).await.unwrap();
+        sender.send(notice("second")).await.unwrap();
+
+        assert!(matches!(receiver.recv().await, ServerEvent::BeginGame));
+        assert!(
+            matches!(receiver.recv().await, ServerEvent::Notice { message } if message == "second")
+        );
+    }
+
+    #[tokio::test]
+    async fn event_refused_when_full_under_disconnect_policy() {
+        let (sender, _receiver) = event_queue(1, BackpressurePolicy::Disconnect);
+        sender.send(notice("first")).await.unwrap();
+
+        assert_eq!(sender.send(notice("second")).await, Err(SendError::Full));
+    }
+
+    #[tokio::test]
+    async fn waiting_sender_told_once_game_stops_taking_events() {
+        let (sender, receiver) = event_queue(1, BackpressurePolicy::Buffer);
+        sender.send(notice("first")).await.unwrap();
+
+        let waiting = tokio::spawn({
+            let sender = sender.clone();
+            async move { sender.send(notice("second")).await }
+        });
+        time::sleep(Duration::from_millis(10)).await;
+        drop(receiver);
+
+        assert_eq!(waiting.await.unwrap(), Err(SendError::Closed));
+        assert!(sender.is_closed());
+    }
+}
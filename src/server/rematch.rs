@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+use crate::server::PLAYER_ONE_ID;
+
+/// Sent by a client in answer to the offer of a rematch made with `OutgoingEvent::GameSummary`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub enum RematchResponse {
+    /// Agrees to play again. Player one may name another of the games offered to switch to it,
+    /// which is ignored from anyone else.
+    Accepted {
+        player_id: u8,
+        game: Option<String>,
+    },
+    Declined {
+        player_id: u8,
+    },
+}
+
+impl RematchResponse {
+    pub fn player_id(&self) -> u8 {
+        match self {
+            RematchResponse::Accepted { player_id, .. }
+            | RematchResponse::Declined { player_id } => *player_id,
+        }
+    }
+}
+
+/// The players yet to agree to a rematch, along with the game player one chose to play next.
+#[derive(Debug)]
+pub(super) struct Rematch {
+    waiting_on: Vec<u8>,
+    game: Option<String>,
+}
+
+impl Rematch {
+    pub(super) fn offer(player_ids: Vec<u8>) -> Rematch {
+        Rematch {
+            waiting_on: player_ids,
+            game: None,
+        }
+    }
+
+    /// Records the player agreeing to play again, along with the game they chose if they are
+    /// player one.
+    ///
+    /// # Returns
+    ///
+    /// Whether every player has now agreed.
+    pub(super) fn accept(&mut self, player_id: u8, game: Option<String>) -> bool {
+        self.waiting_on.retain(|&id| id != player_id);
+        if player_id == PLAYER_ONE_ID {
+            self.game = game;
+        }
+
+        self.waiting_on.is_empty()
+    }
+
+    /// The ID of the game player one chose to play next, `None` to play the same game again.
+    pub(super) fn chosen_game(&self) -> Option<&str> {
+        self.game.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::PLAYER_TWO_ID;
+
+    #[test]
+    fn rematch_agreed_once_every_player_accepts() {
+        let mut rematch = Rematch::offer(vec![PLAYER_ONE_ID, PLAYER_TWO_ID]);
+
+        assert!(!rematch.accept(PLAYER_TWO_ID, None));
+        assert!(!rematch.accept(PLAYER_TWO_ID, None));
+        assert!(rematch.accept(PLAYER_ONE_ID, None));
+        assert_eq!(rematch.chosen_game(), None);
+    }
+
+    #[test]
+    fn only_player_one_chooses_next_game() {
+        let mut rematch = Rematch::offer(vec![PLAYER_ONE_ID, PLAYER_TWO_ID]);
+
+        rematch.accept(PLAYER_ONE_ID, Some(String::from("checkers")));
+        rematch.accept(PLAYER_TWO_ID, Some(String::from("othello")));
+
+        assert_eq!(rematch.chosen_game(), Some("checkers"));
+    }
+}
@@ -0,0 +1,165 @@
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, Receiver};
+
+use crate::game::{self, AnyGameServer, GameDescriptor, GameServerEvent};
+use crate::lobby::JoinToken;
+use crate::server::PLAYER_ONE_ID;
+
+/// Incremented whenever a change is made to the layout of save files.
+pub const SAVE_VERSION: u16 = 1;
+
+/// A game suspended part way through, as written to a save file so that it can be resumed later.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedGame {
+    version: u16,
+    /// The ID of the game being played, as in `game::GAMES`.
+    pub game: String,
+    /// The token the players joined with, so that they can rejoin using the same one.
+    pub join_token: Option<JoinToken>,
+    /// The ID of each player, in the order they take their turns.
+    pub player_ids: Vec<u8>,
+    /// The state of the game, as saved by `GameServer::save_state`.
+    pub state: serde_json::Value,
+}
+
+/// Enough of a save file to tell whether the rest of it can be read.
+#[derive(Deserialize)]
+struct SaveHeader {
+    version: u16,
+}
+
+impl SavedGame {
+    pub(crate) fn new(
+        game_id: &str,
+        join_token: Option<JoinToken>,
+        player_ids: Vec<u8>,
+        state: serde_json::Value,
+    ) -> SavedGame {
+        SavedGame {
+            version: SAVE_VERSION,
+            game: String::from(game_id),
+            join_token,
+            player_ids,
+            state,
+        }
+    }
+
+    pub fn read(reader: impl io::Read) -> Result<SavedGame, SaveError> {
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        let header = SaveHeader::deserialize(&value)?;
+        if header.version != SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(header.version));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub(crate) fn write(&self, writer: impl io::Write) -> Result<(), SaveError> {
+        Ok(serde_json::to_writer(writer, self)?)
+    }
+
+    /// Rebuilds the server side of the game from its saved state, ready for its players to
+    /// reconnect to it.
+    pub fn restore(self) -> Result<RestoredGame, SaveError> {
+        let game = game::find_game(&self.game).ok_or(SaveError::UnknownGame(self.game))?;
+        let player_ids: Vec<u8> = (PLAYER_ONE_ID..PLAYER_ONE_ID + game.player_count()).collect();
+        if self.player_ids != player_ids {
+            return Err(SaveError::InvalidState);
+        }
+        let (game_sender, game_receiver) = mpsc::channel(10);
+        let game_server = game
+            .restore_server(game_sender, &self.state)
+            .ok_or(SaveError::InvalidState)?;
+
+        Ok(RestoredGame {
+            game,
+            game_server,
+            game_receiver,
+            player_ids,
+        })
+    }
+}
+
+/// A saved game whose server side has been rebuilt, waiting on its players.
+pub struct RestoredGame {
+    pub(crate) game: &'static dyn GameDescriptor,
+    pub(crate) game_server: Box<dyn AnyGameServer + Send + Sync>,
+    pub(crate) game_receiver: Receiver<GameServerEvent>,
+    pub(crate) player_ids: Vec<u8>,
+}
+
+impl RestoredGame {
+    /// The game being played, for setting up the clients of its players.
+    pub fn game(&self) -> &'static dyn GameDescriptor {
+        self.game
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SaveError {
+    #[error("Failed to read or write the save file")]
+    Io(#[from] io::Error),
+    #[error("The save file is not in the expected format")]
+    Format(#[from] serde_json::Error),
+    #[error("Save files of version {0} are not supported, only version {SAVE_VERSION}")]
+    UnsupportedVersion(u16),
+    #[error("The game \"{0}\" is not known")]
+    UnknownGame(String),
+    #[error("The saved game cannot be carried on from")]
+    InvalidState,
+    #[error("The game is not being saved")]
+    Disabled,
+    #[error("The game \"{0}\" cannot be saved")]
+    Unsupported(&'static str),
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::tic_tac_toe;
+
+    #[test]
+    fn saved_game_can_be_read_back() {
+        let saved_game = SavedGame::new(
+            tic_tac_toe::GAME_ID,
+            Some(JoinToken::generate()),
+            vec![1, 2],
+            json!({"moves": []}),
+        );
+        let mut file = Vec::new();
+        saved_game.write(&mut file).unwrap();
+
+        assert_eq!(SavedGame::read(&file[..]).unwrap(), saved_game);
+    }
+
+    #[test]
+    fn save_of_unsupported_version_rejected() {
+        let file = br#"{"version":99,"game":"tic-tac-toe"}"#;
+
+        assert!(matches!(
+            SavedGame::read(&file[..]),
+            Err(SaveError::UnsupportedVersion(99))
+        ));
+    }
+
+    #[test]
+    fn save_of_unknown_game_cannot_be_restored() {
+        let saved_game = SavedGame::new("chess", None, vec![1, 2], json!(null));
+
+        assert!(matches!(
+            saved_game.restore(),
+            Err(SaveError::UnknownGame(game)) if game == "chess"
+        ));
+    }
+
+    #[test]
+    fn save_with_invalid_state_cannot_be_restored() {
+        let saved_game = SavedGame::new(tic_tac_toe::GAME_ID, None, vec![1, 2], json!("board"));
+
+        assert!(matches!(saved_game.restore(), Err(SaveError::InvalidState)));
+    }
+}
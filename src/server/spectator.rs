@@ -0,0 +1,48 @@
+use std::future::{self, Future};
+use std::task::Poll;
+
+use crate::connection::{Connection, ReadError};
+
+#[derive(Debug, Copy, Clone)]
+pub struct SpectatorConfig {
+    pub max_spectators: usize,
+    pub notify_players: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct Spectator {
+    pub(crate) id: u32,
+    pub(crate) connection: Connection,
+}
+
+/// Waits for any of the provided spectators to leave the game.
+///
+/// Spectators are not expected to send anything, so any message received from one is treated
+/// the same as them disconnecting. If there are no spectators this future never completes.
+///
+/// # Returns
+///
+/// The ID of the spectator that left.
+pub(crate) async fn spectator_departed(spectators: &mut [Spectator]) -> u32 {
+    let mut reads: Vec<_> = spectators
+        .iter_mut()
+        .map(|spectator| {
+            let id = spectator.id;
+            Box::pin(async move {
+                let _: Result<Vec<u8>, ReadError> = spectator.connection.read_event().await;
+                id
+            })
+        })
+        .collect();
+
+    future::poll_fn(|cx| {
+        reads
+            .iter_mut()
+            .find_map(|read| match read.as_mut().poll(cx) {
+                Poll::Ready(id) => Some(Poll::Ready(id)),
+                Poll::Pending => None,
+            })
+            .unwrap_or(Poll::Pending)
+    })
+    .await
+}
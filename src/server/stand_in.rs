@@ -0,0 +1,34 @@
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use tokio::runtime;
+
+use crate::client::Client;
+use crate::connection::Connection;
+use crate::game::GameDescriptor;
+
+/// How long the bot taking over a seat waits before making each move, so that the players left
+/// can follow what it does.
+const STAND_IN_MOVE_DELAY: Duration = Duration::from_secs(1);
+
+/// Has a bot play the seat of the given player over the connection, until the game is over.
+///
+/// Game clients can only be run on the thread that created them, so the bot is given a thread of
+/// its own rather than running alongside the server.
+pub(crate) fn spawn(connection: Connection, player_id: u8, game: &'static dyn GameDescriptor) {
+    thread::spawn(move || {
+        let runtime = match runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(error) => {
+                tracing::warn!(player_id, ?error, "Failed to start the bot");
+                return;
+            }
+        };
+        runtime.block_on(async {
+            let mut client =
+                Client::new_stand_in(connection, player_id, STAND_IN_MOVE_DELAY, io::sink(), game);
+            client.play_game().await;
+        });
+    });
+}
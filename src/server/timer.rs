@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::{self, Instant};
+
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub enum TimeoutAction {
+    Forfeit,
+    SkipTurn,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct TurnTimer {
+    pub duration: Duration,
+    pub action: TimeoutAction,
+    pub handicap: Option<Handicap>,
+}
+
+impl TurnTimer {
+    /// The time the player has to make each move, accounting for any handicap they have been given.
+    pub fn duration_for(&self, player_id: u8) -> Duration {
+        match self.handicap {
+            Some(handicap) if handicap.player_id == player_id => handicap.duration,
+            _ => self.duration,
+        }
+    }
+}
+
+/// Gives one player a different time to make each move than their opponent, evening out a game
+/// between players of different abilities.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Handicap {
+    pub player_id: u8,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Countdown {
+    player_id: u8,
+    deadline: Instant,
+}
+
+impl Countdown {
+    pub(crate) fn start(player_id: u8, duration: Duration) -> Countdown {
+        Countdown {
+            player_id,
+            deadline: Instant::now() + duration,
+        }
+    }
+}
+
+/// Waits until the deadline of the provided `Countdown` has passed.
+///
+/// If no countdown is running this future never completes, allowing it to be used unconditionally
+/// as an arm of a `tokio::select!`.
+///
+/// # Returns
+///
+/// The ID of the player the countdown was started for.
+pub(crate) async fn countdown_elapsed(countdown: Option<Countdown>) -> u8 {
+    match countdown {
+        Some(Countdown {
+            player_id,
+            deadline,
+        }) => {
+            time::sleep_until(deadline).await;
+            player_id
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// A timer started by the game itself, rather than by the server on each turn, such as one limiting
+/// the time every player has to answer at once.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct GameTimer {
+    id: u32,
+    deadline: Instant,
+}
+
+impl GameTimer {
+    pub(crate) fn start(id: u32, duration: Duration) -> GameTimer {
+        GameTimer {
+            id,
+            deadline: Instant::now() + duration,
+        }
+    }
+}
+
+/// Waits until the deadline of the provided `GameTimer` has passed, never completing if no timer
+/// is running, as with `countdown_elapsed`.
+///
+/// # Returns
+///
+/// The ID the game gave the timer.
+pub(crate) async fn game_timer_elapsed(timer: Option<GameTimer>) -> u32 {
+    match timer {
+        Some(GameTimer { id, deadline }) => {
+            time::sleep_until(deadline).await;
+            id
+        }
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_for_gives_handicapped_player_their_own_duration() {
+        let turn_timer = TurnTimer {
+            duration: Duration::from_secs(60),
+            action: TimeoutAction::Forfeit,
+            handicap: Some(Handicap {
+                player_id: 2,
+                duration: Duration::from_secs(180),
+            }),
+        };
+
+        assert_eq!(turn_timer.duration_for(1), Duration::from_secs(60));
+        assert_eq!(turn_timer.duration_for(2), Duration::from_secs(180));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn countdown_elapsed_completes_after_deadline_with_player_id() {
+        let countdown = Countdown::start(2, Duration::from_secs(30));
+
+        let player_id = countdown_elapsed(Some(countdown)).await;
+        assert_eq!(player_id, 2);
+        assert!(Instant::now() >= countdown.deadline);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn game_timer_elapsed_completes_after_deadline_with_id() {
+        let timer = GameTimer::start(7, Duration::from_secs(20));
+
+        let id = game_timer_elapsed(Some(timer)).await;
+        assert_eq!(id, 7);
+        assert!(Instant::now() >= timer.deadline);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn countdown_elapsed_never_completes_without_countdown() {
+        let result = time::timeout(Duration::from_secs(3600), countdown_elapsed(None)).await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub use export::{
+    ExportDestination, ExportError, ExportFormat, Exporter, MatchResult, ResultExporter,
+};
+pub use rating::{update_ratings, PlayerRating, RatingChange, INITIAL_RATING};
+pub use sqlite::SqliteStats;
+
+use crate::server::ThinkTime;
+
+mod export;
+mod rating;
+mod sqlite;
+
+/// A game that was played to completion.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    /// The ID of the `GameDescriptor` that was played.
+    pub game: String,
+    /// The ID of the winning player, `None` if the game was a draw.
+    pub winner: Option<u8>,
+    pub duration: Duration,
+    /// How long each player spent thinking over their turns, keyed by their ID.
+    pub think_times: BTreeMap<u8, ThinkTime>,
+}
+
+/// Somewhere that completed games can be recorded and later read back from.
+pub trait StatsStore {
+    fn record_game(&mut self, record: &GameRecord) -> Result<(), StatsError>;
+    fn get_games(&self) -> Result<Vec<GameRecord>, StatsError>;
+    /// The rating of the named player at the game, `None` if they have not been rated at it.
+    fn get_rating(&self, game: &str, name: &str) -> Result<Option<PlayerRating>, StatsError>;
+    /// Stores the rating, replacing any the player already had at the game.
+    fn set_rating(&mut self, rating: &PlayerRating) -> Result<(), StatsError>;
+    /// Every rating, grouped by game with the highest rated player first.
+    fn get_ratings(&self) -> Result<Vec<PlayerRating>, StatsError>;
+}
+
+/// Keeps records in memory for as long as the store exists, for when nothing needs to persist.
+#[derive(Debug, Default)]
+pub struct MemoryStats {
+    games: Vec<GameRecord>,
+    ratings: Vec<PlayerRating>,
+}
+
+impl StatsStore for MemoryStats {
+    fn record_game(&mut self, record: &GameRecord) -> Result<(), StatsError> {
+        self.games.push(record.clone());
+        Ok(())
+    }
+
+    fn get_games(&self) -> Result<Vec<GameRecord>, StatsError> {
+        Ok(self.games.clone())
+    }
+
+    fn get_rating(&self, game: &str, name: &str) -> Result<Option<PlayerRating>, StatsError> {
+        Ok(self
+            .ratings
+            .iter()
+            .find(|rating| rating.game == game && rating.name == name)
+            .cloned())
+    }
+
+    fn set_rating(&mut self, rating: &PlayerRating) -> Result<(), StatsError> {
+        self.ratings
+            .retain(|other| other.game != rating.game || other.name != rating.name);
+        self.ratings.push(rating.clone());
+        Ok(())
+    }
+
+    fn get_ratings(&self) -> Result<Vec<PlayerRating>, StatsError> {
+        let mut ratings = self.ratings.clone();
+        ratings.sort_by(|a, b| (&a.game, b.rating, &a.name).cmp(&(&b.game, a.rating, &b.name)));
+        Ok(ratings)
+    }
+}
+
+/// How a player has fared across a number of games.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct PlayerRecord {
+    pub won: u32,
+    pub lost: u32,
+    pub drawn: u32,
+    pub time_played: Duration,
+    pub think_time: ThinkTime,
+}
+
+impl PlayerRecord {
+    pub fn played(&self) -> u32 {
+        self.won + self.lost + self.drawn
+    }
+
+    fn add_game(&mut self, record: &GameRecord, player_id: u8) {
+        match record.winner {
+            Some(winner) if winner == player_id => self.won += 1,
+            Some(_) => self.lost += 1,
+            None => self.drawn += 1,
+        }
+        self.time_played += record.duration;
+        if let Some(think_time) = record.think_times.get(&player_id) {
+            self.think_time.add(think_time);
+        }
+    }
+}
+
+/// Totals up the record of the player in each game they have played, keyed by the game's ID.
+pub fn get_records_by_game(games: &[GameRecord], player_id: u8) -> BTreeMap<String, PlayerRecord> {
+    let mut records = BTreeMap::<String, PlayerRecord>::new();
+    for game in games {
+        records
+            .entry(game.game.clone())
+            .or_default()
+            .add_game(game, player_id);
+    }
+
+    records
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum StatsError {
+    #[error("Failed to access the statistics database")]
+    Database(#[from] rusqlite::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_game_record(game: &str, winner: Option<u8>, seconds: u64) -> GameRecord {
+        GameRecord {
+            game: String::from(game),
+            winner,
+            duration: Duration::from_secs(seconds),
+            think_times: BTreeMap::from([(
+                1,
+                ThinkTime {
+                    turns: 2,
+                    total: Duration::from_secs(seconds / 2),
+                    longest: Duration::from_secs(seconds / 4),
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn memory_stats_returns_recorded_games() {
+        let mut stats = MemoryStats::default();
+        let record = get_game_record("tic-tac-toe", Some(1), 30);
+
+        stats.record_game(&record).unwrap();
+
+        assert_eq!(stats.get_games().unwrap(), vec![record]);
+    }
+
+    #[test]
+    fn memory_stats_lists_highest_ratings_first_for_each_game() {
+        let mut stats = MemoryStats::default();
+        let rating = |name: &str, game: &str, rating| PlayerRating {
+            name: String::from(name),
+            game: String::from(game),
+            rating,
+            games: 1,
+        };
+
+        stats
+            .set_rating(&rating("Ada", "tic-tac-toe", 1490))
+            .unwrap();
+        stats
+            .set_rating(&rating("Alan", "tic-tac-toe", 1510))
+            .unwrap();
+        stats.set_rating(&rating("Ada", "checkers", 1500)).unwrap();
+        stats
+            .set_rating(&rating("Ada", "tic-tac-toe", 1520))
+            .unwrap();
+
+        assert_eq!(
+            stats.get_ratings().unwrap(),
+            vec![
+                rating("Ada", "checkers", 1500),
+                rating("Ada", "tic-tac-toe", 1520),
+                rating("Alan", "tic-tac-toe", 1510),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_are_totalled_from_perspective_of_player() {
+        let games = [
+            get_game_record("tic-tac-toe", Some(1), 30),
+            get_game_record("tic-tac-toe", Some(2), 20),
+            get_game_record("tic-tac-toe", None, 40),
+            get_game_record("tic-tac-toe", Some(1), 10),
+        ];
+
+        let records = get_records_by_game(&games, 1);
+
+        assert_eq!(
+            records["tic-tac-toe"],
+            PlayerRecord {
+                won: 2,
+                lost: 1,
+                drawn: 1,
+                time_played: Duration::from_secs(100),
+                think_time: ThinkTime {
+                    turns: 8,
+                    total: Duration::from_secs(50),
+                    longest: Duration::from_secs(10),
+                },
+            }
+        );
+        assert_eq!(records["tic-tac-toe"].played(), 4);
+    }
+
+    #[test]
+    fn records_are_kept_separate_for_each_game() {
+        let games = [
+            get_game_record("tic-tac-toe", Some(1), 30),
+            get_game_record("connect-four", Some(2), 20),
+        ];
+
+        let records = get_records_by_game(&games, 1);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records["tic-tac-toe"].won, 1);
+        assert_eq!(records["connect-four"].lost, 1);
+    }
+}
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::GameRecord;
+
+/// How long an HTTP endpoint is given to accept a result before the export is abandoned.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The result of a completed game, laid out for league and tournament software to import.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    /// The ID of the `GameDescriptor` that was played.
+    pub game: String,
+    /// Seconds since the Unix epoch at which the game began.
+    pub started_at: u64,
+    /// Seconds since the Unix epoch at which the game ended.
+    pub finished_at: u64,
+    pub duration_ms: u64,
+    /// The ID of the winning player, `None` if the game was a draw.
+    pub winner: Option<u8>,
+    /// How the rating of player one changed, `None` while players are not rated.
+    pub player_one_rating_change: Option<i32>,
+    /// How the rating of player two changed, `None` while players are not rated.
+    pub player_two_rating_change: Option<i32>,
+}
+
+impl MatchResult {
+    /// Lays out the record of a game that ended at the given time.
+    pub fn new(record: &GameRecord, finished_at: SystemTime) -> MatchResult {
+        let started_at = finished_at
+            .checked_sub(record.duration)
+            .unwrap_or(UNIX_EPOCH);
+
+        MatchResult {
+            game: record.game.clone(),
+            started_at: unix_seconds(started_at),
+            finished_at: unix_seconds(finished_at),
+            duration_ms: record.duration.as_millis() as u64,
+            winner: record.winner,
+            player_one_rating_change: None,
+            player_two_rating_change: None,
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Somewhere that the results of completed games are sent, such as external league software.
+pub trait ResultExporter {
+    fn export(&mut self, result: &MatchResult) -> Result<(), ExportError>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExportFormat {
+    /// A single JSON object.
+    Json,
+    /// A header row naming each field, followed by a row holding the result.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn encode(&self, result: &MatchResult) -> Result<Vec<u8>, ExportError> {
+        match self {
+            ExportFormat::Json => Ok(serde_json::to_vec(result)?),
+            ExportFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                writer.serialize(result)?;
+                writer
+                    .into_inner()
+                    .map_err(|error| ExportError::Io(error.into_error()))
+            }
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportDestination {
+    /// Each result is written to a file of its own within the directory.
+    Directory(PathBuf),
+    /// Each result is POSTed to the URL.
+    Http(String),
+}
+
+/// Exports results in a format of the `ExportFormat` to an `ExportDestination`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exporter {
+    pub destination: ExportDestination,
+    pub format: ExportFormat,
+}
+
+impl ResultExporter for Exporter {
+    fn export(&mut self, result: &MatchResult) -> Result<(), ExportError> {
+        let body = self.format.encode(result)?;
+
+        match &self.destination {
+            ExportDestination::Directory(directory) => {
+                // Games can finish within the same second, so a random suffix keeps names unique
+                let file_name = format!(
+                    "{}-{}-{:08x}.{}",
+                    result.finished_at,
+                    result.game,
+                    rand::random::<u32>(),
+                    self.format.extension()
+                );
+                fs::create_dir_all(directory)?;
+                fs::write(directory.join(file_name), body)?;
+            }
+            ExportDestination::Http(url) => {
+                ureq::post(url)
+                    .timeout(HTTP_TIMEOUT)
+                    .set("Content-Type", self.format.content_type())
+                    .send_bytes(&body)
+                    .map_err(Box::new)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error("Failed to encode the result as JSON")]
+    Json(#[from] serde_json::Error),
+    #[error("Failed to encode the result as CSV")]
+    Csv(#[from] csv::Error),
+    #[error("Failed to write the result")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to send the result")]
+    Http(#[from] Box<ureq::Error>),
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{Ipv4Addr, TcpListener};
+    use std::thread;
+
+    use super::*;
+
+    fn get_match_result() -> MatchResult {
+        let record = GameRecord {
+            game: String::from("tic-tac-toe"),
+            winner: Some(2),
+            duration: Duration::from_millis(90_500),
+            think_times: BTreeMap::new(),
+        };
+
+        MatchResult::new(&record, UNIX_EPOCH + Duration::from_secs(1_000))
+    }
+
+    #[test]
+    fn match_result_is_timed_from_when_game_finished() {
+        let result = get_match_result();
+
+        assert_eq!(result.started_at, 909);
+        assert_eq!(result.finished_at, 1_000);
+        assert_eq!(result.duration_ms, 90_500);
+        assert_eq!(result.winner, Some(2));
+    }
+
+    #[test]
+    fn json_format_encodes_result_as_object() {
+        let encoded = ExportFormat::Json.encode(&get_match_result()).unwrap();
+
+        assert_eq!(
+            serde_json::from_slice::<MatchResult>(&encoded).unwrap(),
+            get_match_result()
+        );
+    }
+
+    #[test]
+    fn csv_format_encodes_result_as_header_and_row() {
+        let encoded = ExportFormat::Csv.encode(&get_match_result()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(encoded).unwrap(),
+            "game,started_at,finished_at,duration_ms,winner,player_one_rating_change,player_two_rating_change\n\
+             tic-tac-toe,909,1000,90500,2,,\n"
+        );
+    }
+
+    #[test]
+    fn directory_destination_writes_file_for_each_result() {
+        let directory = std::env::temp_dir().join(format!("game-results-{}", std::process::id()));
+        let mut exporter = Exporter {
+            destination: ExportDestination::Directory(directory.clone()),
+            format: ExportFormat::Json,
+        };
+
+        exporter.export(&get_match_result()).unwrap();
+        exporter.export(&get_match_result()).unwrap();
+
+        let files = fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| fs::read(entry.unwrap().path()).unwrap())
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(files.len(), 2);
+        for file in files {
+            assert_eq!(
+                serde_json::from_slice::<MatchResult>(&file).unwrap(),
+                get_match_result()
+            );
+        }
+    }
+
+    #[test]
+    fn http_destination_posts_result() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let url = format!("http://{}/results", listener.local_addr().unwrap());
+
+        // Simulate an endpoint accepting a single result
+        let endpoint = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(length) = header.to_lowercase().strip_prefix("content-length: ") {
+                    content_length = length.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            reader
+                .into_inner()
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+
+            (request_line, body)
+        });
+
+        let mut exporter = Exporter {
+            destination: ExportDestination::Http(url),
+            format: ExportFormat::Json,
+        };
+        exporter.export(&get_match_result()).unwrap();
+
+        let (request_line, body) = endpoint.join().unwrap();
+        assert_eq!(request_line, "POST /results HTTP/1.1\r\n");
+        assert_eq!(
+            serde_json::from_slice::<MatchResult>(&body).unwrap(),
+            get_match_result()
+        );
+    }
+
+    #[test]
+    fn http_destination_returns_error_when_result_is_refused() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let url = format!("http://{}/results", listener.local_addr().unwrap());
+
+        // Simulate an endpoint refusing every result
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream
+                .write_all(b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        });
+
+        let mut exporter = Exporter {
+            destination: ExportDestination::Http(url),
+            format: ExportFormat::Csv,
+        };
+        assert!(matches!(
+            exporter.export(&get_match_result()),
+            Err(ExportError::Http(_))
+        ));
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::{StatsError, StatsStore};
+
+/// The rating a player starts with before their first rated game.
+pub const INITIAL_RATING: i32 = 1500;
+/// The most a rating can change by after a single game.
+const K_FACTOR: f64 = 32.0;
+
+/// How a named player is rated at a game, along with how many rated games it is based on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerRating {
+    pub name: String,
+    /// The ID of the `GameDescriptor` the rating is for.
+    pub game: String,
+    pub rating: i32,
+    pub games: u32,
+}
+
+/// A player's rating following a game, sent to the players once it is over.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RatingChange {
+    pub name: String,
+    pub rating: i32,
+    /// How much the rating went up by, or down by when negative.
+    pub change: i32,
+}
+
+/// The chance of a player with the first rating beating one with the second, with a draw counting
+/// as half a win.
+fn expected_score(rating: i32, opponent_rating: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf(f64::from(opponent_rating - rating) / 400.0))
+}
+
+/// How much the rating of the first of two players goes up by after they played each other, the
+/// second player's going down by the same amount.
+///
+/// # Parameters
+///
+/// - `score`: How the first player fared, 1 for a win, 0 for a loss and 0.5 for a draw.
+fn rating_change(rating: i32, opponent_rating: i32, score: f64) -> i32 {
+    (K_FACTOR * (score - expected_score(rating, opponent_rating))).round() as i32
+}
+
+/// Updates the ratings of the two named players of a game, starting anyone who has not been rated
+/// at the game before from `INITIAL_RATING`.
+///
+/// # Parameters
+///
+/// - `players`: The ID and name of each player.
+/// - `winner`: The ID of the winning player, `None` if the game was a draw.
+///
+/// # Returns
+///
+/// The new rating of each player keyed by their ID.
+pub fn update_ratings(
+    stats_store: &mut dyn StatsStore,
+    game: &str,
+    players: [(u8, &str); 2],
+    winner: Option<u8>,
+) -> Result<BTreeMap<u8, RatingChange>, StatsError> {
+    let [(player_id, name), (opponent_id, opponent_name)] = players;
+    let [rating, opponent_rating] = [name, opponent_name].map(|name| {
+        stats_store.get_rating(game, name).map(|rating| {
+            rating.unwrap_or(PlayerRating {
+                name: String::from(name),
+                game: String::from(game),
+                rating: INITIAL_RATING,
+                games: 0,
+            })
+        })
+    });
+    let (rating, opponent_rating) = (rating?, opponent_rating?);
+
+    let score = match winner {
+        Some(winner) if winner == player_id => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+    let change = rating_change(rating.rating, opponent_rating.rating, score);
+
+    let mut changes = BTreeMap::new();
+    for (player_id, rating, change) in [
+        (player_id, rating, change),
+        (opponent_id, opponent_rating, -change),
+    ] {
+        let rating = PlayerRating {
+            rating: rating.rating + change,
+            games: rating.games + 1,
+            ..rating
+        };
+        stats_store.set_rating(&rating)?;
+        changes.insert(
+            player_id,
+            RatingChange {
+                name: rating.name,
+                rating: rating.rating,
+                change,
+            },
+        );
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::MemoryStats;
+
+    #[test]
+    fn evenly_matched_players_gain_and_lose_half_of_most_possible() {
+        assert_eq!(rating_change(1500, 1500, 1.0), 16);
+        assert_eq!(rating_change(1500, 1500, 0.0), -16);
+        assert_eq!(rating_change(1500, 1500, 0.5), 0);
+    }
+
+    #[test]
+    fn beating_stronger_player_gains_more_than_beating_weaker_one() {
+        assert!(rating_change(1400, 1600, 1.0) > rating_change(1600, 1400, 1.0));
+        assert_eq!(rating_change(1400, 1600, 1.0), 24);
+        assert_eq!(rating_change(1400, 1600, 0.5), 8);
+    }
+
+    #[test]
+    fn unrated_players_start_from_initial_rating() {
+        let mut stats = MemoryStats::default();
+
+        let changes = update_ratings(
+            &mut stats,
+            "tic-tac-toe",
+            [(1, "Ada"), (2, "Alan")],
+            Some(2),
+        )
+        .unwrap();
+
+        assert_eq!(
+            changes[&1],
+            RatingChange {
+                name: String::from("Ada"),
+                rating: INITIAL_RATING - 16,
+                change: -16,
+            }
+        );
+        assert_eq!(changes[&2].rating, INITIAL_RATING + 16);
+        assert_eq!(
+            stats.get_rating("tic-tac-toe", "Alan").unwrap(),
+            Some(PlayerRating {
+                name: String::from("Alan"),
+                game: String::from("tic-tac-toe"),
+                rating: INITIAL_RATING + 16,
+                games: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn ratings_carry_over_between_games_of_same_kind_only() {
+        let mut stats = MemoryStats::default();
+
+        update_ratings(
+            &mut stats,
+            "tic-tac-toe",
+            [(1, "Ada"), (2, "Alan")],
+            Some(1),
+        )
+        .unwrap();
+        let changes =
+            update_ratings(&mut stats, "tic-tac-toe", [(1, "Alan"), (2, "Ada")], None).unwrap();
+        update_ratings(&mut stats, "checkers", [(1, "Ada"), (2, "Alan")], None).unwrap();
+
+        assert_eq!(changes[&1].change, 1);
+        assert_eq!(changes[&2].rating, 1515);
+        let ada = stats.get_rating("tic-tac-toe", "Ada").unwrap().unwrap();
+        assert_eq!((ada.rating, ada.games), (1515, 2));
+        let ada = stats.get_rating("checkers", "Ada").unwrap().unwrap();
+        assert_eq!((ada.rating, ada.games), (INITIAL_RATING, 1));
+    }
+}
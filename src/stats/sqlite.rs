@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::Connection;
+
+use crate::server::ThinkTime;
+use crate::stats::{GameRecord, PlayerRating, StatsError, StatsStore};
+
+/// Keeps records in an SQLite database, so that they persist between games.
+#[derive(Debug)]
+pub struct SqliteStats {
+    connection: Connection,
+}
+
+impl SqliteStats {
+    /// Opens the database at the path, creating it if it does not already exist.
+    pub fn open(path: &Path) -> Result<SqliteStats, StatsError> {
+        SqliteStats::from_connection(Connection::open(path)?)
+    }
+
+    /// Opens a database that only exists for as long as the returned store.
+    pub fn open_in_memory() -> Result<SqliteStats, StatsError> {
+        SqliteStats::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(connection: Connection) -> Result<SqliteStats, StatsError> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS games (
+                id INTEGER PRIMARY KEY,
+                game TEXT NOT NULL,
+                winner INTEGER,
+                duration_ms INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS think_times (
+                game_id INTEGER NOT NULL REFERENCES games (id),
+                player_id INTEGER NOT NULL,
+                turns INTEGER NOT NULL,
+                total_ms INTEGER NOT NULL,
+                longest_ms INTEGER NOT NULL,
+                PRIMARY KEY (game_id, player_id)
+            );
+            CREATE TABLE IF NOT EXISTS ratings (
+                game TEXT NOT NULL,
+                name TEXT NOT NULL,
+                rating INTEGER NOT NULL,
+                games INTEGER NOT NULL,
+                PRIMARY KEY (game, name)
+            );",
+        )?;
+
+        Ok(SqliteStats { connection })
+    }
+
+    fn get_think_times(&self) -> Result<BTreeMap<i64, BTreeMap<u8, ThinkTime>>, StatsError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT game_id, player_id, turns, total_ms, longest_ms FROM think_times")?;
+        let rows = statement.query_map((), |row| {
+            let think_time = ThinkTime {
+                turns: row.get(2)?,
+                total: Duration::from_millis(row.get(3)?),
+                longest: Duration::from_millis(row.get(4)?),
+            };
+            Ok((row.get::<_, i64>(0)?, row.get::<_, u8>(1)?, think_time))
+        })?;
+
+        let mut think_times = BTreeMap::<i64, BTreeMap<u8, ThinkTime>>::new();
+        for row in rows {
+            let (game_id, player_id, think_time) = row?;
+            think_times
+                .entry(game_id)
+                .or_default()
+                .insert(player_id, think_time);
+        }
+
+        Ok(think_times)
+    }
+}
+
+impl StatsStore for SqliteStats {
+    fn record_game(&mut self, record: &GameRecord) -> Result<(), StatsError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO games (game, winner, duration_ms) VALUES (?1, ?2, ?3)",
+            (
+                &record.game,
+                record.winner,
+                record.duration.as_millis() as i64,
+            ),
+        )?;
+
+        let game_id = transaction.last_insert_rowid();
+        for (player_id, think_time) in &record.think_times {
+            transaction.execute(
+                "INSERT INTO think_times (game_id, player_id, turns, total_ms, longest_ms)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                (
+                    game_id,
+                    player_id,
+                    think_time.turns,
+                    think_time.total.as_millis() as i64,
+                    think_time.longest.as_millis() as i64,
+                ),
+            )?;
+        }
+
+        Ok(transaction.commit()?)
+    }
+
+    fn get_games(&self) -> Result<Vec<GameRecord>, StatsError> {
+        let mut think_times = self.get_think_times()?;
+
+        let mut statement = self
+            .connection
+            .prepare("SELECT id, game, winner, duration_ms FROM games ORDER BY id")?;
+        let games = statement
+            .query_map((), |row| {
+                Ok(GameRecord {
+                    game: row.get(1)?,
+                    winner: row.get(2)?,
+                    duration: Duration::from_millis(row.get(3)?),
+                    think_times: think_times.remove(&row.get(0)?).unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        Ok(games)
+    }
+
+    fn get_rating(&self, game: &str, name: &str) -> Result<Option<PlayerRating>, StatsError> {
+        let mut statement = self.connection.prepare(
+            "SELECT name, game, rating, games FROM ratings WHERE game = ?1 AND name = ?2",
+        )?;
+        let mut ratings = statement.query_map((game, name), read_rating)?;
+
+        Ok(ratings.next().transpose()?)
+    }
+
+    fn set_rating(&mut self, rating: &PlayerRating) -> Result<(), StatsError> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO ratings (game, name, rating, games) VALUES (?1, ?2, ?3, ?4)",
+            (&rating.game, &rating.name, rating.rating, rating.games),
+        )?;
+
+        Ok(())
+    }
+
+    fn get_ratings(&self) -> Result<Vec<PlayerRating>, StatsError> {
+        let mut statement = self.connection.prepare(
+            "SELECT name, game, rating, games FROM ratings ORDER BY game, rating DESC, name",
+        )?;
+        let ratings = statement
+            .query_map((), read_rating)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(ratings)
+    }
+}
+
+fn read_rating(row: &rusqlite::Row) -> rusqlite::Result<PlayerRating> {
+    Ok(PlayerRating {
+        name: row.get(0)?,
+        game: row.get(1)?,
+        rating: row.get(2)?,
+        games: row.get(3)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_games_are_read_back_in_order() {
+        let mut stats = SqliteStats::open_in_memory().unwrap();
+        let records = [
+            GameRecord {
+                game: String::from("tic-tac-toe"),
+                winner: Some(2),
+                duration: Duration::from_millis(61500),
+                think_times: BTreeMap::from([
+                    (
+                        1,
+                        ThinkTime {
+                            turns: 3,
+                            total: Duration::from_millis(20250),
+                            longest: Duration::from_secs(12),
+                        },
+                    ),
+                    (
+                        2,
+                        ThinkTime {
+                            turns: 3,
+                            total: Duration::from_secs(41),
+                            longest: Duration::from_secs(30),
+                        },
+                    ),
+                ]),
+            },
+            GameRecord {
+                game: String::from("tic-tac-toe"),
+                winner: None,
+                duration: Duration::from_secs(45),
+                think_times: BTreeMap::new(),
+            },
+        ];
+
+        for record in &records {
+            stats.record_game(record).unwrap();
+        }
+
+        assert_eq!(stats.get_games().unwrap(), records);
+    }
+
+    #[test]
+    fn ratings_are_replaced_and_read_back_highest_first() {
+        let mut stats = SqliteStats::open_in_memory().unwrap();
+        let rating = |name: &str, rating, games| PlayerRating {
+            name: String::from(name),
+            game: String::from("tic-tac-toe"),
+            rating,
+            games,
+        };
+
+        stats.set_rating(&rating("Ada", 1484, 1)).unwrap();
+        stats.set_rating(&rating("Alan", 1516, 1)).unwrap();
+        stats.set_rating(&rating("Ada", 1532, 2)).unwrap();
+
+        assert_eq!(
+            stats.get_rating("tic-tac-toe", "Ada").unwrap(),
+            Some(rating("Ada", 1532, 2))
+        );
+        assert_eq!(stats.get_rating("checkers", "Ada").unwrap(), None);
+        assert_eq!(
+            stats.get_ratings().unwrap(),
+            vec![rating("Ada", 1532, 2), rating("Alan", 1516, 1)]
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+use std::io;
+use std::net::SocketAddr;
+use std::rc::Rc;
+use std::time::Duration;
+
+use tokio::task::{JoinSet, LocalSet};
+use tokio::time::Instant;
+
+use crate::client::Client;
+use crate::game::GameDescriptor;
+use crate::lobby::{self, ConnectionOptions, JoinToken};
+use crate::server::{PLAYER_ONE_ID, PLAYER_TWO_ID};
+
+/// How a server held up while bots played games against each other on it.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StressReport {
+    /// How long it took for every bot to finish.
+    pub elapsed: Duration,
+    /// How long each bot that joined a game waited for the lobby to accept it.
+    pub join_times: Vec<Duration>,
+    /// How long each bot that played its game to the end spent in the game after joining.
+    pub game_times: Vec<Duration>,
+    /// The number of bots that failed to join or stopped before their game was over.
+    pub errors: usize,
+}
+
+impl StressReport {
+    /// The number of games played to the end, each by two bots.
+    pub fn games_completed(&self) -> usize {
+        self.game_times.len() / 2
+    }
+
+    pub fn games_per_second(&self) -> f64 {
+        self.games_completed() as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Completed {} games in {:.1} seconds, {:.2} games per second.",
+            self.games_completed(),
+            self.elapsed.as_secs_f64(),
+            self.games_per_second()
+        )?;
+        writeln!(f, "Errors: {}.", self.errors)?;
+        write_percentiles(f, "Join time", &self.join_times)?;
+        write_percentiles(f, "Game time", &self.game_times)
+    }
+}
+
+fn write_percentiles(f: &mut fmt::Formatter<'_>, name: &str, times: &[Duration]) -> fmt::Result {
+    let [Some(p50), Some(p90), Some(p99), Some(max)] =
+        [50.0, 90.0, 99.0, 100.0].map(|rank| percentile(times, rank))
+    else {
+        return writeln!(f, "{}: none recorded.", name);
+    };
+
+    writeln!(
+        f,
+        "{}: p50 {}ms, p90 {}ms, p99 {}ms, max {}ms.",
+        name,
+        p50.as_millis(),
+        p90.as_millis(),
+        p99.as_millis(),
+        max.as_millis()
+    )
+}
+
+/// Finds the time that the given percentage of the times are no longer than, using the nearest
+/// rank so that the time is always one of those given.
+///
+/// # Returns
+///
+/// - `Some(Duration)` containing the time at that percentile.
+/// - `None` if there are no times.
+pub fn percentile(times: &[Duration], rank: f64) -> Option<Duration> {
+    let mut sorted = times.to_vec();
+    sorted.sort();
+    let position = (rank / 100.0 * sorted.len() as f64).ceil() as usize;
+
+    sorted.get(position.saturating_sub(1)).copied()
+}
+
+/// Has bots play the given number of games against each other on the server at once, with two
+/// bots joining for each game.
+///
+/// Every bot connects from the same address, so the server must allow at least twice as many
+/// connections from it as there are games.
+pub async fn run(
+    address: SocketAddr,
+    join_token: JoinToken,
+    options: ConnectionOptions,
+    game: Rc<dyn GameDescriptor>,
+    games: usize,
+) -> StressReport {
+    // Game clients can only be run on the thread that created them
+    let local = LocalSet::new();
+    local
+        .run_until(async move {
+            let started = Instant::now();
+            let mut bots = JoinSet::new();
+            for _ in 0..games * 2 {
+                let options = options.clone();
+                let game = Rc::clone(&game);
+                bots.spawn_local(
+                    async move { play_bot(address, join_token, options, &*game).await },
+                );
+            }
+
+            let mut report = StressReport::default();
+            while let Some(result) = bots.join_next().await {
+                match result {
+                    Ok(BotRun::Completed {
+                        join_time,
+                        game_time,
+                    }) => {
+                        report.join_times.push(join_time);
+                        report.game_times.push(game_time);
+                    }
+                    Ok(BotRun::Stopped { join_time }) => {
+                        report.join_times.push(join_time);
+                        report.errors += 1;
+                    }
+                    Ok(BotRun::NotJoined) | Err(_) => report.errors += 1,
+                }
+            }
+            report.elapsed = started.elapsed();
+
+            report
+        })
+        .await
+}
+
+/// How far a single bot got in playing its game.
+enum BotRun {
+    Completed {
+        join_time: Duration,
+        game_time: Duration,
+    },
+    Stopped {
+        join_time: Duration,
+    },
+    NotJoined,
+}
+
+async fn play_bot(
+    address: SocketAddr,
+    join_token: JoinToken,
+    options: ConnectionOptions,
+    game: &dyn GameDescriptor,
+) -> BotRun {
+    let joining = Instant::now();
+    let Ok((connection, session)) = lobby::connect_to_game(address, join_token, options).await
+    else {
+        return BotRun::NotJoined;
+    };
+    let join_time = joining.elapsed();
+
+    let player_id = if session.seats.contains(PLAYER_ONE_ID) {
+        PLAYER_ONE_ID
+    } else {
+        PLAYER_TWO_ID
+    };
+    let playing = Instant::now();
+    let mut client = Client::new_bot(
+        connection,
+        session,
+        player_id,
+        Duration::ZERO,
+        io::sink(),
+        game,
+    );
+    client.play_game().await;
+
+    if client.is_game_over() {
+        BotRun::Completed {
+            join_time,
+            game_time: playing.elapsed(),
+        }
+    } else {
+        BotRun::Stopped { join_time }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use tokio::net::TcpListener;
+
+    use crate::lobby::{Lobby, RateLimit};
+    use crate::tic_tac_toe::TicTacToe;
+
+    use super::*;
+
+    fn millis(times: &[u64]) -> Vec<Duration> {
+        times
+            .iter()
+            .map(|&time| Duration::from_millis(time))
+            .collect()
+    }
+
+    #[test]
+    fn percentile_uses_nearest_rank() {
+        let times = millis(&[40, 10, 30, 20, 50, 60, 70, 80, 90, 100]);
+
+        assert_eq!(percentile(&times, 50.0), Some(Duration::from_millis(50)));
+        assert_eq!(percentile(&times, 90.0), Some(Duration::from_millis(90)));
+        assert_eq!(percentile(&times, 99.0), Some(Duration::from_millis(100)));
+        assert_eq!(percentile(&times, 0.0), Some(Duration::from_millis(10)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn report_shows_throughput_errors_and_percentiles() {
+        let report = StressReport {
+            elapsed: Duration::from_secs(2),
+            join_times: millis(&[5, 5, 10]),
+            game_times: millis(&[100, 120]),
+            errors: 2,
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "Completed 1 games in 2.0 seconds, 0.50 games per second.\n\
+            Errors: 2.\n\
+            Join time: p50 5ms, p90 10ms, p99 10ms, max 10ms.\n\
+            Game time: p50 100ms, p90 120ms, p99 120ms, max 120ms.\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn run_plays_every_game_on_server() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let mut lobby = Lobby::new(listener);
+        let join_token = lobby.join_token();
+        lobby.set_rate_limit(RateLimit {
+            max_connections: 100,
+            period: Duration::from_secs(60),
+        });
+        lobby.set_game(TicTacToe::default());
+        tokio::spawn(lobby.serve(|_| {}));
+
+        let report = run(
+            address,
+            join_token,
+            ConnectionOptions::default(),
+            Rc::new(TicTacToe::default()),
+            3,
+        )
+        .await;
+
+        assert_eq!(report.games_completed(), 3);
+        assert_eq!(report.errors, 0);
+        assert_eq!(report.join_times.len(), 6);
+    }
+}
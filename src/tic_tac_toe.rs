@@ -1,18 +1,132 @@
 use std::io;
 use std::string::String;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::Sender;
-
-use crate::client::{ClientType, LocalClient, OnlineClient};
-use crate::game::{self, GameClient, GameClientEvent, GameServer, GameServerEvent};
-use crate::server::{get_alternative_player_id, DispatchMode, PLAYER_ONE_ID, PLAYER_TWO_ID};
-pub use crate::tic_tac_toe::board::BOARD_SIZE;
-use crate::tic_tac_toe::{board::Board, ClientEvent::MoveMade};
+use tokio::time::Instant;
+
+use crate::client::command::{self, Command, GameCommand};
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::rand::GameRng;
+use crate::game::{
+    self, AnyClientEvent, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError,
+    GameClientEvent, GameDescriptor, GameServer, GameServerEvent, RejectedMoves, ServerChannel,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, FirstPlayer, PlayerMarks, TimeoutAction,
+    PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+pub use crate::tic_tac_toe::board::{
+    Board, BoardConfig, Variant, DEFAULT_BOARD_SIZE, MAX_BOARD_SIZE, MIN_BOARD_SIZE,
+};
+use crate::tic_tac_toe::render::{
+    BoardRenderer, BoardView, DescriptionRenderer, GridRenderer, TerseRenderer,
+};
+use crate::tic_tac_toe::ClientEvent::{
+    HistoryRequested, MoveMade, SuspendRequested, UndoAnswered, UndoRequested,
+};
 
 mod board;
+mod bot;
+mod render;
+
+/// Identifies Tic Tac Toe among the games in `game::GAMES`.
+pub const GAME_ID: &str = "tic-tac-toe";
+
+/// The commands players can enter in place of a move, on top of those understood in every game.
+const COMMANDS: [GameCommand; 5] = [
+    GameCommand {
+        name: "undo",
+        description_key: "command-undo",
+    },
+    GameCommand {
+        name: "save",
+        description_key: "command-save",
+    },
+    GameCommand {
+        name: "history",
+        description_key: "command-history",
+    },
+    GameCommand {
+        name: "draw",
+        description_key: "command-draw",
+    },
+    GameCommand {
+        name: "resign",
+        description_key: "command-resign",
+    },
+];
+
+/// Tic Tac Toe played on the board described by the `BoardConfig`.
+///
+/// Only the server makes use of the `BoardConfig`, clients are sent the board being played on
+/// as the game starts.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct TicTacToe {
+    board_config: BoardConfig,
+}
+
+impl TicTacToe {
+    pub const fn new(board_config: BoardConfig) -> TicTacToe {
+        TicTacToe { board_config }
+    }
+}
+
+impl GameDescriptor for TicTacToe {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(TicTacToeServer::new(channel, self.board_config))
+    }
+
+    /// Plays the saved moves out on the saved board, which may differ from this game's own.
+    fn restore_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+        state: &serde_json::Value,
+    ) -> Option<Box<dyn game::AnyGameServer + Send + Sync>> {
+        let state = SavedState::deserialize(state).ok()?;
+        let server = TicTacToeServer::restore(channel, state)?;
+
+        Some(Box::new(server))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(TicTacToeClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(TicTacToeClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(TicTacToeClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(TicTacToeClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
 
 #[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum Outcome {
@@ -20,10 +134,21 @@ pub enum Outcome {
     WinnerFound { player_id: u8 },
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+/// A move in the game's history, in the order it was made.
+#[derive(PartialEq, Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct MoveRecord {
+    pub player_id: u8,
+    /// The cell chosen, numbered from 1 as players enter them.
+    pub cell: usize,
+    /// How long after the game began the move was made.
+    pub made_at: Duration,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ServerEvent {
     BoardUpdated {
-        board_cells: [Option<u8>; BOARD_SIZE],
+        board_config: BoardConfig,
+        board_cells: Vec<Option<u8>>,
     },
     PlayerTurn {
         player_id: u8,
@@ -34,93 +159,483 @@ pub enum ServerEvent {
     ErrorOccurred {
         error: Error,
     },
+    TurnTimedOut {
+        player_id: u8,
+        action: TimeoutAction,
+    },
+    /// Asks the opponent of the given player whether they may take back their last move.
+    UndoRequested {
+        player_id: u8,
+    },
+    UndoDeclined {
+        player_id: u8,
+    },
+    MoveTakenBack {
+        player_id: u8,
+    },
+    /// Announces the player chosen at random to take the first turn, as the game begins.
+    FirstPlayerChosen {
+        player_id: u8,
+    },
+    /// Asks the opponent of the given player whether they accept a draw.
+    DrawOffered {
+        player_id: u8,
+    },
+    DrawDeclined {
+        player_id: u8,
+    },
+    Resigned {
+        player_id: u8,
+    },
+    /// Every move made so far, sent to the player who asked for it.
+    History {
+        moves: Vec<MoveRecord>,
+    },
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
 pub enum Error {
-    #[error("The input should be a number between 1 and {}.", BOARD_SIZE)]
-    InvalidCellIndex,
+    #[error("The input should be a number between 1 and {cell_count}.")]
+    InvalidCellIndex { cell_count: usize },
     #[error("This cell is already occupied.")]
     CellOccupied,
     #[error("It's not your turn.")]
     UnexpectedPlayer,
+    #[error("You have no move that can be taken back.")]
+    NoMoveToTakeBack,
+    #[error("You cannot place that mark.")]
+    MarkNotAllowed,
+}
+
+/// A game in progress as written to a save file.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedState {
+    board_config: BoardConfig,
+    /// Every move made so far, as taken by `Board::apply_moves`.
+    moves: Vec<(u8, usize)>,
+    /// How long after the game began each move was made, missing from saves made before moves
+    /// were timed.
+    #[serde(default)]
+    move_times: Vec<Duration>,
+    /// The mark placed by each move, missing from saves made before there were variants, in which
+    /// every move placed the player's own.
+    #[serde(default)]
+    marks: Vec<u8>,
+    current_player: u8,
 }
 
 pub struct TicTacToeServer {
     current_player: u8,
     board: Board,
-    server_channel: Sender<GameServerEvent>,
+    /// How long after `started_at` each move on the board was made, in the same order.
+    move_times: Vec<Duration>,
+    started_at: Instant,
+    server_channel: ServerChannel<ServerEvent>,
+    rejected_moves: RejectedMoves,
+    /// The player waiting on their opponent to allow them to take back their last move.
+    undo_requested_by: Option<u8>,
+    /// The player waiting on their opponent to accept or decline their offer of a draw.
+    draw_offered_by: Option<u8>,
+    /// Set once the outcome has been sent, after which any moves still arriving are ignored.
+    game_over: bool,
+    /// Whether the first player was chosen at random, which players are told of as the game
+    /// begins.
+    first_player_chosen_at_random: bool,
+    rng: GameRng,
 }
 
 impl TicTacToeServer {
-    pub fn new(server_channel: Sender<GameServerEvent>) -> TicTacToeServer {
+    pub fn new(
+        server_channel: Sender<GameServerEvent>,
+        board_config: BoardConfig,
+    ) -> TicTacToeServer {
         TicTacToeServer {
             current_player: PLAYER_ONE_ID,
-            board: Board::new(),
-            server_channel,
+            board: Board::new(board_config),
+            move_times: Vec::new(),
+            started_at: Instant::now(),
+            server_channel: ServerChannel::new(server_channel),
+            rejected_moves: RejectedMoves::default(),
+            undo_requested_by: None,
+            draw_offered_by: None,
+            game_over: false,
+            first_player_chosen_at_random: false,
+            rng: GameRng::new(),
+        }
+    }
+
+    /// Carries on a saved game, as long as the game it describes could have been played and is
+    /// not yet over.
+    fn restore(
+        server_channel: Sender<GameServerEvent>,
+        state: SavedState,
+    ) -> Option<TicTacToeServer> {
+        let BoardConfig {
+            size, win_length, ..
+        } = state.board_config;
+        if !(MIN_BOARD_SIZE..=MAX_BOARD_SIZE).contains(&size)
+            || !(MIN_BOARD_SIZE..=size).contains(&win_length)
+            || ![PLAYER_ONE_ID, PLAYER_TWO_ID].contains(&state.current_player)
+        {
+            return None;
         }
+
+        let mut server = TicTacToeServer::new(server_channel, state.board_config);
+        if !matches!(
+            server.board.apply_marked_moves(&state.moves, &state.marks),
+            Ok(None)
+        ) {
+            return None;
+        }
+        server.current_player = state.current_player;
+
+        // The clock carries on from the last move, as the time the game spent saved is not played
+        let mut move_times = state.move_times;
+        move_times.resize(state.moves.len(), Duration::ZERO);
+        let elapsed = move_times.iter().copied().max().unwrap_or_default();
+        server.started_at = Instant::now()
+            .checked_sub(elapsed)
+            .unwrap_or(server.started_at);
+        server.move_times = move_times;
+
+        Some(server)
     }
 
     fn swap_player(&mut self) {
         self.current_player = get_alternative_player_id(self.current_player);
+        self.rejected_moves.reset();
+        self.undo_requested_by = None;
+        self.draw_offered_by = None;
+    }
+
+    async fn dispatch_error_event(&self, player_id: u8, error: Error) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode: DispatchMode::SinglePlayer { player_id },
+                event: ServerEvent::ErrorOccurred { error },
+            })
+            .await;
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
     }
 
-    async fn dispatch_board_updated_event(&self) {
+    async fn dispatch_board_updated_event(&self, dispatch_mode: DispatchMode) {
+        let board_config = self.board.get_config();
         let board_cells = self.board.get_cell_occupiers();
         self.server_channel
             .send(GameServerEvent::DispatchToClient {
-                dispatch_mode: DispatchMode::AllPlayers,
-                event: game::serialize_event(ServerEvent::BoardUpdated { board_cells }),
+                dispatch_mode,
+                event: ServerEvent::BoardUpdated {
+                    board_config,
+                    board_cells,
+                },
             })
             .await
-            .unwrap()
     }
 
     async fn dispatch_player_turn_event(&self, dispatch_mode: DispatchMode) {
         self.server_channel
             .send(GameServerEvent::DispatchToClient {
                 dispatch_mode,
-                event: game::serialize_event(ServerEvent::PlayerTurn {
+                event: ServerEvent::PlayerTurn {
                     player_id: self.current_player,
-                }),
+                },
+            })
+            .await
+    }
+
+    async fn start_turn(&self) {
+        self.server_channel
+            .send(GameServerEvent::TurnStarted {
+                player_id: self.current_player,
+            })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+            .await;
+    }
+
+    async fn dispatch_game_over_event(&mut self, outcome: Outcome) {
+        self.game_over = true;
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode: DispatchMode::AllPlayers,
+                event: ServerEvent::GameOver { outcome },
             })
+            .await;
+
+        let winner = match outcome {
+            Outcome::Draw => None,
+            Outcome::WinnerFound { player_id } => Some(player_id),
+        };
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
             .await
-            .unwrap()
     }
 
-    fn handle_move_made_event(&mut self, player_id: u8, move_index: usize) -> Result<(), Error> {
+    fn handle_move_made_event(
+        &mut self,
+        player_id: u8,
+        move_index: usize,
+        mark: Option<u8>,
+    ) -> Result<(), Error> {
         if player_id != self.current_player {
             return Err(Error::UnexpectedPlayer);
         }
 
-        self.board.add_move(player_id, move_index)
+        self.board
+            .add_move(player_id, move_index, mark.unwrap_or(player_id))?;
+        self.move_times.push(self.started_at.elapsed());
+
+        Ok(())
+    }
+
+    /// Every move on the board along with when it was made.
+    fn get_move_history(&self) -> Vec<MoveRecord> {
+        self.board
+            .get_moves()
+            .into_iter()
+            .zip(&self.move_times)
+            .map(|((player_id, cell), &made_at)| MoveRecord {
+                player_id,
+                cell,
+                made_at,
+            })
+            .collect()
+    }
+
+    /// Sends the player every move made so far, giving them their turn again if they asked for it
+    /// while choosing their move.
+    async fn handle_history_requested_event(&self, player_id: u8) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::History {
+                moves: self.get_move_history(),
+            },
+        )
+        .await;
+
+        if player_id == self.current_player {
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+        }
+    }
+
+    /// Asks the opponent of the current player whether they may take back their last move, which
+    /// can only be done on the current player's turn.
+    async fn handle_undo_requested_event(&mut self, player_id: u8) {
+        if player_id != self.current_player {
+            self.dispatch_error_event(player_id, Error::UnexpectedPlayer)
+                .await;
+            return;
+        }
+
+        if self.undo_requested_by.is_some() || !self.board.has_moved(player_id) {
+            self.dispatch_error_event(player_id, Error::NoMoveToTakeBack)
+                .await;
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+            return;
+        }
+
+        self.undo_requested_by = Some(player_id);
+        self.dispatch_event(
+            DispatchMode::SinglePlayer {
+                player_id: get_alternative_player_id(player_id),
+            },
+            ServerEvent::UndoRequested { player_id },
+        )
+        .await;
+    }
+
+    /// Takes back the last move of the player waiting on an answer if their opponent allowed it,
+    /// before giving them their turn again. Answers to requests that are no longer waiting, such
+    /// as after the turn timed out, are ignored.
+    async fn handle_undo_answered_event(&mut self, player_id: u8, approved: bool) {
+        let requester = get_alternative_player_id(player_id);
+        if self.undo_requested_by != Some(requester) {
+            return;
+        }
+        self.undo_requested_by = None;
+
+        if !approved {
+            self.dispatch_event(
+                DispatchMode::SinglePlayer {
+                    player_id: requester,
+                },
+                ServerEvent::UndoDeclined {
+                    player_id: requester,
+                },
+            )
+            .await;
+            self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
+                player_id: requester,
+            })
+            .await;
+            return;
+        }
+
+        self.board.take_back_move(requester);
+        self.move_times.truncate(self.board.get_moves().len());
+        self.rejected_moves.reset();
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::MoveTakenBack {
+                player_id: requester,
+            },
+        )
+        .await;
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+            .await;
+        self.start_turn().await;
+    }
+
+    async fn handle_common_event(&mut self, event: game::ClientEvent) {
+        match event {
+            game::ClientEvent::Resign { player_id } => {
+                self.dispatch_event(
+                    DispatchMode::AllPlayers,
+                    ServerEvent::Resigned { player_id },
+                )
+                .await;
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
+            }
+            game::ClientEvent::DrawOffer { player_id } => {
+                self.handle_draw_offer_event(player_id).await
+            }
+            game::ClientEvent::DrawResponse {
+                player_id,
+                accepted,
+            } => self.handle_draw_response_event(player_id, accepted).await,
+        }
+    }
+
+    /// Asks the opponent of the current player whether they accept a draw, which can only be
+    /// offered on the current player's turn.
+    async fn handle_draw_offer_event(&mut self, player_id: u8) {
+        if player_id != self.current_player {
+            self.dispatch_error_event(player_id, Error::UnexpectedPlayer)
+                .await;
+            return;
+        }
+
+        // The offerer is already waiting on an answer to an earlier offer
+        if self.draw_offered_by.is_some() {
+            return;
+        }
+
+        self.draw_offered_by = Some(player_id);
+        self.dispatch_event(
+            DispatchMode::SinglePlayer {
+                player_id: get_alternative_player_id(player_id),
+            },
+            ServerEvent::DrawOffered { player_id },
+        )
+        .await;
+    }
+
+    /// Ends the game in a draw if the opponent of the player waiting on an answer accepted it,
+    /// otherwise gives them their turn again. Answers to offers that are no longer waiting, such
+    /// as after the turn timed out, are ignored.
+    async fn handle_draw_response_event(&mut self, player_id: u8, accepted: bool) {
+        let offerer = get_alternative_player_id(player_id);
+        if self.draw_offered_by != Some(offerer) {
+            return;
+        }
+        self.draw_offered_by = None;
+
+        if accepted {
+            self.dispatch_game_over_event(Outcome::Draw).await;
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id: offerer },
+            ServerEvent::DrawDeclined { player_id: offerer },
+        )
+        .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id: offerer })
+            .await;
     }
 }
 
 #[async_trait]
 impl GameServer for TicTacToeServer {
+    type ClientEvent = AnyClientEvent<ClientEvent>;
+    type ServerEvent = ServerEvent;
+
+    fn set_first_player(&mut self, first_player: FirstPlayer) {
+        self.current_player = match first_player {
+            FirstPlayer::PlayerOne => PLAYER_ONE_ID,
+            FirstPlayer::PlayerTwo => PLAYER_TWO_ID,
+            FirstPlayer::Random if self.rng.gen() => PLAYER_ONE_ID,
+            FirstPlayer::Random => PLAYER_TWO_ID,
+        };
+        self.first_player_chosen_at_random = first_player == FirstPlayer::Random;
+    }
+
+    fn set_rng(&mut self, rng: GameRng) {
+        self.rng = rng;
+    }
+
+    fn config(&self) -> Vec<u8> {
+        game::serialize_config(self.board.get_config())
+    }
+
     async fn begin(&self) {
-        self.dispatch_board_updated_event().await;
-        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
+        if self.first_player_chosen_at_random {
+            self.dispatch_event(
+                DispatchMode::AllPlayers,
+                ServerEvent::FirstPlayerChosen {
+                    player_id: self.current_player,
+                },
+            )
+            .await;
+        }
+        self.dispatch_board_updated_event(DispatchMode::AllPlayers)
             .await;
+        self.start_turn().await;
     }
 
-    async fn handle_event(&mut self, event: Vec<u8>) {
-        return match game::deserialize_event(event) {
+    async fn handle_event(&mut self, event: AnyClientEvent<ClientEvent>) {
+        if self.game_over {
+            return;
+        }
+
+        let event = match event {
+            AnyClientEvent::Game(event) => event,
+            AnyClientEvent::Common(event) => return self.handle_common_event(event).await,
+        };
+
+        return match event {
             MoveMade {
                 player_id,
                 move_index,
+                mark,
             } => {
-                if let Err(error) = self.handle_move_made_event(player_id, move_index) {
-                    self.server_channel
-                        .send(GameServerEvent::DispatchToClient {
-                            dispatch_mode: DispatchMode::SinglePlayer {
-                                player_id: self.current_player,
-                            },
-                            event: game::serialize_event(ServerEvent::ErrorOccurred { error }),
-                        })
+                if let Err(error) = self.handle_move_made_event(player_id, move_index, mark) {
+                    self.dispatch_error_event(self.current_player, error).await;
+
+                    if self
+                        .rejected_moves
+                        .reject(&self.server_channel, self.current_player, player_id)
                         .await
-                        .unwrap();
+                    {
+                        self.dispatch_game_over_event(Outcome::WinnerFound {
+                            player_id: get_alternative_player_id(player_id),
+                        })
+                        .await;
+                        return;
+                    }
 
                     self.dispatch_player_turn_event(DispatchMode::SinglePlayer {
                         player_id: self.current_player,
@@ -130,270 +645,807 @@ impl GameServer for TicTacToeServer {
                     return;
                 }
 
-                self.dispatch_board_updated_event().await;
+                self.undo_requested_by = None;
+                self.dispatch_board_updated_event(DispatchMode::AllPlayers)
+                    .await;
                 match self.board.determine_outcome() {
                     None => {
                         self.swap_player();
-                        self.dispatch_player_turn_event(DispatchMode::AllPlayers)
-                            .await;
-                    }
-                    Some(outcome) => {
-                        self.server_channel
-                            .send(GameServerEvent::DispatchToClient {
-                                dispatch_mode: DispatchMode::AllPlayers,
-                                event: game::serialize_event(ServerEvent::GameOver { outcome }),
-                            })
-                            .await
-                            .unwrap();
-
-                        self.server_channel
-                            .send(GameServerEvent::GameOver)
-                            .await
-                            .unwrap()
+                        self.start_turn().await;
                     }
+                    Some(outcome) => self.dispatch_game_over_event(outcome).await,
+                }
+            }
+            UndoRequested { player_id } => self.handle_undo_requested_event(player_id).await,
+            SuspendRequested { player_id } => {
+                if player_id != self.current_player {
+                    self.dispatch_error_event(player_id, Error::UnexpectedPlayer)
+                        .await;
+                    return;
                 }
+
+                self.server_channel
+                    .send(GameServerEvent::SuspendRequested { player_id })
+                    .await;
+                // Only reaches the player if the game could not be saved, letting them carry on
+                self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+                    .await;
+            }
+            UndoAnswered {
+                player_id,
+                approved,
+            } => self.handle_undo_answered_event(player_id, approved).await,
+            HistoryRequested { player_id } => self.handle_history_requested_event(player_id).await,
+        };
+    }
+
+    async fn handle_turn_timed_out(&mut self, player_id: u8, action: TimeoutAction) {
+        if self.game_over || player_id != self.current_player {
+            return;
+        }
+
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode: DispatchMode::AllPlayers,
+                event: ServerEvent::TurnTimedOut { player_id, action },
+            })
+            .await;
+
+        match action {
+            TimeoutAction::Forfeit => {
+                self.dispatch_game_over_event(Outcome::WinnerFound {
+                    player_id: get_alternative_player_id(player_id),
+                })
+                .await
             }
+            TimeoutAction::SkipTurn => {
+                self.swap_player();
+                self.start_turn().await;
+            }
+        }
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        self.dispatch_board_updated_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::SinglePlayer { player_id })
+            .await;
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_board_updated_event(DispatchMode::Spectators)
+            .await;
+        self.dispatch_player_turn_event(DispatchMode::Spectators)
+            .await;
+    }
+
+    fn save_state(&self) -> Option<serde_json::Value> {
+        let state = SavedState {
+            board_config: self.board.get_config(),
+            moves: self.board.get_moves(),
+            move_times: self.move_times.clone(),
+            marks: self.board.get_marks(),
+            current_player: self.current_player,
         };
+
+        serde_json::to_value(state).ok()
     }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub enum ClientEvent {
-    MoveMade { player_id: u8, move_index: usize },
+    MoveMade {
+        player_id: u8,
+        move_index: usize,
+        /// The ID of the player whose mark is placed, which can only be another player's in the
+        /// wild variant. Missing from moves made before there were variants, which placed the
+        /// player's own.
+        #[serde(default)]
+        mark: Option<u8>,
+    },
+    /// Asks to take back the player's last move, along with any move their opponent made since.
+    UndoRequested {
+        player_id: u8,
+    },
+    UndoAnswered {
+        player_id: u8,
+        approved: bool,
+    },
+    /// Asks for the game to be saved so that it can be resumed later.
+    SuspendRequested {
+        player_id: u8,
+    },
+    /// Asks for every move made so far.
+    HistoryRequested {
+        player_id: u8,
+    },
 }
 
-pub struct TicTacToeClient<I, O, C>
+pub struct TicTacToeClient<F, C>
 where
-    I: io::BufRead + Send,
-    O: io::Write + Send,
+    F: Frontend + ?Sized,
     C: ClientType,
 {
-    input: I,
-    client_channel: Sender<GameClientEvent>,
+    frontend: Arc<F>,
+    client_channel: ClientChannel<AnyClientEvent<ClientEvent>>,
     client_type: C,
-    user_output: Arc<Mutex<O>>,
+    user_output: UserOutput<F>,
+    board_config: BoardConfig,
+    board_cells: Vec<Option<u8>>,
+    board_received: bool,
+    screen_reader: bool,
+    marks: PlayerMarks,
 }
 
-impl<I, O, C> TicTacToeClient<I, O, C>
+impl<F, C> TicTacToeClient<F, C>
 where
-    I: io::BufRead + Send,
-    O: io::Write + Send,
+    F: Frontend + ?Sized,
     C: ClientType,
 {
     pub fn new(
-        input: I,
-        output: Arc<Mutex<O>>,
+        frontend: Arc<F>,
         client_channel: Sender<GameClientEvent>,
         client_type: C,
-    ) -> TicTacToeClient<I, O, C> {
+    ) -> TicTacToeClient<F, C> {
         TicTacToeClient {
-            input,
-            user_output: output,
-            client_channel,
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
             client_type,
+            board_config: BoardConfig::default(),
+            board_cells: vec![None; BoardConfig::default().cell_count()],
+            board_received: false,
+            screen_reader: false,
+            marks: PlayerMarks::default(),
         }
     }
 
-    fn get_player_icon_by_id(&self, id: u8) -> char {
-        match id {
-            PLAYER_ONE_ID => 'X',
-            PLAYER_TWO_ID => 'O',
-            _ => panic!("Unexpected id provided"),
-        }
+    fn get_player_icon_by_id(&self, id: u8) -> String {
+        String::from(self.marks.get(id))
     }
 
-    fn get_optional_player_icon_by_id(&self, player_id: Option<u8>) -> char {
-        match player_id {
-            None => ' ',
-            Some(i) => self.get_player_icon_by_id(i),
+    /// The renderer drawing the board in the way the user has asked for.
+    fn get_board_renderer(&self) -> &'static dyn BoardRenderer {
+        match self.user_output.output_style() {
+            _ if self.screen_reader => &DescriptionRenderer,
+            OutputStyle::Standard => &GridRenderer,
+            OutputStyle::Terse => &TerseRenderer,
         }
     }
 
-    async fn handle_board_updated_event(&self, board_cells: [Option<u8>; BOARD_SIZE]) {
-        let cell_icons = board_cells.map(|x| self.get_optional_player_icon_by_id(x));
-        let board_output = format!(
-            "_________\n{} | {} | {}\n{} | {} | {}\n{} | {} | {}\n_________\n",
-            cell_icons[0],
-            cell_icons[1],
-            cell_icons[2],
-            cell_icons[3],
-            cell_icons[4],
-            cell_icons[5],
-            cell_icons[6],
-            cell_icons[7],
-            cell_icons[8]
-        );
+    async fn handle_board_updated_event(
+        &mut self,
+        board_config: BoardConfig,
+        board_cells: Vec<Option<u8>>,
+    ) -> Result<(), GameClientError> {
+        self.board_config = board_config;
+        let previous_cells = std::mem::replace(&mut self.board_cells, board_cells);
+        let board_cells = &self.board_cells;
+
+        // The first board received may already contain moves, such as when spectating a game in
+        // progress, so only point out changes from then on. A player's own moves are not pointed
+        // out, as they already know where they played.
+        let own_player_id = self.client_type.get_own_player_id();
+        let changes: Vec<(usize, u8)> = if std::mem::replace(&mut self.board_received, true) {
+            changed_cells(&previous_cells, board_cells)
+                .filter(|&(_, player_id)| Some(player_id) != own_player_id)
+                .collect()
+        } else {
+            Vec::new()
+        };
 
-        writeln!(&mut self.user_output.lock().unwrap(), "{}", board_output).unwrap()
+        self.write_board(&changes)?;
+
+        Ok(())
+    }
+
+    /// Writes the board as last sent by the server, pointing out the given changes.
+    fn write_board(&self, changes: &[(usize, u8)]) -> Result<(), io::Error> {
+        let board_output = self.get_board_renderer().render(&BoardView {
+            config: self.board_config,
+            cells: &self.board_cells,
+            changes,
+            locale: self.user_output.locale(),
+            marks: &self.marks,
+            paint: &|text, paint| self.user_output.paint(text, paint),
+        });
+
+        self.user_output.write_board(board_output)
     }
 
-    async fn handle_game_over_event(&self, outcome: Outcome) {
+    async fn handle_game_over_event(&mut self, outcome: Outcome) -> Result<(), GameClientError> {
         match outcome {
-            Outcome::Draw => writeln!(
-                &mut self.user_output.lock().unwrap(),
-                "Game over! There was a draw!"
-            )
-            .unwrap(),
+            Outcome::Draw => self.user_output.write_message("game-draw", &[])?,
             Outcome::WinnerFound { player_id } => {
                 let player_icon = self.get_player_icon_by_id(player_id);
-                writeln!(
-                    &mut self.user_output.lock().unwrap(),
-                    "Game over! Player {} won!",
-                    player_icon
-                )
-                .unwrap()
+                self.user_output
+                    .write_message("game-won", &[("player", &player_icon)])?
             }
         }
-        self.client_channel
-            .send(GameClientEvent::GameOver)
-            .await
-            .unwrap()
+        self.send_to_client(GameClientEvent::GameOver).await
     }
 
-    async fn handle_error_occurred_event(&self, error: Error) {
-        writeln!(&mut self.user_output.lock().unwrap(), "Error: {}", error).unwrap()
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        match error {
+            Error::InvalidCellIndex { cell_count } => self.user_output.write_error(
+                "tic-tac-toe-error-invalid-cell-index",
+                &[("cells", &cell_count)],
+            ),
+            Error::CellOccupied => self
+                .user_output
+                .write_error("tic-tac-toe-error-cell-occupied", &[]),
+            Error::UnexpectedPlayer => self
+                .user_output
+                .write_error("tic-tac-toe-error-unexpected-player", &[]),
+            Error::NoMoveToTakeBack => self
+                .user_output
+                .write_error("tic-tac-toe-error-no-move-to-take-back", &[]),
+            Error::MarkNotAllowed => self
+                .user_output
+                .write_error("tic-tac-toe-error-mark-not-allowed", &[]),
+        }?;
+
+        Ok(())
     }
 
-    async fn make_player_move(&mut self, player_id: u8) -> Result<(), io::Error> {
-        let move_index = self.get_move().await?;
-        self.client_channel
-            .send(GameClientEvent::DispatchToServer {
-                event: game::serialize_event(MoveMade {
-                    player_id,
-                    move_index,
-                }),
-            })
-            .await
-            .unwrap();
+    async fn handle_turn_timed_out_event(
+        &self,
+        player_id: u8,
+        action: TimeoutAction,
+    ) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        let key = match action {
+            TimeoutAction::Forfeit => "turn-timed-out-forfeit",
+            TimeoutAction::SkipTurn => "turn-timed-out-skip",
+        };
+        self.user_output
+            .write_message(key, &[("player", &player_icon)])?;
 
         Ok(())
     }
 
-    async fn get_move(&mut self) -> Result<usize, io::Error> {
-        loop {
-            writeln!(
-                &mut self.user_output.lock().unwrap(),
-                "Input a number between 1 and {} to make your move:",
-                BOARD_SIZE
-            )?;
+    async fn make_player_move(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        match self.get_move().await? {
+            PlayerInput::Move { cell, mark } => self.send_move(player_id, cell, mark).await,
+            PlayerInput::Undo => self.send_event(UndoRequested { player_id }).await,
+            PlayerInput::Save => self.send_event(SuspendRequested { player_id }).await,
+            PlayerInput::History => self.send_event(HistoryRequested { player_id }).await,
+            PlayerInput::OfferDraw => {
+                self.send_common_event(game::ClientEvent::DrawOffer { player_id })
+                    .await
+            }
+            PlayerInput::Resign => {
+                self.send_common_event(game::ClientEvent::Resign { player_id })
+                    .await
+            }
+            PlayerInput::Quit => self.send_to_client(GameClientEvent::Quit).await,
+        }
+    }
 
-            let input_text = &mut String::new();
-            self.input.read_line(input_text)?;
+    async fn send_move(
+        &mut self,
+        player_id: u8,
+        move_index: usize,
+        mark: Option<u8>,
+    ) -> Result<(), GameClientError> {
+        self.send_event(MoveMade {
+            player_id,
+            move_index,
+            mark,
+        })
+        .await
+    }
 
-            match input_text.trim().parse::<usize>() {
-                Err(_) => writeln!(
-                    &mut self.user_output.lock().unwrap(),
-                    "That is not a number, please try again."
-                )?,
-                Ok(index) => return Ok(index),
-            };
-        }
+    async fn send_event(&mut self, event: ClientEvent) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: AnyClientEvent::Game(event),
+        })
+        .await
     }
-}
 
-#[async_trait]
-pub trait ClientTypeEvent {
-    fn get_game_started_message(&self) -> String;
-    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), io::Error>;
-}
+    /// Sends one of the client events common to every game, such as resigning.
+    async fn send_common_event(&mut self, event: game::ClientEvent) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: AnyClientEvent::Common(event),
+        })
+        .await
+    }
 
-#[async_trait]
-impl<I, O> ClientTypeEvent for TicTacToeClient<I, O, LocalClient>
-where
-    I: io::BufRead + Send,
-    O: io::Write + Send,
-{
-    fn get_game_started_message(&self) -> String {
-        String::from("Lets begin.")
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<AnyClientEvent<ClientEvent>>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
     }
 
-    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), io::Error> {
+    /// Asks the user whether the given player may take back their last move, answering on behalf
+    /// of their opponent.
+    async fn answer_undo_request(&mut self, player_id: u8) -> Result<(), GameClientError> {
         let player_icon = self.get_player_icon_by_id(player_id);
-        writeln!(
-            &mut self.user_output.lock().unwrap(),
-            "Player {}'s turn!",
-            player_icon
-        )
-        .unwrap();
+        self.user_output
+            .write_message("undo-requested", &[("player", &player_icon)])?;
 
-        self.make_player_move(player_id).await
-    }
-}
+        let input_text = self.frontend.read_line().await?;
+        let approved = matches!(input_text.trim().to_ascii_lowercase().as_str(), "y" | "yes");
 
-#[async_trait]
-impl<I, O> ClientTypeEvent for TicTacToeClient<I, O, OnlineClient>
-where
-    I: io::BufRead + Send,
-    O: io::Write + Send,
-{
-    fn get_game_started_message(&self) -> String {
-        String::from("All players connected, lets begin.")
+        self.send_event(UndoAnswered {
+            player_id: get_alternative_player_id(player_id),
+            approved,
+        })
+        .await
     }
 
-    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), io::Error> {
-        if player_id != self.client_type.id {
-            writeln!(
-                &mut self.user_output.lock().unwrap(),
-                "Waiting for other player to make a move."
+    /// Asks the user whether they accept the given player's offer of a draw, answering on behalf
+    /// of their opponent.
+    async fn answer_draw_offer(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("draw-offered", &[("player", &player_icon)])?;
+
+        let input_text = self.frontend.read_line().await?;
+        let accepted = matches!(input_text.trim().to_ascii_lowercase().as_str(), "y" | "yes");
+
+        self.send_common_event(game::ClientEvent::DrawResponse {
+            player_id: get_alternative_player_id(player_id),
+            accepted,
+        })
+        .await
+    }
+
+    async fn handle_draw_declined_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("draw-declined", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_resigned_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-resigned", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_history_event(&self, moves: Vec<MoveRecord>) -> Result<(), GameClientError> {
+        if moves.is_empty() {
+            self.user_output.write_message("move-history-empty", &[])?;
+            return Ok(());
+        }
+
+        self.user_output.write_message("move-history", &[])?;
+        for (number, record) in (1..).zip(moves) {
+            let player_icon = self.get_player_icon_by_id(record.player_id);
+            let seconds = format!("{:.1}", record.made_at.as_secs_f64());
+            self.user_output.write_message(
+                "move-history-entry",
+                &[
+                    ("number", &number),
+                    ("player", &player_icon),
+                    ("cell", &record.cell),
+                    ("seconds", &seconds),
+                ],
             )?;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_undo_declined_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("undo-declined", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_move_taken_back_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("move-taken-back", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_first_player_chosen_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("first-player-chosen", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    /// Writes the message telling the user the rules of the variant being played, if they differ
+    /// from the standard rules.
+    fn write_variant_message(&self) -> Result<(), io::Error> {
+        let key = match self.board_config.variant {
+            Variant::Standard => return Ok(()),
+            Variant::Misere => "variant-misere",
+            Variant::Wild => "variant-wild",
+        };
+        let first = self.get_player_icon_by_id(PLAYER_ONE_ID);
+        let second = self.get_player_icon_by_id(PLAYER_TWO_ID);
+
+        self.user_output
+            .write_message(key, &[("first", &first), ("second", &second)])
+    }
+
+    /// Finds the player whose mark the user entered, ignoring case.
+    fn find_mark(&self, text: &str) -> Option<u8> {
+        [PLAYER_ONE_ID, PLAYER_TWO_ID]
+            .into_iter()
+            .find(|&player_id| self.marks.get(player_id).eq_ignore_ascii_case(text))
+    }
+
+    /// Reads the player's choice of cell, or the command they entered in its place, carrying out
+    /// those that need nothing from the server before asking again.
+    async fn get_move(&mut self) -> Result<PlayerInput, io::Error> {
+        loop {
+            let cell_count = self.board_config.cell_count();
+            let prompt = match self.board_config.variant {
+                Variant::Wild => {
+                    let first = self.get_player_icon_by_id(PLAYER_ONE_ID);
+                    let second = self.get_player_icon_by_id(PLAYER_TWO_ID);
+                    self.user_output.message(
+                        "move-prompt-wild",
+                        &[
+                            ("cells", &cell_count),
+                            ("first", &first),
+                            ("second", &second),
+                        ],
+                    )
+                }
+                Variant::Standard | Variant::Misere => self
+                    .user_output
+                    .message("move-prompt", &[("cells", &cell_count)]),
+            };
+
+            let input_text = self.frontend.prompt_for_move(&prompt).await?;
+            let input_text = input_text.trim();
+            // Taking back a move and saving could be asked for without the prefix before there
+            // were commands, so still can be
+            if input_text.eq_ignore_ascii_case("undo") {
+                return Ok(PlayerInput::Undo);
+            }
+            if input_text.eq_ignore_ascii_case("save") {
+                return Ok(PlayerInput::Save);
+            }
+
+            match command::parse(input_text, &COMMANDS) {
+                Some(Command::Help) => {
+                    for line in command::describe(
+                        &COMMANDS,
+                        self.user_output.locale(),
+                        self.user_output.output_style(),
+                    ) {
+                        self.user_output.write_line(line)?;
+                    }
+                }
+                Some(Command::Board) => self.write_board(&[])?,
+                Some(Command::Quit) => return Ok(PlayerInput::Quit),
+                Some(Command::Game(command)) => {
+                    return Ok(match command.name {
+                        "undo" => PlayerInput::Undo,
+                        "save" => PlayerInput::Save,
+                        "history" => PlayerInput::History,
+                        "draw" => PlayerInput::OfferDraw,
+                        "resign" => PlayerInput::Resign,
+                        name => unreachable!("No input for the {} command", name),
+                    })
+                }
+                Some(Command::Unknown(command)) => self
+                    .user_output
+                    .write_message("unknown-command", &[("command", &command)])?,
+                None => {
+                    // In the wild variant the cell may be followed by the mark to place, such as
+                    // "5 O" or "5o"
+                    let (cell_text, mark_text) = match self.board_config.variant {
+                        Variant::Wild => input_text.split_at(
+                            input_text
+                                .find(|c: char| !c.is_ascii_digit())
+                                .unwrap_or(input_text.len()),
+                        ),
+                        Variant::Standard | Variant::Misere => (input_text, ""),
+                    };
+                    let mark_text = mark_text.trim();
+
+                    match cell_text.parse::<usize>() {
+                        Err(_) => self.user_output.write_message("not-a-number", &[])?,
+                        Ok(cell) if mark_text.is_empty() => {
+                            return Ok(PlayerInput::Move { cell, mark: None })
+                        }
+                        Ok(cell) => match self.find_mark(mark_text) {
+                            Some(mark) => {
+                                return Ok(PlayerInput::Move {
+                                    cell,
+                                    mark: Some(mark),
+                                })
+                            }
+                            None => self
+                                .user_output
+                                .write_message("unknown-mark", &[("mark", &mark_text)])?,
+                        },
+                    }
+                }
+            };
+        }
+    }
+}
+
+/// What a player entered when asked for their move.
+enum PlayerInput {
+    /// The cell chosen, along with the player whose mark is placed there if not the player's own.
+    Move {
+        cell: usize,
+        mark: Option<u8>,
+    },
+    Undo,
+    Save,
+    History,
+    OfferDraw,
+    Resign,
+    Quit,
+}
+
+/// Finds the cells that have been newly occupied, along with the ID of the player occupying them.
+fn changed_cells<'a>(
+    previous: &'a [Option<u8>],
+    current: &'a [Option<u8>],
+) -> impl Iterator<Item = (usize, u8)> + 'a {
+    previous
+        .iter()
+        .zip(current)
+        .enumerate()
+        .filter_map(|(index, (previous, current))| match (previous, current) {
+            (None, Some(player_id)) => Some((index, *player_id)),
+            _ => None,
+        })
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    /// The key of the message telling the user the game has started.
+    fn get_game_started_message_key(&self) -> &'static str;
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+    async fn handle_undo_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+    async fn handle_draw_offered_event(&mut self, player_id: u8) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TicTacToeClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message_key(&self) -> &'static str {
+        "game-started-local"
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        self.make_player_move(player_id).await
+    }
+
+    async fn handle_undo_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.answer_undo_request(player_id).await
+    }
+
+    async fn handle_draw_offered_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.answer_draw_offer(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TicTacToeClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message_key(&self) -> &'static str {
+        "game-started-online"
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            self.user_output
+                .write_message("waiting-for-opponent", &[])?;
 
             return Ok(());
         }
 
-        writeln!(&mut self.user_output.lock().unwrap(), "It's your turn!").unwrap();
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
         self.make_player_move(player_id).await
     }
+
+    async fn handle_undo_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.answer_undo_request(player_id).await
+    }
+
+    async fn handle_draw_offered_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.answer_draw_offer(player_id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TicTacToeClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message_key(&self) -> &'static str {
+        "game-started-spectator"
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let player_icon = self.get_player_icon_by_id(player_id);
+        self.user_output
+            .write_message("player-turn", &[("player", &player_icon)])?;
+
+        Ok(())
+    }
+
+    async fn handle_undo_requested_event(&mut self, _player_id: u8) -> Result<(), GameClientError> {
+        Ok(())
+    }
+
+    async fn handle_draw_offered_event(&mut self, _player_id: u8) -> Result<(), GameClientError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TicTacToeClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message_key(&self) -> &'static str {
+        "game-started-bot"
+    }
+
+    async fn handle_player_turn_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        if player_id != self.client_type.id {
+            return Ok(());
+        }
+
+        self.client_type.wait_to_move().await;
+        if let Some(move_index) = bot::choose_move(&self.board_cells, self.board_config, player_id)
+        {
+            self.send_move(player_id, move_index, None).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Bots are happy to let their opponent take back a move.
+    async fn handle_undo_requested_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.send_event(UndoAnswered {
+            player_id: get_alternative_player_id(player_id),
+            approved: true,
+        })
+        .await
+    }
+
+    /// Bots play every game out rather than agreeing to a draw.
+    async fn handle_draw_offered_event(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        self.send_common_event(game::ClientEvent::DrawResponse {
+            player_id: get_alternative_player_id(player_id),
+            accepted: false,
+        })
+        .await
+    }
 }
 
 #[async_trait]
-impl<I, O, C> GameClient for TicTacToeClient<I, O, C>
+impl<F, C> GameClient for TicTacToeClient<F, C>
 where
-    I: io::BufRead + Send + Sync,
-    O: io::Write + Send + Sync,
+    F: Frontend + ?Sized,
     C: ClientType + Send + Sync,
     Self: ClientTypeEvent,
 {
-    async fn handle_game_started_event(&self) {
-        writeln!(
-            &mut self.user_output.lock().unwrap(),
-            "{}",
-            self.get_game_started_message()
-        )
-        .unwrap();
+    type ServerEvent = ServerEvent;
+    type ClientEvent = AnyClientEvent<ClientEvent>;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn set_screen_reader(&mut self, screen_reader: bool) {
+        self.screen_reader = screen_reader;
+    }
+
+    fn set_player_marks(&mut self, marks: PlayerMarks) {
+        self.marks = marks;
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id)
     }
 
-    async fn handle_event(&mut self, event: Vec<u8>) -> Result<(), io::Error> {
-        match game::deserialize_event(event) {
+    async fn handle_game_started_event(&mut self, config: Vec<u8>) -> Result<(), GameClientError> {
+        // The board is laid out as soon as the game starts, ahead of it first being sent
+        if let Some(board_config) = game::deserialize_config::<BoardConfig>(&config) {
+            self.board_config = board_config;
+            self.board_cells = vec![None; board_config.cell_count()];
+        }
+        self.user_output
+            .write_message(self.get_game_started_message_key(), &[])?;
+        self.write_variant_message()?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
             ServerEvent::GameOver { outcome } => self.handle_game_over_event(outcome).await,
-            ServerEvent::BoardUpdated { board_cells } => {
-                self.handle_board_updated_event(board_cells).await
+            ServerEvent::BoardUpdated {
+                board_config,
+                board_cells,
+            } => {
+                self.handle_board_updated_event(board_config, board_cells)
+                    .await
             }
             ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
-            ServerEvent::PlayerTurn { player_id } => {
-                self.handle_player_turn_event(player_id).await?
+            ServerEvent::PlayerTurn { player_id } => self.handle_player_turn_event(player_id).await,
+            ServerEvent::TurnTimedOut { player_id, action } => {
+                self.handle_turn_timed_out_event(player_id, action).await
             }
-        };
-
-        Ok(())
+            ServerEvent::UndoRequested { player_id } => {
+                self.handle_undo_requested_event(player_id).await
+            }
+            ServerEvent::UndoDeclined { player_id } => {
+                self.handle_undo_declined_event(player_id).await
+            }
+            ServerEvent::MoveTakenBack { player_id } => {
+                self.handle_move_taken_back_event(player_id).await
+            }
+            ServerEvent::FirstPlayerChosen { player_id } => {
+                self.handle_first_player_chosen_event(player_id).await
+            }
+            ServerEvent::DrawOffered { player_id } => {
+                self.handle_draw_offered_event(player_id).await
+            }
+            ServerEvent::DrawDeclined { player_id } => {
+                self.handle_draw_declined_event(player_id).await
+            }
+            ServerEvent::Resigned { player_id } => self.handle_resigned_event(player_id).await,
+            ServerEvent::History { moves } => self.handle_history_event(moves).await,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::io::BufReader;
     use std::str::from_utf8;
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use tokio::sync::mpsc::error::TryRecvError;
     use tokio::sync::mpsc::Receiver;
 
+    use crate::client::TerminalFrontend;
+    use crate::connection::{self, Codec, SUPPORTED_CODECS};
+    use crate::server::OutgoingEvent;
+
     use super::*;
 
     async fn get_test_client_and_output<C: ClientType>(
-        input: &[u8],
+        input: &'static [u8],
         client_type: C,
     ) -> (
-        TicTacToeClient<BufReader<&[u8]>, Vec<u8>, C>,
+        TicTacToeClient<TerminalFrontend<Vec<u8>>, C>,
         Arc<Mutex<Vec<u8>>>,
         Receiver<GameClientEvent>,
     ) {
@@ -401,7 +1453,11 @@ mod tests {
         let output_clone = Arc::clone(&output);
 
         let (sender, receiver) = tokio::sync::mpsc::channel(1);
-        let client = TicTacToeClient::new(BufReader::new(input), output, sender, client_type);
+        let client = TicTacToeClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
 
         (client, output_clone, receiver)
     }
@@ -415,15 +1471,15 @@ mod tests {
             expected.as_bytes(),
             "expected\n{}, actual\n{}",
             expected,
-            from_utf8(actual).unwrap().to_string(),
+            from_utf8(actual).unwrap(),
         )
     }
 
     #[tokio::test]
     async fn client_handles_board_updated_event() {
-        let (client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
 
-        let board_cells = [
+        let board_cells = vec![
             None,
             Some(1),
             Some(1),
@@ -435,7 +1491,10 @@ mod tests {
             Some(1),
         ];
 
-        client.handle_board_updated_event(board_cells).await;
+        client
+            .handle_board_updated_event(BoardConfig::default(), board_cells)
+            .await
+            .unwrap();
         assert_client_output(
             output,
             "_________\n  | X | X\nO |   |  \nO |   | X\n_________\n\n",
@@ -443,89 +1502,1350 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn client_handles_game_over_event_for_draw() {
-        let (client, output, mut receiver) = get_test_client_and_output(&[], LocalClient {}).await;
+    async fn client_handles_board_updated_event_in_terse_style() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
 
-        client.handle_game_over_event(Outcome::Draw).await;
-        assert_client_output(output, "Game over! There was a draw!\n");
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![
+                    None,
+                    Some(1),
+                    Some(1),
+                    Some(2),
+                    None,
+                    None,
+                    Some(2),
+                    None,
+                    Some(1),
+                ],
+            )
+            .await
+            .unwrap();
+        assert_client_output(output, "1XX/O56/O8X\n")
+    }
 
-        let event = receiver.recv().await;
-        assert!(matches!(event, Some(GameClientEvent::GameOver)))
+    /// Keeps boards apart from other messages, as a frontend drawing the board itself would.
+    #[derive(Default)]
+    struct BoardFrontend {
+        boards: Mutex<Vec<String>>,
+        messages: Mutex<Vec<String>>,
     }
 
-    #[tokio::test]
-    async fn client_handles_game_over_event_for_win() {
-        let (client, output, mut receiver) = get_test_client_and_output(&[], LocalClient {}).await;
+    #[async_trait]
+    impl Frontend for BoardFrontend {
+        fn show_message(&self, message: &str, _paint: Option<Paint>) -> io::Result<()> {
+            self.messages.lock().unwrap().push(String::from(message));
+            Ok(())
+        }
 
-        client
-            .handle_game_over_event(Outcome::WinnerFound { player_id: 1 })
-            .await;
-        assert_client_output(output, "Game over! Player X won!\n");
+        fn render_board(&self, board: &str) -> io::Result<()> {
+            self.boards.lock().unwrap().push(String::from(board));
+            Ok(())
+        }
 
-        let event = receiver.recv().await;
-        assert!(matches!(event, Some(GameClientEvent::GameOver)))
+        async fn read_line(&self) -> io::Result<String> {
+            Ok(String::from("5\n"))
+        }
     }
 
     #[tokio::test]
-    async fn client_handles_error_occurred_event() {
-        let (client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+    async fn client_renders_board_and_prompts_through_frontend() {
+        let frontend = Arc::new(BoardFrontend::default());
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(1);
+        let mut client = TicTacToeClient::new(Arc::clone(&frontend), sender, LocalClient {});
+        client.set_output_style(OutputStyle::Terse);
 
         client
-            .handle_error_occurred_event(Error::CellOccupied)
-            .await;
-        assert_client_output(output, "Error: This cell is already occupied.\n")
+            .handle_board_updated_event(BoardConfig::default(), vec![None; 9])
+            .await
+            .unwrap();
+        client.make_player_move(PLAYER_ONE_ID).await.unwrap();
+
+        assert_eq!(*frontend.boards.lock().unwrap(), ["123/456/789"]);
+        assert_eq!(*frontend.messages.lock().unwrap(), ["Move (1-9):"]);
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<ClientEvent>(event),
+                MoveMade {
+                    player_id: PLAYER_ONE_ID,
+                    move_index: 5,
+                    mark: None
+                }
+            ),
+            _ => panic!("Expected the move to be sent to the server"),
+        }
     }
 
     #[tokio::test]
-    async fn client_get_move_handles_errors_until_valid_move_provided() {
-        let input = "not a number\n1".as_bytes();
+    async fn client_shows_players_with_marks_they_chose() {
+        let (mut client, output, _receiver) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+        client.set_player_marks(PlayerMarks::assign([
+            (PLAYER_ONE_ID, Some("★".parse().unwrap())),
+            (PLAYER_TWO_ID, Some("☆".parse().unwrap())),
+        ]));
+
+        let mut cells = vec![None; 9];
+        cells[0] = Some(1);
+        cells[4] = Some(2);
+        client
+            .handle_board_updated_event(BoardConfig::default(), cells)
+            .await
+            .unwrap();
+        client
+            .handle_game_over_event(Outcome::WinnerFound { player_id: 2 })
+            .await
+            .unwrap();
+        assert_client_output(output, "★23/4☆6/789\n☆ won.\n")
+    }
 
-        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_online_client_in_terse_style() {
+        let input = "3".as_bytes();
+        let (mut client, output, _receiver) =
+            get_test_client_and_output(input, OnlineClient { id: 1 }).await;
+        client.set_output_style(OutputStyle::Terse);
 
-        client.get_move().await.unwrap();
-        assert_client_output(output, "Input a number between 1 and 9 to make your move:\nThat is not a number, please try again.\nInput a number between 1 and 9 to make your move:\n")
+        client.handle_player_turn_event(1).await.unwrap();
+        assert_client_output(output, "Your move.\nMove (1-9):\n");
     }
 
     #[tokio::test]
-    async fn client_handles_game_started_event_for_local_client() {
-        let (client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+    async fn client_highlights_cells_changed_since_previous_board() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_color(true);
 
-        client.handle_game_started_event().await;
-        assert_client_output(output, "Lets begin.\n")
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![None, None, None, None, Some(1), None, None, None, None],
+            )
+            .await
+            .unwrap();
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![Some(2), None, None, None, Some(1), None, None, None, None],
+            )
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "_________\n  |   |  \n  | \x1b[36mX\x1b[39m |  \n  |   |  \n_________\n\n\
+            _________\n\x1b[7m\x1b[33mO\x1b[39m\x1b[27m |   |  \n  | \x1b[36mX\x1b[39m |  \n  |   |  \n_________\n\n",
+        )
     }
 
     #[tokio::test]
-    async fn client_handles_game_started_event_for_online_client() {
-        let (client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+    async fn client_does_not_highlight_changed_cells_without_color() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
 
-        client.handle_game_started_event().await;
-        assert_client_output(output, "All players connected, lets begin.\n")
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![None, None, None, None, Some(1), None, None, None, None],
+            )
+            .await
+            .unwrap();
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![Some(2), None, None, None, Some(1), None, None, None, None],
+            )
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "_________\n  |   |  \n  | X |  \n  |   |  \n_________\n\n\
+            _________\nO |   |  \n  | X |  \n  |   |  \n_________\n\n",
+        )
     }
 
     #[tokio::test]
-    async fn client_handles_player_turn_event_for_local_client() {
-        let input = "2".as_bytes();
-        let (mut client, output, mut receiver) =
-            get_test_client_and_output(input, LocalClient {}).await;
+    async fn client_highlights_own_turn_and_errors_with_color() {
+        let (mut client, output, _receiver) =
+            get_test_client_and_output("3".as_bytes(), OnlineClient { id: 1 }).await;
+        client.set_color(true);
 
         client.handle_player_turn_event(1).await.unwrap();
+        client
+            .handle_error_occurred_event(Error::CellOccupied)
+            .await
+            .unwrap();
         assert_client_output(
             output,
-            "Player X's turn!\nInput a number between 1 and 9 to make your move:\n",
+            "\x1b[1mIt's your turn!\x1b[22m\n\
+            Input a number between 1 and 9 to make your move:\n\
+            \x1b[31mError: This cell is already occupied.\x1b[39m\n",
         );
-
-        let event = receiver.recv().await;
-        assert!(matches!(
-            event,
-            Some(GameClientEvent::DispatchToServer { .. })
-        ))
     }
 
     #[tokio::test]
-    async fn client_handles_player_turn_event_for_online_client_current_player() {
-        let input = "3".as_bytes();
-        let (mut client, output, mut receiver) =
-            get_test_client_and_output(input, OnlineClient { id: 1 }).await;
+    async fn client_lists_opponent_moves_but_not_own_in_terse_output() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        let mut board_cells = vec![None; 9];
+        client
+            .handle_board_updated_event(BoardConfig::default(), board_cells.clone())
+            .await
+            .unwrap();
+        board_cells[4] = Some(1);
+        client
+            .handle_board_updated_event(BoardConfig::default(), board_cells.clone())
+            .await
+            .unwrap();
+        board_cells[0] = Some(2);
+        client
+            .handle_board_updated_event(BoardConfig::default(), board_cells)
+            .await
+            .unwrap();
+        assert_client_output(output, "123/456/789\n123/4X6/789\nO23/4X6/789\nO:1\n")
+    }
+
+    #[tokio::test]
+    async fn client_describes_board_for_screen_reader_in_any_style() {
+        for output_style in [OutputStyle::Standard, OutputStyle::Terse] {
+            let (mut client, output, _) =
+                get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+            client.set_output_style(output_style);
+            client.set_color(true);
+            client.set_screen_reader(true);
+
+            let mut board_cells = vec![None; 9];
+            board_cells[4] = Some(1);
+            client
+                .handle_board_updated_event(BoardConfig::default(), board_cells.clone())
+                .await
+                .unwrap();
+            board_cells[2] = Some(2);
+            client
+                .handle_board_updated_event(BoardConfig::default(), board_cells)
+                .await
+                .unwrap();
+            assert_client_output(
+                output,
+                "Row 1: empty, empty, empty.\nRow 2: empty, X, empty.\nRow 3: empty, empty, empty.\n\
+                O took cell 3.\n\
+                Row 1: empty, empty, O.\nRow 2: empty, X, empty.\nRow 3: empty, empty, empty.\n",
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn client_asks_user_to_allow_undo() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(b"y\n", OnlineClient { id: 2 }).await;
+
+        client
+            .handle_event(ServerEvent::UndoRequested {
+                player_id: PLAYER_ONE_ID,
+            })
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "Player X asks to take back their last move, do you allow it y/N?\n",
+        );
+
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<ClientEvent>(event),
+                UndoAnswered {
+                    player_id: PLAYER_TWO_ID,
+                    approved: true
+                }
+            ),
+            _ => panic!("Expected the answer to be sent to the server"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_announces_first_player_chosen_at_random() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client
+            .handle_event(ServerEvent::FirstPlayerChosen {
+                player_id: PLAYER_TWO_ID,
+            })
+            .await
+            .unwrap();
+
+        assert_client_output(output, "A coin has been tossed, player O goes first!\n");
+    }
+
+    #[tokio::test]
+    async fn client_sends_suspend_request_instead_of_move() {
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(b"SAVE\n", OnlineClient { id: 1 }).await;
+
+        client.make_player_move(PLAYER_ONE_ID).await.unwrap();
+
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<ClientEvent>(event),
+                SuspendRequested {
+                    player_id: PLAYER_ONE_ID
+                }
+            ),
+            _ => panic!("Expected the request to be sent to the server"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_sends_undo_request_instead_of_move() {
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(b"undo\n", OnlineClient { id: 1 }).await;
+
+        client.make_player_move(PLAYER_ONE_ID).await.unwrap();
+
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<ClientEvent>(event),
+                UndoRequested {
+                    player_id: PLAYER_ONE_ID
+                }
+            ),
+            _ => panic!("Expected the undo request to be sent to the server"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_sends_resignation_or_draw_offer_instead_of_move() {
+        for (input, expected) in [
+            (
+                &b"/resign\n"[..],
+                game::ClientEvent::Resign {
+                    player_id: PLAYER_ONE_ID,
+                },
+            ),
+            (
+                &b"/DRAW\n"[..],
+                game::ClientEvent::DrawOffer {
+                    player_id: PLAYER_ONE_ID,
+                },
+            ),
+        ] {
+            let (mut client, _, mut receiver) =
+                get_test_client_and_output(input, OnlineClient { id: 1 }).await;
+
+            client.make_player_move(PLAYER_ONE_ID).await.unwrap();
+
+            match receiver.recv().await {
+                Some(GameClientEvent::DispatchToServer { event }) => {
+                    assert_eq!(
+                        game::deserialize_event::<game::ClientEvent>(event),
+                        expected
+                    )
+                }
+                _ => panic!("Expected the event to be sent to the server"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn client_asks_user_to_accept_draw() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(b"yes\n", OnlineClient { id: 2 }).await;
+
+        client
+            .handle_event(ServerEvent::DrawOffered {
+                player_id: PLAYER_ONE_ID,
+            })
+            .await
+            .unwrap();
+        assert_client_output(output, "Player X offers a draw, do you accept it y/N?\n");
+
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<game::ClientEvent>(event),
+                game::ClientEvent::DrawResponse {
+                    player_id: PLAYER_TWO_ID,
+                    accepted: true
+                }
+            ),
+            _ => panic!("Expected the answer to be sent to the server"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_sends_history_request_instead_of_move() {
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(b"/history\n", LocalClient {}).await;
+
+        client.make_player_move(PLAYER_TWO_ID).await.unwrap();
+
+        match receiver.recv().await {
+            Some(GameClientEvent::DispatchToServer { event }) => assert_eq!(
+                game::deserialize_event::<ClientEvent>(event),
+                HistoryRequested {
+                    player_id: PLAYER_TWO_ID
+                }
+            ),
+            _ => panic!("Expected the history request to be sent to the server"),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_carries_out_commands_before_asking_for_move_again() {
+        let (mut client, output, _) =
+            get_test_client_and_output(b"/board\n/castle\n/help\n3\n", LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+
+        assert!(matches!(
+            client.get_move().await.unwrap(),
+            PlayerInput::Move {
+                cell: 3,
+                mark: None
+            }
+        ));
+        assert_client_output(
+            output,
+            "Move (1-9):\n\
+             123/456/789\n\
+             Move (1-9):\n\
+             Unknown command, see /help.\n\
+             Move (1-9):\n\
+             Commands:\n\
+             /help: list the commands you can enter.\n\
+             /board: show the board again.\n\
+             /quit: leave the game.\n\
+             /undo: ask to take back your last move.\n\
+             /save: save the game to carry on later.\n\
+             /history: see the moves so far.\n\
+             /draw: offer a draw.\n\
+             /resign: give up the game.\n\
+             Move (1-9):\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_leaves_game_when_user_quits() {
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(b"/quit\n", OnlineClient { id: 1 }).await;
+
+        client.make_player_move(PLAYER_ONE_ID).await.unwrap();
+
+        assert_eq!(receiver.recv().await, Some(GameClientEvent::Quit));
+    }
+
+    #[tokio::test]
+    async fn client_lists_moves_so_far() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_event(ServerEvent::History { moves: vec![] })
+            .await
+            .unwrap();
+        client
+            .handle_event(ServerEvent::History {
+                moves: vec![
+                    MoveRecord {
+                        player_id: PLAYER_ONE_ID,
+                        cell: 5,
+                        made_at: Duration::from_millis(2500),
+                    },
+                    MoveRecord {
+                        player_id: PLAYER_TWO_ID,
+                        cell: 1,
+                        made_at: Duration::from_secs(4),
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "No moves have been made yet.\n\
+             Moves so far:\n\
+             1. Player X took cell 5 after 2.5 seconds.\n\
+             2. Player O took cell 1 after 4.0 seconds.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_fails_rather_than_waiting_when_input_closed() {
+        let (mut client, _output, _receiver) =
+            get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        let result = client.handle_player_turn_event(1).await;
+        assert!(matches!(
+            result,
+            Err(GameClientError::User(error)) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_fails_to_send_move_once_client_stopped() {
+        let (mut client, _output, receiver) =
+            get_test_client_and_output("5\n".as_bytes(), OnlineClient { id: 1 }).await;
+        drop(receiver);
+
+        let result = client.handle_player_turn_event(1).await;
+        assert!(matches!(result, Err(GameClientError::ClientStopped)));
+    }
+
+    #[tokio::test]
+    async fn client_handles_game_over_event_for_draw() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(&[], LocalClient {}).await;
+
+        client.handle_game_over_event(Outcome::Draw).await.unwrap();
+        assert_client_output(output, "Game over! There was a draw!\n");
+
+        let event = receiver.recv().await;
+        assert!(matches!(event, Some(GameClientEvent::GameOver)))
+    }
+
+    #[tokio::test]
+    async fn client_handles_game_over_event_for_win() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_game_over_event(Outcome::WinnerFound { player_id: 1 })
+            .await
+            .unwrap();
+        assert_client_output(output, "Game over! Player X won!\n");
+
+        let event = receiver.recv().await;
+        assert!(matches!(event, Some(GameClientEvent::GameOver)))
+    }
+
+    #[tokio::test]
+    async fn client_handles_error_occurred_event() {
+        let (client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_error_occurred_event(Error::CellOccupied)
+            .await
+            .unwrap();
+        assert_client_output(output, "Error: This cell is already occupied.\n")
+    }
+
+    #[tokio::test]
+    async fn client_handles_turn_timed_out_event() {
+        let (client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client
+            .handle_turn_timed_out_event(2, TimeoutAction::SkipTurn)
+            .await
+            .unwrap();
+        assert_client_output(output, "Player O ran out of time and loses their turn!\n")
+    }
+
+    #[tokio::test]
+    async fn server_skips_turn_of_timed_out_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::SkipTurn)
+            .await;
+        assert_eq!(server.current_player, PLAYER_TWO_ID);
+
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::DispatchToClient { .. })
+        ));
+        assert!(matches!(
+            receiver.recv().await,
+            Some(GameServerEvent::TurnStarted {
+                player_id: PLAYER_TWO_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_forfeits_game_of_timed_out_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(10);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        server
+            .handle_turn_timed_out(PLAYER_ONE_ID, TimeoutAction::Forfeit)
+            .await;
+
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+        match &events[1] {
+            GameServerEvent::DispatchToClient { event, .. } => assert!(matches!(
+                game::deserialize_event(event.clone()),
+                ServerEvent::GameOver {
+                    outcome: Outcome::WinnerFound {
+                        player_id: PLAYER_TWO_ID
+                    }
+                }
+            )),
+            _ => panic!("Expected game over event to be dispatched"),
+        }
+        assert!(matches!(
+            events[2],
+            GameServerEvent::GameOver {
+                winner: Some(PLAYER_TWO_ID)
+            }
+        ));
+    }
+
+    async fn make_invalid_moves(server: &mut TicTacToeServer, player_id: u8, count: u8) {
+        for _ in 0..count {
+            server
+                .handle_event(AnyClientEvent::Game(MoveMade {
+                    player_id,
+                    move_index: 0,
+                    mark: None,
+                }))
+                .await;
+        }
+    }
+
+    fn drain_dispatched_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<ServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            if let GameServerEvent::DispatchToClient { event, .. } = event {
+                events.push(game::deserialize_event(event));
+            }
+        }
+
+        events
+    }
+
+    #[test]
+    fn largest_board_fits_within_event_length_limit() {
+        let board_config = BoardConfig {
+            size: MAX_BOARD_SIZE,
+            win_length: MAX_BOARD_SIZE,
+            ..BoardConfig::DEFAULT
+        };
+        let event = OutgoingEvent::Game {
+            event: game::serialize_event(ServerEvent::BoardUpdated {
+                board_config,
+                board_cells: vec![None; board_config.cell_count()],
+            }),
+        };
+
+        for codec in SUPPORTED_CODECS {
+            assert!(codec.encode(&event).unwrap().len() <= connection::MAX_EVENT_LENGTH);
+        }
+    }
+
+    #[tokio::test]
+    async fn server_counts_each_invalid_move_before_final_one() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        make_invalid_moves(&mut server, PLAYER_ONE_ID, RejectedMoves::LIMIT - 1).await;
+
+        let mut attempts = Vec::new();
+        let mut last_event = None;
+        while let Ok(event) = receiver.try_recv() {
+            match event {
+                GameServerEvent::MoveRejected {
+                    player_id: PLAYER_ONE_ID,
+                    attempts_remaining,
+                } => attempts.push(attempts_remaining),
+                GameServerEvent::DispatchToClient { event, .. } => {
+                    last_event = Some(game::deserialize_event(event))
+                }
+                event => panic!("Unexpected event {:?}", event),
+            }
+        }
+        assert_eq!(attempts, [4, 3, 2, 1]);
+        assert!(matches!(
+            last_event,
+            Some(ServerEvent::PlayerTurn {
+                player_id: PLAYER_ONE_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_forfeits_game_of_player_exceeding_invalid_moves() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        make_invalid_moves(&mut server, PLAYER_ONE_ID, RejectedMoves::LIMIT).await;
+
+        let events = drain_dispatched_events(&mut receiver);
+        assert!(matches!(
+            events[..],
+            [
+                ..,
+                ServerEvent::ErrorOccurred { .. },
+                ServerEvent::GameOver {
+                    outcome: Outcome::WinnerFound {
+                        player_id: PLAYER_TWO_ID
+                    }
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ignores_moves_arriving_after_forfeit() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+        make_invalid_moves(&mut server, PLAYER_ONE_ID, RejectedMoves::LIMIT).await;
+        drain_dispatched_events(&mut receiver);
+
+        make_invalid_moves(&mut server, PLAYER_ONE_ID, 1).await;
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn server_restored_from_saved_state_carries_on_game() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+        for (player_id, move_index) in [(PLAYER_ONE_ID, 5), (PLAYER_TWO_ID, 1)] {
+            send_client_event(
+                &mut server,
+                MoveMade {
+                    player_id,
+                    move_index,
+                    mark: None,
+                },
+            )
+            .await;
+        }
+        let state = server.save_state().unwrap();
+
+        let (sender, mut restored_receiver) = tokio::sync::mpsc::channel(20);
+        let restored = TicTacToe::default().restore_server(sender, &state).unwrap();
+        restored.begin().await;
+
+        drain_dispatched_events(&mut receiver);
+        let events = drain_dispatched_events(&mut restored_receiver);
+        assert!(matches!(
+            &events[..],
+            [
+                ServerEvent::BoardUpdated { board_cells, .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ] if board_cells[4] == Some(PLAYER_ONE_ID) && board_cells[0] == Some(PLAYER_TWO_ID)
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_restored_from_saved_wild_game_keeps_marks_placed() {
+        let board_config = BoardConfig {
+            variant: Variant::Wild,
+            ..BoardConfig::DEFAULT
+        };
+        let (sender, _receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, board_config);
+        send_client_event(
+            &mut server,
+            MoveMade {
+                player_id: PLAYER_ONE_ID,
+                move_index: 5,
+                mark: Some(PLAYER_TWO_ID),
+            },
+        )
+        .await;
+        let state = server.save_state().unwrap();
+
+        let (sender, mut restored_receiver) = tokio::sync::mpsc::channel(20);
+        let restored = TicTacToe::default().restore_server(sender, &state).unwrap();
+        restored.begin().await;
+
+        assert!(matches!(
+            &drain_dispatched_events(&mut restored_receiver)[..],
+            [ServerEvent::BoardUpdated { board_cells, .. }, ..]
+                if board_cells[4] == Some(PLAYER_TWO_ID)
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_rejects_opponents_mark_outside_wild_variant() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        send_client_event(
+            &mut server,
+            MoveMade {
+                player_id: PLAYER_ONE_ID,
+                move_index: 5,
+                mark: Some(PLAYER_TWO_ID),
+            },
+        )
+        .await;
+
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::MarkNotAllowed
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[test]
+    fn server_cannot_be_restored_from_finished_game() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(20);
+        let state = serde_json::json!({
+            "board_config": BoardConfig::default(),
+            "moves": [[1, 1], [2, 4], [1, 2], [2, 5], [1, 3]],
+            "current_player": PLAYER_TWO_ID,
+        });
+
+        assert!(TicTacToe::default()
+            .restore_server(sender, &state)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn server_forwards_suspend_request_of_current_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        send_client_event(
+            &mut server,
+            SuspendRequested {
+                player_id: PLAYER_TWO_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [ServerEvent::ErrorOccurred {
+                error: Error::UnexpectedPlayer
+            }]
+        ));
+
+        send_client_event(
+            &mut server,
+            SuspendRequested {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(GameServerEvent::SuspendRequested {
+                player_id: PLAYER_ONE_ID
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_begins_with_turn_of_chosen_first_player() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+        server.set_first_player(FirstPlayer::PlayerTwo);
+
+        server.begin().await;
+
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_TWO_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_announces_first_player_chosen_at_random() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+        server.set_first_player(FirstPlayer::Random);
+
+        server.begin().await;
+
+        let events = drain_dispatched_events(&mut receiver);
+        match events[..] {
+            [ServerEvent::FirstPlayerChosen { player_id }, ServerEvent::BoardUpdated { .. }, ServerEvent::PlayerTurn {
+                player_id: turn_player_id,
+            }] => assert_eq!(player_id, turn_player_id),
+            _ => panic!("Unexpected events: {:?}", events),
+        }
+    }
+
+    #[test]
+    fn server_given_same_seed_chooses_same_first_player() {
+        let first_players: Vec<u8> = (0..2)
+            .map(|_| {
+                let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+                let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+                server.set_rng(GameRng::from_seed(3));
+                server.set_first_player(FirstPlayer::Random);
+
+                server.current_player
+            })
+            .collect();
+
+        assert_eq!(first_players[0], first_players[1]);
+    }
+
+    async fn send_client_event(server: &mut TicTacToeServer, event: ClientEvent) {
+        server.handle_event(AnyClientEvent::Game(event)).await;
+    }
+
+    async fn send_common_event(server: &mut TicTacToeServer, event: game::ClientEvent) {
+        server.handle_event(AnyClientEvent::Common(event)).await;
+    }
+
+    /// Sets up a game where each player has made a move, and player one has asked to take back
+    /// theirs.
+    async fn request_undo_after_each_player_moves(
+        server: &mut TicTacToeServer,
+        receiver: &mut Receiver<GameServerEvent>,
+    ) -> Vec<ServerEvent> {
+        for (player_id, move_index) in [(PLAYER_ONE_ID, 5), (PLAYER_TWO_ID, 1)] {
+            send_client_event(
+                server,
+                MoveMade {
+                    player_id,
+                    move_index,
+                    mark: None,
+                },
+            )
+            .await;
+        }
+        drain_dispatched_events(receiver);
+
+        send_client_event(
+            server,
+            UndoRequested {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+
+        drain_dispatched_events(receiver)
+    }
+
+    #[tokio::test]
+    async fn server_takes_back_move_once_opponent_allows_it() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        let events = request_undo_after_each_player_moves(&mut server, &mut receiver).await;
+        assert!(matches!(
+            events[..],
+            [ServerEvent::UndoRequested {
+                player_id: PLAYER_ONE_ID
+            }]
+        ));
+
+        send_client_event(
+            &mut server,
+            UndoAnswered {
+                player_id: PLAYER_TWO_ID,
+                approved: true,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::MoveTakenBack {
+                    player_id: PLAYER_ONE_ID
+                },
+                ServerEvent::BoardUpdated { .. },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+        assert_eq!(server.board.get_cell_occupiers(), vec![None; 9]);
+    }
+
+    #[tokio::test]
+    async fn server_gives_turn_back_when_opponent_declines_undo() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+        request_undo_after_each_player_moves(&mut server, &mut receiver).await;
+
+        send_client_event(
+            &mut server,
+            UndoAnswered {
+                player_id: PLAYER_TWO_ID,
+                approved: false,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::UndoDeclined {
+                    player_id: PLAYER_ONE_ID
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+        assert_eq!(server.board.get_cell_occupiers()[4], Some(PLAYER_ONE_ID));
+
+        // The answer cannot be changed once given
+        send_client_event(
+            &mut server,
+            UndoAnswered {
+                player_id: PLAYER_TWO_ID,
+                approved: true,
+            },
+        )
+        .await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn server_gives_game_to_opponent_of_player_who_resigns() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        send_common_event(
+            &mut server,
+            game::ClientEvent::Resign {
+                player_id: PLAYER_TWO_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::Resigned {
+                    player_id: PLAYER_TWO_ID
+                },
+                ServerEvent::GameOver {
+                    outcome: Outcome::WinnerFound {
+                        player_id: PLAYER_ONE_ID
+                    }
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ends_game_in_draw_once_opponent_accepts_offer() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawOffer {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [ServerEvent::DrawOffered {
+                player_id: PLAYER_ONE_ID
+            }]
+        ));
+
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawResponse {
+                player_id: PLAYER_TWO_ID,
+                accepted: true,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [ServerEvent::GameOver {
+                outcome: Outcome::Draw
+            }]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_gives_turn_back_when_opponent_declines_draw() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        // Only the current player may offer a draw
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawOffer {
+                player_id: PLAYER_TWO_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [ServerEvent::ErrorOccurred {
+                error: Error::UnexpectedPlayer
+            }]
+        ));
+
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawOffer {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+        drain_dispatched_events(&mut receiver);
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawResponse {
+                player_id: PLAYER_TWO_ID,
+                accepted: false,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::DrawDeclined {
+                    player_id: PLAYER_ONE_ID
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+
+        // The answer cannot be changed once given
+        send_common_event(
+            &mut server,
+            game::ClientEvent::DrawResponse {
+                player_id: PLAYER_TWO_ID,
+                accepted: true,
+            },
+        )
+        .await;
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn server_sends_moves_so_far_to_player_who_asks() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        tokio::time::advance(Duration::from_secs(3)).await;
+        request_undo_after_each_player_moves(&mut server, &mut receiver).await;
+        send_client_event(
+            &mut server,
+            HistoryRequested {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+        match &drain_dispatched_events(&mut receiver)[..] {
+            [ServerEvent::History { moves }, ServerEvent::PlayerTurn {
+                player_id: PLAYER_ONE_ID,
+            }] => assert_eq!(
+                moves,
+                &[
+                    MoveRecord {
+                        player_id: PLAYER_ONE_ID,
+                        cell: 5,
+                        made_at: Duration::from_secs(3),
+                    },
+                    MoveRecord {
+                        player_id: PLAYER_TWO_ID,
+                        cell: 1,
+                        made_at: Duration::from_secs(3),
+                    },
+                ]
+            ),
+            events => panic!("Unexpected events: {:?}", events),
+        }
+
+        // Moves taken back are no longer part of the history
+        send_client_event(
+            &mut server,
+            UndoAnswered {
+                player_id: PLAYER_TWO_ID,
+                approved: true,
+            },
+        )
+        .await;
+        drain_dispatched_events(&mut receiver);
+        send_client_event(
+            &mut server,
+            HistoryRequested {
+                player_id: PLAYER_TWO_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            &drain_dispatched_events(&mut receiver)[..],
+            [ServerEvent::History { moves }] if moves.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_refuses_undo_before_player_has_moved() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        send_client_event(
+            &mut server,
+            UndoRequested {
+                player_id: PLAYER_ONE_ID,
+            },
+        )
+        .await;
+        assert!(matches!(
+            drain_dispatched_events(&mut receiver)[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::NoMoveToTakeBack
+                },
+                ServerEvent::PlayerTurn {
+                    player_id: PLAYER_ONE_ID
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ignores_invalid_moves_from_other_player_towards_limit() {
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(20);
+        let mut server = TicTacToeServer::new(sender, BoardConfig::default());
+
+        make_invalid_moves(&mut server, PLAYER_TWO_ID, RejectedMoves::LIMIT).await;
+
+        let events = drain_dispatched_events(&mut receiver);
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, ServerEvent::GameOver { .. })));
+        assert_eq!(server.rejected_moves.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn client_get_move_handles_errors_until_valid_move_provided() {
+        let input = "not a number\n1".as_bytes();
+
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+
+        client.get_move().await.unwrap();
+        assert_client_output(output, "Input a number between 1 and 9 to make your move:\nThat is not a number, please try again.\nInput a number between 1 and 9 to make your move:\n")
+    }
+
+    #[tokio::test]
+    async fn client_handles_game_started_event_for_local_client() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+
+        client.handle_game_started_event(Vec::new()).await.unwrap();
+        assert_client_output(
+            output,
+            "Lets begin. Enter \"/help\" on your turn to list the commands you can enter in place of a move.\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_game_started_event_for_online_client() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+
+        client.handle_game_started_event(Vec::new()).await.unwrap();
+        assert_client_output(output, "All players connected, lets begin. Enter \"/help\" on your turn to list the commands you can enter in place of a move.\n")
+    }
+
+    #[tokio::test]
+    async fn client_lays_out_board_sent_as_game_starts() {
+        let input = "16".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        let board_config = BoardConfig {
+            size: 4,
+            win_length: 3,
+            ..BoardConfig::DEFAULT
+        };
+
+        client
+            .handle_game_started_event(game::serialize_config(board_config))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            client.get_move().await.unwrap(),
+            PlayerInput::Move {
+                cell: 16,
+                mark: None
+            }
+        ));
+        assert_client_output(
+            output,
+            "Lets begin. Enter \"/help\" on your turn to list the commands you can enter in place of a move.\nInput a number between 1 and 16 to make your move:\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_describes_variant_as_game_starts() {
+        let (mut client, output, _) = get_test_client_and_output(&[], LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+        let board_config = BoardConfig {
+            variant: Variant::Misere,
+            ..BoardConfig::DEFAULT
+        };
+
+        client
+            .handle_game_started_event(game::serialize_config(board_config))
+            .await
+            .unwrap();
+
+        assert_client_output(output, "Started.\nMisère: completing a line loses.\n")
+    }
+
+    #[tokio::test]
+    async fn client_reads_mark_following_cell_in_wild_variant() {
+        let input = "5 o\n6 Z\n6\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+        client.board_config.variant = Variant::Wild;
+
+        assert!(matches!(
+            client.get_move().await.unwrap(),
+            PlayerInput::Move {
+                cell: 5,
+                mark: Some(PLAYER_TWO_ID)
+            }
+        ));
+        assert!(matches!(
+            client.get_move().await.unwrap(),
+            PlayerInput::Move {
+                cell: 6,
+                mark: None
+            }
+        ));
+        assert_client_output(
+            output,
+            "Move (1-9, then X/O):\nMove (1-9, then X/O):\nUnknown mark.\nMove (1-9, then X/O):\n",
+        )
+    }
+
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_local_client() {
+        let input = "2".as_bytes();
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(input, LocalClient {}).await;
+
+        client.handle_player_turn_event(1).await.unwrap();
+        assert_client_output(
+            output,
+            "Player X's turn!\nInput a number between 1 and 9 to make your move:\n",
+        );
+
+        let event = receiver.recv().await;
+        assert!(matches!(
+            event,
+            Some(GameClientEvent::DispatchToServer { .. })
+        ))
+    }
+
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_online_client_current_player() {
+        let input = "3".as_bytes();
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(input, OnlineClient { id: 1 }).await;
 
         client.handle_player_turn_event(1).await.unwrap();
         assert_client_output(
@@ -540,6 +2860,47 @@ mod tests {
         ))
     }
 
+    #[tokio::test]
+    async fn client_handles_player_turn_event_for_spectator_client() {
+        let (mut client, output, mut receiver) =
+            get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        client.handle_player_turn_event(2).await.unwrap();
+        assert_client_output(output, "Player O's turn!\n");
+
+        assert_eq!(Err(TryRecvError::Empty), receiver.try_recv());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn client_handles_player_turn_event_for_bot_client() {
+        let bot_client = BotClient {
+            id: 2,
+            move_delay: Duration::from_secs(1),
+        };
+        let (mut client, _, mut receiver) = get_test_client_and_output(&[], bot_client).await;
+        client
+            .handle_board_updated_event(
+                BoardConfig::default(),
+                vec![None, None, None, None, Some(1), None, None, None, None],
+            )
+            .await
+            .unwrap();
+
+        client.handle_player_turn_event(2).await.unwrap();
+
+        let event = receiver.recv().await;
+        assert_eq!(
+            event,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(MoveMade {
+                    player_id: 2,
+                    move_index: 1,
+                    mark: None,
+                })
+            })
+        )
+    }
+
     #[tokio::test]
     async fn client_handles_player_turn_event_for_online_client_other_player() {
         let input = "3".as_bytes();
@@ -1,6 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game;
+use crate::server::{get_alternative_player_id, PLAYER_ONE_ID, PLAYER_TWO_ID};
 use crate::tic_tac_toe::{Error, Outcome};
 
-pub const BOARD_SIZE: usize = 9;
+pub const DEFAULT_BOARD_SIZE: usize = 3;
+pub const MIN_BOARD_SIZE: usize = 3;
+pub const MAX_BOARD_SIZE: usize = 10;
+
+/// The rules deciding who wins once a line is completed.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Variant {
+    /// The player completing a line of their own mark wins.
+    #[default]
+    Standard,
+    /// The player completing a line of their own mark loses.
+    Misere,
+    /// Either player may place either mark, and the player completing a line of either wins.
+    Wild,
+}
+
+/// The width and height of the board, how many cells in a row complete a line, and the rules
+/// played by.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoardConfig {
+    pub size: usize,
+    pub win_length: usize,
+    /// Missing from boards described before there were variants, which were all standard.
+    #[serde(default)]
+    pub variant: Variant,
+}
+
+impl BoardConfig {
+    pub const DEFAULT: BoardConfig = BoardConfig {
+        size: DEFAULT_BOARD_SIZE,
+        win_length: DEFAULT_BOARD_SIZE,
+        variant: Variant::Standard,
+    };
+
+    pub fn cell_count(&self) -> usize {
+        self.size * self.size
+    }
+
+    /// Finds every run of cells that a player could win by occupying, going along rows, down
+    /// columns, and along both diagonals in that order.
+    pub(crate) fn lines(&self) -> Vec<Vec<usize>> {
+        let size = self.size as isize;
+        let length = self.win_length as isize;
+        let directions = [(0, 1), (1, 0), (1, 1), (1, -1)];
+
+        directions
+            .into_iter()
+            .flat_map(|(row_step, column_step)| {
+                (0..size).flat_map(move |row| {
+                    (0..size).filter_map(move |column| {
+                        let end_row = row + row_step * (length - 1);
+                        let end_column = column + column_step * (length - 1);
+                        if end_row >= size || end_column < 0 || end_column >= size {
+                            return None;
+                        }
+
+                        Some(
+                            (0..length)
+                                .map(|step| {
+                                    let row = row + row_step * step;
+                                    let column = column + column_step * step;
+                                    (row * size + column) as usize
+                                })
+                                .collect(),
+                        )
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        BoardConfig::DEFAULT
+    }
+}
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 enum BoardCellState {
@@ -37,33 +117,65 @@ impl BoardCell {
     }
 }
 
+/// The cells of a game of Tic Tac Toe and the moves made on them, which can be played out without
+/// a server to simulate games.
 pub struct Board {
-    cells: [BoardCell; BOARD_SIZE],
+    config: BoardConfig,
+    cells: Vec<BoardCell>,
+    /// Every move made so far as the player ID and cell index, with the latest move last.
+    history: Vec<(u8, usize)>,
 }
 
 impl Board {
-    pub(crate) fn new() -> Board {
+    pub fn new(config: BoardConfig) -> Board {
         Board {
-            cells: [BoardCell::new(); BOARD_SIZE],
+            config,
+            cells: vec![BoardCell::new(); config.cell_count()],
+            history: Vec::new(),
         }
     }
 
-    pub(crate) fn get_cell_occupiers(&self) -> [Option<u8>; BOARD_SIZE] {
-        self.cells.map(|cell| match cell.state {
-            BoardCellState::Empty => None,
-            BoardCellState::Occupied { player_id } => Some(player_id),
-        })
+    pub(crate) fn get_config(&self) -> BoardConfig {
+        self.config
     }
 
-    pub(crate) fn add_move(&mut self, player_id: u8, player_move: usize) -> Result<(), Error> {
-        if player_move == 0 || player_move > BOARD_SIZE {
-            return Err(Error::InvalidCellIndex);
+    pub fn get_cell_occupiers(&self) -> Vec<Option<u8>> {
+        self.cells
+            .iter()
+            .map(|cell| match cell.state {
+                BoardCellState::Empty => None,
+                BoardCellState::Occupied { player_id } => Some(player_id),
+            })
+            .collect()
+    }
+
+    /// Adds a move placing the mark of the player with the ID given by `mark`, which can only be
+    /// another player's in the wild variant.
+    pub(crate) fn add_move(
+        &mut self,
+        player_id: u8,
+        player_move: usize,
+        mark: u8,
+    ) -> Result<(), Error> {
+        if player_move == 0 || player_move > self.cells.len() {
+            return Err(Error::InvalidCellIndex {
+                cell_count: self.cells.len(),
+            });
+        }
+
+        let mark_allowed = match self.config.variant {
+            Variant::Wild => [PLAYER_ONE_ID, PLAYER_TWO_ID].contains(&mark),
+            Variant::Standard | Variant::Misere => mark == player_id,
+        };
+        if !mark_allowed {
+            return Err(Error::MarkNotAllowed);
         }
 
         let cell = &mut self.cells[player_move - 1];
         match cell.state {
             BoardCellState::Empty => {
-                cell.state = BoardCellState::Occupied { player_id };
+                cell.state = BoardCellState::Occupied { player_id: mark };
+                self.history.push((player_id, player_move - 1));
 
                 Ok(())
             }
@@ -71,69 +183,112 @@ impl Board {
         }
     }
 
-    /// Calculates the outcome of the current state of the `Board`
-    ///
-    /// An `Outcome::WinnerFound` is determined if the same player occupies an entire row, column,
-    /// or diagonal. An `Outcome::Draw` is determined if the board if fully occupied with no winners.
+    /// Plays the moves in order as the server would, each being the ID of the player and the cell
+    /// they chose, numbered from 1 as players enter them. As with the server, moves made once the
+    /// game has an outcome are ignored.
     ///
     /// # Returns
     ///
-    /// - `Some(Outcome)` if an outcome is found.
-    /// - `None` if there is no outcome yet (the game is ongoing).
-    pub(crate) fn determine_outcome(&self) -> Option<Outcome> {
-        // If first cell is occupied, check for win in first row, column, and left diagonal
-        if self.cells[0].is_occupied()
-            && ((self.cells[0] == self.cells[1] && self.cells[0] == self.cells[2])
-                || (self.cells[0] == self.cells[3] && self.cells[0] == self.cells[6])
-                || (self.cells[0] == self.cells[4] && self.cells[0] == self.cells[8]))
-        {
-            return Some(Outcome::WinnerFound {
-                player_id: self.cells[0].get_occupying_player_id(),
-            });
-        }
+    /// - `Ok(Option<Outcome>)` containing the outcome after the moves, if there is one yet.
+    /// - `Err(Error)` for the first move that cannot be made, keeping the moves made before it.
+    pub fn apply_moves(&mut self, moves: &[(u8, usize)]) -> Result<Option<Outcome>, Error> {
+        self.apply_marked_moves(moves, &[])
+    }
 
-        // Check for win in second column
-        if self.cells[1].is_occupied()
-            && self.cells[1] == self.cells[4]
-            && self.cells[1] == self.cells[7]
-        {
-            return Some(Outcome::WinnerFound {
-                player_id: self.cells[1].get_occupying_player_id(),
-            });
+    /// Plays the moves in order as `apply_moves` does, each placing the mark at the same position
+    /// in `marks`. Moves without a mark place the player's own.
+    pub(crate) fn apply_marked_moves(
+        &mut self,
+        moves: &[(u8, usize)],
+        marks: &[u8],
+    ) -> Result<Option<Outcome>, Error> {
+        for (index, &(player_id, player_move)) in moves.iter().enumerate() {
+            if self.determine_outcome().is_some() {
+                break;
+            }
+            let mark = marks.get(index).copied().unwrap_or(player_id);
+            self.add_move(player_id, player_move, mark)?;
         }
 
-        // Check for win in third column and right diagonal
-        if self.cells[2].is_occupied()
-            && ((self.cells[2] == self.cells[5] && self.cells[2] == self.cells[8])
-                || (self.cells[2] == self.cells[4] && self.cells[2] == self.cells[6]))
-        {
-            return Some(Outcome::WinnerFound {
-                player_id: self.cells[2].get_occupying_player_id(),
-            });
+        Ok(self.determine_outcome())
+    }
+
+    /// Every move made so far in the form taken by `apply_moves`, with cells numbered from 1.
+    pub(crate) fn get_moves(&self) -> Vec<(u8, usize)> {
+        self.history
+            .iter()
+            .map(|&(player_id, cell)| (player_id, cell + 1))
+            .collect()
+    }
+
+    /// The mark placed by each move made so far, in the same order as `get_moves`.
+    pub(crate) fn get_marks(&self) -> Vec<u8> {
+        self.history
+            .iter()
+            .map(|&(_, cell)| self.cells[cell].get_occupying_player_id())
+            .collect()
+    }
+
+    pub(crate) fn has_moved(&self, player_id: u8) -> bool {
+        self.history.iter().any(|&(id, _)| id == player_id)
+    }
+
+    /// Takes back the last move made by the player, along with every move made since.
+    ///
+    /// # Returns
+    ///
+    /// - `true` if a move was taken back.
+    /// - `false` if the player has not made a move, leaving the board unchanged.
+    pub(crate) fn take_back_move(&mut self, player_id: u8) -> bool {
+        if !self.has_moved(player_id) {
+            return false;
         }
 
-        // Check for win in second row
-        if self.cells[3].is_occupied()
-            && self.cells[3] == self.cells[4]
-            && self.cells[3] == self.cells[5]
-        {
-            return Some(Outcome::WinnerFound {
-                player_id: self.cells[3].get_occupying_player_id(),
-            });
+        while let Some((id, cell)) = self.history.pop() {
+            self.cells[cell].state = BoardCellState::Empty;
+            if id == player_id {
+                break;
+            }
         }
 
-        // Check for win in third row
-        if self.cells[6].is_occupied()
-            && self.cells[6] == self.cells[7]
-            && self.cells[6] == self.cells[8]
-        {
-            return Some(Outcome::WinnerFound {
-                player_id: self.cells[6].get_occupying_player_id(),
-            });
+        true
+    }
+
+    /// Calculates the outcome of the current state of the `Board`
+    ///
+    /// An `Outcome::WinnerFound` is determined if the same mark occupies enough cells in a row,
+    /// column, or diagonal to meet the win length of the board, the winner depending on the
+    /// variant played. An `Outcome::Draw` is determined once every such line holds both marks, so
+    /// no one can complete one even with cells left.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(Outcome)` if an outcome is found.
+    /// - `None` if there is no outcome yet (the game is ongoing).
+    pub fn determine_outcome(&self) -> Option<Outcome> {
+        // Check for a line occupied entirely by one mark
+        let lines = self.config.lines();
+        let winning_line = lines.iter().find(|line| {
+            let first = self.cells[line[0]];
+            first.is_occupied() && line.iter().all(|&cell| self.cells[cell] == first)
+        });
+        if let Some(line) = winning_line {
+            let mark = self.cells[line[0]].get_occupying_player_id();
+            let player_id = match self.config.variant {
+                Variant::Standard => mark,
+                Variant::Misere => get_alternative_player_id(mark),
+                // Only the last move can have completed the line
+                Variant::Wild => self
+                    .history
+                    .last()
+                    .map_or(mark, |&(player_id, _)| player_id),
+            };
+
+            return Some(Outcome::WinnerFound { player_id });
         }
 
         // Check for draw
-        if self.cells.iter().all(|cell| cell.is_occupied()) {
+        if game::is_dead_position(&lines, &self.get_cell_occupiers()) {
             return Some(Outcome::Draw);
         }
 
@@ -143,6 +298,10 @@ impl Board {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
+    use crate::server::{get_alternative_player_id, PLAYER_ONE_ID};
+
     use super::*;
 
     struct TestSetup {
@@ -160,14 +319,14 @@ mod tests {
                 occupied_cell_player2: BoardCell {
                     state: BoardCellState::Occupied { player_id: 2 },
                 },
-                board: Board::new(),
+                board: Board::new(BoardConfig::default()),
             }
         }
     }
 
     #[test]
     fn determine_outcome_empty_board_in_progress() {
-        let board = Board::new();
+        let board = Board::new(BoardConfig::default());
 
         let outcome = board.determine_outcome();
         assert_eq!(None, outcome);
@@ -277,7 +436,7 @@ mod tests {
     #[test]
     fn recalculate_state_draw() {
         let mut setup = TestSetup::new();
-        setup.board.cells = [
+        setup.board.cells = vec![
             setup.occupied_cell_player1,
             setup.occupied_cell_player2,
             setup.occupied_cell_player1,
@@ -292,4 +451,270 @@ mod tests {
         let outcome = setup.board.determine_outcome();
         assert_eq!(Some(Outcome::Draw), outcome);
     }
+
+    #[test]
+    fn recalculate_state_draw_before_board_is_full() {
+        let mut setup = TestSetup::new();
+        let player1 = setup.occupied_cell_player1;
+        let player2 = setup.occupied_cell_player2;
+        for (index, cell) in [
+            player1, player2, player1, player1, player2, player2, player2, player1,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            setup.board.cells[index] = cell;
+        }
+
+        let outcome = setup.board.determine_outcome();
+        assert_eq!(Some(Outcome::Draw), outcome);
+    }
+
+    #[test]
+    fn recalculate_state_no_draw_while_a_line_can_be_completed() {
+        let mut setup = TestSetup::new();
+        setup.board.cells[0] = setup.occupied_cell_player1;
+        setup.board.cells[1] = setup.occupied_cell_player2;
+        setup.board.cells[4] = setup.occupied_cell_player1;
+        setup.board.cells[8] = setup.occupied_cell_player2;
+
+        let outcome = setup.board.determine_outcome();
+        assert_eq!(None, outcome);
+    }
+
+    #[test]
+    fn lines_cover_rows_columns_and_diagonals_of_default_board() {
+        let lines = BoardConfig::default().lines();
+
+        assert_eq!(
+            lines,
+            vec![
+                vec![0, 1, 2],
+                vec![3, 4, 5],
+                vec![6, 7, 8],
+                vec![0, 3, 6],
+                vec![1, 4, 7],
+                vec![2, 5, 8],
+                vec![0, 4, 8],
+                vec![2, 4, 6],
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_fit_within_larger_board() {
+        let config = BoardConfig {
+            size: 4,
+            win_length: 3,
+            ..BoardConfig::DEFAULT
+        };
+
+        // Two runs in each of the four rows and columns, and two in each of the two diagonal
+        // directions starting from each of the first two rows
+        assert_eq!(config.lines().len(), 8 + 8 + 4 + 4);
+    }
+
+    #[test]
+    fn recalculate_state_win_with_shorter_win_length_than_board() {
+        let mut setup = TestSetup::new();
+        setup.board = Board::new(BoardConfig {
+            size: 5,
+            win_length: 4,
+            ..BoardConfig::DEFAULT
+        });
+        setup.board.cells[6] = setup.occupied_cell_player2;
+        setup.board.cells[12] = setup.occupied_cell_player2;
+        setup.board.cells[18] = setup.occupied_cell_player2;
+        setup.board.cells[24] = setup.occupied_cell_player2;
+
+        let outcome = setup.board.determine_outcome();
+        assert_eq!(Some(Outcome::WinnerFound { player_id: 2 }), outcome);
+    }
+
+    #[test]
+    fn recalculate_state_in_progress_when_line_is_too_short() {
+        let mut setup = TestSetup::new();
+        setup.board = Board::new(BoardConfig {
+            size: 5,
+            win_length: 4,
+            ..BoardConfig::DEFAULT
+        });
+        setup.board.cells[0] = setup.occupied_cell_player1;
+        setup.board.cells[1] = setup.occupied_cell_player1;
+        setup.board.cells[2] = setup.occupied_cell_player1;
+
+        let outcome = setup.board.determine_outcome();
+        assert_eq!(None, outcome);
+    }
+
+    #[test]
+    fn completing_own_line_loses_in_misere_variant() {
+        let mut board = Board::new(BoardConfig {
+            variant: Variant::Misere,
+            ..BoardConfig::DEFAULT
+        });
+
+        let outcome = board.apply_moves(&[(1, 1), (2, 4), (1, 2), (2, 5), (1, 3)]);
+        assert_eq!(
+            outcome.unwrap(),
+            Some(Outcome::WinnerFound { player_id: 2 })
+        );
+    }
+
+    #[test]
+    fn completing_line_of_either_mark_wins_in_wild_variant() {
+        let mut board = Board::new(BoardConfig {
+            variant: Variant::Wild,
+            ..BoardConfig::DEFAULT
+        });
+
+        // Player two completes the top row with the mark of player one
+        let outcome = board.apply_marked_moves(&[(1, 1), (2, 2), (1, 5), (2, 3)], &[1, 1, 2, 1]);
+        assert_eq!(
+            outcome.unwrap(),
+            Some(Outcome::WinnerFound { player_id: 2 })
+        );
+    }
+
+    #[test]
+    fn opponents_mark_rejected_outside_wild_variant() {
+        let mut board = Board::new(BoardConfig::default());
+
+        assert!(matches!(
+            board.add_move(1, 1, 2),
+            Err(Error::MarkNotAllowed)
+        ));
+        assert!(matches!(board.add_move(1, 1, 1), Ok(())));
+    }
+
+    #[test]
+    fn add_move_rejects_cell_beyond_board() {
+        let mut board = Board::new(BoardConfig {
+            size: 4,
+            win_length: 4,
+            ..BoardConfig::DEFAULT
+        });
+
+        assert!(board.add_move(1, 16, 1).is_ok());
+        assert!(matches!(
+            board.add_move(1, 17, 1),
+            Err(Error::InvalidCellIndex { cell_count: 16 })
+        ));
+    }
+
+    #[test]
+    fn take_back_move_reverts_moves_since_players_last_move() {
+        let mut board = Board::new(BoardConfig::default());
+        board.add_move(1, 5, 1).unwrap();
+        board.add_move(2, 1, 2).unwrap();
+        board.add_move(1, 9, 1).unwrap();
+        board.add_move(2, 3, 2).unwrap();
+
+        assert!(board.take_back_move(1));
+        assert_eq!(
+            board.get_cell_occupiers(),
+            vec![Some(2), None, None, None, Some(1), None, None, None, None]
+        );
+    }
+
+    #[test]
+    fn take_back_move_refused_before_player_has_moved() {
+        let mut board = Board::new(BoardConfig::default());
+        board.add_move(1, 5, 1).unwrap();
+
+        assert!(!board.take_back_move(2));
+        assert_eq!(board.get_cell_occupiers()[4], Some(1));
+    }
+
+    #[test]
+    fn apply_moves_ignores_moves_after_outcome() {
+        let mut board = Board::new(BoardConfig::default());
+
+        let outcome = board
+            .apply_moves(&[(1, 1), (2, 4), (1, 2), (2, 5), (1, 3), (2, 6)])
+            .unwrap();
+        assert_eq!(outcome, Some(Outcome::WinnerFound { player_id: 1 }));
+        assert_eq!(board.get_cell_occupiers()[5], None);
+    }
+
+    #[test]
+    fn apply_moves_stops_at_first_move_that_cannot_be_made() {
+        let mut board = Board::new(BoardConfig::default());
+
+        assert!(matches!(
+            board.apply_moves(&[(1, 1), (2, 1), (1, 2)]),
+            Err(Error::CellOccupied)
+        ));
+        assert_eq!(board.get_cell_occupiers()[..2], [Some(1), None]);
+    }
+
+    /// Every cell of the board in a random order, to be played in turn starting with player one.
+    fn moves_on(config: BoardConfig) -> impl Strategy<Value = Vec<(u8, usize)>> {
+        let cells: Vec<usize> = (1..=config.cell_count()).collect();
+        Just(cells).prop_shuffle().prop_map(|cells| {
+            let mut player_id = PLAYER_ONE_ID;
+            cells
+                .into_iter()
+                .map(|cell| {
+                    let player_move = (player_id, cell);
+                    player_id = get_alternative_player_id(player_id);
+                    player_move
+                })
+                .collect()
+        })
+    }
+
+    /// A board of any size up to 5x5 along with moves filling every one of its cells.
+    fn board_and_moves() -> impl Strategy<Value = (BoardConfig, Vec<(u8, usize)>)> {
+        (MIN_BOARD_SIZE..=5)
+            .prop_flat_map(|size| (Just(size), MIN_BOARD_SIZE..=size))
+            .prop_flat_map(|(size, win_length)| {
+                let config = BoardConfig {
+                    size,
+                    win_length,
+                    ..BoardConfig::DEFAULT
+                };
+                (Just(config), moves_on(config))
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn no_outcome_before_five_moves_on_default_board(
+            moves in moves_on(BoardConfig::default())
+        ) {
+            let mut board = Board::new(BoardConfig::default());
+
+            prop_assert_eq!(board.apply_moves(&moves[..4]).unwrap(), None);
+        }
+
+        #[test]
+        fn winners_cells_form_a_line((config, moves) in board_and_moves()) {
+            let mut board = Board::new(config);
+
+            let outcome = board.apply_moves(&moves).unwrap();
+            prop_assert!(outcome.is_some());
+            if let Some(Outcome::WinnerFound { player_id }) = outcome {
+                let cells = board.get_cell_occupiers();
+                prop_assert!(config
+                    .lines()
+                    .iter()
+                    .any(|line| line.iter().all(|&cell| cells[cell] == Some(player_id))));
+            }
+        }
+
+        #[test]
+        fn outcome_never_changes_once_decided((config, moves) in board_and_moves()) {
+            let mut board = Board::new(config);
+            let mut decided = None;
+
+            for player_move in moves {
+                let outcome = board.apply_moves(&[player_move]).unwrap();
+                if decided.is_some() {
+                    prop_assert_eq!(outcome, decided);
+                }
+                decided = outcome;
+            }
+        }
+    }
 }
@@ -0,0 +1,180 @@
+use crate::server::get_alternative_player_id;
+use crate::tic_tac_toe::{BoardConfig, Variant};
+
+/// Chooses a move for the given player based on the current state of the board, always placing
+/// their own mark.
+///
+/// Moves are prioritised as follows; completing a line, blocking the opponent from completing a
+/// line, taking the centre, taking a corner, and finally taking any remaining cell. In the misère
+/// variant, where completing a line loses, any cell that does not complete one is taken instead.
+///
+/// # Returns
+///
+/// - `Some(usize)` containing the chosen cell, numbered from 1 as a player would enter it.
+/// - `None` if the board is full.
+pub(crate) fn choose_move(
+    board_cells: &[Option<u8>],
+    config: BoardConfig,
+    player_id: u8,
+) -> Option<usize> {
+    let opponent_id = get_alternative_player_id(player_id);
+    let lines = config.lines();
+    let last = config.size - 1;
+    let centre = (config.size / 2) * config.size + config.size / 2;
+    let corners = [0, last, last * config.size, config.cell_count() - 1];
+
+    if config.variant == Variant::Misere {
+        let empty_cells = || (0..board_cells.len()).filter(|&cell| board_cells[cell].is_none());
+        return empty_cells()
+            .find(|&cell| !completes_line(board_cells, &lines, cell, player_id))
+            .or_else(|| empty_cells().next())
+            .map(|cell| cell + 1);
+    }
+
+    find_line_completing_cell(board_cells, &lines, player_id)
+        .or_else(|| find_line_completing_cell(board_cells, &lines, opponent_id))
+        .or_else(|| Some(centre).filter(|&cell| board_cells[cell].is_none()))
+        .or_else(|| {
+            corners
+                .into_iter()
+                .find(|&cell| board_cells[cell].is_none())
+        })
+        .or_else(|| (0..board_cells.len()).find(|&cell| board_cells[cell].is_none()))
+        .map(|cell| cell + 1)
+}
+
+fn find_line_completing_cell(
+    board_cells: &[Option<u8>],
+    lines: &[Vec<usize>],
+    player_id: u8,
+) -> Option<usize> {
+    lines.iter().find_map(|line| {
+        let occupied = line
+            .iter()
+            .filter(|&&cell| board_cells[cell] == Some(player_id))
+            .count();
+        let empty_cell = line.iter().find(|&&cell| board_cells[cell].is_none());
+
+        match empty_cell {
+            Some(&cell) if occupied == line.len() - 1 => Some(cell),
+            _ => None,
+        }
+    })
+}
+
+/// Whether the player occupying the given cell would complete a line.
+fn completes_line(
+    board_cells: &[Option<u8>],
+    lines: &[Vec<usize>],
+    cell: usize,
+    player_id: u8,
+) -> bool {
+    lines.iter().any(|line| {
+        line.contains(&cell)
+            && line
+                .iter()
+                .all(|&other| other == cell || board_cells[other] == Some(player_id))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose_move_completes_own_line() {
+        let board_cells = [
+            Some(1),
+            Some(1),
+            None,
+            Some(2),
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        ];
+
+        assert_eq!(
+            choose_move(&board_cells, BoardConfig::default(), 1),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn choose_move_blocks_opponent_line() {
+        let board_cells = [
+            Some(1),
+            Some(1),
+            None,
+            None,
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        ];
+
+        assert_eq!(
+            choose_move(&board_cells, BoardConfig::default(), 2),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn choose_move_takes_centre_on_empty_board() {
+        assert_eq!(choose_move(&[None; 9], BoardConfig::default(), 1), Some(5));
+    }
+
+    #[test]
+    fn choose_move_takes_corner_when_centre_occupied() {
+        let board_cells = [None, None, None, None, Some(1), None, None, None, None];
+
+        assert_eq!(
+            choose_move(&board_cells, BoardConfig::default(), 2),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn choose_move_avoids_completing_own_line_in_misere() {
+        let config = BoardConfig {
+            variant: Variant::Misere,
+            ..BoardConfig::DEFAULT
+        };
+        let board_cells = [
+            None,
+            Some(1),
+            Some(1),
+            Some(2),
+            Some(2),
+            None,
+            None,
+            None,
+            None,
+        ];
+
+        // Cell 1 would complete the top row
+        assert_eq!(choose_move(&board_cells, config, 1), Some(6));
+    }
+
+    #[test]
+    fn choose_move_returns_none_for_full_board() {
+        assert_eq!(choose_move(&[Some(1); 9], BoardConfig::default(), 2), None);
+    }
+
+    #[test]
+    fn choose_move_completes_own_line_on_larger_board() {
+        let config = BoardConfig {
+            size: 4,
+            win_length: 3,
+            ..BoardConfig::DEFAULT
+        };
+        let mut board_cells = vec![None; config.cell_count()];
+        board_cells[5] = Some(2);
+        board_cells[6] = Some(2);
+        board_cells[0] = Some(1);
+
+        assert_eq!(choose_move(&board_cells, config, 2), Some(5));
+    }
+}
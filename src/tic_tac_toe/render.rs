@@ -0,0 +1,213 @@
+use crate::client::Paint;
+use crate::locale::Locale;
+use crate::server::{PlayerMarks, PLAYER_ONE_ID, PLAYER_TWO_ID};
+use crate::tic_tac_toe::BoardConfig;
+
+/// The board as it is to be shown to the user, along with what is needed to show it.
+pub(crate) struct BoardView<'a> {
+    pub(crate) config: BoardConfig,
+    pub(crate) cells: &'a [Option<u8>],
+    /// The cells taken since the board was last shown that should be pointed out, along with the
+    /// ID of the player taking them.
+    pub(crate) changes: &'a [(usize, u8)],
+    pub(crate) locale: Locale,
+    pub(crate) marks: &'a PlayerMarks,
+    /// Paints the text in the colour given, if colours are in use.
+    pub(crate) paint: &'a dyn Fn(String, Paint) -> String,
+}
+
+impl BoardView<'_> {
+    fn is_changed(&self, index: usize) -> bool {
+        self.changes
+            .iter()
+            .any(|&(changed_index, _)| changed_index == index)
+    }
+}
+
+/// Turns the board into the text written to the user whenever it is updated.
+pub(crate) trait BoardRenderer {
+    fn render(&self, board: &BoardView) -> String;
+}
+
+/// Draws the board as a grid between two rules, painting each player's icons in their colour and
+/// highlighting the cells that have changed.
+pub(crate) struct GridRenderer;
+
+impl BoardRenderer for GridRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        let cell_icons: Vec<String> = board
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(index, &player_id)| {
+                let icon = painted_player_icon(board, player_id);
+                if board.is_changed(index) {
+                    (board.paint)(icon, Paint::Changed)
+                } else {
+                    icon
+                }
+            })
+            .collect();
+        let rows: Vec<String> = cell_icons
+            .chunks(board.config.size)
+            .map(|row| row.join(" | "))
+            .collect();
+        // Measured from the cells rather than the rows, which may contain escape codes
+        let rule = "_".repeat(board.config.size * 4 - 3);
+
+        format!("{}\n{}\n{}\n", rule, rows.join("\n"), rule)
+    }
+}
+
+/// Writes the board on a single line with rows separated by slashes.
+///
+/// Empty cells are shown as their number when every number fits in a single character, so that
+/// no key to the board is needed. Changed cells are listed after the board, as terse output is
+/// kept to plain text.
+pub(crate) struct TerseRenderer;
+
+impl BoardRenderer for TerseRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        let numbered = board.config.cell_count() < 10;
+        let cell_icons: Vec<String> = board
+            .cells
+            .iter()
+            .enumerate()
+            .map(|(index, player_id)| match player_id {
+                Some(id) => String::from(board.marks.get(*id)),
+                None if numbered => (index + 1).to_string(),
+                None => String::from("."),
+            })
+            .collect();
+        let rendered = cell_icons
+            .chunks(board.config.size)
+            .map(|row| row.concat())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        board
+            .changes
+            .iter()
+            .fold(rendered, |output, &(index, player_id)| {
+                format!("{}\n{}:{}", output, board.marks.get(player_id), index + 1)
+            })
+    }
+}
+
+/// Describes the board in words, one row to a line, for players using a screen reader. Changed
+/// cells are announced before the board, as they are what the player most needs to hear.
+pub(crate) struct DescriptionRenderer;
+
+impl BoardRenderer for DescriptionRenderer {
+    fn render(&self, board: &BoardView) -> String {
+        let empty = board.locale.message("board-cell-empty", &[]);
+        let changes = board.changes.iter().map(|&(index, player_id)| {
+            board.locale.message(
+                "board-cell-taken",
+                &[
+                    ("player", &board.marks.get(player_id)),
+                    ("cell", &(index + 1)),
+                ],
+            )
+        });
+        let rows = board
+            .cells
+            .chunks(board.config.size)
+            .enumerate()
+            .map(|(row_index, row)| {
+                let cells = row
+                    .iter()
+                    .map(|player_id| match player_id {
+                        Some(id) => String::from(board.marks.get(*id)),
+                        None => empty.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                board
+                    .locale
+                    .message("board-row", &[("row", &(row_index + 1)), ("cells", &cells)])
+            });
+
+        changes.chain(rows).collect::<Vec<_>>().join("\n")
+    }
+}
+
+/// The mark of the player occupying a cell in the colour used for them, or a space if empty.
+fn painted_player_icon(board: &BoardView, player_id: Option<u8>) -> String {
+    match player_id {
+        Some(id @ PLAYER_ONE_ID) => {
+            (board.paint)(board.marks.get(id).to_string(), Paint::PlayerOne)
+        }
+        Some(id @ PLAYER_TWO_ID) => {
+            (board.paint)(board.marks.get(id).to_string(), Paint::PlayerTwo)
+        }
+        Some(id) => board.marks.get(id).to_string(),
+        None => String::from(" "),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(
+        renderer: &dyn BoardRenderer,
+        cells: &[Option<u8>],
+        changes: &[(usize, u8)],
+    ) -> String {
+        renderer.render(&BoardView {
+            config: BoardConfig::default(),
+            cells,
+            changes,
+            locale: Locale::English,
+            marks: &PlayerMarks::default(),
+            paint: &|text, _| text,
+        })
+    }
+
+    #[test]
+    fn description_renderer_reads_out_each_row() {
+        let cells = [
+            Some(1),
+            None,
+            Some(2),
+            None,
+            Some(1),
+            None,
+            None,
+            None,
+            None,
+        ];
+
+        assert_eq!(
+            render(&DescriptionRenderer, &cells, &[]),
+            "Row 1: X, empty, O.\nRow 2: empty, X, empty.\nRow 3: empty, empty, empty."
+        );
+    }
+
+    #[test]
+    fn description_renderer_announces_changed_cells_first() {
+        let cells = [None, None, None, None, Some(2), None, None, None, None];
+
+        assert_eq!(
+            render(&DescriptionRenderer, &cells, &[(4, 2)]),
+            "O took cell 5.\n\
+            Row 1: empty, empty, empty.\nRow 2: empty, O, empty.\nRow 3: empty, empty, empty."
+        );
+    }
+
+    #[test]
+    fn description_renderer_uses_locale() {
+        let cells = [Some(1), None, None, None, None, None, None, None, None];
+        let description = DescriptionRenderer.render(&BoardView {
+            config: BoardConfig::default(),
+            cells: &cells,
+            changes: &[],
+            locale: Locale::Spanish,
+            marks: &PlayerMarks::default(),
+            paint: &|text, _| text,
+        });
+
+        assert!(description.starts_with("Fila 1: X, vacía, vacía."));
+    }
+}
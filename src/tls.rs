@@ -0,0 +1,281 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::crypto::{self, CryptoProvider};
+use tokio_rustls::rustls::pki_types::{
+    CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime,
+};
+use tokio_rustls::rustls::{
+    self, CertificateError, ClientConfig, DigitallySignedStruct, ServerConfig, SignatureScheme,
+};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+/// Where the certificate is saved when the host generates a self-signed one.
+pub const SELF_SIGNED_CERTIFICATE_PATH: &str = "game-server-cert.pem";
+
+/// The certificate a host presents to players connecting over TLS.
+#[derive(Debug, Clone)]
+pub enum HostCertificate {
+    Load {
+        cert_path: PathBuf,
+        key_path: PathBuf,
+    },
+    SelfSigned,
+}
+
+impl HostCertificate {
+    /// The path of the certificate that players need in order to connect.
+    pub fn cert_path(&self) -> &Path {
+        match self {
+            HostCertificate::Load { cert_path, .. } => cert_path,
+            HostCertificate::SelfSigned => Path::new(SELF_SIGNED_CERTIFICATE_PATH),
+        }
+    }
+}
+
+/// Creates the acceptor used by a host to encrypt connections from players and spectators.
+///
+/// Self-signed certificates are saved to [`SELF_SIGNED_CERTIFICATE_PATH`] so that they can be
+/// shared with anyone wishing to connect.
+pub fn acceptor(certificate: &HostCertificate) -> Result<TlsAcceptor, Error> {
+    let (certificates, private_key) = match certificate {
+        HostCertificate::Load {
+            cert_path,
+            key_path,
+        } => (load_certificates(cert_path)?, load_private_key(key_path)?),
+        HostCertificate::SelfSigned => {
+            let generated = rcgen::generate_simple_self_signed(vec![String::from("localhost")])?;
+            fs::write(SELF_SIGNED_CERTIFICATE_PATH, generated.cert.pem())?;
+
+            let private_key = PrivatePkcs8KeyDer::from(generated.key_pair.serialize_der()).into();
+            (vec![generated.cert.der().clone()], private_key)
+        }
+    };
+
+    let config = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()?
+        .with_no_client_auth()
+        .with_single_cert(certificates, private_key)?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Creates the connector used by players and spectators to connect to a host over TLS.
+///
+/// Hosts typically use self-signed certificates, so rather than relying on certificate authorities
+/// the connector only trusts the exact certificate found at `cert_path`.
+pub fn connector(cert_path: &Path) -> Result<TlsConnector, Error> {
+    let pinned = load_certificates(cert_path)?
+        .into_iter()
+        .next()
+        .ok_or(Error::MissingCertificate)?;
+
+    let provider = provider();
+    let verifier = PinnedCertificateVerifier {
+        pinned,
+        provider: Arc::clone(&provider),
+    };
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(verifier))
+        .with_no_client_auth();
+
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(crypto::ring::default_provider())
+}
+
+fn load_certificates(path: &Path) -> Result<Vec<CertificateDer<'static>>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let certificates = rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?;
+    if certificates.is_empty() {
+        return Err(Error::MissingCertificate);
+    }
+
+    Ok(certificates)
+}
+
+fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>, Error> {
+    let mut reader = BufReader::new(File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?.ok_or(Error::MissingPrivateKey)
+}
+
+/// Accepts only a single known certificate, regardless of who issued it or which names it covers.
+#[derive(Debug)]
+struct PinnedCertificateVerifier {
+    pinned: CertificateDer<'static>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertificateVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if end_entity.as_ref() == self.pinned.as_ref() {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to read or write the certificate files: {0}")]
+    Io(#[from] io::Error),
+    #[error("Failed to configure TLS: {0}")]
+    Rustls(#[from] rustls::Error),
+    #[error("Failed to generate a self-signed certificate: {0}")]
+    Generation(#[from] rcgen::Error),
+    #[error("No certificate was found in the certificate file")]
+    MissingCertificate,
+    #[error("No private key was found in the key file")]
+    MissingPrivateKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use serde::{Deserialize, Serialize};
+    use tokio::net::{TcpListener, TcpStream};
+
+    use crate::connection::Connection;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct TestEvent {
+        content: String,
+    }
+
+    fn write_certificate_files(name: &str) -> (PathBuf, PathBuf) {
+        let generated =
+            rcgen::generate_simple_self_signed(vec![String::from("localhost")]).unwrap();
+        let directory = std::env::temp_dir();
+        let cert_path = directory.join(format!("{}-{}-cert.pem", name, std::process::id()));
+        let key_path = directory.join(format!("{}-{}-key.pem", name, std::process::id()));
+        fs::write(&cert_path, generated.cert.pem()).unwrap();
+        fs::write(&key_path, generated.key_pair.serialize_pem()).unwrap();
+
+        (cert_path, key_path)
+    }
+
+    async fn connect(
+        acceptor: TlsAcceptor,
+        connector: TlsConnector,
+    ) -> (io::Result<Connection>, io::Result<Connection>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let host = async {
+            let (stream, _) = listener.accept().await.unwrap();
+            acceptor.accept(stream).await.map(Connection::new)
+        };
+        let player = async {
+            let stream = TcpStream::connect(address).await.unwrap();
+            let server_name = ServerName::IpAddress(address.ip().into());
+            connector
+                .connect(server_name, stream)
+                .await
+                .map(Connection::new)
+        };
+
+        tokio::join!(host, player)
+    }
+
+    #[tokio::test]
+    async fn connector_trusting_host_certificate_can_exchange_events() {
+        let (cert_path, key_path) = write_certificate_files("trusted");
+        let acceptor = acceptor(&HostCertificate::Load {
+            cert_path: cert_path.clone(),
+            key_path,
+        })
+        .unwrap();
+        let connector = connector(&cert_path).unwrap();
+
+        let (host, player) = connect(acceptor, connector).await;
+        let (mut host, mut player) = (host.unwrap(), player.unwrap());
+
+        player
+            .write_event(&TestEvent {
+                content: String::from("Encrypted content"),
+            })
+            .await
+            .unwrap();
+        let event: TestEvent = host.read_event().await.unwrap();
+        assert_eq!(event.content, "Encrypted content");
+    }
+
+    #[tokio::test]
+    async fn connector_rejects_unknown_certificate() {
+        let (host_cert_path, host_key_path) = write_certificate_files("host");
+        let (other_cert_path, _) = write_certificate_files("other");
+        let acceptor = acceptor(&HostCertificate::Load {
+            cert_path: host_cert_path,
+            key_path: host_key_path,
+        })
+        .unwrap();
+        let connector = connector(&other_cert_path).unwrap();
+
+        let (_, player) = connect(acceptor, connector).await;
+        assert!(player.is_err());
+    }
+
+    #[test]
+    fn connector_requires_certificate_in_file() {
+        let path = std::env::temp_dir().join(format!("empty-{}-cert.pem", std::process::id()));
+        fs::write(&path, "").unwrap();
+
+        assert!(matches!(connector(&path), Err(Error::MissingCertificate)));
+    }
+}
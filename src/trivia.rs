@@ -0,0 +1,1093 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::Sender;
+
+use crate::client::{
+    BotClient, ClientType, Frontend, LocalClient, OnlineClient, OutputStyle, Paint,
+    SpectatorClient, UserOutput,
+};
+use crate::game::rand::GameRng;
+use crate::game::{
+    self, ClientChannel, ClientContext, ClientKind, GameClient, GameClientError, GameClientEvent,
+    GameDescriptor, GameServer, GameServerEvent, ServerChannel, SimultaneousMoves,
+};
+use crate::locale::Locale;
+use crate::server::{
+    get_alternative_player_id, DispatchMode, TimeoutAction, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+use crate::trivia::questions::Question;
+pub use crate::trivia::questions::{QuestionBank, QuestionBankError};
+
+mod questions;
+
+/// Identifies Trivia among the games in `game::GAMES`.
+pub const GAME_ID: &str = "trivia";
+
+/// The number of questions asked unless another is given.
+pub const DEFAULT_ROUNDS: u8 = 5;
+/// The time players have to answer each question unless another is given.
+pub const DEFAULT_ANSWER_TIME: Duration = Duration::from_secs(20);
+
+/// The options a game of trivia is played with.
+#[derive(Debug, Clone)]
+pub struct TriviaConfig {
+    /// The number of questions asked, fewer if the question bank does not hold enough.
+    pub rounds: u8,
+    /// The time players have to answer each question, after which any who have not answered
+    /// score nothing for it.
+    pub answer_time: Duration,
+    /// The questions to draw from, the built-in questions if `None`.
+    pub questions: Option<Arc<QuestionBank>>,
+}
+
+impl TriviaConfig {
+    pub const DEFAULT: TriviaConfig = TriviaConfig {
+        rounds: DEFAULT_ROUNDS,
+        answer_time: DEFAULT_ANSWER_TIME,
+        questions: None,
+    };
+}
+
+impl Default for TriviaConfig {
+    fn default() -> Self {
+        TriviaConfig::DEFAULT
+    }
+}
+
+/// A head-to-head quiz, where both players are asked the same question at once and score a point
+/// for answering it correctly before time runs out. The player with the most points once every
+/// question has been asked wins.
+#[derive(Debug, Clone)]
+pub struct Trivia {
+    config: TriviaConfig,
+}
+
+impl Trivia {
+    pub const fn new(config: TriviaConfig) -> Trivia {
+        Trivia { config }
+    }
+}
+
+impl GameDescriptor for Trivia {
+    fn id(&self) -> &'static str {
+        GAME_ID
+    }
+
+    fn make_server(
+        &self,
+        channel: Sender<GameServerEvent>,
+    ) -> Box<dyn game::AnyGameServer + Send + Sync> {
+        Box::new(TriviaServer::new(channel, &self.config))
+    }
+
+    fn make_client<'a>(&self, context: ClientContext<'a>) -> Box<dyn game::AnyGameClient + 'a> {
+        let ClientContext {
+            frontend,
+            channel,
+            kind,
+        } = context;
+
+        match kind {
+            ClientKind::Local => Box::new(TriviaClient::new(frontend, channel, LocalClient {})),
+            ClientKind::Online { id } => {
+                Box::new(TriviaClient::new(frontend, channel, OnlineClient { id }))
+            }
+            ClientKind::Spectator => {
+                Box::new(TriviaClient::new(frontend, channel, SpectatorClient {}))
+            }
+            ClientKind::Bot { id, move_delay } => Box::new(TriviaClient::new(
+                frontend,
+                channel,
+                BotClient { id, move_delay },
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum ServerEvent {
+    /// Asks every player the question at once, without its answer, giving the number of seconds
+    /// they have to answer it.
+    QuestionAsked {
+        round: u8,
+        rounds: u8,
+        prompt: String,
+        choices: Vec<String>,
+        seconds: u64,
+    },
+    /// Tells everyone a player has answered, without revealing their answer until the question
+    /// closes.
+    AnswerGiven {
+        player_id: u8,
+    },
+    /// Reveals the index of the correct choice along with the choice made by each player, once
+    /// every player has answered or time has run out. A player who ran out of time has no choice.
+    QuestionClosed {
+        answer: u8,
+        choices: BTreeMap<u8, u8>,
+        scores: BTreeMap<u8, u8>,
+    },
+    GameOver {
+        /// The ID of the player with the most points, `None` if the players are tied.
+        winner: Option<u8>,
+        scores: BTreeMap<u8, u8>,
+    },
+    ErrorOccurred {
+        error: Error,
+    },
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize, thiserror::Error, Debug)]
+pub enum Error {
+    #[error("You have already answered this question.")]
+    AlreadyAnswered,
+    #[error("That question has closed, answer the one being asked now.")]
+    QuestionClosed,
+    #[error("That is not one of the choices.")]
+    InvalidChoice,
+}
+
+pub struct TriviaServer {
+    /// The questions to draw from, or `None` if those given are the only ones to ask.
+    bank: Option<Arc<QuestionBank>>,
+    /// The questions drawn for the game, asked in order.
+    questions: Vec<Question>,
+    /// The number of the question being asked, counting from one, which also identifies the
+    /// timer limiting the time to answer it.
+    round: u8,
+    answer_time: Duration,
+    scores: BTreeMap<u8, u8>,
+    choices: SimultaneousMoves<u8>,
+    server_channel: ServerChannel<ServerEvent>,
+    /// Set once the outcome has been sent, after which any answers still arriving are ignored.
+    game_over: bool,
+}
+
+impl TriviaServer {
+    pub fn new(server_channel: Sender<GameServerEvent>, config: &TriviaConfig) -> TriviaServer {
+        let bank = match &config.questions {
+            Some(bank) => Arc::clone(bank),
+            None => Arc::new(QuestionBank::built_in()),
+        };
+        let questions = bank.draw(config.rounds as usize, &mut GameRng::new());
+        let mut server =
+            TriviaServer::with_questions(server_channel, questions, config.answer_time);
+        server.bank = Some(bank);
+
+        server
+    }
+
+    fn with_questions(
+        server_channel: Sender<GameServerEvent>,
+        questions: Vec<Question>,
+        answer_time: Duration,
+    ) -> TriviaServer {
+        let player_ids = [PLAYER_ONE_ID, PLAYER_TWO_ID];
+        TriviaServer {
+            bank: None,
+            questions,
+            round: 1,
+            answer_time,
+            scores: player_ids.iter().map(|player_id| (*player_id, 0)).collect(),
+            choices: SimultaneousMoves::new(&player_ids),
+            server_channel: ServerChannel::new(server_channel),
+            game_over: false,
+        }
+    }
+
+    fn current_question(&self) -> &Question {
+        &self.questions[self.round as usize - 1]
+    }
+
+    async fn dispatch_event(&self, dispatch_mode: DispatchMode, event: ServerEvent) {
+        self.server_channel
+            .send(GameServerEvent::DispatchToClient {
+                dispatch_mode,
+                event,
+            })
+            .await
+    }
+
+    async fn dispatch_error_event(&self, player_id: u8, error: Error) {
+        self.dispatch_event(
+            DispatchMode::SinglePlayer { player_id },
+            ServerEvent::ErrorOccurred { error },
+        )
+        .await
+    }
+
+    async fn dispatch_question_asked_event(&self, dispatch_mode: DispatchMode) {
+        let question = self.current_question();
+        let event = ServerEvent::QuestionAsked {
+            round: self.round,
+            rounds: self.questions.len() as u8,
+            prompt: question.prompt.clone(),
+            choices: question.choices.clone(),
+            seconds: self.answer_time.as_secs(),
+        };
+        self.dispatch_event(dispatch_mode, event).await
+    }
+
+    /// Asks every player the current question, giving them until the answer time runs out.
+    async fn ask_question(&self) {
+        self.dispatch_question_asked_event(DispatchMode::AllPlayers)
+            .await;
+        self.server_channel
+            .send(GameServerEvent::TimerStarted {
+                id: self.round as u32,
+                duration: self.answer_time,
+            })
+            .await
+    }
+
+    async fn dispatch_game_over_event(&mut self) {
+        self.game_over = true;
+        let (player_one_score, player_two_score) =
+            (self.scores[&PLAYER_ONE_ID], self.scores[&PLAYER_TWO_ID]);
+        let winner = match player_one_score.cmp(&player_two_score) {
+            std::cmp::Ordering::Greater => Some(PLAYER_ONE_ID),
+            std::cmp::Ordering::Less => Some(PLAYER_TWO_ID),
+            std::cmp::Ordering::Equal => None,
+        };
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::GameOver {
+                winner,
+                scores: self.scores.clone(),
+            },
+        )
+        .await;
+        self.server_channel
+            .send(GameServerEvent::GameOver { winner })
+            .await
+    }
+
+    /// Scores the choices made and reveals the answer, then either asks the next question or
+    /// ends the game.
+    async fn close_question(&mut self, choices: BTreeMap<u8, u8>) {
+        let answer = self.current_question().answer_index();
+        for (player_id, choice) in &choices {
+            if *choice == answer {
+                *self.scores.entry(*player_id).or_default() += 1;
+            }
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::QuestionClosed {
+                answer,
+                choices,
+                scores: self.scores.clone(),
+            },
+        )
+        .await;
+
+        if self.round as usize >= self.questions.len() {
+            self.dispatch_game_over_event().await;
+            return;
+        }
+
+        self.round += 1;
+        self.ask_question().await;
+    }
+
+    async fn handle_answer_given_event(&mut self, player_id: u8, round: u8, choice: u8) {
+        // The player may have answered just as the question closed
+        if round != self.round {
+            self.dispatch_error_event(player_id, Error::QuestionClosed)
+                .await;
+            return;
+        }
+        if choice as usize >= self.current_question().choices.len() {
+            self.dispatch_error_event(player_id, Error::InvalidChoice)
+                .await;
+            return;
+        }
+        if !self.choices.commit(player_id, choice) {
+            self.dispatch_error_event(player_id, Error::AlreadyAnswered)
+                .await;
+            return;
+        }
+
+        self.dispatch_event(
+            DispatchMode::AllPlayers,
+            ServerEvent::AnswerGiven { player_id },
+        )
+        .await;
+
+        if let Some(choices) = self.choices.reveal() {
+            self.close_question(choices).await;
+        }
+    }
+}
+
+#[async_trait]
+impl GameServer for TriviaServer {
+    type ClientEvent = ClientEvent;
+    type ServerEvent = ServerEvent;
+
+    fn set_rng(&mut self, mut rng: GameRng) {
+        if let Some(bank) = &self.bank {
+            self.questions = bank.draw(self.questions.len(), &mut rng);
+        }
+    }
+
+    async fn begin(&self) {
+        self.ask_question().await;
+    }
+
+    async fn handle_event(&mut self, event: ClientEvent) {
+        if self.game_over {
+            return;
+        }
+
+        let ClientEvent::AnswerGiven {
+            player_id,
+            round,
+            choice,
+        } = event;
+
+        self.handle_answer_given_event(player_id, round, choice)
+            .await;
+    }
+
+    // No turns are started, as every player answers at once against the game's own timer
+    async fn handle_turn_timed_out(&mut self, _player_id: u8, _action: TimeoutAction) {}
+
+    async fn handle_timer_elapsed(&mut self, id: u32) {
+        // The timer may have run out just as the last player answered
+        if self.game_over || id != self.round as u32 {
+            return;
+        }
+
+        let choices = self.choices.take();
+        self.close_question(choices).await;
+    }
+
+    async fn handle_player_reconnected(&self, player_id: u8) {
+        if !self.choices.has_moved(player_id) {
+            self.dispatch_question_asked_event(DispatchMode::SinglePlayer { player_id })
+                .await;
+        }
+    }
+
+    async fn handle_spectator_joined(&self) {
+        self.dispatch_question_asked_event(DispatchMode::Spectators)
+            .await;
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// The index of the choice made for the question of the given round.
+    AnswerGiven {
+        player_id: u8,
+        round: u8,
+        choice: u8,
+    },
+}
+
+pub struct TriviaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    frontend: Arc<F>,
+    client_channel: ClientChannel<ClientEvent>,
+    client_type: C,
+    user_output: UserOutput<F>,
+    /// The round of the question being asked, sent back with each answer.
+    round: u8,
+    /// The choices of the question being asked, for showing the one each player made.
+    choices: Vec<String>,
+}
+
+impl<F, C> TriviaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType,
+{
+    pub fn new(
+        frontend: Arc<F>,
+        client_channel: Sender<GameClientEvent>,
+        client_type: C,
+    ) -> TriviaClient<F, C> {
+        TriviaClient {
+            user_output: UserOutput::new(Arc::clone(&frontend)),
+            frontend,
+            client_channel: ClientChannel::new(client_channel),
+            client_type,
+            round: 0,
+            choices: Vec::new(),
+        }
+    }
+
+    fn get_player_icon_by_id(&self, id: u8) -> char {
+        match id {
+            PLAYER_ONE_ID => 'X',
+            PLAYER_TWO_ID => 'O',
+            _ => '?',
+        }
+    }
+
+    /// Shows the question with its choices numbered from one, as they are chosen.
+    fn write_question(
+        &mut self,
+        round: u8,
+        rounds: u8,
+        prompt: &str,
+        choices: Vec<String>,
+        seconds: u64,
+    ) -> Result<(), io::Error> {
+        self.round = round;
+        self.choices = choices;
+
+        let numbered_choices = |format_choice: fn(usize, &String) -> String, separator| {
+            self.choices
+                .iter()
+                .enumerate()
+                .map(|(index, choice)| format_choice(index + 1, choice))
+                .collect::<Vec<String>>()
+                .join(separator)
+        };
+        let output_style = self.user_output.output_style();
+        let choices = match output_style {
+            OutputStyle::Standard => {
+                numbered_choices(|number, choice| format!("  {}. {}", number, choice), "\n")
+            }
+            OutputStyle::Terse => {
+                numbered_choices(|number, choice| format!("{}.{}", number, choice), " ")
+            }
+        };
+
+        self.user_output.write_message(
+            "trivia-question",
+            &[
+                ("round", &round),
+                ("rounds", &rounds),
+                ("seconds", &seconds),
+                ("prompt", &prompt),
+                ("choices", &choices),
+            ],
+        )?;
+        // Terse output fits the prompt and choices onto the same line as the round
+        if output_style == OutputStyle::Standard {
+            self.user_output
+                .write_line(format!("{}\n{}", prompt, choices))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_scores(&self, scores: &BTreeMap<u8, u8>) -> Result<(), io::Error> {
+        let score = |player_id| scores.get(&player_id).copied().unwrap_or_default();
+        let (player_one_score, player_two_score) = (score(PLAYER_ONE_ID), score(PLAYER_TWO_ID));
+        self.user_output.write_message(
+            "trivia-score",
+            &[("first", &player_one_score), ("second", &player_two_score)],
+        )
+    }
+
+    async fn handle_answer_given_event(&self, player_id: u8) -> Result<(), GameClientError> {
+        if self.client_type.get_own_player_id() == Some(player_id) {
+            self.user_output
+                .write_message("trivia-waiting-for-answer", &[])?;
+        } else {
+            let player_icon = self.get_player_icon_by_id(player_id);
+            self.user_output
+                .write_message("trivia-player-answered", &[("player", &player_icon)])?;
+        }
+
+        Ok(())
+    }
+
+    /// Reveals the answer along with the choice each player made, or that they ran out of time.
+    async fn handle_question_closed_event(
+        &self,
+        answer: u8,
+        choices: BTreeMap<u8, u8>,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        let choice_name = |choice: u8| {
+            self.choices
+                .get(choice as usize)
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let mut results = vec![self.user_output.message(
+            "trivia-answer-was",
+            &[("answer", &choice_name(answer)), ("number", &(answer + 1))],
+        )];
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let player_icon = self.get_player_icon_by_id(player_id);
+            results.push(match choices.get(&player_id) {
+                Some(&choice) => {
+                    let key = match choice == answer {
+                        true => "trivia-answered-right",
+                        false => "trivia-answered-wrong",
+                    };
+                    self.user_output.message(
+                        key,
+                        &[
+                            ("player", &player_icon),
+                            ("choice", &choice_name(choice)),
+                            ("number", &(choice + 1)),
+                        ],
+                    )
+                }
+                None => self
+                    .user_output
+                    .message("trivia-out-of-time", &[("player", &player_icon)]),
+            });
+        }
+
+        self.user_output.write_line(results.join(" "))?;
+        self.write_scores(&scores)?;
+
+        Ok(())
+    }
+
+    async fn handle_game_over_event(
+        &mut self,
+        winner: Option<u8>,
+        scores: BTreeMap<u8, u8>,
+    ) -> Result<(), GameClientError> {
+        let score = |player_id| scores.get(&player_id).copied().unwrap_or_default();
+        match winner {
+            Some(winner) => self.user_output.write_message(
+                "trivia-game-won",
+                &[
+                    ("player", &self.get_player_icon_by_id(winner)),
+                    ("score", &score(winner)),
+                    ("other", &score(get_alternative_player_id(winner))),
+                ],
+            )?,
+            None => self
+                .user_output
+                .write_message("trivia-game-draw", &[("score", &score(PLAYER_ONE_ID))])?,
+        };
+        self.send_to_client(GameClientEvent::GameOver).await
+    }
+
+    async fn handle_error_occurred_event(&self, error: Error) -> Result<(), GameClientError> {
+        let key = match error {
+            Error::AlreadyAnswered => "trivia-error-already-answered",
+            Error::QuestionClosed => "trivia-error-question-closed",
+            Error::InvalidChoice => "trivia-error-invalid-choice",
+        };
+        self.user_output.write_error(key, &[])?;
+
+        Ok(())
+    }
+
+    async fn answer_question(&mut self, player_id: u8) -> Result<(), GameClientError> {
+        let choice = self.get_choice().await?;
+        self.send_answer(player_id, choice).await
+    }
+
+    async fn send_answer(&mut self, player_id: u8, choice: u8) -> Result<(), GameClientError> {
+        self.send_to_client(GameClientEvent::DispatchToServer {
+            event: ClientEvent::AnswerGiven {
+                player_id,
+                round: self.round,
+                choice,
+            },
+        })
+        .await
+    }
+
+    async fn send_to_client(
+        &mut self,
+        event: GameClientEvent<ClientEvent>,
+    ) -> Result<(), GameClientError> {
+        self.client_channel
+            .send(event)
+            .await
+            .map_err(|_| GameClientError::ClientStopped)
+    }
+
+    /// Reads the number of the choice made, giving its index among the choices.
+    async fn get_choice(&mut self) -> Result<u8, io::Error> {
+        let choice_count = self.choices.len();
+        loop {
+            self.user_output
+                .write_message("trivia-answer-prompt", &[("count", &choice_count)])?;
+
+            let input_text = self.frontend.read_line().await?;
+            match input_text.trim().parse::<usize>() {
+                Ok(number) if (1..=choice_count).contains(&number) => return Ok(number as u8 - 1),
+                _ => self.user_output.write_message("trivia-not-a-choice", &[])?,
+            };
+        }
+    }
+}
+
+#[async_trait]
+pub trait ClientTypeEvent {
+    fn get_game_started_message(&self) -> String;
+    async fn handle_question_asked_event(&mut self) -> Result<(), GameClientError>;
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TriviaClient<F, LocalClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("trivia-game-started-local", &[])
+    }
+
+    async fn handle_question_asked_event(&mut self) -> Result<(), GameClientError> {
+        for player_id in [PLAYER_ONE_ID, PLAYER_TWO_ID] {
+            let player_icon = self.get_player_icon_by_id(player_id);
+            self.user_output
+                .write_message("player-turn", &[("player", &player_icon)])?;
+            self.answer_question(player_id).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TriviaClient<F, OnlineClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("trivia-game-started-online", &[])
+    }
+
+    async fn handle_question_asked_event(&mut self) -> Result<(), GameClientError> {
+        self.user_output
+            .write_painted_message("your-turn", &[], Paint::Highlight)?;
+        self.answer_question(self.client_type.id).await
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TriviaClient<F, SpectatorClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-spectator", &[])
+    }
+
+    async fn handle_question_asked_event(&mut self) -> Result<(), GameClientError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ClientTypeEvent for TriviaClient<F, BotClient>
+where
+    F: Frontend + ?Sized,
+{
+    fn get_game_started_message(&self) -> String {
+        self.user_output.message("game-started-bot", &[])
+    }
+
+    /// Bots are never told the answer, so choose at random.
+    async fn handle_question_asked_event(&mut self) -> Result<(), GameClientError> {
+        self.client_type.wait_to_move().await;
+        let choice = rand::thread_rng().gen_range(0..self.choices.len()) as u8;
+        self.send_answer(self.client_type.id, choice).await
+    }
+}
+
+#[async_trait]
+impl<F, C> GameClient for TriviaClient<F, C>
+where
+    F: Frontend + ?Sized,
+    C: ClientType + Send + Sync,
+    Self: ClientTypeEvent,
+{
+    type ServerEvent = ServerEvent;
+    type ClientEvent = ClientEvent;
+
+    fn set_output_style(&mut self, output_style: OutputStyle) {
+        self.user_output.set_output_style(output_style);
+    }
+
+    fn set_color(&mut self, color: bool) {
+        self.user_output.set_color(color);
+    }
+
+    fn set_locale(&mut self, locale: Locale) {
+        self.user_output.set_locale(locale);
+    }
+
+    fn get_player_name(&self, player_id: u8) -> String {
+        self.get_player_icon_by_id(player_id).to_string()
+    }
+
+    async fn handle_game_started_event(&mut self, _config: Vec<u8>) -> Result<(), GameClientError> {
+        self.user_output
+            .write_line(self.get_game_started_message())?;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: ServerEvent) -> Result<(), GameClientError> {
+        match event {
+            ServerEvent::QuestionAsked {
+                round,
+                rounds,
+                prompt,
+                choices,
+                seconds,
+            } => {
+                self.write_question(round, rounds, &prompt, choices, seconds)?;
+                self.handle_question_asked_event().await
+            }
+            ServerEvent::AnswerGiven { player_id } => {
+                self.handle_answer_given_event(player_id).await
+            }
+            ServerEvent::QuestionClosed {
+                answer,
+                choices,
+                scores,
+            } => {
+                self.handle_question_closed_event(answer, choices, scores)
+                    .await
+            }
+            ServerEvent::GameOver { winner, scores } => {
+                self.handle_game_over_event(winner, scores).await
+            }
+            ServerEvent::ErrorOccurred { error } => self.handle_error_occurred_event(error).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::from_utf8;
+    use std::sync::Mutex;
+
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::client::TerminalFrontend;
+
+    use super::*;
+
+    async fn get_test_client_and_output<C: ClientType>(
+        input: &'static [u8],
+        client_type: C,
+    ) -> (
+        TriviaClient<TerminalFrontend<Vec<u8>>, C>,
+        Arc<Mutex<Vec<u8>>>,
+        Receiver<GameClientEvent>,
+    ) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let output_clone = Arc::clone(&output);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(1);
+        let client = TriviaClient::new(
+            Arc::new(TerminalFrontend::new(input, output)),
+            sender,
+            client_type,
+        );
+
+        (client, output_clone, receiver)
+    }
+
+    fn assert_client_output(output: Arc<Mutex<Vec<u8>>>, expected: &str) {
+        let mutex = output.lock().unwrap();
+        let actual = mutex.as_slice();
+
+        assert_eq!(
+            actual,
+            expected.as_bytes(),
+            "expected\n{}, actual\n{}",
+            expected,
+            from_utf8(actual).unwrap(),
+        )
+    }
+
+    fn get_test_server(rounds: usize) -> (TriviaServer, Receiver<GameServerEvent>) {
+        let (sender, receiver) = tokio::sync::mpsc::channel(20);
+        let question = Question {
+            prompt: String::from("2 + 2?"),
+            choices: vec![String::from("3"), String::from("4")],
+            answer: String::from("4"),
+        };
+        let server =
+            TriviaServer::with_questions(sender, vec![question; rounds], Duration::from_secs(10));
+
+        (server, receiver)
+    }
+
+    async fn answer(server: &mut TriviaServer, player_id: u8, round: u8, choice: u8) {
+        let event = ClientEvent::AnswerGiven {
+            player_id,
+            round,
+            choice,
+        };
+        server.handle_event(event).await;
+    }
+
+    fn drain_server_events(receiver: &mut Receiver<GameServerEvent>) -> Vec<GameServerEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            events.push(event);
+        }
+
+        events
+    }
+
+    fn get_dispatched_events(events: &[GameServerEvent]) -> Vec<ServerEvent> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                GameServerEvent::DispatchToClient { event, .. } => {
+                    Some(game::deserialize_event(event.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn server_given_same_seed_draws_same_questions() {
+        let (sender, _receiver) = tokio::sync::mpsc::channel(1);
+        let mut one = TriviaServer::new(sender.clone(), &TriviaConfig::default());
+        let mut two = TriviaServer::new(sender, &TriviaConfig::default());
+
+        one.set_rng(GameRng::from_seed(7));
+        two.set_rng(GameRng::from_seed(7));
+        assert_eq!(one.questions, two.questions);
+        assert_eq!(one.questions.len(), DEFAULT_ROUNDS as usize);
+    }
+
+    #[tokio::test]
+    async fn server_asks_question_without_answer_and_starts_timer() {
+        let (server, mut receiver) = get_test_server(2);
+
+        server.begin().await;
+
+        let events = drain_server_events(&mut receiver);
+        assert!(matches!(
+            get_dispatched_events(&events)[..],
+            [ServerEvent::QuestionAsked {
+                round: 1,
+                rounds: 2,
+                seconds: 10,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(GameServerEvent::TimerStarted { id: 1, duration }) if *duration == Duration::from_secs(10)
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_closes_question_once_both_players_answer() {
+        let (mut server, mut receiver) = get_test_server(2);
+
+        answer(&mut server, PLAYER_ONE_ID, 1, 1).await;
+        answer(&mut server, PLAYER_TWO_ID, 1, 0).await;
+
+        let events = get_dispatched_events(&drain_server_events(&mut receiver));
+        let ServerEvent::QuestionClosed {
+            answer,
+            choices,
+            scores,
+        } = &events[2]
+        else {
+            panic!("Expected the question to be closed");
+        };
+        assert_eq!(*answer, 1);
+        assert_eq!(choices, &BTreeMap::from([(1, 1), (2, 0)]));
+        assert_eq!(scores, &BTreeMap::from([(1, 1), (2, 0)]));
+        assert!(matches!(
+            events[3],
+            ServerEvent::QuestionAsked { round: 2, .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_closes_question_once_time_runs_out() {
+        let (mut server, mut receiver) = get_test_server(1);
+        answer(&mut server, PLAYER_TWO_ID, 1, 1).await;
+
+        server.handle_timer_elapsed(1).await;
+
+        assert!(server.game_over);
+        let events = drain_server_events(&mut receiver);
+        assert!(matches!(
+            get_dispatched_events(&events).last(),
+            Some(ServerEvent::GameOver {
+                winner: Some(PLAYER_TWO_ID),
+                ..
+            })
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(GameServerEvent::GameOver {
+                winner: Some(PLAYER_TWO_ID)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_ignores_timer_of_question_already_closed() {
+        let (mut server, mut receiver) = get_test_server(2);
+        answer(&mut server, PLAYER_ONE_ID, 1, 1).await;
+        answer(&mut server, PLAYER_TWO_ID, 1, 1).await;
+        drain_server_events(&mut receiver);
+
+        server.handle_timer_elapsed(1).await;
+
+        assert_eq!(server.round, 2);
+        assert!(drain_server_events(&mut receiver).is_empty());
+    }
+
+    #[tokio::test]
+    async fn server_refuses_late_repeated_and_invalid_answers() {
+        let (mut server, mut receiver) = get_test_server(2);
+        server.handle_timer_elapsed(1).await;
+        drain_server_events(&mut receiver);
+
+        answer(&mut server, PLAYER_ONE_ID, 1, 1).await;
+        answer(&mut server, PLAYER_ONE_ID, 2, 5).await;
+        answer(&mut server, PLAYER_ONE_ID, 2, 1).await;
+        answer(&mut server, PLAYER_ONE_ID, 2, 0).await;
+
+        assert!(matches!(
+            get_dispatched_events(&drain_server_events(&mut receiver))[..],
+            [
+                ServerEvent::ErrorOccurred {
+                    error: Error::QuestionClosed
+                },
+                ServerEvent::ErrorOccurred {
+                    error: Error::InvalidChoice
+                },
+                ServerEvent::AnswerGiven {
+                    player_id: PLAYER_ONE_ID
+                },
+                ServerEvent::ErrorOccurred {
+                    error: Error::AlreadyAnswered
+                },
+            ]
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_declares_draw_on_tied_scores() {
+        let (mut server, mut receiver) = get_test_server(1);
+        answer(&mut server, PLAYER_ONE_ID, 1, 1).await;
+        answer(&mut server, PLAYER_TWO_ID, 1, 1).await;
+
+        assert!(matches!(
+            drain_server_events(&mut receiver).last(),
+            Some(GameServerEvent::GameOver { winner: None })
+        ));
+    }
+
+    #[tokio::test]
+    async fn client_shows_question_and_answers() {
+        let (mut client, output, _) = get_test_client_and_output(&[], SpectatorClient {}).await;
+
+        client
+            .write_question(
+                1,
+                5,
+                "2 + 2?",
+                vec![String::from("3"), String::from("4")],
+                20,
+            )
+            .unwrap();
+        client
+            .handle_question_closed_event(
+                1,
+                BTreeMap::from([(PLAYER_ONE_ID, 0)]),
+                BTreeMap::from([(PLAYER_ONE_ID, 0), (PLAYER_TWO_ID, 0)]),
+            )
+            .await
+            .unwrap();
+        client.set_output_style(OutputStyle::Terse);
+        client
+            .write_question(2, 5, "1 + 2?", vec![String::from("3")], 20)
+            .unwrap();
+
+        assert_client_output(
+            output,
+            "Question 1 of 5, with 20 seconds to answer:\n2 + 2?\n  1. 3\n  2. 4\n\
+            The answer was 4. Player X answered 3, which is wrong. Player O ran out of time.\n\
+            The score is X 0 - 0 O.\n\
+            Q2/5 (20s): 1 + 2? 1.3\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_writes_messages_in_locale() {
+        let (mut client, output, _) = get_test_client_and_output(&[], OnlineClient { id: 1 }).await;
+        client.set_locale(Locale::Spanish);
+
+        client
+            .handle_answer_given_event(PLAYER_TWO_ID)
+            .await
+            .unwrap();
+        client
+            .handle_error_occurred_event(Error::AlreadyAnswered)
+            .await
+            .unwrap();
+        assert_client_output(
+            output,
+            "El jugador O ha respondido.\nError: Ya has respondido esta pregunta.\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_get_choice_handles_errors_until_choice_provided() {
+        let input = "0\nfour\n2\n".as_bytes();
+        let (mut client, output, _) = get_test_client_and_output(input, LocalClient {}).await;
+        client.set_output_style(OutputStyle::Terse);
+        client.choices = vec![String::from("3"), String::from("4")];
+
+        assert_eq!(client.get_choice().await.unwrap(), 1);
+        assert_client_output(
+            output,
+            "Answer (1-2):\nInvalid choice.\nAnswer (1-2):\nInvalid choice.\nAnswer (1-2):\n",
+        );
+    }
+
+    #[tokio::test]
+    async fn client_sends_answer_for_question_being_asked() {
+        let input = "2\n".as_bytes();
+        let (mut client, _, mut receiver) =
+            get_test_client_and_output(input, OnlineClient { id: PLAYER_TWO_ID }).await;
+        client
+            .write_question(
+                3,
+                5,
+                "2 + 2?",
+                vec![String::from("3"), String::from("4")],
+                20,
+            )
+            .unwrap();
+
+        client.handle_question_asked_event().await.unwrap();
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(GameClientEvent::DispatchToServer {
+                event: game::serialize_event(ClientEvent::AnswerGiven {
+                    player_id: PLAYER_TWO_ID,
+                    round: 3,
+                    choice: 1,
+                })
+            })
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The fewest choices a question may offer.
+pub const MIN_CHOICES: usize = 2;
+/// The most choices a question may offer, so that each can be chosen with a single digit.
+pub const MAX_CHOICES: usize = 9;
+
+/// The questions asked when no question bank is given, as the prompt, the choices and the answer.
+const BUILT_IN_QUESTIONS: [(&str, [&str; 4], &str); 10] = [
+    (
+        "What is the capital of Australia?",
+        ["Sydney", "Melbourne", "Canberra", "Perth"],
+        "Canberra",
+    ),
+    (
+        "How many sides does a hexagon have?",
+        ["5", "6", "7", "8"],
+        "6",
+    ),
+    (
+        "Which planet is closest to the Sun?",
+        ["Venus", "Mars", "Mercury", "Earth"],
+        "Mercury",
+    ),
+    (
+        "What is the chemical symbol for gold?",
+        ["Au", "Ag", "Gd", "Go"],
+        "Au",
+    ),
+    (
+        "Which ocean is the largest?",
+        ["Atlantic", "Indian", "Arctic", "Pacific"],
+        "Pacific",
+    ),
+    (
+        "How many players does each side have on a football pitch?",
+        ["9", "10", "11", "12"],
+        "11",
+    ),
+    (
+        "Who wrote \"Pride and Prejudice\"?",
+        [
+            "Jane Austen",
+            "Charlotte Brontë",
+            "Mary Shelley",
+            "George Eliot",
+        ],
+        "Jane Austen",
+    ),
+    (
+        "What is the hardest natural substance?",
+        ["Quartz", "Diamond", "Granite", "Iron"],
+        "Diamond",
+    ),
+    (
+        "In which year did the first person walk on the Moon?",
+        ["1965", "1969", "1972", "1975"],
+        "1969",
+    ),
+    (
+        "What is the largest mammal?",
+        ["Elephant", "Giraffe", "Blue whale", "Hippopotamus"],
+        "Blue whale",
+    ),
+];
+
+/// A question with several choices, one of which is its answer.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Question {
+    pub prompt: String,
+    pub choices: Vec<String>,
+    /// The correct choice, which must be one of `choices`.
+    pub answer: String,
+}
+
+impl Question {
+    /// The index of the correct choice among the choices.
+    pub fn answer_index(&self) -> u8 {
+        self.choices
+            .iter()
+            .position(|choice| *choice == self.answer)
+            .expect("Answer must be one of the choices") as u8
+    }
+
+    fn is_valid(&self) -> bool {
+        (MIN_CHOICES..=MAX_CHOICES).contains(&self.choices.len())
+            && self.choices.contains(&self.answer)
+    }
+}
+
+/// The questions a game of trivia draws from, as written in a TOML or JSON file holding a list of
+/// `questions`, each with a `prompt`, its `choices` and the `answer` among them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuestionBank {
+    questions: Vec<Question>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum QuestionBankError {
+    #[error("Failed to read the question bank")]
+    Io(#[from] io::Error),
+    #[error("The question bank is not in the expected format: {0}")]
+    Format(String),
+    #[error("The question bank has no questions")]
+    Empty,
+    #[error("Question {0} must have 2 to 9 choices, one of which is its answer")]
+    InvalidQuestion(usize),
+}
+
+impl QuestionBank {
+    /// The questions asked when no question bank is given.
+    pub fn built_in() -> QuestionBank {
+        let questions = BUILT_IN_QUESTIONS
+            .iter()
+            .map(|(prompt, choices, answer)| Question {
+                prompt: String::from(*prompt),
+                choices: choices.iter().map(|choice| String::from(*choice)).collect(),
+                answer: String::from(*answer),
+            })
+            .collect();
+
+        QuestionBank { questions }
+    }
+
+    /// Reads the question bank in the given file, as TOML if its extension is ".toml" and
+    /// otherwise as JSON.
+    pub fn load(path: &Path) -> Result<QuestionBank, QuestionBankError> {
+        let contents = fs::read_to_string(path)?;
+        let bank: QuestionBank = match path
+            .extension()
+            .is_some_and(|extension| extension == "toml")
+        {
+            true => toml::from_str(&contents)
+                .map_err(|error| QuestionBankError::Format(error.message().to_string()))?,
+            false => serde_json::from_str(&contents)
+                .map_err(|error| QuestionBankError::Format(error.to_string()))?,
+        };
+
+        bank.validate()?;
+        Ok(bank)
+    }
+
+    fn validate(&self) -> Result<(), QuestionBankError> {
+        if self.questions.is_empty() {
+            return Err(QuestionBankError::Empty);
+        }
+
+        match self
+            .questions
+            .iter()
+            .position(|question| !question.is_valid())
+        {
+            Some(index) => Err(QuestionBankError::InvalidQuestion(index + 1)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    /// Chooses the given number of questions at random, each at most once, or every question if
+    /// there are not enough.
+    pub(crate) fn draw(&self, count: usize, rng: &mut impl Rng) -> Vec<Question> {
+        self.questions
+            .choose_multiple(rng, count)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::game::rand::GameRng;
+
+    fn write_bank(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = env::temp_dir().join(format!("question-bank-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    #[test]
+    fn built_in_questions_are_valid() {
+        let bank = QuestionBank::built_in();
+
+        assert_eq!(bank.len(), BUILT_IN_QUESTIONS.len());
+        assert!(bank.validate().is_ok());
+    }
+
+    #[test]
+    fn load_reads_toml_and_json() {
+        let toml = write_bank(
+            "questions.toml",
+            r#"
+            [[questions]]
+            prompt = "2 + 2?"
+            choices = ["3", "4"]
+            answer = "4"
+            "#,
+        );
+        let json = write_bank(
+            "questions.json",
+            r#"{"questions": [{"prompt": "2 + 2?", "choices": ["3", "4"], "answer": "4"}]}"#,
+        );
+
+        let from_toml = QuestionBank::load(&toml).unwrap();
+        let from_json = QuestionBank::load(&json).unwrap();
+        assert_eq!(from_toml, from_json);
+        assert_eq!(from_toml.questions[0].answer_index(), 1);
+
+        fs::remove_file(toml).unwrap();
+        fs::remove_file(json).unwrap();
+    }
+
+    #[test]
+    fn load_refuses_question_without_its_answer() {
+        let path = write_bank(
+            "invalid.json",
+            r#"{"questions": [
+                {"prompt": "2 + 2?", "choices": ["3", "4"], "answer": "4"},
+                {"prompt": "3 + 3?", "choices": ["5", "7"], "answer": "6"}
+            ]}"#,
+        );
+
+        assert!(matches!(
+            QuestionBank::load(&path),
+            Err(QuestionBankError::InvalidQuestion(2))
+        ));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn draw_chooses_each_question_at_most_once() {
+        let bank = QuestionBank::built_in();
+
+        let mut rng = GameRng::new();
+
+        let questions = bank.draw(3, &mut rng);
+        assert_eq!(questions.len(), 3);
+        assert_ne!(questions[0], questions[1]);
+        assert_eq!(bank.draw(100, &mut rng).len(), bank.len());
+    }
+}
@@ -0,0 +1,195 @@
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use ratatui::DefaultTerminal;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+
+use crate::client::{Frontend, Paint};
+use crate::locale::Locale;
+use crate::tui::app::{App, KeyAction};
+
+mod app;
+
+/// How long to wait for a key press before redrawing what the game has shown since.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A full-screen terminal interface for playing a game, shown in place of the usual line by line
+/// output.
+///
+/// The game client is driven as normal, playing through a `TuiFrontend` that sorts what the game
+/// shows into panes, with the latest board, the moves made so far and status messages each shown
+/// separately. Keys are handled as they are pressed, so the interface stays responsive while the
+/// game is waiting on the user.
+pub struct Tui {
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl Tui {
+    /// Takes over the terminal, returning the frontend to give the game client. The interface's
+    /// own messages are written in the given language.
+    pub fn start(locale: Locale) -> io::Result<(Tui, TuiFrontend)> {
+        let terminal = ratatui::try_init()?;
+        let app = Arc::new(Mutex::new(App::new(locale)));
+        let (frontend, input) = TuiFrontend::new(Arc::clone(&app));
+
+        let handle = thread::spawn(move || {
+            let result = run(terminal, &app, input);
+            ratatui::restore();
+            result
+        });
+
+        Ok((Tui { handle }, frontend))
+    }
+
+    /// Waits for the user to leave the interface, either part way through the game or once asked
+    /// to after the `TuiFrontend` has been dropped, then hands the terminal back.
+    pub fn wait(self) -> io::Result<()> {
+        self.handle.join().unwrap()
+    }
+}
+
+/// Draws the interface and handles keys until the user leaves it, which closes their input.
+fn run(mut terminal: DefaultTerminal, app: &Mutex<App>, input: TuiInput) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.lock().unwrap().draw(frame))?;
+
+        if !event::poll(POLL_INTERVAL)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match app.lock().unwrap().handle_key(key) {
+            Some(KeyAction::Submit(line)) => {
+                let _ = input.lines.send(line);
+            }
+            Some(KeyAction::Exit | KeyAction::Quit) => return Ok(()),
+            None => {}
+        }
+    }
+}
+
+/// What the user enters through the interface, passed on to the `TuiFrontend` until dropped as
+/// the user leaves.
+struct TuiInput {
+    lines: UnboundedSender<String>,
+    /// Never sent on, only dropped to tell the frontend the user has left.
+    _open: watch::Sender<()>,
+}
+
+/// Plays through the interface, showing what the game writes in its panes and reading the lines
+/// the user enters. The interface is told the game has finished once the frontend is dropped.
+pub struct TuiFrontend {
+    app: Arc<Mutex<App>>,
+    lines: tokio::sync::Mutex<UnboundedReceiver<String>>,
+    open: watch::Receiver<()>,
+}
+
+impl TuiFrontend {
+    /// Creates the frontend along with the user's input, which is read until dropped.
+    fn new(app: Arc<Mutex<App>>) -> (TuiFrontend, TuiInput) {
+        let (sender, lines) = mpsc::unbounded_channel();
+        let (open_sender, open) = watch::channel(());
+        let frontend = TuiFrontend {
+            app,
+            lines: tokio::sync::Mutex::new(lines),
+            open,
+        };
+        let input = TuiInput {
+            lines: sender,
+            _open: open_sender,
+        };
+
+        (frontend, input)
+    }
+}
+
+#[async_trait]
+impl Frontend for TuiFrontend {
+    fn show_message(&self, message: &str, _paint: Option<Paint>) -> io::Result<()> {
+        self.app.lock().unwrap().show_message(message);
+        Ok(())
+    }
+
+    fn render_board(&self, board: &str) -> io::Result<()> {
+        self.app.lock().unwrap().show_board(board);
+        Ok(())
+    }
+
+    async fn read_line(&self) -> io::Result<String> {
+        match self.lines.lock().await.recv().await {
+            Some(line) => Ok(format!("{}\n", line)),
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    async fn closed(&self) {
+        // Nothing is ever sent, so the wait only ends once the input has been dropped
+        let _ = self.open.clone().changed().await;
+    }
+}
+
+impl Drop for TuiFrontend {
+    fn drop(&mut self) {
+        self.app.lock().unwrap().finish();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::time;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn frontend_shows_game_in_app_and_reads_lines_entered() {
+        let app = Arc::new(Mutex::new(App::default()));
+        let (frontend, input) = TuiFrontend::new(Arc::clone(&app));
+
+        frontend
+            .show_message("It's your turn!", Some(Paint::Highlight))
+            .unwrap();
+        frontend.render_board("  | X |  \n").unwrap();
+        input.lines.send(String::from("5")).unwrap();
+        drop(input);
+
+        assert_eq!(frontend.read_line().await.unwrap(), "5\n");
+        assert_eq!(
+            frontend.read_line().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert_eq!(app.lock().unwrap().board, ["  | X |  "]);
+        assert_eq!(app.lock().unwrap().messages, ["It's your turn!"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn frontend_closed_once_user_leaves() {
+        let app = Arc::new(Mutex::new(App::default()));
+        let (frontend, input) = TuiFrontend::new(Arc::clone(&app));
+
+        let still_open = time::timeout(Duration::from_secs(3600), frontend.closed()).await;
+        assert!(still_open.is_err());
+
+        drop(input);
+        frontend.closed().await;
+    }
+
+    #[test]
+    fn app_finished_once_frontend_dropped() {
+        let app = Arc::new(Mutex::new(App::default()));
+        let (frontend, _input) = TuiFrontend::new(Arc::clone(&app));
+
+        assert!(!app.lock().unwrap().finished);
+        drop(frontend);
+        assert!(app.lock().unwrap().finished);
+    }
+}
@@ -0,0 +1,266 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::Frame;
+
+use crate::locale::Locale;
+
+/// What the interface should do following a key press.
+#[derive(Debug, PartialEq)]
+pub(crate) enum KeyAction {
+    /// Pass the line the user has entered to the game.
+    Submit(String),
+    /// Leave the interface once the game is over.
+    Exit,
+    /// Leave the interface part way through the game.
+    Quit,
+}
+
+/// The state of the interface, built up from what the game client shows through the
+/// `TuiFrontend`.
+#[derive(Debug, Default)]
+pub(crate) struct App {
+    /// The rows of the board as last laid out by the game.
+    pub(crate) board: Vec<String>,
+    pub(crate) history: Vec<String>,
+    pub(crate) messages: Vec<String>,
+    input: String,
+    pub(crate) finished: bool,
+    locale: Locale,
+}
+
+impl App {
+    /// Creates the interface, writing its own messages in the given language.
+    pub(crate) fn new(locale: Locale) -> App {
+        App {
+            locale,
+            ..App::default()
+        }
+    }
+
+    /// Adds each line of the message to the status pane.
+    pub(crate) fn show_message(&mut self, message: &str) {
+        self.messages.extend(
+            message
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(String::from),
+        );
+    }
+
+    /// Replaces the board with the one given, adding the cells that changed between them to the
+    /// move history.
+    pub(crate) fn show_board(&mut self, board: &str) {
+        let board: Vec<String> = board.lines().map(String::from).collect();
+        self.history.extend(describe_changes(&self.board, &board));
+        self.board = board;
+    }
+
+    /// Notes that the game has finished and nothing more will be written.
+    pub(crate) fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            let message = self.locale.message("tui-game-finished", &[]);
+            self.messages.push(message);
+        }
+    }
+
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) -> Option<KeyAction> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            _ if self.finished => Some(KeyAction::Exit),
+            KeyCode::Esc => Some(KeyAction::Quit),
+            KeyCode::Char('c') if ctrl => Some(KeyAction::Quit),
+            KeyCode::Char(c) => {
+                self.input.push(c);
+                None
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+                None
+            }
+            KeyCode::Enter => {
+                let line = std::mem::take(&mut self.input);
+                self.messages.push(format!("> {}", line));
+                Some(KeyAction::Submit(line))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn draw(&self, frame: &mut Frame) {
+        let board_height = self.board.len().max(1) as u16 + 2;
+        let [top, messages_area, input_area] = Layout::vertical([
+            Constraint::Length(board_height),
+            Constraint::Min(3),
+            Constraint::Length(3),
+        ])
+        .areas(frame.area());
+        let [board_area, history_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(24)]).areas(top);
+
+        frame.render_widget(
+            Paragraph::new(self.board.join("\n")).block(Block::bordered().title("Board")),
+            board_area,
+        );
+        frame.render_widget(
+            Paragraph::new(last_lines(&self.history, history_area))
+                .block(Block::bordered().title("Moves")),
+            history_area,
+        );
+        frame.render_widget(
+            Paragraph::new(last_lines(&self.messages, messages_area))
+                .block(Block::bordered().title("Status")),
+            messages_area,
+        );
+
+        let title = if self.finished {
+            "Press any key to exit"
+        } else {
+            "Input (Esc to quit)"
+        };
+        frame.render_widget(
+            Paragraph::new(self.input.as_str())
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .block(Block::bordered().title(title)),
+            input_area,
+        );
+        frame.set_cursor_position(Position::new(
+            input_area.x + 1 + self.input.chars().count() as u16,
+            input_area.y + 1,
+        ));
+    }
+}
+
+/// The most recent lines that fit within the bordered area.
+fn last_lines<'a>(lines: &'a [String], area: Rect) -> Vec<Line<'a>> {
+    let visible = area.height.saturating_sub(2) as usize;
+    lines[lines.len().saturating_sub(visible)..]
+        .iter()
+        .map(|line| Line::raw(line.as_str()))
+        .collect()
+}
+
+/// Describes the cells that differ between two boards laid out as rows of cells separated by `|`,
+/// numbering the cells from 1 along each row in turn. Rows without cells, such as rules above and
+/// below the board, are passed over. The first board shown has no previous board to compare
+/// against, so has no changes.
+fn describe_changes(previous: &[String], current: &[String]) -> Vec<String> {
+    if previous.len() != current.len() {
+        return Vec::new();
+    }
+
+    let mut changes = Vec::new();
+    let mut cell_number = 0;
+    let rows = previous.iter().zip(current);
+    for (previous_row, current_row) in rows.filter(|(row, _)| row.contains('|')) {
+        for (before, after) in previous_row.split('|').zip(current_row.split('|')) {
+            cell_number += 1;
+            match (before.trim(), after.trim()) {
+                (before, after) if before == after => {}
+                ("", icon) => changes.push(format!("{} played in cell {}", icon, cell_number)),
+                (icon, "") => changes.push(format!("{} took back cell {}", icon, cell_number)),
+                (_, icon) => changes.push(format!("{} now in cell {}", icon, cell_number)),
+            }
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_sorted_into_board_and_messages() {
+        let mut app = App::default();
+
+        app.show_message("All players connected, lets begin.");
+        app.show_board("_________\n  |   |  \n  | X |  \n  |   |  \n_________\n");
+        app.show_message("\nIt's your turn!");
+
+        assert_eq!(
+            app.board,
+            [
+                "_________",
+                "  |   |  ",
+                "  | X |  ",
+                "  |   |  ",
+                "_________"
+            ]
+        );
+        assert_eq!(
+            app.messages,
+            ["All players connected, lets begin.", "It's your turn!"]
+        );
+        assert!(app.history.is_empty());
+    }
+
+    #[test]
+    fn changes_between_boards_added_to_history() {
+        let mut app = App::default();
+
+        for board in [
+            "_________\n  |   |  \n  |   |  \n  |   |  \n_________\n",
+            "_________\n  |   |  \n  | X |  \n  |   |  \n_________\n",
+            "_________\n  |   | O\n  | X |  \n  |   |  \n_________\n",
+            "_________\n  |   |  \n  | X |  \n  |   |  \n_________\n",
+        ] {
+            app.show_board(board);
+        }
+
+        assert_eq!(
+            app.history,
+            [
+                "X played in cell 5",
+                "O played in cell 3",
+                "O took back cell 3"
+            ]
+        );
+    }
+
+    #[test]
+    fn entered_line_submitted_and_echoed() {
+        let mut app = App::default();
+
+        for c in ['1', '2', '3'] {
+            assert_eq!(app.handle_key(KeyEvent::from(KeyCode::Char(c))), None);
+        }
+        app.handle_key(KeyEvent::from(KeyCode::Backspace));
+        let action = app.handle_key(KeyEvent::from(KeyCode::Enter));
+
+        assert_eq!(action, Some(KeyAction::Submit(String::from("12"))));
+        assert_eq!(app.messages, ["> 12"]);
+        assert!(app.input.is_empty());
+    }
+
+    #[test]
+    fn any_key_exits_once_finished() {
+        let mut app = App::default();
+        assert_eq!(
+            app.handle_key(KeyEvent::from(KeyCode::Esc)),
+            Some(KeyAction::Quit)
+        );
+
+        app.finish();
+        assert_eq!(
+            app.handle_key(KeyEvent::from(KeyCode::Char('q'))),
+            Some(KeyAction::Exit)
+        );
+    }
+
+    #[test]
+    fn finished_message_written_in_locale() {
+        let mut app = App::new(Locale::Spanish);
+
+        app.finish();
+
+        assert_eq!(
+            app.messages,
+            ["La partida ha terminado, pulsa cualquier tecla para salir."]
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::thread;
+
+use async_trait::async_trait;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+use tokio::runtime;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+
+use crate::client::{ClientBuilder, Frontend, Paint};
+use crate::game::GameDescriptor;
+use crate::lobby::{self, ConnectionOptions, JoinToken, RateLimit, RateLimiter};
+use crate::locale::Locale;
+
+/// The page served to browsers, which shows what the game sends it and passes on what the user
+/// enters.
+const PAGE: &str = include_str!("web/index.html");
+
+/// How many browsers may be playing at once, as each is given a thread and client of its own.
+const MAX_RELAYS: usize = 16;
+
+/// How the clients run for browsers reach the lobby of the game being hosted.
+pub struct LobbyAccess {
+    pub address: SocketAddr,
+    /// The token the lobby requires, which browsers must also present to be let in.
+    pub join_token: JoinToken,
+    pub options: ConnectionOptions,
+}
+
+/// Serves the web page on the listener, letting each browser that opens it join the game in the
+/// lobby as a player of its own. Runs until `shutdown` completes, then waits for the pages still
+/// open to be sent everything their game showed, so that players see how it ended.
+///
+/// Browsers do not speak the protocol players connect with. Instead each is given a client run by
+/// the host on its behalf, which joins the lobby as any other player would and is played through
+/// a `WebFrontend` relaying what it shows, and the lines the user enters, over a WebSocket. As
+/// these clients all connect from the host, browsers are held to the lobby's `RateLimit` here.
+pub async fn serve(
+    listener: TcpListener,
+    lobby: LobbyAccess,
+    game: Arc<dyn GameDescriptor>,
+    locale: Locale,
+    rate_limit: RateLimit,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> io::Result<()> {
+    let (relaying, mut relays_finished) = mpsc::channel(1);
+    let bridge = Arc::new(Bridge {
+        lobby,
+        game,
+        locale,
+        rate_limiter: std::sync::Mutex::new(RateLimiter::new(rate_limit)),
+        relays: Arc::new(Semaphore::new(MAX_RELAYS)),
+        relaying,
+    });
+    let router = Router::new()
+        .route("/", get(Html(PAGE)))
+        .route("/ws", get(open_socket))
+        .with_state(bridge);
+
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown)
+    .await?;
+    // Nothing is ever sent, so this returns once every relay, and client, has dropped its sender
+    relays_finished.recv().await;
+
+    Ok(())
+}
+
+/// How the clients run for browsers reach the game.
+struct Bridge {
+    lobby: LobbyAccess,
+    game: Arc<dyn GameDescriptor>,
+    locale: Locale,
+    rate_limiter: std::sync::Mutex<RateLimiter>,
+    /// A permit for each browser that may yet be let in, held until its client has finished.
+    relays: Arc<Semaphore>,
+    /// Held by each relay while it runs, which is never sent on.
+    relaying: mpsc::Sender<()>,
+}
+
+#[derive(Deserialize)]
+struct JoinQuery {
+    /// The join token given by the host, which is part of the link they share.
+    token: String,
+}
+
+/// Upgrades the browser's request to the WebSocket its game is relayed over, turning it away
+/// before a client is run for it if it exceeds the rate limit, has the wrong join token, or there
+/// are already as many browsers playing as there can be.
+async fn open_socket(
+    State(bridge): State<Arc<Bridge>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    Query(query): Query<JoinQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    if !bridge.rate_limiter.lock().unwrap().allow(peer.ip()) {
+        tracing::info!(%peer, "Refused a browser exceeding the rate limit");
+        return (StatusCode::TOO_MANY_REQUESTS, "Too many attempts to join.").into_response();
+    }
+    let Ok(join_token) = query.token.parse::<JoinToken>() else {
+        return (StatusCode::BAD_REQUEST, "The join token is not valid.").into_response();
+    };
+    if join_token != bridge.lobby.join_token {
+        return (StatusCode::FORBIDDEN, "The join token is not valid.").into_response();
+    }
+    let Ok(permit) = Arc::clone(&bridge.relays).try_acquire_owned() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "The game is full.").into_response();
+    };
+
+    let relaying = bridge.relaying.clone();
+    upgrade.on_upgrade(move |socket| relay(socket, bridge, permit, relaying))
+}
+
+/// Relays between the browser and the client playing for it, until either has finished.
+async fn relay(
+    mut socket: WebSocket,
+    bridge: Arc<Bridge>,
+    permit: OwnedSemaphorePermit,
+    _relaying: mpsc::Sender<()>,
+) {
+    let (frontend, mut shown, lines) = WebFrontend::new();
+    spawn_client(bridge, frontend, permit);
+
+    loop {
+        tokio::select! {
+            shown = shown.recv() => {
+                // Everything shown is sent before the client finishing is noticed
+                let Some(shown) = shown else { break };
+                let text = match serde_json::to_string(&shown) {
+                    Ok(text) => text,
+                    // Ending the relay closes the client's input, so that it leaves the game
+                    Err(error) => {
+                        tracing::warn!(?error, "Failed to encode what the game showed");
+                        break;
+                    }
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(line))) => {
+                    let _ = lines.send(line);
+                }
+                Some(Ok(_)) => {}
+                // The client takes its input closing as the user having left the game
+                Some(Err(_)) | None => break,
+            },
+        }
+    }
+}
+
+/// Has a client join the game on behalf of the browser, until the game is over, holding the
+/// browser's permit until then.
+///
+/// Game clients can only be run on the thread that created them, so each is given a thread of
+/// its own rather than running alongside the web server.
+fn spawn_client(bridge: Arc<Bridge>, frontend: WebFrontend, permit: OwnedSemaphorePermit) {
+    thread::spawn(move || {
+        let _permit = permit;
+        let runtime = match runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(error) => {
+                let _ = frontend.show_message(&error.to_string(), Some(Paint::Error));
+                return;
+            }
+        };
+        runtime.block_on(async {
+            let LobbyAccess {
+                address,
+                join_token,
+                ref options,
+            } = bridge.lobby;
+            let options = ConnectionOptions {
+                game: Some(String::from(bridge.game.id())),
+                ..options.clone()
+            };
+            let (connection, session) =
+                match lobby::connect_to_game(address, join_token, options).await {
+                    Ok(joined) => joined,
+                    Err(error) => {
+                        let _ = frontend.show_message(&error.to_string(), Some(Paint::Error));
+                        return;
+                    }
+                };
+
+            let mut client =
+                ClientBuilder::new().joined(connection, session, frontend, &*bridge.game);
+            // The page shows paint as styles of its own, which any browser can show
+            client.set_color(true);
+            client.set_locale(bridge.locale);
+            client.play_game().await;
+        });
+    });
+}
+
+/// What the page is sent to show, as JSON tagged with its kind.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Shown {
+    Message {
+        text: String,
+        /// The class styling the message on the page, if it is painted.
+        paint: Option<&'static str>,
+    },
+    Board {
+        text: String,
+    },
+    /// Shown until the user next enters a line.
+    Prompt {
+        text: String,
+    },
+}
+
+/// Plays through a web page, sending what the game shows to the page and reading the lines the
+/// user enters there, whether typed or by clicking the board.
+pub struct WebFrontend {
+    shown: UnboundedSender<Shown>,
+    lines: Mutex<UnboundedReceiver<String>>,
+}
+
+impl WebFrontend {
+    /// Creates the frontend along with the receiver of what is to be shown on the page, and the
+    /// sender for lines entered by the user, which are read until the sender is dropped.
+    fn new() -> (
+        WebFrontend,
+        UnboundedReceiver<Shown>,
+        UnboundedSender<String>,
+    ) {
+        let (shown_sender, shown) = mpsc::unbounded_channel();
+        let (line_sender, lines) = mpsc::unbounded_channel();
+        let frontend = WebFrontend {
+            shown: shown_sender,
+            lines: Mutex::new(lines),
+        };
+
+        (frontend, shown, line_sender)
+    }
+
+    fn show(&self, shown: Shown) -> io::Result<()> {
+        self.shown
+            .send(shown)
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+    }
+}
+
+#[async_trait]
+impl Frontend for WebFrontend {
+    fn show_message(&self, message: &str, paint: Option<Paint>) -> io::Result<()> {
+        self.show(Shown::Message {
+            text: String::from(message),
+            paint: paint.map(paint_class),
+        })
+    }
+
+    fn render_board(&self, board: &str) -> io::Result<()> {
+        self.show(Shown::Board {
+            text: String::from(board),
+        })
+    }
+
+    async fn read_line(&self) -> io::Result<String> {
+        match self.lines.lock().await.recv().await {
+            Some(line) => Ok(format!("{}\n", line)),
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    async fn prompt_for_move(&self, prompt: &str) -> io::Result<String> {
+        self.show(Shown::Prompt {
+            text: String::from(prompt),
+        })?;
+        self.read_line().await
+    }
+}
+
+fn paint_class(paint: Paint) -> &'static str {
+    match paint {
+        Paint::PlayerOne => "player-one",
+        Paint::PlayerTwo => "player-two",
+        Paint::Error => "error",
+        Paint::Highlight => "highlight",
+        Paint::Changed => "changed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+    use std::time::Duration;
+
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite;
+
+    use crate::tic_tac_toe::TicTacToe;
+
+    use super::*;
+
+    /// Serves the page for a lobby requiring the join token, returning the address to reach it.
+    async fn serve_test_page(join_token: JoinToken, rate_limit: RateLimit) -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let lobby = LobbyAccess {
+            address: (Ipv4Addr::LOCALHOST, 0).into(),
+            join_token,
+            options: ConnectionOptions::default(),
+        };
+        tokio::spawn(serve(
+            listener,
+            lobby,
+            Arc::new(TicTacToe::default()),
+            Locale::default(),
+            rate_limit,
+            std::future::pending(),
+        ));
+
+        address
+    }
+
+    /// The status a browser presenting the join token is refused with, or `None` if let in.
+    async fn refusal_status(address: SocketAddr, join_token: JoinToken) -> Option<u16> {
+        let stream = TcpStream::connect(address).await.unwrap();
+        let url = format!("ws://{}/ws?token={}", address, join_token);
+        match tokio_tungstenite::client_async(url, stream).await {
+            Ok(_) => None,
+            Err(tungstenite::Error::Http(response)) => Some(response.status().as_u16()),
+            Err(error) => panic!("Failed to open socket: {}", error),
+        }
+    }
+
+    #[tokio::test]
+    async fn browser_with_wrong_join_token_refused_before_upgrade() {
+        let join_token = JoinToken::generate();
+        let address = serve_test_page(join_token, RateLimit::default()).await;
+
+        assert_eq!(
+            refusal_status(address, JoinToken::generate()).await,
+            Some(403)
+        );
+        assert_eq!(refusal_status(address, join_token).await, None);
+    }
+
+    #[tokio::test]
+    async fn browser_exceeding_rate_limit_refused() {
+        let join_token = JoinToken::generate();
+        let rate_limit = RateLimit {
+            max_connections: 1,
+            period: Duration::from_secs(60),
+        };
+        let address = serve_test_page(join_token, rate_limit).await;
+
+        assert_eq!(refusal_status(address, join_token).await, None);
+        assert_eq!(refusal_status(address, join_token).await, Some(429));
+    }
+
+    #[tokio::test]
+    async fn page_sent_what_is_shown_as_tagged_json() {
+        let (frontend, mut shown, lines) = WebFrontend::new();
+        frontend
+            .show_message("Error: Invalid message sent.", Some(Paint::Error))
+            .unwrap();
+        lines.send(String::from("5")).unwrap();
+
+        assert_eq!(frontend.prompt_for_move("Cell:").await.unwrap(), "5\n");
+        let shown: Vec<String> = [shown.recv().await, shown.recv().await]
+            .into_iter()
+            .map(|shown| serde_json::to_string(&shown.unwrap()).unwrap())
+            .collect();
+        assert_eq!(
+            shown,
+            [
+                r#"{"kind":"message","text":"Error: Invalid message sent.","paint":"error"}"#,
+                r#"{"kind":"prompt","text":"Cell:"}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn user_leaving_page_ends_input() {
+        let (frontend, _shown, lines) = WebFrontend::new();
+        drop(lines);
+
+        assert_eq!(
+            frontend.read_line().await.unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::time::Duration;
+
+use rust_game_server::connection::{Chaos, ChaosStream, Connection};
+use rust_game_server::game::{self, GameEvent};
+use rust_game_server::server::{
+    OnlineConnection, OutgoingEvent, Player, Server, ShutdownReason, PLAYER_ONE_ID, PLAYER_TWO_ID,
+};
+use rust_game_server::tic_tac_toe::{ClientEvent, ServerEvent, TicTacToe};
+use tokio::task::JoinHandle;
+
+/// Starts a game of Tic Tac Toe on the server, with the chaos injected into the server's side of
+/// each player's connection.
+///
+/// # Returns
+///
+/// The connections of player one and two, and the handle of the server, which finishes once the
+/// game has ended.
+fn start_game(chaos: [Chaos; 2]) -> (Connection, Connection, JoinHandle<()>) {
+    let [chaos_one, chaos_two] = chaos;
+    let (server_one, player_one) = tokio::io::duplex(4096);
+    let (server_two, player_two) = tokio::io::duplex(4096);
+    let players = vec![
+        Player::new(
+            PLAYER_ONE_ID,
+            Connection::new(ChaosStream::new(server_one, chaos_one)),
+            1,
+        ),
+        Player::new(
+            PLAYER_TWO_ID,
+            Connection::new(ChaosStream::new(server_two, chaos_two)),
+            2,
+        ),
+    ];
+
+    let server = tokio::spawn(async move {
+        let mut server = Server::<OnlineConnection>::new(players, &TicTacToe::default());
+        server.init().await;
+    });
+
+    (
+        Connection::new(player_one),
+        Connection::new(player_two),
+        server,
+    )
+}
+
+/// Reads events until a game event matching the predicate arrives, failing on any other kind of
+/// event from the server.
+async fn read_game_event_until(
+    connection: &mut Connection,
+    predicate: impl Fn(&ServerEvent) -> bool,
+) -> ServerEvent {
+    loop {
+        match connection.read_event().await.unwrap() {
+            OutgoingEvent::GameStarted { .. } => continue,
+            OutgoingEvent::Game { event } => {
+                let event = ServerEvent::decode(&event).unwrap().unwrap();
+                if predicate(&event) {
+                    return event;
+                }
+            }
+            event => panic!("Unexpected event received from server: {:?}", event),
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn game_is_played_despite_latency_and_partial_writes() {
+    let chaos = Chaos {
+        latency: Duration::from_millis(150),
+        max_write_size: Some(5),
+        ..Chaos::default()
+    };
+    let (mut player_one, mut player_two, _server) = start_game([chaos.clone(), chaos]);
+
+    read_game_event_until(&mut player_one, |event| {
+        matches!(event, ServerEvent::PlayerTurn { player_id } if *player_id == PLAYER_ONE_ID)
+    })
+    .await;
+    player_one
+        .write_event(&game::serialize_event(ClientEvent::MoveMade {
+            player_id: PLAYER_ONE_ID,
+            move_index: 5,
+            mark: None,
+        }))
+        .await
+        .unwrap();
+
+    // The move reaches the other player whole, in full view of the board
+    let ServerEvent::BoardUpdated { board_cells, .. } =
+        read_game_event_until(&mut player_two, |event| {
+            matches!(event, ServerEvent::BoardUpdated { board_cells, .. } if board_cells[4].is_some())
+        })
+        .await
+    else {
+        unreachable!();
+    };
+    assert_eq!(board_cells[4], Some(PLAYER_ONE_ID));
+}
+
+#[tokio::test(start_paused = true)]
+async fn game_is_shut_down_for_other_player_once_player_disconnects() {
+    let (mut player_one, _player_two, server) = start_game([
+        Chaos::default(),
+        Chaos {
+            disconnect_after_bytes: Some(0),
+            ..Chaos::default()
+        },
+    ]);
+
+    // Without a lobby to rejoin through, the game cannot wait on the player to reconnect
+    assert!(matches!(
+        player_one.read_event().await.unwrap(),
+        OutgoingEvent::GameStarted { .. }
+    ));
+    assert!(matches!(
+        player_one.read_event().await.unwrap(),
+        OutgoingEvent::Shutdown {
+            reason: ShutdownReason::OpponentDisconnected
+        }
+    ));
+    tokio::time::timeout(Duration::from_secs(1), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn game_is_shut_down_once_random_disconnect_strikes() {
+    let (mut player_one, _player_two, server) = start_game([
+        Chaos::default(),
+        Chaos {
+            disconnect_chance: 0.5,
+            seed: 3,
+            ..Chaos::default()
+        },
+    ]);
+
+    // However far the game got, the remaining player is told that it has ended
+    loop {
+        let event = player_one.read_event().await.unwrap();
+        if let OutgoingEvent::Shutdown { reason } = event {
+            assert_eq!(reason, ShutdownReason::OpponentDisconnected);
+            break;
+        }
+    }
+    tokio::time::timeout(Duration::from_secs(1), server)
+        .await
+        .unwrap()
+        .unwrap();
+}
@@ -2,6 +2,8 @@ use std::process::Stdio;
 use std::time::Duration;
 
 use regex::Regex;
+use rust_game_server::connection;
+use rust_game_server::lobby::PROTOCOL_VERSION;
 use tokio::io::{
     AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
 };
@@ -30,15 +32,23 @@ impl<I: AsyncWrite + Unpin, O: AsyncBufRead + Unpin> InputOutput<I, O> {
     }
 
     async fn assert_stdout_contains(&mut self, string: &String) -> String {
-        tokio::time::timeout(Duration::from_secs(10), self.stream_contains(string))
+        self.assert_stdout_contains_within(string, Duration::from_secs(10))
             .await
-            .expect(
-                format!(
+    }
+
+    async fn assert_stdout_contains_within(
+        &mut self,
+        string: &String,
+        timeout: Duration,
+    ) -> String {
+        tokio::time::timeout(timeout, self.stream_contains(string))
+            .await
+            .unwrap_or_else(|_| {
+                panic!(
                     "Timeout reached while asserting stream contents: {}",
                     string
                 )
-                .as_str(),
-            )
+            })
     }
 
     async fn assert_stream_contains_board(&mut self, cells: [&str; 9]) {
@@ -79,11 +89,16 @@ impl<I: AsyncWrite + Unpin, O: AsyncBufRead + Unpin> InputOutput<I, O> {
 }
 
 fn get_io() -> (InputOutput<ChildStdin, BufReader<ChildStdout>>, Child) {
-    // Set up command to run the program
-    let mut cmd = Command::new("cargo");
-    cmd.arg("run");
+    get_io_with_args(&[])
+}
+
+fn get_io_with_args(args: &[&str]) -> (InputOutput<ChildStdin, BufReader<ChildStdout>>, Child) {
+    // Set up command to run the program, using the binary Cargo built for these tests
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_rust-game-server"));
+    cmd.args(args);
     cmd.stdin(Stdio::piped()); // Open a pipe for stdin
     cmd.stdout(Stdio::piped()); // Open a pipe for stdout
+    cmd.kill_on_drop(true); // Stop the program if the test ends early, such as by failing
 
     // Spawn the command and get handles to stdin and stdout
     let mut child = cmd.spawn().unwrap();
@@ -96,7 +111,7 @@ fn get_io() -> (InputOutput<ChildStdin, BufReader<ChildStdout>>, Child) {
 #[tokio::test]
 async fn local_game_runs_until_win() {
     // Set up command to run the program
-    let (mut io, _) = get_io();
+    let (mut io, _process) = get_io();
 
     // Prompt for the game mode
     io.assert_stdout_contains(&String::from(
@@ -151,11 +166,244 @@ async fn local_game_runs_until_win() {
         .await;
 }
 
+#[tokio::test]
+async fn local_game_can_be_played_on_largest_board() {
+    let (mut io, _process) =
+        get_io_with_args(&["local", "--board-size", "10", "--win-length", "3"]);
+
+    // The full board reaches the client, allowing a move in the last cell
+    io.assert_stdout_contains(&String::from(
+        "Input a number between 1 and 100 to make your move:",
+    ))
+    .await;
+    io.write_string("100\n").await;
+
+    // The updated board also reaches the client, which moves on to the next turn
+    io.assert_stdout_contains(&String::from("Player O's turn"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_in_another_locale() {
+    let (mut io, _process) = get_io_with_args(&["--locale", "es"]);
+
+    io.assert_stdout_contains(&String::from(
+        "Elige tu modo de juego; local u online (local/online).",
+    ))
+    .await;
+    io.write_string("local\n").await;
+
+    io.assert_stdout_contains(&String::from("¡Turno del jugador X!"))
+        .await;
+    io.assert_stdout_contains(&String::from("Introduce un número entre 1 y 9 para mover:"))
+        .await;
+    io.write_string("x\n").await;
+
+    io.assert_stdout_contains(&String::from("Eso no es un número, inténtalo de nuevo."))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_checkers() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "checkers", "--terse"]);
+
+    io.assert_stdout_contains(&String::from(
+        ".o.o.o.o/o.o.o.o./.o.o.o.o/......../......../x.x.x.x./.x.x.x.x/x.x.x.x.",
+    ))
+    .await;
+    for (player, squares) in [("X", "c3-d4"), ("O", "f6-e5"), ("X", "d4xf6")] {
+        io.assert_stdout_contains(&format!("{} to move.", player))
+            .await;
+        io.assert_stdout_contains(&String::from("Move (e.g. c3-d4):"))
+            .await;
+        io.write_string(&format!("{}\n", squares)).await;
+    }
+
+    // The capture removes the jumped piece, and the other player must then capture in return
+    io.assert_stdout_contains(&String::from("X:d4xf6")).await;
+    io.assert_stdout_contains(&String::from(
+        ".o.o.o.o/o.o.o.o./.o.o.x.o/......../......../x...x.x./.x.x.x.x/x.x.x.x.",
+    ))
+    .await;
+    io.assert_stdout_contains(&String::from("O to move.")).await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_battleship() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "battleship"]);
+
+    // Each player places their fleet before the first shot is fired
+    for player in ["X", "O"] {
+        io.assert_stdout_contains(&format!("Player {}, place your fleet!", player))
+            .await;
+        io.write_string("random\n").await;
+    }
+    io.assert_stdout_contains(&String::from("Player O's fleet:"))
+        .await;
+
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.assert_stdout_contains(&String::from("Input the square to fire at"))
+        .await;
+    io.write_string("e5\n").await;
+    io.assert_stdout_contains(&String::from("Player X fired at e5 and"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_othello() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "othello"]);
+
+    io.assert_stdout_contains(&String::from("Discs: X 2 - 2 O"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.write_string("d6\n").await;
+
+    // The server works out which discs the move flips
+    io.assert_stdout_contains(&String::from("Discs: X 4 - 1 O"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_mancala() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "mancala"]);
+
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.write_string("3\n").await;
+
+    // The last seed lands in player X's store, so they go again
+    io.assert_stdout_contains(&String::from("so they go again"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.write_string("1\n").await;
+
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_nim() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "nim"]);
+
+    io.assert_stdout_contains(&String::from("3: ||||| (5)"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.write_string("3 2\n").await;
+
+    io.assert_stdout_contains(&String::from("3: ||| (3)")).await;
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_dots_and_boxes() {
+    let (mut io, _process) =
+        get_io_with_args(&["local", "--game", "dots-and-boxes", "--board-size", "4"]);
+
+    // The grid has one more dot along each side than it has boxes
+    io.assert_stdout_contains(&String::from(" 5 +   +   +   +   +"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+    io.write_string("a1-b1\n").await;
+
+    io.assert_stdout_contains(&String::from(" 1 +---+   +   +   +"))
+        .await;
+    io.assert_stdout_contains(&String::from("Boxes: X 0 - 0 O"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_hangman() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "hangman"]);
+
+    io.assert_stdout_contains(&String::from(
+        "Player X, choose your word while player O looks away!",
+    ))
+    .await;
+    io.write_string("apple\n").await;
+
+    io.assert_stdout_contains(&String::from("Word: _ _ _ _ _"))
+        .await;
+    io.assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+    io.write_string("p\n").await;
+
+    io.assert_stdout_contains(&String::from("Word: _ p p _ _"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_trivia() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "trivia", "--rounds", "1"]);
+
+    io.assert_stdout_contains(&String::from("Question 1 of 1, with 20 seconds to answer:"))
+        .await;
+    for player in ["X", "O"] {
+        io.assert_stdout_contains(&format!("Player {}'s turn!", player))
+            .await;
+        io.write_string("1\n").await;
+    }
+
+    // Neither answer is revealed until both players have answered
+    io.assert_stdout_contains(&String::from("The answer was"))
+        .await;
+    io.assert_stdout_contains(&String::from("Game over!")).await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_blackjack() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "blackjack"]);
+
+    io.assert_stdout_contains(&String::from("Round 1 of 5, betting 10 chips."))
+        .await;
+    io.assert_stdout_contains(&String::from("Dealer: ")).await;
+    // A player dealt 21 stands without being asked, leaving any spare input to the next round
+    io.write_string("stand\nstand\n").await;
+
+    // The dealer's face down card is only revealed once both players have stood
+    io.assert_stdout_contains(&String::from("The dealer reveals"))
+        .await;
+    io.assert_stdout_contains(&String::from("Round 2 of 5"))
+        .await;
+}
+
+#[tokio::test]
+async fn local_game_can_be_played_as_rock_paper_scissors() {
+    let (mut io, _process) = get_io_with_args(&["local", "--game", "rock-paper-scissors"]);
+
+    io.assert_stdout_contains(&String::from("Round 1! The score is X 0 - 0 O."))
+        .await;
+    for (player, hand) in [("X", "rock"), ("O", "scissors")] {
+        io.assert_stdout_contains(&format!("Player {}'s turn!", player))
+            .await;
+        io.write_string(&format!("{}\n", hand)).await;
+    }
+
+    // Neither hand is revealed until both players have chosen
+    io.assert_stdout_contains(&String::from(
+        "Player X chose rock and Player O chose scissors. Player X wins the round!",
+    ))
+    .await;
+    io.assert_stdout_contains(&String::from("Round 2! The score is X 1 - 0 O."))
+        .await;
+}
+
 #[tokio::test]
 async fn online_game_runs_until_win() {
     // Set up command to run the program
-    let (mut player_one_io, _) = get_io();
-    let (mut player_two_io, _) = get_io();
+    let (mut player_one_io, _player_one) = get_io();
+    let (mut player_two_io, _player_two) = get_io();
 
     // Assert online connections of both players
     assert_online_client_connections(&mut player_one_io, &mut player_two_io).await;
@@ -270,7 +518,7 @@ async fn online_game_runs_until_win() {
 #[tokio::test]
 async fn online_game_handles_client_disconnection() {
     // Set up command to run the program
-    let (mut player_one_io, _) = get_io();
+    let (mut player_one_io, _player_one) = get_io();
     let (mut player_two_io, mut player_two_process) = get_io();
 
     // Assert online connections of both players
@@ -298,58 +546,70 @@ async fn online_game_handles_client_disconnection() {
         .await;
     player_one_io.stdin.write_all(&[49, b'\n']).await.unwrap();
 
-    // Player one shuts down
+    // Player one is told that player two has disconnected
     player_one_io
         .assert_stdout_contains(&String::from(
-            "An unrecoverable error has occurred, game terminating.",
+            "Your opponent has disconnected, waiting up to 10 seconds for them to reconnect.",
         ))
         .await;
+
+    // Player one shuts down once player two fails to reconnect
+    player_one_io
+        .assert_stdout_contains_within(
+            &String::from("Your opponent has left the game, game terminating."),
+            Duration::from_secs(20),
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn host_exits_with_own_code_when_port_already_in_use() {
+    let listener = std::net::TcpListener::bind(("0.0.0.0", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port().to_string();
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(10),
+        Command::new(env!("CARGO_BIN_EXE_rust-game-server"))
+            .args(["--terse", "host", "--port", &port])
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .expect("Timeout reached while waiting for host to give up")
+    .unwrap();
+
+    assert_eq!(output.status.code(), Some(8));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        format!(
+            "Port {} is already in use, choose another with --port. Aborting.\n",
+            port
+        )
+    );
 }
 
 #[tokio::test]
 async fn online_game_handles_client_sending_malicious_message() {
     // Set up command to run the program
-    let (mut player_one_io, _) = get_io();
+    let (mut player_one_io, _player_one) = get_io();
 
     // Set up player one to host the game
-    player_one_io
-        .assert_stdout_contains(&String::from(
-            "Please select your game mode; local or online.",
-        ))
-        .await;
-    player_one_io.write_string("online\n").await;
-    player_one_io
-        .assert_stdout_contains(&String::from("Do you want to host or join a game?"))
-        .await;
-    player_one_io.write_string("host\n").await;
-    player_one_io
-        .assert_stdout_contains(&String::from(
-            "Do you wish to specify a port to bind to (the default is 22222) y/N?",
-        ))
-        .await;
-    player_one_io.write_string("yes\n").await;
-    player_one_io
-        .assert_stdout_contains(&String::from("Please provide the port:"))
-        .await;
-    player_one_io.write_string("0\n").await;
-    // Grab the bound port for later connecting, ensuring the port is always available
-    let buf = player_one_io
-        .assert_stdout_contains(&String::from("People can join you on port"))
-        .await;
-    let re = Regex::new(r"People can join you on port\s*(\d*)\s*!").unwrap();
-    let port = re
-        .captures(buf.as_str())
-        .unwrap()
-        .get(1)
-        .unwrap()
-        .as_str()
-        .parse::<u16>()
-        .unwrap();
+    let (port, join_token) = host_online_game(&mut player_one_io, false).await;
 
     // Set up malicious connection of player two
     let mut stream = TcpStream::connect(("0.0.0.0", port)).await.unwrap();
-    stream.write_all(&17_u16.to_be_bytes()[..]).await.unwrap();
-    stream.write_all(b"{\"game_id\":12345}").await.unwrap();
+    let request = format!(
+        "{{\"kind\":\"\",\"version\":{},\"sequence\":0,\"payload\":{{\"join_token\":{},\"protocol_version\":{}}}}}",
+        connection::SCHEMA_VERSION,
+        u64::from_str_radix(&join_token, 16).unwrap(),
+        PROTOCOL_VERSION
+    );
+    stream
+        .write_all(&(request.len() as u32).to_be_bytes()[..])
+        .await
+        .unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
 
     // Game begin event being received
     player_one_io
@@ -369,16 +629,120 @@ async fn online_game_handles_client_sending_malicious_message() {
 
     assert!(buf.contains("InvalidMessage"));
     assert!(buf.contains("Shutdown"));
+    assert!(buf.contains("ProtocolViolation"));
 
-    // Player one makes their move as normal
-    player_one_io.stdin.write_all(&[49, b'\n']).await.unwrap();
+    // Player one receives Shutdown event without having to make their move first
+    player_one_io
+        .assert_stdout_contains(&String::from(
+            "A player sent messages the server could not accept, game terminating.",
+        ))
+        .await;
+}
+
+#[tokio::test]
+async fn online_game_can_be_spectated() {
+    // Set up command to run the program
+    let (mut player_one_io, _player_one) = get_io();
+    let (mut player_two_io, _player_two) = get_io();
+    let (mut spectator_io, _spectator) = get_io();
+
+    // Player one hosts a game allowing spectators, which player two joins
+    let (port, join_token) = host_online_game(&mut player_one_io, true).await;
+    connect_to_online_game(&mut player_two_io, "join", port, &join_token).await;
+    player_one_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+    player_two_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+
+    // Spectator joins and is sent the current state of the game
+    connect_to_online_game(&mut spectator_io, "spectate", port, &join_token).await;
+    spectator_io
+        .assert_stdout_contains(&String::from("You are now spectating the game."))
+        .await;
+    spectator_io.assert_stream_contains_board([" "; 9]).await;
+    spectator_io
+        .assert_stdout_contains(&String::from("Player X's turn!"))
+        .await;
+
+    // Player two is told that someone is watching
+    player_two_io
+        .assert_stdout_contains(&String::from(
+            "A spectator has started watching, there are now 1 watching.",
+        ))
+        .await;
 
-    // Player one receives Shutdown event
+    // Spectator sees player one's move
     player_one_io
+        .assert_player_move(
+            "It's your turn!",
+            5,
+            [" ", " ", " ", " ", "X", " ", " ", " ", " "],
+        )
+        .await;
+    spectator_io
+        .assert_stream_contains_board([" ", " ", " ", " ", "X", " ", " ", " ", " "])
+        .await;
+    spectator_io
+        .assert_stdout_contains(&String::from("Player O's turn!"))
+        .await;
+}
+
+#[tokio::test]
+async fn exhibition_game_can_be_spectated() {
+    // Set up command to run the program
+    let (mut host_io, _host) = get_io();
+    let (mut spectator_io, _spectator) = get_io();
+
+    // Host runs an exhibition on a random port
+    host_io
         .assert_stdout_contains(&String::from(
-            "An unrecoverable error has occurred, game terminating.",
+            "Please select your game mode; local or online.",
+        ))
+        .await;
+    host_io.write_string("online\n").await;
+    host_io
+        .assert_stdout_contains(&String::from(
+            "Do you want to host, join, or spectate a game, or run an exhibition?",
+        ))
+        .await;
+    host_io.write_string("exhibition\n").await;
+    host_io
+        .assert_stdout_contains(&String::from(
+            "Do you wish to specify a port to bind to (the default is 22222) y/N?",
         ))
         .await;
+    host_io.write_string("yes\n").await;
+    host_io
+        .assert_stdout_contains(&String::from("Please provide the port:"))
+        .await;
+    host_io.write_string("0\n").await;
+    let buf = host_io
+        .assert_stdout_contains(&String::from("People can spectate the exhibition on port"))
+        .await;
+    let re = Regex::new(
+        r"People can spectate the exhibition on port\s*(\d*)\s*using the join token\s*([0-9a-f]*)\s*!",
+    )
+    .unwrap();
+    let captures = re.captures(buf.as_str()).unwrap();
+    let port = captures[1].parse::<u16>().unwrap();
+    let join_token = captures[2].to_string();
+
+    // Spectator watches the bots play to the end
+    connect_to_online_game(&mut spectator_io, "spectate", port, &join_token).await;
+    spectator_io
+        .assert_stdout_contains(&String::from("You are now spectating the game."))
+        .await;
+    spectator_io
+        .assert_stdout_contains_within(
+            &String::from("Game over! There was a draw!"),
+            Duration::from_secs(30),
+        )
+        .await;
+    host_io
+        .assert_stdout_contains(&String::from("The exhibition has finished."))
+        .await;
 }
 
 async fn assert_online_client_connections(
@@ -386,6 +750,16 @@ async fn assert_online_client_connections(
     player_two_io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
 ) {
     // Player one selects to host the game, specifying port 0
+    let (port, join_token) = host_online_game(player_one_io, false).await;
+
+    // Player two selects to join the game
+    connect_to_online_game(player_two_io, "join", port, &join_token).await;
+}
+
+async fn host_online_game(
+    player_one_io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
+    allow_spectators: bool,
+) -> (u16, String) {
     player_one_io
         .assert_stdout_contains(&String::from(
             "Please select your game mode; local or online.",
@@ -393,7 +767,9 @@ async fn assert_online_client_connections(
         .await;
     player_one_io.write_string("online\n").await;
     player_one_io
-        .assert_stdout_contains(&String::from("Do you want to host or join a game?"))
+        .assert_stdout_contains(&String::from(
+            "Do you want to host, join, or spectate a game, or run an exhibition?",
+        ))
         .await;
     player_one_io.write_string("host\n").await;
     player_one_io
@@ -406,30 +782,802 @@ async fn assert_online_client_connections(
         .assert_stdout_contains(&String::from("Please provide the port:"))
         .await;
     player_one_io.write_string("0\n").await;
+    player_one_io
+        .assert_stdout_contains(&String::from(
+            "Do you wish to limit the time each player has to make a move y/N?",
+        ))
+        .await;
+    player_one_io.write_string("no\n").await;
+    player_one_io
+        .assert_stdout_contains(&String::from(
+            "Do you wish to allow spectators to watch the game y/N?",
+        ))
+        .await;
+    if allow_spectators {
+        player_one_io.write_string("yes\n").await;
+        player_one_io
+            .assert_stdout_contains(&String::from(
+                "Please provide the maximum number of spectators:",
+            ))
+            .await;
+        player_one_io.write_string("1\n").await;
+        player_one_io
+            .assert_stdout_contains(&String::from(
+                "Should players be told when someone starts or stops watching y/N?",
+            ))
+            .await;
+        player_one_io.write_string("yes\n").await;
+    } else {
+        player_one_io.write_string("no\n").await;
+    }
+    player_one_io
+        .assert_stdout_contains(&String::from(
+            "Who should make the first move; host, joiner or random (the default is host)?",
+        ))
+        .await;
+    player_one_io.write_string("host\n").await;
+
     // Grab the bound port for later connecting, ensuring the port is always available
-    let buf = player_one_io
+    read_hosted_game(player_one_io).await
+}
+
+/// Reads the port and join token that the host prints for people to join them with.
+async fn read_hosted_game(
+    io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
+) -> (u16, String) {
+    let buf = io
         .assert_stdout_contains(&String::from("People can join you on port"))
         .await;
-    let re = Regex::new(r"People can join you on port\s*(\d*)\s*!").unwrap();
-    let port = re.captures(buf.as_str()).unwrap().get(1).unwrap().as_str();
+    let re =
+        Regex::new(r"People can join you on port\s*(\d*)\s*using the join token\s*([0-9a-f]*)\s*!")
+            .unwrap();
+    let captures = re.captures(buf.as_str()).unwrap();
+    (captures[1].parse::<u16>().unwrap(), captures[2].to_string())
+}
 
-    // Player two selects to join the game
+async fn connect_to_online_game(
+    io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
+    online_mode: &str,
+    port: u16,
+    join_token: &str,
+) {
+    io.assert_stdout_contains(&String::from(
+        "Please select your game mode; local or online.",
+    ))
+    .await;
+    io.write_string("online\n").await;
+    io.assert_stdout_contains(&String::from(
+        "Do you want to host, join, or spectate a game, or run an exhibition?",
+    ))
+    .await;
+    io.write_string(format!("{}\n", online_mode).as_str()).await;
+    io.assert_stdout_contains(&format!(
+        "Please enter the address of the game to {}:",
+        online_mode
+    ))
+    .await;
+    io.write_string(format!("0.0.0.0:{}\n", port).as_str())
+        .await;
+    io.assert_stdout_contains(&String::from(
+        "Please enter the join token shared by the host:",
+    ))
+    .await;
+    io.write_string(format!("{}\n", join_token).as_str()).await;
+}
+
+#[tokio::test]
+async fn online_game_can_be_set_up_with_arguments() {
+    // Player one hosts a game on a random port without being prompted
+    let (mut player_one_io, _player_one) = get_io_with_args(&["host", "--port", "0"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+
+    // Player two joins the game without being prompted
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["join", address.as_str(), join_token.as_str()]);
+
+    // Game begin event being received
+    player_one_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+    player_two_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+}
+
+#[tokio::test]
+async fn online_game_cannot_be_joined_without_join_token() {
+    let (mut player_one_io, _player_one) = get_io_with_args(&["host", "--port", "0"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+
+    // Player two guesses at the join token rather than being given it by the host
+    let address = format!("0.0.0.0:{}", port);
+    let guessed_token = if join_token == "0000000000000000" {
+        "1"
+    } else {
+        "0"
+    };
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-game-server"))
+        .args(["join", address.as_str(), guessed_token])
+        .output()
+        .await
+        .unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("The join token does not match the one shared by the host."));
+}
+
+#[tokio::test]
+async fn joining_game_no_one_is_hosting_reports_connection_refused() {
+    // Nothing is listening on the port once the listener bound to it has been dropped
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    drop(listener);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rust-game-server"))
+        .args(["join", address.as_str(), "1", "--connect-attempts", "2"])
+        .output()
+        .await
+        .unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("after 2 attempts, as the connection was refused"));
+}
+
+#[tokio::test]
+async fn online_game_can_be_played_over_websocket() {
+    // Player one hosts a game accepting WebSocket connections, which player two joins
+    let (mut player_one_io, _player_one) =
+        get_io_with_args(&["host", "--port", "0", "--websocket"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["join", address.as_str(), join_token.as_str(), "--websocket"]);
+
+    // Game begin event being received
+    player_one_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+    player_two_io
+        .assert_stdout_contains(&String::from("All players connected, lets begin."))
+        .await;
+
+    // Player one makes a move, which player two sees
+    player_one_io
+        .assert_player_move(
+            "It's your turn!",
+            5,
+            [" ", " ", " ", " ", "X", " ", " ", " ", " "],
+        )
+        .await;
+    player_two_io
+        .assert_stream_contains_board([" ", " ", " ", " ", "X", " ", " ", " ", " "])
+        .await;
+}
+
+#[tokio::test]
+async fn online_game_can_be_played_with_terse_output() {
+    // Both players ask for terse output
+    let (mut player_one_io, _player_one) = get_io_with_args(&["--terse", "host", "--port", "0"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["join", address.as_str(), join_token.as_str(), "--terse"]);
+
+    // Game begin event and the compact initial board being received
+    player_one_io
+        .assert_stdout_contains(&String::from("Started.\n123/456/789\n"))
+        .await;
+    player_two_io
+        .assert_stdout_contains(&String::from("Started.\n123/456/789\n"))
+        .await;
+
+    // Player one makes a move, which player two sees in the same compact form
+    player_one_io
+        .assert_stdout_contains(&String::from("Your move.\nMove (1-9):\n"))
+        .await;
+    player_one_io.write_string("5\n").await;
+    player_one_io
+        .assert_stdout_contains(&String::from("123/4X6/789\n"))
+        .await;
     player_two_io
+        .assert_stdout_contains(&String::from("Their move.\n123/4X6/789\nX:5\n"))
+        .await;
+}
+
+#[tokio::test]
+async fn online_game_can_be_played_on_larger_board() {
+    // Player one hosts a game on a four by four board, which player two joins
+    let (mut player_one_io, _player_one) = get_io_with_args(&[
+        "host",
+        "--port",
+        "0",
+        "--board-size",
+        "4",
+        "--win-length",
+        "3",
+    ]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["join", address.as_str(), join_token.as_str()]);
+
+    // Both players are sent the larger board, including the player who did not choose it
+    let empty_board = "_____________\n  |   |   |  \n  |   |   |  \n  |   |   |  \n  |   |   |  \n_____________\n";
+    player_one_io
+        .assert_stdout_contains(&String::from(empty_board))
+        .await;
+    player_two_io
+        .assert_stdout_contains(&String::from(empty_board))
+        .await;
+
+    // Player one can make a move in a cell beyond the default board
+    player_one_io
         .assert_stdout_contains(&String::from(
-            "Please select your game mode; local or online.",
+            "Input a number between 1 and 16 to make your move:",
         ))
         .await;
-    player_two_io.write_string("online\n").await;
+    player_one_io.write_string("16\n").await;
+    player_two_io
+        .assert_stdout_contains(&String::from("  |   |   | X\n"))
+        .await;
+}
+
+#[tokio::test]
+async fn hosted_game_is_recorded_in_stats() {
+    let stats_path = std::env::temp_dir().join(format!("game-stats-{}.db", std::process::id()));
+    let stats_path = stats_path.to_str().unwrap();
+    let _ = std::fs::remove_file(stats_path);
+
+    // Player one hosts a game recording statistics, which player two joins
+    let (mut player_one_io, mut player_one) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--stats", stats_path]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+
+    play_terse_game_won_by_player_one(&mut player_one_io, &mut player_two_io).await;
+
+    // Both players are told how long each of them spent thinking
+    player_one_io
+        .assert_stdout_contains(&String::from("X: avg"))
+        .await;
     player_two_io
-        .assert_stdout_contains(&String::from("Do you want to host or join a game?"))
+        .assert_stdout_contains(&String::from("O: avg"))
+        .await;
+    player_one.wait().await.unwrap();
+
+    // The host's record includes the win
+    let (mut stats_io, _stats) = get_io_with_args(&["stats", stats_path]);
+    stats_io
+        .assert_stdout_contains(&String::from(
+            "tic-tac-toe: played 1, won 1, lost 0, drawn 0",
+        ))
+        .await;
+    let _ = std::fs::remove_file(stats_path);
+}
+
+#[tokio::test]
+async fn named_players_are_rated_once_game_is_over() {
+    let stats_path = std::env::temp_dir().join(format!("game-ratings-{}.db", std::process::id()));
+    let stats_path = stats_path.to_str().unwrap();
+    let _ = std::fs::remove_file(stats_path);
+
+    // Player one hosts a game recording statistics, both players giving a name
+    let (mut player_one_io, mut player_one) = get_io_with_args(&[
+        "--terse", "host", "--port", "0", "--stats", stats_path, "--name", "Ada",
+    ]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) = get_io_with_args(&[
+        "--terse",
+        "join",
+        address.as_str(),
+        join_token.as_str(),
+        "--name",
+        "Alan",
+    ]);
+
+    play_terse_game_won_by_player_one(&mut player_one_io, &mut player_two_io).await;
+
+    // Both players are told the new rating of each of them
+    player_one_io
+        .assert_stdout_contains(&String::from("Ada: 1516 (+16)."))
         .await;
-    player_two_io.write_string("join\n").await;
     player_two_io
+        .assert_stdout_contains(&String::from("Alan: 1484 (-16)."))
+        .await;
+    player_one.wait().await.unwrap();
+
+    // The leaderboard lists the winner first
+    let (mut ratings_io, _ratings) = get_io_with_args(&["ratings", stats_path]);
+    ratings_io
         .assert_stdout_contains(&String::from(
-            "Please enter the address of the game to join:",
+            "tic-tac-toe:\n1. Ada, rated 1516 after 1 game(s).\n2. Alan, rated 1484 after 1 game(s).",
         ))
         .await;
+    let _ = std::fs::remove_file(stats_path);
+}
+
+#[tokio::test]
+async fn hosted_game_can_be_replayed() {
+    let replay_path =
+        std::env::temp_dir().join(format!("game-replay-{}.jsonl", std::process::id()));
+    let replay_path = replay_path.to_str().unwrap();
+
+    // Player one hosts a game recording a replay, which player two joins and loses
+    let (mut player_one_io, mut player_one) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--record", replay_path]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+    play_terse_game_won_by_player_one(&mut player_one_io, &mut player_two_io).await;
+    player_one.wait().await.unwrap();
+
+    // The replay shows each move in turn, through to the end of the game
+    let (mut replay_io, _replay) = get_io_with_args(&["--terse", "replay", replay_path]);
+    replay_io
+        .assert_stdout_contains(&String::from("Started.\n123/456/789\n"))
+        .await;
+    replay_io
+        .assert_stdout_contains(&String::from("XX3/O56/789\nX:2\n"))
+        .await;
+    replay_io
+        .assert_stdout_contains(&String::from("X won."))
+        .await;
+    let _ = std::fs::remove_file(replay_path);
+}
+
+#[tokio::test]
+async fn hosted_game_can_be_started_by_joiner() {
+    let (mut player_one_io, _player_one) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--first-move", "joiner"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+
+    player_one_io
+        .assert_stdout_contains(&String::from("123/456/789\nTheir move."))
+        .await;
+    enter_terse_move(&mut player_two_io, "5").await;
+    player_one_io
+        .assert_stdout_contains(&String::from("123/4O6/789"))
+        .await;
+}
+
+#[tokio::test]
+async fn saved_game_can_be_resumed() {
+    let save_path = std::env::temp_dir().join(format!("game-save-{}.json", std::process::id()));
+    let save_path = save_path.to_str().unwrap();
+
+    // Player one saves the game after each player has made a move
+    let (mut player_one_io, mut player_one) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--save", save_path]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+    enter_terse_move(&mut player_one_io, "1").await;
+    enter_terse_move(&mut player_two_io, "4").await;
+    enter_terse_move(&mut player_one_io, "save").await;
+    for io in [&mut player_one_io, &mut player_two_io] {
+        io.assert_stdout_contains(&String::from("Saved.")).await;
+    }
+    player_one.wait().await.unwrap();
+
+    // Both players rejoin with the same join token, carrying on from the saved board
+    let (mut player_one_io, _player_one) =
+        get_io_with_args(&["--terse", "resume", save_path, "--port", "0"]);
+    let (port, resumed_join_token) = read_hosted_game(&mut player_one_io).await;
+    assert_eq!(resumed_join_token, join_token);
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+    player_one_io
+        .assert_stdout_contains(&String::from("X23/O56/789"))
+        .await;
+    enter_terse_move(&mut player_one_io, "2").await;
+    enter_terse_move(&mut player_two_io, "5").await;
+    enter_terse_move(&mut player_one_io, "3").await;
+    player_two_io
+        .assert_stdout_contains(&String::from("X won."))
+        .await;
+    let _ = std::fs::remove_file(save_path);
+}
+
+#[tokio::test]
+async fn players_can_play_again_in_game_chosen_by_host() {
+    let (mut player_one_io, _player_one) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--rematch"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+
+    // Player one resigns, then both agree to play again with player one choosing Othello
+    enter_terse_move(&mut player_one_io, "/resign").await;
+    player_two_io
+        .assert_stdout_contains(&String::from("Again? Y/n\n"))
+        .await;
+    player_two_io.write_string("y\n").await;
+    player_one_io
+        .assert_stdout_contains(&String::from("Again? Y/n or checkers,"))
+        .await;
+    player_one_io.write_string("othello\n").await;
+    for io in [&mut player_one_io, &mut player_two_io] {
+        io.assert_stdout_contains(&String::from("New game: othello."))
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn player_joining_chooses_game_offered_by_host() {
+    let (mut player_one_io, _player_one) =
+        get_io_with_args(&["host", "--port", "0", "--offer-games"]);
+    let (port, join_token) = read_hosted_game(&mut player_one_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_two_io, _player_two) =
+        get_io_with_args(&["join", address.as_str(), join_token.as_str()]);
+
+    // Player two is shown the games offered, then both play the one they choose
+    player_two_io
+        .assert_stdout_contains(&String::from("4. othello"))
+        .await;
+    player_two_io.write_string("4\n").await;
+    for io in [&mut player_one_io, &mut player_two_io] {
+        io.assert_stdout_contains(&String::from("Discs: X 2 - 2 O"))
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn hot_seat_game_can_be_played_from_one_connection() {
+    // The host only runs the server, letting a single player join and take both sides
+    let (mut host_io, mut host) =
+        get_io_with_args(&["--terse", "host", "--port", "0", "--hot-seat"]);
+    let (port, join_token) = read_hosted_game(&mut host_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let (mut player_io, _player) =
+        get_io_with_args(&["--terse", "join", address.as_str(), join_token.as_str()]);
+
+    // The joining player makes the moves of both players, winning as X along the top row
+    for move_made in ["1", "4", "2", "5", "3"] {
+        player_io
+            .assert_stdout_contains(&String::from("Move (1-9):"))
+            .await;
+        player_io
+            .write_string(format!("{}\n", move_made).as_str())
+            .await;
+    }
+    player_io
+        .assert_stdout_contains(&String::from("X won."))
+        .await;
+    host.wait().await.unwrap();
+}
+
+#[tokio::test]
+async fn dedicated_server_hosts_games_for_each_pair_of_players() {
+    // The server only hosts games, pairing players in the order they join
+    let (mut server_io, _server) = get_io_with_args(&["--terse", "serve", "--port", "0"]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+    let address = format!("0.0.0.0:{}", port);
+
+    let ((mut player_one_io, _player_one), (mut player_two_io, _player_two)) =
+        join_game_as_pair(&address, &join_token).await;
+    let ((mut player_three_io, _player_three), (mut player_four_io, _player_four)) =
+        join_game_as_pair(&address, &join_token).await;
+
+    // Both games are played at once, each won by the player seated first
+    play_terse_game_won_by_player_one(&mut player_three_io, &mut player_four_io).await;
+    play_terse_game_won_by_player_one(&mut player_one_io, &mut player_two_io).await;
+    player_two_io
+        .assert_stdout_contains(&String::from("X won."))
+        .await;
+}
+
+#[tokio::test]
+async fn dedicated_server_runs_tournament_until_won() {
+    let (mut server_io, mut server) =
+        get_io_with_args(&["--terse", "serve", "--port", "0", "--tournament", "2"]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+    let address = format!("0.0.0.0:{}", port);
+
+    // Both players are shown the bracket once it has been drawn, before playing the final
+    let mut players = Vec::new();
+    for name in ["Ada", "Alan"] {
+        players.push(get_io_with_args(&[
+            "--terse",
+            "join",
+            address.as_str(),
+            join_token.as_str(),
+            "--name",
+            name,
+        ]));
+    }
+    let mut first_seated = None;
+    for (index, (player_io, _)) in players.iter_mut().enumerate() {
+        player_io.assert_stdout_contains(&String::from("R1:")).await;
+        let output = player_io
+            .assert_stdout_contains(&String::from(" move."))
+            .await;
+        if output.contains("Your move.") {
+            first_seated = Some(index);
+        }
+    }
+    let (mut player_one_io, _player_one) = players.remove(first_seated.unwrap());
+    let (mut player_two_io, _player_two) = players.remove(0);
+
+    // The server stops once the final has been won
+    play_terse_game_won_by_player_one(&mut player_one_io, &mut player_two_io).await;
+    let status = tokio::time::timeout(Duration::from_secs(10), server.wait())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(status.success());
+}
+
+#[tokio::test]
+async fn players_meet_in_private_room_of_dedicated_server() {
+    let (mut server_io, _server) = get_io_with_args(&["--terse", "serve", "--port", "0"]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+    let address = format!("0.0.0.0:{}", port);
+    let join_private_room = || {
+        get_io_with_args(&[
+            "--terse",
+            "join",
+            address.as_str(),
+            join_token.as_str(),
+            "--private-room",
+            "beef",
+        ])
+    };
+
+    // Both players connect out to the server, the first to arrive playing first
+    let (mut first_io, _first) = join_private_room();
+    let (mut second_io, _second) = join_private_room();
+    let first_output = first_io
+        .assert_stdout_contains(&String::from(" move."))
+        .await;
+    second_io
+        .assert_stdout_contains(&String::from(" move."))
+        .await;
+    if first_output.contains("Your move.") {
+        play_terse_game_won_by_player_one(&mut first_io, &mut second_io).await;
+    } else {
+        play_terse_game_won_by_player_one(&mut second_io, &mut first_io).await;
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn dedicated_server_games_can_be_controlled_through_admin_socket() {
+    let socket_path =
+        std::env::temp_dir().join(format!("game-server-admin-{}.sock", std::process::id()));
+    let (mut server_io, _server) = get_io_with_args(&[
+        "--terse",
+        "serve",
+        "--port",
+        "0",
+        "--admin-socket",
+        socket_path.to_str().unwrap(),
+    ]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+    let ((_player_one_io, _player_one), (mut player_two_io, mut player_two)) =
+        join_game_as_pair(&format!("0.0.0.0:{}", port), &join_token).await;
+
+    let admin = tokio::net::UnixStream::connect(&socket_path).await.unwrap();
+    let (admin_reader, mut admin_writer) = admin.into_split();
+    let mut admin_lines = BufReader::new(admin_reader).lines();
+
+    // The operator lists the games being played, then sends everyone a notice
+    admin_writer
+        .write_all(b"games\nnotice Restarting soon.\n")
+        .await
+        .unwrap();
+    let games = admin_lines.next_line().await.unwrap().unwrap();
+    assert!(games.starts_with("Game 1: tic-tac-toe"));
+    assert_eq!(
+        admin_lines.next_line().await.unwrap().unwrap(),
+        "Sent to 1 games."
+    );
+    player_two_io
+        .assert_stdout_contains(&String::from("Server: Restarting soon."))
+        .await;
+
+    // The operator removes the second player, who is told why before their client stops
+    admin_writer.write_all(b"kick 1 2\n").await.unwrap();
+    assert_eq!(
+        admin_lines.next_line().await.unwrap().unwrap(),
+        "Player 2 has been removed from game 1."
+    );
     player_two_io
-        .write_string(format!("0.0.0.0:{}\n", port).as_str())
+        .assert_stdout_contains(&String::from(
+            "Error: You have been removed from the game by the server operator.",
+        ))
+        .await;
+    tokio::time::timeout(Duration::from_secs(10), player_two.wait())
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn stress_mode_reports_games_played_on_dedicated_server() {
+    let (mut server_io, _server) = get_io_with_args(&[
+        "--terse",
+        "serve",
+        "--port",
+        "0",
+        "--max-connections-per-minute",
+        "100",
+    ]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+
+    let output = tokio::time::timeout(
+        Duration::from_secs(20),
+        Command::new(env!("CARGO_BIN_EXE_rust-game-server"))
+            .args(["stress", &format!("0.0.0.0:{}", port), &join_token])
+            .args(["--games", "5"])
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .expect("Timeout reached while waiting for stress test to finish")
+    .unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Completed 5 games in "));
+    assert!(stdout.contains("Errors: 0."));
+    assert!(stdout.contains("Game time: p50 "));
+}
+
+/// Has two players join a dedicated server with terse output, waiting for their game to start.
+/// Either may connect first, so they are returned in the order they were seated.
+async fn join_game_as_pair(
+    address: &str,
+    join_token: &str,
+) -> (
+    (InputOutput<ChildStdin, BufReader<ChildStdout>>, Child),
+    (InputOutput<ChildStdin, BufReader<ChildStdout>>, Child),
+) {
+    let mut first = get_io_with_args(&["--terse", "join", address, join_token]);
+    let mut second = get_io_with_args(&["--terse", "join", address, join_token]);
+
+    let first_output = first
+        .0
+        .assert_stdout_contains(&String::from(" move."))
+        .await;
+    second
+        .0
+        .assert_stdout_contains(&String::from(" move."))
+        .await;
+
+    if first_output.contains("Your move.") {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// Plays out a game between two players using terse output, with player one winning along the
+/// top row.
+async fn play_terse_game_won_by_player_one(
+    player_one_io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
+    player_two_io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>,
+) {
+    for (player_one_move, player_two_move) in [("1", Some("4")), ("2", Some("5")), ("3", None)] {
+        player_one_io
+            .assert_stdout_contains(&String::from("Move (1-9):"))
+            .await;
+        player_one_io
+            .write_string(format!("{}\n", player_one_move).as_str())
+            .await;
+        if let Some(player_two_move) = player_two_move {
+            player_two_io
+                .assert_stdout_contains(&String::from("Move (1-9):"))
+                .await;
+            player_two_io
+                .write_string(format!("{}\n", player_two_move).as_str())
+                .await;
+        }
+    }
+    player_one_io
+        .assert_stdout_contains(&String::from("X won."))
+        .await;
+}
+
+/// Waits for the player to be asked for their move, then enters the given input.
+async fn enter_terse_move(io: &mut InputOutput<ChildStdin, BufReader<ChildStdout>>, input: &str) {
+    io.assert_stdout_contains(&String::from("Move (1-9):"))
         .await;
+    io.write_string(format!("{}\n", input).as_str()).await;
+}
+
+/// Runs a bot in the game until it stops, giving the JSON report it writes and whether it
+/// reported a pass through its exit status.
+async fn run_bot(address: &str, join_token: &str, args: &[&str]) -> (serde_json::Value, bool) {
+    let output = tokio::time::timeout(
+        Duration::from_secs(20),
+        Command::new(env!("CARGO_BIN_EXE_rust-game-server"))
+            .args(["--terse", "bot", address, join_token])
+            .args(args)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .expect("Timeout reached while waiting for bot to finish")
+    .unwrap();
+
+    let report = serde_json::from_slice(&output.stdout).unwrap();
+    (report, output.status.success())
+}
+
+/// Writes the moves to a script file that a bot can enter, one to a line.
+fn write_bot_script(name: &str, moves: &[&str]) -> std::path::PathBuf {
+    let script_path = std::env::temp_dir().join(format!("{}-{}.txt", name, std::process::id()));
+    std::fs::write(&script_path, moves.join("\n")).unwrap();
+    script_path
+}
+
+#[tokio::test]
+async fn scripted_bot_plays_both_sides_of_hot_seat_game() {
+    let (mut host_io, _host) = get_io_with_args(&["--terse", "host", "--port", "0", "--hot-seat"]);
+    let (port, join_token) = read_hosted_game(&mut host_io).await;
+    let script_path = write_bot_script("bot-script-hot-seat", &["1", "4", "2", "5", "3"]);
+
+    let (report, passed) = run_bot(
+        &format!("0.0.0.0:{}", port),
+        &join_token,
+        &["--script", script_path.to_str().unwrap()],
+    )
+    .await;
+
+    assert_eq!(
+        report,
+        serde_json::json!({"result": "pass", "game": "tic-tac-toe"})
+    );
+    assert!(passed);
+    let _ = std::fs::remove_file(script_path);
+}
+
+#[tokio::test]
+async fn scripted_bot_fails_once_script_runs_out() {
+    let (mut host_io, _host) = get_io_with_args(&["--terse", "host", "--port", "0", "--hot-seat"]);
+    let (port, join_token) = read_hosted_game(&mut host_io).await;
+    let script_path = write_bot_script("bot-script-short", &["1", "4"]);
+
+    let (report, passed) = run_bot(
+        &format!("0.0.0.0:{}", port),
+        &join_token,
+        &["--script", script_path.to_str().unwrap()],
+    )
+    .await;
+
+    assert_eq!(report["result"], "fail");
+    assert!(!passed);
+    let _ = std::fs::remove_file(script_path);
+}
+
+#[tokio::test]
+async fn bots_play_each_other_on_dedicated_server() {
+    let (mut server_io, _server) =
+        get_io_with_args(&["--terse", "serve", "--port", "0", "--game", "othello"]);
+    let (port, join_token) = read_hosted_game(&mut server_io).await;
+    let address = format!("0.0.0.0:{}", port);
+
+    let ((report_one, passed_one), (report_two, passed_two)) = tokio::join!(
+        run_bot(&address, &join_token, &["--game", "othello"]),
+        run_bot(&address, &join_token, &["--game", "othello"]),
+    );
+
+    for (report, passed) in [(report_one, passed_one), (report_two, passed_two)] {
+        assert_eq!(
+            report,
+            serde_json::json!({"result": "pass", "game": "othello"})
+        );
+        assert!(passed);
+    }
 }